@@ -70,12 +70,14 @@ fn main() {
         .with_rule(Rule::Collide {
             radius: 0.02,
             restitution: 0.8,
+            self_type: None,
+            target_type: None,
         })
         // Color based on local density (more neighbors = warmer)
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             let density_color = clamp(cohesion_count / 20.0, 0.0, 1.0);
             p.color = mix(vec3<f32>(0.2, 0.4, 1.0), vec3<f32>(1.0, 0.6, 0.2), density_color);
-        "#.into()))
+        "#))
         .with_rule(Rule::Drag(1.0))
         .with_rule(Rule::SpeedLimit { min: 0.2, max: 1.5 })
         .with_rule(Rule::BounceWalls)
@@ -6,7 +6,7 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion, Benchmark
 use glam::Vec3;
 
 // Import rules directly
-use rdpe::rules::Rule;
+use rdpe::rules::{Falloff, Rule};
 
 fn bench_rule_to_wgsl(c: &mut Criterion) {
     let mut group = c.benchmark_group("rule_to_wgsl");
@@ -35,22 +35,24 @@ fn bench_rule_to_wgsl(c: &mut Criterion) {
             center: Vec3::ZERO,
             axis: Vec3::Y,
             strength: 1.0,
+            radius: 0.0,
+            falloff: Falloff::Constant,
         };
         b.iter(|| black_box(rule.to_wgsl(1.0)))
     });
 
     group.bench_function("custom_short", |b| {
-        let rule = Rule::Custom("p.velocity *= 0.99;".into());
+        let rule = Rule::custom("p.velocity *= 0.99;");
         b.iter(|| black_box(rule.to_wgsl(1.0)))
     });
 
     group.bench_function("custom_long", |b| {
-        let rule = Rule::Custom(r#"
+        let rule = Rule::custom(r#"
             let dist = length(p.position);
             let t = clamp(dist / 2.0, 0.0, 1.0);
             p.color = mix(vec3<f32>(1.0, 0.0, 0.0), vec3<f32>(0.0, 0.0, 1.0), t);
             p.velocity += normalize(p.position) * 0.1;
-        "#.into());
+        "#);
         b.iter(|| black_box(rule.to_wgsl(1.0)))
     });
 
@@ -95,7 +97,7 @@ fn bench_many_rules(c: &mut Criterion) {
         Rule::Gravity(9.8),
         Rule::Drag(0.5),
         Rule::Turbulence { scale: 2.0, strength: 1.5 },
-        Rule::Vortex { center: Vec3::ZERO, axis: Vec3::Y, strength: 1.0 },
+        Rule::Vortex { center: Vec3::ZERO, axis: Vec3::Y, strength: 1.0, radius: 0.0, falloff: Falloff::Constant },
         Rule::SpeedLimit { min: 0.0, max: 3.0 },
         Rule::BounceWalls,
     ];
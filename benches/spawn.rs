@@ -0,0 +1,42 @@
+//! Benchmarks for CPU-side particle spawning.
+//!
+//! Compare with and without the `rayon` feature:
+//!
+//! ```text
+//! cargo bench --bench spawn
+//! cargo bench --bench spawn --features rayon
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rdpe::prelude::*;
+
+#[derive(Particle, Clone)]
+struct Spark {
+    position: Vec3,
+    velocity: Vec3,
+}
+
+fn bench_spawn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spawn");
+    group.sample_size(10);
+
+    for count in [100_000u32, 1_000_000] {
+        let sim = Simulation::<Spark>::new()
+            .with_particle_count(count)
+            .with_spawner(|ctx| Spark {
+                position: ctx.random_in_sphere(1.0),
+                velocity: ctx.random_direction() * ctx.random_range(0.1, 0.5),
+            })
+            .deterministic(1, 1.0 / 60.0);
+
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &sim, |b, sim| {
+            b.iter(|| black_box(sim.initial_particles().unwrap()))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_spawn);
+criterion_main!(benches);
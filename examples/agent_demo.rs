@@ -80,7 +80,7 @@ fn main() {
         .with_spawner(move |ctx| particles[ctx.index as usize].clone())
 
         // Simulate food detection (using noise as proxy for "food location")
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             // Simulate food detection based on position (food "hotspots")
             let food_pos = vec3<f32>(
@@ -90,8 +90,7 @@ fn main() {
             );
             p.food_dist = length(p.position - food_pos);
             p.food_detected = select(0.0, 1.0, p.food_dist < 0.4);
-        "#
-            .into(),
+        "#,
         ))
 
         // The Agent state machine
@@ -204,11 +203,10 @@ fn main() {
         })
 
         // Dim color when low energy
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             p.color *= (0.5 + p.energy * 0.5);
-        "#
-            .into(),
+        "#,
         ))
 
         // Add wander movement (separate from state machine for reliability)
@@ -224,7 +224,7 @@ fn main() {
         })
 
         // === Fish-Specific Behaviors ===
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             // Only for fish
             if (p.particle_type == 0u) {
@@ -279,8 +279,7 @@ fn main() {
                 // Shark prefers mid-depths
                 p.velocity.y -= p.position.y * 0.01;
             }
-        "#
-            .into(),
+        "#,
         ))
 
         // === Physics ===
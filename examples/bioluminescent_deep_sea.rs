@@ -103,7 +103,7 @@ fn main() {
         .with_rule(Rule::SpeedLimit { min: 0.0, max: 0.3 })
         // === Bioluminescence: Mouse Trigger ===
         // Only triggers if particle has enough charge
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             let to_mouse = uniforms.mouse_pos - p.position;
             let mouse_dist = length(to_mouse);
@@ -114,8 +114,7 @@ fn main() {
                 let flash = trigger_strength * 0.8 * p.charge;
                 p.glow = max(p.glow, flash);
             }
-            "#
-            .into(),
+            "#,
         ))
         // === Bioluminescence: Light Spreading ===
         // Using Rule::Diffuse for natural light propagation to neighbors
@@ -134,7 +133,7 @@ fn main() {
             regen_rate: 0.008,
         })
         // === Glow Decay & Color Mapping ===
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             // Natural glow decay
             p.glow = p.glow * 0.92;
@@ -152,8 +151,7 @@ fn main() {
             } else {
                 p.color = mix(glow_color, bright_color, (p.glow - 0.5) * 2.0);
             }
-            "#
-            .into(),
+            "#,
         ))
         .with_rule(Rule::WrapWalls)
         // === Visuals ===
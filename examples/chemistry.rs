@@ -188,7 +188,7 @@ fn main() {
             frequency: 3.0,
         })
         // Reaction products get energy boost (exothermic)
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             if p.particle_type == 4u { // Water
                 // Newly formed water gets a speed boost
@@ -198,18 +198,16 @@ fn main() {
                     p.energy = 1.0;
                 }
             }
-        "#
-            .into(),
+        "#,
         ))
         // Energy slowly equalizes (heat dissipation)
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             p.energy = mix(p.energy, 0.5, 0.1 * uniforms.delta_time);
-        "#
-            .into(),
+        "#,
         ))
         // === COLORING ===
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             // Base colors by type
             if p.particle_type == 0u { // Hydrogen
@@ -230,19 +228,17 @@ fn main() {
             // Speed adds brightness
             let speed = length(p.velocity);
             p.color += vec3<f32>(speed * 0.3);
-        "#
-            .into(),
+        "#,
         ))
         // === PHYSICS ===
         // Mass affects movement (heavier = slower response)
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             // Scale velocity changes by inverse mass
             let inv_mass = 1.0 / max(p.mass, 0.1);
             // Lighter atoms are faster
             p.velocity *= mix(1.0, inv_mass * 4.0, 0.02);
-        "#
-            .into(),
+        "#,
         ))
         .with_rule(Rule::Drag(1.2))
         .with_rule(Rule::SpeedLimit { min: 0.0, max: 1.2 })
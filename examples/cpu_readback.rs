@@ -194,7 +194,7 @@ fn main() {
             }),
         })
         // Followers chase the center of mass (uniform-based)
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             // Only apply to followers (type 1)
             if p.particle_type == 1u {
@@ -213,8 +213,7 @@ fn main() {
                     p.velocity += dir * 3.0 * uniforms.delta_time;
                 }
             }
-            "#
-            .to_string(),
+            "#,
         ))
         // Everyone maintains some separation
         .with_rule(Rule::Separate {
@@ -95,7 +95,7 @@ fn main() {
         // Spatial hashing for collision detection
         .with_spatial_config(0.08, 32)
         // Brownian motion for free particles + drift toward center
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             if p.is_crystal == 0u {
                 // Pure random walk - classic DLA behavior
@@ -115,8 +115,7 @@ fn main() {
                 // Crystallized - frozen in place
                 p.velocity = vec3<f32>(0.0, 0.0, 0.0);
             }
-            "#
-            .into(),
+            "#,
         ))
         // When free particle touches crystal, it crystallizes
         .with_rule(Rule::OnCollision {
@@ -132,7 +131,7 @@ fn main() {
             .into(),
         })
         // Color based on state and crystallization time
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             if p.is_crystal == 1u {
                 // Crystallized: color by time (blue -> cyan -> white -> pink)
@@ -151,8 +150,7 @@ fn main() {
                 // Free particle - dim red-gray
                 p.color = vec3<f32>(0.3, 0.9, 0.3);
             }
-            "#
-            .into(),
+            "#,
         ))
         // No drag/speed limit since we set velocity directly each frame
         // Visuals
@@ -121,12 +121,12 @@ fn main() {
             v.background(Vec3::ZERO); // Pure black for maximum glow contrast
         })
         // Gentle orbital motion
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             let dist = length(p.position);
             let tangent = normalize(cross(p.position, vec3<f32>(0.0, 1.0, 0.0)));
             p.velocity += tangent * 0.3;
             p.velocity *= 0.98;
-        "#.into()))
+        "#))
         .run().expect("Simulation failed");
 }
 
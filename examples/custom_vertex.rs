@@ -108,13 +108,12 @@ fn main() {
             point: Vec3::ZERO,
             strength: 0.3,
         })
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             let r = length(p.position.xz);
             let swirl = 0.4 / (r + 0.1);
             p.velocity += vec3<f32>(-p.position.z, 0.0, p.position.x) * swirl * uniforms.delta_time;
-            "#
-            .into(),
+            "#,
         ))
         .with_rule(Rule::Drag(0.3))
         .with_rule(Rule::SpeedLimit { min: 0.0, max: 1.0 })
@@ -51,7 +51,7 @@ fn main() {
             sediment: 0.0,
         })
         // Water flow over implicit terrain (procedural hills)
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             let dt = uniforms.delta_time;
 
@@ -142,8 +142,7 @@ fn main() {
                 p.velocity = vec3<f32>(0.0, 0.0, 0.0);
                 p.sediment = 0.0;
             }
-            "#
-            .into(),
+            "#,
         ))
         // Visuals
         .with_visuals(|v| {
@@ -89,7 +89,7 @@ fn main() {
         .with_uniform("drag", 0.3f32)
         .with_uniform("color_mode", 0u32)
         // Magnetic field dynamics
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             // Dipole magnetic field approximation
             // Field points upward near poles, curves around
             let dipole_pos = vec3<f32>(0.0, -uniforms.dipole_height, 0.0);
@@ -126,9 +126,9 @@ fn main() {
 
             // Drag
             p.velocity *= 1.0 - uniforms.drag * uniforms.delta_time;
-        "#.into()))
+        "#))
         // Respawn at top when falling too low (continuous aurora)
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             if p.position.y < -0.8 {
                 // Respawn at top with fresh velocity
                 p.position.y = 0.9;
@@ -151,9 +151,9 @@ fn main() {
                 p.position.z = sign(p.position.z) * bounds;
                 p.velocity.z *= -0.3;
             }
-        "#.into()))
+        "#))
         // Aurora coloring
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             let altitude = (p.position.y + 1.0) / 2.0; // 0 to 1
             let intensity = min(p.energy, 1.5);
 
@@ -194,7 +194,7 @@ fn main() {
             // Fade based on speed (stationary = dimmer)
             let speed_factor = min(length(p.velocity) * 3.0, 1.0);
             p.color *= 0.3 + speed_factor * 0.7;
-        "#.into()))
+        "#))
         .with_ui(move |ctx| {
             let mut s = ui_state.lock().unwrap();
 
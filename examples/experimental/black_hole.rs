@@ -136,7 +136,7 @@ fn main() {
         "#)
 
         // === Disk Particle Behavior ===
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             if p.particle_type == 0u {
                 // Gravitational pull - weaker to keep disk stable longer
                 let gravity = gravity_force(p.position, 1.5);
@@ -173,10 +173,10 @@ fn main() {
                 let temp = length(p.velocity) * 0.3 + (1.0 - r) * 0.5;
                 p.color = doppler_color(p.velocity, temp);
             }
-        "#.into()))
+        "#))
 
         // === Infalling Particles ===
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             if p.particle_type == 1u {
                 // Strong gravity, stretching
                 let gravity = gravity_force(p.position, 8.0);
@@ -204,10 +204,10 @@ fn main() {
                     p.particle_type = 2u;
                 }
             }
-        "#.into()))
+        "#))
 
         // === Jet Particles ===
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             if p.particle_type == 2u {
                 // Collimated outward acceleration
                 let y_sign = sign(p.position.y);
@@ -233,7 +233,7 @@ fn main() {
                     p.particle_type = 0u;
                 }
             }
-        "#.into()))
+        "#))
 
         .with_rule(Rule::Drag(0.3))
         .with_rule(Rule::SpeedLimit { min: 0.0, max: 5.0 })
@@ -35,9 +35,9 @@
 //! })
 //!
 //! // 5. Rules read uniforms
-//! .with_rule(Rule::Custom(r#"
+//! .with_rule(Rule::custom(r#"
 //!     p.velocity.y -= uniforms.gravity * uniforms.delta_time;
-//! "#.into()))
+//! "#))
 //! ```
 //!
 //! ## Why Arc<Mutex>?
@@ -178,7 +178,7 @@ fn main() {
         })
 
         // Custom rule that uses the uniforms
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             // Apply gravity (controlled by slider)
             p.velocity.y -= uniforms.gravity * uniforms.delta_time;
 
@@ -191,7 +191,7 @@ fn main() {
 
             // Integrate position
             p.position += p.velocity * uniforms.delta_time * speed_mult;
-        "#.into()))
+        "#))
 
         .with_rule(Rule::BounceWalls)
         .run().expect("Simulation failed");
@@ -193,7 +193,7 @@ fn main() {
             "#);
         })
         // Complex organic movement
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             // Get position info
             let pos = p.position;
             let dist_from_center = length(pos);
@@ -233,7 +233,7 @@ fn main() {
                 let push = (dist_from_center - 0.8) * 0.5;
                 p.velocity -= radial * push;
             }
-        "#.into()))
+        "#))
         .run().expect("Simulation failed");
 }
 
@@ -192,7 +192,7 @@ fn main() {
         })
 
         // Main galaxy dynamics
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             let dt = uniforms.delta_time * uniforms.speed_mult;
             let t = uniforms.time;
 
@@ -255,7 +255,7 @@ fn main() {
 
             // === INTEGRATE ===
             p.position += p.velocity * dt;
-        "#.into()))
+        "#))
 
         // Spatial hashing for fluid interactions
         .with_spatial_config(0.1, 32)
@@ -289,10 +289,10 @@ fn main() {
         "#.into()))
 
         // Reset density each frame (done via a simple custom rule)
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             // Decay density toward base (will be rebuilt by neighbor pass)
             p.density = max(p.density * 0.8, 0.5);
-        "#.into()))
+        "#))
 
         .run().expect("Simulation failed");
 }
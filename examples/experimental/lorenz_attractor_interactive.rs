@@ -183,7 +183,7 @@ fn main() {
             ctx.set("color_mode", s.color_mode);
         })
         // The Lorenz system dynamics using uniforms
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
     // Lorenz attractor dynamics
     let scale = uniforms.lorenz_scale;
     let lx = p.position.x / scale;
@@ -241,7 +241,7 @@ fn main() {
             p.color = vec3<f32>(1.0, 0.5, 0.2) * intensity;
         }
     }
-"#.into()))
+"#))
         // Slow drag to smooth out motion
         .with_rule(Rule::Drag(0.1))
         // Visual settings
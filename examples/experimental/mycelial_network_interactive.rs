@@ -282,18 +282,17 @@ fn main() {
             ctx.set("max_speed", s.max_speed);
         })
         // === NODE BEHAVIOR ===
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             if p.particle_type == 0u {
                 p.velocity = vec3<f32>(0.0);
                 p.nutrients = 0.7 + sin(uniforms.time * 0.4 + p.position.x * 3.0) * 0.3;
                 p.color = vec3<f32>(0.35, 0.2, 0.08) + vec3<f32>(0.25, 0.15, 0.07) * p.nutrients;
             }
-        "#
-            .into(),
+        "#,
         ))
         // === HYPHA EXPLORATION ===
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             if p.particle_type == 1u {
                 let speed = uniforms.base_speed + p.nutrients * uniforms.nutrient_speed_boost;
@@ -326,8 +325,7 @@ fn main() {
                     t
                 );
             }
-        "#
-            .into(),
+        "#,
         ))
         // === CHEMOTROPISM ===
         .with_rule(Rule::NeighborCustom(
@@ -369,14 +367,13 @@ fn main() {
             .into(),
         ))
         // Receive nutrient transfers
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             if p.particle_type == 1u {
                 let received = inbox_receive_at(index, 0u);
                 p.nutrients = clamp(p.nutrients + received, 0.0, 1.0);
             }
-        "#
-            .into(),
+        "#,
         ))
         // === NETWORK STRUCTURE ===
         // Dynamic separation using uniforms
@@ -402,15 +399,14 @@ fn main() {
             .into(),
         ))
         // === PHYSICS ===
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             p.velocity *= (1.0 - uniforms.drag * uniforms.delta_time);
             let speed = length(p.velocity);
             if speed > uniforms.max_speed {
                 p.velocity = normalize(p.velocity) * uniforms.max_speed;
             }
-        "#
-            .into(),
+        "#,
         ))
         .run().expect("Simulation failed");
 }
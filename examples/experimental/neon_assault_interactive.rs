@@ -200,7 +200,7 @@ fn main() {
             "#);
         })
         // Movement rule
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             let pos = p.position;
             let entity = floor(p.entity_type);
 
@@ -247,7 +247,7 @@ fn main() {
             let speed = length(p.velocity);
             if speed > 2.0 { p.velocity = normalize(p.velocity) * 2.0; }
             p.velocity *= 0.97;
-        "#.into()))
+        "#))
         // UI callback
         .with_ui(move |ctx| {
             let mut s = ui_state.lock().unwrap();
@@ -359,7 +359,7 @@ fn main() {
         "#.into()))
 
         // Apply drag and speed limit via custom rule using uniforms
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             // Apply drag
             p.velocity *= 1.0 - uniforms.drag * uniforms.delta_time;
 
@@ -368,7 +368,7 @@ fn main() {
             if speed > uniforms.speed_limit {
                 p.velocity = normalize(p.velocity) * uniforms.speed_limit;
             }
-        "#.into()))
+        "#))
 
         .with_rule(Rule::WrapWalls)
         .with_particle_size(0.012)
@@ -158,7 +158,7 @@ fn main() {
         .with_uniform("speed", 2.0f32)
         .with_uniform("drag", 3.0f32)
         // Reset concentration
-        .with_rule(Rule::Custom("p.concentration = 0.0;".into()))
+        .with_rule(Rule::custom("p.concentration = 0.0;"))
         // Neighbor interactions
         .with_rule(Rule::NeighborCustom(r#"
             let interact_radius = 0.2;
@@ -198,7 +198,7 @@ fn main() {
             }
         "#.into()))
         // Boundary and damping
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             // Wrap around boundaries
             if p.position.x > 0.95 { p.position.x = -0.95; }
             if p.position.x < -0.95 { p.position.x = 0.95; }
@@ -219,7 +219,7 @@ fn main() {
             } else {
                 p.color = vec3<f32>(1.0, 0.35 + conc * 0.35, 0.15 + conc * 0.2);
             }
-        "#.into()))
+        "#))
         .with_ui(move |ctx| {
             let mut s = ui_state.lock().unwrap();
 
@@ -103,7 +103,7 @@ fn main() {
         "#)
 
         // === Phase and Energy Evolution ===
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             // Advance personal phase
             p.phase += 0.5 * uniforms.delta_time;
             if p.phase > 1.0 {
@@ -130,11 +130,11 @@ fn main() {
 
             // Cap energy
             p.energy = min(p.energy, 1.2);
-        "#.into()))
+        "#))
 
         // === Clustering Behavior ===
         // Attracted to areas of similar energy
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             // Flow toward energy concentrations
             let gradient = vec3<f32>(
                 noise3(p.position * 3.0 + vec3<f32>(0.1, 0.0, 0.0) + uniforms.time * 0.1) -
@@ -148,10 +148,10 @@ fn main() {
             // Low energy particles cluster, high energy repel
             let cluster_strength = 1.0 - p.energy;
             p.velocity += gradient * cluster_strength * 2.0 * uniforms.delta_time;
-        "#.into()))
+        "#))
 
         // === Explosion Behavior ===
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             // When energy exceeds threshold, EXPLODE
             if p.energy > 0.75 {
                 // Burst outward dramatically!
@@ -174,10 +174,10 @@ fn main() {
                 let jitter = explosion_pattern(p.position, uniforms.time * 3.0) * (p.energy - 0.4) * 2.0;
                 p.velocity += jitter * uniforms.delta_time;
             }
-        "#.into()))
+        "#))
 
         // === Containment and Movement ===
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             // Spherical soft boundary
             let dist = length(p.position);
             if dist > 0.7 {
@@ -196,13 +196,13 @@ fn main() {
                 noise3(curl_pos + vec3<f32>(0.0, 0.0, 100.0)) - noise3(curl_pos - vec3<f32>(0.0, 0.0, 100.0))
             );
             p.velocity += drift * 0.8 * uniforms.delta_time;
-        "#.into()))
+        "#))
 
         // === Visual Representation ===
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             p.color = thought_color(p.energy, p.phase);
             p.scale = 0.5 + p.energy * 1.5;
-        "#.into()))
+        "#))
 
         .with_rule(Rule::Drag(2.5))
         .with_rule(Rule::SpeedLimit { min: 0.0, max: 2.0 })
@@ -102,7 +102,7 @@ fn main() {
         // Very light drag (dynamical friction)
         .with_rule(Rule::Drag(0.1))
         // Central gravity + coloring + scale by mass
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             // Scale by mass (visual size)
             p.scale = sqrt(p.mass) * 0.5;
@@ -142,8 +142,7 @@ fn main() {
             // Brighten massive stars
             let mass_bright = 0.7 + p.mass * 0.15;
             p.color *= mass_bright;
-            "#
-            .into(),
+            "#,
         ))
         .with_visuals(|v| {
             v.background(Vec3::new(0.0, 0.0, 0.02)); // Deep space
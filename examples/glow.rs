@@ -72,13 +72,12 @@ fn main() {
             strength: 0.5,
         })
         // Some swirl
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             let r = length(p.position.xz);
             let swirl_strength = 0.3 / (r + 0.1);
             p.velocity += vec3<f32>(-p.position.z, 0.0, p.position.x) * swirl_strength * uniforms.delta_time;
-            "#
-            .into(),
+            "#,
         ))
         // Light drag to keep things stable
         .with_rule(Rule::Drag(0.3))
@@ -216,21 +216,19 @@ fn main() {
             radius: 0.8,
         })
         // Deposit gravitational influence into field
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             // Deposit mass-weighted influence into potential field
             // Heavier masses create brighter wells
             let deposit = uniforms.field_deposit * p.mass;
             field_write(0u, p.position, deposit);
-            "#
-            .into(),
+            "#,
         ))
         // Apply drag
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             p.velocity *= 1.0 - uniforms.drag * uniforms.delta_time;
-            "#
-            .into(),
+            "#,
         ))
         .with_rule(Rule::SpeedLimit { min: 0.0, max: 2.0 })
         .with_rule(Rule::BounceWalls)
@@ -117,7 +117,7 @@ fn main() {
         "#.into()))
 
         // Custom rule: receive energy and update visuals
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             // Receive accumulated energy from inbox channel 0
             let received = inbox_receive_at(index, 0u);
 
@@ -146,7 +146,7 @@ fn main() {
             // Add subtle floating motion
             p.velocity.y = sin(uniforms.time * 2.0 + p.position.x * 5.0) * 0.01;
             p.position.y = sin(uniforms.time + p.position.x * 3.0 + p.position.z * 2.0) * 0.05;
-        "#.into()))
+        "#))
 
         .run().expect("Simulation failed");
 }
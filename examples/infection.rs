@@ -140,7 +140,7 @@ fn main() {
             strength: 1.0,
         })
         // Update color to match current health status
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
     if p.particle_type == 0u {
         p.color = vec3<f32>(0.1, 0.9, 0.2); // Healthy: green
@@ -149,8 +149,7 @@ fn main() {
     } else {
         p.color = vec3<f32>(0.2, 0.4, 1.0); // Recovered: blue
     }
-"#
-            .to_string(),
+"#,
         ))
         // Random wandering keeps population mixing
         .with_rule(Rule::Wander {
@@ -91,7 +91,7 @@ fn main() {
             }
         })
         // Force rules
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             // Attraction force (mouse left click)
             if uniforms.attract_strength > 0.0 {
@@ -138,8 +138,7 @@ fn main() {
                 vec3<f32>(1.0, 0.4, 0.2),  // Orange when fast
                 clamp(speed / 3.0, 0.0, 1.0)
             );
-"#
-            .into(),
+"#,
         ))
         .with_rule(Rule::Drag(2.0))
         .with_rule(Rule::SpeedLimit { min: 0.0, max: 5.0 })
@@ -108,7 +108,7 @@ fn main() {
                 .with_decay(0.97)
                 .with_blur(0.15),
         )
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             let dt = uniforms.delta_time;
             let speed = 0.4;
@@ -179,8 +179,7 @@ fn main() {
             }
 
             p.velocity = vec3<f32>(0.0, 0.0, 0.0);
-        "#
-            .into(),
+        "#,
         ))
         .run().expect("Simulation failed");
 }
@@ -152,7 +152,7 @@ fn main() {
             }),
         })
         // Custom rule using variant-specific fields and generated helpers
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             // Use the generated is_predator helper
             if is_predator(p) {
@@ -166,8 +166,7 @@ fn main() {
 
             // Could also access boid's flock_id if needed:
             // if is_boid(p) { let flock = p.flock_id; }
-            "#
-            .into(),
+            "#,
         ))
         // Physics
         .with_rule(Rule::SpeedLimit { min: 0.0, max: 2.5 })
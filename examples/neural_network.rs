@@ -311,7 +311,7 @@ fn main() {
             }
         })
         // Neural dynamics with homeostatic mechanisms
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             // === HOMEOSTATIC DECAY ===
             // Fatigue recovers slowly (synaptic vesicle replenishment)
             p.fatigue = max(0.0, p.fatigue - uniforms.delta_time * 0.5);
@@ -414,7 +414,7 @@ fn main() {
             let fatigue_dim = 1.0 - p.fatigue * 0.3; // Fatigued neurons look dimmer
 
             p.color = mix(base_color * fatigue_dim, fire_color, p.activation + potential_glow);
-        "#.into()))
+        "#))
         // Gentle drift to prevent static structure
         .with_rule(Rule::Wander {
             strength: 0.02,
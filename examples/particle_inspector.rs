@@ -106,7 +106,7 @@ fn main() {
         .with_rule(Rule::Drag(2.0))
         .with_rule(Rule::SpeedLimit { min: 0.0, max: 0.5 })
         // Energy affects color brightness
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             // Slowly regenerate energy
             p.energy = min(p.energy + 0.01 * uniforms.delta_time, 1.0);
@@ -117,8 +117,7 @@ fn main() {
             // Update color intensity based on energy
             let intensity = 0.3 + p.energy * 0.7;
             p.color = p.color * intensity / length(p.color);
-            "#
-            .into(),
+            "#,
         ))
         .with_rule(Rule::BounceWalls)
         // Visual setup
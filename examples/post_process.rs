@@ -138,12 +138,12 @@ fn main() {
             "#);
         })
         // Gentle orbital motion
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             let dist = length(p.position);
             let tangent = normalize(cross(p.position, vec3<f32>(0.0, 1.0, 0.0)));
             p.velocity += tangent * 0.2;
             p.velocity *= 0.985;
-        "#.into()))
+        "#))
         .run().expect("Simulation failed");
 }
 
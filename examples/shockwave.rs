@@ -6,7 +6,7 @@
 //! ## What This Demonstrates
 //!
 //! - `Rule::Shockwave` - expanding ring of force
-//! - `Rule::Pulse` - rhythmic push/pull from a point
+//! - `Rule::RadialPulse` - rhythmic push/pull from a point
 //! - `Rule::Radial` - distance-based force with falloff
 //! - `Falloff::Linear` - force decreases linearly with distance
 //! - `Palette::Ocean` with `ColorMapping::Distance`
@@ -82,7 +82,7 @@ fn main() {
             repeat: 3.0,
         })
         // Gentle breathing pulse
-        .with_rule(Rule::Pulse {
+        .with_rule(Rule::RadialPulse {
             point: Vec3::ZERO,
             strength: 0.5,
             frequency: 0.3,
@@ -166,7 +166,7 @@ fn main() {
             ctx.set("deposit_amount", s.deposit_amount);
         })
         // Slime mold behavior: sense pheromones, turn toward highest, deposit trail
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             let dt = uniforms.delta_time;
             let speed = uniforms.speed;
@@ -224,8 +224,7 @@ fn main() {
 
             // Zero velocity (we handle movement directly)
             p.velocity = vec3<f32>(0.0, 0.0, 0.0);
-        "#
-            .into(),
+        "#,
         ))
         .run().expect("Simulation failed");
 }
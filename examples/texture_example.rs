@@ -95,7 +95,7 @@ fn main() {
         .with_texture("noise", noise_texture)
         .with_texture("gradient", gradient)
         // Simple orbital motion
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             let to_center = -p.position;
             let dist = length(to_center);
             let dir = normalize(to_center);
@@ -104,7 +104,7 @@ fn main() {
             // Tangential velocity
             let tangent = vec3<f32>(-dir.z, 0.0, dir.x);
             p.velocity += tangent * 0.1 * uniforms.delta_time;
-        "#.into()))
+        "#))
         .with_rule(Rule::Drag(0.5))
         // Custom fragment shader that uses textures
         .with_fragment_shader(r#"
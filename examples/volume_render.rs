@@ -90,6 +90,8 @@ fn main() {
             center: Vec3::ZERO,
             axis: Vec3::Y,
             strength: 0.5,
+            radius: 0.0,
+            falloff: Falloff::Constant,
         })
         .with_rule(Rule::Wander { strength: 0.3, frequency: 100.0 })
         .with_rule(Rule::AttractTo {
@@ -98,12 +100,11 @@ fn main() {
         })
         .with_rule(Rule::SpeedLimit { min: 0.0, max: 1.0 })
         // Deposit into the field and bounce off walls
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             // Deposit particle presence into the field
             field_write(0u, p.position, 0.3);
-            "#
-            .into(),
+            "#,
         ))
         .with_rule(Rule::BounceWalls)
         .with_visuals(|v| {
@@ -209,7 +209,7 @@ fn main() {
             frequency: 0.5,
         })
         // Custom wave emission to field
-        .with_rule(Rule::Custom(r#"
+        .with_rule(Rule::custom(r#"
             // Skip dead particles
             if p.amplitude < 0.01 {
                 return;
@@ -253,7 +253,7 @@ fn main() {
                     }
                 }
             }
-        "#.into()))
+        "#))
         // Keep sources contained
         .with_rule(Rule::Drag(2.0))
         .with_rule(Rule::SpeedLimit { min: 0.0, max: 0.5 })
@@ -70,13 +70,12 @@ fn main() {
             strength: 0.2,
         })
         // Add some swirl
-        .with_rule(Rule::Custom(
+        .with_rule(Rule::custom(
             r#"
             let r = length(p.position.xz);
             let swirl = 0.2 / (r + 0.2);
             p.velocity += vec3<f32>(-p.position.z, 0.0, p.position.x) * swirl * uniforms.delta_time;
-            "#
-            .into(),
+            "#,
         ))
         // Light drag
         .with_rule(Rule::Drag(0.5))
@@ -47,7 +47,7 @@
 //!
 //! - `particle_type: u32` - For typed interactions (auto-added if missing)
 //! - `#[color] color: Vec3` - Custom particle color
-//! - Any `f32`, `u32`, `i32`, `Vec2`, `Vec3`, `Vec4` fields
+//! - Any `f32`, `u32`, `i32`, `bool`, `Vec2`, `Vec3`, `Vec4` fields
 //!
 //! ## GPU Memory Layout
 //!
@@ -120,6 +120,10 @@ use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident, Type};
 ///
 /// // Get variant count
 /// let num_species = Species::count();  // 3
+///
+/// // Checked conversion (e.g. for GPU readback)
+/// assert_eq!(Species::try_from_u32(1), Some(Species::Predator));
+/// assert_eq!(Species::try_from_u32(7), None);
 /// ```
 ///
 /// # Panics
@@ -171,6 +175,17 @@ pub fn derive_particle_type(input: TokenStream) -> TokenStream {
         })
         .collect();
 
+    // Generate match arms for try_from_u32
+    let try_from_arms: Vec<_> = variants
+        .iter()
+        .enumerate()
+        .map(|(i, variant)| {
+            let variant_name = &variant.ident;
+            let idx = i as u32;
+            quote! { #idx => Some(#name::#variant_name) }
+        })
+        .collect();
+
     let first_variant = &variants.first().expect("Enum must have at least one variant").ident;
     let variant_count = variants.len() as u32;
 
@@ -197,6 +212,17 @@ pub fn derive_particle_type(input: TokenStream) -> TokenStream {
             pub const fn count() -> u32 {
                 #variant_count
             }
+
+            /// Converts a raw type id back into a variant.
+            ///
+            /// Returns `None` for values `>= count()`, unlike `From<u32>`
+            /// which falls back to the first variant.
+            pub fn try_from_u32(v: u32) -> Option<#name> {
+                match v {
+                    #(#try_from_arms,)*
+                    _ => None,
+                }
+            }
         }
     };
 
@@ -226,7 +252,32 @@ pub fn derive_particle_type(input: TokenStream) -> TokenStream {
 /// |-------|------|---------|
 /// | `particle_type` | `u32` | Category for typed interactions (auto-added if missing) |
 /// | `#[color] name` | `Vec3` | Custom particle color (RGB, 0.0-1.0) |
-/// | *(any name)* | `f32`, `u32`, `i32`, `Vec2`, `Vec3`, `Vec4` | Custom data |
+/// | *(any name)* | `f32`, `u32`, `i32`, `bool`, `Vec2`, `Vec3`, `Vec4` | Custom data |
+///
+/// # Field Attributes
+///
+/// - `#[color]` - Marks a `Vec3` field as the particle color
+/// - `#[range(min, max)]` - Clamps an `f32`, `u32` or `i32` field in the
+///   inspector and in `from_gpu`. Ignored (with a warning) on vector fields.
+/// - `#[label("Display Name")]` - Name shown for the field in the inspector
+///   (`inspect_fields` and `render_editable_fields`). The GPU and WGSL field
+///   names are unchanged.
+/// - `#[hidden]` - Keeps the field on the GPU but omits it from the
+///   inspector entirely (useful for packed flags or adjacency indices)
+///
+/// # Default
+///
+/// Add `#[particle(default)]` to the struct to generate a zero-initializing
+/// `Default` impl (`0.0`, `0`, `false`, `Vec3::ZERO`, ...). Without it, derive
+/// or implement `Default` yourself as usual:
+///
+/// ```ignore
+/// #[derive(Particle, Clone)]
+/// #[particle(default)]
+/// struct Spark { position: Vec3, velocity: Vec3, heat: f32 }
+///
+/// let spark = Spark::default(); // all zeros
+/// ```
 ///
 /// # Supported Types
 ///
@@ -238,6 +289,10 @@ pub fn derive_particle_type(input: TokenStream) -> TokenStream {
 /// | `f32` | `f32` | 4 bytes | 4 bytes |
 /// | `u32` | `u32` | 4 bytes | 4 bytes |
 /// | `i32` | `i32` | 4 bytes | 4 bytes |
+/// | `bool` | `u32` | 4 bytes | 4 bytes |
+///
+/// `bool` fields are stored as `u32` on the GPU (`0u` = false, `1u` = true),
+/// so WGSL code compares them with `p.flag != 0u`.
 ///
 /// # GPU Memory Layout
 ///
@@ -318,8 +373,8 @@ pub fn derive_particle_type(input: TokenStream) -> TokenStream {
 /// The macro panics at compile time if:
 /// - Applied to an enum instead of a struct
 /// - Struct uses tuple fields instead of named fields
-/// - Any field has an unsupported type
-#[proc_macro_derive(Particle, attributes(color))]
+/// - Any field has an unsupported type (reported as a compile error on the field)
+#[proc_macro_derive(Particle, attributes(color, range, label, hidden, particle))]
 pub fn derive_particle(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
@@ -339,10 +394,13 @@ pub fn derive_particle(input: TokenStream) -> TokenStream {
     let mut from_gpu_conversions = Vec::new();
     let mut inspect_field_entries = Vec::new();
     let mut editable_field_widgets = Vec::new();
+    let mut default_field_values = Vec::new();
+    let mut range_warnings = Vec::new();
     let mut field_offset = 0u32;
     let mut padding_count = 0u32;
     let mut color_field: Option<String> = None;
     let mut color_offset: Option<u32> = None;
+    let mut velocity_offset: Option<u32> = None;
     let mut has_particle_type = false;
 
     for field in fields.iter() {
@@ -356,15 +414,32 @@ pub fn derive_particle(input: TokenStream) -> TokenStream {
         let field_name = field.ident.as_ref().unwrap();
         let field_name_str = field_name.to_string();
         let field_type = &field.ty;
-        let type_info = rust_type_info(field_type);
+        let type_info = match rust_type_info(field_type) {
+            Ok(info) => info,
+            Err(err) => return err.to_compile_error().into(),
+        };
 
-        // Check for #[color] attribute - need to track offset before padding
+        // Check for #[color] and #[hidden] attributes - need to track offset before padding
         let mut is_color_field = false;
+        let mut is_hidden = false;
         for attr in &field.attrs {
             if attr.path().is_ident("color") {
                 color_field = Some(field_name_str.clone());
                 is_color_field = true;
             }
+            if attr.path().is_ident("hidden") {
+                is_hidden = true;
+            }
+        }
+
+        // Check for #[label("...")] attribute (inspector display name)
+        let display_name = parse_field_label(&field.attrs).unwrap_or_else(|| field_name_str.clone());
+
+        // Check for #[range(min, max)] attribute (scalar fields only)
+        let field_type_str = type_string(field_type);
+        let range = parse_field_range(&field.attrs);
+        if range.is_some() && !is_range_type(&field_type_str) {
+            range_warnings.push(range_ignored_warning(field_name));
         }
 
         // Add padding before field if needed for alignment
@@ -391,6 +466,9 @@ pub fn derive_particle(input: TokenStream) -> TokenStream {
         if is_color_field {
             color_offset = Some(field_offset);
         }
+        if field_name_str == "velocity" {
+            velocity_offset = Some(field_offset);
+        }
 
         // Add the actual field
         wgsl_fields.push(format!("    {}: {},", field_name_str, type_info.wgsl_type));
@@ -402,15 +480,22 @@ pub fn derive_particle(input: TokenStream) -> TokenStream {
         to_gpu_conversions.push(quote! { #field_name: #conversion });
 
         let reverse_conversion = generate_reverse_conversion(field_name, field_type);
+        let reverse_conversion = clamp_to_range(reverse_conversion, range, &field_type_str);
         from_gpu_conversions.push(quote! { #field_name: #reverse_conversion });
 
-        // Generate inspect field entry with nice formatting
-        let inspect_format = generate_inspect_format(field_name, field_type);
-        inspect_field_entries.push(quote! { (#field_name_str, #inspect_format) });
+        let default_value = default_field_value(&field_type_str);
+        default_field_values.push(quote! { #field_name: #default_value });
 
-        // Generate editable widget for this field
-        let editable_widget = generate_editable_widget(field_name, &field_name_str, field_type);
-        editable_field_widgets.push(editable_widget);
+        // Hidden fields live on the GPU but are left out of the inspector
+        if !is_hidden {
+            // Generate inspect field entry with nice formatting
+            let inspect_format = generate_inspect_format(field_name, field_type);
+            inspect_field_entries.push(quote! { (#display_name, #inspect_format) });
+
+            // Generate editable widget for this field
+            let editable_widget = generate_editable_widget(field_name, &display_name, field_type, range);
+            editable_field_widgets.push(editable_widget);
+        }
 
         field_offset += type_info.size;
     }
@@ -488,8 +573,29 @@ pub fn derive_particle(input: TokenStream) -> TokenStream {
         gpu_struct_fields.push(quote! { scale: f32 });
         to_gpu_conversions.push(quote! { scale: 1.0 }); // Default scale of 1.0
         field_offset += 4;
+
+        // triggered: u32, set once an age-triggered sub-emitter has fired
+        wgsl_fields.push("    triggered: u32,".to_string());
+        gpu_struct_fields.push(quote! { triggered: u32 });
+        to_gpu_conversions.push(quote! { triggered: 0u32 });
+        field_offset += 4;
     }
 
+    // Zero-initializing Default impl, only when asked for
+    let default_impl = if wants_default_impl(&input.attrs) {
+        quote! {
+            impl Default for #name {
+                fn default() -> Self {
+                    Self {
+                        #(#default_field_values),*
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
     // Ensure struct size is multiple of 16 (vec4 alignment for GPU arrays)
     let final_padding = (16 - (field_offset % 16)) % 16;
     if final_padding > 0 {
@@ -515,10 +621,8 @@ pub fn derive_particle(input: TokenStream) -> TokenStream {
         None => quote! { None },
     };
 
-    let color_offset_expr = match color_offset {
-        Some(offset) => quote! { Some(#offset) },
-        None => quote! { None },
-    };
+    let color_offset_expr = option_u32_tokens(color_offset);
+    let velocity_offset_expr = option_u32_tokens(velocity_offset);
 
     let expanded = quote! {
         #[repr(C)]
@@ -535,6 +639,8 @@ pub fn derive_particle(input: TokenStream) -> TokenStream {
             const COLOR_OFFSET: Option<u32> = #color_offset_expr;
             const ALIVE_OFFSET: u32 = #alive_offset;
             const SCALE_OFFSET: u32 = #scale_offset;
+            const VELOCITY_OFFSET: Option<u32> = #velocity_offset_expr;
+            const GPU_STRIDE: usize = std::mem::size_of::<#gpu_name>();
 
             fn to_gpu(&self) -> Self::Gpu {
                 #gpu_name {
@@ -566,11 +672,49 @@ pub fn derive_particle(input: TokenStream) -> TokenStream {
                 modified
             }
         }
+
+        #default_impl
+
+        #(#range_warnings)*
     };
 
     TokenStream::from(expanded)
 }
 
+/// Whether the Particle derive should emit a `Default` impl.
+///
+/// Opt-in through `#[particle(default)]`. Emitting it unconditionally would
+/// conflict with a `Default` listed in the same `#[derive(...)]` (which is
+/// stripped before this macro runs, so it can't be detected) and with
+/// hand-written impls.
+fn wants_default_impl(attrs: &[syn::Attribute]) -> bool {
+    let mut wants_default = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("particle")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("default") {
+                wants_default = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown #[particle] option (expected `default`)"))
+            }
+        })
+        .unwrap_or_else(|e| panic!("Invalid #[particle] attribute: {}", e));
+    }
+    wants_default
+}
+
+/// Zero value for a field type, used by the generated `Default` impl.
+fn default_field_value(type_str: &str) -> proc_macro2::TokenStream {
+    match type_str {
+        "Vec3" | "glam::Vec3" => quote! { ::glam::Vec3::ZERO },
+        "Vec2" | "glam::Vec2" => quote! { ::glam::Vec2::ZERO },
+        "Vec4" | "glam::Vec4" => quote! { ::glam::Vec4::ZERO },
+        "f32" => quote! { 0.0 },
+        "bool" => quote! { false },
+        _ => quote! { 0 },
+    }
+}
+
 /// Type metadata for GPU memory layout calculations.
 struct TypeInfo {
     /// WGSL type name (e.g., "vec3<f32>")
@@ -583,13 +727,137 @@ struct TypeInfo {
     align: u32,
 }
 
+/// Normalize a type to a compact string for matching.
+///
+/// Whitespace is removed and a leading `::` is stripped, so `::glam::Vec3`
+/// matches the same arms as `glam::Vec3`.
+fn type_string(ty: &Type) -> String {
+    let type_str = quote!(#ty).to_string().replace(" ", "");
+    match type_str.strip_prefix("::") {
+        Some(stripped) => stripped.to_string(),
+        None => type_str,
+    }
+}
+
+/// A MultiParticle variant field: (name, type string, is_color, range, display name, is_hidden).
+type MultiField = (Ident, String, bool, Option<FieldRange>, String, bool);
+
+/// Inclusive bounds from a `#[range(min, max)]` field attribute.
+#[derive(Clone, Copy)]
+struct FieldRange {
+    min: f64,
+    max: f64,
+}
+
+/// Parse an optional `#[label("Display Name")]` attribute from a field.
+///
+/// Panics (a compile error for the user) if the argument isn't a string literal.
+fn parse_field_label(attrs: &[syn::Attribute]) -> Option<String> {
+    let attr = attrs.iter().find(|a| a.path().is_ident("label"))?;
+    let lit: syn::LitStr = attr
+        .parse_args()
+        .unwrap_or_else(|_| panic!("#[label] expects a string literal: #[label(\"Display Name\")]"));
+    Some(lit.value())
+}
+
+/// Parse an optional `#[range(min, max)]` attribute from a field.
+///
+/// Bounds may be integer or float literals, optionally negated.
+fn parse_field_range(attrs: &[syn::Attribute]) -> Option<FieldRange> {
+    let attr = attrs.iter().find(|a| a.path().is_ident("range"))?;
+    let args = attr
+        .parse_args_with(syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated)
+        .unwrap_or_else(|e| panic!("Invalid #[range] attribute: {}", e));
+    let bounds: Vec<f64> = args.iter().map(range_bound_value).collect();
+    if bounds.len() != 2 {
+        panic!("#[range] expects exactly two bounds: #[range(min, max)]");
+    }
+    if bounds[0] > bounds[1] {
+        panic!("#[range] min ({}) must not exceed max ({})", bounds[0], bounds[1]);
+    }
+    Some(FieldRange { min: bounds[0], max: bounds[1] })
+}
+
+/// Evaluate a literal `#[range]` bound.
+fn range_bound_value(expr: &syn::Expr) -> f64 {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Float(f), .. }) => f.base10_parse().unwrap(),
+        syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(i), .. }) => i.base10_parse().unwrap(),
+        syn::Expr::Unary(syn::ExprUnary { op: syn::UnOp::Neg(_), expr, .. }) => -range_bound_value(expr),
+        _ => panic!("#[range] bounds must be numeric literals, got: {}", quote!(#expr)),
+    }
+}
+
+/// Whether a `#[range]` attribute applies to this type.
+fn is_range_type(type_str: &str) -> bool {
+    matches!(type_str, "f32" | "u32" | "i32")
+}
+
+/// Emit `min` and `max` as literals of the field's type.
+///
+/// `u32` fields are edited through an `i64` in the inspector, so `widget`
+/// selects `i64` literals for them.
+fn range_literals(range: FieldRange, type_str: &str, widget: bool) -> (proc_macro2::Literal, proc_macro2::Literal) {
+    use proc_macro2::Literal;
+    match type_str {
+        "f32" => (Literal::f32_suffixed(range.min as f32), Literal::f32_suffixed(range.max as f32)),
+        "u32" if widget => (Literal::i64_suffixed(range.min.max(0.0) as i64), Literal::i64_suffixed(range.max.max(0.0) as i64)),
+        "u32" => (Literal::u32_suffixed(range.min.max(0.0) as u32), Literal::u32_suffixed(range.max.max(0.0) as u32)),
+        _ => (Literal::i32_suffixed(range.min as i32), Literal::i32_suffixed(range.max as i32)),
+    }
+}
+
+/// Generate a `.range(min..=max)` call for a `DragValue`, or nothing.
+fn drag_value_range(range: Option<FieldRange>, type_str: &str) -> proc_macro2::TokenStream {
+    match range {
+        Some(range) if is_range_type(type_str) => {
+            let (min, max) = range_literals(range, type_str, true);
+            quote! { .range(#min..=#max) }
+        }
+        _ => quote! {},
+    }
+}
+
+/// Wrap a GPU-to-Rust conversion in a clamp to the field's range, if any.
+fn clamp_to_range(
+    conversion: proc_macro2::TokenStream,
+    range: Option<FieldRange>,
+    type_str: &str,
+) -> proc_macro2::TokenStream {
+    match range {
+        Some(range) if is_range_type(type_str) => {
+            let (min, max) = range_literals(range, type_str, false);
+            quote! { (#conversion).clamp(#min, #max) }
+        }
+        _ => conversion,
+    }
+}
+
+/// Emit a compile-time warning that `#[range]` was ignored on a field.
+///
+/// Proc macros can't emit warnings directly on stable, so this references a
+/// deprecated constant spanned to the field.
+fn range_ignored_warning(field_name: &Ident) -> proc_macro2::TokenStream {
+    let note = format!("#[range] only applies to f32, u32 and i32 fields; ignored on `{}`", field_name);
+    let marker = Ident::new("range_ignored", field_name.span());
+    quote! {
+        const _: () = {
+            #[deprecated(note = #note)]
+            #[allow(non_upper_case_globals)]
+            const #marker: () = ();
+            #marker
+        };
+    }
+}
+
 /// Get type information for a Rust type.
 ///
 /// Maps Rust types to their WGSL equivalents and alignment requirements.
-fn rust_type_info(ty: &Type) -> TypeInfo {
-    let type_str = quote!(#ty).to_string().replace(" ", "");
+/// Unsupported types produce an error spanned to the field's type.
+fn rust_type_info(ty: &Type) -> syn::Result<TypeInfo> {
+    let type_str = type_string(ty);
 
-    match type_str.as_str() {
+    let info = match type_str.as_str() {
         "Vec3" | "glam::Vec3" => TypeInfo {
             wgsl_type: "vec3<f32>",
             gpu_type: quote! { [f32; 3] },
@@ -626,20 +894,42 @@ fn rust_type_info(ty: &Type) -> TypeInfo {
             size: 4,
             align: 4,
         },
-        _ => panic!("Unsupported type in Particle struct: {}", type_str),
-    }
+        // WGSL has no host-shareable bool, so pack as u32
+        "bool" => TypeInfo {
+            wgsl_type: "u32",
+            gpu_type: quote! { u32 },
+            size: 4,
+            align: 4,
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ty,
+                format!(
+                    "unsupported type `{}` in Particle struct\n\
+                     note: supported types are Vec2, Vec3, Vec4, f32, u32, i32 and bool\n\
+                     help: use one of the supported types, or store a newtype's inner value \
+                     in a supported field and convert it yourself",
+                    type_str
+                ),
+            ))
+        }
+    };
+    Ok(info)
 }
 
 /// Generate code to convert a field from Rust to GPU format.
 ///
 /// Vector types need `.to_array()`, scalars are passed through.
 fn generate_conversion(field_name: &Ident, ty: &Type) -> proc_macro2::TokenStream {
-    let type_str = quote!(#ty).to_string().replace(" ", "");
+    let type_str = type_string(ty);
 
     match type_str.as_str() {
         "Vec3" | "glam::Vec3" | "Vec2" | "glam::Vec2" | "Vec4" | "glam::Vec4" => {
             quote! { self.#field_name.to_array() }
         }
+        "bool" => {
+            quote! { self.#field_name as u32 }
+        }
         _ => {
             quote! { self.#field_name }
         }
@@ -650,17 +940,20 @@ fn generate_conversion(field_name: &Ident, ty: &Type) -> proc_macro2::TokenStrea
 ///
 /// Vector types need `from_array()`, scalars are passed through.
 fn generate_reverse_conversion(field_name: &Ident, ty: &Type) -> proc_macro2::TokenStream {
-    let type_str = quote!(#ty).to_string().replace(" ", "");
+    let type_str = type_string(ty);
 
     match type_str.as_str() {
         "Vec3" | "glam::Vec3" => {
-            quote! { glam::Vec3::from_array(gpu.#field_name) }
+            quote! { ::glam::Vec3::from_array(gpu.#field_name) }
         }
         "Vec2" | "glam::Vec2" => {
-            quote! { glam::Vec2::from_array(gpu.#field_name) }
+            quote! { ::glam::Vec2::from_array(gpu.#field_name) }
         }
         "Vec4" | "glam::Vec4" => {
-            quote! { glam::Vec4::from_array(gpu.#field_name) }
+            quote! { ::glam::Vec4::from_array(gpu.#field_name) }
+        }
+        "bool" => {
+            quote! { gpu.#field_name != 0 }
         }
         _ => {
             quote! { gpu.#field_name }
@@ -672,7 +965,7 @@ fn generate_reverse_conversion(field_name: &Ident, ty: &Type) -> proc_macro2::To
 ///
 /// Produces human-readable formatted strings for the inspector panel.
 fn generate_inspect_format(field_name: &Ident, ty: &Type) -> proc_macro2::TokenStream {
-    let type_str = quote!(#ty).to_string().replace(" ", "");
+    let type_str = type_string(ty);
 
     match type_str.as_str() {
         "Vec3" | "glam::Vec3" => {
@@ -693,7 +986,7 @@ fn generate_inspect_format(field_name: &Ident, ty: &Type) -> proc_macro2::TokenS
         "f32" => {
             quote! { format!("{:.3}", self.#field_name) }
         }
-        "u32" | "i32" => {
+        "u32" | "i32" | "bool" => {
             quote! { format!("{}", self.#field_name) }
         }
         _ => {
@@ -705,8 +998,14 @@ fn generate_inspect_format(field_name: &Ident, ty: &Type) -> proc_macro2::TokenS
 /// Generate editable UI widget code for a field.
 ///
 /// Produces egui widget code for editing particle fields in the inspector.
-fn generate_editable_widget(field_name: &Ident, field_name_str: &str, ty: &Type) -> proc_macro2::TokenStream {
-    let type_str = quote!(#ty).to_string().replace(" ", "");
+fn generate_editable_widget(
+    field_name: &Ident,
+    field_name_str: &str,
+    ty: &Type,
+    range: Option<FieldRange>,
+) -> proc_macro2::TokenStream {
+    let type_str = type_string(ty);
+    let range_call = drag_value_range(range, &type_str);
 
     match type_str.as_str() {
         "Vec3" | "glam::Vec3" => {
@@ -763,7 +1062,7 @@ fn generate_editable_widget(field_name: &Ident, field_name_str: &str, ty: &Type)
         "f32" => {
             quote! {
                 ui.label(#field_name_str);
-                if ui.add(egui::DragValue::new(&mut self.#field_name).speed(0.01)).changed() {
+                if ui.add(egui::DragValue::new(&mut self.#field_name).speed(0.01)#range_call).changed() {
                     modified = true;
                 }
                 ui.end_row();
@@ -773,7 +1072,7 @@ fn generate_editable_widget(field_name: &Ident, field_name_str: &str, ty: &Type)
             quote! {
                 ui.label(#field_name_str);
                 let mut val = self.#field_name as i64;
-                if ui.add(egui::DragValue::new(&mut val).speed(1.0)).changed() {
+                if ui.add(egui::DragValue::new(&mut val).speed(1.0)#range_call).changed() {
                     self.#field_name = val.max(0) as u32;
                     modified = true;
                 }
@@ -783,7 +1082,16 @@ fn generate_editable_widget(field_name: &Ident, field_name_str: &str, ty: &Type)
         "i32" => {
             quote! {
                 ui.label(#field_name_str);
-                if ui.add(egui::DragValue::new(&mut self.#field_name).speed(1.0)).changed() {
+                if ui.add(egui::DragValue::new(&mut self.#field_name).speed(1.0)#range_call).changed() {
+                    modified = true;
+                }
+                ui.end_row();
+            }
+        }
+        "bool" => {
+            quote! {
+                ui.label(#field_name_str);
+                if ui.checkbox(&mut self.#field_name, "").changed() {
                     modified = true;
                 }
                 ui.end_row();
@@ -803,7 +1111,13 @@ fn generate_editable_widget(field_name: &Ident, field_name_str: &str, ty: &Type)
 /// Generate editable UI widget code for a field (from string type).
 ///
 /// Used by MultiParticle derive where we have type as string.
-fn generate_editable_widget_from_string(field_name: &Ident, field_name_str: &str, type_str: &str) -> proc_macro2::TokenStream {
+fn generate_editable_widget_from_string(
+    field_name: &Ident,
+    field_name_str: &str,
+    type_str: &str,
+    range: Option<FieldRange>,
+) -> proc_macro2::TokenStream {
+    let range_call = drag_value_range(range, type_str);
     match type_str {
         "Vec3" => {
             quote! {
@@ -859,7 +1173,7 @@ fn generate_editable_widget_from_string(field_name: &Ident, field_name_str: &str
         "f32" => {
             quote! {
                 ui.label(#field_name_str);
-                if ui.add(egui::DragValue::new(&mut self.#field_name).speed(0.01)).changed() {
+                if ui.add(egui::DragValue::new(&mut self.#field_name).speed(0.01)#range_call).changed() {
                     modified = true;
                 }
                 ui.end_row();
@@ -869,7 +1183,7 @@ fn generate_editable_widget_from_string(field_name: &Ident, field_name_str: &str
             quote! {
                 ui.label(#field_name_str);
                 let mut val = self.#field_name as i64;
-                if ui.add(egui::DragValue::new(&mut val).speed(1.0)).changed() {
+                if ui.add(egui::DragValue::new(&mut val).speed(1.0)#range_call).changed() {
                     self.#field_name = val.max(0) as u32;
                     modified = true;
                 }
@@ -879,7 +1193,7 @@ fn generate_editable_widget_from_string(field_name: &Ident, field_name_str: &str
         "i32" => {
             quote! {
                 ui.label(#field_name_str);
-                if ui.add(egui::DragValue::new(&mut self.#field_name).speed(1.0)).changed() {
+                if ui.add(egui::DragValue::new(&mut self.#field_name).speed(1.0)#range_call).changed() {
                     modified = true;
                 }
                 ui.end_row();
@@ -954,6 +1268,8 @@ fn generate_editable_widget_from_string(field_name: &Ident, field_name_str: &str
 /// - Each variant must use struct-like syntax with named fields
 /// - Each variant must have `position: Vec3` and `velocity: Vec3` fields
 /// - Use `#[color]` attribute on a `Vec3` field for custom particle color
+/// - `#[range]`, `#[label]` and `#[hidden]` work on variant fields as they do
+///   for `#[derive(Particle)]`
 ///
 /// # Generated Code
 ///
@@ -989,7 +1305,7 @@ fn generate_editable_widget_from_string(field_name: &Ident, field_name_str: &str
 ///     p.hunger -= uniforms.delta_time * 0.1;
 /// }
 /// ```
-#[proc_macro_derive(MultiParticle, attributes(color))]
+#[proc_macro_derive(MultiParticle, attributes(color, range, label, hidden))]
 pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let enum_name = &input.ident;
@@ -1002,8 +1318,9 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
         _ => panic!("MultiParticle derive only supports enums"),
     };
 
-    // Collect variant info: (name, fields as Vec<(name, type_string, is_color)>)
-    let mut variant_info: Vec<(Ident, Vec<(Ident, String, bool)>)> = Vec::new();
+    // Collect variant info: (name, fields as Vec<(name, type_string, is_color, range, label, is_hidden)>)
+    let mut variant_info: Vec<(Ident, Vec<MultiField>)> = Vec::new();
+    let mut range_warnings = Vec::new();
 
     for variant in variants.iter() {
         let variant_name = variant.ident.clone();
@@ -1013,9 +1330,15 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
                 named.named.iter().map(|f| {
                     let field_name = f.ident.clone().unwrap();
                     let ty = &f.ty;
-                    let type_str = quote!(#ty).to_string().replace(" ", "");
+                    let type_str = type_string(ty);
                     let is_color = f.attrs.iter().any(|a| a.path().is_ident("color"));
-                    (field_name, type_str, is_color)
+                    let range = parse_field_range(&f.attrs);
+                    if range.is_some() && !is_range_type(&type_str.replace("glam::", "")) {
+                        range_warnings.push(range_ignored_warning(&field_name));
+                    }
+                    let label = parse_field_label(&f.attrs).unwrap_or_else(|| field_name.to_string());
+                    let is_hidden = f.attrs.iter().any(|a| a.path().is_ident("hidden"));
+                    (field_name, type_str, is_color, range, label, is_hidden)
                 }).collect::<Vec<_>>()
             }
             _ => panic!(
@@ -1025,8 +1348,8 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
         };
 
         // Validate required fields
-        let has_position = fields.iter().any(|(n, t, _)| n == "position" && (t == "Vec3" || t == "glam::Vec3"));
-        let has_velocity = fields.iter().any(|(n, t, _)| n == "velocity" && (t == "Vec3" || t == "glam::Vec3"));
+        let has_position = fields.iter().any(|(n, t, ..)| n == "position" && (t == "Vec3" || t == "glam::Vec3"));
+        let has_velocity = fields.iter().any(|(n, t, ..)| n == "velocity" && (t == "Vec3" || t == "glam::Vec3"));
 
         if !has_position {
             panic!("MultiParticle variant '{}' must have 'position: Vec3' field", variant_name);
@@ -1047,14 +1370,15 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
         let struct_gpu_name = Ident::new(&format!("{}Gpu", variant_name), Span::call_site());
 
         // Build struct fields
-        let struct_fields: Vec<_> = fields.iter().map(|(name, type_str, _)| {
+        let struct_fields: Vec<_> = fields.iter().map(|(name, type_str, ..)| {
             let ty = rust_type_from_string(type_str);
             quote! { pub #name: #ty }
         }).collect();
 
         // Build GPU struct using the generate_particle_gpu_struct helper
-        let (gpu_fields, wgsl_struct, color_field, color_offset, alive_offset, scale_offset) =
+        let (gpu_fields, wgsl_struct, color_field, color_offset, velocity_offset, alive_offset, scale_offset) =
             generate_particle_gpu_struct(fields, false); // false = include particle_type
+        let velocity_offset_expr = option_u32_tokens(velocity_offset);
 
         let gpu_field_tokens: Vec<_> = gpu_fields.iter().map(|(name, ty)| {
             let name_ident = Ident::new(name, Span::call_site());
@@ -1075,11 +1399,13 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
                 quote! { #name_ident: 1u32 }
             } else if name == "scale" {
                 quote! { #name_ident: 1.0 }
+            } else if name == "triggered" {
+                quote! { #name_ident: 0u32 }
             } else {
                 // User field - check if it needs to_array
                 #[allow(clippy::cmp_owned)]
-                let field_info = fields.iter().find(|(n, _, _)| n.to_string() == *name);
-                if let Some((_, type_str, _)) = field_info {
+                let field_info = fields.iter().find(|(n, ..)| n.to_string() == *name);
+                if let Some((_, type_str, ..)) = field_info {
                     if type_str == "Vec3" || type_str == "Vec2" || type_str == "Vec4" ||
                        type_str == "glam::Vec3" || type_str == "glam::Vec2" || type_str == "glam::Vec4" {
                         quote! { #name_ident: self.#name_ident.to_array() }
@@ -1093,20 +1419,24 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
         }).collect();
 
         // Build from_gpu conversions for each user field
-        let from_gpu_conversions: Vec<_> = fields.iter().map(|(name, type_str, _)| {
+        let from_gpu_conversions: Vec<_> = fields.iter().map(|(name, type_str, _, range, ..)| {
             let name_ident = name;
             let type_normalized = type_str.replace("glam::", "");
             match type_normalized.as_str() {
                 "Vec3" => quote! { #name_ident: rdpe::Vec3::from_array(gpu.#name_ident) },
                 "Vec2" => quote! { #name_ident: rdpe::Vec2::from_array(gpu.#name_ident) },
                 "Vec4" => quote! { #name_ident: rdpe::Vec4::from_array(gpu.#name_ident) },
-                _ => quote! { #name_ident: gpu.#name_ident },
+                _ => {
+                    let value = clamp_to_range(quote! { gpu.#name_ident }, *range, &type_normalized);
+                    quote! { #name_ident: #value }
+                }
             }
         }).collect();
 
-        // Build inspect field entries
-        let inspect_entries: Vec<_> = fields.iter().map(|(name, type_str, _)| {
-            let name_str = name.to_string();
+        // Build inspect field entries (hidden fields are skipped)
+        let visible_fields = || fields.iter().filter(|(.., is_hidden)| !is_hidden);
+        let inspect_entries: Vec<_> = visible_fields().map(|(name, type_str, _, _, label, _)| {
+            let name_str = label;
             let type_normalized = type_str.replace("glam::", "");
             match type_normalized.as_str() {
                 "Vec3" => quote! { (#name_str, format!("({:.3}, {:.3}, {:.3})", self.#name.x, self.#name.y, self.#name.z)) },
@@ -1119,10 +1449,9 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
         }).collect();
 
         // Build editable widget entries
-        let editable_entries: Vec<_> = fields.iter().map(|(name, type_str, _)| {
-            let name_str = name.to_string();
+        let editable_entries: Vec<_> = visible_fields().map(|(name, type_str, _, range, label, _)| {
             let type_normalized = type_str.replace("glam::", "");
-            generate_editable_widget_from_string(name, &name_str, &type_normalized)
+            generate_editable_widget_from_string(name, label, &type_normalized, *range)
         }).collect();
 
         let color_field_expr = match &color_field {
@@ -1156,6 +1485,7 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
                 const COLOR_OFFSET: Option<u32> = #color_offset_expr;
                 const ALIVE_OFFSET: u32 = #alive_offset;
                 const SCALE_OFFSET: u32 = #scale_offset;
+                const VELOCITY_OFFSET: Option<u32> = #velocity_offset_expr;
 
                 fn to_gpu(&self) -> Self::Gpu {
                     #struct_gpu_name {
@@ -1198,36 +1528,56 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
         ("velocity".to_string(), "Vec3".to_string(), false),
     ];
 
-    let mut seen_fields: std::collections::HashMap<String, String> = std::collections::HashMap::new();
-    seen_fields.insert("position".to_string(), "Vec3".to_string());
-    seen_fields.insert("velocity".to_string(), "Vec3".to_string());
+    // Field name -> (normalized type, first declaring field, first declaring variant)
+    let mut seen_fields: std::collections::HashMap<String, (String, &Ident, &Ident)> =
+        std::collections::HashMap::new();
+    let mut conflicts: Option<syn::Error> = None;
 
     for (variant_name, fields) in &variant_info {
-        for (fname, ftype, is_color) in fields {
+        for (fname, ftype, is_color, ..) in fields {
             let fname_str = fname.to_string();
             let ftype_normalized = ftype.replace("glam::", "");
             if fname_str == "position" || fname_str == "velocity" {
                 continue; // Already added
             }
-            if let Some(existing_type) = seen_fields.get(&fname_str) {
+            if let Some((existing_type, first_field, first_variant)) = seen_fields.get(&fname_str) {
                 if existing_type != &ftype_normalized {
-                    panic!(
-                        "Field '{}' has conflicting types: '{}' in one variant, '{}' in '{}'",
-                        fname_str, existing_type, ftype_normalized, variant_name
+                    let mut err = syn::Error::new_spanned(
+                        fname,
+                        format!(
+                            "field `{}` has conflicting types: `{}` in variant `{}`, `{}` in variant `{}`",
+                            fname_str, existing_type, first_variant, ftype_normalized, variant_name
+                        ),
                     );
+                    err.combine(syn::Error::new_spanned(
+                        first_field,
+                        format!(
+                            "note: `{}` first declared as `{}` in variant `{}` here",
+                            fname_str, existing_type, first_variant
+                        ),
+                    ));
+                    match &mut conflicts {
+                        Some(all) => all.combine(err),
+                        None => conflicts = Some(err),
+                    }
                 }
             } else {
-                seen_fields.insert(fname_str.clone(), ftype_normalized.clone());
+                seen_fields.insert(fname_str.clone(), (ftype_normalized.clone(), fname, variant_name));
                 all_fields.push((fname_str, ftype_normalized, *is_color));
             }
         }
     }
 
+    if let Some(err) = conflicts {
+        return err.to_compile_error().into();
+    }
+
     // ========================================
     // Generate unified GPU struct for enum
     // ========================================
-    let (enum_gpu_fields, enum_wgsl_struct, _, _, enum_alive_offset, enum_scale_offset) =
+    let (enum_gpu_fields, enum_wgsl_struct, _, _, enum_velocity_offset, enum_alive_offset, enum_scale_offset) =
         generate_unified_gpu_struct(&all_fields);
+    let enum_velocity_offset_expr = option_u32_tokens(enum_velocity_offset);
 
     let enum_gpu_field_tokens: Vec<_> = enum_gpu_fields.iter().map(|(name, ty)| {
         let name_ident = Ident::new(name, Span::call_site());
@@ -1264,7 +1614,7 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
             let idx_u32 = idx as u32;
 
             // Generate field bindings for the match pattern
-            let field_bindings: Vec<_> = variant_fields.iter().map(|(fname, _, _)| {
+            let field_bindings: Vec<_> = variant_fields.iter().map(|(fname, ..)| {
                 quote! { #fname }
             }).collect();
 
@@ -1276,7 +1626,7 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
 
                 // Check if this variant has this field
                 #[allow(clippy::cmp_owned)]
-                let has_field = variant_fields.iter().any(|(vf, _, _)| vf.to_string() == *fname);
+                let has_field = variant_fields.iter().any(|(vf, ..)| vf.to_string() == *fname);
 
                 if has_field {
                     let type_info = type_info_from_string(ftype);
@@ -1296,6 +1646,7 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
             field_assignments.push(quote! { age: 0.0 });
             field_assignments.push(quote! { alive: 1u32 });
             field_assignments.push(quote! { scale: 1.0 });
+            field_assignments.push(quote! { triggered: 0u32 });
 
             quote! {
                 #enum_name::#variant_name { #(#field_bindings),* } => {
@@ -1316,13 +1667,16 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
             let idx_u32 = idx as u32;
 
             // Generate field assignments from GPU to enum variant
-            let field_assignments: Vec<_> = variant_fields.iter().map(|(fname, ftype, _)| {
+            let field_assignments: Vec<_> = variant_fields.iter().map(|(fname, ftype, _, range, ..)| {
                 let type_normalized = ftype.replace("glam::", "");
                 match type_normalized.as_str() {
                     "Vec3" => quote! { #fname: rdpe::Vec3::from_array(gpu.#fname) },
                     "Vec2" => quote! { #fname: rdpe::Vec2::from_array(gpu.#fname) },
                     "Vec4" => quote! { #fname: rdpe::Vec4::from_array(gpu.#fname) },
-                    _ => quote! { #fname: gpu.#fname },
+                    _ => {
+                        let value = clamp_to_range(quote! { gpu.#fname }, *range, &type_normalized);
+                        quote! { #fname: #value }
+                    }
                 }
             }).collect();
 
@@ -1343,14 +1697,15 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
     let inspect_arms: Vec<_> = variant_info
         .iter()
         .map(|(variant_name, variant_fields)| {
-            // Generate field bindings for the match pattern
-            let field_bindings: Vec<_> = variant_fields.iter().map(|(fname, _, _)| {
-                quote! { #fname }
+            // Bind only the fields that are shown; `..` skips hidden ones
+            let visible_fields = || variant_fields.iter().filter(|(.., is_hidden)| !is_hidden);
+            let field_bindings: Vec<_> = visible_fields().map(|(fname, ..)| {
+                quote! { #fname, }
             }).collect();
 
             // Generate inspect entries
-            let inspect_entries: Vec<_> = variant_fields.iter().map(|(fname, ftype, _)| {
-                let fname_str = fname.to_string();
+            let inspect_entries: Vec<_> = visible_fields().map(|(fname, ftype, _, _, label, _)| {
+                let fname_str = label;
                 let type_normalized = ftype.replace("glam::", "");
                 match type_normalized.as_str() {
                     "Vec3" => quote! { (#fname_str, format!("({:.3}, {:.3}, {:.3})", #fname.x, #fname.y, #fname.z)) },
@@ -1363,7 +1718,7 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
             }).collect();
 
             quote! {
-                #enum_name::#variant_name { #(#field_bindings),* } => {
+                #enum_name::#variant_name { #(#field_bindings)* .. } => {
                     vec![#(#inspect_entries),*]
                 }
             }
@@ -1374,15 +1729,17 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
     let editable_arms: Vec<_> = variant_info
         .iter()
         .map(|(variant_name, variant_fields)| {
-            // Generate mutable field bindings for the match pattern
-            let field_bindings: Vec<_> = variant_fields.iter().map(|(fname, _, _)| {
-                quote! { #fname }
+            // Generate mutable field bindings for the match pattern, skipping hidden fields
+            let visible_fields = || variant_fields.iter().filter(|(.., is_hidden)| !is_hidden);
+            let field_bindings: Vec<_> = visible_fields().map(|(fname, ..)| {
+                quote! { #fname, }
             }).collect();
 
-            // Generate editable widgets for each field
-            let editable_widgets: Vec<_> = variant_fields.iter().map(|(fname, ftype, _)| {
-                let fname_str = fname.to_string();
+            // Generate editable widgets for each visible field
+            let editable_widgets: Vec<_> = visible_fields().map(|(fname, ftype, _, range, label, _)| {
+                let fname_str = label;
                 let type_normalized = ftype.replace("glam::", "");
+                let range_call = drag_value_range(*range, &type_normalized);
                 match type_normalized.as_str() {
                     "Vec3" => quote! {
                         ui.label(#fname_str);
@@ -1431,7 +1788,7 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
                     },
                     "f32" => quote! {
                         ui.label(#fname_str);
-                        if ui.add(egui::DragValue::new(#fname).speed(0.01)).changed() {
+                        if ui.add(egui::DragValue::new(#fname).speed(0.01)#range_call).changed() {
                             modified = true;
                         }
                         ui.end_row();
@@ -1439,7 +1796,7 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
                     "u32" => quote! {
                         ui.label(#fname_str);
                         let mut val = *#fname as i64;
-                        if ui.add(egui::DragValue::new(&mut val).speed(1.0)).changed() {
+                        if ui.add(egui::DragValue::new(&mut val).speed(1.0)#range_call).changed() {
                             *#fname = val.max(0) as u32;
                             modified = true;
                         }
@@ -1447,7 +1804,7 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
                     },
                     "i32" => quote! {
                         ui.label(#fname_str);
-                        if ui.add(egui::DragValue::new(#fname).speed(1.0)).changed() {
+                        if ui.add(egui::DragValue::new(#fname).speed(1.0)#range_call).changed() {
                             modified = true;
                         }
                         ui.end_row();
@@ -1461,7 +1818,7 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
             }).collect();
 
             quote! {
-                #enum_name::#variant_name { #(#field_bindings),* } => {
+                #enum_name::#variant_name { #(#field_bindings)* .. } => {
                     egui::Grid::new("editable_fields")
                         .num_columns(2)
                         .spacing([20.0, 4.0])
@@ -1490,13 +1847,48 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
         })
         .collect();
 
+    // Generate is_<variant>() predicates, named like the WGSL helpers
+    let variant_predicates: Vec<_> = variant_info
+        .iter()
+        .map(|(variant_name, _)| {
+            let fn_name = Ident::new(
+                &format!("is_{}", variant_name.to_string().to_lowercase()),
+                Span::call_site(),
+            );
+            let doc = format!("Returns `true` if this particle is a `{}`.", variant_name);
+            quote! {
+                #[doc = #doc]
+                pub fn #fn_name(&self) -> bool {
+                    matches!(self, #enum_name::#variant_name { .. })
+                }
+            }
+        })
+        .collect();
+
+    let variant_name_arms: Vec<_> = variant_info
+        .iter()
+        .map(|(variant_name, _)| {
+            let name_str = variant_name.to_string();
+            quote! { #enum_name::#variant_name { .. } => #name_str }
+        })
+        .collect();
+
     let expanded = quote! {
         // Standalone structs with full Particle implementations
         #(#standalone_structs)*
 
-        // Type ID constants for the enum
+        // Type ID constants and variant helpers for the enum
         impl #enum_name {
             #(#type_constants)*
+
+            #(#variant_predicates)*
+
+            /// Name of this particle's variant, exactly as written in the enum.
+            pub fn variant_name(&self) -> &'static str {
+                match self {
+                    #(#variant_name_arms),*
+                }
+            }
         }
 
         // Unified GPU struct for the enum (we don't re-declare the enum itself!)
@@ -1514,6 +1906,7 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
             const COLOR_OFFSET: Option<u32> = None;
             const ALIVE_OFFSET: u32 = #enum_alive_offset;
             const SCALE_OFFSET: u32 = #enum_scale_offset;
+            const VELOCITY_OFFSET: Option<u32> = #enum_velocity_offset_expr;
             const EXTRA_WGSL: &'static str = #extra_wgsl;
 
             fn to_gpu(&self) -> Self::Gpu {
@@ -1543,6 +1936,8 @@ pub fn derive_multi_particle(input: TokenStream) -> TokenStream {
                 modified
             }
         }
+
+        #(#range_warnings)*
     };
 
     TokenStream::from(expanded)
@@ -1561,19 +1956,28 @@ fn rust_type_from_string(ty: &str) -> proc_macro2::TokenStream {
     }
 }
 
+/// Tokens for an `Option<u32>` offset constant.
+fn option_u32_tokens(offset: Option<u32>) -> proc_macro2::TokenStream {
+    match offset {
+        Some(offset) => quote! { Some(#offset) },
+        None => quote! { None },
+    }
+}
+
 /// Generate GPU struct fields, WGSL, and offsets for a single particle type
 fn generate_particle_gpu_struct(
-    fields: &[(Ident, String, bool)],
+    fields: &[MultiField],
     _is_standalone: bool,
-) -> (Vec<(String, proc_macro2::TokenStream)>, String, Option<String>, Option<u32>, u32, u32) {
+) -> (Vec<(String, proc_macro2::TokenStream)>, String, Option<String>, Option<u32>, Option<u32>, u32, u32) {
     let mut gpu_fields: Vec<(String, proc_macro2::TokenStream)> = Vec::new();
     let mut wgsl_lines = Vec::new();
     let mut field_offset = 0u32;
     let mut padding_count = 0u32;
     let mut color_field: Option<String> = None;
     let mut color_offset: Option<u32> = None;
+    let mut velocity_offset: Option<u32> = None;
 
-    for (field_name, type_str, is_color) in fields {
+    for (field_name, type_str, is_color, ..) in fields {
         let type_info = type_info_from_string(&type_str.replace("glam::", ""));
 
         // Add padding if needed
@@ -1597,6 +2001,9 @@ fn generate_particle_gpu_struct(
             color_field = Some(field_name.to_string());
             color_offset = Some(field_offset);
         }
+        if field_name == "velocity" {
+            velocity_offset = Some(field_offset);
+        }
 
         wgsl_lines.push(format!("    {}: {},", field_name, type_info.wgsl_type));
         gpu_fields.push((field_name.to_string(), type_info.gpu_type.clone()));
@@ -1640,6 +2047,10 @@ fn generate_particle_gpu_struct(
     gpu_fields.push(("scale".to_string(), quote! { f32 }));
     field_offset += 4;
 
+    wgsl_lines.push("    triggered: u32,".to_string());
+    gpu_fields.push(("triggered".to_string(), quote! { u32 }));
+    field_offset += 4;
+
     // Final padding
     let final_padding = (16 - (field_offset % 16)) % 16;
     if final_padding > 0 {
@@ -1656,17 +2067,18 @@ fn generate_particle_gpu_struct(
 
     let wgsl_struct = format!("struct Particle {{\n{}\n}}", wgsl_lines.join("\n"));
 
-    (gpu_fields, wgsl_struct, color_field, color_offset, alive_offset, scale_offset)
+    (gpu_fields, wgsl_struct, color_field, color_offset, velocity_offset, alive_offset, scale_offset)
 }
 
 /// Generate unified GPU struct for the enum (containing all fields from all variants)
 fn generate_unified_gpu_struct(
     all_fields: &[(String, String, bool)],
-) -> (Vec<(String, proc_macro2::TokenStream)>, String, Option<String>, Option<u32>, u32, u32) {
+) -> (Vec<(String, proc_macro2::TokenStream)>, String, Option<String>, Option<u32>, Option<u32>, u32, u32) {
     let mut gpu_fields: Vec<(String, proc_macro2::TokenStream)> = Vec::new();
     let mut wgsl_lines = Vec::new();
     let mut field_offset = 0u32;
     let mut padding_count = 0u32;
+    let mut velocity_offset: Option<u32> = None;
 
     for (field_name, type_str, _) in all_fields {
         let type_info = type_info_from_string(type_str);
@@ -1688,6 +2100,9 @@ fn generate_unified_gpu_struct(
             field_offset += padding_needed;
         }
 
+        if field_name == "velocity" {
+            velocity_offset = Some(field_offset);
+        }
         wgsl_lines.push(format!("    {}: {},", field_name, type_info.wgsl_type));
         gpu_fields.push((field_name.clone(), type_info.gpu_type.clone()));
         field_offset += type_info.size;
@@ -1730,6 +2145,10 @@ fn generate_unified_gpu_struct(
     gpu_fields.push(("scale".to_string(), quote! { f32 }));
     field_offset += 4;
 
+    wgsl_lines.push("    triggered: u32,".to_string());
+    gpu_fields.push(("triggered".to_string(), quote! { u32 }));
+    field_offset += 4;
+
     // Final padding
     let final_padding = (16 - (field_offset % 16)) % 16;
     if final_padding > 0 {
@@ -1746,7 +2165,7 @@ fn generate_unified_gpu_struct(
 
     let wgsl_struct = format!("struct Particle {{\n{}\n}}", wgsl_lines.join("\n"));
 
-    (gpu_fields, wgsl_struct, None, None, alive_offset, scale_offset)
+    (gpu_fields, wgsl_struct, None, None, velocity_offset, alive_offset, scale_offset)
 }
 
 /// Type info from string for MultiParticle macro
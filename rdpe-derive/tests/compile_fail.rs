@@ -0,0 +1,7 @@
+//! Compile-fail tests for the derive macros' error messages.
+
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
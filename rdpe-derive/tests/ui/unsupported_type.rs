@@ -0,0 +1,9 @@
+use rdpe_derive::Particle;
+
+#[derive(Particle)]
+struct Spark {
+    energy: f32,
+    mass: f64,
+}
+
+fn main() {}
@@ -1,7 +1,7 @@
 //! RDPE Simulation Runner
 //!
-//! Loads a simulation config from JSON and runs it.
-//! Designed to be spawned by the RDPE editor.
+//! Loads a simulation config from JSON (or TOML with the `toml-config`
+//! feature) and runs it. Designed to be spawned by the RDPE editor.
 //!
 //! Usage: `rdpe-runner config.json`
 
@@ -49,7 +49,14 @@ fn hsv_to_rgb(h: f32, s: f32, v: f32) -> Vec3 {
     Vec3::new(r + m, g + m, b + m)
 }
 
-fn spawn_particle(ctx: &mut SpawnContext, spawn: &SpawnConfig) -> MetaParticle {
+/// Spawn data worked out once up front and shared by every particle
+struct SpawnData {
+    mesh_sampler: Option<MeshSurfaceSampler>,
+    poisson_points: Vec<Vec3>,
+    gradient_stops: Vec<([f32; 3], f32)>,
+}
+
+fn spawn_particle(ctx: &mut SpawnContext, spawn: &SpawnConfig, data: &SpawnData) -> MetaParticle {
     let position = match &spawn.shape {
         SpawnShape::Cube { size } => ctx.random_in_cube(*size),
         SpawnShape::Sphere { radius } => ctx.random_in_sphere(*radius),
@@ -75,6 +82,12 @@ fn spawn_particle(ctx: &mut SpawnContext, spawn: &SpawnConfig) -> MetaParticle {
                 (ctx.random() - 0.5) * *depth,
             )
         }
+        SpawnShape::MeshSurface { offset, .. } => {
+            let sampler = data.mesh_sampler.as_ref().expect("sampler is built for mesh shapes");
+            let (point, normal) = ctx.random_on_mesh(sampler);
+            point + normal * *offset
+        }
+        SpawnShape::PoissonDisc { .. } => data.poisson_points.get(ctx.index as usize).copied().unwrap_or(Vec3::ZERO),
     };
 
     let velocity = match &spawn.velocity {
@@ -123,12 +136,8 @@ fn spawn_particle(ctx: &mut SpawnContext, spawn: &SpawnConfig) -> MetaParticle {
             let speed = velocity.length();
             hsv_to_rgb((speed * 2.0).fract(), 0.9, 0.9)
         }
-        ColorMode::Gradient { start, end } => {
-            // Gradient based on spawn index normalized to 0-1
-            let t = ctx.random(); // Approximate gradient with random for now
-            let start = Vec3::from_array(*start);
-            let end = Vec3::from_array(*end);
-            start.lerp(end, t)
+        ColorMode::Gradient { .. } | ColorMode::Rainbow => {
+            Vec3::from_array(sample_gradient(&data.gradient_stops, ctx.progress()))
         }
     };
 
@@ -160,7 +169,15 @@ fn main() {
     };
 
     // Load config
-    let config = match SimConfig::load(&config_path) {
+    #[cfg(feature = "toml-config")]
+    let loaded = if config_path.extension().is_some_and(|ext| ext == "toml") {
+        SimConfig::load_toml(&config_path)
+    } else {
+        SimConfig::load(&config_path)
+    };
+    #[cfg(not(feature = "toml-config"))]
+    let loaded = SimConfig::load(&config_path);
+    let config = match loaded {
         Ok(c) => c,
         Err(e) => {
             eprintln!("Failed to load config from {:?}: {}", config_path, e);
@@ -171,21 +188,61 @@ fn main() {
 
     // Clone spawn config for the spawner closure
     let spawn_config = config.spawn.clone();
+    let mesh_sampler = match &spawn_config.shape {
+        SpawnShape::MeshSurface { mesh, .. } => Some(MeshSurfaceSampler::new(mesh)),
+        _ => None,
+    };
+    let mut particle_count = config.particle_count;
+    let poisson_points = match &spawn_config.shape {
+        SpawnShape::PoissonDisc { min_distance, max_attempts } => {
+            let points = poisson_disc_xz(
+                config.bounds,
+                *min_distance,
+                *max_attempts,
+                particle_count as usize,
+                rand::random(),
+            );
+            if points.len() < particle_count as usize {
+                eprintln!(
+                    "Poisson disc spacing {} only fits {} of {} particles; spawning {}",
+                    min_distance,
+                    points.len(),
+                    particle_count,
+                    points.len()
+                );
+                particle_count = points.len() as u32;
+            }
+            points
+        }
+        _ => Vec::new(),
+    };
+    let spawn_data = SpawnData {
+        mesh_sampler,
+        poisson_points,
+        gradient_stops: spawn_config.color_mode.gradient_stops().unwrap_or_default(),
+    };
 
     // Convert rules
-    let rules: Vec<Rule> = config.rules.iter().map(|r| r.to_rule()).collect();
+    let rules: Vec<Rule> = config.active_rules().map(|r| r.to_rule()).collect();
     let needs_spatial = config.needs_spatial();
 
     // Build simulation
     let mut sim = Simulation::<MetaParticle>::new()
-        .with_particle_count(config.particle_count)
+        .with_particle_count(particle_count)
         .with_bounds(config.bounds)
         .with_particle_size(config.particle_size)
-        .with_spawner(move |ctx| spawn_particle(ctx, &spawn_config));
+        .with_spawner(move |ctx| spawn_particle(ctx, &spawn_config, &spawn_data));
 
     // Add spatial config if needed
     if needs_spatial {
         sim = sim.with_spatial_config(config.spatial_cell_size, config.spatial_resolution);
+        if !config.skip_dead_neighbors {
+            sim = sim.with_skip_dead_neighbors(false);
+        }
+    }
+
+    if config.two_d {
+        sim = sim.with_2d_mode();
     }
 
     // Add rules
@@ -195,11 +252,15 @@ fn main() {
 
     // Add vertex effects
     for effect_config in &config.vertex_effects {
-        sim = sim.with_vertex_effect(effect_config.to_effect());
+        match effect_config.to_effect() {
+            Some(effect) => sim = sim.with_vertex_effect(effect),
+            None => eprintln!("Skipping {} effect: only supported in the editor", effect_config.name()),
+        }
     }
 
     // Apply visuals
     let visuals = config.visuals.clone();
+    let post_process_chain = config.custom_shaders.post_process_chain.clone();
     sim = sim.with_visuals(|v| {
         v.blend_mode(visuals.blend_mode.to_blend_mode());
         v.shape(visuals.shape.to_shape());
@@ -213,12 +274,20 @@ fn main() {
         // Apply trails
         if visuals.trail_length > 0 {
             v.trails(visuals.trail_length);
+            if let Some(tail) = visuals.trail_tail_color {
+                v.trail_tail_color(glam::Vec3::from_array(tail));
+            }
         }
 
         // Apply connections
         if visuals.connections_enabled {
             v.connections(visuals.connections_radius);
-            v.connections_color(glam::Vec3::from_array(visuals.connections_color));
+            v.connections_distance_colors(
+                glam::Vec3::from_array(visuals.connections_near_color),
+                glam::Vec3::from_array(visuals.connections_far_color.unwrap_or(visuals.connections_near_color)),
+            );
+            v.connections_directed(visuals.connections_directed);
+            v.connections_flow_speed(visuals.connections_flow_speed);
         }
 
         // Apply velocity stretch
@@ -235,6 +304,34 @@ fn main() {
         if let Some(mesh) = visuals.wireframe.to_mesh() {
             v.wireframe(mesh, visuals.wireframe_thickness);
         }
+
+        // Apply bloom
+        if let Some(bloom) = visuals.bloom {
+            v.bloom(bloom.to_bloom_config());
+        }
+
+        // Apply chromatic aberration
+        if visuals.chromatic_aberration > 0.0 {
+            v.chromatic_aberration(visuals.chromatic_aberration);
+        }
+
+        // Apply HDR tone mapping
+        if visuals.hdr {
+            v.hdr(visuals.tone_map.to_tone_map_mode());
+        }
+
+        // Apply MSAA
+        v.msaa(visuals.msaa_samples.to_msaa_samples());
+
+        // Apply directional light shadows
+        if let Some(light) = visuals.light {
+            v.light(light.to_light_config());
+        }
+
+        // Apply post-process chain
+        for code in &post_process_chain {
+            v.add_post_process(code);
+        }
     });
 
     // Run with inspectors enabled
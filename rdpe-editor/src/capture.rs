@@ -0,0 +1,340 @@
+//! Image and animation encoding and file naming for viewport captures
+
+use std::sync::mpsc;
+
+use image::codecs::png::PngEncoder;
+use image::{ExtendedColorType, ImageEncoder};
+
+/// Most raw frame bytes a recording keeps in memory before it stops itself
+pub const MAX_BUFFERED_BYTES: usize = 1024 * 1024 * 1024;
+
+/// Animated image formats for viewport recordings
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VideoFormat {
+    /// Palette-quantized GIF; small files, 256 colors per frame
+    #[default]
+    Gif,
+    /// Animated PNG; full color, larger files
+    Apng,
+}
+
+impl VideoFormat {
+    pub const ALL: [VideoFormat; 2] = [VideoFormat::Gif, VideoFormat::Apng];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            VideoFormat::Gif => "GIF",
+            VideoFormat::Apng => "APNG",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            VideoFormat::Gif => "gif",
+            VideoFormat::Apng => "png",
+        }
+    }
+
+    /// Rough encoded bytes per pixel, used for size estimates while recording
+    fn estimated_bytes_per_pixel(&self) -> f32 {
+        match self {
+            VideoFormat::Gif => 0.5,
+            VideoFormat::Apng => 2.0,
+        }
+    }
+}
+
+/// Recording options chosen before starting
+#[derive(Clone, Debug)]
+pub struct VideoSettings {
+    pub format: VideoFormat,
+    /// Frames captured per second of wall-clock time
+    pub fps: u32,
+    /// Recording stops on its own after this many seconds
+    pub max_duration: f32,
+}
+
+impl Default for VideoSettings {
+    fn default() -> Self {
+        Self {
+            format: VideoFormat::Gif,
+            fps: 30,
+            max_duration: 10.0,
+        }
+    }
+}
+
+/// Frames accumulated during a viewport recording
+///
+/// Every frame has the size of the first one, so resizing the viewport
+/// mid-recording doesn't break the animation.
+pub struct VideoRecorder {
+    settings: VideoSettings,
+    width: u32,
+    height: u32,
+    frames: Vec<Vec<u8>>,
+    since_frame: f32,
+}
+
+impl VideoRecorder {
+    /// Start a recording of `width` x `height` frames. The first frame is due immediately.
+    pub fn new(settings: VideoSettings, width: u32, height: u32) -> Self {
+        let since_frame = 1.0 / settings.fps.max(1) as f32;
+        Self {
+            settings,
+            width,
+            height,
+            frames: Vec::new(),
+            since_frame,
+        }
+    }
+
+    pub fn settings(&self) -> &VideoSettings {
+        &self.settings
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Advance the frame timer by `delta_time` seconds; `true` when a frame should be captured.
+    pub fn advance(&mut self, delta_time: f32) -> bool {
+        self.since_frame += delta_time;
+        self.since_frame >= 1.0 / self.settings.fps.max(1) as f32
+    }
+
+    /// Add a captured RGBA8 frame.
+    pub fn push_frame(&mut self, rgba: Vec<u8>) {
+        debug_assert_eq!(rgba.len(), (self.width * self.height * 4) as usize);
+        self.frames.push(rgba);
+        self.since_frame = 0.0;
+    }
+
+    /// Raw RGBA bytes held by the captured frames
+    pub fn buffered_bytes(&self) -> usize {
+        self.frames.len() * self.frame_bytes()
+    }
+
+    fn frame_bytes(&self) -> usize {
+        self.width as usize * self.height as usize * 4
+    }
+
+    /// Whether the maximum duration has been reached, or another frame
+    /// would take the buffered bytes past [`MAX_BUFFERED_BYTES`]
+    pub fn is_full(&self) -> bool {
+        self.frames.len() as f32 >= self.settings.max_duration * self.settings.fps as f32
+            || self.buffered_bytes() + self.frame_bytes() > MAX_BUFFERED_BYTES
+    }
+
+    /// Approximate size of the encoded file in bytes
+    pub fn estimated_size(&self) -> usize {
+        let pixels = (self.width * self.height) as f32 * self.frames.len() as f32;
+        (pixels * self.settings.format.estimated_bytes_per_pixel()) as usize
+    }
+
+    /// Encode the recorded frames in the chosen format.
+    pub fn encode(self) -> Result<Vec<u8>, String> {
+        if self.frames.is_empty() {
+            return Err("no frames recorded".to_string());
+        }
+        match self.settings.format {
+            VideoFormat::Gif => encode_gif(self.width, self.height, self.frames, self.settings.fps),
+            VideoFormat::Apng => encode_apng(self.width, self.height, &self.frames, self.settings.fps),
+        }
+    }
+}
+
+/// A finished recording being encoded off the UI thread
+///
+/// On the web there are no threads, so the recording is encoded when the
+/// job is created.
+pub struct EncodingJob {
+    format: VideoFormat,
+    frame_count: usize,
+    result: mpsc::Receiver<Result<Vec<u8>, String>>,
+}
+
+impl EncodingJob {
+    /// Start encoding `recorder`'s frames.
+    pub fn start(recorder: VideoRecorder) -> Self {
+        let format = recorder.settings().format;
+        let frame_count = recorder.frame_count();
+        let (sender, result) = mpsc::channel();
+        let encode = move || {
+            let _ = sender.send(recorder.encode());
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        std::thread::spawn(encode);
+        #[cfg(target_arch = "wasm32")]
+        encode();
+        Self { format, frame_count, result }
+    }
+
+    pub fn format(&self) -> VideoFormat {
+        self.format
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    /// The encoded file once the worker is done, `None` while it is still running
+    pub fn poll(&self) -> Option<Result<Vec<u8>, String>> {
+        match self.result.try_recv() {
+            Ok(result) => Some(result),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => Some(Err("encoder stopped unexpectedly".to_string())),
+        }
+    }
+}
+
+/// Encode RGBA8 frames as a looping GIF, quantizing each frame to its own palette.
+pub fn encode_gif(width: u32, height: u32, frames: Vec<Vec<u8>>, fps: u32) -> Result<Vec<u8>, String> {
+    let (width, height) = (
+        u16::try_from(width).map_err(|_| "frame too wide for GIF")?,
+        u16::try_from(height).map_err(|_| "frame too tall for GIF")?,
+    );
+    // GIF delays are in hundredths of a second
+    let delay = (100.0 / fps.max(1) as f32).round() as u16;
+
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = gif::Encoder::new(&mut bytes, width, height, &[]).map_err(|e| e.to_string())?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(|e| e.to_string())?;
+        for mut rgba in frames {
+            let mut frame = gif::Frame::from_rgba_speed(width, height, &mut rgba, 10);
+            frame.delay = delay;
+            encoder.write_frame(&frame).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(bytes)
+}
+
+/// Encode RGBA8 frames as a looping animated PNG.
+pub fn encode_apng(width: u32, height: u32, frames: &[Vec<u8>], fps: u32) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut bytes, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_animated(frames.len() as u32, 0).map_err(|e| e.to_string())?;
+        encoder.set_frame_delay(1, fps.max(1) as u16).map_err(|e| e.to_string())?;
+        let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+        for rgba in frames {
+            writer.write_image_data(rgba).map_err(|e| e.to_string())?;
+        }
+        writer.finish().map_err(|e| e.to_string())?;
+    }
+    Ok(bytes)
+}
+
+/// Encode tightly packed RGBA8 pixels as a PNG file.
+pub fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    PngEncoder::new(&mut bytes)
+        .write_image(rgba, width, height, ExtendedColorType::Rgba8)
+        .map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
+
+/// File name like `screenshot_20250131_142502.png` for a capture taken
+/// `unix_secs` seconds after the Unix epoch (UTC).
+pub fn timestamped_filename(prefix: &str, extension: &str, unix_secs: u64) -> String {
+    let days = (unix_secs / 86_400) as i64;
+    let secs_of_day = unix_secs % 86_400;
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{}_{:04}{:02}{:02}_{:02}{:02}{:02}.{}",
+        prefix,
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60,
+        extension
+    )
+}
+
+/// Convert days since 1970-01-01 to a (year, month, day) date.
+///
+/// Howard Hinnant's `civil_from_days` algorithm for the proleptic Gregorian
+/// calendar.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamped_filename() {
+        assert_eq!(timestamped_filename("screenshot", "png", 0), "screenshot_19700101_000000.png");
+        assert_eq!(timestamped_filename("screenshot", "png", 1_700_000_000), "screenshot_20231114_221320.png");
+        // Leap day and the last second before midnight
+        assert_eq!(timestamped_filename("recording", "gif", 951_868_799), "recording_20000229_235959.gif");
+    }
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(59), (1970, 3, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        // 2100 is not a leap year
+        assert_eq!(civil_from_days(47_540), (2100, 2, 28));
+        assert_eq!(civil_from_days(47_541), (2100, 3, 1));
+    }
+
+    #[test]
+    fn test_encode_png_round_trip() {
+        let rgba = [255, 0, 0, 255, 0, 255, 0, 128];
+        let bytes = encode_png(2, 1, &rgba).unwrap();
+        let decoded = image::load_from_memory(&bytes).unwrap().to_rgba8();
+        assert_eq!(decoded.dimensions(), (2, 1));
+        assert_eq!(decoded.into_raw(), rgba);
+    }
+
+    #[test]
+    fn test_recording_stops_at_buffer_cap() {
+        let settings = VideoSettings { max_duration: 1000.0, ..VideoSettings::default() };
+        // 64 MiB frames, so the cap allows 16 of them
+        let (width, height) = (4096, 4096);
+        let mut recorder = VideoRecorder::new(settings, width, height);
+        let mut frames = 0;
+        while !recorder.is_full() {
+            recorder.frames.push(Vec::new());
+            frames += 1;
+        }
+        assert_eq!(frames, MAX_BUFFERED_BYTES / (width * height * 4) as usize);
+    }
+
+    #[test]
+    fn test_encoding_job_returns_encoded_file() {
+        let mut recorder = VideoRecorder::new(VideoSettings::default(), 2, 2);
+        recorder.push_frame(vec![255; 16]);
+        let job = EncodingJob::start(recorder);
+        assert_eq!(job.frame_count(), 1);
+        let bytes = loop {
+            if let Some(result) = job.poll() {
+                break result.unwrap();
+            }
+            std::thread::yield_now();
+        };
+        assert!(bytes.starts_with(b"GIF89a"));
+    }
+}
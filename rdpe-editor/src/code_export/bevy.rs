@@ -0,0 +1,755 @@
+//! Bevy export - generates a standalone Bevy app that runs the simulation's
+//! compute shader.
+//!
+//! The output is a single `main.rs` with the matching `Cargo.toml` in its
+//! header comment. It contains `MySimPlugin`, which uploads the initial
+//! particles at startup and dispatches the editor's compute shader from a
+//! render graph node every frame.
+//!
+//! The export is compute-only: the app draws nothing until the user binds
+//! the particle buffer in a render pipeline of their own.
+//!
+//! Only single-pass simulations are supported: rules that need neighbor
+//! queries or 3D fields are left out (and listed in the output), since they
+//! rely on rdpe's spatial hashing and field passes.
+
+use crate::config::*;
+use crate::shader_gen::generate_compute_shader;
+use glam::Vec3;
+
+/// Workgroup size baked into the exported shader.
+const WORKGROUP_SIZE: u32 = 64;
+
+/// Size of the view_proj, time, delta_time, num_particles and padding
+/// uniforms that start the uniform buffer.
+const BASE_UNIFORMS_SIZE: usize = 80;
+
+const TEMPLATE: &str = r####"//! @NAME@ - exported from the RDPE editor as a Bevy app.
+//!
+//! Cargo.toml:
+//!
+//! ```toml
+//! [package]
+//! name = "@CRATE@"
+//! version = "0.1.0"
+//! edition = "2021"
+//!
+//! [dependencies]
+//! bevy = "0.13"
+//! rand = "0.8"
+//! ```
+//!
+//! This app is compute-only: it simulates the particles but does not draw
+//! them, so the window stays empty. The compute shader updates
+//! `ParticleBuffers::particles` every frame. Bind it in your own render
+//! pipeline to draw the particles; the layout of each particle is given by
+//! the `*_OFFSET` constants.
+@NOTES@
+use std::borrow::Cow;
+
+use bevy::prelude::*;
+use bevy::render::extract_resource::{ExtractResource, ExtractResourcePlugin};
+use bevy::render::render_graph::{self, RenderGraph, RenderLabel};
+use bevy::render::render_resource::*;
+use bevy::render::renderer::{RenderContext, RenderDevice, RenderQueue};
+use bevy::render::{Render, RenderApp, RenderSet};
+@RAND_IMPORT@
+const PARTICLE_COUNT: u32 = @COUNT@;
+const PARTICLE_STRIDE: usize = @STRIDE@;
+const POSITION_OFFSET: usize = @POSITION_OFFSET@;
+const VELOCITY_OFFSET: usize = @VELOCITY_OFFSET@;
+const COLOR_OFFSET: usize = @COLOR_OFFSET@;
+const AGE_OFFSET: usize = @AGE_OFFSET@;
+const ALIVE_OFFSET: usize = @ALIVE_OFFSET@;
+const SCALE_OFFSET: usize = @SCALE_OFFSET@;
+const PARTICLE_TYPE_OFFSET: usize = @PARTICLE_TYPE_OFFSET@;
+const WORKGROUP_SIZE: u32 = @WORKGROUP_SIZE@;
+
+/// Mouse and custom uniform bytes that follow the base uniforms.
+const UNIFORM_TAIL: &[u8] = &[
+@UNIFORM_TAIL@];
+
+const COMPUTE_SHADER: &str = r###"@SHADER@"###;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, MySimPlugin))
+        .add_systems(Startup, setup_camera)
+        .run();
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 0.0, @CAMERA_DISTANCE@).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+}
+
+/// Simulation settings exported from the editor.
+#[derive(Resource, Clone, ExtractResource)]
+pub struct SimConfig {
+    pub particle_count: u32,
+    pub bounds: f32,
+    /// Simulation speed multiplier (1.0 = normal)
+    pub speed: f32,
+    /// Simulation time in seconds
+    pub time: f32,
+    /// Length of the last step in seconds
+    pub delta_time: f32,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            particle_count: PARTICLE_COUNT,
+            bounds: @BOUNDS@,
+            speed: @SPEED@,
+            time: 0.0,
+            delta_time: 0.0,
+        }
+    }
+}
+
+/// GPU buffers bound to the compute shader.
+#[derive(Resource, Clone, ExtractResource)]
+pub struct ParticleBuffers {
+    pub particles: Buffer,
+    pub uniforms: Buffer,
+    pub alive_count: Buffer,
+}
+
+/// Runs the exported particle simulation on the GPU.
+pub struct MySimPlugin;
+
+impl Plugin for MySimPlugin {
+    fn build(&self, app: &mut App) {
+        let shader = app
+            .world
+            .resource_mut::<Assets<Shader>>()
+            .add(Shader::from_wgsl(COMPUTE_SHADER, "rdpe_particles.wgsl"));
+
+        app.init_resource::<SimConfig>()
+            .add_plugins((
+                ExtractResourcePlugin::<SimConfig>::default(),
+                ExtractResourcePlugin::<ParticleBuffers>::default(),
+            ))
+            .add_systems(Startup, upload_particles)
+            .add_systems(Update, advance_time);
+
+        let render_app = app.sub_app_mut(RenderApp);
+        render_app
+            .insert_resource(ParticleShader(shader))
+            .add_systems(Render, prepare_bind_group.in_set(RenderSet::PrepareBindGroups));
+
+        let mut render_graph = render_app.world.resource_mut::<RenderGraph>();
+        render_graph.add_node(ParticleComputeLabel, ParticleComputeNode);
+        render_graph.add_node_edge(ParticleComputeLabel, bevy::render::graph::CameraDriverLabel);
+    }
+
+    fn finish(&self, app: &mut App) {
+        app.sub_app_mut(RenderApp).init_resource::<ParticlePipeline>();
+    }
+}
+
+/// Create the GPU buffers and upload the initial particles.
+fn upload_particles(mut commands: Commands, config: Res<SimConfig>, render_device: Res<RenderDevice>) {
+    let particles = render_device.create_buffer_with_data(&BufferInitDescriptor {
+        label: Some("particles"),
+        contents: &spawn_particles(),
+        usage: BufferUsages::STORAGE | BufferUsages::VERTEX | BufferUsages::COPY_DST,
+    });
+    let uniforms = render_device.create_buffer_with_data(&BufferInitDescriptor {
+        label: Some("particle_uniforms"),
+        contents: &uniform_bytes(&config),
+        usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+    });
+    let alive_count = render_device.create_buffer_with_data(&BufferInitDescriptor {
+        label: Some("particle_alive_count"),
+        contents: &[0; 4],
+        usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+    });
+    commands.insert_resource(ParticleBuffers { particles, uniforms, alive_count });
+}
+
+fn advance_time(time: Res<Time>, mut config: ResMut<SimConfig>) {
+    config.delta_time = time.delta_seconds() * config.speed;
+    config.time += config.delta_time;
+}
+
+/// Initial particle data, generated like the editor's spawner.
+fn spawn_particles() -> Vec<u8> {
+@RNG@@SPAWN_SETUP@    let mut data = vec![0u8; PARTICLE_COUNT as usize * PARTICLE_STRIDE];
+    for @LOOP_VAR@ in data.chunks_exact_mut(PARTICLE_STRIDE)@ENUMERATE@ {
+        let position: Vec3 = @POSITION@;
+        let velocity: Vec3 = @VELOCITY@;
+        let color: Vec3 = @COLOR@;
+        let particle_type: u32 = @PARTICLE_TYPE@;
+
+        write_vec3(particle, POSITION_OFFSET, position);
+        write_vec3(particle, VELOCITY_OFFSET, velocity);
+        write_vec3(particle, COLOR_OFFSET, color);
+        particle[AGE_OFFSET..AGE_OFFSET + 4].copy_from_slice(&0.0f32.to_le_bytes());
+        particle[ALIVE_OFFSET..ALIVE_OFFSET + 4].copy_from_slice(&1u32.to_le_bytes());
+        particle[SCALE_OFFSET..SCALE_OFFSET + 4].copy_from_slice(&1.0f32.to_le_bytes());
+        particle[PARTICLE_TYPE_OFFSET..PARTICLE_TYPE_OFFSET + 4].copy_from_slice(&particle_type.to_le_bytes());
+        // Custom fields start at zero
+    }
+    data
+}
+
+fn write_vec3(bytes: &mut [u8], offset: usize, value: Vec3) {
+    for (i, component) in value.to_array().iter().enumerate() {
+        bytes[offset + i * 4..offset + i * 4 + 4].copy_from_slice(&component.to_le_bytes());
+    }
+}
+@HELPERS@
+/// Uniform buffer contents for the current settings.
+fn uniform_bytes(config: &SimConfig) -> Vec<u8> {
+    // view_proj is not used by the compute shader
+    let mut data = vec![0u8; 64];
+    data.extend_from_slice(&config.time.to_le_bytes());
+    data.extend_from_slice(&config.delta_time.to_le_bytes());
+    data.extend_from_slice(&config.particle_count.to_le_bytes());
+    data.extend_from_slice(&config.bounds.to_le_bytes());
+    data.extend_from_slice(UNIFORM_TAIL);
+    data
+}
+
+// ============================================================================
+// Render world
+// ============================================================================
+
+#[derive(Resource)]
+struct ParticleShader(Handle<Shader>);
+
+#[derive(Resource)]
+struct ParticlePipeline {
+    layout: BindGroupLayout,
+    pipeline: CachedComputePipelineId,
+}
+
+impl FromWorld for ParticlePipeline {
+    fn from_world(world: &mut World) -> Self {
+        let layout = world.resource::<RenderDevice>().create_bind_group_layout(
+            "particle_bind_group_layout",
+            &[
+                buffer_layout_entry(0, BufferBindingType::Storage { read_only: false }),
+                buffer_layout_entry(1, BufferBindingType::Uniform),
+                buffer_layout_entry(6, BufferBindingType::Storage { read_only: false }),
+            ],
+        );
+        let shader = world.resource::<ParticleShader>().0.clone();
+        let pipeline = world.resource::<PipelineCache>().queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("particle_compute_pipeline".into()),
+            layout: vec![layout.clone()],
+            push_constant_ranges: Vec::new(),
+            shader,
+            shader_defs: Vec::new(),
+            entry_point: Cow::from("main"),
+        });
+        Self { layout, pipeline }
+    }
+}
+
+fn buffer_layout_entry(binding: u32, ty: BufferBindingType) -> BindGroupLayoutEntry {
+    BindGroupLayoutEntry {
+        binding,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    }
+}
+
+#[derive(Resource)]
+struct ParticleBindGroup(BindGroup);
+
+/// Write this frame's uniforms and bind the particle buffers.
+fn prepare_bind_group(
+    mut commands: Commands,
+    pipeline: Res<ParticlePipeline>,
+    buffers: Option<Res<ParticleBuffers>>,
+    config: Res<SimConfig>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    let Some(buffers) = buffers else {
+        return;
+    };
+    render_queue.write_buffer(&buffers.uniforms, 0, &uniform_bytes(&config));
+    let bind_group = render_device.create_bind_group(
+        "particle_bind_group",
+        &pipeline.layout,
+        &[
+            BindGroupEntry { binding: 0, resource: buffers.particles.as_entire_binding() },
+            BindGroupEntry { binding: 1, resource: buffers.uniforms.as_entire_binding() },
+            BindGroupEntry { binding: 6, resource: buffers.alive_count.as_entire_binding() },
+        ],
+    );
+    commands.insert_resource(ParticleBindGroup(bind_group));
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct ParticleComputeLabel;
+
+/// Dispatches the particle compute shader once per frame.
+struct ParticleComputeNode;
+
+impl render_graph::Node for ParticleComputeNode {
+    fn run(
+        &self,
+        _graph: &mut render_graph::RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), render_graph::NodeRunError> {
+        let Some(bind_group) = world.get_resource::<ParticleBindGroup>() else {
+            return Ok(());
+        };
+        let pipeline = world.resource::<ParticlePipeline>();
+        // The pipeline compiles in the background; skip frames until it's ready
+        let Some(compute_pipeline) = world.resource::<PipelineCache>().get_compute_pipeline(pipeline.pipeline) else {
+            return Ok(());
+        };
+
+        let mut pass = render_context
+            .command_encoder()
+            .begin_compute_pass(&ComputePassDescriptor::default());
+        pass.set_bind_group(0, &bind_group.0, &[]);
+        pass.set_pipeline(compute_pipeline);
+        pass.dispatch_workgroups(PARTICLE_COUNT.div_ceil(WORKGROUP_SIZE), 1, 1);
+        Ok(())
+    }
+}
+"####;
+
+const RANDOM_DIRECTION_FN: &str = r#"
+fn random_direction(rng: &mut impl Rng) -> Vec3 {
+    loop {
+        let v = Vec3::new(rng.gen::<f32>() * 2.0 - 1.0, rng.gen::<f32>() * 2.0 - 1.0, rng.gen::<f32>() * 2.0 - 1.0);
+        let len_sq = v.length_squared();
+        if len_sq > 0.001 && len_sq <= 1.0 {
+            return v.normalize();
+        }
+    }
+}
+"#;
+
+const RANDOM_IN_SPHERE_FN: &str = r#"
+fn random_in_sphere(rng: &mut impl Rng, radius: f32) -> Vec3 {
+    loop {
+        let v = Vec3::new(rng.gen::<f32>() * 2.0 - 1.0, rng.gen::<f32>() * 2.0 - 1.0, rng.gen::<f32>() * 2.0 - 1.0);
+        if v.length_squared() <= 1.0 {
+            return v * radius;
+        }
+    }
+}
+"#;
+
+const POISSON_DISC_FN: &str = r#"
+/// Bridson's Poisson disc sampling on the XZ plane, growing outward from the origin.
+fn poisson_disc_xz(rng: &mut impl Rng, half_size: f32, min_distance: f32, max_attempts: u32, max_points: usize) -> Vec<Vec3> {
+    let cell_size = min_distance / std::f32::consts::SQRT_2;
+    let cell_of = |p: Vec3| ((p.x / cell_size).floor() as i32, (p.z / cell_size).floor() as i32);
+    let mut grid = std::collections::HashMap::new();
+    let mut points = vec![Vec3::ZERO];
+    let mut active = vec![0];
+    grid.insert(cell_of(Vec3::ZERO), 0);
+    while !active.is_empty() && points.len() < max_points {
+        let slot = rng.gen_range(0..active.len());
+        let center = points[active[slot]];
+        let mut placed = false;
+        for _ in 0..max_attempts {
+            let angle = rng.gen::<f32>() * std::f32::consts::TAU;
+            let radius = min_distance * (1.0 + rng.gen::<f32>());
+            let candidate = center + Vec3::new(angle.cos(), 0.0, angle.sin()) * radius;
+            if candidate.x.abs() > half_size || candidate.z.abs() > half_size {
+                continue;
+            }
+            let (cx, cz) = cell_of(candidate);
+            let too_close = (cx - 2..=cx + 2)
+                .flat_map(|x| (cz - 2..=cz + 2).map(move |z| (x, z)))
+                .filter_map(|cell| grid.get(&cell))
+                .any(|&i: &usize| points[i].distance_squared(candidate) < min_distance * min_distance);
+            if !too_close {
+                grid.insert((cx, cz), points.len());
+                active.push(points.len());
+                points.push(candidate);
+                placed = true;
+                break;
+            }
+        }
+        if !placed {
+            active.swap_remove(slot);
+        }
+    }
+    points
+}
+"#;
+
+const HSV_TO_RGB_FN: &str = r#"
+fn hsv_to_rgb(h: f32, s: f32, v: f32) -> Vec3 {
+    let c = v * s;
+    let h = h * 6.0;
+    let x = c * (1.0 - ((h % 2.0) - 1.0).abs());
+    let m = v - c;
+    let (r, g, b) = if h < 1.0 {
+        (c, x, 0.0)
+    } else if h < 2.0 {
+        (x, c, 0.0)
+    } else if h < 3.0 {
+        (0.0, c, x)
+    } else if h < 4.0 {
+        (0.0, x, c)
+    } else if h < 5.0 {
+        (x, 0.0, c)
+    } else {
+        (c, 0.0, x)
+    };
+    Vec3::new(r + m, g + m, b + m)
+}
+"#;
+
+const PICK_TYPE_FN: &str = r#"
+/// Pick a particle type with probability proportional to its weight.
+fn pick_type(rng: &mut impl Rng, weights: &[f32]) -> u32 {
+    let total: f32 = weights.iter().sum();
+    if total <= 0.0 {
+        return 0;
+    }
+    let mut r = rng.gen::<f32>() * total;
+    for (i, &weight) in weights.iter().enumerate() {
+        r -= weight;
+        if r <= 0.0 {
+            return i as u32;
+        }
+    }
+    (weights.len() - 1) as u32
+}
+"#;
+
+/// Generate a standalone Bevy app running the simulation's compute shader.
+pub(super) fn generate_bevy_code(config: &SimConfig) -> String {
+    // Keep only what a single compute pass without spatial hashing can run
+    let (supported, skipped): (Vec<_>, Vec<_>) = config
+        .active_rules()
+        .cloned()
+        .partition(|r| !r.requires_neighbors() && !samples_3d_fields(r));
+    let mut export_config = config.clone();
+    export_config.rules = supported;
+    export_config.fields.clear();
+
+    let mut notes = String::new();
+    if !skipped.is_empty() || !config.fields.is_empty() {
+        notes.push_str("//!\n//! Not exported (needs rdpe's neighbor or field passes):\n");
+        for rule in &skipped {
+            notes.push_str(&format!("//! - {} rule\n", rule.name()));
+        }
+        for field in &config.fields {
+            notes.push_str(&format!("//! - field '{}'\n", field.name));
+        }
+    }
+
+    let layout = export_config.particle_layout();
+    let spawn = &config.spawn;
+    let position = position_expr(&spawn.shape);
+    let velocity = velocity_expr(&spawn.velocity);
+    let color = color_expr(&spawn.color_mode);
+    let particle_type = if spawn.type_weights.len() > 1 {
+        let weights: Vec<String> = spawn.type_weights.iter().map(|w| lit(*w)).collect();
+        format!("pick_type(&mut rng, &[{}])", weights.join(", "))
+    } else {
+        "0".to_string()
+    };
+    let spawn_exprs = [&position, &velocity, &color, &particle_type];
+
+    let mut helpers = String::new();
+    let uses = |name: &str| spawn_exprs.iter().any(|e| e.contains(name));
+    for (name, code) in [
+        ("random_direction(", RANDOM_DIRECTION_FN),
+        ("random_in_sphere(", RANDOM_IN_SPHERE_FN),
+        ("hsv_to_rgb(", HSV_TO_RGB_FN),
+        ("pick_type(", PICK_TYPE_FN),
+    ] {
+        if uses(name) {
+            helpers.push_str(code);
+        }
+    }
+    if let SpawnShape::MeshSurface { mesh, .. } = &spawn.shape {
+        helpers.push_str(&mesh_surface_helper(mesh));
+    }
+    let spawn_setup = match &spawn.shape {
+        SpawnShape::PoissonDisc { min_distance, max_attempts } => {
+            helpers.push_str(POISSON_DISC_FN);
+            format!(
+                "    let poisson_points = poisson_disc_xz(&mut rng, {}, {}, {}, PARTICLE_COUNT as usize);\n",
+                lit(config.bounds),
+                lit(*min_distance),
+                max_attempts
+            )
+        }
+        _ => String::new(),
+    };
+    let uses_rng = uses("rng") || spawn_setup.contains("rng");
+    let uses_index = uses("index");
+
+    let crate_name: String = config
+        .name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    TEMPLATE
+        .replace("@NAME@", &config.name)
+        .replace("@CRATE@", if crate_name.is_empty() { "rdpe-sim" } else { &crate_name })
+        .replace("@NOTES@", &notes)
+        .replace("@RAND_IMPORT@", if uses_rng { "use rand::Rng;\n" } else { "" })
+        .replace("@COUNT@", &config.particle_count.to_string())
+        .replace("@STRIDE@", &layout.stride.to_string())
+        .replace("@POSITION_OFFSET@", &layout.position_offset.to_string())
+        .replace("@VELOCITY_OFFSET@", &layout.velocity_offset.to_string())
+        .replace("@COLOR_OFFSET@", &layout.color_offset.to_string())
+        .replace("@AGE_OFFSET@", &layout.age_offset.to_string())
+        .replace("@ALIVE_OFFSET@", &layout.alive_offset.to_string())
+        .replace("@SCALE_OFFSET@", &layout.scale_offset.to_string())
+        .replace("@PARTICLE_TYPE_OFFSET@", &layout.particle_type_offset.to_string())
+        .replace("@WORKGROUP_SIZE@", &WORKGROUP_SIZE.to_string())
+        .replace("@UNIFORM_TAIL@", &byte_array_lines(&uniform_tail_bytes(config)))
+        .replace("@CAMERA_DISTANCE@", &lit(config.bounds * 3.0))
+        .replace("@BOUNDS@", &lit(config.bounds))
+        .replace("@SPEED@", &lit(config.speed))
+        .replace("@SPAWN_SETUP@", &spawn_setup)
+        .replace("@RNG@", if uses_rng { "    let mut rng = rand::thread_rng();\n" } else { "" })
+        .replace("@LOOP_VAR@", if uses_index { "(index, particle)" } else { "particle" })
+        .replace("@ENUMERATE@", if uses_index { ".enumerate()" } else { "" })
+        .replace("@POSITION@", &position)
+        .replace("@VELOCITY@", &velocity)
+        .replace("@COLOR@", &color)
+        .replace("@PARTICLE_TYPE@", &particle_type)
+        .replace("@HELPERS@", &helpers)
+        // Last, so placeholders inside the shader text are never touched
+        .replace("@SHADER@", &generate_compute_shader(&export_config, WORKGROUP_SIZE))
+}
+
+/// Rules that read or write the 3D field system.
+fn samples_3d_fields(rule: &RuleConfig) -> bool {
+    matches!(
+        rule,
+        RuleConfig::Deposit { .. }
+            | RuleConfig::Sense { .. }
+            | RuleConfig::Consume { .. }
+            | RuleConfig::Gradient { .. }
+            | RuleConfig::FieldGradient { .. }
+            | RuleConfig::Sync { .. }
+    )
+}
+
+/// Rust literal for an `f32`.
+fn lit(v: f32) -> String {
+    format!("{:?}", v)
+}
+
+fn vec3_lit(v: &[f32; 3]) -> String {
+    format!("Vec3::new({}, {}, {})", lit(v[0]), lit(v[1]), lit(v[2]))
+}
+
+fn position_expr(shape: &SpawnShape) -> String {
+    match shape {
+        SpawnShape::Cube { size } => format!(
+            "Vec3::new(rng.gen::<f32>() - 0.5, rng.gen::<f32>() - 0.5, rng.gen::<f32>() - 0.5) * {}",
+            lit(2.0 * size)
+        ),
+        SpawnShape::Sphere { radius } => format!("random_in_sphere(&mut rng, {})", lit(*radius)),
+        SpawnShape::Shell { inner, outer } => format!(
+            "random_direction(&mut rng) * ({} + rng.gen::<f32>() * {})",
+            lit(*inner),
+            lit(outer - inner)
+        ),
+        SpawnShape::Ring { radius, thickness } => format!(
+            "{{\n            let angle = rng.gen::<f32>() * std::f32::consts::TAU;\n            \
+             let r = {} + (rng.gen::<f32>() - 0.5) * {};\n            \
+             Vec3::new(angle.cos() * r, (rng.gen::<f32>() - 0.5) * {}, angle.sin() * r)\n        }}",
+            lit(*radius),
+            lit(*thickness),
+            lit(*thickness)
+        ),
+        SpawnShape::Point => "Vec3::ZERO".to_string(),
+        SpawnShape::Line { length } => format!("Vec3::new((rng.gen::<f32>() - 0.5) * {}, 0.0, 0.0)", lit(*length)),
+        SpawnShape::Plane { width, depth } => format!(
+            "Vec3::new((rng.gen::<f32>() - 0.5) * {}, 0.0, (rng.gen::<f32>() - 0.5) * {})",
+            lit(*width),
+            lit(*depth)
+        ),
+        SpawnShape::MeshSurface { offset, .. } => format!("sample_mesh_surface(&mut rng, {})", lit(*offset)),
+        // Particles beyond what fits at the spacing start at the origin
+        SpawnShape::PoissonDisc { .. } => "poisson_points.get(index).copied().unwrap_or(Vec3::ZERO)".to_string(),
+    }
+}
+
+/// The spawn mesh as a table of triangles plus the function that samples it.
+///
+/// Each triangle carries its face normal and the running total of areas up
+/// to it, worked out here so the exported app only does a binary search.
+fn mesh_surface_helper(mesh: &rdpe::MeshData) -> String {
+    let mut rows = String::new();
+    let mut total = 0.0;
+    for tri in mesh.indices.chunks_exact(3) {
+        let [a, b, c] = [tri[0], tri[1], tri[2]].map(|i| Vec3::from(mesh.vertices[i as usize]));
+        let cross = (b - a).cross(c - a);
+        let area = cross.length() * 0.5;
+        if area <= f32::EPSILON {
+            continue;
+        }
+        total += area;
+        let corner = |v: Vec3| format!("[{}, {}, {}]", lit(v.x), lit(v.y), lit(v.z));
+        rows.push_str(&format!(
+            "    ([{}, {}, {}], {}, {}),\n",
+            corner(a),
+            corner(b),
+            corner(c),
+            corner(cross.normalize()),
+            lit(total)
+        ));
+    }
+
+    format!(
+        r#"
+/// Spawn mesh triangles: corners, face normal, running total of areas
+const MESH_TRIANGLES: &[([[f32; 3]; 3], [f32; 3], f32)] = &[
+{rows}];
+
+/// Random point on the spawn mesh, spread evenly by area and pushed `offset` along the normal.
+fn sample_mesh_surface(rng: &mut impl Rng, offset: f32) -> Vec3 {{
+    let Some(&(_, _, total)) = MESH_TRIANGLES.last() else {{
+        return Vec3::ZERO;
+    }};
+    let target = rng.gen::<f32>() * total;
+    let index = MESH_TRIANGLES.partition_point(|t| t.2 <= target).min(MESH_TRIANGLES.len() - 1);
+    let ([a, b, c], normal, _) = MESH_TRIANGLES[index];
+    let s = rng.gen::<f32>().sqrt();
+    let v = rng.gen::<f32>() * s;
+    Vec3::from(a) * (1.0 - s) + Vec3::from(b) * (s - v) + Vec3::from(c) * v + Vec3::from(normal) * offset
+}}
+"#,
+        rows = rows
+    )
+}
+
+fn velocity_expr(velocity: &InitialVelocity) -> String {
+    // Radial directions fall back to a random one at the origin, like the editor
+    let radial = |direction: &str, speed: f32| {
+        format!(
+            "if {direction}.length() > 0.001 {{ {direction}.normalize() * {speed} }} else {{ random_direction(&mut rng) * {speed} }}",
+            direction = direction,
+            speed = lit(speed)
+        )
+    };
+    match velocity {
+        InitialVelocity::Zero => "Vec3::ZERO".to_string(),
+        InitialVelocity::RandomDirection { speed } => format!("random_direction(&mut rng) * {}", lit(*speed)),
+        InitialVelocity::Outward { speed } => radial("position", *speed),
+        InitialVelocity::Inward { speed } => radial("(-position)", *speed),
+        InitialVelocity::Swirl { speed } => radial("Vec3::new(-position.z, 0.0, position.x)", *speed),
+        InitialVelocity::Directional { direction, speed } => {
+            format!("{}.normalize_or_zero() * {}", vec3_lit(direction), lit(*speed))
+        }
+    }
+}
+
+fn color_expr(mode: &ColorMode) -> String {
+    match mode {
+        ColorMode::Uniform { r, g, b } => vec3_lit(&[*r, *g, *b]),
+        ColorMode::RandomHue { saturation, value } => {
+            format!("hsv_to_rgb(rng.gen::<f32>(), {}, {})", lit(*saturation), lit(*value))
+        }
+        ColorMode::ByPosition => "position * 0.5 + Vec3::splat(0.5)".to_string(),
+        ColorMode::ByVelocity => "hsv_to_rgb((velocity.length() * 2.0).fract(), 0.9, 0.9)".to_string(),
+        ColorMode::Gradient { .. } | ColorMode::Rainbow => super::gradient_code(
+            &mode.gradient_stops().unwrap_or_default(),
+            "index as f32 / PARTICLE_COUNT.max(1) as f32",
+        ),
+    }
+}
+
+/// Uniform bytes after the base uniforms: particle size, idle mouse state,
+/// then the custom uniforms in name order, laid out like the editor's
+/// uniform buffer.
+fn uniform_tail_bytes(config: &SimConfig) -> Vec<u8> {
+    let mut data = vec![0u8; BASE_UNIFORMS_SIZE];
+    data.extend_from_slice(&config.particle_size.to_le_bytes());
+    // The mouse struct starts on a 16-byte boundary
+    data.resize(data.len().div_ceil(16) * 16, 0);
+    let mouse = &config.mouse;
+    let mouse_values = [
+        [0.0; 4], // ray origin
+        [0.0; 4], // ray direction
+        [0.0, mouse.radius, mouse.strength, 0.0],
+        [mouse.color[0], mouse.color[1], mouse.color[2], 0.0],
+    ];
+    for v in mouse_values.iter().flatten() {
+        data.extend_from_slice(&v.to_le_bytes());
+    }
+
+    let mut uniforms: Vec<_> = config.custom_uniforms.iter().collect();
+    uniforms.sort_by_key(|(name, _)| *name);
+    for (_, value) in uniforms {
+        let aligned = data.len().div_ceil(value.alignment()) * value.alignment();
+        data.resize(aligned, 0);
+        data.extend_from_slice(&value.to_bytes());
+    }
+    data.resize(data.len().div_ceil(16) * 16, 0);
+    data.split_off(BASE_UNIFORMS_SIZE)
+}
+
+/// Bytes as the body of a Rust array literal, 16 per line.
+fn byte_array_lines(bytes: &[u8]) -> String {
+    bytes
+        .chunks(16)
+        .map(|line| {
+            let values: Vec<String> = line.iter().map(|b| format!("0x{:02x}", b)).collect();
+            format!("    {},\n", values.join(", "))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The WGSL embedded in the `COMPUTE_SHADER` constant of an export.
+    fn embedded_shader(code: &str) -> &str {
+        let (_, rest) = code.split_once("const COMPUTE_SHADER: &str = r###\"").unwrap();
+        rest.split_once("\"###;").unwrap().0
+    }
+
+    #[test]
+    fn test_bevy_export_snapshot() {
+        // Set UPDATE_SNAPSHOTS=1 to rewrite the snapshot after an intended change
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/code_export/snapshots/bevy_default.rs.snap");
+        let code = generate_bevy_code(&SimConfig::default());
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(path, &code).unwrap();
+        }
+        let expected = std::fs::read_to_string(path).unwrap();
+        assert!(code == expected, "Bevy export differs from {}; rerun with UPDATE_SNAPSHOTS=1 if intended", path);
+    }
+
+    #[test]
+    fn test_bevy_export_shader_validates() {
+        let mut config = SimConfig::default();
+        config.rules.push(RuleConfig::Separate { radius: 0.1, strength: 1.0 });
+        let code = generate_bevy_code(&config);
+
+        assert!(code.contains("//! - Separate rule"));
+        assert!(code.contains("compute-only"));
+        crate::shader_validate::validate_compute_shader(embedded_shader(&code)).expect("exported shader validates");
+        assert!(!embedded_shader(&code).contains("sorted_indices"));
+    }
+}
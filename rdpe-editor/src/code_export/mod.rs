@@ -1,9 +1,67 @@
 //! Code export functionality - generates Rust code from SimConfig.
 
+mod bevy;
+
 use crate::config::*;
+use crate::spawn::{read_f32, read_i32, read_u32};
+
+/// Kind of program the code export produces
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CodeExportTarget {
+    /// An rdpe `Simulation` builder chain
+    #[default]
+    Rdpe,
+    /// A standalone Bevy app with a `MySimPlugin` running the compute shader.
+    /// It does not draw the particles.
+    Bevy,
+}
+
+impl CodeExportTarget {
+    pub const ALL: [CodeExportTarget; 2] = [CodeExportTarget::Rdpe, CodeExportTarget::Bevy];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CodeExportTarget::Rdpe => "RDPE",
+            CodeExportTarget::Bevy => "Bevy (compute only)",
+        }
+    }
+
+    /// What the exported program does, shown next to the target picker.
+    pub fn description(&self) -> &'static str {
+        match self {
+            CodeExportTarget::Rdpe => "A complete rdpe program that simulates and draws the particles.",
+            CodeExportTarget::Bevy => {
+                "Runs the compute shader only and draws nothing. Bind ParticleBuffers::particles \
+                 in your own Bevy render pipeline to see the particles."
+            }
+        }
+    }
+}
+
+/// Generate code for `target`; see [`generate_code`] for the error case.
+pub fn generate_code_for_target(config: &SimConfig, target: CodeExportTarget) -> Result<String, Vec<ConfigError>> {
+    match target {
+        CodeExportTarget::Rdpe => generate_code(config),
+        CodeExportTarget::Bevy => {
+            let errors = config.validate();
+            if !errors.is_empty() {
+                return Err(errors);
+            }
+            Ok(bevy::generate_bevy_code(config))
+        }
+    }
+}
 
 /// Generate Rust code that creates a simulation matching the given config.
-pub fn generate_code(config: &SimConfig) -> String {
+///
+/// Returns the [`SimConfig::validate`] errors instead if the config has any,
+/// since the generated code would not work.
+pub fn generate_code(config: &SimConfig) -> Result<String, Vec<ConfigError>> {
+    let errors = config.validate();
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
     let mut code = String::new();
 
     // Header
@@ -15,6 +73,9 @@ pub fn generate_code(config: &SimConfig) -> String {
     code.push_str(&format!("        .with_particle_count({})\n", config.particle_count));
     code.push_str(&format!("        .with_bounds({:.2})\n", config.bounds));
     code.push_str(&format!("        .with_particle_size({:.4})\n", config.particle_size));
+    if config.two_d {
+        code.push_str("        .with_2d_mode()\n");
+    }
 
     // Spatial config (if needed)
     if config.needs_spatial() {
@@ -22,13 +83,16 @@ pub fn generate_code(config: &SimConfig) -> String {
             "        .with_spatial_config({:.2}, {})\n",
             config.spatial_cell_size, config.spatial_resolution
         ));
+        if !config.skip_dead_neighbors {
+            code.push_str("        .with_skip_dead_neighbors(false)\n");
+        }
     }
 
     // Spawner closure
-    code.push_str(&spawner_code(&config.spawn));
+    code.push_str(&spawner_code(config));
 
     // Rules
-    for rule in &config.rules {
+    for rule in config.active_rules() {
         code.push_str(&format!("        .with_rule({})\n", rule_code(rule)));
     }
 
@@ -57,7 +121,7 @@ pub fn generate_code(config: &SimConfig) -> String {
     }
 
     // Visuals closure (if any non-default)
-    let visuals_code = visuals_closure_code(&config.visuals);
+    let visuals_code = visuals_closure_code(&config.visuals, &config.custom_shaders.post_process_chain);
     if !visuals_code.is_empty() {
         code.push_str(&visuals_code);
     }
@@ -66,18 +130,78 @@ pub fn generate_code(config: &SimConfig) -> String {
     code.push_str("        .run();\n");
     code.push_str("}\n");
 
-    code
+    Ok(code)
+}
+
+/// Serialize raw particle data as CSV, one row per particle.
+///
+/// The header names each column after its layout field, with vector fields
+/// split into components (`position.x`, ...). Names that contain a comma,
+/// quote or line break are quoted. Particles with `alive == 0` are skipped
+/// unless `include_dead` is set.
+pub fn generate_csv(particle_bytes: &[u8], layout: &ParticleLayout, include_dead: bool) -> String {
+    let mut csv = String::new();
+
+    let mut header = Vec::new();
+    for field in &layout.fields {
+        match field_components(field.field_type) {
+            [""] => header.push(csv_escape(&field.name)),
+            suffixes => header.extend(suffixes.iter().map(|suffix| csv_escape(&format!("{}{}", field.name, suffix)))),
+        }
+    }
+    csv.push_str(&header.join(","));
+    csv.push('\n');
+
+    for particle in particle_bytes.chunks_exact(layout.stride) {
+        if !include_dead && read_u32(particle, layout.alive_offset) == 0 {
+            continue;
+        }
+        let mut row = Vec::with_capacity(header.len());
+        for field in &layout.fields {
+            for i in 0..field_components(field.field_type).len() {
+                let offset = field.offset + i * 4;
+                row.push(match field.field_type {
+                    ParticleFieldType::U32 => read_u32(particle, offset).to_string(),
+                    ParticleFieldType::I32 => read_i32(particle, offset).to_string(),
+                    _ => read_f32(particle, offset).to_string(),
+                });
+            }
+        }
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// Quote a CSV cell if it contains a separator, quote or line break
+fn csv_escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// Column name suffixes for each component of a field type
+fn field_components(field_type: ParticleFieldType) -> &'static [&'static str] {
+    match field_type {
+        ParticleFieldType::F32 | ParticleFieldType::U32 | ParticleFieldType::I32 => &[""],
+        ParticleFieldType::Vec2 => &[".x", ".y"],
+        ParticleFieldType::Vec3 => &[".x", ".y", ".z"],
+        ParticleFieldType::Vec4 => &[".x", ".y", ".z", ".w"],
+    }
 }
 
 /// Generate the spawner closure code
-fn spawner_code(spawn: &SpawnConfig) -> String {
+fn spawner_code(config: &SimConfig) -> String {
+    let spawn = &config.spawn;
     let position_code = position_spawn_code(&spawn.shape);
     let velocity_code = velocity_spawn_code(&spawn.velocity);
     let color_code = color_spawn_code(&spawn.color_mode);
 
-    format!(
-        r#"        .with_spawner(|ctx| {{
-            let position = {};
+    let body = format!(
+        r#"            let position = {};
             let velocity = {};
             let color = {};
             MetaParticle {{
@@ -85,10 +209,51 @@ fn spawner_code(spawn: &SpawnConfig) -> String {
                 velocity,
                 color,
                 ..Default::default()
+            }}"#,
+        position_code, velocity_code, color_code
+    );
+
+    // Shapes that need precomputed data build it once and move it into the spawner
+    let setup = match &spawn.shape {
+        SpawnShape::MeshSurface { mesh, .. } => {
+            format!("let mesh_sampler = MeshSurfaceSampler::new(&{});", mesh_data_code(mesh))
+        }
+        SpawnShape::PoissonDisc { min_distance, max_attempts } => format!(
+            "// Particles beyond what fits at this spacing start at the origin\n            \
+             let poisson_points = poisson_disc_xz({:.2}, {:.4}, {}, {}, 0);",
+            config.bounds, min_distance, max_attempts, config.particle_count
+        ),
+        _ => return format!("        .with_spawner(|ctx| {{\n{}\n        }})\n", body),
+    };
+
+    format!(
+        r#"        .with_spawner({{
+            {}
+            move |ctx| {{
+    {}
             }}
         }})
 "#,
-        position_code, velocity_code, color_code
+        setup,
+        body.replace('\n', "\n    ")
+    )
+}
+
+/// `MeshData` literal holding `mesh`'s vertices, normals and indices.
+fn mesh_data_code(mesh: &rdpe::MeshData) -> String {
+    let vec3s = |values: &[[f32; 3]]| {
+        values
+            .iter()
+            .map(|v| format!("[{:?}, {:?}, {:?}]", v[0], v[1], v[2]))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let indices: Vec<String> = mesh.indices.iter().map(|i| i.to_string()).collect();
+    format!(
+        "MeshData {{\n                vertices: vec![{}],\n                normals: vec![{}],\n                indices: vec![{}],\n            }}",
+        vec3s(&mesh.vertices),
+        vec3s(&mesh.normals),
+        indices.join(", ")
     )
 }
 
@@ -112,6 +277,12 @@ fn position_spawn_code(shape: &SpawnShape) -> String {
             format!("Vec3::new(ctx.random_range(-{:.2}, {:.2}), 0.0, ctx.random_range(-{:.2}, {:.2}))",
                 width / 2.0, width / 2.0, depth / 2.0, depth / 2.0)
         }
+        SpawnShape::MeshSurface { offset, .. } => {
+            format!("{{ let (point, normal) = ctx.random_on_mesh(&mesh_sampler); point + normal * {:.3} }}", offset)
+        }
+        SpawnShape::PoissonDisc { .. } => {
+            "poisson_points.get(ctx.index as usize).copied().unwrap_or(Vec3::ZERO)".to_string()
+        }
     }
 }
 
@@ -137,15 +308,34 @@ fn color_spawn_code(mode: &ColorMode) -> String {
         }
         ColorMode::ByPosition => "position.abs().normalize()".to_string(),
         ColorMode::ByVelocity => "velocity.abs().normalize()".to_string(),
-        ColorMode::Gradient { start, end } => {
-            format!("Vec3::new({:.2}, {:.2}, {:.2}).lerp(Vec3::new({:.2}, {:.2}, {:.2}), ctx.progress())",
-                start[0], start[1], start[2], end[0], end[1], end[2])
+        ColorMode::Gradient { .. } | ColorMode::Rainbow => {
+            gradient_code(&mode.gradient_stops().unwrap_or_default(), "ctx.progress()")
+        }
+    }
+}
+
+/// Expression for the color of `stops` at `t`, blended like [`sample_gradient`].
+pub(super) fn gradient_code(stops: &[([f32; 3], f32)], t: &str) -> String {
+    let color = |c: &[f32; 3]| format!("Vec3::new({:?}, {:?}, {:?})", c[0], c[1], c[2]);
+    match stops {
+        [] => "Vec3::ONE".to_string(),
+        [(only, _)] => color(only),
+        _ => {
+            let stops: Vec<String> = stops.iter().map(|(c, p)| format!("({}, {:?})", color(c), p)).collect();
+            format!(
+                "{{ let stops = [{}]; let t = {}; \
+                 let next = stops.partition_point(|s| s.1 <= t).clamp(1, stops.len() - 1); \
+                 let ((a, ta), (b, tb)) = (stops[next - 1], stops[next]); \
+                 a.lerp(b, ((t - ta) / (tb - ta).max(f32::EPSILON)).clamp(0.0, 1.0)) }}",
+                stops.join(", "),
+                t
+            )
         }
     }
 }
 
 /// Generate with_visuals closure if needed
-fn visuals_closure_code(visuals: &VisualsConfig) -> String {
+fn visuals_closure_code(visuals: &VisualsConfig, post_process_chain: &[String]) -> String {
     let default = VisualsConfig::default();
     let mut settings = Vec::new();
 
@@ -157,13 +347,28 @@ fn visuals_closure_code(visuals: &VisualsConfig) -> String {
     }
     if visuals.trail_length > 0 {
         settings.push(format!("v.trails({});", visuals.trail_length));
+        if let Some(tail) = visuals.trail_tail_color {
+            settings.push(format!("v.trail_tail_color(Vec3::new({:.2}, {:.2}, {:.2}));", tail[0], tail[1], tail[2]));
+        }
     }
     if visuals.connections_enabled {
         settings.push(format!("v.connections({:.2});", visuals.connections_radius));
         let default_color = [0.5, 0.7, 1.0];
-        if visuals.connections_color != default_color {
+        let near = visuals.connections_near_color;
+        let far = visuals.connections_far_color.unwrap_or(near);
+        if near != far {
+            settings.push(format!(
+                "v.connections_distance_colors(Vec3::new({:.2}, {:.2}, {:.2}), Vec3::new({:.2}, {:.2}, {:.2}));",
+                near[0], near[1], near[2], far[0], far[1], far[2]));
+        } else if near != default_color {
             settings.push(format!("v.connections_color(Vec3::new({:.2}, {:.2}, {:.2}));",
-                visuals.connections_color[0], visuals.connections_color[1], visuals.connections_color[2]));
+                near[0], near[1], near[2]));
+        }
+        if visuals.connections_directed {
+            settings.push("v.connections_directed(true);".to_string());
+        }
+        if visuals.connections_flow_speed > 0.0 {
+            settings.push(format!("v.connections_flow_speed({:.2});", visuals.connections_flow_speed));
         }
     }
     if visuals.velocity_stretch {
@@ -173,6 +378,30 @@ fn visuals_closure_code(visuals: &VisualsConfig) -> String {
         settings.push(format!("v.background(Vec3::new({:.2}, {:.2}, {:.2}));",
             visuals.background_color[0], visuals.background_color[1], visuals.background_color[2]));
     }
+    if let Some(bloom) = &visuals.bloom {
+        settings.push(format!(
+            "v.bloom(BloomConfig {{ threshold: {:.2}, intensity: {:.2}, radius: {:.2}, iterations: {} }});",
+            bloom.threshold, bloom.intensity, bloom.radius, bloom.iterations));
+    }
+    if visuals.chromatic_aberration > 0.0 {
+        settings.push(format!("v.chromatic_aberration({:.2});", visuals.chromatic_aberration));
+    }
+    if visuals.hdr {
+        settings.push(format!("v.hdr(ToneMapMode::{:?});", visuals.tone_map));
+    }
+    if visuals.msaa_samples != default.msaa_samples {
+        settings.push(format!("v.msaa(MsaaSamples::{:?});", visuals.msaa_samples));
+    }
+    if let Some(light) = &visuals.light {
+        settings.push(format!(
+            "v.light(LightConfig {{ direction: Vec3::new({:.2}, {:.2}, {:.2}), color: Vec3::new({:.2}, {:.2}, {:.2}), shadow_map_size: {}, shadow_bias: {:.4} }});",
+            light.direction[0], light.direction[1], light.direction[2],
+            light.color[0], light.color[1], light.color[2],
+            light.shadow_map_size, light.shadow_bias));
+    }
+    for code in post_process_chain {
+        settings.push(format!("v.add_post_process(r#\"{}\"#);", code));
+    }
 
     if settings.is_empty() {
         String::new()
@@ -206,6 +435,14 @@ fn rule_code(rule: &RuleConfig) -> String {
         RuleConfig::Acceleration { direction } => {
             format!("Rule::Acceleration {{ direction: {} }}", vec3_code(direction))
         }
+        RuleConfig::Pulse { direction, frequency, amplitude, phase, wave_shape } => {
+            format!("Rule::Pulse {{ direction: {}, frequency: {:.2}, amplitude: {:.2}, phase: {:.2}, wave_shape: WaveShape::{:?} }}",
+                vec3_code(direction), frequency, amplitude, phase, wave_shape)
+        }
+        RuleConfig::Magnetic { charge_field, field_strength } => {
+            format!("Rule::Magnetic {{ charge_field: \"{}\".into(), field_strength: {} }}",
+                charge_field, vec3_code(field_strength))
+        }
 
         // Boundaries
         RuleConfig::BounceWalls => "Rule::BounceWalls".to_string(),
@@ -221,25 +458,34 @@ fn rule_code(rule: &RuleConfig) -> String {
         RuleConfig::PointGravity { point, strength, softening } => {
             format!("Rule::PointGravity {{ point: {}, strength: {:.2}, softening: {:.3} }}", vec3_code(point), strength, softening)
         }
-        RuleConfig::Orbit { center, strength } => {
-            format!("Rule::Orbit {{ center: {}, strength: {:.2} }}", vec3_code(center), strength)
+        RuleConfig::Orbit { center, gravity, softening } => {
+            format!("Rule::Orbit {{ center: {}, gravity: {:.2}, softening: {:.3} }}", vec3_code(center), gravity, softening)
+        }
+        RuleConfig::OrbitEllipse { center, gravity, central_mass, particle_mass, softening } => {
+            format!("Rule::OrbitEllipse {{ center: {}, gravity: {:.2}, central_mass: {:.3}, particle_mass: {:.3}, softening: {:.3} }}",
+                vec3_code(center), gravity, central_mass, particle_mass, softening)
         }
-        RuleConfig::Spring { anchor, stiffness, damping } => {
-            format!("Rule::Spring {{ anchor: {}, stiffness: {:.2}, damping: {:.3} }}", vec3_code(anchor), stiffness, damping)
+        RuleConfig::Spring { anchor, rest_length, stiffness, damping } => {
+            format!("Rule::Spring {{ anchor: {}, rest_length: {:.3}, stiffness: {:.2}, damping: {:.3} }}", vec3_code(anchor), rest_length, stiffness, damping)
         }
         RuleConfig::Radial { point, strength, radius, falloff } => {
             format!("Rule::Radial {{ point: {}, strength: {:.2}, radius: {:.2}, falloff: {} }}",
                 vec3_code(point), strength, radius, falloff_code(falloff))
         }
-        RuleConfig::Vortex { center, axis, strength } => {
-            format!("Rule::Vortex {{ center: {}, axis: {}, strength: {:.2} }}", vec3_code(center), vec3_code(axis), strength)
+        RuleConfig::Vortex { center, axis, strength, radius, falloff } => {
+            format!("Rule::Vortex {{ center: {}, axis: {}, strength: {:.2}, radius: {:.2}, falloff: {} }}",
+                vec3_code(center), vec3_code(axis), strength, radius, falloff_code(falloff))
         }
-        RuleConfig::Pulse { point, strength, frequency, radius } => {
-            format!("Rule::Pulse {{ point: {}, strength: {:.2}, frequency: {:.2}, radius: {:.2} }}",
+        RuleConfig::RadialPulse { point, strength, frequency, radius } => {
+            format!("Rule::RadialPulse {{ point: {}, strength: {:.2}, frequency: {:.2}, radius: {:.2} }}",
                 vec3_code(point), strength, frequency, radius)
         }
 
         // Noise & Flow
+        RuleConfig::NoiseField { frequency, amplitude, octaves, time_scale, .. } => {
+            format!("Rule::NoiseField {{ frequency: {:.2}, amplitude: {:.3}, octaves: {}, time_scale: {:.3} }}",
+                frequency, amplitude, octaves, time_scale)
+        }
         RuleConfig::Turbulence { scale, strength } => {
             format!("Rule::Turbulence {{ scale: {:.2}, strength: {:.3} }}", scale, strength)
         }
@@ -283,13 +529,30 @@ fn rule_code(rule: &RuleConfig) -> String {
             format!("Rule::Flock {{ radius: {:.2}, separation: {:.3}, cohesion: {:.3}, alignment: {:.3} }}",
                 radius, separation, cohesion, alignment)
         }
+        RuleConfig::Boid {
+            separation_radius,
+            cohesion_radius,
+            alignment_radius,
+            separation_weight,
+            cohesion_weight,
+            alignment_weight,
+            self_type,
+        } => {
+            let type_str = match self_type {
+                Some(t) => format!("Some({})", t),
+                None => "None".to_string(),
+            };
+            format!("Rule::Boid {{ separation_radius: {:.3}, cohesion_radius: {:.3}, alignment_radius: {:.3}, separation_weight: {:.3}, cohesion_weight: {:.3}, alignment_weight: {:.3}, self_type: {} }}",
+                separation_radius, cohesion_radius, alignment_radius, separation_weight, cohesion_weight, alignment_weight, type_str)
+        }
         RuleConfig::Avoid { radius, strength } => {
             format!("Rule::Avoid {{ radius: {:.2}, strength: {:.3} }}", radius, strength)
         }
 
         // Physics
-        RuleConfig::Collide { radius, restitution } => {
-            format!("Rule::Collide {{ radius: {:.3}, restitution: {:.2} }}", radius, restitution)
+        RuleConfig::Collide { radius, restitution, self_type, target_type } => {
+            format!("Rule::Collide {{ radius: {:.3}, restitution: {:.2}, self_type: {:?}, target_type: {:?} }}",
+                radius, restitution, self_type, target_type)
         }
         RuleConfig::NBodyGravity { strength, softening, radius } => {
             format!("Rule::NBodyGravity {{ strength: {:.3}, softening: {:.3}, radius: {:.2} }}", strength, softening, radius)
@@ -300,8 +563,9 @@ fn rule_code(rule: &RuleConfig) -> String {
         RuleConfig::Viscosity { radius, strength } => {
             format!("Rule::Viscosity {{ radius: {:.2}, strength: {:.3} }}", radius, strength)
         }
-        RuleConfig::Pressure { radius, strength, target_density } => {
-            format!("Rule::Pressure {{ radius: {:.2}, strength: {:.3}, target_density: {:.2} }}", radius, strength, target_density)
+        RuleConfig::Pressure { rest_density, pressure_constant, viscosity, kernel_radius } => {
+            format!("Rule::Pressure {{ rest_density: {:.2}, pressure_constant: {:.3}, viscosity: {:.3}, kernel_radius: {:.3} }}",
+                rest_density, pressure_constant, viscosity, kernel_radius)
         }
         RuleConfig::SurfaceTension { radius, strength, threshold } => {
             format!("Rule::SurfaceTension {{ radius: {:.2}, strength: {:.3}, threshold: {:.2} }}", radius, strength, threshold)
@@ -324,6 +588,12 @@ fn rule_code(rule: &RuleConfig) -> String {
         // Lifecycle
         RuleConfig::Age => "Rule::Age".to_string(),
         RuleConfig::Lifetime(t) => format!("Rule::Lifetime({:.2})", t),
+        RuleConfig::MaxAge { max_age, fade_out_start, scale_field } => {
+            format!(
+                "Rule::MaxAge {{ max_age: {:.2}, fade_out_start: {:.2}, scale_field: \"{}\".into() }}",
+                max_age, fade_out_start, scale_field
+            )
+        }
         RuleConfig::FadeOut(t) => format!("Rule::FadeOut({:.2})", t),
         RuleConfig::ShrinkOut(t) => format!("Rule::ShrinkOut({:.2})", t),
         RuleConfig::ColorOverLife { start, end, duration } => {
@@ -381,7 +651,13 @@ fn rule_code(rule: &RuleConfig) -> String {
 
         // Custom
         RuleConfig::Custom { code } => {
-            format!("Rule::Custom(r#\"{}\"#.into())", code)
+            let helpers = rule.shader_helpers();
+            if helpers.is_empty() {
+                format!("Rule::custom(r#\"{}\"#)", code)
+            } else {
+                let helpers: Vec<String> = helpers.iter().map(|h| format!("ShaderHelper::{:?}", h)).collect();
+                format!("Rule::Custom {{ wgsl: r#\"{}\"#.into(), helpers: vec![{}] }}", code, helpers.join(", "))
+            }
         }
         RuleConfig::NeighborCustom { code } => {
             format!("Rule::NeighborCustom(r#\"{}\"#.into())", code)
@@ -524,10 +800,10 @@ fn rule_code(rule: &RuleConfig) -> String {
         // Conditional (simplified)
         RuleConfig::Switch { condition, then_code, else_code } => {
             if let Some(else_c) = else_code {
-                format!("Rule::Custom(r#\"if ({}) {{\n    {}\n}} else {{\n    {}\n}}\"#.into())",
+                format!("Rule::custom(r#\"if ({}) {{\n    {}\n}} else {{\n    {}\n}}\"#)",
                     condition, then_code, else_c)
             } else {
-                format!("Rule::Custom(r#\"if ({}) {{\n    {}\n}}\"#.into())", condition, then_code)
+                format!("Rule::custom(r#\"if ({}) {{\n    {}\n}}\"#)", condition, then_code)
             }
         }
         RuleConfig::TypedNeighbor { self_type, other_type, radius, code } => {
@@ -540,6 +816,21 @@ fn rule_code(rule: &RuleConfig) -> String {
             format!("Rule::NeighborCustom(r#\"{}if neighbor_dist < {} && neighbor_dist > 0.001 {{\n    {}\n}}\"#.into())",
                 type_check, radius, code)
         }
+        RuleConfig::TypedMulti(matrix) => {
+            let n = matrix.num_types() as u32;
+            let mut code = format!("{{\n            let mut matrix = InteractionMatrix::new({});\n", n);
+            for self_type in 0..n {
+                for other_type in 0..n {
+                    let (strength, radius) = matrix.get(self_type, other_type);
+                    if strength != 0.0 || radius != 0.0 {
+                        code.push_str(&format!("            matrix.set({}u32, {}u32, {:.3}, {:.3});\n",
+                            self_type, other_type, strength, radius));
+                    }
+                }
+            }
+            code.push_str("            Rule::TypedMulti(matrix)\n        }");
+            code
+        }
 
         // Advanced Physics
         RuleConfig::DensityBuoyancy { density_field, medium_density, strength } => {
@@ -599,6 +890,9 @@ fn rule_code(rule: &RuleConfig) -> String {
         RuleConfig::Gradient { field, strength, ascending } => {
             format!("Rule::Gradient {{ field: {}, strength: {:.3}, ascending: {} }}", field, strength, ascending)
         }
+        RuleConfig::FieldGradient { field_index, strength, signed } => {
+            format!("Rule::FieldGradient {{ field_index: {}, strength: {:.3}, signed: {} }}", field_index, strength, signed)
+        }
 
         // Neighbor Field Operations
         RuleConfig::Accumulate { source, target, radius, operation, falloff } => {
@@ -685,32 +979,60 @@ fn rule_code(rule: &RuleConfig) -> String {
                     UniformValueConfig::Vec2(arr) => format!("Vec2(Vec2::new({:.4}, {:.4}))", arr[0], arr[1]),
                     UniformValueConfig::Vec3(arr) => format!("Vec3(Vec3::new({:.4}, {:.4}, {:.4}))", arr[0], arr[1], arr[2]),
                     UniformValueConfig::Vec4(arr) => format!("Vec4(Vec4::new({:.4}, {:.4}, {:.4}, {:.4}))", arr[0], arr[1], arr[2], arr[3]),
+                    UniformValueConfig::I32(i) => format!("I32({})", i),
+                    UniformValueConfig::U32(u) => format!("U32({})", u),
+                    UniformValueConfig::AnimatedF32 { keyframes } => format!("F32({:.4})", evaluate_keyframes(keyframes, 0.0)),
                 })
             }).collect();
             format!("Rule::OnCollisionDynamic {{ radius: {:.4}, response: r#\"{}\"#.into(), params: vec![{}] }}",
                 radius, response, params_code.join(", "))
         }
+        RuleConfig::Disabled(rule) => rule_code(rule),
     }
 }
 
 fn field_code(field: &FieldConfigEntry) -> String {
+    if let Some(gs) = field.gray_scott {
+        let config = format!(
+            "FieldConfig::gray_scott({:.4}, {:.4}, {:.2}, {:.2})\n            .with_extent({:.2})\n            .with_blur_iterations({}){}",
+            gs.feed, gs.kill, gs.da, gs.db, field.extent, field.blur_iterations, field_boundary_code(field)
+        );
+        return if field.resolution == 64 {
+            config
+        } else {
+            format!("FieldConfig {{ resolution: {}, ..{} }}", field.resolution, config)
+        };
+    }
+
     let field_type = match field.field_type {
         FieldTypeConfig::Scalar => format!("FieldConfig::new({})", field.resolution),
         FieldTypeConfig::Vector => format!("FieldConfig::new_vector({})", field.resolution),
     };
 
     format!(
-        "{}\n            .with_extent({:.2})\n            .with_decay({:.3})\n            .with_blur({:.3})\n            .with_blur_iterations({})",
-        field_type, field.extent, field.decay, field.blur, field.blur_iterations
+        "{}\n            .with_extent({:.2})\n            .with_decay({:.3})\n            .with_blur({:.3})\n            .with_blur_iterations({}){}",
+        field_type, field.extent, field.decay, field.blur, field.blur_iterations, field_boundary_code(field)
     )
 }
 
+fn field_boundary_code(field: &FieldConfigEntry) -> &'static str {
+    match field.boundary {
+        FieldBoundaryConfig::Clamp => "",
+        FieldBoundaryConfig::Zero => "\n            .with_boundary(FieldBoundary::Zero)",
+        FieldBoundaryConfig::Wrap => "\n            .with_boundary(FieldBoundary::Wrap)",
+    }
+}
+
 fn uniform_value_code(value: &UniformValueConfig) -> String {
     match value {
         UniformValueConfig::F32(v) => format!("{:.4}", v),
         UniformValueConfig::Vec2(v) => format!("Vec2::new({:.4}, {:.4})", v[0], v[1]),
         UniformValueConfig::Vec3(v) => format!("Vec3::new({:.4}, {:.4}, {:.4})", v[0], v[1], v[2]),
         UniformValueConfig::Vec4(v) => format!("Vec4::new({:.4}, {:.4}, {:.4}, {:.4})", v[0], v[1], v[2], v[3]),
+        UniformValueConfig::I32(v) => format!("{}i32", v),
+        UniformValueConfig::U32(v) => format!("{}u32", v),
+        // Exported simulations start from the curve's initial value
+        UniformValueConfig::AnimatedF32 { keyframes } => format!("{:.4}", evaluate_keyframes(keyframes, 0.0)),
     }
 }
 
@@ -736,3 +1058,74 @@ fn shape_code(shape: &ParticleShapeConfig) -> String {
     }.to_string()
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn put(bytes: &mut [u8], offset: usize, value: [u8; 4]) {
+        bytes[offset..offset + 4].copy_from_slice(&value);
+    }
+
+    #[test]
+    fn test_csv_escape() {
+        assert_eq!(csv_escape("energy"), "energy");
+        assert_eq!(csv_escape("a,b"), "\"a,b\"");
+        assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_escape("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn test_generate_csv() {
+        let layout = ParticleLayout::compute(&[
+            ParticleFieldDef::new("heading", ParticleFieldType::Vec2),
+            ParticleFieldDef::new("charge", ParticleFieldType::I32),
+            ParticleFieldDef::new("odd,name", ParticleFieldType::F32),
+        ]);
+        let offset = |name: &str| layout.field_offset(name).unwrap();
+
+        let mut bytes = vec![0u8; layout.stride * 2];
+        for (i, particle) in bytes.chunks_exact_mut(layout.stride).enumerate() {
+            put(particle, layout.position_offset, (i as f32 + 1.0).to_le_bytes());
+            put(particle, layout.alive_offset, (i as u32).to_le_bytes());
+            put(particle, layout.particle_type_offset, 7u32.to_le_bytes());
+            put(particle, offset("heading") + 4, 0.5f32.to_le_bytes());
+            put(particle, offset("charge"), (-3i32).to_le_bytes());
+            put(particle, offset("odd,name"), 2.5f32.to_le_bytes());
+        }
+
+        let csv = generate_csv(&bytes, &layout, false);
+        let lines: Vec<&str> = csv.lines().collect();
+        // Header, then only the alive particle
+        assert_eq!(lines.len(), 2);
+
+        let header: Vec<&str> = lines[0].split(',').collect();
+        assert_eq!(
+            &header[..9],
+            ["position.x", "position.y", "position.z", "velocity.x", "velocity.y", "velocity.z", "color.x", "color.y", "color.z"]
+        );
+        assert!(lines[0].contains("heading.x,heading.y"));
+        assert!(lines[0].contains(",charge"));
+        assert!(lines[0].ends_with(",\"odd,name\""));
+
+        // One column per component, in header order
+        let columns: Vec<String> = layout
+            .fields
+            .iter()
+            .flat_map(|f| field_components(f.field_type).iter().map(move |s| format!("{}{}", f.name, s)))
+            .collect();
+        let row: Vec<&str> = lines[1].split(',').collect();
+        assert_eq!(row.len(), columns.len());
+        let cell = |name: &str| row[columns.iter().position(|c| c == name).unwrap()];
+        assert_eq!(cell("position.x"), "2");
+        assert_eq!(cell("alive"), "1");
+        assert_eq!(cell("particle_type"), "7");
+        assert_eq!(cell("heading.x"), "0");
+        assert_eq!(cell("heading.y"), "0.5");
+        assert_eq!(cell("charge"), "-3");
+        assert_eq!(cell("odd,name"), "2.5");
+
+        // Dead particles are kept on request
+        assert_eq!(generate_csv(&bytes, &layout, true).lines().count(), 3);
+    }
+}
@@ -1,6 +1,7 @@
 //! Field system configuration
 
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 /// Custom shader code configuration.
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
@@ -11,6 +12,9 @@ pub struct CustomShaderConfig {
     /// Custom fragment shader code (injected before final color output).
     #[serde(default)]
     pub fragment_code: String,
+    /// Fullscreen post-process shader bodies, run in order on the final image.
+    #[serde(default)]
+    pub post_process_chain: Vec<String>,
 }
 
 /// Field type for editor configuration.
@@ -34,6 +38,138 @@ impl FieldTypeConfig {
     }
 }
 
+/// Field boundary condition for editor configuration.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum FieldBoundaryConfig {
+    Zero,
+    #[default]
+    Clamp,
+    Wrap,
+}
+
+impl FieldBoundaryConfig {
+    pub fn variants() -> &'static [&'static str] {
+        &["Zero", "Clamp", "Wrap"]
+    }
+
+    pub fn all() -> [FieldBoundaryConfig; 3] {
+        [FieldBoundaryConfig::Zero, FieldBoundaryConfig::Clamp, FieldBoundaryConfig::Wrap]
+    }
+
+    pub fn to_field_boundary(&self) -> rdpe::FieldBoundary {
+        match self {
+            FieldBoundaryConfig::Zero => rdpe::FieldBoundary::Zero,
+            FieldBoundaryConfig::Clamp => rdpe::FieldBoundary::Clamp,
+            FieldBoundaryConfig::Wrap => rdpe::FieldBoundary::Wrap,
+        }
+    }
+}
+
+/// Gray-Scott reaction-diffusion parameters for editor configuration.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GrayScottConfig {
+    pub feed: f32,
+    pub kill: f32,
+    pub da: f32,
+    pub db: f32,
+}
+
+impl GrayScottConfig {
+    /// Well-known parameter sets.
+    pub const PRESETS: &'static [(&'static str, GrayScottConfig)] = &[
+        ("Spots", GrayScottConfig { feed: 0.0367, kill: 0.0649, da: 1.0, db: 0.5 }),
+        ("Stripes", GrayScottConfig { feed: 0.022, kill: 0.051, da: 1.0, db: 0.5 }),
+        ("Worms", GrayScottConfig { feed: 0.078, kill: 0.061, da: 1.0, db: 0.5 }),
+    ];
+
+    /// Name of the preset these parameters match, if any.
+    pub fn preset_name(&self) -> Option<&'static str> {
+        Self::PRESETS.iter().find(|(_, p)| p == self).map(|(name, _)| *name)
+    }
+}
+
+/// Image channel used to initialize a field.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum ImageChannel {
+    R,
+    G,
+    B,
+    #[default]
+    Luminance,
+}
+
+impl ImageChannel {
+    pub fn variants() -> &'static [&'static str] {
+        &["R", "G", "B", "Luminance"]
+    }
+
+    pub fn all() -> [ImageChannel; 4] {
+        [ImageChannel::R, ImageChannel::G, ImageChannel::B, ImageChannel::Luminance]
+    }
+
+    /// Extract this channel from an RGBA pixel, in `[0, 255]`.
+    fn extract(&self, pixel: &[u8]) -> f32 {
+        let [r, g, b] = [pixel[0], pixel[1], pixel[2]].map(f32::from);
+        match self {
+            ImageChannel::R => r,
+            ImageChannel::G => g,
+            ImageChannel::B => b,
+            ImageChannel::Luminance => 0.2126 * r + 0.7152 * g + 0.0722 * b,
+        }
+    }
+}
+
+/// Image used as the initial state of a field.
+///
+/// The image is stretched over the field's X/Y plane (top row at +Y) and
+/// repeated along Z.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct InitialFieldImage {
+    /// Image path, relative to the config file when saved.
+    pub path: String,
+    /// Channel to read.
+    #[serde(default)]
+    pub channel: ImageChannel,
+    /// Field values that channel values 0 and 255 map to.
+    pub range: [f32; 2],
+}
+
+impl InitialFieldImage {
+    /// Load the image and sample one value per cell of a `resolution³` field.
+    pub fn load_values(&self, resolution: u32) -> Result<Vec<f32>, rdpe::TextureError> {
+        let image = rdpe::TextureConfig::try_from_file(&self.path)?;
+        let res = resolution as usize;
+        let (width, height) = (image.width as usize, image.height as usize);
+
+        let mut slice = Vec::with_capacity(res * res);
+        for y in 0..res {
+            // Field +Y is the top of the image
+            let py = ((res - 1 - y) * height / res).min(height - 1);
+            for x in 0..res {
+                let px = (x * width / res).min(width - 1);
+                let offset = (py * width + px) * 4;
+                let t = self.channel.extract(&image.data[offset..offset + 4]) / 255.0;
+                slice.push(self.range[0] + t * (self.range[1] - self.range[0]));
+            }
+        }
+        Ok(slice.repeat(res))
+    }
+
+    /// Make a relative path absolute against `base_dir`.
+    pub(crate) fn resolve_path(&mut self, base_dir: &Path) {
+        if Path::new(&self.path).is_relative() {
+            self.path = base_dir.join(&self.path).display().to_string();
+        }
+    }
+
+    /// Make the path relative to `base_dir` if it lies inside it.
+    pub(crate) fn relativize_path(&mut self, base_dir: &Path) {
+        if let Ok(relative) = Path::new(&self.path).strip_prefix(base_dir) {
+            self.path = relative.display().to_string();
+        }
+    }
+}
+
 /// Configuration for a single 3D spatial field.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct FieldConfigEntry {
@@ -51,6 +187,15 @@ pub struct FieldConfigEntry {
     pub blur_iterations: u32,
     /// Field type (Scalar or Vector).
     pub field_type: FieldTypeConfig,
+    /// Behavior at the edges of the grid.
+    #[serde(default)]
+    pub boundary: FieldBoundaryConfig,
+    /// Gray-Scott reaction-diffusion parameters (replaces blur and decay).
+    #[serde(default)]
+    pub gray_scott: Option<GrayScottConfig>,
+    /// Image loaded as the field's initial state.
+    #[serde(default)]
+    pub init_from_image: Option<InitialFieldImage>,
 }
 
 impl Default for FieldConfigEntry {
@@ -63,12 +208,35 @@ impl Default for FieldConfigEntry {
             blur: 0.1,
             blur_iterations: 1,
             field_type: FieldTypeConfig::Scalar,
+            boundary: FieldBoundaryConfig::Clamp,
+            gray_scott: None,
+            init_from_image: None,
         }
     }
 }
 
 impl FieldConfigEntry {
+    /// A Gray-Scott field with the given parameters.
+    pub fn gray_scott(name: impl Into<String>, params: GrayScottConfig) -> Self {
+        Self {
+            name: name.into(),
+            decay: 1.0,
+            blur: 0.0,
+            blur_iterations: 8,
+            gray_scott: Some(params),
+            ..Default::default()
+        }
+    }
+
     pub fn to_field_config(&self) -> rdpe::FieldConfig {
+        if let Some(gs) = self.gray_scott {
+            let mut config = rdpe::FieldConfig::gray_scott(gs.feed, gs.kill, gs.da, gs.db)
+                .with_extent(self.extent)
+                .with_boundary(self.boundary.to_field_boundary())
+                .with_blur_iterations(self.blur_iterations);
+            config.resolution = self.resolution.clamp(8, 256);
+            return config;
+        }
         let mut config = if self.field_type == FieldTypeConfig::Vector {
             rdpe::FieldConfig::new_vector(self.resolution.clamp(8, 256))
         } else {
@@ -78,7 +246,98 @@ impl FieldConfigEntry {
             .with_extent(self.extent)
             .with_decay(self.decay)
             .with_blur(self.blur)
-            .with_blur_iterations(self.blur_iterations);
+            .with_blur_iterations(self.blur_iterations)
+            .with_boundary(self.boundary.to_field_boundary());
         config
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SimConfig;
+
+    /// Write a 2x2 image: red, green on top; blue, white on the bottom.
+    fn write_test_image(path: &Path) {
+        let pixels = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255], [255, 255, 255, 255]];
+        image::RgbaImage::from_raw(2, 2, pixels.concat()).unwrap().save(path).unwrap();
+    }
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("rdpe_editor_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_load_values_samples_channel_into_range() {
+        let dir = temp_dir("field_image");
+        let path = dir.join("init.png");
+        write_test_image(&path);
+
+        let mut image = InitialFieldImage {
+            path: path.display().to_string(),
+            channel: ImageChannel::R,
+            range: [0.0, 1.0],
+        };
+        // Rows run from the bottom of the image up, and repeat along Z
+        assert_eq!(image.load_values(2).unwrap(), [0.0, 1.0, 1.0, 0.0].repeat(2));
+
+        image.channel = ImageChannel::G;
+        image.range = [-1.0, 1.0];
+        assert_eq!(image.load_values(2).unwrap(), [-1.0, 1.0, -1.0, 1.0].repeat(2));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_values_missing_file() {
+        let image = InitialFieldImage {
+            path: "does/not/exist.png".to_string(),
+            channel: ImageChannel::Luminance,
+            range: [0.0, 1.0],
+        };
+        assert!(image.load_values(4).is_err());
+    }
+
+    #[test]
+    fn test_image_path_round_trip() {
+        let dir = temp_dir("field_config");
+        let image_path = dir.join("init.png");
+        write_test_image(&image_path);
+        let outside = InitialFieldImage {
+            path: "/elsewhere/other.png".to_string(),
+            channel: ImageChannel::B,
+            range: [0.0, 2.0],
+        };
+
+        let config = SimConfig {
+            fields: vec![
+                FieldConfigEntry {
+                    init_from_image: Some(InitialFieldImage {
+                        path: image_path.display().to_string(),
+                        channel: ImageChannel::R,
+                        range: [0.0, 1.0],
+                    }),
+                    ..Default::default()
+                },
+                FieldConfigEntry { init_from_image: Some(outside), ..Default::default() },
+            ],
+            ..Default::default()
+        };
+
+        // Saved relative to the config file when inside its directory
+        let config_path = dir.join("config.json");
+        config.save(&config_path).unwrap();
+        let json = std::fs::read_to_string(&config_path).unwrap();
+        assert!(json.contains("\"path\": \"init.png\""));
+        assert!(json.contains("/elsewhere/other.png"));
+
+        // Loaded back as absolute paths that still open
+        let loaded = SimConfig::load(&config_path).unwrap();
+        assert_eq!(loaded.fields, config.fields);
+        assert!(loaded.fields[0].init_from_image.as_ref().unwrap().load_values(2).is_ok());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
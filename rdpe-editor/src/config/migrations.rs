@@ -0,0 +1,299 @@
+//! Forward migrations for saved configuration files.
+//!
+//! Every saved config records the format `version` it was written with.
+//! Files without one predate versioning and count as version 1. When an
+//! older file is loaded, the raw JSON runs through one migration function
+//! per version step before it is deserialized, so renamed or restructured
+//! fields keep working.
+//!
+//! To change the format: bump [`CURRENT_VERSION`], write a
+//! `migrate_vN_to_vN+1` function and append it to [`MIGRATIONS`].
+
+use serde_json::{Map, Value};
+
+/// Format version written by this editor.
+pub const CURRENT_VERSION: u32 = 3;
+
+/// Migration steps in order; entry `i` upgrades version `i + 1` to `i + 2`.
+const MIGRATIONS: &[fn(&mut Value)] = &[migrate_v1_to_v2, migrate_v2_to_v3];
+
+/// Version recorded in a raw config, 1 if it has none.
+pub fn version_of(config: &Value) -> u32 {
+    config.get("version").and_then(Value::as_u64).map_or(1, |v| v as u32)
+}
+
+/// Upgrade a raw config to [`CURRENT_VERSION`] in place.
+///
+/// Returns the version the config had before migrating. Configs from a
+/// newer editor are left untouched.
+pub fn migrate(config: &mut Value) -> u32 {
+    let original = version_of(config);
+    for (from, step) in MIGRATIONS.iter().enumerate().skip(original.saturating_sub(1) as usize) {
+        step(config);
+        if let Some(object) = config.as_object_mut() {
+            object.insert("version".into(), Value::from(from as u32 + 2));
+        }
+    }
+    original
+}
+
+/// v1 -> v2: rules whose physics changed get equivalent parameters.
+///
+/// - `Orbit { strength }` was a constant pull of `strength` toward the
+///   center and is now inverse-square `gravity`, see [`convert_orbit`]
+/// - `Pressure { target_density, strength, radius }` counted neighbors with
+///   a linear falloff and is now SPH, see [`convert_pressure`]
+/// - the radial `Pulse { point, .. }` became `RadialPulse`, freeing `Pulse`
+///   for the directional oscillating force
+fn migrate_v1_to_v2(config: &mut Value) {
+    rename_rule(config, "Pulse", "RadialPulse", |params| params.contains_key("point"));
+    let bounds = config.get("bounds").and_then(Value::as_f64).unwrap_or(1.0);
+    for_each_rule(config, |name, params| match name {
+        "Orbit" => convert_orbit(params, bounds),
+        "Pressure" => convert_pressure(params),
+        _ => {}
+    });
+}
+
+/// v2 -> v3: `MaxAge` stores the unfaded scale in a particle field, and
+/// spawn gradients hold a list of stops.
+///
+/// - `MaxAge` rules without a `scale_field` get `base_scale`, which is added
+///   to the custom particle fields if missing
+/// - a `Gradient { start, end }` color mode becomes stops at 0 and 1
+fn migrate_v2_to_v3(config: &mut Value) {
+    convert_gradient(config);
+    let mut needs_field = false;
+    for_each_rule(config, |name, params| {
+        if name == "MaxAge" && !params.contains_key("scale_field") {
+            params.insert("scale_field".into(), Value::from("base_scale"));
+            needs_field = true;
+        }
+    });
+    if needs_field {
+        add_f32_field(config, "base_scale");
+    }
+}
+
+/// Turn an old two-color spawn `Gradient { start, end }` into stops.
+fn convert_gradient(config: &mut Value) {
+    let Some(params) = config.pointer_mut("/spawn/color_mode/Gradient").and_then(Value::as_object_mut) else {
+        return;
+    };
+    if params.contains_key("stops") {
+        return;
+    }
+    let (Some(start), Some(end)) = (params.remove("start"), params.remove("end")) else {
+        return;
+    };
+    params.insert("stops".into(), serde_json::json!([[start, 0.0], [end, 1.0]]));
+}
+
+/// Append an f32 custom particle field unless one with that name exists.
+fn add_f32_field(config: &mut Value, name: &str) {
+    let Some(object) = config.as_object_mut() else {
+        return;
+    };
+    let fields = object.entry("particle_fields").or_insert_with(|| Value::Array(Vec::new()));
+    if let Some(fields) = fields.as_array_mut() {
+        if !fields.iter().any(|f| f.get("name").and_then(Value::as_str) == Some(name)) {
+            fields.push(serde_json::json!({ "name": name, "field_type": "F32" }));
+        }
+    }
+}
+
+/// Turn an old constant-pull `Orbit { strength }` into inverse-square gravity.
+///
+/// The old rule pulled with acceleration `strength` at every distance, so
+/// circular orbits at radius `r` ran at `sqrt(strength * r)`. Gravity is
+/// chosen so both forces, and so the orbital speeds, agree at half the
+/// bounds, where most scenes spawn their orbiting particles.
+fn convert_orbit(params: &mut Map<String, Value>, bounds: f64) {
+    if params.contains_key("gravity") {
+        return;
+    }
+    let Some(strength) = params.remove("strength").and_then(|v| v.as_f64()) else {
+        return;
+    };
+    let softening = params.get("softening").and_then(Value::as_f64).unwrap_or(0.05);
+    params.insert("softening".into(), Value::from(softening));
+    let radius = bounds * 0.5;
+    params.insert("gravity".into(), Value::from(strength * (radius * radius + softening * softening)));
+}
+
+/// Turn an old neighbor-counting `Pressure` into SPH parameters.
+///
+/// The old rule summed the weights `1 - d / radius` into a density and
+/// pushed with `strength * (density - target_density)` along the weighted
+/// neighbor directions. For neighbors spread evenly inside the radius,
+/// the SPH density is `315 / 64π` for the particle itself plus `0.955`
+/// per unit of the old density, and the spiky gradient weighs each
+/// neighbor `1.432 / radius` on average against the old `0.25`. Matching
+/// both gives the rest density and a pressure constant that push about
+/// as hard as before.
+fn convert_pressure(params: &mut Map<String, Value>) {
+    if params.contains_key("rest_density") {
+        return;
+    }
+    let mut take = |key: &str| params.remove(key).and_then(|v| v.as_f64());
+    let (Some(target_density), Some(strength), Some(radius)) = (take("target_density"), take("strength"), take("radius"))
+    else {
+        return;
+    };
+    let self_density = 315.0 / (64.0 * std::f64::consts::PI);
+    let rest_density = self_density + 0.955 * target_density;
+    params.insert("rest_density".into(), Value::from(rest_density));
+    params.insert("pressure_constant".into(), Value::from(strength * rest_density * rest_density * radius / 10.94));
+    params.insert("kernel_radius".into(), Value::from(radius));
+    params.insert("viscosity".into(), Value::from(0.0));
+}
+
+/// Call `f` with the variant name and parameters of every struct-like rule,
+/// looking inside disabled rules.
+fn for_each_rule(config: &mut Value, mut f: impl FnMut(&str, &mut Map<String, Value>)) {
+    for_each_rule_object(config, |object| {
+        for (name, params) in object.iter_mut() {
+            if let Some(params) = params.as_object_mut() {
+                f(name, params);
+            }
+        }
+    });
+}
+
+/// Rename the rule variant `from` to `to` wherever its parameters satisfy
+/// `matches`, looking inside disabled rules.
+fn rename_rule(config: &mut Value, from: &str, to: &str, matches: impl Fn(&Map<String, Value>) -> bool) {
+    for_each_rule_object(config, |object| {
+        if object.get(from).and_then(Value::as_object).is_some_and(&matches) {
+            let params = object.remove(from).unwrap();
+            object.insert(to.into(), params);
+        }
+    });
+}
+
+/// Call `f` with the `{ "Variant": params }` object of every struct-like
+/// rule, unwrapping disabled rules.
+fn for_each_rule_object(config: &mut Value, mut f: impl FnMut(&mut Map<String, Value>)) {
+    fn visit(rule: &mut Value, f: &mut impl FnMut(&mut Map<String, Value>)) {
+        let Some(object) = rule.as_object_mut() else {
+            // Unit rules serialize as plain strings
+            return;
+        };
+        match object.get_mut("Disabled") {
+            Some(inner) => visit(inner, f),
+            None => f(object),
+        }
+    }
+
+    if let Some(rules) = config.get_mut("rules").and_then(Value::as_array_mut) {
+        for rule in rules {
+            visit(rule, &mut f);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ColorMode, RuleConfig, SimConfig, WaveShape};
+    use serde_json::json;
+
+    /// A default config without a version, holding `rules`.
+    fn old_config(rules: Value) -> Value {
+        let mut config = serde_json::to_value(SimConfig::default()).unwrap();
+        let object = config.as_object_mut().unwrap();
+        object.remove("version");
+        object.insert("bounds".into(), json!(2.0));
+        object.insert("rules".into(), rules);
+        config
+    }
+
+    fn migrated_rules(mut config: Value) -> Vec<RuleConfig> {
+        assert_eq!(migrate(&mut config), 1);
+        assert_eq!(version_of(&config), CURRENT_VERSION);
+        serde_json::from_value::<SimConfig>(config).unwrap().rules
+    }
+
+    #[test]
+    fn test_v1_orbit_matches_old_pull_at_half_bounds() {
+        let rules = migrated_rules(old_config(json!([
+            { "Orbit": { "center": [0.0, 0.0, 0.0], "strength": 2.0 } }
+        ])));
+        let RuleConfig::Orbit { gravity, softening, .. } = rules[0] else {
+            panic!("expected Orbit, got {:?}", rules[0]);
+        };
+        // Half the bounds is 1.0, where gravity / (r² + s²) must equal the old strength
+        assert!((gravity / (1.0 + softening * softening) - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_v1_pressure_converted_to_sph() {
+        let rules = migrated_rules(old_config(json!([
+            { "Pressure": { "radius": 0.1, "strength": 2.0, "target_density": 4.0 } }
+        ])));
+        let RuleConfig::Pressure { rest_density, pressure_constant, viscosity, kernel_radius } = rules[0] else {
+            panic!("expected Pressure, got {:?}", rules[0]);
+        };
+        assert_eq!(kernel_radius, 0.1);
+        assert!((rest_density - 5.387).abs() < 1e-3);
+        assert!((pressure_constant - 2.0 * 5.387 * 5.387 * 0.1 / 10.94).abs() < 1e-3);
+        assert_eq!(viscosity, 0.0);
+    }
+
+    #[test]
+    fn test_v1_radial_pulse_renamed() {
+        let rules = migrated_rules(old_config(json!([
+            { "Pulse": { "point": [0.0, 1.0, 0.0], "strength": 2.0, "frequency": 3.0, "radius": 0.5 } },
+            { "Disabled": { "Pulse": { "point": [0.0, 0.0, 0.0], "strength": 1.0, "frequency": 1.0, "radius": 1.0 } } }
+        ])));
+        assert_eq!(
+            rules[0],
+            RuleConfig::RadialPulse { point: [0.0, 1.0, 0.0], strength: 2.0, frequency: 3.0, radius: 0.5 }
+        );
+        assert!(matches!(&rules[1], RuleConfig::Disabled(inner) if matches!(**inner, RuleConfig::RadialPulse { .. })));
+    }
+
+    #[test]
+    fn test_v2_max_age_gets_scale_field() {
+        let mut config = old_config(json!([
+            { "MaxAge": { "max_age": 3.0, "fade_out_start": 2.0 } }
+        ]));
+        config["version"] = json!(2);
+        assert_eq!(migrate(&mut config), 2);
+
+        let config: SimConfig = serde_json::from_value(config).unwrap();
+        assert_eq!(
+            config.rules[0],
+            RuleConfig::MaxAge { max_age: 3.0, fade_out_start: 2.0, scale_field: "base_scale".into() }
+        );
+        assert!(config.has_custom_field("base_scale"));
+        assert!(config.validate().is_empty());
+    }
+
+    #[test]
+    fn test_v2_gradient_becomes_stops() {
+        let mut config = old_config(json!([]));
+        config["version"] = json!(2);
+        config["spawn"]["color_mode"] = json!({ "Gradient": { "start": [1.0, 0.0, 0.0], "end": [0.0, 0.0, 1.0] } });
+        assert_eq!(migrate(&mut config), 2);
+
+        let config: SimConfig = serde_json::from_value(config).unwrap();
+        assert_eq!(
+            config.spawn.color_mode,
+            ColorMode::Gradient { stops: vec![([1.0, 0.0, 0.0], 0.0), ([0.0, 0.0, 1.0], 1.0)] }
+        );
+    }
+
+    #[test]
+    fn test_migration_keeps_new_format_rules() {
+        let rules = migrated_rules(old_config(json!([
+            { "Orbit": { "center": [0.0, 0.0, 0.0], "gravity": 3.0, "softening": 0.1 } },
+            { "Pulse": {
+                "direction": [0.0, 1.0, 0.0], "frequency": 2.0, "amplitude": 1.0,
+                "phase": 0.0, "wave_shape": "Square"
+            } }
+        ])));
+        assert_eq!(rules[0], RuleConfig::Orbit { center: [0.0; 3], gravity: 3.0, softening: 0.1 });
+        assert!(matches!(rules[1], RuleConfig::Pulse { wave_shape: WaveShape::Square, .. }));
+    }
+}
@@ -1,14 +1,16 @@
 //! Configuration types for RDPE simulations.
 //!
 //! These types represent simulation configurations that can be serialized
-//! to JSON and loaded by the runner.
+//! to JSON (or TOML with the `toml-config` feature) and loaded by the runner.
 
 mod fields;
+pub mod migrations;
 mod mouse;
 mod particle_fields;
 mod rules;
 mod spawn;
 mod uniforms;
+mod validation;
 mod visuals;
 mod volume;
 
@@ -18,15 +20,19 @@ use std::fs;
 use std::path::Path;
 
 // Re-export all types from submodules
-pub use fields::{CustomShaderConfig, FieldConfigEntry, FieldTypeConfig};
+pub use fields::{
+    CustomShaderConfig, FieldBoundaryConfig, FieldConfigEntry, FieldTypeConfig, GrayScottConfig, ImageChannel,
+    InitialFieldImage,
+};
 pub use mouse::{MouseConfig, MousePower};
 pub use particle_fields::{ParticleFieldDef, ParticleFieldInfo, ParticleFieldType, ParticleLayout};
-pub use rules::{AgentStateConfig, Falloff, RuleConfig, TransitionConfig};
-pub use spawn::{ColorMode, InitialVelocity, SpawnConfig, SpawnShape};
-pub use uniforms::UniformValueConfig;
+pub use rules::{AgentStateConfig, Falloff, NoiseFieldPreview, RuleConfig, TransitionConfig, WaveShape};
+pub use spawn::{sample_gradient, ColorMode, InitialVelocity, SpawnConfig, SpawnShape};
+pub use uniforms::{evaluate_keyframes, sort_keyframes, UniformValueConfig};
+pub use validation::ConfigError;
 pub use visuals::{
-    BlendModeConfig, ColorMappingConfig, PaletteConfig, ParticleShapeConfig, VertexEffectConfig,
-    VisualsConfig, WireframeMeshConfig,
+    BlendModeConfig, BloomConfig, ColorMappingConfig, LightConfig, MsaaSamplesConfig, PaletteConfig, ParticleShapeConfig,
+    ToneMapModeConfig, VertexEffectConfig, VisualsConfig, WireframeMeshConfig,
 };
 pub use volume::VolumeRenderConfig;
 
@@ -34,9 +40,21 @@ fn default_speed() -> f32 {
     1.0
 }
 
+fn default_version() -> u32 {
+    1
+}
+
+fn default_skip_dead_neighbors() -> bool {
+    true
+}
+
 /// Complete simulation configuration
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct SimConfig {
+    /// Format version the config was saved with; see [`migrations`].
+    /// Files from before versioning read as 1.
+    #[serde(default = "default_version")]
+    pub version: u32,
     pub name: String,
     pub particle_count: u32,
     pub bounds: f32,
@@ -46,6 +64,9 @@ pub struct SimConfig {
     pub speed: f32,
     pub spatial_cell_size: f32,
     pub spatial_resolution: u32,
+    /// Leave dead particles out of neighbor rules
+    #[serde(default = "default_skip_dead_neighbors")]
+    pub skip_dead_neighbors: bool,
     pub spawn: SpawnConfig,
     pub rules: Vec<RuleConfig>,
     #[serde(default)]
@@ -80,11 +101,15 @@ pub struct SimConfig {
     /// Mouse interaction configuration.
     #[serde(default)]
     pub mouse: MouseConfig,
+    /// Flatten the simulation onto the XY plane and view it with an orthographic camera.
+    #[serde(default)]
+    pub two_d: bool,
 }
 
 impl Default for SimConfig {
     fn default() -> Self {
         Self {
+            version: Self::CURRENT_VERSION,
             name: "Untitled".into(),
             particle_count: 5000,
             bounds: 1.0,
@@ -92,6 +117,7 @@ impl Default for SimConfig {
             speed: 1.0,
             spatial_cell_size: 0.1,
             spatial_resolution: 32,
+            skip_dead_neighbors: true,
             spawn: SpawnConfig::default(),
             rules: vec![
                 RuleConfig::Gravity(2.0),
@@ -106,37 +132,132 @@ impl Default for SimConfig {
             volume_render: VolumeRenderConfig::default(),
             particle_fields: Vec::new(),
             mouse: MouseConfig::default(),
+            two_d: false,
         }
     }
 }
 
 impl SimConfig {
+    /// Config format version written by this editor.
+    pub const CURRENT_VERSION: u32 = migrations::CURRENT_VERSION;
+
     /// Save the configuration to a JSON file.
+    ///
+    /// Field image paths inside the file's directory are stored relative to it.
     pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
-        let json = serde_json::to_string_pretty(self)?;
+        let json = serde_json::to_string_pretty(&self.relative_to(path.as_ref()))?;
         fs::write(path, json)?;
         Ok(())
     }
 
     /// Load a configuration from a JSON file.
+    ///
+    /// Files saved by older versions are migrated to the current format
+    /// first. Relative field image paths are resolved against the file's
+    /// directory.
     pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
-        let json = fs::read_to_string(path)?;
-        let config = serde_json::from_str(&json)?;
+        let json = fs::read_to_string(path.as_ref())?;
+        Self::from_raw(serde_json::from_str(&json)?, path.as_ref())
+    }
+
+    /// Save the configuration to a TOML file.
+    ///
+    /// The document has the same structure as the JSON format. Field image
+    /// paths are stored relative to the file as in [`save`](Self::save).
+    #[cfg(feature = "toml-config")]
+    pub fn save_toml(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
+        let toml = toml::to_string_pretty(&self.relative_to(path.as_ref()))?;
+        fs::write(path, toml)?;
+        Ok(())
+    }
+
+    /// Load a configuration from a TOML file.
+    ///
+    /// Relative field image paths are resolved against the file's directory.
+    #[cfg(feature = "toml-config")]
+    pub fn load_toml(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let toml = fs::read_to_string(path.as_ref())?;
+        Self::from_raw(toml::from_str(&toml)?, path.as_ref())
+    }
+
+    /// Migrate a raw config loaded from `path` and deserialize it.
+    fn from_raw(mut raw: serde_json::Value, path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let version = migrations::migrate(&mut raw);
+        if version < Self::CURRENT_VERSION {
+            eprintln!(
+                "Migrated config {} from version {} to {}",
+                path.display(),
+                version,
+                Self::CURRENT_VERSION
+            );
+        } else if version > Self::CURRENT_VERSION {
+            eprintln!(
+                "Config {} has version {}, newer than supported version {}",
+                path.display(),
+                version,
+                Self::CURRENT_VERSION
+            );
+        }
+        let mut config: Self = serde_json::from_value(raw)?;
+        config.resolve_paths(path);
+        for value in config.custom_uniforms.values_mut() {
+            value.sort_keyframes();
+        }
         Ok(config)
     }
 
+    /// Copy with field image paths made relative to the directory of `path`.
+    fn relative_to(&self, path: &Path) -> Self {
+        let mut config = self.clone();
+        if let Some(base_dir) = path.parent() {
+            for image in config.fields.iter_mut().filter_map(|f| f.init_from_image.as_mut()) {
+                image.relativize_path(base_dir);
+            }
+        }
+        config
+    }
+
+    /// Resolve relative field image paths against the directory of `path`.
+    fn resolve_paths(&mut self, path: &Path) {
+        if let Some(base_dir) = path.parent() {
+            for image in self.fields.iter_mut().filter_map(|f| f.init_from_image.as_mut()) {
+                image.resolve_path(base_dir);
+            }
+        }
+    }
+
+    /// Rules that are switched on, in order. Disabled rules are skipped.
+    pub fn active_rules(&self) -> impl Iterator<Item = &RuleConfig> {
+        self.rules.iter().filter(|r| r.is_enabled())
+    }
+
     /// Check if the simulation needs spatial hashing.
     pub fn needs_spatial(&self) -> bool {
         self.visuals.connections_enabled
             || self.visuals.spatial_grid_opacity > 0.0
-            || self.rules.iter().any(|r| r.requires_neighbors())
+            || self.active_rules().any(|r| r.requires_neighbors())
+    }
+
+    /// Preview settings of the first `NoiseField` rule with its preview enabled.
+    pub fn noise_field_preview(&self) -> Option<NoiseFieldPreview> {
+        self.active_rules().find_map(|r| r.noise_field_preview(self.bounds))
     }
 
     /// Create a FieldRegistry from the config.
+    ///
+    /// Fields with an initial image start from it; images that fail to load
+    /// are reported on stderr and the field starts empty.
     pub fn to_field_registry(&self) -> rdpe::FieldRegistry {
         let mut registry = rdpe::FieldRegistry::new();
         for field in &self.fields {
-            registry.add(&field.name, field.to_field_config());
+            let mut config = field.to_field_config();
+            if let Some(image) = &field.init_from_image {
+                match image.load_values(config.resolution) {
+                    Ok(values) => config = config.with_initial_values(values),
+                    Err(e) => eprintln!("Failed to load initial image for field '{}': {}", field.name, e),
+                }
+            }
+            registry.add(&field.name, config);
         }
         registry
     }
@@ -151,8 +272,58 @@ impl SimConfig {
         self.particle_layout().to_wgsl_struct()
     }
 
+    /// Check that every particle field a rule reads as `f32` exists with that type.
+    pub fn validate_rule_fields(&self) -> Result<(), String> {
+        let layout = self.particle_layout();
+        let mut errors = Vec::new();
+        for rule in self.active_rules().map(|r| r.to_rule()) {
+            for name in rule.required_f32_fields() {
+                match layout.field_info(name) {
+                    Some(info) if info.field_type == ParticleFieldType::F32 => {}
+                    Some(info) => errors.push(format!(
+                        "{} rule reads particle field '{}' as f32, but it is {}",
+                        rule.display_name(),
+                        name,
+                        info.field_type.wgsl_type()
+                    )),
+                    None => errors.push(format!(
+                        "{} rule reads particle field '{}', which is not defined. Add an f32 field named '{}' in the Particle Fields panel",
+                        rule.display_name(),
+                        name,
+                        name
+                    )),
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+
     /// Check if a custom field with the given name is defined.
     pub fn has_custom_field(&self, name: &str) -> bool {
         self.particle_fields.iter().any(|f| f.name == name)
     }
 }
+
+#[cfg(all(test, feature = "toml-config"))]
+mod tests {
+    use super::*;
+    use crate::ui::PRESETS;
+
+    #[test]
+    fn test_every_preset_round_trips_through_toml() {
+        let dir = std::env::temp_dir().join(format!("rdpe_toml_presets_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for preset in PRESETS.iter() {
+            let config = (preset.config)();
+            let path = dir.join("preset.toml");
+            config.save_toml(&path).unwrap_or_else(|e| panic!("{}: save failed: {}", preset.name, e));
+            let loaded = SimConfig::load_toml(&path).unwrap_or_else(|e| panic!("{}: load failed: {}", preset.name, e));
+            assert_eq!(loaded, config, "{} changed after a TOML round trip", preset.name);
+        }
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -22,6 +22,41 @@ impl Falloff {
     }
 }
 
+/// Waveform for oscillating rules like `Pulse`
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub enum WaveShape {
+    #[default]
+    Sine,
+    Square,
+    Triangle,
+}
+
+impl WaveShape {
+    pub fn variants() -> &'static [&'static str] {
+        &["Sine", "Square", "Triangle"]
+    }
+}
+
+/// Orbit configs saved before `softening` existed.
+fn default_orbit_softening() -> f32 {
+    0.05
+}
+
+/// Vortex configs saved before `falloff` existed had no falloff at all.
+fn default_vortex_falloff() -> Falloff {
+    Falloff::Constant
+}
+
+/// Parameters for the viewport overlay of a previewed `NoiseField` rule.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NoiseFieldPreview {
+    pub frequency: f32,
+    pub octaves: u32,
+    pub time_scale: f32,
+    /// Half-extent of the previewed region (the simulation bounds).
+    pub bounds: f32,
+}
+
 /// A transition in an agent state machine.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct TransitionConfig {
@@ -80,6 +115,8 @@ pub enum RuleConfig {
     Gravity(f32),
     Drag(f32),
     Acceleration { direction: [f32; 3] },
+    Pulse { direction: [f32; 3], frequency: f32, amplitude: f32, phase: f32, wave_shape: WaveShape },
+    Magnetic { charge_field: String, field_strength: [f32; 3] },
 
     // === Boundaries ===
     BounceWalls,
@@ -89,14 +126,49 @@ pub enum RuleConfig {
     AttractTo { point: [f32; 3], strength: f32 },
     RepelFrom { point: [f32; 3], strength: f32, radius: f32 },
     PointGravity { point: [f32; 3], strength: f32, softening: f32 },
-    Orbit { center: [f32; 3], strength: f32 },
-    Spring { anchor: [f32; 3], stiffness: f32, damping: f32 },
+    Orbit {
+        center: [f32; 3],
+        gravity: f32,
+        #[serde(default = "default_orbit_softening")]
+        softening: f32,
+    },
+    OrbitEllipse {
+        center: [f32; 3],
+        gravity: f32,
+        central_mass: f32,
+        particle_mass: f32,
+        softening: f32,
+    },
+    Spring {
+        anchor: [f32; 3],
+        #[serde(default)]
+        rest_length: f32,
+        stiffness: f32,
+        damping: f32,
+    },
     Radial { point: [f32; 3], strength: f32, radius: f32, falloff: Falloff },
-    Vortex { center: [f32; 3], axis: [f32; 3], strength: f32 },
-    Pulse { point: [f32; 3], strength: f32, frequency: f32, radius: f32 },
+    Vortex {
+        center: [f32; 3],
+        axis: [f32; 3],
+        strength: f32,
+        #[serde(default)]
+        radius: f32,
+        #[serde(default = "default_vortex_falloff")]
+        falloff: Falloff,
+    },
+    RadialPulse { point: [f32; 3], strength: f32, frequency: f32, radius: f32 },
 
     // === Noise & Flow ===
     Turbulence { scale: f32, strength: f32 },
+    NoiseField {
+        frequency: f32,
+        amplitude: f32,
+        octaves: u32,
+        time_scale: f32,
+        /// Show the noise vectors as an overlay in the viewport (editor only).
+        #[serde(default)]
+        preview: bool,
+    },
     Curl { scale: f32, strength: f32 },
     Wind { direction: [f32; 3], strength: f32, turbulence: f32 },
     PositionNoise { scale: f32, strength: f32, speed: f32 },
@@ -112,14 +184,36 @@ pub enum RuleConfig {
     Cohere { radius: f32, strength: f32 },
     Align { radius: f32, strength: f32 },
     Flock { radius: f32, separation: f32, cohesion: f32, alignment: f32 },
+    Boid {
+        separation_radius: f32,
+        cohesion_radius: f32,
+        alignment_radius: f32,
+        separation_weight: f32,
+        cohesion_weight: f32,
+        alignment_weight: f32,
+        self_type: Option<u32>,
+    },
     Avoid { radius: f32, strength: f32 },
 
     // === Physics ===
-    Collide { radius: f32, restitution: f32 },
+    Collide {
+        radius: f32,
+        restitution: f32,
+        #[serde(default)]
+        self_type: Option<u32>,
+        #[serde(default)]
+        target_type: Option<u32>,
+    },
     NBodyGravity { strength: f32, softening: f32, radius: f32 },
     LennardJones { epsilon: f32, sigma: f32, cutoff: f32 },
     Viscosity { radius: f32, strength: f32 },
-    Pressure { radius: f32, strength: f32, target_density: f32 },
+    Pressure {
+        rest_density: f32,
+        pressure_constant: f32,
+        #[serde(default)]
+        viscosity: f32,
+        kernel_radius: f32,
+    },
     SurfaceTension { radius: f32, strength: f32, threshold: f32 },
     Magnetism { radius: f32, strength: f32, same_repel: bool },
 
@@ -131,6 +225,7 @@ pub enum RuleConfig {
     // === Lifecycle ===
     Age,
     Lifetime(f32),
+    MaxAge { max_age: f32, fade_out_start: f32, scale_field: String },
     FadeOut(f32),
     ShrinkOut(f32),
     ColorOverLife { start: [f32; 3], end: [f32; 3], duration: f32 },
@@ -189,6 +284,8 @@ pub enum RuleConfig {
     // === Conditional ===
     Switch { condition: String, then_code: String, else_code: Option<String> },
     TypedNeighbor { self_type: Option<u32>, other_type: Option<u32>, radius: f32, code: String },
+    /// Per-type-pair forces, edited in the Interactions tab
+    TypedMulti(rdpe::InteractionMatrix),
 
     // === Advanced Physics ===
     DensityBuoyancy { density_field: String, medium_density: f32, strength: f32 },
@@ -218,6 +315,7 @@ pub enum RuleConfig {
     Sense { field_index: u32, target: String },
     Consume { field_index: u32, target: String, rate: f32 },
     Gradient { field: u32, strength: f32, ascending: bool },
+    FieldGradient { field_index: usize, strength: f32, signed: bool },
 
     // === Neighbor Field Operations ===
     Accumulate { source: String, target: String, radius: f32, operation: String, falloff: Option<Falloff> },
@@ -262,6 +360,10 @@ pub enum RuleConfig {
         response: String,
         params: Vec<(String, UniformValueConfig)>,
     },
+
+    /// A rule switched off in the editor. Keeps its parameters but is left
+    /// out of the generated simulation (see `SimConfig::active_rules`).
+    Disabled(Box<RuleConfig>),
 }
 
 impl RuleConfig {
@@ -271,6 +373,8 @@ impl RuleConfig {
             RuleConfig::Gravity(_) => "Gravity",
             RuleConfig::Drag(_) => "Drag",
             RuleConfig::Acceleration { .. } => "Acceleration",
+            RuleConfig::Pulse { .. } => "Pulse",
+            RuleConfig::Magnetic { .. } => "Magnetic",
             // Boundaries
             RuleConfig::BounceWalls => "Bounce Walls",
             RuleConfig::WrapWalls => "Wrap Walls",
@@ -279,12 +383,14 @@ impl RuleConfig {
             RuleConfig::RepelFrom { .. } => "Repel From",
             RuleConfig::PointGravity { .. } => "Point Gravity",
             RuleConfig::Orbit { .. } => "Orbit",
+            RuleConfig::OrbitEllipse { .. } => "Orbit Ellipse",
             RuleConfig::Spring { .. } => "Spring",
             RuleConfig::Radial { .. } => "Radial",
             RuleConfig::Vortex { .. } => "Vortex",
-            RuleConfig::Pulse { .. } => "Pulse",
+            RuleConfig::RadialPulse { .. } => "Radial Pulse",
             // Noise & Flow
             RuleConfig::Turbulence { .. } => "Turbulence",
+            RuleConfig::NoiseField { .. } => "Noise Field",
             RuleConfig::Curl { .. } => "Curl",
             RuleConfig::Wind { .. } => "Wind",
             RuleConfig::PositionNoise { .. } => "Position Noise",
@@ -298,6 +404,7 @@ impl RuleConfig {
             RuleConfig::Cohere { .. } => "Cohere",
             RuleConfig::Align { .. } => "Align",
             RuleConfig::Flock { .. } => "Flock",
+            RuleConfig::Boid { .. } => "Boid",
             RuleConfig::Avoid { .. } => "Avoid",
             // Physics
             RuleConfig::Collide { .. } => "Collide",
@@ -314,6 +421,7 @@ impl RuleConfig {
             // Lifecycle
             RuleConfig::Age => "Age",
             RuleConfig::Lifetime(_) => "Lifetime",
+            RuleConfig::MaxAge { .. } => "Max Age",
             RuleConfig::FadeOut(_) => "Fade Out",
             RuleConfig::ShrinkOut(_) => "Shrink Out",
             RuleConfig::ColorOverLife { .. } => "Color Over Life",
@@ -358,6 +466,7 @@ impl RuleConfig {
             // Conditional
             RuleConfig::Switch { .. } => "Switch",
             RuleConfig::TypedNeighbor { .. } => "Typed Neighbor",
+            RuleConfig::TypedMulti(_) => "Typed Multi",
             // Advanced Physics
             RuleConfig::DensityBuoyancy { .. } => "Density Buoyancy",
             RuleConfig::Diffuse { .. } => "Diffuse",
@@ -383,6 +492,7 @@ impl RuleConfig {
             RuleConfig::Sense { .. } => "Sense",
             RuleConfig::Consume { .. } => "Consume",
             RuleConfig::Gradient { .. } => "Gradient",
+            RuleConfig::FieldGradient { .. } => "Field Gradient",
             // Neighbor Field Operations
             RuleConfig::Accumulate { .. } => "Accumulate",
             RuleConfig::Signal { .. } => "Signal",
@@ -402,27 +512,31 @@ impl RuleConfig {
             RuleConfig::Split { .. } => "Split",
             // Dynamic Collision
             RuleConfig::OnCollisionDynamic { .. } => "On Collision Dynamic",
+            RuleConfig::Disabled(rule) => rule.name(),
         }
     }
 
     pub fn category(&self) -> &'static str {
         match self {
-            RuleConfig::Gravity(_) | RuleConfig::Drag(_) | RuleConfig::Acceleration { .. } => "Forces",
+            RuleConfig::Gravity(_) | RuleConfig::Drag(_) | RuleConfig::Acceleration { .. } | RuleConfig::Pulse { .. } |
+            RuleConfig::Magnetic { .. } |
+            RuleConfig::Vortex { .. } => "Forces",
             RuleConfig::BounceWalls | RuleConfig::WrapWalls => "Boundaries",
             RuleConfig::AttractTo { .. } | RuleConfig::RepelFrom { .. } | RuleConfig::PointGravity { .. } |
-            RuleConfig::Orbit { .. } | RuleConfig::Spring { .. } | RuleConfig::Radial { .. } |
-            RuleConfig::Vortex { .. } | RuleConfig::Pulse { .. } => "Point Forces",
-            RuleConfig::Turbulence { .. } | RuleConfig::Curl { .. } | RuleConfig::Wind { .. } |
+            RuleConfig::Orbit { .. } | RuleConfig::OrbitEllipse { .. } | RuleConfig::Spring { .. } | RuleConfig::Radial { .. } |
+            RuleConfig::RadialPulse { .. } => "Point Forces",
+            RuleConfig::Turbulence { .. } | RuleConfig::NoiseField { .. } | RuleConfig::Curl { .. } |
+            RuleConfig::Wind { .. } |
             RuleConfig::PositionNoise { .. } => "Noise & Flow",
             RuleConfig::Seek { .. } | RuleConfig::Flee { .. } | RuleConfig::Arrive { .. } |
             RuleConfig::Wander { .. } => "Steering",
             RuleConfig::Separate { .. } | RuleConfig::Cohere { .. } | RuleConfig::Align { .. } |
-            RuleConfig::Flock { .. } | RuleConfig::Avoid { .. } => "Flocking",
+            RuleConfig::Flock { .. } | RuleConfig::Boid { .. } | RuleConfig::Avoid { .. } => "Flocking",
             RuleConfig::Collide { .. } | RuleConfig::NBodyGravity { .. } | RuleConfig::LennardJones { .. } |
-            RuleConfig::Viscosity { .. } | RuleConfig::Pressure { .. } | RuleConfig::SurfaceTension { .. } |
             RuleConfig::Magnetism { .. } => "Physics",
+            RuleConfig::Viscosity { .. } | RuleConfig::Pressure { .. } | RuleConfig::SurfaceTension { .. } => "Fluid",
             RuleConfig::SpeedLimit { .. } | RuleConfig::Buoyancy { .. } | RuleConfig::Friction { .. } => "Constraints",
-            RuleConfig::Age | RuleConfig::Lifetime(_) | RuleConfig::FadeOut(_) | RuleConfig::ShrinkOut(_) |
+            RuleConfig::Age | RuleConfig::Lifetime(_) | RuleConfig::MaxAge { .. } | RuleConfig::FadeOut(_) | RuleConfig::ShrinkOut(_) |
             RuleConfig::ColorOverLife { .. } | RuleConfig::ColorBySpeed { .. } | RuleConfig::ColorByAge { .. } |
             RuleConfig::ScaleBySpeed { .. } => "Lifecycle",
             RuleConfig::Chase { .. } | RuleConfig::Evade { .. } | RuleConfig::Convert { .. } => "Typed",
@@ -439,11 +553,11 @@ impl RuleConfig {
             RuleConfig::ChainSprings { .. } | RuleConfig::RadialSprings { .. } | RuleConfig::BondSprings { .. } => "Springs",
             RuleConfig::State { .. } | RuleConfig::Agent { .. } => "State Machine",
             RuleConfig::Switch { .. } => "Conditional",
-            RuleConfig::TypedNeighbor { .. } => "Typed",
+            RuleConfig::TypedNeighbor { .. } | RuleConfig::TypedMulti(_) => "Typed",
             RuleConfig::DensityBuoyancy { .. } | RuleConfig::Diffuse { .. } | RuleConfig::Mass { .. } => "Physics",
             RuleConfig::CopyField { .. } | RuleConfig::Current { .. } |
             RuleConfig::Deposit { .. } | RuleConfig::Sense { .. } | RuleConfig::Consume { .. } |
-            RuleConfig::Gradient { .. } => "Fields",
+            RuleConfig::Gradient { .. } | RuleConfig::FieldGradient { .. } => "Fields",
             RuleConfig::Lerp { .. } | RuleConfig::Clamp { .. } | RuleConfig::Remap { .. } |
             RuleConfig::Quantize { .. } | RuleConfig::Noise { .. } | RuleConfig::Smooth { .. } |
             RuleConfig::Modulo { .. } | RuleConfig::Copy { .. } | RuleConfig::Threshold { .. } |
@@ -454,16 +568,52 @@ impl RuleConfig {
             RuleConfig::Edge { .. } | RuleConfig::Select { .. } | RuleConfig::Blend { .. } => "Logic",
             RuleConfig::Sync { .. } | RuleConfig::Split { .. } => "Lifecycle",
             RuleConfig::OnCollisionDynamic { .. } => "Custom",
+            RuleConfig::Disabled(rule) => rule.category(),
+        }
+    }
+
+    /// Whether the rule takes part in the simulation.
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self, RuleConfig::Disabled(_))
+    }
+
+    /// Switch the rule on or off, keeping its parameters.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if enabled == self.is_enabled() {
+            return;
         }
+        let rule = std::mem::replace(self, RuleConfig::BounceWalls);
+        *self = match rule {
+            RuleConfig::Disabled(inner) => *inner,
+            rule => RuleConfig::Disabled(Box::new(rule)),
+        };
     }
 
     /// Convert to rdpe::Rule
+    ///
+    /// A disabled rule converts to the rule it wraps; use
+    /// `SimConfig::active_rules` to leave disabled rules out.
     pub fn to_rule(&self) -> rdpe::Rule {
         use rdpe::Rule;
         match self {
             RuleConfig::Gravity(g) => Rule::Gravity(*g),
             RuleConfig::Drag(d) => Rule::Drag(*d),
             RuleConfig::Acceleration { direction } => Rule::Acceleration(Vec3::from_array(*direction)),
+            RuleConfig::Pulse { direction, frequency, amplitude, phase, wave_shape } => Rule::Pulse {
+                direction: Vec3::from_array(*direction),
+                frequency: *frequency,
+                amplitude: *amplitude,
+                phase: *phase,
+                wave_shape: match wave_shape {
+                    WaveShape::Sine => rdpe::WaveShape::Sine,
+                    WaveShape::Square => rdpe::WaveShape::Square,
+                    WaveShape::Triangle => rdpe::WaveShape::Triangle,
+                },
+            },
+            RuleConfig::Magnetic { charge_field, field_strength } => Rule::Magnetic {
+                charge_field: charge_field.clone(),
+                field_strength: Vec3::from_array(*field_strength),
+            },
             RuleConfig::BounceWalls => Rule::BounceWalls,
             RuleConfig::WrapWalls => Rule::WrapWalls,
             RuleConfig::AttractTo { point, strength } => Rule::AttractTo {
@@ -480,12 +630,21 @@ impl RuleConfig {
                 strength: *strength,
                 softening: *softening,
             },
-            RuleConfig::Orbit { center, strength } => Rule::Orbit {
+            RuleConfig::Orbit { center, gravity, softening } => Rule::Orbit {
                 center: Vec3::from_array(*center),
-                strength: *strength,
+                gravity: *gravity,
+                softening: *softening,
+            },
+            RuleConfig::OrbitEllipse { center, gravity, central_mass, particle_mass, softening } => Rule::OrbitEllipse {
+                center: Vec3::from_array(*center),
+                gravity: *gravity,
+                central_mass: *central_mass,
+                particle_mass: *particle_mass,
+                softening: *softening,
             },
-            RuleConfig::Spring { anchor, stiffness, damping } => Rule::Spring {
+            RuleConfig::Spring { anchor, rest_length, stiffness, damping } => Rule::Spring {
                 anchor: Vec3::from_array(*anchor),
+                rest_length: *rest_length,
                 stiffness: *stiffness,
                 damping: *damping,
             },
@@ -501,12 +660,20 @@ impl RuleConfig {
                     Falloff::Smooth => rdpe::Falloff::Smooth,
                 },
             },
-            RuleConfig::Vortex { center, axis, strength } => Rule::Vortex {
+            RuleConfig::Vortex { center, axis, strength, radius, falloff } => Rule::Vortex {
                 center: Vec3::from_array(*center),
                 axis: Vec3::from_array(*axis),
                 strength: *strength,
+                radius: *radius,
+                falloff: match falloff {
+                    Falloff::Constant => rdpe::Falloff::Constant,
+                    Falloff::Linear => rdpe::Falloff::Linear,
+                    Falloff::Inverse => rdpe::Falloff::Inverse,
+                    Falloff::InverseSquare => rdpe::Falloff::InverseSquare,
+                    Falloff::Smooth => rdpe::Falloff::Smooth,
+                },
             },
-            RuleConfig::Pulse { point, strength, frequency, radius } => Rule::Pulse {
+            RuleConfig::RadialPulse { point, strength, frequency, radius } => Rule::RadialPulse {
                 point: Vec3::from_array(*point),
                 strength: *strength,
                 frequency: *frequency,
@@ -516,6 +683,12 @@ impl RuleConfig {
                 scale: *scale,
                 strength: *strength,
             },
+            RuleConfig::NoiseField { frequency, amplitude, octaves, time_scale, .. } => Rule::NoiseField {
+                frequency: *frequency,
+                amplitude: *amplitude,
+                octaves: *octaves,
+                time_scale: *time_scale,
+            },
             RuleConfig::Curl { scale, strength } => Rule::Curl {
                 scale: *scale,
                 strength: *strength,
@@ -569,13 +742,32 @@ impl RuleConfig {
                 cohesion: *cohesion,
                 alignment: *alignment,
             },
+            RuleConfig::Boid {
+                separation_radius,
+                cohesion_radius,
+                alignment_radius,
+                separation_weight,
+                cohesion_weight,
+                alignment_weight,
+                self_type,
+            } => Rule::Boid {
+                separation_radius: *separation_radius,
+                cohesion_radius: *cohesion_radius,
+                alignment_radius: *alignment_radius,
+                separation_weight: *separation_weight,
+                cohesion_weight: *cohesion_weight,
+                alignment_weight: *alignment_weight,
+                self_type: *self_type,
+            },
             RuleConfig::Avoid { radius, strength } => Rule::Avoid {
                 radius: *radius,
                 strength: *strength,
             },
-            RuleConfig::Collide { radius, restitution } => Rule::Collide {
+            RuleConfig::Collide { radius, restitution, self_type, target_type } => Rule::Collide {
                 radius: *radius,
                 restitution: *restitution,
+                self_type: *self_type,
+                target_type: *target_type,
             },
             RuleConfig::NBodyGravity { strength, softening, radius } => Rule::NBodyGravity {
                 strength: *strength,
@@ -591,10 +783,11 @@ impl RuleConfig {
                 radius: *radius,
                 strength: *strength,
             },
-            RuleConfig::Pressure { radius, strength, target_density } => Rule::Pressure {
-                radius: *radius,
-                strength: *strength,
-                target_density: *target_density,
+            RuleConfig::Pressure { rest_density, pressure_constant, viscosity, kernel_radius } => Rule::Pressure {
+                rest_density: *rest_density,
+                pressure_constant: *pressure_constant,
+                viscosity: *viscosity,
+                kernel_radius: *kernel_radius,
             },
             RuleConfig::SurfaceTension { radius, strength, threshold } => Rule::SurfaceTension {
                 radius: *radius,
@@ -621,6 +814,11 @@ impl RuleConfig {
             },
             RuleConfig::Age => Rule::Age,
             RuleConfig::Lifetime(t) => Rule::Lifetime(*t),
+            RuleConfig::MaxAge { max_age, fade_out_start, scale_field } => Rule::MaxAge {
+                max_age: *max_age,
+                fade_out_start: *fade_out_start,
+                scale_field: scale_field.clone(),
+            },
             RuleConfig::FadeOut(t) => Rule::FadeOut(*t),
             RuleConfig::ShrinkOut(t) => Rule::ShrinkOut(*t),
             RuleConfig::ColorOverLife { start, end, duration } => Rule::ColorOverLife {
@@ -688,7 +886,10 @@ impl RuleConfig {
                 condition: condition.clone(),
                 action: action.clone(),
             },
-            RuleConfig::Custom { code } => Rule::Custom(code.clone()),
+            RuleConfig::Custom { code } => Rule::Custom {
+                wgsl: code.clone(),
+                helpers: self.shader_helpers(),
+            },
             RuleConfig::NeighborCustom { code } => Rule::NeighborCustom(code.clone()),
             RuleConfig::OnCollision { radius, response } => Rule::OnCollision {
                 radius: *radius,
@@ -837,7 +1038,7 @@ impl RuleConfig {
                 } else {
                     format!("if ({}) {{\n    {}\n}}", condition, then_code)
                 };
-                Rule::Custom(code)
+                Rule::custom(code)
             },
             RuleConfig::TypedNeighbor { self_type, other_type, radius, code } => {
                 let type_check = match (self_type, other_type) {
@@ -852,6 +1053,7 @@ impl RuleConfig {
                 );
                 Rule::NeighborCustom(full_code)
             },
+            RuleConfig::TypedMulti(matrix) => Rule::TypedMulti(matrix.clone()),
             // Advanced Physics
             RuleConfig::DensityBuoyancy { density_field, medium_density, strength } => Rule::DensityBuoyancy {
                 density_field: density_field.clone(),
@@ -933,6 +1135,11 @@ impl RuleConfig {
                 strength: *strength,
                 ascending: *ascending,
             },
+            RuleConfig::FieldGradient { field_index, strength, signed } => Rule::FieldGradient {
+                field_index: *field_index,
+                strength: *strength,
+                signed: *signed,
+            },
             // Neighbor Field Operations
             RuleConfig::Accumulate { source, target, radius, operation, falloff } => Rule::Accumulate {
                 source: source.clone(),
@@ -1050,10 +1257,148 @@ impl RuleConfig {
                         UniformValueConfig::Vec2(arr) => rdpe::UniformValue::Vec2(glam::Vec2::from_array(*arr)),
                         UniformValueConfig::Vec3(arr) => rdpe::UniformValue::Vec3(glam::Vec3::from_array(*arr)),
                         UniformValueConfig::Vec4(arr) => rdpe::UniformValue::Vec4(glam::Vec4::from_array(*arr)),
+                        UniformValueConfig::I32(i) => rdpe::UniformValue::I32(*i),
+                        UniformValueConfig::U32(u) => rdpe::UniformValue::U32(*u),
+                        UniformValueConfig::AnimatedF32 { keyframes } => {
+                            rdpe::UniformValue::F32(super::evaluate_keyframes(keyframes, 0.0))
+                        }
                     };
                     (k.clone(), uv)
                 }).collect(),
             },
+            RuleConfig::Disabled(rule) => rule.to_rule(),
+        }
+    }
+
+    /// Noise preview settings, if this is a `NoiseField` rule with preview enabled.
+    pub fn noise_field_preview(&self, bounds: f32) -> Option<NoiseFieldPreview> {
+        match self {
+            RuleConfig::NoiseField { frequency, octaves, time_scale, preview: true, .. } => {
+                Some(NoiseFieldPreview {
+                    frequency: *frequency,
+                    octaves: *octaves,
+                    time_scale: *time_scale,
+                    bounds,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Particle types this rule reads or filters on.
+    pub fn referenced_types(&self) -> Vec<u32> {
+        match self {
+            RuleConfig::Boid { self_type, .. } => self_type.iter().copied().collect(),
+            RuleConfig::Collide { self_type, target_type, .. } => {
+                self_type.iter().chain(target_type).copied().collect()
+            }
+            RuleConfig::Chase { self_type, target_type, .. } => vec![*self_type, *target_type],
+            RuleConfig::Evade { self_type, threat_type, .. } => vec![*self_type, *threat_type],
+            RuleConfig::Convert { from_type, trigger_type, .. } => vec![*from_type, *trigger_type],
+            RuleConfig::DLA { seed_type, mobile_type, .. } => vec![*seed_type, *mobile_type],
+            RuleConfig::TypedNeighbor { self_type, other_type, .. } => {
+                self_type.iter().chain(other_type).copied().collect()
+            }
+            RuleConfig::Absorb { target_type, .. } => target_type.iter().copied().collect(),
+            RuleConfig::Disabled(rule) => rule.referenced_types(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Particle types this rule can turn particles into or spawn.
+    pub fn produced_types(&self) -> Vec<u32> {
+        match self {
+            RuleConfig::Convert { to_type, .. } => vec![*to_type],
+            RuleConfig::Split { offspring_type, .. } => offspring_type.iter().copied().collect(),
+            RuleConfig::Disabled(rule) => rule.produced_types(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// WGSL the user wrote for this rule, if it takes any.
+    pub fn custom_code(&self) -> Option<&str> {
+        match self {
+            RuleConfig::Custom { code }
+            | RuleConfig::NeighborCustom { code }
+            | RuleConfig::CustomDynamic { code, .. }
+            | RuleConfig::NeighborCustomDynamic { code, .. } => Some(code),
+            RuleConfig::OnCollision { response, .. } => Some(response),
+            RuleConfig::OnCondition { action, .. }
+            | RuleConfig::OnDeath { action }
+            | RuleConfig::OnInterval { action, .. }
+            | RuleConfig::OnSpawn { action } => Some(action),
+            _ => None,
+        }
+    }
+
+    /// Helper libraries called from a Custom rule's code.
+    ///
+    /// The editor has no helper picker; a library is included whenever the
+    /// code calls one of its functions.
+    pub fn shader_helpers(&self) -> Vec<rdpe::ShaderHelper> {
+        let RuleConfig::Custom { code } = self else {
+            return Vec::new();
+        };
+        rdpe::ShaderHelper::ALL
+            .into_iter()
+            .filter(|helper| {
+                helper.functions().iter().any(|signature| {
+                    let name = signature.split('(').next().unwrap_or(signature);
+                    code.contains(&format!("{}(", name))
+                })
+            })
+            .collect()
+    }
+
+    /// Names of the particle fields this rule reads or writes.
+    ///
+    /// Only covers parameters that name a field directly; WGSL code and
+    /// condition expressions are not inspected.
+    pub fn particle_field_refs(&self) -> Vec<&str> {
+        match self {
+            RuleConfig::Magnetic { charge_field, .. } => vec![charge_field],
+            RuleConfig::MaxAge { scale_field, .. } => vec![scale_field],
+            RuleConfig::Decay { field, .. }
+            | RuleConfig::State { field, .. }
+            | RuleConfig::Diffuse { field, .. }
+            | RuleConfig::Mass { field }
+            | RuleConfig::Current { field, .. }
+            | RuleConfig::Lerp { field, .. }
+            | RuleConfig::Clamp { field, .. }
+            | RuleConfig::Remap { field, .. }
+            | RuleConfig::Quantize { field, .. }
+            | RuleConfig::Noise { field, .. }
+            | RuleConfig::Smooth { field, .. }
+            | RuleConfig::Modulo { field, .. } => vec![field],
+            RuleConfig::DensityBuoyancy { density_field, .. } => vec![density_field],
+            RuleConfig::Refractory { trigger, charge, .. } => vec![trigger, charge],
+            RuleConfig::Agent { state_field, prev_state_field, state_timer_field, .. } => {
+                let mut fields = vec![state_field.as_str(), prev_state_field];
+                fields.extend(state_timer_field.as_deref());
+                fields
+            }
+            RuleConfig::CopyField { from, to } | RuleConfig::Copy { from, to, .. } => vec![from, to],
+            RuleConfig::Threshold { input_field, output_field, .. } => vec![input_field, output_field],
+            RuleConfig::Tween { field, timer_field, .. } => vec![field, timer_field],
+            RuleConfig::Periodic { phase_field, .. } => phase_field.as_deref().into_iter().collect(),
+            RuleConfig::Deposit { source, .. } => vec![source],
+            RuleConfig::Sense { target, .. } | RuleConfig::Consume { target, .. } => vec![target],
+            RuleConfig::Accumulate { source, target, .. } | RuleConfig::Signal { source, target, .. } => {
+                vec![source, target]
+            }
+            RuleConfig::Absorb { source_field, target_field, .. } => vec![source_field, target_field],
+            RuleConfig::And { a, b, output } | RuleConfig::Or { a, b, output } | RuleConfig::Xor { a, b, output } => {
+                vec![a, b, output]
+            }
+            RuleConfig::Not { input, output, .. } | RuleConfig::Hysteresis { input, output, .. } => vec![input, output],
+            RuleConfig::Latch { output, .. } => vec![output],
+            RuleConfig::Edge { input, prev_field, output, .. } => vec![input, prev_field, output],
+            RuleConfig::Select { then_field, else_field, output, .. } => vec![then_field, else_field, output],
+            RuleConfig::Blend { a, b, weight, output } => vec![a, b, weight, output],
+            RuleConfig::Sync { phase_field, .. } => vec![phase_field],
+            RuleConfig::Split { resource_field, .. } => resource_field.as_deref().into_iter().collect(),
+            RuleConfig::Disabled(rule) => rule.particle_field_refs(),
+            _ => Vec::new(),
         }
     }
 
@@ -1061,7 +1406,7 @@ impl RuleConfig {
     pub fn requires_neighbors(&self) -> bool {
         matches!(self,
             RuleConfig::Separate { .. } | RuleConfig::Cohere { .. } | RuleConfig::Align { .. } |
-            RuleConfig::Flock { .. } | RuleConfig::Avoid { .. } | RuleConfig::Collide { .. } |
+            RuleConfig::Flock { .. } | RuleConfig::Boid { .. } | RuleConfig::Avoid { .. } | RuleConfig::Collide { .. } |
             RuleConfig::NBodyGravity { .. } | RuleConfig::LennardJones { .. } |
             RuleConfig::Viscosity { .. } | RuleConfig::Pressure { .. } |
             RuleConfig::SurfaceTension { .. } | RuleConfig::Magnetism { .. } |
@@ -1070,7 +1415,8 @@ impl RuleConfig {
             RuleConfig::DLA { .. } | RuleConfig::Diffuse { .. } |
             RuleConfig::NeighborCustomDynamic { .. } |
             RuleConfig::Accumulate { .. } | RuleConfig::Signal { .. } | RuleConfig::Absorb { .. } |
-            RuleConfig::OnCollisionDynamic { .. } | RuleConfig::TypedNeighbor { .. }
+            RuleConfig::OnCollisionDynamic { .. } | RuleConfig::TypedNeighbor { .. } |
+            RuleConfig::TypedMulti(_)
         )
     }
 
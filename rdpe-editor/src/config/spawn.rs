@@ -12,6 +12,10 @@ pub enum SpawnShape {
     Point,
     Line { length: f32 },
     Plane { width: f32, depth: f32 },
+    /// Spread evenly over a triangle mesh's surface, pushed `offset` along the surface normal
+    MeshSurface { mesh: rdpe::MeshData, offset: f32 },
+    /// Evenly spaced on the XZ plane within the bounds, no two closer than `min_distance`
+    PoissonDisc { min_distance: f32, max_attempts: u32 },
 }
 
 impl Default for SpawnShape {
@@ -30,11 +34,13 @@ impl SpawnShape {
             SpawnShape::Point => "Point",
             SpawnShape::Line { .. } => "Line",
             SpawnShape::Plane { .. } => "Plane",
+            SpawnShape::MeshSurface { .. } => "Mesh Surface",
+            SpawnShape::PoissonDisc { .. } => "Poisson Disc",
         }
     }
 
     pub fn variants() -> &'static [&'static str] {
-        &["Cube", "Sphere", "Shell", "Ring", "Point", "Line", "Plane"]
+        &["Cube", "Sphere", "Shell", "Ring", "Point", "Line", "Plane", "Mesh Surface", "Poisson Disc"]
     }
 }
 
@@ -79,7 +85,10 @@ pub enum ColorMode {
     RandomHue { saturation: f32, value: f32 },
     ByPosition,
     ByVelocity,
-    Gradient { start: [f32; 3], end: [f32; 3] },
+    /// Colors blended across particle index; each stop is (RGB, position 0-1), sorted by position
+    Gradient { stops: Vec<([f32; 3], f32)> },
+    /// Hue wheel across particle index: a gradient with six evenly spaced hue stops
+    Rainbow,
 }
 
 impl Default for ColorMode {
@@ -99,11 +108,50 @@ impl ColorMode {
             ColorMode::ByPosition => "By Position",
             ColorMode::ByVelocity => "By Velocity",
             ColorMode::Gradient { .. } => "Gradient",
+            ColorMode::Rainbow => "Rainbow",
         }
     }
 
     pub fn variants() -> &'static [&'static str] {
-        &["Uniform", "Random Hue", "By Position", "By Velocity", "Gradient"]
+        &["Uniform", "Random Hue", "By Position", "By Velocity", "Gradient", "Rainbow"]
+    }
+
+    /// Stops of the index gradient, for `Gradient` and `Rainbow`.
+    pub fn gradient_stops(&self) -> Option<Vec<([f32; 3], f32)>> {
+        match self {
+            ColorMode::Gradient { stops } => Some(stops.clone()),
+            ColorMode::Rainbow => Some(vec![
+                ([1.0, 0.0, 0.0], 0.0),
+                ([1.0, 1.0, 0.0], 0.2),
+                ([0.0, 1.0, 0.0], 0.4),
+                ([0.0, 1.0, 1.0], 0.6),
+                ([0.0, 0.0, 1.0], 0.8),
+                ([1.0, 0.0, 1.0], 1.0),
+            ]),
+            _ => None,
+        }
+    }
+}
+
+/// Color of a gradient at `t`, blending linearly between the stops around it.
+///
+/// `stops` are normally sorted by position; unsorted ones are sampled from a
+/// sorted copy. Before the first stop and after the last the end colors
+/// hold; no stops gives white.
+pub fn sample_gradient(stops: &[([f32; 3], f32)], t: f32) -> [f32; 3] {
+    if !stops.is_sorted_by(|a, b| a.1 <= b.1) {
+        let mut sorted = stops.to_vec();
+        sorted.sort_by(|a, b| a.1.total_cmp(&b.1));
+        return sample_gradient(&sorted, t);
+    }
+    let next = stops.partition_point(|&(_, position)| position <= t);
+    match (next.checked_sub(1).map(|i| stops[i]), stops.get(next)) {
+        (Some((a, ta)), Some(&(b, tb))) => {
+            let f = if tb > ta { (t - ta) / (tb - ta) } else { 0.0 };
+            [a[0] + (b[0] - a[0]) * f, a[1] + (b[1] - a[1]) * f, a[2] + (b[2] - a[2]) * f]
+        }
+        (Some((color, _)), None) | (None, Some(&(color, _))) => color,
+        (None, None) => [1.0; 3],
     }
 }
 
@@ -135,3 +183,52 @@ impl Default for SpawnConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: [f32; 3] = [1.0, 0.0, 0.0];
+    const BLUE: [f32; 3] = [0.0, 0.0, 1.0];
+    const GREEN: [f32; 3] = [0.0, 1.0, 0.0];
+
+    #[test]
+    fn test_sample_gradient_no_stops() {
+        assert_eq!(sample_gradient(&[], 0.5), [1.0; 3]);
+    }
+
+    #[test]
+    fn test_sample_gradient_one_stop() {
+        for t in [-1.0, 0.0, 0.3, 1.0, 2.0] {
+            assert_eq!(sample_gradient(&[(GREEN, 0.3)], t), GREEN);
+        }
+    }
+
+    #[test]
+    fn test_sample_gradient_many_stops() {
+        let stops = [(RED, 0.0), (GREEN, 0.5), (BLUE, 1.0)];
+        assert_eq!(sample_gradient(&stops, 0.0), RED);
+        assert_eq!(sample_gradient(&stops, 0.25), [0.5, 0.5, 0.0]);
+        assert_eq!(sample_gradient(&stops, 0.5), GREEN);
+        assert_eq!(sample_gradient(&stops, 0.75), [0.0, 0.5, 0.5]);
+        assert_eq!(sample_gradient(&stops, 1.0), BLUE);
+    }
+
+    #[test]
+    fn test_sample_gradient_clamps_outside_range() {
+        let stops = [(RED, 0.2), (BLUE, 0.8)];
+        assert_eq!(sample_gradient(&stops, -0.5), RED);
+        assert_eq!(sample_gradient(&stops, 0.1), RED);
+        assert_eq!(sample_gradient(&stops, 0.9), BLUE);
+        assert_eq!(sample_gradient(&stops, 1.5), BLUE);
+    }
+
+    #[test]
+    fn test_sample_gradient_unsorted_stops() {
+        let sorted = [(RED, 0.0), (GREEN, 0.5), (BLUE, 1.0)];
+        let unsorted = [(BLUE, 1.0), (RED, 0.0), (GREEN, 0.5)];
+        for t in [-0.1, 0.0, 0.25, 0.5, 0.6, 1.0, 1.1] {
+            assert_eq!(sample_gradient(&unsorted, t), sample_gradient(&sorted, t), "t = {t}");
+        }
+    }
+}
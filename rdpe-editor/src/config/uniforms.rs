@@ -12,18 +12,41 @@ pub enum UniformValueConfig {
     Vec2([f32; 2]),
     Vec3([f32; 3]),
     Vec4([f32; 4]),
+    I32(i32),
+    U32(u32),
+    /// `f32` that follows a curve through `(time, value)` keyframes, sorted by time
+    AnimatedF32 { keyframes: Vec<(f32, f32)> },
 }
 
 impl UniformValueConfig {
     pub fn wgsl_type(&self) -> &'static str {
         match self {
-            UniformValueConfig::F32(_) => "f32",
+            UniformValueConfig::F32(_) | UniformValueConfig::AnimatedF32 { .. } => "f32",
             UniformValueConfig::Vec2(_) => "vec2<f32>",
             UniformValueConfig::Vec3(_) => "vec3<f32>",
             UniformValueConfig::Vec4(_) => "vec4<f32>",
+            UniformValueConfig::I32(_) => "i32",
+            UniformValueConfig::U32(_) => "u32",
         }
     }
 
+    /// Sort an animated curve's keyframes by time (no-op for other values).
+    pub fn sort_keyframes(&mut self) {
+        if let UniformValueConfig::AnimatedF32 { keyframes } = self {
+            sort_keyframes(keyframes);
+        }
+    }
+
+    /// The value at `time` seconds, with animated curves evaluated to a plain `F32`.
+    pub fn at_time(&self, time: f32) -> UniformValueConfig {
+        match self {
+            UniformValueConfig::AnimatedF32 { keyframes } => UniformValueConfig::F32(evaluate_keyframes(keyframes, time)),
+            other => other.clone(),
+        }
+    }
+
+    /// Bytes for GPU upload. Animated values are written as of time zero;
+    /// use [`at_time`](Self::at_time) first to upload the current value.
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut bytes = Vec::new();
         match self {
@@ -43,6 +66,11 @@ impl UniformValueConfig {
                 bytes.extend_from_slice(&v[2].to_le_bytes());
                 bytes.extend_from_slice(&v[3].to_le_bytes());
             }
+            UniformValueConfig::I32(v) => bytes.extend_from_slice(&v.to_le_bytes()),
+            UniformValueConfig::U32(v) => bytes.extend_from_slice(&v.to_le_bytes()),
+            UniformValueConfig::AnimatedF32 { keyframes } => {
+                bytes.extend_from_slice(&evaluate_keyframes(keyframes, 0.0).to_le_bytes())
+            }
         }
         bytes
     }
@@ -53,6 +81,8 @@ impl UniformValueConfig {
             UniformValueConfig::Vec2(_) => 8,
             UniformValueConfig::Vec3(_) => 12,
             UniformValueConfig::Vec4(_) => 16,
+            UniformValueConfig::I32(_) | UniformValueConfig::U32(_) => 4,
+            UniformValueConfig::AnimatedF32 { .. } => 4,
         }
     }
 
@@ -62,6 +92,111 @@ impl UniformValueConfig {
             UniformValueConfig::Vec2(_) => 8,
             UniformValueConfig::Vec3(_) => 16, // vec3 aligns to 16 in std140
             UniformValueConfig::Vec4(_) => 16,
+            UniformValueConfig::I32(_) | UniformValueConfig::U32(_) => 4,
+            UniformValueConfig::AnimatedF32 { .. } => 4,
+        }
+    }
+}
+
+/// Sort `(time, value)` keyframes by time, keeping the order of equal times.
+pub fn sort_keyframes(keyframes: &mut [(f32, f32)]) {
+    keyframes.sort_by(|a, b| a.0.total_cmp(&b.0));
+}
+
+/// Evaluate a keyframe curve at `time` with cubic Hermite interpolation.
+///
+/// Keyframes are `(time, value)` pairs, normally sorted by time; unsorted
+/// ones are evaluated from a sorted copy. Tangents follow the
+/// neighboring keyframes (Catmull-Rom style), so the curve passes through
+/// every keyframe smoothly. Before the first and after the last keyframe the
+/// curve holds that keyframe's value; with no keyframes it is `0.0`.
+pub fn evaluate_keyframes(keyframes: &[(f32, f32)], time: f32) -> f32 {
+    let (Some(&(first_t, first_v)), Some(&(last_t, last_v))) = (keyframes.first(), keyframes.last()) else {
+        return 0.0;
+    };
+    if !keyframes.is_sorted_by(|a, b| a.0 <= b.0) {
+        let mut sorted = keyframes.to_vec();
+        sort_keyframes(&mut sorted);
+        return evaluate_keyframes(&sorted, time);
+    }
+    if time <= first_t {
+        return first_v;
+    }
+    if time >= last_t {
+        return last_v;
+    }
+
+    let i = keyframes.partition_point(|&(t, _)| t <= time).saturating_sub(1).min(keyframes.len() - 2);
+    let (t0, v0) = keyframes[i];
+    let (t1, v1) = keyframes[i + 1];
+    let span = t1 - t0;
+    if span <= 0.0 {
+        return v1;
+    }
+
+    // Slope at keyframe `k` from its neighbors, one-sided at the ends
+    let slope = |k: usize| {
+        let (ta, va) = keyframes[k.saturating_sub(1)];
+        let (tb, vb) = keyframes[(k + 1).min(keyframes.len() - 1)];
+        if tb > ta { (vb - va) / (tb - ta) } else { 0.0 }
+    };
+    let (m0, m1) = (slope(i) * span, slope(i + 1) * span);
+
+    let s = (time - t0) / span;
+    let (s2, s3) = (s * s, s * s * s);
+    (2.0 * s3 - 3.0 * s2 + 1.0) * v0 + (s3 - 2.0 * s2 + s) * m0 + (-2.0 * s3 + 3.0 * s2) * v1 + (s3 - s2) * m1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyframes_empty_and_single() {
+        assert_eq!(evaluate_keyframes(&[], 1.0), 0.0);
+        for time in [-1.0, 0.0, 2.0, 10.0] {
+            assert_eq!(evaluate_keyframes(&[(2.0, 0.7)], time), 0.7);
         }
     }
+
+    #[test]
+    fn test_keyframes_endpoints() {
+        let keys = [(1.0, 2.0), (2.0, 5.0), (4.0, -1.0)];
+        // Held before the first and after the last keyframe
+        assert_eq!(evaluate_keyframes(&keys, 0.0), 2.0);
+        assert_eq!(evaluate_keyframes(&keys, 1.0), 2.0);
+        assert_eq!(evaluate_keyframes(&keys, 4.0), -1.0);
+        assert_eq!(evaluate_keyframes(&keys, 9.0), -1.0);
+        // Passes through the middle keyframe
+        assert!((evaluate_keyframes(&keys, 2.0) - 5.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_keyframes_between_keys() {
+        // Two keys: tangents equal the chord, so the curve is the straight line
+        let keys = [(0.0, 0.0), (2.0, 4.0)];
+        for (time, expected) in [(0.5, 1.0), (1.0, 2.0), (1.5, 3.0)] {
+            assert!((evaluate_keyframes(&keys, time) - expected).abs() < 1e-5, "{time}");
+        }
+
+        // A peak: values rise towards it and stay below it on both sides
+        let keys = [(0.0, 0.0), (1.0, 1.0), (2.0, 0.0)];
+        let rising = evaluate_keyframes(&keys, 0.5);
+        let falling = evaluate_keyframes(&keys, 1.5);
+        assert!(rising > 0.0 && rising < 1.0);
+        assert!((rising - falling).abs() < 1e-6, "symmetric curve");
+    }
+
+    #[test]
+    fn test_keyframes_unsorted() {
+        let sorted = [(0.0, 0.0), (1.0, 1.0), (3.0, 0.5)];
+        let unsorted = [(3.0, 0.5), (0.0, 0.0), (1.0, 1.0)];
+        for time in [-1.0, 0.0, 0.5, 1.0, 2.0, 3.0, 4.0] {
+            assert_eq!(evaluate_keyframes(&unsorted, time), evaluate_keyframes(&sorted, time), "{time}");
+        }
+
+        let mut value = UniformValueConfig::AnimatedF32 { keyframes: unsorted.to_vec() };
+        value.sort_keyframes();
+        assert_eq!(value, UniformValueConfig::AnimatedF32 { keyframes: sorted.to_vec() });
+    }
 }
@@ -0,0 +1,240 @@
+//! Semantic checks on a whole configuration.
+//!
+//! These catch mistakes that deserialize fine but would generate a broken
+//! or misleading simulation, such as rules naming fields that don't exist.
+
+use std::fmt;
+
+use super::{RuleConfig, SimConfig};
+
+/// Names already used by a generated `Uniforms` struct.
+///
+/// Covers the editor's shaders and, since exported code runs on the core
+/// library, the core `Uniforms` fields as well.
+const BUILTIN_UNIFORMS: &[&str] = &[
+    "view_proj", "time", "delta_time", "particle_count", "bounds", "particle_size", "mouse",
+    "seed_lo", "seed_hi", "_pad0", "_pad1",
+];
+
+/// A problem found by [`SimConfig::validate`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigError {
+    /// The spatial grid resolution must be a power of two.
+    SpatialResolutionNotPowerOfTwo(u32),
+    /// A rule uses a particle type that is never spawned or produced.
+    RuleReferencesUnknownType { rule: String, type_id: u32, max_type_id: u32 },
+    /// A rule names a particle field that the layout doesn't have.
+    UnknownCustomField { rule: String, field_name: String },
+    /// A rule samples a spatial field index past the defined fields.
+    RuleReferencesUnknownField { rule: String, field_index: usize, field_count: usize },
+    /// A custom uniform has the same name as a built-in uniform.
+    UniformNameConflictsWithBuiltin(String),
+    /// More than one enabled rule carries an interaction matrix.
+    MultipleInteractionMatrices(usize),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::SpatialResolutionNotPowerOfTwo(resolution) => {
+                write!(f, "Spatial resolution {} is not a power of two", resolution)
+            }
+            ConfigError::RuleReferencesUnknownType { rule, type_id, max_type_id } => write!(
+                f,
+                "{} rule uses particle type {}, but only types 0-{} are spawned or produced",
+                rule, type_id, max_type_id
+            ),
+            ConfigError::UnknownCustomField { rule, field_name } => write!(
+                f,
+                "{} rule uses particle field '{}', which is not defined",
+                rule, field_name
+            ),
+            ConfigError::RuleReferencesUnknownField { rule, field_index, field_count: 0 } => write!(
+                f,
+                "{} rule samples field {}, but no fields are defined. Add one in the Fields tab",
+                rule, field_index
+            ),
+            ConfigError::RuleReferencesUnknownField { rule, field_index, field_count } => write!(
+                f,
+                "{} rule samples field {}, but only {} field(s) are defined",
+                rule, field_index, field_count
+            ),
+            ConfigError::UniformNameConflictsWithBuiltin(name) => {
+                write!(f, "Custom uniform '{}' conflicts with a built-in uniform", name)
+            }
+            ConfigError::MultipleInteractionMatrices(count) => write!(
+                f,
+                "{} Typed Multi rules are enabled, but a simulation can only use one interaction matrix",
+                count
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl SimConfig {
+    /// Check the configuration for semantic errors.
+    ///
+    /// Disabled rules are skipped, and so are checks that custom WGSL could
+    /// satisfy at runtime: particle types are not checked once custom code
+    /// assigns `particle_type`. Returns every problem found, in a stable
+    /// order; an empty list means the config is fine.
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut errors = Vec::new();
+
+        if self.needs_spatial() && !self.spatial_resolution.is_power_of_two() {
+            errors.push(ConfigError::SpatialResolutionNotPowerOfTwo(self.spatial_resolution));
+        }
+
+        // Types exist if they are spawned or some rule can create them
+        let spawned_max = self.spawn.type_weights.len().max(1) as u32 - 1;
+        let max_type_id = self
+            .active_rules()
+            .flat_map(|r| r.produced_types())
+            .fold(spawned_max, u32::max);
+        let custom_types = self
+            .active_rules()
+            .filter_map(|r| r.custom_code())
+            .any(assigns_particle_type);
+        let layout = self.particle_layout();
+        for rule in self.active_rules() {
+            for type_id in rule.referenced_types() {
+                if type_id > max_type_id && !custom_types {
+                    errors.push(ConfigError::RuleReferencesUnknownType {
+                        rule: rule.name().to_string(),
+                        type_id,
+                        max_type_id,
+                    });
+                }
+            }
+            for field_name in rule.particle_field_refs() {
+                // Allow component access such as `position.x`
+                let root = field_name.split('.').next().unwrap_or(field_name);
+                if layout.field_info(root).is_none() {
+                    errors.push(ConfigError::UnknownCustomField {
+                        rule: rule.name().to_string(),
+                        field_name: field_name.to_string(),
+                    });
+                }
+            }
+            for field_index in rule.to_rule().required_fields() {
+                if field_index >= self.fields.len() {
+                    errors.push(ConfigError::RuleReferencesUnknownField {
+                        rule: rule.name().to_string(),
+                        field_index,
+                        field_count: self.fields.len(),
+                    });
+                }
+            }
+        }
+
+        let matrices = self
+            .active_rules()
+            .filter(|r| matches!(r, RuleConfig::TypedMulti(_)))
+            .count();
+        if matrices > 1 {
+            errors.push(ConfigError::MultipleInteractionMatrices(matrices));
+        }
+
+        let mut uniform_names: Vec<&String> = self.custom_uniforms.keys().collect();
+        uniform_names.sort();
+        for name in uniform_names {
+            if BUILTIN_UNIFORMS.contains(&name.as_str()) {
+                errors.push(ConfigError::UniformNameConflictsWithBuiltin(name.clone()));
+            }
+        }
+
+        errors
+    }
+}
+
+/// Whether WGSL `code` assigns `particle_type`, so any type may exist at runtime.
+fn assigns_particle_type(code: &str) -> bool {
+    code.match_indices("particle_type").any(|(pos, name)| {
+        let rest = code[pos + name.len()..].trim_start();
+        rest.starts_with('=') && !rest.starts_with("==")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::UniformValueConfig;
+
+    #[test]
+    fn test_builtin_uniform_names_conflict() {
+        // The list must match the fields the editor and core shaders write
+        let field_names = |fields: &str| -> Vec<String> {
+            fields.lines().filter_map(|l| l.trim().split_once(':')).map(|(n, _)| n.to_string()).collect()
+        };
+        let shader = crate::shader_gen::generate_compute_shader(&SimConfig::default(), 256);
+        let (_, uniforms) = shader.split_once("struct Uniforms {").unwrap();
+        let (uniforms, _) = uniforms.split_once('}').unwrap();
+        let mut fields = field_names(uniforms);
+        fields.extend(field_names(rdpe::UNIFORM_FIELDS_WGSL));
+        fields.sort();
+        fields.dedup();
+        let mut expected: Vec<String> = BUILTIN_UNIFORMS.iter().map(|n| n.to_string()).collect();
+        expected.sort();
+        assert_eq!(fields, expected);
+
+        for name in BUILTIN_UNIFORMS {
+            let mut config = SimConfig::default();
+            config.custom_uniforms.insert(name.to_string(), UniformValueConfig::F32(1.0));
+            assert_eq!(config.validate(), vec![ConfigError::UniformNameConflictsWithBuiltin(name.to_string())]);
+        }
+    }
+
+    #[test]
+    fn test_unknown_type_unless_custom_code_sets_types() {
+        let chase = RuleConfig::Chase { self_type: 0, target_type: 2, radius: 0.1, strength: 1.0 };
+        let mut config = SimConfig { rules: vec![chase], ..Default::default() };
+        assert_eq!(
+            config.validate(),
+            vec![ConfigError::RuleReferencesUnknownType { rule: "Chase".to_string(), type_id: 2, max_type_id: 0 }]
+        );
+
+        // Comparing types doesn't create any
+        config.rules.push(RuleConfig::Custom { code: "if p.particle_type == 2u { p.velocity = vec3(0.0); }".into() });
+        assert_eq!(config.validate().len(), 1);
+
+        // Custom code can turn particles into type 2
+        config.rules.push(RuleConfig::Custom { code: "if p.age > 1.0 { p.particle_type = 2u; }".into() });
+        assert_eq!(config.validate(), vec![]);
+
+        config.rules[2].set_enabled(false);
+        assert_eq!(config.validate().len(), 1);
+    }
+
+    #[test]
+    fn test_field_gradient_needs_field() {
+        let gradient = RuleConfig::FieldGradient { field_index: 1, strength: 1.0, signed: false };
+        let mut config = SimConfig { rules: vec![gradient], ..Default::default() };
+        let error = ConfigError::RuleReferencesUnknownField {
+            rule: "Field Gradient".to_string(),
+            field_index: 1,
+            field_count: 0,
+        };
+        assert_eq!(config.validate(), vec![error]);
+        assert!(config.validate()[0].to_string().contains("no fields are defined"));
+
+        config.rules[0].set_enabled(false);
+        assert_eq!(config.validate(), vec![]);
+    }
+
+    #[test]
+    fn test_one_interaction_matrix() {
+        let mut config = SimConfig {
+            rules: vec![RuleConfig::TypedMulti(rdpe::InteractionMatrix::new(1))],
+            ..Default::default()
+        };
+        assert_eq!(config.validate(), vec![]);
+
+        config.rules.push(RuleConfig::TypedMulti(rdpe::InteractionMatrix::new(1)));
+        assert_eq!(config.validate(), vec![ConfigError::MultipleInteractionMatrices(2)]);
+
+        config.rules[1].set_enabled(false);
+        assert_eq!(config.validate(), vec![]);
+    }
+}
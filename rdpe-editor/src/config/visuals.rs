@@ -180,7 +180,7 @@ impl ColorMappingConfig {
 }
 
 /// Wireframe mesh for 3D particle rendering
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
 pub enum WireframeMeshConfig {
     #[default]
     None,
@@ -188,9 +188,28 @@ pub enum WireframeMeshConfig {
     Cube,
     Octahedron,
     Icosahedron,
+    Sphere,
+    Torus,
+    Cylinder,
+    /// Edges of a mesh loaded from an OBJ file
+    Obj(rdpe::MeshData),
 }
 
 impl WireframeMeshConfig {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WireframeMeshConfig::None => "None",
+            WireframeMeshConfig::Tetrahedron => "Tetrahedron",
+            WireframeMeshConfig::Cube => "Cube",
+            WireframeMeshConfig::Octahedron => "Octahedron",
+            WireframeMeshConfig::Icosahedron => "Icosahedron",
+            WireframeMeshConfig::Sphere => "Sphere",
+            WireframeMeshConfig::Torus => "Torus",
+            WireframeMeshConfig::Cylinder => "Cylinder",
+            WireframeMeshConfig::Obj(_) => "OBJ",
+        }
+    }
+
     pub fn to_mesh(&self) -> Option<rdpe::WireframeMesh> {
         match self {
             WireframeMeshConfig::None => None,
@@ -198,6 +217,119 @@ impl WireframeMeshConfig {
             WireframeMeshConfig::Cube => Some(rdpe::WireframeMesh::cube()),
             WireframeMeshConfig::Octahedron => Some(rdpe::WireframeMesh::octahedron()),
             WireframeMeshConfig::Icosahedron => Some(rdpe::WireframeMesh::icosahedron()),
+            WireframeMeshConfig::Sphere => Some(rdpe::WireframeMesh::from_primitive(rdpe::Primitive::Sphere)),
+            WireframeMeshConfig::Torus => Some(rdpe::WireframeMesh::from_primitive(rdpe::Primitive::Torus)),
+            WireframeMeshConfig::Cylinder => Some(rdpe::WireframeMesh::from_primitive(rdpe::Primitive::Cylinder)),
+            WireframeMeshConfig::Obj(mesh) => Some(rdpe::WireframeMesh::from_mesh(mesh)),
+        }
+    }
+}
+
+/// Bloom post-processing settings
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub struct BloomConfig {
+    pub threshold: f32,
+    pub intensity: f32,
+    pub radius: f32,
+    pub iterations: u32,
+}
+
+impl Default for BloomConfig {
+    fn default() -> Self {
+        let rdpe::BloomConfig { threshold, intensity, radius, iterations } = rdpe::BloomConfig::default();
+        Self { threshold, intensity, radius, iterations }
+    }
+}
+
+impl BloomConfig {
+    pub fn to_bloom_config(&self) -> rdpe::BloomConfig {
+        rdpe::BloomConfig {
+            threshold: self.threshold,
+            intensity: self.intensity,
+            radius: self.radius,
+            iterations: self.iterations,
+        }
+    }
+}
+
+/// Directional light with particle shadows
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub struct LightConfig {
+    pub direction: [f32; 3],
+    pub color: [f32; 3],
+    pub shadow_map_size: u32,
+    pub shadow_bias: f32,
+}
+
+impl Default for LightConfig {
+    fn default() -> Self {
+        let rdpe::LightConfig { direction, color, shadow_map_size, shadow_bias } = rdpe::LightConfig::default();
+        Self {
+            direction: direction.to_array(),
+            color: color.to_array(),
+            shadow_map_size,
+            shadow_bias,
+        }
+    }
+}
+
+impl LightConfig {
+    pub fn to_light_config(&self) -> rdpe::LightConfig {
+        rdpe::LightConfig {
+            direction: self.direction.into(),
+            color: self.color.into(),
+            shadow_map_size: self.shadow_map_size,
+            shadow_bias: self.shadow_bias,
+        }
+    }
+}
+
+/// Tone mapping curve applied when HDR is enabled
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum ToneMapModeConfig {
+    Reinhard,
+    AcesFast,
+    #[default]
+    AcesFilmic,
+    Linear,
+}
+
+impl ToneMapModeConfig {
+    pub fn variants() -> &'static [&'static str] {
+        &["Reinhard", "AcesFast", "AcesFilmic", "Linear"]
+    }
+
+    pub fn to_tone_map_mode(&self) -> rdpe::ToneMapMode {
+        match self {
+            ToneMapModeConfig::Reinhard => rdpe::ToneMapMode::Reinhard,
+            ToneMapModeConfig::AcesFast => rdpe::ToneMapMode::AcesFast,
+            ToneMapModeConfig::AcesFilmic => rdpe::ToneMapMode::AcesFilmic,
+            ToneMapModeConfig::Linear => rdpe::ToneMapMode::Linear,
+        }
+    }
+}
+
+/// MSAA sample count for scene rendering
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum MsaaSamplesConfig {
+    #[default]
+    X1,
+    X2,
+    X4,
+    X8,
+}
+
+impl MsaaSamplesConfig {
+    pub fn variants() -> &'static [&'static str] {
+        &["X1", "X2", "X4", "X8"]
+    }
+
+    pub fn to_msaa_samples(&self) -> rdpe::MsaaSamples {
+        match self {
+            MsaaSamplesConfig::X1 => rdpe::MsaaSamples::X1,
+            MsaaSamplesConfig::X2 => rdpe::MsaaSamples::X2,
+            MsaaSamplesConfig::X4 => rdpe::MsaaSamples::X4,
+            MsaaSamplesConfig::X8 => rdpe::MsaaSamples::X8,
         }
     }
 }
@@ -211,17 +343,41 @@ pub struct VisualsConfig {
     pub color_mapping: ColorMappingConfig,
     pub background_color: [f32; 3],
     pub trail_length: u32,
+    /// Color trails fade toward, or `None` for the plain trail color
+    #[serde(default)]
+    pub trail_tail_color: Option<[f32; 3]>,
     pub connections_enabled: bool,
     pub connections_radius: f32,
-    #[serde(default = "default_connections_color")]
-    pub connections_color: [f32; 3],
+    #[serde(default = "default_connections_color", alias = "connections_color")]
+    pub connections_near_color: [f32; 3],
+    /// Color at the full connection radius, or `None` for the near color
+    #[serde(default)]
+    pub connections_far_color: Option<[f32; 3]>,
+    #[serde(default)]
+    pub connections_directed: bool,
+    #[serde(default)]
+    pub connections_flow_speed: f32,
     pub velocity_stretch: bool,
     pub velocity_stretch_factor: f32,
     pub spatial_grid_opacity: f32,
     #[serde(default)]
+    pub axes_visible: bool,
+    #[serde(default)]
     pub wireframe: WireframeMeshConfig,
     #[serde(default = "default_wireframe_thickness")]
     pub wireframe_thickness: f32,
+    #[serde(default)]
+    pub bloom: Option<BloomConfig>,
+    #[serde(default)]
+    pub chromatic_aberration: f32,
+    #[serde(default)]
+    pub hdr: bool,
+    #[serde(default)]
+    pub tone_map: ToneMapModeConfig,
+    #[serde(default)]
+    pub msaa_samples: MsaaSamplesConfig,
+    #[serde(default)]
+    pub light: Option<LightConfig>,
 }
 
 impl Default for VisualsConfig {
@@ -233,14 +389,25 @@ impl Default for VisualsConfig {
             color_mapping: ColorMappingConfig::None,
             background_color: [0.02, 0.02, 0.05],
             trail_length: 0,
+            trail_tail_color: None,
             connections_enabled: false,
             connections_radius: 0.1,
-            connections_color: [0.5, 0.7, 1.0],
+            connections_near_color: [0.5, 0.7, 1.0],
+            connections_far_color: None,
+            connections_directed: false,
+            connections_flow_speed: 0.0,
             velocity_stretch: false,
             velocity_stretch_factor: 2.0,
             spatial_grid_opacity: 0.0,
+            axes_visible: false,
             wireframe: WireframeMeshConfig::None,
             wireframe_thickness: 0.003,
+            bloom: None,
+            chromatic_aberration: 0.0,
+            hdr: false,
+            tone_map: ToneMapModeConfig::AcesFilmic,
+            msaa_samples: MsaaSamplesConfig::X1,
+            light: None,
         }
     }
 }
@@ -267,6 +434,11 @@ pub enum VertexEffectConfig {
     BillboardCylindrical { axis: [f32; 3] },
     BillboardFixed { forward: [f32; 3], up: [f32; 3] },
     FacePoint { target: [f32; 3] },
+    /// WGSL run after the billboard position is computed. Reads
+    /// `particle_position`, `particle_velocity`, `particle_color`,
+    /// `particle_age` and `particle_scale`, and may modify `out_position`
+    /// (the final clip-space position).
+    CustomVertex(String),
 }
 
 impl VertexEffectConfig {
@@ -283,12 +455,15 @@ impl VertexEffectConfig {
             VertexEffectConfig::BillboardCylindrical { .. } => "Billboard Cylindrical",
             VertexEffectConfig::BillboardFixed { .. } => "Billboard Fixed",
             VertexEffectConfig::FacePoint { .. } => "Face Point",
+            VertexEffectConfig::CustomVertex(_) => "Custom Vertex",
         }
     }
 
-    pub fn to_effect(&self) -> rdpe::VertexEffect {
+    /// The matching rdpe effect; `None` for [`VertexEffectConfig::CustomVertex`],
+    /// which only the editor's render shader supports.
+    pub fn to_effect(&self) -> Option<rdpe::VertexEffect> {
         use rdpe::VertexEffect;
-        match self {
+        let effect = match self {
             VertexEffectConfig::Rotate { speed } => VertexEffect::Rotate { speed: *speed },
             VertexEffectConfig::Wobble { frequency, amplitude } => VertexEffect::Wobble {
                 frequency: *frequency,
@@ -330,6 +505,27 @@ impl VertexEffectConfig {
             VertexEffectConfig::FacePoint { target } => VertexEffect::FacePoint {
                 target: Vec3::from_array(*target),
             },
-        }
+            VertexEffectConfig::CustomVertex(_) => return None,
+        };
+        Some(effect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_old_connection_color_keeps_single_color() {
+        // Saved before connections had a distance gradient
+        let mut json = serde_json::to_value(VisualsConfig::default()).unwrap();
+        let object = json.as_object_mut().unwrap();
+        object.remove("connections_near_color");
+        object.remove("connections_far_color");
+        object.insert("connections_color".into(), serde_json::json!([1.0, 0.2, 0.2]));
+
+        let visuals: VisualsConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(visuals.connections_near_color, [1.0, 0.2, 0.2]);
+        assert_eq!(visuals.connections_far_color, None);
     }
 }
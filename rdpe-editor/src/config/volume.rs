@@ -13,10 +13,22 @@ pub struct VolumeRenderConfig {
     pub field_index: u32,
     /// Number of ray march steps (higher = better quality, slower).
     pub steps: u32,
+    /// Distance between ray march samples (0 = derive from field extent).
+    #[serde(default)]
+    pub step_size: f32,
     /// Density multiplier (higher = more opaque).
     pub density_scale: f32,
-    /// Color palette for density mapping.
+    /// Absorption multiplier (opacity only, palette mapping unchanged).
+    #[serde(default = "default_density_multiplier")]
+    pub density_multiplier: f32,
+    /// Color palette for density mapping (used when `transfer_function` is empty).
     pub palette: PaletteConfig,
+    /// Custom color and opacity curve as `(rgba, value)` stops.
+    #[serde(default)]
+    pub transfer_function: Vec<([f32; 4], f32)>,
+    /// Clip planes as `[a, b, c, d]` with `ax + by + cz + d >= 0` kept (up to 4).
+    #[serde(default)]
+    pub clip_planes: Vec<[f32; 4]>,
     /// Minimum density threshold (values below are transparent).
     pub threshold: f32,
     /// Whether to use additive blending (glow effect).
@@ -29,22 +41,63 @@ impl Default for VolumeRenderConfig {
             enabled: false,
             field_index: 0,
             steps: 64,
+            step_size: 0.0,
             density_scale: 5.0,
+            density_multiplier: 1.0,
             palette: PaletteConfig::Inferno,
+            transfer_function: Vec::new(),
+            clip_planes: Vec::new(),
             threshold: 0.01,
             additive: true,
         }
     }
 }
 
+fn default_density_multiplier() -> f32 {
+    1.0
+}
+
 impl VolumeRenderConfig {
+    /// Whether switching from `other` needs a rebuild.
+    ///
+    /// The palette and transfer function only feed the lookup texture,
+    /// which is updated in place, so they are not compared.
+    pub fn needs_rebuild(&self, other: &Self) -> bool {
+        let Self {
+            enabled,
+            field_index,
+            steps,
+            step_size,
+            density_scale,
+            density_multiplier,
+            palette: _,
+            transfer_function: _,
+            clip_planes,
+            threshold,
+            additive,
+        } = self;
+        *enabled != other.enabled
+            || *field_index != other.field_index
+            || *steps != other.steps
+            || *step_size != other.step_size
+            || *density_scale != other.density_scale
+            || *density_multiplier != other.density_multiplier
+            || *clip_planes != other.clip_planes
+            || *threshold != other.threshold
+            || *additive != other.additive
+    }
+
     /// Convert to rdpe::VolumeConfig.
     pub fn to_volume_config(&self) -> rdpe::VolumeConfig {
         rdpe::VolumeConfig {
             field_index: self.field_index,
-            steps: self.steps,
+            step_count: self.steps,
+            step_size: self.step_size,
             density_scale: self.density_scale,
+            density_multiplier: self.density_multiplier,
             palette: self.palette.to_palette(),
+            transfer_function: self.transfer_function.clone(),
+            clip_planes: self.clip_planes.clone(),
             threshold: self.threshold,
             additive: self.additive,
         }
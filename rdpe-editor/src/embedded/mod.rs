@@ -19,12 +19,12 @@ pub use picking::{PickingState, PickingRequest};
 use bytemuck::{Pod, Zeroable};
 use glam::{Mat4, Vec3};
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use wgpu::util::DeviceExt;
-use crate::config::{BlendModeConfig, UniformValueConfig, ParticleLayout, MouseConfig, VolumeRenderConfig};
-use rdpe::{FieldSystemGpu, VolumeRenderState, create_particle_field_bind_group_layout, SpatialGpu, SpatialConfig};
-use visualizations::{GridVisualization, ConnectionVisualization, WireframeVisualization, TrailVisualization};
-
-const WORKGROUP_SIZE: u32 = 256;
+use crate::config::{BlendModeConfig, UniformValueConfig, ParticleLayout, MouseConfig, NoiseFieldPreview, VolumeRenderConfig};
+use crate::shader_validate::{lint, LintWarning};
+use rdpe::{FieldSystemGpu, VolumeRenderState, PostProcessState, create_particle_field_bind_group_layout, SpatialGpu, SpatialConfig, FrameTimings, GpuProfiler, ProfiledPass};
+use visualizations::{GridVisualization, ConnectionVisualization, WireframeVisualization, TrailVisualization, AxesVisualization, NoiseFieldVisualization};
 
 /// Base uniforms passed to shaders (fixed layout).
 #[repr(C)]
@@ -33,7 +33,11 @@ struct BaseUniforms {
     view_proj: [[f32; 4]; 4],
     time: f32,
     delta_time: f32,
-    _padding: [f32; 2],
+    particle_count: u32,
+    bounds: f32,
+    /// Base billboard and wireframe size, before per-particle scale
+    particle_size: f32,
+    _padding: [f32; 3],
 }
 
 const BASE_UNIFORMS_SIZE: usize = std::mem::size_of::<BaseUniforms>();
@@ -66,10 +70,14 @@ pub struct MouseState {
 }
 
 /// Build uniform buffer data with base uniforms, mouse uniforms, and custom values.
+#[allow(clippy::too_many_arguments)]
 fn build_uniform_data(
     view_proj: Mat4,
     time: f32,
     delta_time: f32,
+    num_particles: u32,
+    bounds: f32,
+    particle_size: f32,
     mouse_state: &MouseState,
     mouse_config: &MouseConfig,
     custom_uniforms: &[(String, UniformValueConfig)],
@@ -81,7 +89,10 @@ fn build_uniform_data(
         view_proj: view_proj.to_cols_array_2d(),
         time,
         delta_time,
-        _padding: [0.0; 2],
+        particle_count: num_particles,
+        bounds,
+        particle_size,
+        _padding: [0.0; 3],
     };
     data.extend_from_slice(bytemuck::bytes_of(&base));
 
@@ -118,22 +129,49 @@ fn build_uniform_data(
     data
 }
 
+/// Where a `map_async` callback leaves its result.
+///
+/// A Mutex rather than a channel so the resources stay `Sync` for egui's callback storage.
+type MapResultSlot = Arc<Mutex<Option<Result<(), wgpu::BufferAsyncError>>>>;
+
 pub struct SimulationResources {
     // Pipelines
     compute_pipeline: wgpu::ComputePipeline,
     render_pipeline: wgpu::RenderPipeline,
 
+    // Kept so the render pipeline can be rebuilt with another blend mode
+    render_shader: wgpu::ShaderModule,
+    render_pipeline_layout: wgpu::PipelineLayout,
+    particle_layout: ParticleLayout,
+    // Format of the scene pipelines: egui's target, or HDR when tone mapping
+    scene_format: wgpu::TextureFormat,
+
     // Buffers
     particle_buffer: wgpu::Buffer,
     uniform_buffer: wgpu::Buffer,
     _uniform_buffer_size: usize,
 
+    // Alive particle counter and its non-blocking readback
+    alive_count_buffer: wgpu::Buffer,
+    alive_count_staging: wgpu::Buffer,
+    alive_count_copied: bool,
+    alive_count_map: Option<MapResultSlot>,
+    alive_count: Option<u32>,
+
+    // Per-pass timestamp profiling (None if the device lacks TIMESTAMP_QUERY)
+    profiler: Option<GpuProfiler>,
+
     // Bind groups
     compute_bind_group: wgpu::BindGroup,
     render_bind_group: wgpu::BindGroup,
 
     // Configuration
+    /// Number of active particles (dispatched and drawn).
     pub num_particles: u32,
+    /// Number of particles the particle buffer can hold.
+    buffer_capacity: u32,
+    /// Compute workgroup size the shader was generated with.
+    workgroup_size: u32,
     pub particle_stride: usize,
     background_color: Vec3,
 
@@ -148,6 +186,8 @@ pub struct SimulationResources {
     camera_distance: f32,
     camera_yaw: f32,
     camera_pitch: f32,
+    /// Point the camera orbits around and looks at
+    orbit_center: Vec3,
 
     // Cached camera info for volume rendering
     last_inv_view_proj: Mat4,
@@ -171,6 +211,12 @@ pub struct SimulationResources {
     // Spatial grid visualization (debug overlay)
     grid_viz: Option<GridVisualization>,
 
+    // Coordinate axes (orientation overlay)
+    axes: AxesVisualization,
+
+    // NoiseField rule preview (debug overlay)
+    noise_preview: Option<NoiseFieldVisualization>,
+
     // Connection visualization
     connections: Option<ConnectionVisualization>,
 
@@ -180,9 +226,21 @@ pub struct SimulationResources {
     // Trail visualization
     trails: Option<TrailVisualization>,
 
+    // Custom post-process chain, bloom and tone mapping: the scene renders offscreen, the last pass draws it into egui's target
+    post_process: Option<PostProcessState>,
+    post_process_size: (u32, u32),
+
     // Mouse interaction
     mouse_state: MouseState,
     mouse_config: MouseConfig,
+    bounds: f32,
+    /// Base particle size, written to `uniforms.particle_size` every frame
+    particle_size: f32,
+    /// Flat XY simulation viewed head-on through an orthographic camera
+    two_d: bool,
+
+    // Lint findings in the user's custom WGSL, by code block
+    lint_warnings: Vec<(String, LintWarning)>,
 }
 
 impl SimulationResources {
@@ -190,7 +248,7 @@ impl SimulationResources {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         device: &wgpu::Device,
-        _queue: &wgpu::Queue,
+        queue: &wgpu::Queue,
         target_format: wgpu::TextureFormat,
         particle_data: &[u8],
         num_particles: u32,
@@ -207,16 +265,35 @@ impl SimulationResources {
         particle_wgsl_struct: &str,
         blend_mode: &BlendModeConfig,
         spatial_grid_opacity: f32,
+        axes_visible: bool,
         connections_enabled: bool,
         connections_radius: f32,
-        connections_color: [f32; 3],
+        connections_near_color: [f32; 3],
+        connections_far_color: [f32; 3],
+        connections_directed: bool,
+        connections_flow_speed: f32,
         wireframe_mesh: Option<&rdpe::WireframeMesh>,
         wireframe_thickness: f32,
         particle_size: f32,
         trail_length: u32,
+        trail_tail_color: Option<[f32; 3]>,
+        noise_preview: Option<NoiseFieldPreview>,
         mouse_config: MouseConfig,
+        custom_code: &[(String, String)],
+        bounds: f32,
+        two_d: bool,
+        post_process_chain: &[String],
+        post_process_uniforms: &str,
+        bloom: Option<&rdpe::BloomConfig>,
+        tone_map: Option<rdpe::ToneMapMode>,
     ) -> Self {
+        // HDR renders the scene to a float target that the last pass tone-maps
+        let scene_format = match tone_map {
+            Some(_) => wgpu::TextureFormat::Rgba16Float,
+            None => target_format,
+        };
         let particle_stride = layout.stride;
+        let workgroup_size = rdpe::select_workgroup_size(&device.limits());
         // Create particle buffer
         let particle_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Particle Buffer"),
@@ -233,6 +310,7 @@ impl SimulationResources {
                 cell_size: spatial_cell_size,
                 grid_resolution: spatial_resolution,
                 max_neighbors: 0, // unlimited
+                skip_dead_neighbors: true,
             };
             Some(SpatialGpu::new(
                 device,
@@ -240,6 +318,7 @@ impl SimulationResources {
                 num_particles,
                 spatial_config,
                 particle_wgsl_struct,
+                workgroup_size,
             ))
         } else {
             None
@@ -256,6 +335,9 @@ impl SimulationResources {
             Mat4::IDENTITY,
             0.0,
             0.016,
+            num_particles,
+            bounds,
+            particle_size,
             &MouseState::default(),
             &mouse_config,
             &custom_uniforms,
@@ -275,12 +357,28 @@ impl SimulationResources {
                 spatial_cell_size,
                 spatial_resolution,
                 spatial_grid_opacity,
-                target_format,
+                scene_format,
             ))
         } else {
             None
         };
 
+        // Create coordinate axes overlay (always created so it can be toggled live)
+        let axes = AxesVisualization::new(
+            device,
+            &uniform_buffer,
+            axes_visible,
+            scene_format,
+        );
+
+        // Create noise field preview if a NoiseField rule asks for it
+        let noise_preview = noise_preview.map(|preview| NoiseFieldVisualization::new(
+            device,
+            &uniform_buffer,
+            &preview,
+            scene_format,
+        ));
+
         // Create connection visualization if enabled (requires spatial)
         let connections = if connections_enabled {
             spatial.as_ref().map(|s| ConnectionVisualization::new(
@@ -289,10 +387,14 @@ impl SimulationResources {
                 &uniform_buffer,
                 s,
                 num_particles,
+                workgroup_size,
                 connections_radius,
-                connections_color,
+                connections_near_color,
+                connections_far_color,
+                connections_directed,
+                connections_flow_speed,
                 particle_stride,
-                target_format,
+                scene_format,
             ))
         } else {
             None
@@ -305,13 +407,12 @@ impl SimulationResources {
             &uniform_buffer,
             mesh,
             wireframe_thickness,
-            particle_size,
             num_particles,
             particle_stride,
             Some(layout.color_offset as u32),
             layout.alive_offset as u32,
             layout.scale_offset as u32,
-            target_format,
+            scene_format,
             blend_mode,
         ));
 
@@ -322,10 +423,13 @@ impl SimulationResources {
                 &particle_buffer,
                 &uniform_buffer,
                 num_particles,
+                workgroup_size,
                 trail_length,
                 particle_stride,
                 layout.alive_offset as u32,
-                target_format,
+                layout.color_offset as u32,
+                trail_tail_color,
+                scene_format,
             ))
         } else {
             None
@@ -333,13 +437,29 @@ impl SimulationResources {
 
         // Create field system if fields are defined
         let (field_system, field_bind_group_layout) = if !field_registry.is_empty() {
-            let fs = FieldSystemGpu::new(device, field_registry);
+            let fs = FieldSystemGpu::new(device, field_registry, workgroup_size);
             let layout = create_particle_field_bind_group_layout(device, field_registry.len());
             (Some(fs), Some(layout))
         } else {
             (None, None)
         };
 
+        // Create alive particle counter (incremented atomically by the compute shader)
+        let alive_count_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Alive Count Buffer"),
+            size: 4,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_SRC
+                | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let alive_count_staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Alive Count Staging"),
+            size: 4,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
         // Create compute bind group layout (with optional spatial bindings)
         let mut compute_layout_entries = vec![
             // Particles (storage, read-write)
@@ -416,50 +536,31 @@ impl SimulationResources {
             ]);
         }
 
+        // Alive counter (binding 6, after the optional spatial bindings)
+        compute_layout_entries.push(wgpu::BindGroupLayoutEntry {
+            binding: 6,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: false },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        });
+
         let compute_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("Compute Bind Group Layout"),
             entries: &compute_layout_entries,
         });
 
-        // Create compute bind group entries
-        let mut compute_bind_entries = vec![
-            wgpu::BindGroupEntry {
-                binding: 0,
-                resource: particle_buffer.as_entire_binding(),
-            },
-            wgpu::BindGroupEntry {
-                binding: 1,
-                resource: uniform_buffer.as_entire_binding(),
-            },
-        ];
-
-        // Add spatial bind entries if needed
-        if let Some(ref sp) = spatial {
-            compute_bind_entries.extend([
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: sp.particle_indices_a.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 3,
-                    resource: sp.cell_start.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 4,
-                    resource: sp.cell_end.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 5,
-                    resource: sp.spatial_params_buffer.as_entire_binding(),
-                },
-            ]);
-        }
-
-        let compute_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Compute Bind Group"),
-            layout: &compute_bind_group_layout,
-            entries: &compute_bind_entries,
-        });
+        let compute_bind_group = create_compute_bind_group(
+            device,
+            &compute_bind_group_layout,
+            &particle_buffer,
+            &uniform_buffer,
+            spatial.as_ref(),
+            &alive_count_buffer,
+        );
 
         // Create field bind group if fields exist
         let field_bind_group: Option<wgpu::BindGroup> = if let (Some(ref fs), Some(ref layout)) = (&field_system, &field_bind_group_layout) {
@@ -550,82 +651,14 @@ impl SimulationResources {
             push_constant_ranges: &[],
         });
 
-        // Get particle offsets from layout
-        let velocity_offset = layout.velocity_offset;
-        let color_offset = layout.color_offset;
-        let age_offset = layout.age_offset;
-        let alive_offset = layout.alive_offset;
-        let scale_offset = layout.scale_offset;
-
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Render Pipeline"),
-            layout: Some(&render_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &render_shader,
-                entry_point: Some("vs_main"),
-                buffers: &[wgpu::VertexBufferLayout {
-                    array_stride: particle_stride as wgpu::BufferAddress,
-                    step_mode: wgpu::VertexStepMode::Instance,
-                    attributes: &[
-                        // Position at offset 0
-                        wgpu::VertexAttribute {
-                            offset: 0,
-                            shader_location: 0,
-                            format: wgpu::VertexFormat::Float32x3,
-                        },
-                        // Velocity
-                        wgpu::VertexAttribute {
-                            offset: velocity_offset as wgpu::BufferAddress,
-                            shader_location: 1,
-                            format: wgpu::VertexFormat::Float32x3,
-                        },
-                        // Color
-                        wgpu::VertexAttribute {
-                            offset: color_offset as wgpu::BufferAddress,
-                            shader_location: 2,
-                            format: wgpu::VertexFormat::Float32x3,
-                        },
-                        // Age
-                        wgpu::VertexAttribute {
-                            offset: age_offset as wgpu::BufferAddress,
-                            shader_location: 3,
-                            format: wgpu::VertexFormat::Float32,
-                        },
-                        // Alive flag
-                        wgpu::VertexAttribute {
-                            offset: alive_offset as wgpu::BufferAddress,
-                            shader_location: 4,
-                            format: wgpu::VertexFormat::Uint32,
-                        },
-                        // Scale
-                        wgpu::VertexAttribute {
-                            offset: scale_offset as wgpu::BufferAddress,
-                            shader_location: 5,
-                            format: wgpu::VertexFormat::Float32,
-                        },
-                    ],
-                }],
-                compilation_options: Default::default(),
-            },
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleStrip,
-                ..Default::default()
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            fragment: Some(wgpu::FragmentState {
-                module: &render_shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: target_format,
-                    blend: Some(blend_mode.to_wgpu_blend_state()),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: Default::default(),
-            }),
-            multiview: None,
-            cache: None,
-        });
+        let render_pipeline = create_render_pipeline(
+            device,
+            &render_shader,
+            &render_pipeline_layout,
+            layout,
+            scene_format,
+            blend_mode,
+        );
 
         // Create picking state with a default size (will be resized on first frame)
         let picking = PickingState::new(
@@ -640,7 +673,7 @@ impl SimulationResources {
         let (volume_render_state, stored_volume_config) = if volume_config.enabled {
             if let Some(ref fs) = field_system {
                 let rdpe_config = volume_config.to_volume_config();
-                let state = VolumeRenderState::new(device, fs, &rdpe_config, target_format);
+                let state = VolumeRenderState::new(device, queue, fs, &rdpe_config, scene_format);
                 (Some(state), Some(volume_config.clone()))
             } else {
                 (None, None)
@@ -649,15 +682,44 @@ impl SimulationResources {
             (None, None)
         };
 
+        // Post-process chain and bloom, sized to the viewport on the first frame
+        let post_process = (!post_process_chain.is_empty() || bloom.is_some() || tone_map.is_some()).then(|| {
+            let shaders: Vec<&str> = post_process_chain.iter().map(String::as_str).collect();
+            PostProcessState::with_uniforms(
+                device,
+                &uniform_buffer,
+                &shaders,
+                post_process_uniforms,
+                bloom,
+                tone_map,
+                800,
+                600,
+                scene_format,
+                target_format,
+            )
+        });
+
         Self {
             compute_pipeline,
             render_pipeline,
+            render_shader,
+            render_pipeline_layout,
+            particle_layout: layout.clone(),
+            scene_format,
             particle_buffer,
             uniform_buffer,
             _uniform_buffer_size: uniform_buffer_size,
+            alive_count_buffer,
+            alive_count_staging,
+            alive_count_copied: false,
+            alive_count_map: None,
+            alive_count: None,
+            profiler: GpuProfiler::new(device, queue),
+            workgroup_size,
             compute_bind_group,
             render_bind_group,
             num_particles,
+            buffer_capacity: num_particles,
             particle_stride,
             background_color,
             custom_uniforms,
@@ -666,6 +728,7 @@ impl SimulationResources {
             camera_distance: 3.0,
             camera_yaw: 0.0,
             camera_pitch: 0.3,
+            orbit_center: Vec3::ZERO,
             last_inv_view_proj: Mat4::IDENTITY,
             last_camera_pos: Vec3::new(0.0, 0.0, 3.0),
             picking,
@@ -676,14 +739,35 @@ impl SimulationResources {
             _volume_config: stored_volume_config,
             spatial,
             grid_viz,
+            axes,
+            noise_preview,
             connections,
             wireframe,
             trails,
+            post_process,
+            post_process_size: (800, 600),
             mouse_state: MouseState::default(),
             mouse_config,
+            bounds,
+            particle_size,
+            two_d,
+            lint_warnings: custom_code
+                .iter()
+                .flat_map(|(block, code)| lint(code).into_iter().map(move |w| (block.clone(), w)))
+                .collect(),
         }
     }
 
+    /// Lint findings in the custom WGSL this simulation was built from, by code block.
+    pub fn lint_warnings(&self) -> &[(String, LintWarning)] {
+        &self.lint_warnings
+    }
+
+    /// Hide the lint findings until the next rebuild.
+    pub fn dismiss_lint_warnings(&mut self) {
+        self.lint_warnings.clear();
+    }
+
     /// Update uniforms and optionally run compute.
     pub fn prepare(
         &mut self,
@@ -697,47 +781,62 @@ impl SimulationResources {
             self.time += delta_time;
         }
 
-        // Calculate view-projection matrix
-        let eye = Vec3::new(
-            self.camera_distance * self.camera_yaw.cos() * self.camera_pitch.cos(),
-            self.camera_distance * self.camera_pitch.sin(),
-            self.camera_distance * self.camera_yaw.sin() * self.camera_pitch.cos(),
-        );
-        let view = Mat4::look_at_rh(eye, Vec3::ZERO, Vec3::Y);
-        let proj = Mat4::perspective_rh(45.0_f32.to_radians(), aspect_ratio, 0.1, 100.0);
-        let view_proj = proj * view;
+        let (view_proj, eye) = self.view_projection(aspect_ratio);
 
         // Cache camera info for volume rendering
         self.last_inv_view_proj = view_proj.inverse();
         self.last_camera_pos = eye;
 
+        // Animated uniforms take their value at the current time
+        let custom_uniforms: Vec<(String, UniformValueConfig)> = self
+            .custom_uniforms
+            .iter()
+            .map(|(name, value)| (name.clone(), value.at_time(self.time)))
+            .collect();
+
         // Build uniform data including custom uniforms and mouse
         let uniform_data = build_uniform_data(
             view_proj,
             self.time,
             delta_time,
+            self.num_particles,
+            self.bounds,
+            self.particle_size,
             &self.mouse_state,
             &self.mouse_config,
-            &self.custom_uniforms,
+            &custom_uniforms,
         );
         queue.write_buffer(&self.uniform_buffer, 0, &uniform_data);
 
+        // Last frame's copies have been submitted by now, so their mappings can be requested
+        self.poll_alive_count(device);
+        if let Some(ref mut profiler) = self.profiler {
+            profiler.after_submit();
+            profiler.poll(device);
+        }
+
         // Run compute pass if not paused
-        let result = if !self.paused {
+        let mut result = if !self.paused {
             let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
                 label: Some("Compute Encoder"),
             });
 
             // Run spatial hashing passes (if enabled) before particle compute
             if let Some(ref spatial) = self.spatial {
-                spatial.execute(&mut encoder, queue);
+                let timestamps = self.profiler.as_mut()
+                    .and_then(|p| p.compute_timestamps(ProfiledPass::SpatialHash));
+                spatial.execute_timed(&mut encoder, queue, timestamps);
             }
 
+            // Reset alive counter before compute pass
+            queue.write_buffer(&self.alive_count_buffer, 0, &[0u8; 4]);
+
             // Run particle compute pass
             {
                 let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                     label: Some("Particle Compute"),
-                    timestamp_writes: None,
+                    timestamp_writes: self.profiler.as_mut()
+                        .and_then(|p| p.compute_timestamps(ProfiledPass::Compute)),
                 });
                 compute_pass.set_pipeline(&self.compute_pipeline);
                 compute_pass.set_bind_group(0, &self.compute_bind_group, &[]);
@@ -748,10 +847,16 @@ impl SimulationResources {
                     compute_pass.set_bind_group(2, field_bg, &[]);
                 }
 
-                let workgroups = self.num_particles.div_ceil(WORKGROUP_SIZE);
+                let workgroups = self.num_particles.div_ceil(self.workgroup_size);
                 compute_pass.dispatch_workgroups(workgroups, 1, 1);
             }
 
+            // Copy alive counter to staging unless a previous read is still in flight
+            if self.alive_count_map.is_none() && !self.alive_count_copied {
+                encoder.copy_buffer_to_buffer(&self.alive_count_buffer, 0, &self.alive_count_staging, 0, 4);
+                self.alive_count_copied = true;
+            }
+
             // Run field processing passes (merge, blur, decay, clear)
             if let Some(ref mut field_system) = self.field_system {
                 field_system.process(device, &mut encoder, queue);
@@ -764,12 +869,21 @@ impl SimulationResources {
 
             // Run connection finding compute pass (after spatial update)
             if let Some(ref connections) = self.connections {
-                connections.compute(&mut encoder, queue);
+                let timestamps = self.profiler.as_mut()
+                    .and_then(|p| p.compute_timestamps(ProfiledPass::ConnectionCompute));
+                connections.compute(&mut encoder, queue, timestamps);
             }
 
             // Run trail update compute pass
             if let Some(ref trails) = self.trails {
-                trails.compute(&mut encoder);
+                let timestamps = self.profiler.as_mut()
+                    .and_then(|p| p.compute_timestamps(ProfiledPass::TrailCompute));
+                trails.compute(&mut encoder, timestamps);
+            }
+
+            // Resolve pass timestamps (render passes belong to egui and aren't timed)
+            if let Some(ref mut profiler) = self.profiler {
+                profiler.resolve(&mut encoder);
             }
 
             vec![encoder.finish()]
@@ -777,6 +891,41 @@ impl SimulationResources {
             vec![]
         };
 
+        // Render the scene offscreen and run all but the last post-process pass
+        if let Some(ref post_process) = self.post_process {
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Post-Process Encoder"),
+            });
+            {
+                let bg = self.background_color;
+                let mut render_pass = encoder
+                    .begin_render_pass(&wgpu::RenderPassDescriptor {
+                        label: Some("Offscreen Scene Pass"),
+                        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                            view: &post_process.view,
+                            resolve_target: None,
+                            ops: wgpu::Operations {
+                                load: wgpu::LoadOp::Clear(wgpu::Color {
+                                    r: bg.x as f64,
+                                    g: bg.y as f64,
+                                    b: bg.z as f64,
+                                    a: 1.0,
+                                }),
+                                store: wgpu::StoreOp::Store,
+                            },
+                        })],
+                        depth_stencil_attachment: None,
+                        timestamp_writes: None,
+                        occlusion_query_set: None,
+                    })
+                    .forget_lifetime();
+                self.paint_scene(&mut render_pass);
+            }
+            post_process.apply_bloom(&mut encoder);
+            post_process.execute_intermediate(&mut encoder);
+            result.push(encoder.finish());
+        }
+
         // Update volume render params (always, even when paused, for camera movement)
         if let (Some(ref volume_state), Some(ref field_system)) = (&self.volume_render_state, &self.field_system) {
             if volume_state.field_index < field_system.fields.len() {
@@ -795,7 +944,18 @@ impl SimulationResources {
     }
 
     /// Issue draw commands.
+    ///
+    /// With a post-process chain this draws its last pass over the scene
+    /// rendered in [`prepare`](Self::prepare).
     pub fn paint(&self, render_pass: &mut wgpu::RenderPass<'static>) {
+        match self.post_process {
+            Some(ref post_process) => post_process.draw_final(render_pass),
+            None => self.paint_scene(render_pass),
+        }
+    }
+
+    /// Draw the scene itself: volume, overlays, particles.
+    fn paint_scene(&self, render_pass: &mut wgpu::RenderPass<'static>) {
         // Render volume first (behind particles) - uses additive blending
         if let Some(ref volume_state) = self.volume_render_state {
             render_pass.set_pipeline(&volume_state.pipeline);
@@ -808,6 +968,14 @@ impl SimulationResources {
             grid.render(render_pass);
         }
 
+        // Render coordinate axes (debug overlay)
+        self.axes.render(render_pass);
+
+        // Render noise field preview (debug overlay)
+        if let Some(ref noise_preview) = self.noise_preview {
+            noise_preview.render(render_pass);
+        }
+
         // Render trails (before particles so they're behind)
         if let Some(ref trails) = self.trails {
             trails.render(render_pass);
@@ -855,6 +1023,43 @@ impl SimulationResources {
         self.background_color
     }
 
+    /// Update the volume transfer function (hot-swappable).
+    ///
+    /// Rewrites the lookup texture in place; does nothing when volume
+    /// rendering is off.
+    pub fn set_volume_transfer_function(&mut self, queue: &wgpu::Queue, config: &VolumeRenderConfig) {
+        if let Some(ref mut volume) = self.volume_render_state {
+            volume.set_transfer_function(queue, &config.to_volume_config());
+        }
+    }
+
+    /// Set the base particle size (hot-swappable).
+    ///
+    /// The size is a uniform, so it takes effect on the next `prepare`
+    /// without touching any pipeline.
+    pub fn set_particle_size(&mut self, size: f32) {
+        self.particle_size = size;
+    }
+
+    /// Switch the particle blend mode.
+    ///
+    /// Blending is part of the pipeline state rather than the shader, so only
+    /// the render pipelines are recreated; the compiled shader modules,
+    /// buffers and bind groups are reused.
+    pub fn set_blend_mode(&mut self, device: &wgpu::Device, mode: &BlendModeConfig) {
+        self.render_pipeline = create_render_pipeline(
+            device,
+            &self.render_shader,
+            &self.render_pipeline_layout,
+            &self.particle_layout,
+            self.scene_format,
+            mode,
+        );
+        if let Some(ref mut wireframe) = self.wireframe {
+            wireframe.set_blend_mode(device, self.scene_format, mode);
+        }
+    }
+
     /// Set grid visualization opacity.
     pub fn set_grid_opacity(&mut self, queue: &wgpu::Queue, opacity: f32) {
         if let Some(ref mut grid) = self.grid_viz {
@@ -862,6 +1067,132 @@ impl SimulationResources {
         }
     }
 
+    /// Show or hide the coordinate axes overlay.
+    pub fn set_axes_visible(&mut self, visible: bool) {
+        self.axes.set_visible(visible);
+    }
+
+    /// Change the number of active particles without rebuilding.
+    ///
+    /// If `n` fits in the existing particle buffer, only the first `n`
+    /// particles are simulated and drawn from now on; particles beyond the
+    /// active count keep their state and resume if the count grows again.
+    /// Spatial hashing, connections, trails and the wireframe overlay are
+    /// resized to the new count; trail history is cleared.
+    /// Returns `false` if `n` exceeds the buffer capacity, in which case the
+    /// caller must reinitialize the simulation.
+    pub fn set_num_particles(&mut self, n: u32, device: &wgpu::Device, queue: &wgpu::Queue) -> bool {
+        if n > self.buffer_capacity {
+            return false;
+        }
+        if n == self.num_particles {
+            return true;
+        }
+        self.num_particles = n;
+        queue.write_buffer(
+            &self.uniform_buffer,
+            std::mem::offset_of!(BaseUniforms, particle_count) as wgpu::BufferAddress,
+            bytemuck::bytes_of(&n),
+        );
+        self.resize_subsystems(device, queue);
+        true
+    }
+
+    /// Number of particles the particle buffer can hold.
+    pub fn buffer_capacity(&self) -> u32 {
+        self.buffer_capacity
+    }
+
+    /// Reallocate the particle buffer for `new_count` particles without
+    /// recompiling any shaders.
+    ///
+    /// The first `min(num_particles, new_count)` particles are copied across
+    /// on the GPU and `spawner(index, bytes)` fills each new slot with one
+    /// particle's raw bytes. Spatial hashing, connections, trails and the
+    /// wireframe overlay are rebound to the new buffer; trail history is cleared.
+    pub fn resize_particles(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        new_count: u32,
+        mut spawner: impl FnMut(u32, &mut [u8]),
+    ) {
+        let old_count = self.num_particles;
+        let stride = self.particle_stride as u64;
+        let particle_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Particle Buffer"),
+            size: new_count.max(1) as u64 * stride,
+            usage: wgpu::BufferUsages::VERTEX
+                | wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Particle Resize Encoder"),
+        });
+        let kept = old_count.min(new_count) as u64;
+        encoder.copy_buffer_to_buffer(&self.particle_buffer, 0, &particle_buffer, 0, kept * stride);
+
+        if new_count > old_count {
+            let mut data = vec![0u8; (new_count - old_count) as usize * self.particle_stride];
+            for (i, bytes) in data.chunks_exact_mut(self.particle_stride).enumerate() {
+                spawner(old_count + i as u32, bytes);
+            }
+            queue.write_buffer(&particle_buffer, kept * stride, &data);
+        }
+        queue.submit(std::iter::once(encoder.finish()));
+
+        self.particle_buffer = particle_buffer;
+        self.num_particles = new_count;
+        self.buffer_capacity = new_count;
+        queue.write_buffer(
+            &self.uniform_buffer,
+            std::mem::offset_of!(BaseUniforms, particle_count) as wgpu::BufferAddress,
+            bytemuck::bytes_of(&new_count),
+        );
+        self.resize_subsystems(device, queue);
+    }
+
+    /// Rebind every subsystem that holds the particle buffer or sizes its
+    /// own buffers by the particle count to the current buffer and count.
+    fn resize_subsystems(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        let count = self.num_particles;
+        if let Some(ref mut spatial) = self.spatial {
+            spatial.resize(device, queue, &self.particle_buffer, count);
+        }
+
+        self.compute_bind_group = create_compute_bind_group(
+            device,
+            &self.compute_pipeline.get_bind_group_layout(0),
+            &self.particle_buffer,
+            &self.uniform_buffer,
+            self.spatial.as_ref(),
+            &self.alive_count_buffer,
+        );
+
+        if let (Some(connections), Some(spatial)) = (self.connections.as_mut(), self.spatial.as_ref()) {
+            connections.resize(device, &self.particle_buffer, &self.uniform_buffer, spatial, count);
+        }
+        if let Some(ref mut trails) = self.trails {
+            trails.resize(device, &self.particle_buffer, &self.uniform_buffer, count);
+        }
+        if let Some(ref mut wireframe) = self.wireframe {
+            wireframe.set_particle_buffer(device, &self.particle_buffer, &self.uniform_buffer, count);
+        }
+    }
+
+    /// Fill a field with a constant value without restarting the simulation.
+    ///
+    /// Returns `false` if there is no field with that name.
+    pub fn reset_field_to(&mut self, name: &str, value: f32, queue: &wgpu::Queue) -> bool {
+        match self.field_system {
+            Some(ref mut field_system) => field_system.reset_field_to(name, value, queue),
+            None => false,
+        }
+    }
+
     /// Update mouse state (ray and button).
     pub fn set_mouse_state(&mut self, ray_origin: Vec3, ray_dir: Vec3, is_down: bool) {
         self.mouse_state.ray_origin = ray_origin;
@@ -879,8 +1210,49 @@ impl SimulationResources {
         &self.mouse_config
     }
 
-    /// Rotate camera.
+    /// View-projection matrix and eye position for a viewport of `aspect_ratio`.
+    ///
+    /// In 2D mode the camera looks straight down -Z with an orthographic
+    /// projection that frames the bounds; zooming scales the framed area.
+    pub fn view_projection(&self, aspect_ratio: f32) -> (Mat4, Vec3) {
+        let target = self.orbit_center;
+        if self.two_d {
+            let eye = target + Vec3::new(0.0, 0.0, self.camera_distance);
+            let view = Mat4::look_at_rh(eye, target, Vec3::Y);
+            let half_height = self.bounds * 1.1 * self.camera_distance / 3.0;
+            let half_width = half_height * aspect_ratio;
+            let proj = Mat4::orthographic_rh(-half_width, half_width, -half_height, half_height, 0.1, 100.0);
+            return (proj * view, eye);
+        }
+
+        let eye = target + Vec3::new(
+            self.camera_distance * self.camera_yaw.cos() * self.camera_pitch.cos(),
+            self.camera_distance * self.camera_pitch.sin(),
+            self.camera_distance * self.camera_yaw.sin() * self.camera_pitch.cos(),
+        );
+        let view = Mat4::look_at_rh(eye, target, Vec3::Y);
+        let proj = Mat4::perspective_rh(45.0_f32.to_radians(), aspect_ratio, 0.1, 100.0);
+        (proj * view, eye)
+    }
+
+    /// Set the point the camera orbits around and looks at.
+    ///
+    /// Takes effect on the next `prepare`. In 2D mode the view pans so the
+    /// center sits in the middle of the viewport.
+    pub fn set_orbit_center(&mut self, center: Vec3) {
+        self.orbit_center = center;
+    }
+
+    /// Point the camera orbits around.
+    pub fn orbit_center(&self) -> Vec3 {
+        self.orbit_center
+    }
+
+    /// Rotate camera. Does nothing in 2D mode.
     pub fn rotate_camera(&mut self, delta_yaw: f32, delta_pitch: f32) {
+        if self.two_d {
+            return;
+        }
         self.camera_yaw += delta_yaw;
         self.camera_pitch = (self.camera_pitch + delta_pitch).clamp(-1.4, 1.4);
     }
@@ -915,11 +1287,85 @@ impl SimulationResources {
         self.picking.resize(device, width, height);
     }
 
+    /// Resize the post-process textures to match viewport.
+    pub fn resize_post_process(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        if let Some(ref mut post_process) = self.post_process {
+            if self.post_process_size != (width, height) {
+                post_process.resize(device, &self.uniform_buffer, width, height);
+                self.post_process_size = (width, height);
+            }
+        }
+    }
+
     /// Run picking pass and update selected particle data.
     pub fn update_picking(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
         self.picking.render_and_pick(device, queue, &self.particle_buffer, self.num_particles);
     }
 
+    /// Number of alive particles from the most recent completed readback.
+    ///
+    /// Lags the simulation by a frame or two; `None` until the first read lands.
+    pub fn alive_count(&self) -> Option<u32> {
+        self.alive_count
+    }
+
+    /// Whether the GPU supports per-pass timing.
+    pub fn profiling_supported(&self) -> bool {
+        self.profiler.is_some()
+    }
+
+    /// Whether per-pass timing is on.
+    pub fn is_profiling(&self) -> bool {
+        self.profiler.as_ref().is_some_and(|p| p.is_enabled())
+    }
+
+    /// Turn per-pass timing on or off (no-op if unsupported).
+    pub fn set_profiling(&mut self, enabled: bool) {
+        if let Some(ref mut profiler) = self.profiler {
+            profiler.set_enabled(enabled);
+        }
+    }
+
+    /// GPU time per compute pass for the most recent profiled frame.
+    pub fn last_frame_timings(&self) -> Option<FrameTimings> {
+        self.profiler.as_ref().and_then(|p| p.last_frame_timings())
+    }
+
+    /// Map the alive count staging buffer after a copy, and pick up the
+    /// result once ready. Never blocks.
+    fn poll_alive_count(&mut self, device: &wgpu::Device) {
+        if self.alive_count_copied {
+            let slot = Arc::new(Mutex::new(None));
+            let callback_slot = slot.clone();
+            self.alive_count_staging.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+                if let Ok(mut slot) = callback_slot.lock() {
+                    *slot = Some(result);
+                }
+            });
+            self.alive_count_map = Some(slot);
+            self.alive_count_copied = false;
+        }
+
+        let Some(ref slot) = self.alive_count_map else {
+            return;
+        };
+        device.poll(wgpu::Maintain::Poll);
+        let result = slot.lock().ok().and_then(|mut slot| slot.take());
+        match result {
+            Some(Ok(())) => {
+                let data = self.alive_count_staging.slice(..).get_mapped_range();
+                self.alive_count = Some(u32::from_le_bytes([data[0], data[1], data[2], data[3]]));
+                drop(data);
+                self.alive_count_staging.unmap();
+                self.alive_count_map = None;
+            }
+            Some(Err(_)) => {
+                self.alive_count_map = None;
+            }
+            None => {}
+        }
+    }
+
     /// Read particle data from GPU.
     ///
     /// Returns `None` if the buffer cannot be mapped for reading.
@@ -960,6 +1406,126 @@ impl SimulationResources {
         Some(result)
     }
 
+    /// Render the current frame offscreen and read it back as RGBA8 pixels.
+    ///
+    /// Draws everything `paint` draws over the background color into a
+    /// `width` x `height` texture, using the uniforms from the last `prepare`
+    /// so the image matches the viewport. `format` must be egui's target
+    /// format, which the final (tone-mapped) pass draws into; BGRA output is swizzled to RGBA and alpha
+    /// is forced opaque. Returns tightly packed rows, or `None` for non-8-bit
+    /// formats or if the readback fails.
+    pub fn capture_frame(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> Option<Vec<u8>> {
+        let swizzle = match format {
+            wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+            _ => return None,
+        };
+        let (width, height) = (width.max(1), height.max(1));
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Capture Texture"),
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Rows in the staging buffer are padded to the copy alignment
+        let row_bytes = width * 4;
+        let padded_row_bytes = row_bytes.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Capture Staging"),
+            size: (padded_row_bytes * height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Capture Encoder"),
+        });
+        {
+            let bg = self.background_color;
+            let mut render_pass = encoder
+                .begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Capture Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: &view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color {
+                                r: bg.x as f64,
+                                g: bg.y as f64,
+                                b: bg.z as f64,
+                                a: 1.0,
+                            }),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                })
+                .forget_lifetime();
+            self.paint(&mut render_pass);
+        }
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &staging,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row_bytes),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+        );
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let buffer_slice = staging.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+            // Ignore send errors - receiver may have been dropped
+            let _ = tx.send(result);
+        });
+
+        device.poll(wgpu::Maintain::Wait);
+        rx.recv().ok()?.ok()?;
+
+        let data = buffer_slice.get_mapped_range();
+        let mut pixels = Vec::with_capacity((row_bytes * height) as usize);
+        for row in data.chunks_exact(padded_row_bytes as usize) {
+            pixels.extend_from_slice(&row[..row_bytes as usize]);
+        }
+        drop(data);
+        staging.unmap();
+
+        for pixel in pixels.chunks_exact_mut(4) {
+            if swizzle {
+                pixel.swap(0, 2);
+            }
+            // The viewport is opaque; blending may have left partial alpha
+            pixel[3] = 255;
+        }
+        Some(pixels)
+    }
+
     /// Write particle data to GPU.
     pub fn write_particles(&self, queue: &wgpu::Queue, data: &[u8]) {
         queue.write_buffer(&self.particle_buffer, 0, data);
@@ -984,15 +1550,9 @@ impl SimulationResources {
     pub fn sync_custom_uniforms(&mut self, uniforms: &HashMap<String, UniformValueConfig>) {
         for (name, value) in &mut self.custom_uniforms {
             if let Some(new_value) = uniforms.get(name) {
-                // Only update if types match (can't change type without rebuild)
-                let types_match = matches!(
-                    (&*value, new_value),
-                    (UniformValueConfig::F32(_), UniformValueConfig::F32(_))
-                    | (UniformValueConfig::Vec2(_), UniformValueConfig::Vec2(_))
-                    | (UniformValueConfig::Vec3(_), UniformValueConfig::Vec3(_))
-                    | (UniformValueConfig::Vec4(_), UniformValueConfig::Vec4(_))
-                );
-                if types_match {
+                // Only update if the WGSL type matches (can't change type without rebuild);
+                // a fixed f32 can become an animated one and back
+                if value.wgsl_type() == new_value.wgsl_type() {
                     *value = new_value.clone();
                 }
             }
@@ -1007,14 +1567,7 @@ impl SimulationResources {
         for (name, value) in &self.custom_uniforms {
             match uniforms.get(name) {
                 Some(other) => {
-                    let types_match = matches!(
-                        (value, other),
-                        (UniformValueConfig::F32(_), UniformValueConfig::F32(_))
-                        | (UniformValueConfig::Vec2(_), UniformValueConfig::Vec2(_))
-                        | (UniformValueConfig::Vec3(_), UniformValueConfig::Vec3(_))
-                        | (UniformValueConfig::Vec4(_), UniformValueConfig::Vec4(_))
-                    );
-                    if !types_match {
+                    if value.wgsl_type() != other.wgsl_type() {
                         return false;
                     }
                 }
@@ -1025,6 +1578,145 @@ impl SimulationResources {
     }
 }
 
+/// Build the billboard particle pipeline.
+///
+/// Split out so the blend mode can be swapped without recompiling the shader.
+fn create_render_pipeline(
+    device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
+    pipeline_layout: &wgpu::PipelineLayout,
+    layout: &ParticleLayout,
+    target_format: wgpu::TextureFormat,
+    blend_mode: &BlendModeConfig,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Render Pipeline"),
+        layout: Some(pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs_main"),
+            buffers: &[wgpu::VertexBufferLayout {
+                array_stride: layout.stride as wgpu::BufferAddress,
+                step_mode: wgpu::VertexStepMode::Instance,
+                attributes: &[
+                    // Position at offset 0
+                    wgpu::VertexAttribute {
+                        offset: 0,
+                        shader_location: 0,
+                        format: wgpu::VertexFormat::Float32x3,
+                    },
+                    // Velocity
+                    wgpu::VertexAttribute {
+                        offset: layout.velocity_offset as wgpu::BufferAddress,
+                        shader_location: 1,
+                        format: wgpu::VertexFormat::Float32x3,
+                    },
+                    // Color
+                    wgpu::VertexAttribute {
+                        offset: layout.color_offset as wgpu::BufferAddress,
+                        shader_location: 2,
+                        format: wgpu::VertexFormat::Float32x3,
+                    },
+                    // Age
+                    wgpu::VertexAttribute {
+                        offset: layout.age_offset as wgpu::BufferAddress,
+                        shader_location: 3,
+                        format: wgpu::VertexFormat::Float32,
+                    },
+                    // Alive flag
+                    wgpu::VertexAttribute {
+                        offset: layout.alive_offset as wgpu::BufferAddress,
+                        shader_location: 4,
+                        format: wgpu::VertexFormat::Uint32,
+                    },
+                    // Scale
+                    wgpu::VertexAttribute {
+                        offset: layout.scale_offset as wgpu::BufferAddress,
+                        shader_location: 5,
+                        format: wgpu::VertexFormat::Float32,
+                    },
+                ],
+            }],
+            compilation_options: Default::default(),
+        },
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleStrip,
+            ..Default::default()
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some("fs_main"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format: target_format,
+                blend: Some(blend_mode.to_wgpu_blend_state()),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        multiview: None,
+        cache: None,
+    })
+}
+
+/// Create the main compute bind group (group 0).
+///
+/// With spatial hashing the spatial buffers are bound at 2-5; the alive
+/// counter is always at binding 6.
+fn create_compute_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    particle_buffer: &wgpu::Buffer,
+    uniform_buffer: &wgpu::Buffer,
+    spatial: Option<&SpatialGpu>,
+    alive_count_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    let mut entries = vec![
+        wgpu::BindGroupEntry {
+            binding: 0,
+            resource: particle_buffer.as_entire_binding(),
+        },
+        wgpu::BindGroupEntry {
+            binding: 1,
+            resource: uniform_buffer.as_entire_binding(),
+        },
+    ];
+
+    // Add spatial bind entries if needed
+    if let Some(sp) = spatial {
+        entries.extend([
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: sp.particle_indices_a.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 3,
+                resource: sp.cell_start.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 4,
+                resource: sp.cell_end.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 5,
+                resource: sp.spatial_params_buffer.as_entire_binding(),
+            },
+        ]);
+    }
+
+    entries.push(wgpu::BindGroupEntry {
+        binding: 6,
+        resource: alive_count_buffer.as_entire_binding(),
+    });
+
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Compute Bind Group"),
+        layout,
+        entries: &entries,
+    })
+}
+
 /// Lightweight callback struct for each frame.
 ///
 /// This is passed to `Callback::new_paint_callback()` and contains
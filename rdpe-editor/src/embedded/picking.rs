@@ -4,10 +4,14 @@ use crate::config::ParticleLayout;
 
 /// Picking shader - outputs particle index + 1 (0 = no particle).
 const PICKING_SHADER: &str = r#"
+// Leading fields of the editor's uniform buffer
 struct Uniforms {
     view_proj: mat4x4<f32>,
     time: f32,
     delta_time: f32,
+    particle_count: u32,
+    bounds: f32,
+    particle_size: f32,
 };
 
 @group(0) @binding(0)
@@ -50,7 +54,7 @@ fn vs_main(
     let up = vec3<f32>(view_proj[0][1], view_proj[1][1], view_proj[2][1]);
 
     // Slightly larger for easier picking
-    let particle_size = 0.03 * scale * 1.5;
+    let particle_size = uniforms.particle_size * scale * 1.5;
     let world_pos = particle_pos + right * quad_pos.x * particle_size + up * quad_pos.y * particle_size;
 
     out.clip_position = view_proj * vec4<f32>(world_pos, 1.0);
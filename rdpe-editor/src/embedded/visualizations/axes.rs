@@ -0,0 +1,209 @@
+//! Coordinate axes visualization for the 3D editor.
+//!
+//! This module draws three short colored line segments from the world origin
+//! (X = red, Y = green, Z = blue) so users can keep track of orientation while
+//! orbiting the camera. The axes use their own pipeline with fixed alpha
+//! blending and no depth writes, so they look the same regardless of the
+//! simulation's blend mode.
+
+use bytemuck;
+use wgpu;
+use wgpu::util::DeviceExt;
+
+/// Length of each axis line in world units.
+const AXIS_LENGTH: f32 = 0.2;
+
+const AXES_SHADER: &str = r#"
+struct Uniforms {
+    view_proj: mat4x4<f32>,
+    time: f32,
+    delta_time: f32,
+};
+
+struct AxisLine {
+    start: vec4<f32>,
+    end: vec4<f32>,
+    color: vec4<f32>,
+};
+
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+@group(0) @binding(1) var<storage, read> axes: array<AxisLine>;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec3<f32>,
+};
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    @builtin(instance_index) instance_index: u32,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    let axis = axes[instance_index];
+    let pos_a = axis.start.xyz;
+    let pos_b = axis.end.xyz;
+    let dir = normalize(pos_b - pos_a);
+
+    var perp = cross(dir, vec3<f32>(0.0, 1.0, 0.0));
+    if length(perp) < 0.001 {
+        perp = cross(dir, vec3<f32>(1.0, 0.0, 0.0));
+    }
+    perp = normalize(perp) * 0.004;
+
+    var pos: vec3<f32>;
+    switch vertex_index {
+        case 0u: { pos = pos_a - perp; }
+        case 1u: { pos = pos_a + perp; }
+        case 2u: { pos = pos_b - perp; }
+        case 3u: { pos = pos_a + perp; }
+        case 4u: { pos = pos_b - perp; }
+        default: { pos = pos_b + perp; }
+    }
+
+    out.clip_position = uniforms.view_proj * vec4<f32>(pos, 1.0);
+    out.color = axis.color.rgb;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(in.color, 1.0);
+}
+"#;
+
+pub(crate) struct AxesVisualization {
+    pipeline: wgpu::RenderPipeline,
+    bind_group: wgpu::BindGroup,
+    _axes_buffer: wgpu::Buffer,
+    visible: bool,
+}
+
+impl AxesVisualization {
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        uniform_buffer: &wgpu::Buffer,
+        visible: bool,
+        target_format: wgpu::TextureFormat,
+    ) -> Self {
+        let axes = Self::generate_axes();
+
+        let axes_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Axes Line Buffer"),
+            contents: bytemuck::cast_slice(&axes),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Axes Shader"),
+            source: wgpu::ShaderSource::Wgsl(AXES_SHADER.into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Axes Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Axes Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: axes_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Axes Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        // Fixed alpha blending and no depth state: the axes are an overlay and
+        // must not follow the simulation's blend mode.
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Axes Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: target_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            pipeline,
+            bind_group,
+            _axes_buffer: axes_buffer,
+            visible,
+        }
+    }
+
+    /// Build the three axis lines as (start, end, color) triples.
+    fn generate_axes() -> Vec<[[f32; 4]; 3]> {
+        vec![
+            [[0.0, 0.0, 0.0, 1.0], [AXIS_LENGTH, 0.0, 0.0, 1.0], [1.0, 0.2, 0.2, 1.0]],
+            [[0.0, 0.0, 0.0, 1.0], [0.0, AXIS_LENGTH, 0.0, 1.0], [0.2, 1.0, 0.2, 1.0]],
+            [[0.0, 0.0, 0.0, 1.0], [0.0, 0.0, AXIS_LENGTH, 1.0], [0.3, 0.4, 1.0, 1.0]],
+        ]
+    }
+
+    pub(crate) fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    pub(crate) fn render(&self, render_pass: &mut wgpu::RenderPass<'static>) {
+        if self.visible {
+            render_pass.set_pipeline(&self.pipeline);
+            render_pass.set_bind_group(0, &self.bind_group, &[]);
+            render_pass.draw(0..6, 0..3);
+        }
+    }
+}
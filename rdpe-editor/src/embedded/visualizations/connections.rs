@@ -2,7 +2,12 @@
 //!
 //! This module provides GPU-accelerated visualization of connections between particles
 //! within a specified radius. It uses spatial hashing for efficient neighbor finding
-//! and renders connections as thin lines with distance-based alpha blending.
+//! and renders connections as thin lines with distance-based alpha blending,
+//! colored from a near color to a far color by length. Directed connections
+//! add an arrowhead and an optional flow dot moving from the lower-index
+//! particle toward the higher-index one.
+
+use std::collections::HashMap;
 
 use rdpe::SpatialGpu;
 use wgpu::util::DeviceExt;
@@ -12,10 +17,12 @@ use wgpu::util::DeviceExt;
 /// This struct manages the GPU resources needed to:
 /// - Find connections between particles within a specified radius using spatial hashing
 /// - Store connection data as line segments
-/// - Render connections with distance-based alpha blending
+/// - Render connections with distance-based alpha blending and color
 pub(crate) struct ConnectionVisualization {
     /// Buffer storing connection line segments.
     _connection_buffer: wgpu::Buffer,
+    /// Per-connection `distance / radius`, read as an instance vertex attribute.
+    data_buffer: wgpu::Buffer,
     /// Atomic counter for connections found.
     count_buffer: wgpu::Buffer,
     /// Compute pipeline to find connections.
@@ -28,10 +35,14 @@ pub(crate) struct ConnectionVisualization {
     render_bind_group: wgpu::BindGroup,
     /// Maximum connections.
     max_connections: u32,
+    /// Vertices drawn per connection (line, plus arrowhead and flow dot when directed).
+    vertices_per_connection: u32,
     /// Connection radius.
-    _radius: f32,
+    radius: f32,
     /// Number of particles.
     num_particles: u32,
+    /// Compute workgroup size the pipeline was built with.
+    workgroup_size: u32,
 }
 
 impl ConnectionVisualization {
@@ -42,8 +53,12 @@ impl ConnectionVisualization {
         uniform_buffer: &wgpu::Buffer,
         spatial: &SpatialGpu,
         num_particles: u32,
+        workgroup_size: u32,
         radius: f32,
-        color: [f32; 3],
+        near_color: [f32; 3],
+        far_color: [f32; 3],
+        directed: bool,
+        flow_speed: f32,
         particle_stride: usize,
         target_format: wgpu::TextureFormat,
     ) -> Self {
@@ -59,6 +74,9 @@ impl ConnectionVisualization {
             mapped_at_creation: false,
         });
 
+        // Connection data buffer: one f32 (distance / radius) per connection
+        let data_buffer = Self::create_data_buffer(device, max_connections);
+
         // Atomic counter
         let count_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Connection Count Buffer"),
@@ -93,6 +111,7 @@ impl ConnectionVisualization {
                 wgpu::BindGroupLayoutEntry { binding: 5, visibility: wgpu::ShaderStages::COMPUTE, ty: wgpu::BindingType::Buffer { ty: wgpu::BufferBindingType::Storage { read_only: true }, has_dynamic_offset: false, min_binding_size: None }, count: None },
                 wgpu::BindGroupLayoutEntry { binding: 6, visibility: wgpu::ShaderStages::COMPUTE, ty: wgpu::BindingType::Buffer { ty: wgpu::BufferBindingType::Storage { read_only: true }, has_dynamic_offset: false, min_binding_size: None }, count: None },
                 wgpu::BindGroupLayoutEntry { binding: 7, visibility: wgpu::ShaderStages::COMPUTE, ty: wgpu::BindingType::Buffer { ty: wgpu::BufferBindingType::Uniform, has_dynamic_offset: false, min_binding_size: None }, count: None },
+                wgpu::BindGroupLayoutEntry { binding: 8, visibility: wgpu::ShaderStages::COMPUTE, ty: wgpu::BindingType::Buffer { ty: wgpu::BufferBindingType::Storage { read_only: false }, has_dynamic_offset: false, min_binding_size: None }, count: None },
             ],
         });
 
@@ -108,6 +127,7 @@ impl ConnectionVisualization {
                 wgpu::BindGroupEntry { binding: 5, resource: spatial.cell_start.as_entire_binding() },
                 wgpu::BindGroupEntry { binding: 6, resource: spatial.cell_end.as_entire_binding() },
                 wgpu::BindGroupEntry { binding: 7, resource: spatial.spatial_params_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 8, resource: data_buffer.as_entire_binding() },
             ],
         });
 
@@ -122,12 +142,15 @@ impl ConnectionVisualization {
             layout: Some(&compute_pipeline_layout),
             module: &compute_shader,
             entry_point: Some("main"),
-            compilation_options: Default::default(),
+            compilation_options: wgpu::PipelineCompilationOptions {
+                constants: &HashMap::from([("WORKGROUP_SIZE".to_string(), workgroup_size as f64)]),
+                ..Default::default()
+            },
             cache: None,
         });
 
-        // Create render shader with color
-        let render_shader_src = generate_connection_render_shader(color);
+        // Create render shader with near/far colors
+        let render_shader_src = generate_connection_render_shader(near_color, far_color, directed, flow_speed);
         let render_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Connection Render Shader"),
             source: wgpu::ShaderSource::Wgsl(render_shader_src.into()),
@@ -162,7 +185,16 @@ impl ConnectionVisualization {
             vertex: wgpu::VertexState {
                 module: &render_shader,
                 entry_point: Some("vs_main"),
-                buffers: &[],
+                // Per-connection distance factor
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: 4,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &[wgpu::VertexAttribute {
+                        offset: 0,
+                        shader_location: 0,
+                        format: wgpu::VertexFormat::Float32,
+                    }],
+                }],
                 compilation_options: Default::default(),
             },
             fragment: Some(wgpu::FragmentState {
@@ -187,17 +219,86 @@ impl ConnectionVisualization {
 
         Self {
             _connection_buffer: connection_buffer,
+            data_buffer,
             count_buffer,
             compute_pipeline,
             compute_bind_group,
             render_pipeline,
             render_bind_group,
             max_connections,
-            _radius: radius,
+            vertices_per_connection: if directed || flow_speed > 0.0 { 15 } else { 6 },
+            radius,
             num_particles,
+            workgroup_size,
         }
     }
 
+    /// Reallocate the connection buffer for a new particle count.
+    ///
+    /// Pipelines are kept; call after [`SpatialGpu::resize`] so the new
+    /// sort buffers are bound.
+    pub(crate) fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        particle_buffer: &wgpu::Buffer,
+        uniform_buffer: &wgpu::Buffer,
+        spatial: &SpatialGpu,
+        num_particles: u32,
+    ) {
+        let max_connections = num_particles * 8;
+        let connection_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Connection Buffer"),
+            size: (max_connections as usize * 32) as u64,
+            usage: wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+        let data_buffer = Self::create_data_buffer(device, max_connections);
+        let params_data: [f32; 4] = [self.radius, f32::from_bits(max_connections), f32::from_bits(num_particles), 0.0];
+        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Connection Params Buffer"),
+            contents: bytemuck::cast_slice(&params_data),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        self.compute_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Connection Compute Bind Group"),
+            layout: &self.compute_pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: particle_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: connection_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 2, resource: self.count_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 3, resource: params_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 4, resource: spatial.particle_indices_a.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 5, resource: spatial.cell_start.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 6, resource: spatial.cell_end.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 7, resource: spatial.spatial_params_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 8, resource: data_buffer.as_entire_binding() },
+            ],
+        });
+        self.render_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Connection Render Bind Group"),
+            layout: &self.render_pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: connection_buffer.as_entire_binding() },
+            ],
+        });
+
+        self._connection_buffer = connection_buffer;
+        self.data_buffer = data_buffer;
+        self.max_connections = max_connections;
+        self.num_particles = num_particles;
+    }
+
+    fn create_data_buffer(device: &wgpu::Device, max_connections: u32) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Connection Data Buffer"),
+            size: max_connections as u64 * 4,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::VERTEX,
+            mapped_at_creation: false,
+        })
+    }
+
     fn generate_compute_shader(particle_stride_vec4: usize) -> String {
         format!(r#"
 struct ConnectionParams {{
@@ -250,8 +351,11 @@ fn pos_to_cell(pos: vec3<f32>, cell_size: f32, grid_res: u32) -> vec3<i32> {{
 @group(0) @binding(5) var<storage, read> cell_start: array<u32>;
 @group(0) @binding(6) var<storage, read> cell_end: array<u32>;
 @group(0) @binding(7) var<uniform> spatial: SpatialParams;
+@group(0) @binding(8) var<storage, read_write> connection_data: array<f32>;
 
-@compute @workgroup_size(256)
+override WORKGROUP_SIZE: u32 = 256u;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
     let idx = global_id.x;
     if idx >= params.num_particles {{
@@ -292,6 +396,7 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
                             let alpha = 1.0 - dist / params.radius;
                             connections[conn_idx * 2u] = vec4<f32>(my_pos, alpha);
                             connections[conn_idx * 2u + 1u] = vec4<f32>(other_pos, 0.0);
+                            connection_data[conn_idx] = dist / params.radius;
                         }}
                     }}
                 }}
@@ -302,24 +407,30 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
 "#, particle_stride_vec4 = particle_stride_vec4)
     }
 
-    pub(crate) fn compute(&self, encoder: &mut wgpu::CommandEncoder, queue: &wgpu::Queue) {
+    pub(crate) fn compute(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        queue: &wgpu::Queue,
+        timestamp_writes: Option<wgpu::ComputePassTimestampWrites<'_>>,
+    ) {
         // Reset connection count
         queue.write_buffer(&self.count_buffer, 0, &[0u8; 4]);
 
         let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
             label: Some("Connection Compute Pass"),
-            timestamp_writes: None,
+            timestamp_writes,
         });
         compute_pass.set_pipeline(&self.compute_pipeline);
         compute_pass.set_bind_group(0, &self.compute_bind_group, &[]);
-        compute_pass.dispatch_workgroups(self.num_particles.div_ceil(256), 1, 1);
+        compute_pass.dispatch_workgroups(self.num_particles.div_ceil(self.workgroup_size), 1, 1);
     }
 
     pub(crate) fn render(&self, render_pass: &mut wgpu::RenderPass<'static>) {
         render_pass.set_pipeline(&self.render_pipeline);
         render_pass.set_bind_group(0, &self.render_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.data_buffer.slice(..));
         // Draw max_connections instances (empty ones will be culled by alpha check)
-        render_pass.draw(0..6, 0..self.max_connections);
+        render_pass.draw(0..self.vertices_per_connection, 0..self.max_connections);
     }
 }
 
@@ -330,9 +441,22 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
 ///
 /// # Arguments
 ///
-/// * `color` - RGB color values for the connection lines (range 0.0-1.0)
-pub(crate) fn generate_connection_render_shader(color: [f32; 3]) -> String {
+/// * `near_color` - RGB color of the shortest connections (range 0.0-1.0)
+/// * `far_color` - RGB color of connections at the full radius (range 0.0-1.0)
+/// * `directed` - Draw an arrowhead 60% of the way from A to B (vertices 6-8)
+/// * `flow_speed` - Speed of a dot moving from A to B (vertices 9-14, 0.0 = off)
+pub(crate) fn generate_connection_render_shader(
+    near_color: [f32; 3],
+    far_color: [f32; 3],
+    directed: bool,
+    flow_speed: f32,
+) -> String {
     format!(r#"
+const DIRECTED: bool = {directed};
+const FLOW_SPEED: f32 = {flow_speed:?};
+const ARROW_SIZE: f32 = 0.015;
+const DOT_SIZE: f32 = 0.006;
+
 struct Uniforms {{
     view_proj: mat4x4<f32>,
     time: f32,
@@ -345,12 +469,73 @@ struct Uniforms {{
 struct VertexOutput {{
     @builtin(position) clip_position: vec4<f32>,
     @location(0) alpha: f32,
+    @location(1) distance_t: f32,
+    @location(2) uv: vec2<f32>,
 }};
 
+fn hidden_vertex() -> VertexOutput {{
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(0.0, 0.0, -1000.0, 1.0);
+    out.alpha = 0.0;
+    out.distance_t = 0.0;
+    out.uv = vec2<f32>(0.0);
+    return out;
+}}
+
+// Arrowhead triangle or flow dot quad, built in screen space
+fn directed_vertex(vertex_index: u32, pos_a: vec3<f32>, pos_b: vec3<f32>) -> VertexOutput {{
+    var out: VertexOutput;
+    out.uv = vec2<f32>(0.0);
+
+    let clip_a = uniforms.view_proj * vec4<f32>(pos_a, 1.0);
+    let clip_b = uniforms.view_proj * vec4<f32>(pos_b, 1.0);
+    var dir = clip_b.xy / clip_b.w - clip_a.xy / clip_a.w;
+    if length(dir) < 0.000001 {{
+        dir = vec2<f32>(1.0, 0.0);
+    }}
+    dir = normalize(dir);
+    let normal = vec2<f32>(-dir.y, dir.x);
+
+    var center: vec4<f32>;
+    var offset: vec2<f32>;
+    if vertex_index < 9u {{
+        if !DIRECTED {{
+            return hidden_vertex();
+        }}
+        center = uniforms.view_proj * vec4<f32>(mix(pos_a, pos_b, 0.6), 1.0);
+        switch vertex_index {{
+            case 6u: {{ offset = dir * ARROW_SIZE; }}
+            case 7u: {{ offset = (-dir + normal * 0.6) * ARROW_SIZE; }}
+            default: {{ offset = (-dir - normal * 0.6) * ARROW_SIZE; }}
+        }}
+    }} else {{
+        if FLOW_SPEED <= 0.0 {{
+            return hidden_vertex();
+        }}
+        var quad = array<vec2<f32>, 6>(
+            vec2<f32>(-1.0, -1.0),
+            vec2<f32>( 1.0, -1.0),
+            vec2<f32>(-1.0,  1.0),
+            vec2<f32>(-1.0,  1.0),
+            vec2<f32>( 1.0, -1.0),
+            vec2<f32>( 1.0,  1.0),
+        );
+        let corner = quad[vertex_index - 9u];
+        let t = fract(uniforms.time * FLOW_SPEED);
+        center = uniforms.view_proj * vec4<f32>(mix(pos_a, pos_b, t), 1.0);
+        offset = corner * DOT_SIZE;
+        out.uv = corner;
+    }}
+
+    out.clip_position = vec4<f32>(center.xy + offset * center.w, center.zw);
+    return out;
+}}
+
 @vertex
 fn vs_main(
     @builtin(vertex_index) vertex_index: u32,
     @builtin(instance_index) instance_index: u32,
+    @location(0) distance_t: f32,
 ) -> VertexOutput {{
     var out: VertexOutput;
 
@@ -362,8 +547,13 @@ fn vs_main(
     let alpha = conn_data_a.w;
 
     if alpha < 0.001 {{
-        out.clip_position = vec4<f32>(0.0, 0.0, -1000.0, 1.0);
-        out.alpha = 0.0;
+        return hidden_vertex();
+    }}
+
+    if vertex_index >= 6u {{
+        out = directed_vertex(vertex_index, pos_a, pos_b);
+        out.alpha = alpha;
+        out.distance_t = distance_t;
         return out;
     }}
 
@@ -387,13 +577,22 @@ fn vs_main(
 
     out.clip_position = uniforms.view_proj * vec4<f32>(pos, 1.0);
     out.alpha = alpha * 0.6;
+    out.distance_t = distance_t;
+    out.uv = vec2<f32>(0.0);
 
     return out;
 }}
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {{
-    return vec4<f32>({}, {}, {}, in.alpha);
+    // Round the flow dot; lines and arrowheads have zero uv
+    if length(in.uv) > 1.0 {{
+        discard;
+    }}
+    let near_color = vec3<f32>({}, {}, {});
+    let far_color = vec3<f32>({}, {}, {});
+    let color = mix(near_color, far_color, clamp(in.distance_t, 0.0, 1.0));
+    return vec4<f32>(color, in.alpha);
 }}
-"#, color[0], color[1], color[2])
+"#, near_color[0], near_color[1], near_color[2], far_color[0], far_color[1], far_color[2])
 }
@@ -7,8 +7,12 @@ mod grid;
 mod connections;
 mod wireframe;
 mod trails;
+mod axes;
+mod noise_field;
 
 pub(crate) use grid::GridVisualization;
 pub(crate) use connections::ConnectionVisualization;
 pub(crate) use wireframe::WireframeVisualization;
 pub(crate) use trails::TrailVisualization;
+pub(crate) use axes::AxesVisualization;
+pub(crate) use noise_field::NoiseFieldVisualization;
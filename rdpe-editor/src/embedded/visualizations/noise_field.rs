@@ -0,0 +1,226 @@
+//! Noise field preview for the `NoiseField` rule.
+//!
+//! Draws a short line segment at every point of a coarse grid spanning the
+//! simulation bounds, pointing along the noise vector the rule would apply
+//! there. The noise is evaluated in the vertex shader with the same
+//! `fbm_value3` function the compute shader uses, so the preview animates in
+//! step with the simulation time.
+
+use bytemuck::{Pod, Zeroable};
+use wgpu;
+use wgpu::util::DeviceExt;
+
+use crate::config::NoiseFieldPreview;
+
+/// Number of sample points along each axis of the preview grid.
+const PREVIEW_RESOLUTION: u32 = 12;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct NoisePreviewParams {
+    frequency: f32,
+    time_scale: f32,
+    octaves: u32,
+    resolution: u32,
+    bounds: f32,
+    _pad: [f32; 3],
+}
+
+const NOISE_PREVIEW_SHADER: &str = r#"
+struct Uniforms {
+    view_proj: mat4x4<f32>,
+    time: f32,
+    delta_time: f32,
+};
+
+struct NoisePreviewParams {
+    frequency: f32,
+    time_scale: f32,
+    octaves: u32,
+    resolution: u32,
+    bounds: f32,
+};
+
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+@group(0) @binding(1) var<uniform> params: NoisePreviewParams;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    @builtin(instance_index) instance_index: u32,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    let res = params.resolution;
+    let cell = vec3<u32>(instance_index % res, (instance_index / res) % res, instance_index / (res * res));
+    let spacing = 2.0 * params.bounds / f32(res);
+    let pos_a = -vec3<f32>(params.bounds) + (vec3<f32>(cell) + 0.5) * spacing;
+
+    let noise_pos = pos_a * params.frequency + vec3<f32>(uniforms.time * params.time_scale);
+    let noise = fbm_value3(noise_pos, params.octaves);
+    let pos_b = pos_a + noise * spacing * 0.9;
+
+    var dir = pos_b - pos_a;
+    if length(dir) < 0.0001 {
+        dir = vec3<f32>(0.0, 1.0, 0.0);
+    }
+    dir = normalize(dir);
+    var perp = cross(dir, vec3<f32>(0.0, 1.0, 0.0));
+    if length(perp) < 0.001 {
+        perp = cross(dir, vec3<f32>(1.0, 0.0, 0.0));
+    }
+    perp = normalize(perp) * spacing * 0.03;
+
+    // Fade from tail to head so the direction is readable
+    var pos: vec3<f32>;
+    var head = 0.0;
+    switch vertex_index {
+        case 0u: { pos = pos_a - perp; }
+        case 1u: { pos = pos_a + perp; }
+        case 2u: { pos = pos_b - perp; head = 1.0; }
+        case 3u: { pos = pos_a + perp; }
+        case 4u: { pos = pos_b - perp; head = 1.0; }
+        default: { pos = pos_b + perp; head = 1.0; }
+    }
+
+    out.clip_position = uniforms.view_proj * vec4<f32>(pos, 1.0);
+    out.color = vec4<f32>(abs(dir) * 0.8 + 0.2, mix(0.15, 0.8, head));
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
+"#;
+
+pub(crate) struct NoiseFieldVisualization {
+    pipeline: wgpu::RenderPipeline,
+    bind_group: wgpu::BindGroup,
+    _params_buffer: wgpu::Buffer,
+}
+
+impl NoiseFieldVisualization {
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        uniform_buffer: &wgpu::Buffer,
+        preview: &NoiseFieldPreview,
+        target_format: wgpu::TextureFormat,
+    ) -> Self {
+        let params = NoisePreviewParams {
+            frequency: preview.frequency,
+            time_scale: preview.time_scale,
+            octaves: preview.octaves,
+            resolution: PREVIEW_RESOLUTION,
+            bounds: preview.bounds,
+            _pad: [0.0; 3],
+        };
+
+        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Noise Preview Params Buffer"),
+            contents: bytemuck::cast_slice(&[params]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        // The preview samples the exact noise functions the rule uses
+        let shader_src = format!("{}\n{}", rdpe::shader_utils::VALUE_NOISE_WGSL, NOISE_PREVIEW_SHADER);
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Noise Preview Shader"),
+            source: wgpu::ShaderSource::Wgsl(shader_src.into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Noise Preview Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Noise Preview Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: params_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Noise Preview Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Noise Preview Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: target_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            pipeline,
+            bind_group,
+            _params_buffer: params_buffer,
+        }
+    }
+
+    pub(crate) fn render(&self, render_pass: &mut wgpu::RenderPass<'static>) {
+        let instances = PREVIEW_RESOLUTION * PREVIEW_RESOLUTION * PREVIEW_RESOLUTION;
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..6, 0..instances);
+    }
+}
@@ -8,6 +8,8 @@
 //! - A compute pipeline to update the trail history buffer each frame
 //! - A render pipeline to draw the trail segments as textured quads
 
+use std::collections::HashMap;
+
 use bytemuck;
 use wgpu;
 use wgpu::util::DeviceExt;
@@ -18,13 +20,20 @@ struct TrailParams {
     trail_length: u32,
     particle_stride: u32,
     alive_offset: u32,
+    color_offset: u32,
+    _pad0: u32,
+    _pad1: u32,
+    _pad2: u32,
+    tail_color: vec4<f32>,
 };
 
 @group(0) @binding(0) var<storage, read> particles: array<u32>;
 @group(0) @binding(1) var<storage, read_write> trails: array<vec4<f32>>;
 @group(0) @binding(2) var<uniform> params: TrailParams;
 
-@compute @workgroup_size(256)
+override WORKGROUP_SIZE: u32 = 256u;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let particle_idx = global_id.x;
     if particle_idx >= params.num_particles {
@@ -69,15 +78,22 @@ struct TrailParams {
     trail_length: u32,
     particle_stride: u32,
     alive_offset: u32,
+    color_offset: u32,
+    _pad0: u32,
+    _pad1: u32,
+    _pad2: u32,
+    tail_color: vec4<f32>,
 };
 
 @group(0) @binding(0) var<uniform> uniforms: Uniforms;
 @group(0) @binding(1) var<storage, read> trails: array<vec4<f32>>;
 @group(0) @binding(2) var<uniform> params: TrailParams;
+@group(0) @binding(3) var<storage, read> particles: array<u32>;
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) alpha: f32,
+    @location(1) color: vec3<f32>,
 };
 
 @vertex
@@ -103,6 +119,7 @@ fn vs_main(
     if pos_a.w < 0.001 || pos_b.w < 0.001 {
         out.clip_position = vec4<f32>(0.0, 0.0, -1000.0, 1.0);
         out.alpha = 0.0;
+        out.color = vec3<f32>(0.0);
         return out;
     }
 
@@ -117,6 +134,7 @@ fn vs_main(
     if line_len < 0.0001 {
         out.clip_position = vec4<f32>(0.0, 0.0, -1000.0, 1.0);
         out.alpha = 0.0;
+        out.color = vec3<f32>(0.0);
         return out;
     }
 
@@ -144,15 +162,41 @@ fn vs_main(
     out.clip_position = uniforms.view_proj * vec4<f32>(pos, 1.0);
     out.alpha = base_alpha * min(pos_a.w, pos_b.w);
 
+    // With a tail color (w = 1), blend from the particle's current color toward it as the trail ages
+    if params.tail_color.w > 0.5 {
+        let color_base = particle_idx * params.particle_stride + params.color_offset;
+        let particle_color = vec3<f32>(
+            bitcast<f32>(particles[color_base]),
+            bitcast<f32>(particles[color_base + 1u]),
+            bitcast<f32>(particles[color_base + 2u])
+        );
+        out.color = mix(particle_color, params.tail_color.rgb, segment_t);
+    } else {
+        out.color = vec3<f32>(0.7, 0.85, 1.0);
+    }
+
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return vec4<f32>(0.7, 0.85, 1.0, in.alpha * 0.6);
+    return vec4<f32>(in.color, in.alpha * 0.6);
 }
 "#;
 
+/// Uniform parameters shared by the trail compute and render shaders.
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct TrailParams {
+    num_particles: u32,
+    trail_length: u32,
+    particle_stride: u32,
+    alive_offset: u32,
+    color_offset: u32,
+    _pad: [u32; 3],
+    tail_color: [f32; 4],
+}
+
 /// GPU-accelerated trail visualization for particle systems.
 ///
 /// Maintains a circular buffer of past positions for each particle and renders
@@ -172,8 +216,12 @@ pub(crate) struct TrailVisualization {
     render_bind_group: wgpu::BindGroup,
     /// Number of particles.
     num_particles: u32,
+    /// Compute workgroup size the pipeline was built with.
+    workgroup_size: u32,
     /// Trail length (number of past positions stored).
     trail_length: u32,
+    /// Shader params, kept so a resize only has to change the particle count.
+    params: TrailParams,
 }
 
 impl TrailVisualization {
@@ -183,9 +231,12 @@ impl TrailVisualization {
         particle_buffer: &wgpu::Buffer,
         uniform_buffer: &wgpu::Buffer,
         num_particles: u32,
+        workgroup_size: u32,
         trail_length: u32,
         particle_stride: usize,
         alive_offset: u32,
+        color_offset: u32,
+        tail_color: Option<[f32; 3]>,
         target_format: wgpu::TextureFormat,
     ) -> Self {
         let particle_stride_u32 = particle_stride / 4;
@@ -199,11 +250,22 @@ impl TrailVisualization {
             mapped_at_creation: false,
         });
 
-        // Params buffer: [num_particles, trail_length, particle_stride, alive_offset]
-        let params: [u32; 4] = [num_particles, trail_length, particle_stride_u32 as u32, alive_offset];
+        // Color offset is converted from bytes to a u32 index for the shaders
+        let params = TrailParams {
+            num_particles,
+            trail_length,
+            particle_stride: particle_stride_u32 as u32,
+            alive_offset,
+            color_offset: color_offset / 4,
+            _pad: [0; 3],
+            tail_color: match tail_color {
+                Some([r, g, b]) => [r, g, b, 1.0],
+                None => [0.0; 4],
+            },
+        };
         let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Trail Params Buffer"),
-            contents: bytemuck::cast_slice(&params),
+            contents: bytemuck::bytes_of(&params),
             usage: wgpu::BufferUsages::UNIFORM,
         });
 
@@ -274,7 +336,10 @@ impl TrailVisualization {
             layout: Some(&compute_pipeline_layout),
             module: &compute_shader,
             entry_point: Some("main"),
-            compilation_options: Default::default(),
+            compilation_options: wgpu::PipelineCompilationOptions {
+                constants: &HashMap::from([("WORKGROUP_SIZE".to_string(), workgroup_size as f64)]),
+                ..Default::default()
+            },
             cache: None,
         });
 
@@ -321,6 +386,17 @@ impl TrailVisualization {
                     },
                     count: None,
                 },
+                // Particle buffer (for current color)
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
             ],
         });
 
@@ -331,6 +407,7 @@ impl TrailVisualization {
                 wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() },
                 wgpu::BindGroupEntry { binding: 1, resource: trail_buffer.as_entire_binding() },
                 wgpu::BindGroupEntry { binding: 2, resource: params_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 3, resource: particle_buffer.as_entire_binding() },
             ],
         });
 
@@ -376,18 +453,72 @@ impl TrailVisualization {
             render_pipeline,
             render_bind_group,
             num_particles,
+            workgroup_size,
             trail_length,
+            params,
         }
     }
 
-    pub(crate) fn compute(&self, encoder: &mut wgpu::CommandEncoder) {
+    /// Reallocate the trail history for a new particle count.
+    ///
+    /// Pipelines are kept; trail history is cleared.
+    pub(crate) fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        particle_buffer: &wgpu::Buffer,
+        uniform_buffer: &wgpu::Buffer,
+        num_particles: u32,
+    ) {
+        let buffer_size = (num_particles as usize) * (self.trail_length as usize) * 16;
+        let trail_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Trail Buffer"),
+            size: buffer_size as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        self.params.num_particles = num_particles;
+        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Trail Params Buffer"),
+            contents: bytemuck::bytes_of(&self.params),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        self.compute_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Trail Compute Bind Group"),
+            layout: &self.compute_pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: particle_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: trail_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 2, resource: params_buffer.as_entire_binding() },
+            ],
+        });
+        self.render_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Trail Render Bind Group"),
+            layout: &self.render_pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: trail_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 2, resource: params_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 3, resource: particle_buffer.as_entire_binding() },
+            ],
+        });
+
+        self._trail_buffer = trail_buffer;
+        self.num_particles = num_particles;
+    }
+
+    pub(crate) fn compute(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        timestamp_writes: Option<wgpu::ComputePassTimestampWrites<'_>>,
+    ) {
         let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
             label: Some("Trail Compute Pass"),
-            timestamp_writes: None,
+            timestamp_writes,
         });
         compute_pass.set_pipeline(&self.compute_pipeline);
         compute_pass.set_bind_group(0, &self.compute_bind_group, &[]);
-        compute_pass.dispatch_workgroups(self.num_particles.div_ceil(256), 1, 1);
+        compute_pass.dispatch_workgroups(self.num_particles.div_ceil(self.workgroup_size), 1, 1);
     }
 
     pub(crate) fn render(&self, render_pass: &mut wgpu::RenderPass<'static>) {
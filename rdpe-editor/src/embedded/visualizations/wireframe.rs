@@ -16,19 +16,21 @@ use crate::config::BlendModeConfig;
 /// and each line is rendered as a thin quad to ensure visibility.
 pub(crate) struct WireframeVisualization {
     /// Buffer storing mesh line segments (6 floats per line: x0,y0,z0,x1,y1,z1).
-    _mesh_buffer: wgpu::Buffer,
+    mesh_buffer: wgpu::Buffer,
     /// Number of lines per mesh.
     lines_per_mesh: u32,
     /// Render pipeline.
     pipeline: wgpu::RenderPipeline,
+    /// Shader module, kept for rebuilding the pipeline.
+    shader: wgpu::ShaderModule,
+    /// Pipeline layout, kept for rebuilding the pipeline.
+    pipeline_layout: wgpu::PipelineLayout,
     /// Bind group.
     bind_group: wgpu::BindGroup,
     /// Params buffer.
-    _params_buffer: wgpu::Buffer,
+    params_buffer: wgpu::Buffer,
     /// Number of particles.
     num_particles: u32,
-    /// Base particle size.
-    _base_size: f32,
 }
 
 impl WireframeVisualization {
@@ -39,7 +41,6 @@ impl WireframeVisualization {
         uniform_buffer: &wgpu::Buffer,
         mesh: &rdpe::WireframeMesh,
         line_thickness: f32,
-        particle_size: f32,
         num_particles: u32,
         particle_stride: usize,
         color_offset: Option<u32>,
@@ -58,11 +59,11 @@ impl WireframeVisualization {
             usage: wgpu::BufferUsages::STORAGE,
         });
 
-        // Wireframe params: [line_thickness, lines_per_mesh, base_size, _pad]
+        // Wireframe params: [line_thickness, lines_per_mesh, _pad, _pad]
         let params: [f32; 4] = [
             line_thickness,
             f32::from_bits(lines_per_mesh),
-            particle_size,
+            0.0,
             0.0,
         ];
         let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -160,24 +161,85 @@ impl WireframeVisualization {
             push_constant_ranges: &[],
         });
 
-        // Convert blend mode
-        let blend_state = blend_mode.to_wgpu_blend_state();
+        let pipeline = Self::create_pipeline(device, &shader, &pipeline_layout, target_format, blend_mode);
+
+        Self {
+            mesh_buffer,
+            lines_per_mesh,
+            pipeline,
+            shader,
+            pipeline_layout,
+            bind_group,
+            params_buffer,
+            num_particles,
+        }
+    }
+
+    /// Rebind to a new particle buffer after the particle count changed.
+    pub(crate) fn set_particle_buffer(
+        &mut self,
+        device: &wgpu::Device,
+        particle_buffer: &wgpu::Buffer,
+        uniform_buffer: &wgpu::Buffer,
+        num_particles: u32,
+    ) {
+        self.bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Wireframe Bind Group"),
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: particle_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.mesh_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: self.params_buffer.as_entire_binding(),
+                },
+            ],
+        });
+        self.num_particles = num_particles;
+    }
+
+    /// Rebuild the pipeline with another blend mode, keeping buffers and bind group.
+    pub(crate) fn set_blend_mode(
+        &mut self,
+        device: &wgpu::Device,
+        target_format: wgpu::TextureFormat,
+        blend_mode: &BlendModeConfig,
+    ) {
+        self.pipeline = Self::create_pipeline(device, &self.shader, &self.pipeline_layout, target_format, blend_mode);
+    }
 
-        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+    fn create_pipeline(
+        device: &wgpu::Device,
+        shader: &wgpu::ShaderModule,
+        pipeline_layout: &wgpu::PipelineLayout,
+        target_format: wgpu::TextureFormat,
+        blend_mode: &BlendModeConfig,
+    ) -> wgpu::RenderPipeline {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Wireframe Pipeline"),
-            layout: Some(&pipeline_layout),
+            layout: Some(pipeline_layout),
             vertex: wgpu::VertexState {
-                module: &shader,
+                module: shader,
                 entry_point: Some("vs_main"),
                 buffers: &[],
                 compilation_options: Default::default(),
             },
             fragment: Some(wgpu::FragmentState {
-                module: &shader,
+                module: shader,
                 entry_point: Some("fs_main"),
                 targets: &[Some(wgpu::ColorTargetState {
                     format: target_format,
-                    blend: Some(blend_state),
+                    blend: Some(blend_mode.to_wgpu_blend_state()),
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
                 compilation_options: Default::default(),
@@ -195,17 +257,7 @@ impl WireframeVisualization {
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
             cache: None,
-        });
-
-        Self {
-            _mesh_buffer: mesh_buffer,
-            lines_per_mesh,
-            pipeline,
-            bind_group,
-            _params_buffer: params_buffer,
-            num_particles,
-            _base_size: particle_size,
-        }
+        })
     }
 
     fn generate_shader(
@@ -240,12 +292,14 @@ impl WireframeVisualization {
     view_proj: mat4x4<f32>,
     time: f32,
     delta_time: f32,
+    particle_count: u32,
+    bounds: f32,
+    particle_size: f32,
 }};
 
 struct WireframeParams {{
     line_thickness: f32,
     lines_per_mesh: u32,
-    base_size: f32,
 }};
 
 @group(0) @binding(0) var<uniform> uniforms: Uniforms;
@@ -307,7 +361,7 @@ fn vs_main(
     );
 
     // Transform to world space
-    let mesh_scale = params.base_size * scale;
+    let mesh_scale = uniforms.particle_size * scale;
     let world_a = particle_pos + local_a * mesh_scale;
     let world_b = particle_pos + local_b * mesh_scale;
 
@@ -372,9 +426,9 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {{
         let params: [f32; 4] = [
             thickness,
             f32::from_bits(self.lines_per_mesh),
-            self._base_size,
+            0.0,
             0.0,
         ];
-        queue.write_buffer(&self._params_buffer, 0, bytemuck::cast_slice(&params));
+        queue.write_buffer(&self.params_buffer, 0, bytemuck::cast_slice(&params));
     }
 }
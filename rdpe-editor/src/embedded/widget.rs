@@ -17,7 +17,7 @@
 //! sim.show(ui, wgpu_state, speed);
 //! ```
 
-use glam::{Mat4, Vec3};
+use glam::Vec3;
 use crate::config::SimConfig;
 use crate::shader_gen;
 use crate::shader_validate;
@@ -56,6 +56,8 @@ pub struct EmbeddedSimulation {
     last_frame: std::time::Instant,
     /// Shader compilation error message (if any).
     shader_error: Option<String>,
+    /// Warning from the last particle spawn (if any).
+    spawn_warning: Option<String>,
 }
 
 impl EmbeddedSimulation {
@@ -66,6 +68,7 @@ impl EmbeddedSimulation {
             delta_time: 0.016,
             last_frame: std::time::Instant::now(),
             shader_error: None,
+            spawn_warning: None,
         }
     }
 
@@ -74,6 +77,18 @@ impl EmbeddedSimulation {
         self.shader_error.as_deref()
     }
 
+    /// Get the warning from the last time particles were spawned, if any.
+    pub fn spawn_warning(&self) -> Option<&str> {
+        self.spawn_warning.as_deref()
+    }
+
+    /// Spawn fresh particles, remembering any warning.
+    fn spawn_particles(&mut self, config: &SimConfig) -> Vec<u8> {
+        let spawned = spawn::generate_particles(config);
+        self.spawn_warning = spawned.warning;
+        spawned.data
+    }
+
     /// Clear the shader error.
     pub fn clear_error(&mut self) {
         self.shader_error = None;
@@ -91,18 +106,18 @@ impl EmbeddedSimulation {
             return;
         }
 
-        // Get particle layout from config
-        let layout = config.particle_layout();
-
         // Generate particle data using proper spawn config
-        let particle_data = spawn::generate_particles(config);
+        let particle_data = self.spawn_particles(config);
 
         // Generate shaders using the actual rule system
-        let compute_shader = shader_gen::generate_compute_shader(config);
+        let compute_shader = shader_gen::generate_compute_shader(
+            config,
+            rdpe::select_workgroup_size(&wgpu_render_state.device.limits()),
+        );
         let render_shader = shader_gen::generate_render_shader(config);
 
         // Validate shaders before compiling
-        if let Err(errors) = shader_validate::validate_shaders(&compute_shader, &render_shader) {
+        if let Err(errors) = shader_validate::validate_simulation(config, &compute_shader, &render_shader) {
             let error_msg = errors.iter()
                 .map(|e| e.to_string())
                 .collect::<Vec<_>>()
@@ -114,36 +129,14 @@ impl EmbeddedSimulation {
         // Clear any previous error
         self.shader_error = None;
 
-        let field_registry = config.to_field_registry();
-        let particle_wgsl_struct = config.particle_wgsl_struct();
-        let wireframe_mesh = config.visuals.wireframe.to_mesh();
-        let resources = SimulationResources::new(
+        let resources = create_resources(
             &wgpu_render_state.device,
             &wgpu_render_state.queue,
             wgpu_render_state.target_format,
-            &particle_data,
-            config.particle_count,
-            &layout,
+            config,
             &compute_shader,
             &render_shader,
-            Vec3::from_array(config.visuals.background_color),
-            &config.custom_uniforms,
-            &field_registry,
-            &config.volume_render,
-            config.needs_spatial(),
-            config.spatial_cell_size,
-            config.spatial_resolution,
-            &particle_wgsl_struct,
-            &config.visuals.blend_mode,
-            config.visuals.spatial_grid_opacity,
-            config.visuals.connections_enabled,
-            config.visuals.connections_radius,
-            config.visuals.connections_color,
-            wireframe_mesh.as_ref(),
-            config.visuals.wireframe_thickness,
-            config.particle_size,
-            config.visuals.trail_length,
-            config.mouse.clone(),
+            &particle_data,
         );
 
         wgpu_render_state
@@ -170,11 +163,14 @@ impl EmbeddedSimulation {
         config: &SimConfig,
     ) {
         // Generate new shaders first to validate before any state changes
-        let compute_shader = shader_gen::generate_compute_shader(config);
+        let compute_shader = shader_gen::generate_compute_shader(
+            config,
+            rdpe::select_workgroup_size(&wgpu_render_state.device.limits()),
+        );
         let render_shader = shader_gen::generate_render_shader(config);
 
         // Validate shaders before compiling
-        if let Err(errors) = shader_validate::validate_shaders(&compute_shader, &render_shader) {
+        if let Err(errors) = shader_validate::validate_simulation(config, &compute_shader, &render_shader) {
             let error_msg = errors.iter()
                 .map(|e| e.to_string())
                 .collect::<Vec<_>>()
@@ -199,7 +195,7 @@ impl EmbeddedSimulation {
                 } else {
                     None // Particle count or stride changed, can't preserve
                 };
-                let camera = Some((sim.camera_distance, sim.camera_yaw, sim.camera_pitch));
+                let camera = Some((sim.camera_distance, sim.camera_yaw, sim.camera_pitch, sim.orbit_center));
                 (particles, camera)
             } else {
                 (None, None)
@@ -212,40 +208,18 @@ impl EmbeddedSimulation {
         let particle_data = if let Some(data) = existing_particles {
             data
         } else {
-            spawn::generate_particles(config)
+            self.spawn_particles(config)
         };
 
         // Create new resources
-        let field_registry = config.to_field_registry();
-        let particle_wgsl_struct = config.particle_wgsl_struct();
-        let wireframe_mesh = config.visuals.wireframe.to_mesh();
-        let resources = SimulationResources::new(
+        let resources = create_resources(
             &wgpu_render_state.device,
             &wgpu_render_state.queue,
             wgpu_render_state.target_format,
-            &particle_data,
-            config.particle_count,
-            &layout,
+            config,
             &compute_shader,
             &render_shader,
-            Vec3::from_array(config.visuals.background_color),
-            &config.custom_uniforms,
-            &field_registry,
-            &config.volume_render,
-            config.needs_spatial(),
-            config.spatial_cell_size,
-            config.spatial_resolution,
-            &particle_wgsl_struct,
-            &config.visuals.blend_mode,
-            config.visuals.spatial_grid_opacity,
-            config.visuals.connections_enabled,
-            config.visuals.connections_radius,
-            config.visuals.connections_color,
-            wireframe_mesh.as_ref(),
-            config.visuals.wireframe_thickness,
-            config.particle_size,
-            config.visuals.trail_length,
-            config.mouse.clone(),
+            &particle_data,
         );
 
         // Replace resources
@@ -256,11 +230,12 @@ impl EmbeddedSimulation {
             .insert(resources);
 
         // Restore camera state if we had one
-        if let Some((distance, yaw, pitch)) = old_camera {
+        if let Some((distance, yaw, pitch, orbit_center)) = old_camera {
             if let Some(sim) = wgpu_render_state.renderer.write().callback_resources.get_mut::<SimulationResources>() {
                 sim.camera_distance = distance;
                 sim.camera_yaw = yaw;
                 sim.camera_pitch = pitch;
+                sim.orbit_center = orbit_center;
             }
         }
 
@@ -276,11 +251,14 @@ impl EmbeddedSimulation {
         config: &SimConfig,
     ) {
         // Generate new shaders first to validate
-        let compute_shader = shader_gen::generate_compute_shader(config);
+        let compute_shader = shader_gen::generate_compute_shader(
+            config,
+            rdpe::select_workgroup_size(&wgpu_render_state.device.limits()),
+        );
         let render_shader = shader_gen::generate_render_shader(config);
 
         // Validate shaders before compiling
-        if let Err(errors) = shader_validate::validate_shaders(&compute_shader, &render_shader) {
+        if let Err(errors) = shader_validate::validate_simulation(config, &compute_shader, &render_shader) {
             let error_msg = errors.iter()
                 .map(|e| e.to_string())
                 .collect::<Vec<_>>()
@@ -292,50 +270,25 @@ impl EmbeddedSimulation {
         // Clear any previous error
         self.shader_error = None;
 
-        // Get particle layout from config
-        let layout = config.particle_layout();
-
         // Save camera state before replacing resources
         let old_camera = {
             let resources = wgpu_render_state.renderer.read();
             resources.callback_resources.get::<SimulationResources>()
-                .map(|sim| (sim.camera_distance, sim.camera_yaw, sim.camera_pitch))
+                .map(|sim| (sim.camera_distance, sim.camera_yaw, sim.camera_pitch, sim.orbit_center))
         };
 
         // Always generate fresh particles
-        let particle_data = spawn::generate_particles(config);
+        let particle_data = self.spawn_particles(config);
 
         // Create new resources
-        let field_registry = config.to_field_registry();
-        let particle_wgsl_struct = config.particle_wgsl_struct();
-        let wireframe_mesh = config.visuals.wireframe.to_mesh();
-        let resources = SimulationResources::new(
+        let resources = create_resources(
             &wgpu_render_state.device,
             &wgpu_render_state.queue,
             wgpu_render_state.target_format,
-            &particle_data,
-            config.particle_count,
-            &layout,
+            config,
             &compute_shader,
             &render_shader,
-            Vec3::from_array(config.visuals.background_color),
-            &config.custom_uniforms,
-            &field_registry,
-            &config.volume_render,
-            config.needs_spatial(),
-            config.spatial_cell_size,
-            config.spatial_resolution,
-            &particle_wgsl_struct,
-            &config.visuals.blend_mode,
-            config.visuals.spatial_grid_opacity,
-            config.visuals.connections_enabled,
-            config.visuals.connections_radius,
-            config.visuals.connections_color,
-            wireframe_mesh.as_ref(),
-            config.visuals.wireframe_thickness,
-            config.particle_size,
-            config.visuals.trail_length,
-            config.mouse.clone(),
+            &particle_data,
         );
 
         // Replace resources
@@ -346,17 +299,68 @@ impl EmbeddedSimulation {
             .insert(resources);
 
         // Restore camera state if we had one
-        if let Some((distance, yaw, pitch)) = old_camera {
+        if let Some((distance, yaw, pitch, orbit_center)) = old_camera {
             if let Some(sim) = wgpu_render_state.renderer.write().callback_resources.get_mut::<SimulationResources>() {
                 sim.camera_distance = distance;
                 sim.camera_yaw = yaw;
                 sim.camera_pitch = pitch;
+                sim.orbit_center = orbit_center;
             }
         }
 
         self.initialized = true;
     }
 
+    /// Run `config` offscreen and return one rendered frame as RGBA8 pixels.
+    ///
+    /// Builds throwaway resources, steps the simulation `frames` times at
+    /// 60 fps so particles can settle, then draws a `width` x `height`
+    /// image into an `Rgba8Unorm` texture. The rows are tightly packed
+    /// (`width * height * 4` bytes). Returns an empty vector if the shaders
+    /// fail to validate or the readback fails. Nothing in egui's callback
+    /// resources is touched, so the live viewport keeps running.
+    pub fn render_to_image(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        config: &SimConfig,
+        width: u32,
+        height: u32,
+        frames: u32,
+    ) -> Vec<u8> {
+        const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+        let compute_shader = shader_gen::generate_compute_shader(
+            config,
+            rdpe::select_workgroup_size(&device.limits()),
+        );
+        let render_shader = shader_gen::generate_render_shader(config);
+        if shader_validate::validate_simulation(config, &compute_shader, &render_shader).is_err() {
+            return Vec::new();
+        }
+
+        let particle_data = spawn::generate_particles(config).data;
+        let mut resources = create_resources(
+            device,
+            queue,
+            FORMAT,
+            config,
+            &compute_shader,
+            &render_shader,
+            &particle_data,
+        );
+
+        // Warm up; the last prepare also leaves the uniforms the capture draws with
+        let aspect_ratio = width as f32 / height.max(1) as f32;
+        for _ in 0..frames.max(1) {
+            let commands = resources.prepare(device, queue, 1.0 / 60.0, aspect_ratio);
+            queue.submit(commands);
+        }
+
+        resources
+            .capture_frame(device, queue, FORMAT, width, height)
+            .unwrap_or_default()
+    }
+
     /// Render the simulation viewport in egui.
     ///
     /// Call this in your UI code where you want the viewport to appear.
@@ -381,6 +385,7 @@ impl EmbeddedSimulation {
             if let Some(sim) = renderer.callback_resources.get_mut::<SimulationResources>() {
                 // Resize picking texture to match viewport
                 sim.resize_picking(&wgpu_render_state.device, viewport_width.max(1), viewport_height.max(1));
+                sim.resize_post_process(&wgpu_render_state.device, viewport_width.max(1), viewport_height.max(1));
 
                 // Handle click for particle picking (only on click, not drag)
                 if response.clicked() {
@@ -408,14 +413,7 @@ impl EmbeddedSimulation {
 
                     // Compute view-projection matrix fresh to match current viewport
                     let aspect_ratio = rect.width() / rect.height().max(1.0);
-                    let eye = Vec3::new(
-                        sim.camera_distance * sim.camera_yaw.cos() * sim.camera_pitch.cos(),
-                        sim.camera_distance * sim.camera_pitch.sin(),
-                        sim.camera_distance * sim.camera_yaw.sin() * sim.camera_pitch.cos(),
-                    );
-                    let view = Mat4::look_at_rh(eye, Vec3::ZERO, Vec3::Y);
-                    let proj = Mat4::perspective_rh(45.0_f32.to_radians(), aspect_ratio, 0.1, 100.0);
-                    let view_proj = proj * view;
+                    let (view_proj, _) = sim.view_projection(aspect_ratio);
                     let inv_vp = view_proj.inverse();
 
                     let near_clip = glam::Vec4::new(ndc_x, ndc_y, -1.0, 1.0);
@@ -436,8 +434,9 @@ impl EmbeddedSimulation {
 
                     let ray_dir = (far_point - near_point).normalize();
 
-                    // Pass the ray to the shader - it will check distance from each particle to the ray
-                    sim.set_mouse_state(eye, ray_dir, power_active);
+                    // Pass the ray to the shader - it will check distance from each particle to the ray.
+                    // Start it at the near plane, since orthographic rays don't pass through the eye.
+                    sim.set_mouse_state(near_point, ray_dir, power_active);
                 } else {
                     // Mouse not over viewport
                     sim.set_mouse_state(Vec3::ZERO, Vec3::Z, false);
@@ -487,3 +486,61 @@ impl Default for EmbeddedSimulation {
         Self::new()
     }
 }
+
+/// Create GPU resources for `config` with already generated shaders and particles.
+fn create_resources(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    target_format: wgpu::TextureFormat,
+    config: &SimConfig,
+    compute_shader: &str,
+    render_shader: &str,
+    particle_data: &[u8],
+) -> SimulationResources {
+    let field_registry = config.to_field_registry();
+    let particle_wgsl_struct = config.particle_wgsl_struct();
+    let wireframe_mesh = config.visuals.wireframe.to_mesh();
+    let bloom = config.visuals.bloom.map(|bloom| bloom.to_bloom_config());
+    let tone_map = config.visuals.hdr.then(|| config.visuals.tone_map.to_tone_map_mode());
+    SimulationResources::new(
+        device,
+        queue,
+        target_format,
+        particle_data,
+        config.particle_count,
+        &config.particle_layout(),
+        compute_shader,
+        render_shader,
+        Vec3::from_array(config.visuals.background_color),
+        &config.custom_uniforms,
+        &field_registry,
+        &config.volume_render,
+        config.needs_spatial(),
+        config.spatial_cell_size,
+        config.spatial_resolution,
+        &particle_wgsl_struct,
+        &config.visuals.blend_mode,
+        config.visuals.spatial_grid_opacity,
+        config.visuals.axes_visible,
+        config.visuals.connections_enabled,
+        config.visuals.connections_radius,
+        config.visuals.connections_near_color,
+        config.visuals.connections_far_color.unwrap_or(config.visuals.connections_near_color),
+        config.visuals.connections_directed,
+        config.visuals.connections_flow_speed,
+        wireframe_mesh.as_ref(),
+        config.visuals.wireframe_thickness,
+        config.particle_size,
+        config.visuals.trail_length,
+        config.visuals.trail_tail_color,
+        config.noise_field_preview(),
+        config.mouse.clone(),
+        &shader_validate::custom_code_blocks(config),
+        config.bounds,
+        config.two_d,
+        &config.custom_shaders.post_process_chain,
+        &crate::shader_gen::generate_uniforms_struct(config),
+        bloom.as_ref(),
+        tone_map,
+    )
+}
@@ -0,0 +1,131 @@
+//! Undo/redo history for editor configuration changes
+
+use std::collections::VecDeque;
+
+use crate::config::SimConfig;
+
+/// Maximum number of undo steps kept
+const MAX_HISTORY: usize = 50;
+
+/// Undo and redo stacks of whole-config snapshots
+///
+/// Each entry is the config as it was before a deliberate change. The
+/// oldest undo entry is dropped once the history holds [`MAX_HISTORY`]
+/// snapshots.
+#[derive(Default)]
+pub struct UndoHistory {
+    undo: VecDeque<SimConfig>,
+    redo: VecDeque<SimConfig>,
+}
+
+impl UndoHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the config from before a change. Clears the redo stack.
+    pub fn push(&mut self, previous: SimConfig) {
+        push_capped(&mut self.undo, previous);
+        self.redo.clear();
+    }
+
+    /// Step back: returns the config to restore and keeps `current` for redo
+    pub fn undo(&mut self, current: &SimConfig) -> Option<SimConfig> {
+        let previous = self.undo.pop_back()?;
+        push_capped(&mut self.redo, current.clone());
+        Some(previous)
+    }
+
+    /// Step forward again: returns the config to restore and keeps `current` for undo
+    pub fn redo(&mut self, current: &SimConfig) -> Option<SimConfig> {
+        let next = self.redo.pop_back()?;
+        push_capped(&mut self.undo, current.clone());
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+fn push_capped(stack: &mut VecDeque<SimConfig>, config: SimConfig) {
+    if stack.len() == MAX_HISTORY {
+        stack.pop_front();
+    }
+    stack.push_back(config);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(name: &str) -> SimConfig {
+        SimConfig {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_undo_redo_round_trip() {
+        let mut history = UndoHistory::new();
+        assert!(!history.can_undo());
+        assert!(!history.can_redo());
+
+        history.push(named("a"));
+        let current = named("b");
+        assert!(history.can_undo());
+
+        let restored = history.undo(&current).unwrap();
+        assert_eq!(restored.name, "a");
+        assert!(!history.can_undo());
+        assert!(history.can_redo());
+
+        let redone = history.redo(&restored).unwrap();
+        assert_eq!(redone.name, "b");
+        assert!(history.can_undo());
+        assert!(!history.can_redo());
+
+        assert!(history.redo(&redone).is_none());
+    }
+
+    #[test]
+    fn test_push_after_undo_clears_redo() {
+        let mut history = UndoHistory::new();
+        history.push(named("a"));
+        history.push(named("b"));
+
+        let restored = history.undo(&named("c")).unwrap();
+        assert_eq!(restored.name, "b");
+        assert!(history.can_redo());
+
+        // Branching off from an undone state drops the old future
+        history.push(restored);
+        assert!(!history.can_redo());
+        assert_eq!(history.undo(&named("d")).unwrap().name, "b");
+        assert_eq!(history.undo(&named("b")).unwrap().name, "a");
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let mut history = UndoHistory::new();
+        for i in 0..MAX_HISTORY + 5 {
+            history.push(named(&i.to_string()));
+        }
+
+        let mut current = named("current");
+        let mut steps = 0;
+        while let Some(previous) = history.undo(&current) {
+            current = previous;
+            steps += 1;
+        }
+        assert_eq!(steps, MAX_HISTORY);
+        // The oldest snapshots were dropped first
+        assert_eq!(current.name, "5");
+    }
+}
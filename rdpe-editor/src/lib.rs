@@ -6,20 +6,23 @@
 //! - Embedded simulation viewport for live editing
 //! - A runner binary to execute saved configurations
 
+pub mod capture;
 pub mod code_export;
 pub mod config;
 #[cfg(feature = "egui")]
 pub mod embedded;
+pub mod history;
 pub mod particle;
 pub mod shader_gen;
 pub mod shader_validate;
 pub mod spawn;
 pub mod ui;
 
-pub use code_export::generate_code;
+pub use code_export::{generate_code, generate_code_for_target, generate_csv, CodeExportTarget};
 pub use config::*;
+pub use history::UndoHistory;
 pub use particle::MetaParticle;
 pub use shader_gen::{generate_compute_shader, generate_render_shader};
-pub use spawn::{generate_particles, FieldValue};
+pub use spawn::{generate_particle_range, generate_particles, FieldValue, SpawnedParticles};
 #[cfg(feature = "egui")]
 pub use embedded::{EmbeddedSimulation, SimulationResources, SimulationCallback, ParsedParticle};
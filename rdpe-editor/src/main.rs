@@ -8,16 +8,21 @@ use glam::Vec3;
 
 // Use web-time on WASM for Instant compatibility
 #[cfg(target_arch = "wasm32")]
-use web_time::Instant;
+use web_time::{Instant, SystemTime};
 #[cfg(not(target_arch = "wasm32"))]
-use std::time::Instant;
+use std::time::{Instant, SystemTime};
+use rdpe_editor::capture::{EncodingJob, VideoFormat, VideoRecorder, VideoSettings};
 use rdpe_editor::config::*;
 use rdpe_editor::embedded::{EmbeddedSimulation, SimulationResources, ParsedParticle};
+use rdpe_editor::history::UndoHistory;
+use rdpe_editor::shader_validate::WarnLevel;
 use rdpe_editor::ui::{
     render_custom_panel, render_effects_panel, render_export_button, render_export_window,
-    render_fields_panel, render_mouse_panel, render_particle_fields_panel, render_rules_panel,
-    render_spawn_panel, render_visuals_panel, render_volume_panel, AddUniformState,
-    ExportPanelState, PRESETS,
+    interaction_matrix, render_fields_panel, render_interactions_panel, render_mouse_panel,
+    render_particle_fields_panel, render_rules_panel, render_spawn_panel, render_stats_panel,
+    render_visuals_panel, render_volume_panel, set_interaction_matrix, AddUniformState, ExportPanelState,
+    InteractionsPanelState, MatrixFileRequest, PresetThumbnails, SpawnFileRequest, SpawnPanelState, StatsPanelState,
+    VisualsFileRequest, PRESETS,
 };
 
 /// Sidebar tabs for organizing the editor panels
@@ -26,11 +31,27 @@ enum SidebarTab {
     #[default]
     Spawn,
     Rules,
+    Interactions,
     Particle,
     Fields,
     Visuals,
     Mouse,
     Custom,
+    Stats,
+}
+
+impl SidebarTab {
+    /// Tab holding the settings a validation error is about
+    fn for_error(error: &ConfigError) -> Self {
+        match error {
+            ConfigError::SpatialResolutionNotPowerOfTwo(_) => SidebarTab::Spawn,
+            ConfigError::RuleReferencesUnknownType { .. }
+            | ConfigError::UnknownCustomField { .. }
+            | ConfigError::RuleReferencesUnknownField { .. }
+            | ConfigError::MultipleInteractionMatrices(_) => SidebarTab::Rules,
+            ConfigError::UniformNameConflictsWithBuiltin(_) => SidebarTab::Custom,
+        }
+    }
 }
 
 // ============================================================================
@@ -39,13 +60,34 @@ enum SidebarTab {
 
 #[cfg(not(target_arch = "wasm32"))]
 fn main() -> eframe::Result<()> {
+    use eframe::egui_wgpu::{WgpuConfiguration, WgpuSetup, WgpuSetupCreateNew};
+    use std::sync::Arc;
+
+    // Request timestamp queries when available so the profiler can time GPU
+    // passes, and the adapter's own limits so the compute workgroup size
+    // follows what the hardware supports
+    let default_setup = WgpuSetupCreateNew::default();
+    let base_descriptor = default_setup.device_descriptor.clone();
+    let wgpu_setup = WgpuSetupCreateNew {
+        device_descriptor: Arc::new(move |adapter| {
+            let mut descriptor = base_descriptor(adapter);
+            descriptor.required_features |= adapter.features() & wgpu::Features::TIMESTAMP_QUERY;
+            descriptor.required_limits = adapter.limits();
+            descriptor
+        }),
+        ..default_setup
+    };
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1200.0, 800.0])
             .with_title("RDPE Editor"),
         // Use wgpu renderer for custom painting
         renderer: eframe::Renderer::Wgpu,
-        wgpu_options: eframe::egui_wgpu::WgpuConfiguration::default(),
+        wgpu_options: WgpuConfiguration {
+            wgpu_setup: WgpuSetup::CreateNew(wgpu_setup),
+            ..Default::default()
+        },
         ..Default::default()
     };
 
@@ -124,18 +166,56 @@ struct EditorApp {
     needs_reset: bool,
     /// Track previous background color for live updates
     last_background_color: [f32; 3],
+    /// Track previous blend mode for live updates
+    last_blend_mode: BlendModeConfig,
+    /// Track previous particle size for live updates
+    last_particle_size: f32,
     /// Track previous grid opacity for live updates
     last_grid_opacity: f32,
+    /// Track previous axes visibility for live updates
+    last_axes_visible: bool,
+    /// Track previous volume palette for live transfer function updates
+    last_volume_palette: PaletteConfig,
+    /// Track previous volume transfer function for live updates
+    last_transfer_function: Vec<([f32; 4], f32)>,
+    /// Track previous particle count for live updates
+    last_particle_count: u32,
     /// State for the add uniform UI
     add_uniform_state: AddUniformState,
     /// State for the export panel
     export_panel_state: ExportPanelState,
+    /// State for the spawn panel
+    spawn_panel_state: SpawnPanelState,
+    /// State for the stats panel
+    stats_panel_state: StatsPanelState,
+    /// State for the interactions panel
+    interactions_panel_state: InteractionsPanelState,
+    /// Thumbnails shown in the Presets menu
+    preset_thumbnails: PresetThumbnails,
+    /// Options for the next viewport recording
+    video_settings: VideoSettings,
+    /// Viewport recording in progress
+    video_recorder: Option<VideoRecorder>,
+    /// Finished recording being encoded on a worker thread
+    video_encoding: Option<EncodingJob>,
     /// Currently selected sidebar tab
     selected_tab: SidebarTab,
     /// Debounce timer for auto-rebuild (seconds remaining)
     rebuild_timer: Option<f32>,
     /// Editable copy of selected particle (for live editing)
     editing_particle: Option<(u32, ParsedParticle)>,
+    /// Whether the GPU profiler window is shown (and timing enabled)
+    show_profiler: bool,
+    /// Undo/redo stacks of config snapshots
+    history: UndoHistory,
+    /// Config as of the last history checkpoint
+    history_baseline: SimConfig,
+    /// A deliberate change was made and should be recorded once input settles
+    checkpoint_pending: bool,
+    /// Rule copied from the rules panel context menu
+    clipboard_rule: Option<RuleConfig>,
+    /// Problems found by `SimConfig::validate`, shown next to their tabs
+    config_errors: Vec<ConfigError>,
 }
 
 impl EditorApp {
@@ -149,9 +229,17 @@ impl EditorApp {
         }
 
         let last_background_color = config.visuals.background_color;
+        let last_blend_mode = config.visuals.blend_mode;
+        let last_particle_size = config.particle_size;
         let last_grid_opacity = config.visuals.spatial_grid_opacity;
+        let last_axes_visible = config.visuals.axes_visible;
+        let last_volume_palette = config.volume_render.palette;
+        let last_transfer_function = config.volume_render.transfer_function.clone();
+        let last_particle_count = config.particle_count;
         let applied_config = config.clone();
         let previous_config = config.clone();
+        let history_baseline = config.clone();
+        let config_errors = config.validate();
 
         Self {
             config,
@@ -163,12 +251,31 @@ impl EditorApp {
             needs_rebuild: false,
             needs_reset: false,
             last_background_color,
+            last_blend_mode,
+            last_particle_size,
             last_grid_opacity,
+            last_axes_visible,
+            last_volume_palette,
+            last_transfer_function,
+            last_particle_count,
             add_uniform_state: AddUniformState::default(),
             export_panel_state: ExportPanelState::default(),
+            spawn_panel_state: SpawnPanelState::default(),
+            stats_panel_state: StatsPanelState::default(),
+            interactions_panel_state: InteractionsPanelState::default(),
+            preset_thumbnails: PresetThumbnails::default(),
+            video_settings: VideoSettings::default(),
+            video_recorder: None,
+            video_encoding: None,
             selected_tab: SidebarTab::default(),
             rebuild_timer: None,
             editing_particle: None,
+            show_profiler: false,
+            history: UndoHistory::new(),
+            history_baseline,
+            checkpoint_pending: false,
+            clipboard_rule: None,
+            config_errors,
         }
     }
 }
@@ -178,14 +285,91 @@ impl EditorApp {
         self.status_message = Some((msg.into(), Instant::now()));
     }
 
+    // ========================================================================
+    // Undo/redo
+    // ========================================================================
+
+    /// Mark a deliberate config change for the undo history and re-run
+    /// config validation.
+    ///
+    /// The snapshot is taken by `commit_checkpoint` once no mouse button is
+    /// held and no text field has focus, so a slider drag or a typing
+    /// session becomes a single undo step.
+    fn config_checkpoint(&mut self) {
+        self.checkpoint_pending = true;
+        self.config_errors = self.config.validate();
+    }
+
+    fn commit_checkpoint(&mut self, ctx: &egui::Context) {
+        if !self.checkpoint_pending || ctx.input(|i| i.pointer.any_down()) || ctx.wants_keyboard_input() {
+            return;
+        }
+        self.checkpoint_pending = false;
+        if self.config != self.history_baseline {
+            let previous = std::mem::replace(&mut self.history_baseline, self.config.clone());
+            self.history.push(previous);
+        }
+    }
+
+    fn undo(&mut self) {
+        if let Some(config) = self.history.undo(&self.config) {
+            self.restore_from_history(config);
+            self.show_status("Undo");
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(config) = self.history.redo(&self.config) {
+            self.restore_from_history(config);
+            self.show_status("Redo");
+        }
+    }
+
+    fn restore_from_history(&mut self, config: SimConfig) {
+        self.config = config;
+        self.history_baseline = self.config.clone();
+        self.checkpoint_pending = false;
+        self.config_errors = self.config.validate();
+        self.needs_rebuild = true;
+    }
+
     // ========================================================================
     // Native file operations (using rfd)
     // ========================================================================
 
+    /// Save as TOML for `.toml` paths and JSON otherwise.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn write_config_file(config: &SimConfig, path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+        #[cfg(feature = "toml-config")]
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            return config.save_toml(path);
+        }
+        config.save(path)
+    }
+
+    /// Load TOML for `.toml` paths and JSON otherwise.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn read_config_file(path: &std::path::Path) -> Result<SimConfig, Box<dyn std::error::Error>> {
+        #[cfg(feature = "toml-config")]
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            return SimConfig::load_toml(path);
+        }
+        SimConfig::load(path)
+    }
+
+    /// File dialog with filters for every supported config format.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn config_file_dialog() -> rfd::FileDialog {
+        let dialog = rfd::FileDialog::new();
+        #[cfg(feature = "toml-config")]
+        let dialog = dialog.add_filter("Config", &["json", "toml"]).add_filter("TOML", &["toml"]);
+        dialog.add_filter("JSON", &["json"])
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     fn save_config(&mut self) {
         if let Some(path) = &self.current_file {
-            match self.config.save(path) {
+            match Self::write_config_file(&self.config, std::path::Path::new(path)) {
                 Ok(()) => self.show_status(format!("Saved to {}", path)),
                 Err(e) => self.show_status(format!("Save failed: {}", e)),
             }
@@ -196,13 +380,12 @@ impl EditorApp {
 
     #[cfg(not(target_arch = "wasm32"))]
     fn save_config_as(&mut self) {
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("JSON", &["json"])
+        if let Some(path) = Self::config_file_dialog()
             .set_file_name(format!("{}.json", self.config.name))
             .save_file()
         {
             let path_str = path.display().to_string();
-            match self.config.save(&path) {
+            match Self::write_config_file(&self.config, &path) {
                 Ok(()) => {
                     self.show_status(format!("Saved to {}", path_str));
                     self.current_file = Some(path_str);
@@ -214,12 +397,9 @@ impl EditorApp {
 
     #[cfg(not(target_arch = "wasm32"))]
     fn load_config(&mut self) {
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("JSON", &["json"])
-            .pick_file()
-        {
+        if let Some(path) = Self::config_file_dialog().pick_file() {
             let path_str = path.display().to_string();
-            match SimConfig::load(&path) {
+            match Self::read_config_file(&path) {
                 Ok(config) => {
                     self.config = config;
                     self.current_file = Some(path_str.clone());
@@ -283,6 +463,253 @@ impl EditorApp {
         }
     }
 
+    /// Render the viewport offscreen at `width` x `height` and read back RGBA8 pixels.
+    fn capture_viewport(wgpu_render_state: &egui_wgpu::RenderState, width: u32, height: u32) -> Option<Vec<u8>> {
+        wgpu_render_state
+            .renderer
+            .read()
+            .callback_resources
+            .get::<SimulationResources>()
+            .and_then(|sim| {
+                sim.capture_frame(
+                    &wgpu_render_state.device,
+                    &wgpu_render_state.queue,
+                    wgpu_render_state.target_format,
+                    width,
+                    height,
+                )
+            })
+    }
+
+    /// Render the viewport offscreen and save it as a timestamped PNG.
+    fn take_screenshot(&mut self, wgpu_render_state: &egui_wgpu::RenderState, size: egui::Vec2) {
+        let (width, height) = (size.x.round().max(1.0) as u32, size.y.round().max(1.0) as u32);
+        let Some(pixels) = Self::capture_viewport(wgpu_render_state, width, height) else {
+            self.show_status("Screenshot failed: could not read the viewport");
+            return;
+        };
+
+        let png = match rdpe_editor::capture::encode_png(width, height, &pixels) {
+            Ok(png) => png,
+            Err(e) => {
+                self.show_status(format!("Screenshot failed: {}", e));
+                return;
+            }
+        };
+        let unix_secs = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let file_name = rdpe_editor::capture::timestamped_filename("screenshot", "png", unix_secs);
+        self.save_file(&file_name, &png);
+    }
+
+    /// Capture a frame into the running viewport recording when one is due.
+    ///
+    /// Stops and saves the recording once it reaches its maximum duration.
+    fn update_video_recording(&mut self, wgpu_render_state: &egui_wgpu::RenderState, delta_time: f32) {
+        let Some(recorder) = &mut self.video_recorder else {
+            return;
+        };
+        if recorder.advance(delta_time) {
+            let (width, height) = recorder.size();
+            match Self::capture_viewport(wgpu_render_state, width, height) {
+                Some(pixels) => recorder.push_frame(pixels),
+                None => {
+                    self.video_recorder = None;
+                    self.show_status("Recording failed: could not read the viewport");
+                    return;
+                }
+            }
+        }
+        if recorder.is_full() {
+            self.stop_video_recording();
+        }
+    }
+
+    /// Stop the running recording and start encoding it on a worker thread.
+    fn stop_video_recording(&mut self) {
+        let Some(recorder) = self.video_recorder.take() else {
+            return;
+        };
+        let job = EncodingJob::start(recorder);
+        self.show_status(format!("Encoding {} frames...", job.frame_count()));
+        self.video_encoding = Some(job);
+    }
+
+    /// Save the encoded recording with a timestamped name once the worker is done.
+    fn poll_video_encoding(&mut self) {
+        let Some(job) = &self.video_encoding else {
+            return;
+        };
+        let Some(result) = job.poll() else {
+            let (format, frames) = (job.format(), job.frame_count());
+            self.show_status(format!("Encoding {} frames as {}...", frames, format.name()));
+            return;
+        };
+        let extension = job.format().extension();
+        self.video_encoding = None;
+        let bytes = match result {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                self.show_status(format!("Recording failed: {}", e));
+                return;
+            }
+        };
+        let unix_secs = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let file_name = rdpe_editor::capture::timestamped_filename("recording", extension, unix_secs);
+        self.save_file(&file_name, &bytes);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_file(&mut self, file_name: &str, bytes: &[u8]) {
+        match std::fs::write(file_name, bytes) {
+            Ok(()) => self.show_status(format!("Saved: {}", file_name)),
+            Err(e) => self.show_status(format!("Save failed: {}", e)),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn save_file(&mut self, file_name: &str, bytes: &[u8]) {
+        use wasm_bindgen::JsCast;
+
+        let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+            return;
+        };
+
+        let blob_parts = js_sys::Array::new();
+        blob_parts.push(&js_sys::Uint8Array::from(bytes));
+
+        let options = web_sys::BlobPropertyBag::new();
+        options.set_type(match file_name.rsplit('.').next() {
+            Some("gif") => "image/gif",
+            Some("csv") => "text/csv",
+            Some("json") => "application/json",
+            _ => "image/png",
+        });
+
+        if let Ok(blob) = web_sys::Blob::new_with_u8_array_sequence_and_options(&blob_parts, &options) {
+            if let Ok(url) = web_sys::Url::create_object_url_with_blob(&blob) {
+                if let Ok(anchor) = document.create_element("a") {
+                    let anchor: web_sys::HtmlAnchorElement = anchor.unchecked_into();
+                    anchor.set_href(&url);
+                    anchor.set_download(file_name);
+                    anchor.click();
+                    let _ = web_sys::Url::revoke_object_url(&url);
+                    self.show_status(format!("Saved: {}", file_name));
+                }
+            }
+        }
+    }
+
+    /// Read back the particle buffer and save it as a timestamped CSV file.
+    fn export_csv(&mut self, wgpu_render_state: &egui_wgpu::RenderState) {
+        let data = wgpu_render_state
+            .renderer
+            .read()
+            .callback_resources
+            .get::<SimulationResources>()
+            .and_then(|sim| sim.read_particles(&wgpu_render_state.device, &wgpu_render_state.queue));
+        let Some(data) = data else {
+            self.show_status("Export failed: could not read particle data");
+            return;
+        };
+
+        let csv = rdpe_editor::generate_csv(
+            &data,
+            &self.config.particle_layout(),
+            self.export_panel_state.include_dead,
+        );
+        let unix_secs = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let file_name = rdpe_editor::capture::timestamped_filename("particles", "csv", unix_secs);
+        self.save_file(&file_name, csv.as_bytes());
+    }
+
+    fn save_interaction_matrix(&mut self) {
+        if let Some(json) = interaction_matrix(&self.config.rules).map(|m| m.to_json()) {
+            self.save_file("interaction_matrix.json", json.as_bytes());
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_interaction_matrix(&mut self) {
+        let Some(path) = rfd::FileDialog::new().add_filter("JSON", &["json"]).pick_file() else {
+            return;
+        };
+        let matrix = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| rdpe::InteractionMatrix::from_json(&json).map_err(|e| e.to_string()));
+        match matrix {
+            Ok(matrix) => {
+                set_interaction_matrix(&mut self.config.rules, matrix);
+                self.config_checkpoint();
+                self.show_status(format!("Loaded {}", path.display()));
+            }
+            Err(e) => self.show_status(format!("Load failed: {}", e)),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn load_interaction_matrix(&mut self) {
+        self.show_status("Loading matrix files is not supported on the web");
+    }
+
+    /// Load an OBJ file into the Mesh Surface spawn shape.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_spawn_mesh(&mut self) {
+        let Some(path) = rfd::FileDialog::new().add_filter("OBJ", &["obj"]).pick_file() else {
+            return;
+        };
+        let mesh = std::fs::read(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| rdpe::MeshData::from_obj_bytes(&bytes).map_err(|e| e.to_string()));
+        match mesh {
+            Ok(loaded) => {
+                if let SpawnShape::MeshSurface { mesh, .. } = &mut self.config.spawn.shape {
+                    *mesh = loaded;
+                    self.config_checkpoint();
+                }
+                self.show_status(format!("Loaded {}", path.display()));
+            }
+            Err(e) => self.show_status(format!("Load failed: {}", e)),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn load_spawn_mesh(&mut self) {
+        self.show_status("Loading OBJ files is not supported on the web");
+    }
+
+    /// Load an OBJ file as the wireframe mesh.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_wireframe_mesh(&mut self) {
+        let Some(path) = rfd::FileDialog::new().add_filter("OBJ", &["obj"]).pick_file() else {
+            return;
+        };
+        let mesh = std::fs::read(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| rdpe::MeshData::from_obj_bytes(&bytes).map_err(|e| e.to_string()));
+        match mesh {
+            Ok(mesh) => {
+                self.config.visuals.wireframe = WireframeMeshConfig::Obj(mesh);
+                self.config_checkpoint();
+                self.show_status(format!("Loaded {}", path.display()));
+            }
+            Err(e) => self.show_status(format!("Load failed: {}", e)),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn load_wireframe_mesh(&mut self) {
+        self.show_status("Loading OBJ files is not supported on the web");
+    }
+
     #[cfg(target_arch = "wasm32")]
     fn load_config(&mut self) {
         // For WASM, we need async file reading. We'll trigger a file input click
@@ -301,7 +728,10 @@ impl EditorApp {
         } else {
             // Update applied config on success
             self.applied_config = self.config.clone();
-            self.show_status("Simulation rebuilt");
+            match self.simulation.spawn_warning() {
+                Some(warning) => self.show_status(format!("Simulation rebuilt. {}", warning)),
+                None => self.show_status("Simulation rebuilt"),
+            }
         }
     }
 
@@ -316,7 +746,10 @@ impl EditorApp {
         } else {
             // Update applied config on success
             self.applied_config = self.config.clone();
-            self.show_status("Simulation reset");
+            match self.simulation.spawn_warning() {
+                Some(warning) => self.show_status(format!("Simulation reset. {}", warning)),
+                None => self.show_status("Simulation reset"),
+            }
         }
     }
 }
@@ -330,35 +763,54 @@ impl eframe::App for EditorApp {
         let wgpu_render_state = frame.wgpu_render_state();
         let delta_time = ctx.input(|i| i.stable_dt);
 
+        // Undo/redo shortcuts (text fields keep their own undo while focused)
+        if !ctx.wants_keyboard_input() {
+            let (redo_shift_z, undo, redo_y) = ctx.input_mut(|i| {
+                // Check Shift+Z first: the plain Ctrl+Z pattern also matches with Shift held
+                let redo_shift_z = i.consume_key(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::Z);
+                let undo = i.consume_key(egui::Modifiers::COMMAND, egui::Key::Z);
+                let redo_y = i.consume_key(egui::Modifiers::COMMAND, egui::Key::Y);
+                (redo_shift_z, undo, redo_y)
+            });
+            if undo {
+                self.undo();
+            } else if redo_shift_z || redo_y {
+                self.redo();
+            }
+        }
+
         // Auto-rebuild: detect config changes from previous frame and start/reset debounce timer
         // Compare against previous_config (not applied_config) so we only reset timer on actual changes
         let config_changed = {
-            // Check everything except background_color and custom_uniforms (which are hot-swapped)
+            // Check everything except background_color, blend_mode, particle_size, custom_uniforms and particle_count (which are hot-swapped)
             self.config.name != self.previous_config.name
-                || self.config.particle_count != self.previous_config.particle_count
                 || self.config.bounds != self.previous_config.bounds
-                || self.config.particle_size != self.previous_config.particle_size
                 || self.config.spatial_cell_size != self.previous_config.spatial_cell_size
                 || self.config.spatial_resolution != self.previous_config.spatial_resolution
+                || self.config.skip_dead_neighbors != self.previous_config.skip_dead_neighbors
                 || self.config.spawn != self.previous_config.spawn
                 || self.config.rules != self.previous_config.rules
                 || self.config.vertex_effects != self.previous_config.vertex_effects
-                || self.config.visuals.blend_mode != self.previous_config.visuals.blend_mode
                 || self.config.visuals.shape != self.previous_config.visuals.shape
                 || self.config.visuals.palette != self.previous_config.visuals.palette
                 || self.config.visuals.color_mapping != self.previous_config.visuals.color_mapping
                 || self.config.visuals.trail_length != self.previous_config.visuals.trail_length
+                || self.config.visuals.trail_tail_color != self.previous_config.visuals.trail_tail_color
                 || self.config.visuals.connections_enabled != self.previous_config.visuals.connections_enabled
                 || self.config.visuals.connections_radius != self.previous_config.visuals.connections_radius
                 || self.config.visuals.velocity_stretch != self.previous_config.visuals.velocity_stretch
                 || self.config.visuals.velocity_stretch_factor != self.previous_config.visuals.velocity_stretch_factor
-                // Note: spatial_grid_opacity is hot-swappable, not here
+                // Note: spatial_grid_opacity and axes_visible are hot-swappable, not here
                 || self.config.visuals.wireframe != self.previous_config.visuals.wireframe
                 || self.config.visuals.wireframe_thickness != self.previous_config.visuals.wireframe_thickness
+                || self.config.visuals.bloom != self.previous_config.visuals.bloom
+                || self.config.visuals.hdr != self.previous_config.visuals.hdr
+                || self.config.visuals.tone_map != self.previous_config.visuals.tone_map
                 || self.config.custom_shaders != self.previous_config.custom_shaders
                 || self.config.fields != self.previous_config.fields
                 || self.config.particle_fields != self.previous_config.particle_fields
-                || self.config.volume_render != self.previous_config.volume_render
+                // Note: the volume palette and transfer function are hot-swappable
+                || self.config.volume_render.needs_rebuild(&self.previous_config.volume_render)
         };
 
         if config_changed {
@@ -401,6 +853,26 @@ impl eframe::App for EditorApp {
             self.last_background_color = self.config.visuals.background_color;
         }
 
+        // Live update: blend mode (hot-swappable, rebuilds only the render pipeline)
+        if self.config.visuals.blend_mode != self.last_blend_mode {
+            if let Some(state) = wgpu_render_state {
+                if let Some(sim) = state.renderer.write().callback_resources.get_mut::<rdpe_editor::embedded::SimulationResources>() {
+                    sim.set_blend_mode(&state.device, &self.config.visuals.blend_mode);
+                }
+            }
+            self.last_blend_mode = self.config.visuals.blend_mode;
+        }
+
+        // Live update: particle size (hot-swappable, it's a uniform)
+        if self.config.particle_size != self.last_particle_size {
+            if let Some(state) = wgpu_render_state {
+                if let Some(sim) = state.renderer.write().callback_resources.get_mut::<rdpe_editor::embedded::SimulationResources>() {
+                    sim.set_particle_size(self.config.particle_size);
+                }
+            }
+            self.last_particle_size = self.config.particle_size;
+        }
+
         // Live update: grid opacity (hot-swappable)
         if self.config.visuals.spatial_grid_opacity != self.last_grid_opacity {
             if let Some(state) = wgpu_render_state {
@@ -411,6 +883,66 @@ impl eframe::App for EditorApp {
             self.last_grid_opacity = self.config.visuals.spatial_grid_opacity;
         }
 
+        // Live update: axes visibility (hot-swappable)
+        if self.config.visuals.axes_visible != self.last_axes_visible {
+            if let Some(state) = wgpu_render_state {
+                if let Some(sim) = state.renderer.write().callback_resources.get_mut::<rdpe_editor::embedded::SimulationResources>() {
+                    sim.set_axes_visible(self.config.visuals.axes_visible);
+                }
+            }
+            self.last_axes_visible = self.config.visuals.axes_visible;
+        }
+
+        // Live update: volume transfer function (hot-swappable, rewrites the lookup texture)
+        if self.config.volume_render.palette != self.last_volume_palette
+            || self.config.volume_render.transfer_function != self.last_transfer_function
+        {
+            if let Some(state) = wgpu_render_state {
+                if let Some(sim) = state.renderer.write().callback_resources.get_mut::<rdpe_editor::embedded::SimulationResources>() {
+                    sim.set_volume_transfer_function(&state.queue, &self.config.volume_render);
+                }
+            }
+            self.last_volume_palette = self.config.volume_render.palette;
+            self.last_transfer_function = self.config.volume_render.transfer_function.clone();
+        }
+
+        // Live update: particle count (hot-swappable within buffer capacity;
+        // growing past it resizes the particle buffer once the slider is released)
+        if self.config.particle_count != self.last_particle_count {
+            let dragging = self.spawn_panel_state.particle_count_dragged;
+            let mut applied = false;
+            let mut spawn_warning = None;
+            if let Some(state) = wgpu_render_state {
+                if let Some(sim) = state.renderer.write().callback_resources.get_mut::<rdpe_editor::embedded::SimulationResources>() {
+                    applied = sim.set_num_particles(self.config.particle_count, &state.device, &state.queue);
+                    if !applied && !dragging {
+                        // Only the slots past the current count need spawning
+                        let stride = sim.particle_stride;
+                        let first_new = sim.num_particles;
+                        let fresh = rdpe_editor::generate_particle_range(&self.config, first_new..self.config.particle_count);
+                        sim.resize_particles(&state.device, &state.queue, self.config.particle_count, |i, bytes| {
+                            let start = (i - first_new) as usize * stride;
+                            bytes.copy_from_slice(&fresh.data[start..start + stride]);
+                        });
+                        if let Some(warning) = fresh.warning {
+                            spawn_warning = Some(warning);
+                        }
+                        applied = true;
+                    }
+                }
+            }
+            if let Some(warning) = spawn_warning {
+                self.show_status(warning);
+            }
+            if applied {
+                self.last_particle_count = self.config.particle_count;
+            } else if !dragging {
+                // No running simulation to resize: fall back to a debounced rebuild
+                self.rebuild_timer = Some(REBUILD_DEBOUNCE);
+                self.last_particle_count = self.config.particle_count;
+            }
+        }
+
         // Live update: custom uniform values (hot-swappable)
         if let Some(state) = wgpu_render_state {
             if let Some(sim) = state.renderer.write().callback_resources.get_mut::<rdpe_editor::embedded::SimulationResources>() {
@@ -419,7 +951,11 @@ impl eframe::App for EditorApp {
         }
 
         // Export window (floating)
-        render_export_window(ctx, &mut self.export_panel_state, &self.config, delta_time);
+        if render_export_window(ctx, &mut self.export_panel_state, &self.config, delta_time) {
+            if let Some(state) = wgpu_render_state {
+                self.export_csv(state);
+            }
+        }
 
         // Menu bar
         egui::TopBottomPanel::top("menu").show(ctx, |ui| {
@@ -429,10 +965,12 @@ impl eframe::App for EditorApp {
                         self.config = SimConfig::default();
                         self.current_file = None;
                         self.needs_rebuild = true;
+                        self.config_checkpoint();
                         ui.close_menu();
                     }
                     if ui.button("Open...").clicked() {
                         self.load_config();
+                        self.config_checkpoint();
                         ui.close_menu();
                     }
                     if ui.button("Save").clicked() {
@@ -452,12 +990,39 @@ impl eframe::App for EditorApp {
                     }
                 });
 
+                ui.menu_button("Edit", |ui| {
+                    let undo_button = egui::Button::new("Undo").shortcut_text(ctx.format_shortcut(
+                        &egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z),
+                    ));
+                    if ui.add_enabled(self.history.can_undo(), undo_button).clicked() {
+                        self.undo();
+                        ui.close_menu();
+                    }
+                    let redo_button = egui::Button::new("Redo").shortcut_text(ctx.format_shortcut(
+                        &egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Y),
+                    ));
+                    if ui.add_enabled(self.history.can_redo(), redo_button).clicked() {
+                        self.redo();
+                        ui.close_menu();
+                    }
+                });
+
                 ui.menu_button("Presets", |ui| {
+                    if let Some(state) = wgpu_render_state {
+                        if self.preset_thumbnails.render_next(ctx, state) {
+                            ctx.request_repaint();
+                        }
+                    }
                     for preset in PRESETS {
-                        if ui.button(preset.name).on_hover_text(preset.description).clicked() {
+                        let button = match self.preset_thumbnails.get(preset.name) {
+                            Some(texture) => egui::Button::image_and_text((texture.id(), egui::vec2(48.0, 32.0)), preset.name),
+                            None => egui::Button::new(preset.name),
+                        };
+                        if ui.add(button).on_hover_text(preset.description).clicked() {
                             self.config = (preset.config)();
                             self.current_file = None;
                             self.needs_rebuild = true;
+                            self.config_checkpoint();
                             self.show_status(format!("Loaded preset: {}", preset.name));
                             ui.close_menu();
                         }
@@ -499,6 +1064,23 @@ impl eframe::App for EditorApp {
             });
         });
 
+        // Alive particle count (read back asynchronously by the simulation)
+        let alive_info = wgpu_render_state.as_ref().and_then(|state| {
+            state.renderer.read().callback_resources.get::<SimulationResources>()
+                .and_then(|sim| Some((sim.alive_count()?, sim.num_particles)))
+        });
+
+        // Keep GPU profiling in sync with the toggle (resources are recreated on rebuild)
+        let profiler_info = wgpu_render_state.as_ref().and_then(|state| {
+            let mut renderer = state.renderer.write();
+            renderer.callback_resources.get_mut::<SimulationResources>().map(|sim| {
+                if sim.is_profiling() != self.show_profiler {
+                    sim.set_profiling(self.show_profiler);
+                }
+                (sim.profiling_supported(), sim.last_frame_timings())
+            })
+        });
+
         // Status bar
         egui::TopBottomPanel::bottom("status").show(ctx, |ui| {
             ui.horizontal(|ui| {
@@ -517,6 +1099,14 @@ impl eframe::App for EditorApp {
 
                     ui.separator();
 
+                    if let Some((alive, total)) = alive_info {
+                        ui.label(format!("Alive: {} / {}", alive, total));
+                        ui.separator();
+                    }
+
+                    ui.toggle_value(&mut self.show_profiler, "Profiler");
+                    ui.separator();
+
                     // Show current file
                     if let Some(file) = &self.current_file {
                         ui.label(egui::RichText::new(file).small().weak());
@@ -527,6 +1117,39 @@ impl eframe::App for EditorApp {
             });
         });
 
+        // GPU profiler window
+        if self.show_profiler {
+            egui::Window::new("Profiler")
+                .open(&mut self.show_profiler)
+                .resizable(false)
+                .show(ctx, |ui| match profiler_info {
+                    None => {
+                        ui.label("No simulation running");
+                    }
+                    Some((false, _)) => {
+                        ui.label("GPU timestamp queries are not supported on this device");
+                    }
+                    Some((true, None)) => {
+                        ui.label("Waiting for timings...");
+                    }
+                    Some((true, Some(timings))) => {
+                        egui::Grid::new("profiler_grid").striped(true).show(ui, |ui| {
+                            for pass in rdpe::ProfiledPass::ALL {
+                                ui.label(pass.name());
+                                match timings.get(pass) {
+                                    Some(us) => ui.monospace(format!("{:>8.1} µs", us)),
+                                    None => ui.weak("-"),
+                                };
+                                ui.end_row();
+                            }
+                            ui.strong("Total");
+                            ui.monospace(format!("{:>8.1} µs", timings.total_us()));
+                            ui.end_row();
+                        });
+                    }
+                });
+        }
+
         // Particle Inspector panel (shows when a particle is selected)
         // Get currently selected particle info from GPU
         let selected_info = wgpu_render_state.as_ref().and_then(|state| {
@@ -711,34 +1334,74 @@ impl eframe::App for EditorApp {
                 // Simulation name at top (always visible)
                 ui.horizontal(|ui| {
                     ui.label("Name:");
-                    ui.text_edit_singleline(&mut self.config.name);
+                    if ui.text_edit_singleline(&mut self.config.name).changed() {
+                        self.config_checkpoint();
+                    }
                 });
                 ui.separator();
 
-                // Tab bar
+                // Tab bar; tabs with validation errors get a warning icon
+                let warning_color = egui::Color32::from_rgb(255, 180, 80);
                 ui.horizontal_wrapped(|ui| {
-                    ui.selectable_value(&mut self.selected_tab, SidebarTab::Spawn, "Spawn");
-                    ui.selectable_value(&mut self.selected_tab, SidebarTab::Rules, "Rules");
-                    ui.selectable_value(&mut self.selected_tab, SidebarTab::Particle, "Particle");
-                    ui.selectable_value(&mut self.selected_tab, SidebarTab::Fields, "Fields");
-                    ui.selectable_value(&mut self.selected_tab, SidebarTab::Visuals, "Visuals");
-                    ui.selectable_value(&mut self.selected_tab, SidebarTab::Mouse, "Mouse");
-                    ui.selectable_value(&mut self.selected_tab, SidebarTab::Custom, "Custom");
+                    let tabs = [
+                        (SidebarTab::Spawn, "Spawn"),
+                        (SidebarTab::Rules, "Rules"),
+                        (SidebarTab::Interactions, "Interactions"),
+                        (SidebarTab::Particle, "Particle"),
+                        (SidebarTab::Fields, "Fields"),
+                        (SidebarTab::Visuals, "Visuals"),
+                        (SidebarTab::Mouse, "Mouse"),
+                        (SidebarTab::Custom, "Custom"),
+                        (SidebarTab::Stats, "Stats"),
+                    ];
+                    for (tab, name) in tabs {
+                        let errors: Vec<String> = self
+                            .config_errors
+                            .iter()
+                            .filter(|e| SidebarTab::for_error(e) == tab)
+                            .map(|e| e.to_string())
+                            .collect();
+                        if errors.is_empty() {
+                            ui.selectable_value(&mut self.selected_tab, tab, name);
+                        } else {
+                            let label = egui::RichText::new(format!("⚠ {}", name)).color(warning_color);
+                            ui.selectable_value(&mut self.selected_tab, tab, label)
+                                .on_hover_text(errors.join("\n"));
+                        }
+                    }
                 });
                 ui.separator();
 
                 // Tab content
                 egui::ScrollArea::vertical().show(ui, |ui| {
+                    let mut has_errors = false;
+                    for error in self.config_errors.iter().filter(|e| SidebarTab::for_error(e) == self.selected_tab) {
+                        ui.colored_label(warning_color, format!("⚠ {}", error));
+                        has_errors = true;
+                    }
+                    if has_errors {
+                        ui.separator();
+                    }
+
                     match self.selected_tab {
                         SidebarTab::Spawn => {
-                            render_spawn_panel(ui, &mut self.config);
+                            let mut file_request = None;
+                            if render_spawn_panel(ui, &mut self.config, &mut self.spawn_panel_state, &mut file_request) {
+                                self.config_checkpoint();
+                            }
+                            if file_request == Some(SpawnFileRequest::LoadMesh) {
+                                self.load_spawn_mesh();
+                            }
 
                             // Spatial settings (if needed)
                             if self.config.needs_spatial() {
                                 ui.separator();
                                 ui.heading("Spatial Hashing");
-                                ui.add(egui::Slider::new(&mut self.config.spatial_cell_size, 0.01..=0.5)
-                                    .text("Cell Size"));
+                                if ui.add(egui::Slider::new(&mut self.config.spatial_cell_size, 0.01..=0.5)
+                                    .text("Cell Size")).changed()
+                                {
+                                    self.config_checkpoint();
+                                }
 
                                 // Resolution must be a power of 2
                                 const VALID_RESOLUTIONS: &[u32] = &[8, 16, 32, 64, 128];
@@ -753,38 +1416,92 @@ impl eframe::App for EditorApp {
                                         for (i, &res) in VALID_RESOLUTIONS.iter().enumerate() {
                                             if ui.selectable_value(&mut res_idx, i, format!("{}", res)).clicked() {
                                                 self.config.spatial_resolution = res;
+                                                self.config_checkpoint();
                                             }
                                         }
                                     });
+
+                                if ui.checkbox(&mut self.config.skip_dead_neighbors, "Skip Dead Neighbors")
+                                    .on_hover_text("Leave dead particles out of neighbor rules. Turn off to let the living sense corpses.")
+                                    .changed()
+                                {
+                                    self.config_checkpoint();
+                                }
                             }
                         }
                         SidebarTab::Rules => {
-                            render_rules_panel(ui, &mut self.config.rules);
+                            if render_rules_panel(ui, &mut self.config.rules, &mut self.clipboard_rule) {
+                                self.config_checkpoint();
+                            }
+                        }
+                        SidebarTab::Interactions => {
+                            let mut file_request = None;
+                            if render_interactions_panel(
+                                ui,
+                                &mut self.config.rules,
+                                &mut self.interactions_panel_state,
+                                &mut file_request,
+                            ) {
+                                self.config_checkpoint();
+                            }
+                            match file_request {
+                                Some(MatrixFileRequest::Load) => self.load_interaction_matrix(),
+                                Some(MatrixFileRequest::Save) => self.save_interaction_matrix(),
+                                None => {}
+                            }
                         }
                         SidebarTab::Particle => {
-                            render_particle_fields_panel(ui, &mut self.config);
+                            if render_particle_fields_panel(ui, &mut self.config) {
+                                self.config_checkpoint();
+                            }
                         }
                         SidebarTab::Fields => {
-                            render_fields_panel(ui, &mut self.config.fields);
+                            let mut reset_request = None;
+                            if render_fields_panel(ui, &mut self.config.fields, &mut reset_request) {
+                                self.config_checkpoint();
+                            }
+                            if let Some(reset) = reset_request {
+                                if let Some(wgpu_render_state) = frame.wgpu_render_state() {
+                                    if let Some(sim) = wgpu_render_state
+                                        .renderer
+                                        .write()
+                                        .callback_resources
+                                        .get_mut::<SimulationResources>()
+                                    {
+                                        sim.reset_field_to(&reset.name, reset.value, &wgpu_render_state.queue);
+                                    }
+                                }
+                            }
 
                             ui.separator();
 
                             // Volume rendering panel
                             let num_fields = self.config.fields.len();
-                            render_volume_panel(ui, &mut self.config.volume_render, num_fields);
+                            if render_volume_panel(ui, &mut self.config.volume_render, num_fields) {
+                                self.config_checkpoint();
+                            }
                         }
                         SidebarTab::Visuals => {
-                            let _visuals_changed = render_visuals_panel(ui, &mut self.config);
+                            let mut file_request = None;
+                            if render_visuals_panel(ui, &mut self.config, &mut file_request) {
+                                self.config_checkpoint();
+                            }
+                            if file_request == Some(VisualsFileRequest::LoadWireframeMesh) {
+                                self.load_wireframe_mesh();
+                            }
 
                             ui.separator();
 
                             // Vertex effects
-                            render_effects_panel(ui, &mut self.config.vertex_effects);
+                            if render_effects_panel(ui, &mut self.config.vertex_effects) {
+                                self.config_checkpoint();
+                            }
                         }
                         SidebarTab::Mouse => {
                             let old_power = self.config.mouse.power;
                             let mouse_changed = render_mouse_panel(ui, &mut self.config.mouse);
                             if mouse_changed {
+                                self.config_checkpoint();
                                 // Check if power changed - this requires shader rebuild
                                 if self.config.mouse.power != old_power {
                                     self.needs_rebuild = true;
@@ -804,12 +1521,14 @@ impl eframe::App for EditorApp {
                             }
                         }
                         SidebarTab::Custom => {
-                            render_custom_panel(
+                            if render_custom_panel(
                                 ui,
                                 &mut self.config.custom_uniforms,
                                 &mut self.config.custom_shaders,
                                 &mut self.add_uniform_state,
-                            );
+                            ) {
+                                self.config_checkpoint();
+                            }
 
                             ui.separator();
 
@@ -818,6 +1537,22 @@ impl eframe::App for EditorApp {
                                 render_export_button(ui, &mut self.export_panel_state, &self.config);
                             });
                         }
+                        SidebarTab::Stats => {
+                            // Only read back while this tab is shown
+                            if render_stats_panel(ui, &mut self.stats_panel_state, delta_time) {
+                                if let Some(wgpu_render_state) = frame.wgpu_render_state() {
+                                    let data = wgpu_render_state
+                                        .renderer
+                                        .read()
+                                        .callback_resources
+                                        .get::<SimulationResources>()
+                                        .and_then(|sim| sim.read_particles(&wgpu_render_state.device, &wgpu_render_state.queue));
+                                    if let Some(data) = data {
+                                        self.stats_panel_state.update(&data, &self.config.particle_layout());
+                                    }
+                                }
+                            }
+                        }
                     }
                 });
             });
@@ -832,7 +1567,145 @@ impl eframe::App for EditorApp {
             .show(ctx, |ui| {
                 // Show the simulation viewport
                 if let Some(state) = wgpu_render_state {
+                    let viewport = ui.max_rect();
                     self.simulation.show(ui, state, self.config.speed);
+
+                    if self.video_recorder.is_some() {
+                        self.update_video_recording(state, delta_time);
+                        ui.ctx().request_repaint();
+                    }
+                    if self.video_encoding.is_some() {
+                        self.poll_video_encoding();
+                        ui.ctx().request_repaint();
+                    }
+
+                    // Capture controls in the top-right corner of the viewport
+                    let toolbar_rect = egui::Rect::from_min_max(
+                        viewport.right_top() + egui::vec2(-400.0, 8.0),
+                        viewport.right_top() + egui::vec2(-8.0, 36.0),
+                    );
+                    ui.scope_builder(
+                        egui::UiBuilder::new()
+                            .max_rect(toolbar_rect)
+                            .layout(egui::Layout::right_to_left(egui::Align::Center)),
+                        |ui| {
+                            if ui
+                                .button("📷")
+                                .on_hover_text("Save a screenshot of the viewport")
+                                .clicked()
+                            {
+                                self.take_screenshot(state, viewport.size() * ui.ctx().pixels_per_point());
+                            }
+
+                            let recording = self.video_recorder.is_some();
+                            ui.add_enabled_ui(!recording, |ui| {
+                                ui.menu_button("⚙", |ui| {
+                                    egui::ComboBox::from_label("Format")
+                                        .selected_text(self.video_settings.format.name())
+                                        .show_ui(ui, |ui| {
+                                            for format in VideoFormat::ALL {
+                                                ui.selectable_value(&mut self.video_settings.format, format, format.name());
+                                            }
+                                        });
+                                    ui.add(egui::Slider::new(&mut self.video_settings.fps, 1..=60).text("FPS"));
+                                    ui.add(
+                                        egui::Slider::new(&mut self.video_settings.max_duration, 1.0..=60.0)
+                                            .text("Max Duration (sec)"),
+                                    );
+                                })
+                                .response
+                                .on_hover_text("Recording options");
+                            });
+
+                            let record_label = if recording {
+                                egui::RichText::new("⏹ Stop").color(egui::Color32::RED)
+                            } else {
+                                egui::RichText::new("⏺ Record")
+                            };
+                            let encoding = self.video_encoding.is_some();
+                            if ui
+                                .add_enabled(!encoding, egui::SelectableLabel::new(recording, record_label))
+                                .on_hover_text("Record the viewport to an animated image")
+                                .on_disabled_hover_text("Waiting for the last recording to finish encoding")
+                                .clicked()
+                            {
+                                if recording {
+                                    self.stop_video_recording();
+                                } else {
+                                    // Frames are kept at logical size to bound memory use
+                                    let size = viewport.size();
+                                    self.video_recorder = Some(VideoRecorder::new(
+                                        self.video_settings.clone(),
+                                        size.x.round().max(1.0) as u32,
+                                        size.y.round().max(1.0) as u32,
+                                    ));
+                                }
+                            }
+
+                            if let Some(recorder) = &self.video_recorder {
+                                ui.label(
+                                    egui::RichText::new(format!(
+                                        "{} frames · ~{:.1} MB",
+                                        recorder.frame_count(),
+                                        recorder.estimated_size() as f32 / (1024.0 * 1024.0)
+                                    ))
+                                    .monospace()
+                                    .color(egui::Color32::WHITE),
+                                );
+                            }
+                        },
+                    );
+
+                    // Orbit controls in the top-left corner of the viewport
+                    let orbit_rect = egui::Rect::from_min_max(
+                        viewport.left_top() + egui::vec2(8.0, 8.0),
+                        viewport.left_top() + egui::vec2(560.0, 36.0),
+                    );
+                    let layout = self.config.particle_layout();
+                    ui.scope_builder(
+                        egui::UiBuilder::new()
+                            .max_rect(orbit_rect)
+                            .layout(egui::Layout::left_to_right(egui::Align::Center)),
+                        |ui| {
+                            let mut renderer = state.renderer.write();
+                            let Some(sim) = renderer.callback_resources.get_mut::<SimulationResources>() else {
+                                return;
+                            };
+
+                            let mut center = sim.orbit_center().to_array();
+                            let mut changed = false;
+                            ui.label(egui::RichText::new("Orbit Target").color(egui::Color32::WHITE));
+                            for (value, axis) in center.iter_mut().zip(["X: ", "Y: ", "Z: "]) {
+                                changed |= ui
+                                    .add(egui::DragValue::new(value).speed(0.01).max_decimals(3).prefix(axis))
+                                    .changed();
+                            }
+                            if ui.button("Reset to Origin").clicked() {
+                                center = [0.0; 3];
+                                changed = true;
+                            }
+
+                            let selected_position = sim
+                                .selected_particle_data()
+                                .and_then(|data| ParsedParticle::from_bytes_with_layout(data, &layout))
+                                .map(|particle| particle.position);
+                            if ui
+                                .add_enabled(selected_position.is_some(), egui::Button::new("Focus on Selection"))
+                                .on_hover_text("Orbit around the selected particle")
+                                .on_disabled_hover_text("Click a particle to select it")
+                                .clicked()
+                            {
+                                if let Some(position) = selected_position {
+                                    center = position;
+                                    changed = true;
+                                }
+                            }
+
+                            if changed {
+                                sim.set_orbit_center(Vec3::from_array(center));
+                            }
+                        },
+                    );
                 } else {
                     ui.centered_and_justified(|ui| {
                         ui.label("wgpu not available - simulation requires GPU");
@@ -868,11 +1741,16 @@ impl eframe::App for EditorApp {
                                     egui::ScrollArea::vertical()
                                         .max_height(screen_rect.height() * 0.5)
                                         .show(ui, |ui| {
-                                            ui.add(egui::Label::new(
-                                                egui::RichText::new(&error)
-                                                    .monospace()
-                                                    .color(egui::Color32::LIGHT_RED)
-                                            ).wrap());
+                                            for line in error.lines() {
+                                                // "> " lines quote the user's own code
+                                                let text = egui::RichText::new(line).monospace();
+                                                let text = if line.starts_with("> ") {
+                                                    text.strong().color(egui::Color32::YELLOW)
+                                                } else {
+                                                    text.color(egui::Color32::LIGHT_RED)
+                                                };
+                                                ui.add(egui::Label::new(text).wrap());
+                                            }
                                         });
 
                                     ui.separator();
@@ -890,6 +1768,60 @@ impl eframe::App for EditorApp {
                         self.simulation.clear_error();
                     }
                 }
+
+                // Lint warnings for custom WGSL, below the error overlay
+                if let Some(wgpu_render_state) = frame.wgpu_render_state() {
+                    let warnings = wgpu_render_state
+                        .renderer
+                        .read()
+                        .callback_resources
+                        .get::<SimulationResources>()
+                        .map(|sim| sim.lint_warnings().to_vec())
+                        .unwrap_or_default();
+                    let mut dismiss = false;
+
+                    if !warnings.is_empty() {
+                        egui::Area::new(egui::Id::new("shader_lint_overlay"))
+                            .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -16.0])
+                            .show(ui.ctx(), |ui| {
+                                egui::Frame::popup(ui.style())
+                                    .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(255, 180, 80)))
+                                    .show(ui, |ui| {
+                                        ui.horizontal(|ui| {
+                                            ui.strong("Shader Warnings");
+                                            if ui.small_button("Dismiss").clicked() {
+                                                dismiss = true;
+                                            }
+                                        });
+                                        for (block, warning) in &warnings {
+                                            let color = match warning.severity {
+                                                WarnLevel::Error => egui::Color32::LIGHT_RED,
+                                                WarnLevel::Warning => egui::Color32::from_rgb(255, 180, 80),
+                                            };
+                                            ui.label(
+                                                egui::RichText::new(format!("⚠ {}, {}", block, warning))
+                                                    .small()
+                                                    .color(color),
+                                            );
+                                        }
+                                    });
+                            });
+                    }
+
+                    if dismiss {
+                        if let Some(sim) = wgpu_render_state
+                            .renderer
+                            .write()
+                            .callback_resources
+                            .get_mut::<SimulationResources>()
+                        {
+                            sim.dismiss_lint_warnings();
+                        }
+                    }
+                }
             });
+
+        // Record this frame's deliberate changes once the drag or edit is over
+        self.commit_checkpoint(ctx);
     }
 }
@@ -52,7 +52,7 @@ impl MetaParticle {
 
     /// Get the size of the GPU struct in bytes.
     pub fn gpu_stride() -> usize {
-        std::mem::size_of::<<Self as rdpe::ParticleTrait>::Gpu>()
+        <Self as rdpe::ParticleTrait>::GPU_STRIDE
     }
 }
 
@@ -3,7 +3,7 @@
 //! This module generates WGSL compute and render shaders from SimConfig,
 //! using the actual rdpe rule system for proper behavior.
 
-use crate::config::{SimConfig, ParticleShapeConfig, PaletteConfig, ColorMappingConfig, MousePower};
+use crate::config::{SimConfig, ParticleShapeConfig, PaletteConfig, ColorMappingConfig, MousePower, VertexEffectConfig};
 use rdpe::Rule;
 
 /// Generate field declarations and helper functions from config.
@@ -17,6 +17,25 @@ fn generate_field_code(config: &SimConfig) -> String {
     registry.to_wgsl_declarations(0)
 }
 
+/// Built-in utility functions, plus value noise, SPH kernels, helper
+/// libraries and the interaction matrix when a rule needs them.
+fn generate_shader_utils(rules: &[Rule]) -> String {
+    let mut utils = SHADER_UTILS.to_string();
+    // Validation reports configs with more than one matrix
+    if let Some(matrix) = rules.iter().find_map(|r| r.interaction_matrix()) {
+        utils.push_str(&matrix.to_wgsl("INTERACTION_MATRIX"));
+    }
+    if rules.iter().any(|r| r.requires_value_noise()) {
+        utils.push_str(rdpe::shader_utils::VALUE_NOISE_WGSL);
+    }
+    if rules.iter().any(|r| r.requires_sph_kernels()) {
+        utils.push_str(rdpe::shader_utils::SPH_KERNELS_WGSL);
+    }
+    let helpers: Vec<_> = rules.iter().flat_map(|r| r.required_helpers()).collect();
+    utils.push_str(&rdpe::shader_utils::helpers_wgsl(&helpers));
+    utils
+}
+
 /// Generate mouse power WGSL code based on the selected power.
 fn generate_mouse_power_code(power: &MousePower) -> String {
     power.to_wgsl()
@@ -27,6 +46,34 @@ fn generate_early_mouse_power_code(power: &MousePower) -> String {
     power.to_early_wgsl()
 }
 
+/// Generate the Mouse and Uniforms structs shared by the compute and render shaders.
+///
+/// Matches the buffer written by `build_uniform_data` in the embedded module:
+/// base uniforms, then mouse data, then custom uniforms sorted by name.
+pub fn generate_uniforms_struct(config: &SimConfig) -> String {
+    format!(r#"// Mouse interaction data
+struct Mouse {{
+    ray_origin: vec4<f32>,             // xyz = camera/ray origin
+    ray_dir: vec4<f32>,                // xyz = normalized ray direction
+    down_radius_strength: vec4<f32>,   // x = down (0/1), y = radius, z = strength
+    color: vec4<f32>,                  // rgb = color for paint/spawn
+}}
+
+// Uniforms
+struct Uniforms {{
+    view_proj: mat4x4<f32>,
+    time: f32,
+    delta_time: f32,
+    particle_count: u32,
+    bounds: f32,
+    particle_size: f32,
+    mouse: Mouse,
+{custom_uniform_fields}}}
+"#,
+        custom_uniform_fields = generate_custom_uniform_fields(config),
+    )
+}
+
 /// Generate custom uniform fields for the Uniforms struct.
 fn generate_custom_uniform_fields(config: &SimConfig) -> String {
     if config.custom_uniforms.is_empty() {
@@ -48,27 +95,35 @@ fn generate_custom_uniform_fields(config: &SimConfig) -> String {
 ///
 /// This generates a WGSL compute shader that:
 /// 1. Defines the Particle struct dynamically from config
-/// 2. Defines uniforms (view_proj, time, delta_time, custom uniforms)
+/// 2. Defines uniforms (view_proj, time, delta_time, particle_count, bounds, particle_size, mouse, custom uniforms)
 /// 3. Applies all rules in order
 /// 4. Integrates velocity and updates position
-pub fn generate_compute_shader(config: &SimConfig) -> String {
+///
+/// `workgroup_size` is baked into `@workgroup_size`; pick it with
+/// [`rdpe::select_workgroup_size`] and dispatch with the same value.
+pub fn generate_compute_shader(config: &SimConfig, workgroup_size: u32) -> String {
     let particle_struct = config.particle_wgsl_struct();
 
     // Convert rules to rdpe::Rule and then to WGSL
-    let rules: Vec<Rule> = config.rules.iter().map(|r| r.to_rule()).collect();
+    let rules: Vec<Rule> = config.active_rules().map(|r| r.to_rule()).collect();
 
     // Check if any rules need neighbor access
     let needs_neighbors = rules.iter().any(|r| r.requires_neighbors());
 
     if needs_neighbors {
-        generate_compute_shader_with_neighbors(config, &rules, &particle_struct)
+        generate_compute_shader_with_neighbors(config, &rules, &particle_struct, workgroup_size)
     } else {
-        generate_compute_shader_simple(config, &rules, &particle_struct)
+        generate_compute_shader_simple(config, &rules, &particle_struct, workgroup_size)
     }
 }
 
 /// Generate simple compute shader (no spatial hashing).
-fn generate_compute_shader_simple(config: &SimConfig, rules: &[Rule], particle_struct: &str) -> String {
+fn generate_compute_shader_simple(
+    config: &SimConfig,
+    rules: &[Rule],
+    particle_struct: &str,
+    workgroup_size: u32,
+) -> String {
     // Generate rule code
     let rules_code: String = rules
         .iter()
@@ -78,7 +133,6 @@ fn generate_compute_shader_simple(config: &SimConfig, rules: &[Rule], particle_s
         .join("\n\n");
 
     // Generate custom uniform fields
-    let custom_uniform_fields = generate_custom_uniform_fields(config);
 
     // Generate field code (if any fields are defined)
     let field_code = generate_field_code(config);
@@ -96,37 +150,22 @@ fn generate_compute_shader_simple(config: &SimConfig, rules: &[Rule], particle_s
 // Particle struct
 {particle_struct}
 
-// Mouse interaction data
-struct Mouse {{
-    ray_origin: vec4<f32>,             // xyz = camera/ray origin
-    ray_dir: vec4<f32>,                // xyz = normalized ray direction
-    down_radius_strength: vec4<f32>,   // x = down (0/1), y = radius, z = strength
-    color: vec4<f32>,                  // rgb = color for paint/spawn
-}}
-
-// Uniforms
-struct Uniforms {{
-    view_proj: mat4x4<f32>,
-    time: f32,
-    delta_time: f32,
-    _pad: vec2<f32>,
-    mouse: Mouse,
-{custom_uniform_fields}}}
-
+{uniforms_struct}
 // Bindings
 @group(0) @binding(0) var<storage, read_write> particles: array<Particle>;
 @group(0) @binding(1) var<uniform> uniforms: Uniforms;
+@group(0) @binding(6) var<storage, read_write> alive_count: atomic<u32>;
 
 {field_code}
 // Utility functions
 {shader_utils}
 
 // Main compute shader
-@compute @workgroup_size(256)
+@compute @workgroup_size({workgroup_size}, 1, 1)
 fn main(@builtin(global_invocation_id) id: vec3<u32>) {{
     let idx = id.x;
     let index = idx;  // Alias for rules that use 'index'
-    if (idx >= arrayLength(&particles)) {{
+    if (idx >= uniforms.particle_count) {{
         return;
     }}
 
@@ -177,19 +216,25 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {{
     // Integrate velocity
     // ============================================
     p.position += p.velocity * delta_time;
-
+{flatten_code}
     // Update age
     p.age += delta_time;
 
+    // Count survivors for the status bar
+    if (p.alive == 1u) {{
+        atomicAdd(&alive_count, 1u);
+    }}
+
     // Write back
     particles[idx] = p;
 }}
 "#,
         particle_struct = particle_struct,
-        custom_uniform_fields = custom_uniform_fields,
+        uniforms_struct = generate_uniforms_struct(config),
         field_code = if has_fields { &field_code } else { "// No fields\n" },
-        shader_utils = SHADER_UTILS,
+        shader_utils = generate_shader_utils(rules),
         bounds = config.bounds,
+        flatten_code = flatten_code(config),
         field_count_decl = if has_fields { format!("let field_count = {}u;", config.fields.len()) } else { String::new() },
         rules_code = indent_code(&rules_code, "    "),
         early_mouse_power_code = indent_code(&early_mouse_power_code, "    "),
@@ -198,7 +243,12 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {{
 }
 
 /// Generate compute shader with spatial hashing for neighbor queries.
-fn generate_compute_shader_with_neighbors(config: &SimConfig, rules: &[Rule], particle_struct: &str) -> String {
+fn generate_compute_shader_with_neighbors(
+    config: &SimConfig,
+    rules: &[Rule],
+    particle_struct: &str,
+    workgroup_size: u32,
+) -> String {
     // Separate rules into neighbor and non-neighbor
     let neighbor_rules: Vec<&Rule> = rules.iter().filter(|r| r.requires_neighbors()).collect();
     let simple_rules: Vec<&Rule> = rules.iter().filter(|r| !r.requires_neighbors()).collect();
@@ -211,7 +261,7 @@ fn generate_compute_shader_with_neighbors(config: &SimConfig, rules: &[Rule], pa
         .join("\n\n");
 
     // Generate neighbor rules code, using custom WGSL when available
-    let neighbor_rules_code: String = config.rules.iter()
+    let neighbor_rules_code: String = config.active_rules()
         .filter(|r| r.requires_neighbors())
         .map(|rule_config| {
             // Check if this rule has custom WGSL for the editor
@@ -232,7 +282,6 @@ fn generate_compute_shader_with_neighbors(config: &SimConfig, rules: &[Rule], pa
     let post_neighbor_code = generate_post_neighbor_code(config, &neighbor_rules);
 
     // Generate custom uniform fields
-    let custom_uniform_fields = generate_custom_uniform_fields(config);
 
     // Generate field code (if any fields are defined)
     let field_code = generate_field_code(config);
@@ -242,6 +291,17 @@ fn generate_compute_shader_with_neighbors(config: &SimConfig, rules: &[Rule], pa
     let mouse_power_code = generate_mouse_power_code(&config.mouse.power);
     let early_mouse_power_code = generate_early_mouse_power_code(&config.mouse.power);
 
+    let dead_neighbor_check = if config.skip_dead_neighbors {
+        "
+            // Skip dead neighbors
+            if other.alive == 0u {
+                continue;
+            }
+"
+    } else {
+        ""
+    };
+
     format!(r#"
 // ============================================
 // RDPE Compute Shader (Generated with Spatial Hashing)
@@ -250,23 +310,7 @@ fn generate_compute_shader_with_neighbors(config: &SimConfig, rules: &[Rule], pa
 // Particle struct
 {particle_struct}
 
-// Mouse interaction data
-struct Mouse {{
-    ray_origin: vec4<f32>,             // xyz = camera/ray origin
-    ray_dir: vec4<f32>,                // xyz = normalized ray direction
-    down_radius_strength: vec4<f32>,   // x = down (0/1), y = radius, z = strength
-    color: vec4<f32>,                  // rgb = color for paint/spawn
-}}
-
-// Uniforms
-struct Uniforms {{
-    view_proj: mat4x4<f32>,
-    time: f32,
-    delta_time: f32,
-    _pad: vec2<f32>,
-    mouse: Mouse,
-{custom_uniform_fields}}}
-
+{uniforms_struct}
 // Spatial params for neighbor queries
 struct SpatialParams {{
     cell_size: f32,
@@ -282,6 +326,7 @@ struct SpatialParams {{
 @group(0) @binding(3) var<storage, read> cell_start: array<u32>;
 @group(0) @binding(4) var<storage, read> cell_end: array<u32>;
 @group(0) @binding(5) var<uniform> spatial: SpatialParams;
+@group(0) @binding(6) var<storage, read_write> alive_count: atomic<u32>;
 
 {field_code}
 // ============================================
@@ -300,11 +345,11 @@ struct SpatialParams {{
 {shader_utils}
 
 // Main compute shader
-@compute @workgroup_size(256)
+@compute @workgroup_size({workgroup_size}, 1, 1)
 fn main(@builtin(global_invocation_id) id: vec3<u32>) {{
     let idx = id.x;
     let index = idx;  // Alias for rules that use 'index'
-    if (idx >= arrayLength(&particles)) {{
+    if (idx >= uniforms.particle_count) {{
         return;
     }}
 
@@ -355,7 +400,7 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {{
     var neighbor_count = 0u;
     let max_neighbors = spatial.max_neighbors;
 
-    for (var offset_idx = 0u; offset_idx < 27u; offset_idx++) {{
+    for (var offset_idx = {first_offset}u; offset_idx < {end_offset}u; offset_idx++) {{
         // Early exit if max neighbors reached (0 = unlimited)
         if max_neighbors > 0u && neighbor_count >= max_neighbors {{
             break;
@@ -387,12 +432,7 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {{
             }}
 
             let other = particles[other_idx];
-
-            // Skip dead neighbors
-            if other.alive == 0u {{
-                continue;
-            }}
-
+{dead_neighbor_check}
             let neighbor_pos = other.position;
             let neighbor_vel = other.velocity;
             let diff = my_pos - neighbor_pos;
@@ -427,21 +467,29 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {{
     // Integrate velocity
     // ============================================
     p.position += p.velocity * delta_time;
-
+{flatten_code}
     // Update age
     p.age += delta_time;
 
+    // Count survivors for the status bar
+    if (p.alive == 1u) {{
+        atomicAdd(&alive_count, 1u);
+    }}
+
     // Write back
     particles[idx] = p;
 }}
 "#,
         particle_struct = particle_struct,
-        custom_uniform_fields = custom_uniform_fields,
+        uniforms_struct = generate_uniforms_struct(config),
         field_code = if has_fields { &field_code } else { "// No fields\n" },
         morton_utils = MORTON_WGSL,
         neighbor_utils = NEIGHBOR_UTILS_WGSL,
-        shader_utils = SHADER_UTILS,
+        shader_utils = generate_shader_utils(rules),
         bounds = config.bounds,
+        flatten_code = flatten_code(config),
+        first_offset = if config.two_d { 9 } else { 0 },
+        end_offset = if config.two_d { 18 } else { 27 },
         field_count_decl = if has_fields { format!("let field_count = {}u;", config.fields.len()) } else { String::new() },
         accumulator_vars = indent_code(&accumulator_vars, "    "),
         neighbor_rules_code = indent_code(&neighbor_rules_code, "            "),
@@ -452,11 +500,24 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {{
     )
 }
 
+/// Keep particles on the XY plane in 2D mode.
+///
+/// 2D mode also limits the neighbor search to the 9 cells of the particle's
+/// own Z layer (`NEIGHBOR_OFFSETS` 9 to 17).
+fn flatten_code(config: &SimConfig) -> &'static str {
+    if config.two_d {
+        "\n    // 2D mode: stay on the XY plane\n    p.position.z = 0.0;\n    p.velocity.z = 0.0;\n"
+    } else {
+        ""
+    }
+}
+
 /// Generate accumulator variables needed by neighbor rules.
 fn generate_accumulator_vars(rules: &[&Rule]) -> String {
     // Check which accumulators are needed
     let needs_cohesion = rules.iter().any(|r| matches!(r, Rule::Cohere { .. } | Rule::Flock { .. }));
     let needs_alignment = rules.iter().any(|r| matches!(r, Rule::Align { .. } | Rule::Flock { .. }));
+    let needs_boid = rules.iter().any(|r| matches!(r, Rule::Boid { .. }));
     let needs_chase = rules.iter().any(|r| matches!(r, Rule::Chase { .. }));
     let needs_evade = rules.iter().any(|r| matches!(r, Rule::Evade { .. }));
     let needs_viscosity = rules.iter().any(|r| matches!(r, Rule::Viscosity { .. }));
@@ -471,6 +532,9 @@ fn generate_accumulator_vars(rules: &[&Rule]) -> String {
     if needs_alignment {
         vars.push_str("    var alignment_sum = vec3<f32>(0.0);\n    var alignment_count = 0.0;\n");
     }
+    if needs_boid {
+        vars.push_str("    var boid_separation = vec3<f32>(0.0);\n    var boid_cohesion_sum = vec3<f32>(0.0);\n    var boid_cohesion_count = 0.0;\n    var boid_alignment_sum = vec3<f32>(0.0);\n    var boid_alignment_count = 0.0;\n");
+    }
     if needs_chase {
         vars.push_str("    var chase_nearest_dist = 1000.0;\n    var chase_nearest_pos = vec3<f32>(0.0);\n");
     }
@@ -481,7 +545,7 @@ fn generate_accumulator_vars(rules: &[&Rule]) -> String {
         vars.push_str("    var viscosity_sum = vec3<f32>(0.0);\n    var viscosity_weight = 0.0;\n");
     }
     if needs_pressure {
-        vars.push_str("    var pressure_density = 0.0;\n    var pressure_force = vec3<f32>(0.0);\n");
+        vars.push_str("    var pressure_density = 0.0;\n    var pressure_grad = vec3<f32>(0.0);\n    var pressure_visc = vec3<f32>(0.0);\n");
     }
     if needs_surface_tension {
         vars.push_str("    var surface_neighbor_count = 0.0;\n    var surface_center_sum = vec3<f32>(0.0);\n");
@@ -493,7 +557,7 @@ fn generate_accumulator_vars(rules: &[&Rule]) -> String {
 /// Generate post-neighbor processing code for rules that need it.
 /// Uses custom editor WGSL when available, otherwise falls back to rdpe's Rule::to_post_neighbor_wgsl().
 fn generate_post_neighbor_code(config: &SimConfig, _rules: &[&Rule]) -> String {
-    config.rules.iter()
+    config.active_rules()
         .filter(|r| r.requires_neighbors())
         .map(|rule_config| {
             // Check if this rule has custom post-neighbor WGSL for the editor
@@ -608,12 +672,39 @@ pub fn generate_render_shader(config: &SimConfig) -> String {
     let vertex_effects_code: String = config
         .vertex_effects
         .iter()
-        .map(|effect| effect.to_effect().to_wgsl())
+        .filter_map(|effect| effect.to_effect())
+        .map(|effect| effect.to_wgsl())
         .collect::<Vec<_>>()
         .join("\n");
 
+    // Custom vertex effects run on the final clip-space position
+    let custom_vertex_effects: Vec<&str> = config
+        .vertex_effects
+        .iter()
+        .filter_map(|effect| match effect {
+            VertexEffectConfig::CustomVertex(code) => Some(code.as_str()),
+            _ => None,
+        })
+        .collect();
+    let custom_vertex_effects_code = if custom_vertex_effects.is_empty() {
+        "    out.position = clip_pos;".to_string()
+    } else {
+        let blocks: String = custom_vertex_effects
+            .iter()
+            .map(|code| format!("    {{\n{}\n    }}\n", indent_code(code, "        ")))
+            .collect();
+        format!(r#"
+    // ============================================
+    // Custom vertex effects
+    // ============================================
+    let particle_position = particle_pos;
+    let particle_velocity = particle_vel;
+    let particle_scale = scale;
+    var out_position = clip_pos;
+{blocks}    out.position = out_position;"#)
+    };
+
     // Generate custom uniform fields
-    let custom_uniform_fields = generate_custom_uniform_fields(config);
 
     // Generate palette code
     let (palette_code, color_expr) = generate_palette_code(
@@ -679,12 +770,7 @@ pub fn generate_render_shader(config: &SimConfig) -> String {
 // RDPE Render Shader (Generated)
 // ============================================
 
-struct Uniforms {{
-    view_proj: mat4x4<f32>,
-    time: f32,
-    delta_time: f32,
-{custom_uniform_fields}}}
-
+{uniforms_struct}
 struct VertexOutput {{
     @builtin(position) position: vec4<f32>,
     @location(0) color: vec3<f32>,
@@ -717,7 +803,7 @@ fn vs_main(
     }}
 
     // Base particle size
-    let particle_size = {particle_size:.6} * scale;
+    let particle_size = uniforms.particle_size * scale;
 
     // Quad vertices (triangle strip) - base positions
     var quad_positions = array<vec2<f32>, 4>(
@@ -763,7 +849,7 @@ fn vs_main(
     clip_pos.x += offset.x * clip_pos.w;
     clip_pos.y += offset.y * clip_pos.w;
 
-    out.position = clip_pos;
+{custom_vertex_effects_code}
     out.color = color_mod;
     out.uv = uv;
     out.alpha = 1.0;
@@ -786,12 +872,12 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {{
     return vec4<f32>(frag_color * alpha, alpha);
 }}
 "#,
-        particle_size = config.particle_size,
-        custom_uniform_fields = custom_uniform_fields,
+        uniforms_struct = generate_uniforms_struct(config),
         palette_code = palette_code,
         color_expr = color_expr,
         vertex_effects_code = indent_code(&vertex_effects_code, "    "),
         custom_vertex_code = custom_vertex_code,
+        custom_vertex_effects_code = custom_vertex_effects_code,
         velocity_stretch_code = velocity_stretch_code,
         shape_code = indent_code(shape_code, "    "),
         custom_fragment_code = custom_fragment_code,
@@ -1115,3 +1201,24 @@ fn neighbor_cell_morton(cell: vec3<u32>, offset_idx: u32, grid_res: u32) -> u32
     return morton_encode(u32(neighbor.x), u32(neighbor.y), u32(neighbor.z));
 }
 "#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RuleConfig;
+
+    #[test]
+    fn test_typed_multi_uses_module_matrix() {
+        let mut matrix = rdpe::InteractionMatrix::new(2);
+        matrix.set(0u32, 1u32, 1.0, 0.2);
+        let config = SimConfig {
+            rules: vec![RuleConfig::TypedMulti(matrix)],
+            ..Default::default()
+        };
+
+        let shader = generate_compute_shader(&config, 256);
+        assert!(shader.contains("const INTERACTION_MATRIX: array<array<f32, 2>, 2>"));
+        assert!(shader.contains("apply_typed_interaction(&p, other, uniforms);"));
+        crate::shader_validate::validate_compute_shader(&shader).expect("typed multi shader validates");
+    }
+}
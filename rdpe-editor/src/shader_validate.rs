@@ -3,6 +3,7 @@
 //! This module validates WGSL shaders before they're compiled by wgpu,
 //! providing better error messages and preventing crashes.
 
+use crate::config::{RuleConfig, SimConfig, VertexEffectConfig};
 use naga::front::wgsl;
 use naga::valid::{Capabilities, ValidationFlags, Validator};
 
@@ -11,11 +12,37 @@ use naga::valid::{Capabilities, ValidationFlags, Validator};
 pub struct ShaderError {
     pub message: String,
     pub stage: &'static str,
+    /// 1-based line in the generated shader, when naga reports one
+    pub line: Option<usize>,
+    /// The user's code the error points into, when it could be traced back
+    pub origin: Option<ErrorOrigin>,
+}
+
+/// Location of a shader error within code the user wrote or configured.
+#[derive(Debug, Clone)]
+pub struct ErrorOrigin {
+    /// The custom code block or rule the error comes from
+    pub block: String,
+    /// 1-based line within that block
+    pub line: usize,
+    /// The offending line as written
+    pub code: String,
 }
 
 impl std::fmt::Display for ShaderError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} shader error: {}", self.stage, self.message)
+        match &self.origin {
+            Some(origin) => write!(
+                f,
+                "{} shader error in {}, line {}:\n> {}\n\n{}",
+                self.stage,
+                origin.block,
+                origin.line,
+                origin.code.trim(),
+                self.message
+            ),
+            None => write!(f, "{} shader error: {}", self.stage, self.message),
+        }
     }
 }
 
@@ -37,9 +64,12 @@ fn validate_wgsl(source: &str, stage: &'static str) -> Result<(), ShaderError> {
         Err(err) => {
             // Format the parse error nicely
             let message = err.emit_to_string(source);
+            let line = err.location(source).map(|loc| loc.line_number as usize);
             return Err(ShaderError {
                 message,
                 stage,
+                line,
+                origin: None,
             });
         }
     };
@@ -48,15 +78,116 @@ fn validate_wgsl(source: &str, stage: &'static str) -> Result<(), ShaderError> {
     let mut validator = Validator::new(ValidationFlags::all(), Capabilities::all());
     if let Err(err) = validator.validate(&module) {
         let message = format!("{}", err);
+        let line = err.location(source).map(|loc| loc.line_number as usize);
         return Err(ShaderError {
             message,
             stage,
+            line,
+            origin: None,
         });
     }
 
     Ok(())
 }
 
+/// Validate the config's rule field references, then both shaders.
+///
+/// Missing or mistyped particle fields are reported on their own, since the
+/// naga error they would cause is much harder to read. Shader errors inside
+/// custom code or a rule get an [`ErrorOrigin`] relative to that block.
+pub fn validate_simulation(
+    config: &SimConfig,
+    compute_src: &str,
+    render_src: &str,
+) -> Result<(), Vec<ShaderError>> {
+    if let Err(message) = config.validate_rule_fields() {
+        return Err(vec![ShaderError {
+            message,
+            stage: "Compute",
+            line: None,
+            origin: None,
+        }]);
+    }
+    validate_shaders(compute_src, render_src).map_err(|mut errors| {
+        for error in &mut errors {
+            let source = if error.stage == "Compute" { compute_src } else { render_src };
+            error.origin = error.line.and_then(|line| find_origin(config, error.stage, source, line));
+        }
+        errors
+    })
+}
+
+/// Trace a line of a generated shader back to the block it came from.
+///
+/// Code the user typed is preferred over the generated code of its rule, so
+/// line numbers match what they see in the editor.
+fn find_origin(config: &SimConfig, stage: &str, source: &str, line: usize) -> Option<ErrorOrigin> {
+    let user_blocks = custom_code_blocks(config);
+    let mut generated_blocks: Vec<(String, String)> = Vec::new();
+    if stage == "Compute" {
+        for (i, rule) in config.rules.iter().enumerate().filter(|(_, r)| r.is_enabled()) {
+            let generated = if rule.requires_neighbors() {
+                rule.to_neighbor_wgsl().unwrap_or_else(|| rule.to_rule().to_neighbor_wgsl())
+            } else {
+                rule.to_rule().to_wgsl(config.bounds)
+            };
+            generated_blocks.push((rule_block_name(i, rule), generated));
+        }
+    }
+
+    let source_lines: Vec<&str> = source.lines().collect();
+    user_blocks.into_iter().chain(generated_blocks).find_map(|(block, code)| {
+        let block_line = line_in_block(&source_lines, &code, line.checked_sub(1)?)?;
+        Some(ErrorOrigin {
+            block,
+            line: block_line + 1,
+            code: code.lines().nth(block_line).unwrap_or_default().to_string(),
+        })
+    })
+}
+
+/// Every non-empty piece of WGSL the user typed, named like [`ErrorOrigin::block`].
+pub fn custom_code_blocks(config: &SimConfig) -> Vec<(String, String)> {
+    let mut blocks: Vec<(String, String)> = config
+        .rules
+        .iter()
+        .enumerate()
+        .filter(|(_, rule)| rule.is_enabled())
+        .filter_map(|(i, rule)| Some((rule_block_name(i, rule), rule.custom_code()?.to_string())))
+        .collect();
+    for (i, effect) in config.vertex_effects.iter().enumerate() {
+        if let VertexEffectConfig::CustomVertex(code) = effect {
+            blocks.push((format!("vertex effect {} ({})", i + 1, effect.name()), code.clone()));
+        }
+    }
+    let shaders = &config.custom_shaders;
+    blocks.push(("custom vertex code".to_string(), shaders.vertex_code.clone()));
+    blocks.push(("custom fragment code".to_string(), shaders.fragment_code.clone()));
+    blocks.retain(|(_, code)| !code.trim().is_empty());
+    blocks
+}
+
+fn rule_block_name(index: usize, rule: &RuleConfig) -> String {
+    format!("rule {} ({})", index + 1, rule.name())
+}
+
+/// 0-based line of `block` that source line `line` falls on, if the block
+/// was pasted into the source around it.
+///
+/// Lines are compared trimmed since the generator re-indents code.
+fn line_in_block(source_lines: &[&str], block: &str, line: usize) -> Option<usize> {
+    let block_lines: Vec<&str> = block.lines().collect();
+    if block_lines.iter().all(|l| l.trim().is_empty()) {
+        return None;
+    }
+    let first_start = (line + 1).saturating_sub(block_lines.len());
+    (first_start..=line).find_map(|start| {
+        let candidate = source_lines.get(start..start + block_lines.len())?;
+        let matches = candidate.iter().zip(&block_lines).all(|(a, b)| a.trim() == b.trim());
+        matches.then(|| line - start)
+    })
+}
+
 /// Validate both compute and render shaders, returning all errors.
 pub fn validate_shaders(
     compute_src: &str,
@@ -78,3 +209,355 @@ pub fn validate_shaders(
         Err(errors)
     }
 }
+
+/// How serious a lint finding is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarnLevel {
+    /// Likely to misbehave at runtime (NaN, infinities, flaky comparisons)
+    Warning,
+    /// Will not compile
+    Error,
+}
+
+/// A likely bug found by [`lint`].
+#[derive(Debug, Clone)]
+pub struct LintWarning {
+    pub message: String,
+    /// 1-based line in the linted code
+    pub line: Option<usize>,
+    pub severity: WarnLevel,
+}
+
+impl std::fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Base particle fields that are `vec3<f32>`
+const VEC3_FIELDS: &[&str] = &["p.position", "p.velocity", "p.color"];
+
+/// Base particle fields that are `f32`
+const F32_FIELDS: &[&str] = &["p.age", "p.scale"];
+
+/// Check WGSL snippets for common bugs that naga accepts or reports poorly.
+///
+/// This is a line-by-line heuristic, not a type checker: it looks for
+/// unguarded divisions, `.w` on known `vec3` values, `==`/`!=` on floats and
+/// normalizing a zero vector.
+pub fn lint(wgsl: &str) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+    let mut vec3_names: Vec<String> = VEC3_FIELDS.iter().map(|s| s.to_string()).collect();
+
+    for (i, raw_line) in wgsl.lines().enumerate() {
+        let line = raw_line.split("//").next().unwrap_or_default();
+        let mut warn = |message: String, severity: WarnLevel| {
+            warnings.push(LintWarning { message, line: Some(i + 1), severity });
+        };
+
+        if let Some(name) = declared_vec3(line) {
+            vec3_names.push(name);
+        }
+
+        for divisor in divisors(line) {
+            if !is_guarded_divisor(&divisor) {
+                warn(
+                    format!("division by `{}` may divide by zero; guard it with max({}, 1e-6)", divisor, divisor),
+                    WarnLevel::Warning,
+                );
+            }
+        }
+
+        for name in &vec3_names {
+            for (pos, _) in line.match_indices(name.as_str()) {
+                let before_ok = line[..pos].chars().last().is_none_or(|c| !is_ident_char(c) && c != '.');
+                let rest = &line[pos + name.len()..];
+                let Some(swizzle) = rest.strip_prefix('.') else {
+                    continue;
+                };
+                let swizzle: String = swizzle.chars().take_while(|c| is_ident_char(*c)).collect();
+                if before_ok && is_swizzle(&swizzle) && swizzle.contains(['w', 'a']) {
+                    warn(
+                        format!("`{}.{}`: {} is a vec3 and has no fourth component", name, swizzle, name),
+                        WarnLevel::Error,
+                    );
+                }
+            }
+        }
+
+        for (pos, op) in line.match_indices("==").chain(line.match_indices("!=")) {
+            let lhs = last_operand(&line[..pos]);
+            let rhs = first_operand(&line[pos + op.len()..]);
+            if [lhs, rhs].iter().any(|operand| is_float_literal(operand) || F32_FIELDS.contains(operand)) {
+                warn(
+                    format!("`{} {} {}` compares floats exactly; use abs(a - b) < epsilon", lhs, op, rhs),
+                    WarnLevel::Warning,
+                );
+            }
+        }
+
+        for (pos, _) in line.match_indices("normalize(") {
+            let argument = balanced(&line[pos + "normalize".len()..]);
+            if is_zero_vector(argument) {
+                warn(
+                    format!("`normalize{}` is NaN on some backends; the vector has zero length", argument),
+                    WarnLevel::Warning,
+                );
+            }
+        }
+    }
+
+    warnings
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn is_swizzle(s: &str) -> bool {
+    !s.is_empty() && s.len() <= 4 && (s.chars().all(|c| "xyzw".contains(c)) || s.chars().all(|c| "rgba".contains(c)))
+}
+
+/// Name bound by a `let`/`var` declaration with a `vec3` type or initializer
+fn declared_vec3(line: &str) -> Option<String> {
+    let rest = line.trim_start().strip_prefix("let ").or_else(|| line.trim_start().strip_prefix("var "))?;
+    let name: String = rest.trim_start().chars().take_while(|c| is_ident_char(*c)).collect();
+    let after = rest.trim_start()[name.len()..].trim_start();
+    let ty_or_init = after.strip_prefix(':').or_else(|| after.strip_prefix('='))?.trim_start();
+    (!name.is_empty() && ty_or_init.starts_with("vec3")).then_some(name)
+}
+
+/// Parenthesized group at the start of `s`, including the parentheses
+fn balanced(s: &str) -> &str {
+    let mut depth = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &s[..=i];
+                }
+            }
+            _ if depth == 0 => return "",
+            _ => {}
+        }
+    }
+    s
+}
+
+/// Right-hand operands of every `/` and `/=` on a line
+fn divisors(line: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    for (pos, _) in line.match_indices('/') {
+        let rest = line[pos + 1..].strip_prefix('=').unwrap_or(&line[pos + 1..]);
+        let divisor = first_operand(rest);
+        if !divisor.is_empty() {
+            result.push(divisor.to_string());
+        }
+    }
+    result
+}
+
+/// Operand at the start of `s`: a parenthesized group or a call/identifier/literal
+fn first_operand(s: &str) -> &str {
+    let s = s.trim_start();
+    if s.starts_with('(') {
+        return balanced(s);
+    }
+    let mut end = s
+        .char_indices()
+        .find(|&(_, c)| !(is_ident_char(c) || c == '.'))
+        .map_or(s.len(), |(i, _)| i);
+    // Generic constructors like vec3<f32>(...)
+    if s[end..].starts_with('<') {
+        if let Some(close) = s[end..].find('>') {
+            end += close + 1;
+        }
+    }
+    // Include the argument list of a call like length(v)
+    if s[end..].starts_with('(') {
+        let args = balanced(&s[end..]);
+        return &s[..end + args.len()];
+    }
+    &s[..end]
+}
+
+/// Operand at the end of `s` (identifier, field access or literal)
+fn last_operand(s: &str) -> &str {
+    let s = s.trim_end();
+    let start = s
+        .char_indices()
+        .rev()
+        .find(|&(_, c)| !(is_ident_char(c) || c == '.'))
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    &s[start..]
+}
+
+fn is_float_literal(s: &str) -> bool {
+    let s = s.strip_suffix('f').unwrap_or(s);
+    (s.contains('.') || s.contains('e')) && s.parse::<f32>().is_ok()
+}
+
+/// Whether dividing by `divisor` can't produce a division by zero
+fn is_guarded_divisor(divisor: &str) -> bool {
+    let inner = divisor.trim_start_matches('(').trim_end_matches(')');
+    if let Ok(value) = inner.trim_end_matches(['f', 'u', 'i']).parse::<f32>() {
+        return value != 0.0;
+    }
+    inner.contains("max(") || inner.contains('+') || inner.to_lowercase().contains("eps")
+}
+
+/// Whether `argument` (with parentheses) is a `vec3` built from zeros
+fn is_zero_vector(argument: &str) -> bool {
+    let inner: String = argument.chars().filter(|c| !c.is_whitespace()).collect();
+    let inner = inner.strip_prefix('(').and_then(|s| s.strip_suffix(')')).unwrap_or_default();
+    let Some(args) = inner
+        .strip_prefix("vec3<f32>(")
+        .or_else(|| inner.strip_prefix("vec3f("))
+        .or_else(|| inner.strip_prefix("vec3("))
+        .and_then(|s| s.strip_suffix(')'))
+    else {
+        return false;
+    };
+    args.split(',')
+        .all(|arg| arg.is_empty() || arg.trim_end_matches('f').parse::<f32>() == Ok(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Messages of the findings for `wgsl`, for compact assertions.
+    fn messages(wgsl: &str) -> Vec<String> {
+        lint(wgsl).into_iter().map(|w| w.message).collect()
+    }
+
+    fn custom(code: &str) -> RuleConfig {
+        RuleConfig::Custom { code: code.to_string() }
+    }
+
+    #[test]
+    fn test_line_in_block_at_start_and_end() {
+        let block = "let a = 1.0;\np.velocity *= a;";
+        let source = ["let a = 1.0;", "    p.velocity *= a;", "other();", "let a = 1.0;", "p.velocity *= a;"];
+        assert_eq!(line_in_block(&source, block, 0), Some(0));
+        assert_eq!(line_in_block(&source, block, 1), Some(1));
+        assert_eq!(line_in_block(&source, block, 3), Some(0));
+        assert_eq!(line_in_block(&source, block, 4), Some(1));
+    }
+
+    #[test]
+    fn test_line_in_block_not_found() {
+        let source = ["fn main() {", "    other();", "}"];
+        assert_eq!(line_in_block(&source, "let a = 1.0;\np.velocity *= a;", 1), None);
+        // The line itself matches, but the rest of the block isn't around it
+        let source = ["let a = 1.0;", "other();"];
+        assert_eq!(line_in_block(&source, "let a = 1.0;\np.velocity *= a;", 0), None);
+        // Past the end of the source
+        assert_eq!(line_in_block(&source, "other();", 5), None);
+        // Blank blocks never match
+        assert_eq!(line_in_block(&source, "\n  \n", 0), None);
+    }
+
+    #[test]
+    fn test_find_origin_duplicated_line() {
+        let config = SimConfig {
+            rules: vec![
+                custom("p.velocity *= 0.9;\nlet a = 1.0;"),
+                custom("p.velocity *= 0.9;\nlet b = bad;"),
+            ],
+            ..Default::default()
+        };
+        let source = "fn main() {\n    p.velocity *= 0.9;\n    let a = 1.0;\n    p.velocity *= 0.9;\n    let b = bad;\n}";
+
+        // The shared line is attributed to the block whose neighbours surround it
+        let first = find_origin(&config, "Compute", source, 2).expect("first copy");
+        assert!(first.block.starts_with("rule 1"), "{}", first.block);
+        assert_eq!(first.line, 1);
+
+        let second = find_origin(&config, "Compute", source, 4).expect("second copy");
+        assert!(second.block.starts_with("rule 2"), "{}", second.block);
+        assert_eq!(second.line, 1);
+
+        let error = find_origin(&config, "Compute", source, 5).expect("error line");
+        assert!(error.block.starts_with("rule 2"), "{}", error.block);
+        assert_eq!((error.line, error.code.as_str()), (2, "let b = bad;"));
+
+        assert!(find_origin(&config, "Compute", source, 1).is_none());
+        assert!(find_origin(&config, "Compute", source, 0).is_none());
+    }
+
+    #[test]
+    fn test_lint_unguarded_divisor() {
+        let warnings = lint("let a = 1.0;\nlet d = p.position / dist;");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].line, Some(2));
+        assert_eq!(warnings[0].severity, WarnLevel::Warning);
+        assert!(warnings[0].message.contains("`dist`"));
+
+        assert_eq!(messages("p.velocity /= length(v);").len(), 1);
+    }
+
+    #[test]
+    fn test_lint_guarded_divisor() {
+        assert!(messages("let d = p.position / max(dist, 1e-6);").is_empty());
+        assert!(messages("let d = p.position / (dist + 0.001);").is_empty());
+        assert!(messages("let d = p.position / 2.0;").is_empty());
+        assert!(messages("let d = p.position / EPSILON_DIST;").is_empty());
+        // Comments are ignored
+        assert!(messages("p.velocity *= 0.9; // halve / dist").is_empty());
+    }
+
+    #[test]
+    fn test_lint_vec3_fourth_component() {
+        let warnings = lint("let x = p.velocity.w;");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].severity, WarnLevel::Error);
+
+        // Locally declared vec3s are tracked too
+        assert_eq!(messages("let dir = vec3<f32>(1.0, 0.0, 0.0);\nlet a = dir.xyzw;").len(), 1);
+        assert_eq!(messages("var c: vec3<f32>;\nlet a = c.a;").len(), 1);
+    }
+
+    #[test]
+    fn test_lint_vec3_three_components() {
+        assert!(messages("let x = p.velocity.xyz + p.color.rgb;").is_empty());
+        assert!(messages("let v = vec4<f32>(1.0);\nlet a = v.w;").is_empty());
+        // Only whole names match, not `q.position`
+        assert!(messages("let a = q.position.w;").is_empty());
+    }
+
+    #[test]
+    fn test_lint_float_equality() {
+        let warnings = lint("if p.age == 0.0 { p.scale = 1.0; }");
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("p.age == 0.0"));
+
+        assert_eq!(messages("if p.scale != 1.0 { }").len(), 1);
+        assert_eq!(messages("if x == 0.5f { }").len(), 1);
+    }
+
+    #[test]
+    fn test_lint_integer_equality() {
+        assert!(messages("if p.particle_type == 1u { }").is_empty());
+        assert!(messages("if index != 0 { }").is_empty());
+        assert!(messages("if p.alive == 0u { }").is_empty());
+    }
+
+    #[test]
+    fn test_lint_normalize_zero() {
+        assert_eq!(messages("let n = normalize(vec3<f32>(0.0, 0.0, 0.0));").len(), 1);
+        assert_eq!(messages("let n = normalize(vec3(0.0));").len(), 1);
+        assert_eq!(messages("let n = normalize(vec3f(0.0, 0.0f, 0.0));").len(), 1);
+    }
+
+    #[test]
+    fn test_lint_normalize_nonzero() {
+        assert!(messages("let n = normalize(vec3<f32>(0.0, 1.0, 0.0));").is_empty());
+        assert!(messages("let n = normalize(p.velocity);").is_empty());
+    }
+}
@@ -5,22 +5,69 @@
 //! based on the dynamic ParticleLayout.
 
 use glam::Vec3;
-use crate::config::{SimConfig, SpawnShape, InitialVelocity, ColorMode, ParticleLayout, ParticleFieldType};
+use crate::config::{sample_gradient, SimConfig, SpawnShape, InitialVelocity, ColorMode, ParticleLayout, ParticleFieldType};
 use crate::particle::hsv_to_rgb;
 use rand::Rng;
+use rdpe::{poisson_disc_xz, MeshSurfaceSampler};
+
+/// Initial particle data along with anything the user should know about it.
+pub struct SpawnedParticles {
+    /// GPU-ready particle bytes
+    pub data: Vec<u8>,
+    /// Why fewer particles than requested started alive, if that happened
+    pub warning: Option<String>,
+}
 
 /// Generate initial particle data from config.
 ///
 /// Returns a byte buffer containing GPU-ready particle data.
 /// The layout is determined dynamically from config.particle_layout().
-pub fn generate_particles(config: &SimConfig) -> Vec<u8> {
+pub fn generate_particles(config: &SimConfig) -> SpawnedParticles {
+    generate_particle_range(config, 0..config.particle_count)
+}
+
+/// Generate the particles at `indices` out of `config.particle_count`.
+///
+/// Each particle is spawned as it would be by [`generate_particles`], so
+/// index-dependent placement (gradients, Poisson disc points) lines up with a
+/// full spawn. Used to fill only the new slots when the particle count grows.
+pub fn generate_particle_range(config: &SimConfig, indices: std::ops::Range<u32>) -> SpawnedParticles {
     let mut rng = rand::thread_rng();
     let spawn = &config.spawn;
     let layout = config.particle_layout();
 
-    let mut data = Vec::with_capacity(config.particle_count as usize * layout.stride);
+    let mut data = Vec::with_capacity(indices.len() * layout.stride);
+
+    // Built once so each particle only needs a binary search over the triangles
+    let mesh_sampler = match &spawn.shape {
+        SpawnShape::MeshSurface { mesh, .. } => Some(MeshSurfaceSampler::new(mesh)),
+        _ => None,
+    };
+    let gradient_stops = spawn.color_mode.gradient_stops();
+    let mut warning = None;
+    let poisson_points = match &spawn.shape {
+        SpawnShape::PoissonDisc { min_distance, max_attempts } => {
+            let points = poisson_disc_xz(
+                config.bounds,
+                *min_distance,
+                *max_attempts,
+                config.particle_count as usize,
+                rng.gen(),
+            );
+            if points.len() < config.particle_count as usize {
+                warning = Some(format!(
+                    "Poisson disc spacing {} only fits {} of {} particles; the rest start dead",
+                    min_distance,
+                    points.len(),
+                    config.particle_count
+                ));
+            }
+            points
+        }
+        _ => Vec::new(),
+    };
 
-    for i in 0..config.particle_count {
+    for i in indices {
         // Generate position based on spawn shape
         let position = match &spawn.shape {
             SpawnShape::Cube { size } => {
@@ -55,6 +102,12 @@ pub fn generate_particles(config: &SimConfig) -> Vec<u8> {
                     (rng.gen::<f32>() - 0.5) * *depth,
                 )
             }
+            SpawnShape::MeshSurface { offset, .. } => {
+                let sampler = mesh_sampler.as_ref().expect("sampler is built for mesh shapes");
+                let (point, normal) = sampler.sample(rng.gen(), rng.gen(), rng.gen());
+                point + normal * *offset
+            }
+            SpawnShape::PoissonDisc { .. } => poisson_points.get(i as usize).copied().unwrap_or(Vec3::ZERO),
         };
 
         // Generate velocity based on config
@@ -107,11 +160,9 @@ pub fn generate_particles(config: &SimConfig) -> Vec<u8> {
                 let speed = velocity.length();
                 hsv_to_rgb((speed * 2.0).fract(), 0.9, 0.9)
             }
-            ColorMode::Gradient { start, end } => {
+            ColorMode::Gradient { .. } | ColorMode::Rainbow => {
                 let t = i as f32 / config.particle_count.max(1) as f32;
-                let start = Vec3::from_array(*start);
-                let end = Vec3::from_array(*end);
-                start.lerp(end, t)
+                Vec3::from_array(sample_gradient(gradient_stops.as_deref().unwrap_or_default(), t))
             }
         };
 
@@ -119,17 +170,21 @@ pub fn generate_particles(config: &SimConfig) -> Vec<u8> {
         let particle_type = pick_particle_type(&mut rng, &spawn.type_weights);
 
         // Generate particle bytes
-        let particle_bytes = generate_particle_bytes(
+        let mut particle_bytes = generate_particle_bytes(
             &layout,
             position,
             velocity,
             color,
             particle_type,
         );
+        // Particles the Poisson disc packing had no room for
+        if matches!(spawn.shape, SpawnShape::PoissonDisc { .. }) && i as usize >= poisson_points.len() {
+            write_u32(&mut particle_bytes, layout.alive_offset, 0);
+        }
         data.extend_from_slice(&particle_bytes);
     }
 
-    data
+    SpawnedParticles { data, warning }
 }
 
 /// Pick a particle type based on weight distribution.
@@ -346,3 +401,28 @@ fn random_direction<R: Rng>(rng: &mut R) -> Vec3 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poisson_overflow_is_reported() {
+        let mut config = SimConfig { particle_count: 500, ..SimConfig::default() };
+        config.spawn.shape = SpawnShape::PoissonDisc { min_distance: 0.5, max_attempts: 30 };
+        let spawned = generate_particles(&config);
+        assert_eq!(spawned.data.len(), 500 * config.particle_layout().stride);
+        assert!(spawned.warning.unwrap().contains("of 500 particles"));
+
+        config.spawn.shape = SpawnShape::Cube { size: 0.5 };
+        assert!(generate_particles(&config).warning.is_none());
+    }
+
+    #[test]
+    fn test_particle_range_spawns_only_those_slots() {
+        let config = SimConfig { particle_count: 500, ..SimConfig::default() };
+        let stride = config.particle_layout().stride;
+        assert_eq!(generate_particle_range(&config, 400..500).data.len(), 100 * stride);
+        assert!(generate_particle_range(&config, 500..500).data.is_empty());
+    }
+}
@@ -1,8 +1,12 @@
 //! UI panel for custom uniforms and shaders
 
 use eframe::egui;
+use egui_plot::{Line, Plot, Points};
 use std::collections::HashMap;
-use crate::config::{CustomShaderConfig, UniformValueConfig};
+use crate::config::{evaluate_keyframes, sort_keyframes, CustomShaderConfig, UniformValueConfig};
+
+/// Screen distance in points within which a click grabs a keyframe
+const KEYFRAME_GRAB_RADIUS: f32 = 10.0;
 
 /// Uniform type options for the dropdown
 #[derive(Clone, Copy, PartialEq)]
@@ -11,6 +15,9 @@ pub enum UniformType {
     Vec2,
     Vec3,
     Vec4,
+    I32,
+    U32,
+    AnimatedF32,
 }
 
 impl UniformType {
@@ -20,6 +27,9 @@ impl UniformType {
             UniformType::Vec2 => "vec2",
             UniformType::Vec3 => "vec3",
             UniformType::Vec4 => "vec4",
+            UniformType::I32 => "i32",
+            UniformType::U32 => "u32",
+            UniformType::AnimatedF32 => "f32 curve",
         }
     }
 
@@ -29,6 +39,11 @@ impl UniformType {
             UniformType::Vec2 => UniformValueConfig::Vec2([0.0, 0.0]),
             UniformType::Vec3 => UniformValueConfig::Vec3([0.0, 0.0, 0.0]),
             UniformType::Vec4 => UniformValueConfig::Vec4([0.0, 0.0, 0.0, 1.0]),
+            UniformType::I32 => UniformValueConfig::I32(0),
+            UniformType::U32 => UniformValueConfig::U32(0),
+            UniformType::AnimatedF32 => UniformValueConfig::AnimatedF32 {
+                keyframes: vec![(0.0, 0.0), (2.0, 1.0), (4.0, 0.0)],
+            },
         }
     }
 
@@ -38,6 +53,9 @@ impl UniformType {
             UniformValueConfig::Vec2(_) => UniformType::Vec2,
             UniformValueConfig::Vec3(_) => UniformType::Vec3,
             UniformValueConfig::Vec4(_) => UniformType::Vec4,
+            UniformValueConfig::I32(_) => UniformType::I32,
+            UniformValueConfig::U32(_) => UniformType::U32,
+            UniformValueConfig::AnimatedF32 { .. } => UniformType::AnimatedF32,
         }
     }
 }
@@ -57,13 +75,15 @@ impl Default for AddUniformState {
     }
 }
 
-/// Render the custom uniforms and shaders panel
+/// Render the custom uniforms and shaders panel. Returns true if the
+/// uniforms or shaders changed.
 pub fn render_custom_panel(
     ui: &mut egui::Ui,
     custom_uniforms: &mut HashMap<String, UniformValueConfig>,
     custom_shaders: &mut CustomShaderConfig,
     add_uniform_state: &mut AddUniformState,
-) {
+) -> bool {
+    let mut changed = false;
     // Custom Uniforms Section
     ui.heading("Custom Uniforms");
 
@@ -91,6 +111,9 @@ pub fn render_custom_panel(
                         ui.selectable_value(&mut add_uniform_state.uniform_type, UniformType::Vec2, "vec2");
                         ui.selectable_value(&mut add_uniform_state.uniform_type, UniformType::Vec3, "vec3");
                         ui.selectable_value(&mut add_uniform_state.uniform_type, UniformType::Vec4, "vec4");
+                        ui.selectable_value(&mut add_uniform_state.uniform_type, UniformType::I32, "i32");
+                        ui.selectable_value(&mut add_uniform_state.uniform_type, UniformType::U32, "u32");
+                        ui.selectable_value(&mut add_uniform_state.uniform_type, UniformType::AnimatedF32, "f32 curve");
                     });
 
                 let name_valid = !add_uniform_state.name.is_empty()
@@ -104,6 +127,7 @@ pub fn render_custom_panel(
                         add_uniform_state.uniform_type.default_value(),
                     );
                     add_uniform_state.name.clear();
+                    changed = true;
                 }
             });
         });
@@ -135,7 +159,7 @@ pub fn render_custom_panel(
                     });
 
                     // Value editor
-                    render_uniform_value(ui, value);
+                    changed |= render_uniform_value(ui, name, value);
                 });
         });
         ui.add_space(2.0);
@@ -143,6 +167,7 @@ pub fn render_custom_panel(
 
     if let Some(name) = to_remove {
         custom_uniforms.remove(&name);
+        changed = true;
     }
 
     if custom_uniforms.is_empty() {
@@ -163,13 +188,13 @@ pub fn render_custom_panel(
         egui::ScrollArea::vertical()
             .max_height(150.0)
             .show(ui, |ui| {
-                ui.add(
+                changed |= ui.add(
                     egui::TextEdit::multiline(&mut custom_shaders.vertex_code)
                         .code_editor()
                         .desired_width(f32::INFINITY)
                         .desired_rows(6)
                         .hint_text("// Custom vertex shader code\n// e.g.: size_mult *= 1.0 + 0.2 * sin(uniforms.time);"),
-                );
+                ).changed();
             });
     });
 
@@ -183,14 +208,75 @@ pub fn render_custom_panel(
         egui::ScrollArea::vertical()
             .max_height(150.0)
             .show(ui, |ui| {
-                ui.add(
+                changed |= ui.add(
                     egui::TextEdit::multiline(&mut custom_shaders.fragment_code)
                         .code_editor()
                         .desired_width(f32::INFINITY)
                         .desired_rows(6)
                         .hint_text("// Custom fragment shader code\n// e.g.: frag_color *= vec3(1.0, 0.5, 0.0);"),
-                );
+                ).changed();
+            });
+    });
+
+    ui.add_space(4.0);
+
+    // Post-process chain
+    ui.collapsing("Post-Process Chain", |ui| {
+        ui.label(egui::RichText::new("Fullscreen passes run top to bottom; each samples the previous one through scene, scene_sampler, in.uv").small().weak());
+        ui.add_space(2.0);
+
+        let chain = &mut custom_shaders.post_process_chain;
+        let mut to_remove: Option<usize> = None;
+        let mut to_swap: Option<(usize, usize)> = None;
+        let len = chain.len();
+
+        for (i, code) in chain.iter_mut().enumerate() {
+            ui.push_id(("post_process_pass", i), |ui| {
+                egui::Frame::new()
+                    .fill(ui.visuals().extreme_bg_color)
+                    .inner_margin(6.0)
+                    .corner_radius(4.0)
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.strong(format!("Pass {}", i + 1));
+
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                if ui.small_button("X").clicked() {
+                                    to_remove = Some(i);
+                                }
+                                if ui.add_enabled(i + 1 < len, egui::Button::new("\u{2193}").small()).clicked() {
+                                    to_swap = Some((i, i + 1));
+                                }
+                                if ui.add_enabled(i > 0, egui::Button::new("\u{2191}").small()).clicked() {
+                                    to_swap = Some((i - 1, i));
+                                }
+                            });
+                        });
+
+                        changed |= ui.add(
+                            egui::TextEdit::multiline(code)
+                                .code_editor()
+                                .desired_width(f32::INFINITY)
+                                .desired_rows(4),
+                        ).changed();
+                    });
             });
+            ui.add_space(2.0);
+        }
+
+        if let Some((a, b)) = to_swap {
+            chain.swap(a, b);
+            changed = true;
+        }
+        if let Some(i) = to_remove {
+            chain.remove(i);
+            changed = true;
+        }
+
+        if ui.button("Add Pass").clicked() {
+            chain.push("let color = textureSample(scene, scene_sampler, in.uv);\nreturn color;".to_string());
+            changed = true;
+        }
     });
 
     // Show reference for custom uniforms
@@ -202,40 +288,163 @@ pub fn render_custom_panel(
                 ui.label(egui::RichText::new(format!(
                     "  uniforms.{}: {}",
                     name,
-                    UniformType::from_value(value).name()
+                    value.wgsl_type()
                 )).small().code());
             }
         });
     }
+
+    changed
 }
 
-fn render_uniform_value(ui: &mut egui::Ui, value: &mut UniformValueConfig) {
+fn render_uniform_value(ui: &mut egui::Ui, name: &str, value: &mut UniformValueConfig) -> bool {
+    let mut changed = false;
     match value {
         UniformValueConfig::F32(v) => {
-            ui.add(egui::DragValue::new(v).speed(0.01).prefix("Value: "));
+            changed |= ui.add(egui::DragValue::new(v).speed(0.01).prefix("Value: ")).changed();
         }
         UniformValueConfig::Vec2(v) => {
             ui.horizontal(|ui| {
-                ui.add(egui::DragValue::new(&mut v[0]).speed(0.01).prefix("X: "));
-                ui.add(egui::DragValue::new(&mut v[1]).speed(0.01).prefix("Y: "));
+                changed |= ui.add(egui::DragValue::new(&mut v[0]).speed(0.01).prefix("X: ")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut v[1]).speed(0.01).prefix("Y: ")).changed();
             });
         }
         UniformValueConfig::Vec3(v) => {
             ui.horizontal(|ui| {
-                ui.add(egui::DragValue::new(&mut v[0]).speed(0.01).prefix("X: "));
-                ui.add(egui::DragValue::new(&mut v[1]).speed(0.01).prefix("Y: "));
-                ui.add(egui::DragValue::new(&mut v[2]).speed(0.01).prefix("Z: "));
+                changed |= ui.add(egui::DragValue::new(&mut v[0]).speed(0.01).prefix("X: ")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut v[1]).speed(0.01).prefix("Y: ")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut v[2]).speed(0.01).prefix("Z: ")).changed();
             });
         }
         UniformValueConfig::Vec4(v) => {
             ui.horizontal(|ui| {
-                ui.add(egui::DragValue::new(&mut v[0]).speed(0.01).prefix("X: "));
-                ui.add(egui::DragValue::new(&mut v[1]).speed(0.01).prefix("Y: "));
+                changed |= ui.add(egui::DragValue::new(&mut v[0]).speed(0.01).prefix("X: ")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut v[1]).speed(0.01).prefix("Y: ")).changed();
             });
             ui.horizontal(|ui| {
-                ui.add(egui::DragValue::new(&mut v[2]).speed(0.01).prefix("Z: "));
-                ui.add(egui::DragValue::new(&mut v[3]).speed(0.01).prefix("W: "));
+                changed |= ui.add(egui::DragValue::new(&mut v[2]).speed(0.01).prefix("Z: ")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut v[3]).speed(0.01).prefix("W: ")).changed();
             });
         }
+        UniformValueConfig::I32(v) => {
+            changed |= ui.add(egui::DragValue::new(v).speed(0.1).prefix("Value: ")).changed();
+        }
+        UniformValueConfig::U32(v) => {
+            changed |= ui.add(egui::DragValue::new(v).speed(0.1).prefix("Value: ")).changed();
+        }
+        UniformValueConfig::AnimatedF32 { keyframes } => {
+            let open_id = ui.id().with("curve_editor_open");
+            let mut open = ui.data(|d| d.get_temp::<bool>(open_id).unwrap_or(false));
+            ui.horizontal(|ui| {
+                ui.label(format!("{} keyframes", keyframes.len()));
+                if ui.button("Curve Editor").clicked() {
+                    open = !open;
+                }
+            });
+            let edited = egui::Window::new(format!("Curve: {}", name))
+                .id(ui.id().with("curve_editor"))
+                .open(&mut open)
+                .default_width(360.0)
+                .show(ui.ctx(), |ui| render_curve_editor(ui, keyframes));
+            changed |= edited.and_then(|r| r.inner).unwrap_or(false);
+            ui.data_mut(|d| d.insert_temp(open_id, open));
+        }
     }
+    changed
+}
+
+/// Keyframe editor for an animated uniform.
+///
+/// Drag a point to move its keyframe, double-click empty space to add one,
+/// right-click a point to remove it. Keyframes stay sorted by time because a
+/// dragged keyframe can't pass its neighbors; they are re-sorted every frame
+/// in case they came in out of order. Returns true if a keyframe changed.
+fn render_curve_editor(ui: &mut egui::Ui, keyframes: &mut Vec<(f32, f32)>) -> bool {
+    sort_keyframes(keyframes);
+
+    ui.label(
+        egui::RichText::new("Drag points to move keyframes, double-click to add, right-click to remove")
+            .small()
+            .weak(),
+    );
+
+    let dragged_id = ui.id().with("dragged_keyframe");
+    let mut dragged: Option<usize> = ui.data(|d| d.get_temp(dragged_id));
+
+    let end_time = keyframes.last().map_or(1.0, |&(t, _)| t.max(1.0));
+    let samples = 200;
+    let curve: Vec<[f64; 2]> = (0..=samples)
+        .map(|i| {
+            let t = end_time * i as f32 / samples as f32;
+            [t as f64, evaluate_keyframes(keyframes, t) as f64]
+        })
+        .collect();
+    let points: Vec<[f64; 2]> = keyframes.iter().map(|&(t, v)| [t as f64, v as f64]).collect();
+
+    let edited = Plot::new(ui.id().with("curve_plot"))
+        .height(180.0)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .allow_boxed_zoom(false)
+        .allow_double_click_reset(false)
+        .x_axis_label("time (s)")
+        .show(ui, |plot_ui| {
+            plot_ui.line(Line::new(curve));
+            plot_ui.points(Points::new(points).radius(5.0));
+
+            let response = plot_ui.response().clone();
+            let Some(pointer) = plot_ui.pointer_coordinate() else {
+                return false;
+            };
+            let mut changed = false;
+            let pointer_screen = plot_ui.screen_from_plot(pointer);
+            let nearest = keyframes
+                .iter()
+                .enumerate()
+                .map(|(i, &(t, v))| {
+                    let screen = plot_ui.screen_from_plot(egui_plot::PlotPoint::new(t, v));
+                    (i, screen.distance(pointer_screen))
+                })
+                .filter(|&(_, distance)| distance <= KEYFRAME_GRAB_RADIUS)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(i, _)| i);
+
+            if response.drag_started() {
+                dragged = nearest;
+            }
+            if let Some(i) = dragged.filter(|&i| i < keyframes.len()) {
+                // Keep the view still while dragging so the point follows the pointer
+                plot_ui.set_auto_bounds(false);
+                let min_t = if i > 0 { keyframes[i - 1].0 } else { 0.0 };
+                let max_t = keyframes.get(i + 1).map_or(f32::INFINITY, |&(t, _)| t);
+                keyframes[i] = ((pointer.x as f32).clamp(min_t, max_t), pointer.y as f32);
+                changed = true;
+            }
+            if response.drag_stopped() {
+                dragged = None;
+                plot_ui.set_auto_bounds(true);
+            }
+
+            if response.double_clicked() && nearest.is_none() {
+                let key = ((pointer.x as f32).max(0.0), pointer.y as f32);
+                let at = keyframes.partition_point(|&(t, _)| t <= key.0);
+                keyframes.insert(at, key);
+                changed = true;
+            }
+            if response.secondary_clicked() && keyframes.len() > 1 {
+                if let Some(i) = nearest {
+                    keyframes.remove(i);
+                    changed = true;
+                }
+            }
+            changed
+        })
+        .inner;
+
+    ui.data_mut(|d| match dragged {
+        Some(i) => d.insert_temp(dragged_id, i),
+        None => d.remove::<usize>(dragged_id),
+    });
+    edited
 }
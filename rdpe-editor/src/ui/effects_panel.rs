@@ -21,9 +21,12 @@ const EFFECT_TEMPLATES: &[EffectTemplate] = &[
     EffectTemplate { name: "Billboard Cylindrical", create: || VertexEffectConfig::BillboardCylindrical { axis: [0.0, 1.0, 0.0] } },
     EffectTemplate { name: "Billboard Fixed", create: || VertexEffectConfig::BillboardFixed { forward: [0.0, 0.0, 1.0], up: [0.0, 1.0, 0.0] } },
     EffectTemplate { name: "Face Point", create: || VertexEffectConfig::FacePoint { target: [0.0, 0.0, 0.0] } },
+    EffectTemplate { name: "Custom Vertex", create: || VertexEffectConfig::CustomVertex("out_position.y += 0.02 * sin(uniforms.time + particle_age);".into()) },
 ];
 
-pub fn render_effects_panel(ui: &mut egui::Ui, effects: &mut Vec<VertexEffectConfig>) {
+/// Render the vertex effects list. Returns true if any effect changed.
+pub fn render_effects_panel(ui: &mut egui::Ui, effects: &mut Vec<VertexEffectConfig>) -> bool {
+    let mut changed = false;
     ui.heading("Vertex Effects");
 
     // Add effect dropdown
@@ -35,6 +38,7 @@ pub fn render_effects_panel(ui: &mut egui::Ui, effects: &mut Vec<VertexEffectCon
                 for template in EFFECT_TEMPLATES {
                     if ui.selectable_label(false, template.name).clicked() {
                         effects.push((template.create)());
+                        changed = true;
                     }
                 }
             });
@@ -76,7 +80,7 @@ pub fn render_effects_panel(ui: &mut egui::Ui, effects: &mut Vec<VertexEffectCon
                     });
 
                     // Effect parameters
-                    render_effect_params(ui, effect);
+                    changed |= render_effect_params(ui, effect);
                 });
         });
         ui.add_space(2.0);
@@ -85,89 +89,110 @@ pub fn render_effects_panel(ui: &mut egui::Ui, effects: &mut Vec<VertexEffectCon
     // Handle removals and moves
     if let Some(idx) = to_remove {
         effects.remove(idx);
+        changed = true;
     }
     if let Some(idx) = to_move_up {
         effects.swap(idx, idx - 1);
+        changed = true;
     }
     if let Some(idx) = to_move_down {
         effects.swap(idx, idx + 1);
+        changed = true;
     }
+    changed
 }
 
-fn render_effect_params(ui: &mut egui::Ui, effect: &mut VertexEffectConfig) {
+fn render_effect_params(ui: &mut egui::Ui, effect: &mut VertexEffectConfig) -> bool {
+    let mut changed = false;
     match effect {
         VertexEffectConfig::Rotate { speed } => {
-            ui.add(egui::Slider::new(speed, -10.0..=10.0).text("Speed"));
+            changed |= ui.add(egui::Slider::new(speed, -10.0..=10.0).text("Speed")).changed();
         }
         VertexEffectConfig::Wobble { frequency, amplitude } => {
-            ui.add(egui::Slider::new(frequency, 0.1..=10.0).text("Frequency"));
-            ui.add(egui::Slider::new(amplitude, 0.0..=1.0).text("Amplitude"));
+            changed |= ui.add(egui::Slider::new(frequency, 0.1..=10.0).text("Frequency")).changed();
+            changed |= ui.add(egui::Slider::new(amplitude, 0.0..=1.0).text("Amplitude")).changed();
         }
         VertexEffectConfig::Pulse { frequency, amplitude } => {
-            ui.add(egui::Slider::new(frequency, 0.1..=10.0).text("Frequency"));
-            ui.add(egui::Slider::new(amplitude, 0.0..=1.0).text("Amplitude"));
+            changed |= ui.add(egui::Slider::new(frequency, 0.1..=10.0).text("Frequency")).changed();
+            changed |= ui.add(egui::Slider::new(amplitude, 0.0..=1.0).text("Amplitude")).changed();
         }
         VertexEffectConfig::Wave { direction, frequency, speed, amplitude } => {
             ui.horizontal(|ui| {
                 ui.label("Direction:");
-                ui.add(egui::DragValue::new(&mut direction[0]).speed(0.1).prefix("X:"));
-                ui.add(egui::DragValue::new(&mut direction[1]).speed(0.1).prefix("Y:"));
-                ui.add(egui::DragValue::new(&mut direction[2]).speed(0.1).prefix("Z:"));
+                changed |= ui.add(egui::DragValue::new(&mut direction[0]).speed(0.1).prefix("X:")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut direction[1]).speed(0.1).prefix("Y:")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut direction[2]).speed(0.1).prefix("Z:")).changed();
             });
-            ui.add(egui::Slider::new(frequency, 0.1..=10.0).text("Frequency"));
-            ui.add(egui::Slider::new(speed, 0.1..=10.0).text("Speed"));
-            ui.add(egui::Slider::new(amplitude, 0.0..=1.0).text("Amplitude"));
+            changed |= ui.add(egui::Slider::new(frequency, 0.1..=10.0).text("Frequency")).changed();
+            changed |= ui.add(egui::Slider::new(speed, 0.1..=10.0).text("Speed")).changed();
+            changed |= ui.add(egui::Slider::new(amplitude, 0.0..=1.0).text("Amplitude")).changed();
         }
         VertexEffectConfig::Jitter { amplitude } => {
-            ui.add(egui::Slider::new(amplitude, 0.0..=0.5).text("Amplitude"));
+            changed |= ui.add(egui::Slider::new(amplitude, 0.0..=0.5).text("Amplitude")).changed();
         }
         VertexEffectConfig::StretchToVelocity { max_stretch } => {
-            ui.add(egui::Slider::new(max_stretch, 1.0..=5.0).text("Max Stretch"));
+            changed |= ui.add(egui::Slider::new(max_stretch, 1.0..=5.0).text("Max Stretch")).changed();
         }
         VertexEffectConfig::ScaleByDistance { center, min_scale, max_scale, max_distance } => {
             ui.horizontal(|ui| {
                 ui.label("Center:");
-                ui.add(egui::DragValue::new(&mut center[0]).speed(0.1).prefix("X:"));
-                ui.add(egui::DragValue::new(&mut center[1]).speed(0.1).prefix("Y:"));
-                ui.add(egui::DragValue::new(&mut center[2]).speed(0.1).prefix("Z:"));
+                changed |= ui.add(egui::DragValue::new(&mut center[0]).speed(0.1).prefix("X:")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut center[1]).speed(0.1).prefix("Y:")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut center[2]).speed(0.1).prefix("Z:")).changed();
             });
-            ui.add(egui::Slider::new(min_scale, 0.0..=2.0).text("Min Scale"));
-            ui.add(egui::Slider::new(max_scale, 0.5..=5.0).text("Max Scale"));
-            ui.add(egui::Slider::new(max_distance, 0.1..=5.0).text("Max Distance"));
+            changed |= ui.add(egui::Slider::new(min_scale, 0.0..=2.0).text("Min Scale")).changed();
+            changed |= ui.add(egui::Slider::new(max_scale, 0.5..=5.0).text("Max Scale")).changed();
+            changed |= ui.add(egui::Slider::new(max_distance, 0.1..=5.0).text("Max Distance")).changed();
         }
         VertexEffectConfig::FadeByDistance { near, far } => {
-            ui.add(egui::Slider::new(near, 0.0..=5.0).text("Near"));
-            ui.add(egui::Slider::new(far, 0.1..=10.0).text("Far"));
+            changed |= ui.add(egui::Slider::new(near, 0.0..=5.0).text("Near")).changed();
+            changed |= ui.add(egui::Slider::new(far, 0.1..=10.0).text("Far")).changed();
         }
         VertexEffectConfig::BillboardCylindrical { axis } => {
             ui.horizontal(|ui| {
                 ui.label("Axis:");
-                ui.add(egui::DragValue::new(&mut axis[0]).speed(0.1).prefix("X:"));
-                ui.add(egui::DragValue::new(&mut axis[1]).speed(0.1).prefix("Y:"));
-                ui.add(egui::DragValue::new(&mut axis[2]).speed(0.1).prefix("Z:"));
+                changed |= ui.add(egui::DragValue::new(&mut axis[0]).speed(0.1).prefix("X:")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut axis[1]).speed(0.1).prefix("Y:")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut axis[2]).speed(0.1).prefix("Z:")).changed();
             });
         }
         VertexEffectConfig::BillboardFixed { forward, up } => {
             ui.horizontal(|ui| {
                 ui.label("Forward:");
-                ui.add(egui::DragValue::new(&mut forward[0]).speed(0.1).prefix("X:"));
-                ui.add(egui::DragValue::new(&mut forward[1]).speed(0.1).prefix("Y:"));
-                ui.add(egui::DragValue::new(&mut forward[2]).speed(0.1).prefix("Z:"));
+                changed |= ui.add(egui::DragValue::new(&mut forward[0]).speed(0.1).prefix("X:")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut forward[1]).speed(0.1).prefix("Y:")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut forward[2]).speed(0.1).prefix("Z:")).changed();
             });
             ui.horizontal(|ui| {
                 ui.label("Up:");
-                ui.add(egui::DragValue::new(&mut up[0]).speed(0.1).prefix("X:"));
-                ui.add(egui::DragValue::new(&mut up[1]).speed(0.1).prefix("Y:"));
-                ui.add(egui::DragValue::new(&mut up[2]).speed(0.1).prefix("Z:"));
+                changed |= ui.add(egui::DragValue::new(&mut up[0]).speed(0.1).prefix("X:")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut up[1]).speed(0.1).prefix("Y:")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut up[2]).speed(0.1).prefix("Z:")).changed();
             });
         }
         VertexEffectConfig::FacePoint { target } => {
             ui.horizontal(|ui| {
                 ui.label("Target:");
-                ui.add(egui::DragValue::new(&mut target[0]).speed(0.1).prefix("X:"));
-                ui.add(egui::DragValue::new(&mut target[1]).speed(0.1).prefix("Y:"));
-                ui.add(egui::DragValue::new(&mut target[2]).speed(0.1).prefix("Z:"));
+                changed |= ui.add(egui::DragValue::new(&mut target[0]).speed(0.1).prefix("X:")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut target[1]).speed(0.1).prefix("Y:")).changed();
+                changed |= ui.add(egui::DragValue::new(&mut target[2]).speed(0.1).prefix("Z:")).changed();
             });
         }
+        VertexEffectConfig::CustomVertex(code) => {
+            changed |= ui.add(
+                egui::TextEdit::multiline(code)
+                    .code_editor()
+                    .desired_width(f32::INFINITY)
+                    .desired_rows(4)
+                    .hint_text("// Modify out_position (clip space)"),
+            )
+            .on_hover_text(
+                "Runs after the billboard position is computed.\n\
+                 Reads: particle_position, particle_velocity, particle_color, particle_age, particle_scale\n\
+                 Writes: out_position (vec4<f32>, clip space)",
+            )
+            .changed();
+        }
     }
+    changed
 }
@@ -1,7 +1,7 @@
 //! Code export panel - shows generated Rust code
 
-use crate::config::SimConfig;
-use crate::code_export::generate_code;
+use crate::config::{ConfigError, SimConfig};
+use crate::code_export::{generate_code_for_target, CodeExportTarget};
 use egui::{ScrollArea, TextEdit, Ui};
 
 /// State for the export panel
@@ -9,8 +9,14 @@ use egui::{ScrollArea, TextEdit, Ui};
 pub struct ExportPanelState {
     /// Whether the panel is open
     pub open: bool,
+    /// Kind of program to generate
+    pub target: CodeExportTarget,
     /// Cached generated code
     pub code: String,
+    /// Validation errors that prevented code generation
+    pub errors: Vec<ConfigError>,
+    /// Include dead particles in data exports
+    pub include_dead: bool,
     /// Whether code was just copied
     pub just_copied: bool,
     /// Timer for "Copied!" feedback
@@ -20,19 +26,31 @@ pub struct ExportPanelState {
 impl ExportPanelState {
     /// Regenerate code from config
     pub fn regenerate(&mut self, config: &SimConfig) {
-        self.code = generate_code(config);
+        match generate_code_for_target(config, self.target) {
+            Ok(code) => {
+                self.code = code;
+                self.errors.clear();
+            }
+            Err(errors) => {
+                self.code.clear();
+                self.errors = errors;
+            }
+        }
     }
 }
 
-/// Render the export panel as a window
+/// Render the export panel as a window.
+///
+/// Returns `true` when particle data should be read back and exported with
+/// [`generate_csv`](crate::code_export::generate_csv).
 pub fn render_export_window(
     ctx: &egui::Context,
     state: &mut ExportPanelState,
     config: &SimConfig,
     delta_time: f32,
-) {
+) -> bool {
     if !state.open {
-        return;
+        return false;
     }
 
     // Update copy feedback timer
@@ -43,6 +61,7 @@ pub fn render_export_window(
         }
     }
 
+    let mut export_csv = false;
     let mut open = state.open;
     egui::Window::new("Export to Code")
         .open(&mut open)
@@ -50,13 +69,21 @@ pub fn render_export_window(
         .resizable(true)
         .show(ctx, |ui| {
             ui.horizontal(|ui| {
-                if ui.button("Regenerate").clicked() {
+                let previous_target = state.target;
+                egui::ComboBox::from_id_salt("export_target")
+                    .selected_text(state.target.name())
+                    .show_ui(ui, |ui| {
+                        for target in CodeExportTarget::ALL {
+                            ui.selectable_value(&mut state.target, target, target.name());
+                        }
+                    });
+                if ui.button("Regenerate").clicked() || state.target != previous_target {
                     state.regenerate(config);
                 }
 
                 if state.just_copied {
                     ui.label(egui::RichText::new("Copied!").color(egui::Color32::GREEN));
-                } else if ui.button("Copy to Clipboard").clicked() {
+                } else if ui.add_enabled(state.errors.is_empty(), egui::Button::new("Copy to Clipboard")).clicked() {
                     ui.ctx().copy_text(state.code.clone());
                     state.just_copied = true;
                     state.copy_feedback_timer = 2.0;
@@ -69,10 +96,35 @@ pub fn render_export_window(
                         .weak()
                         .small(),
                 );
+
+                ui.separator();
+
+                ui.menu_button("Export Data", |ui| {
+                    ui.checkbox(&mut state.include_dead, "Include dead particles");
+                    if ui.button("CSV").on_hover_text("Save the current particle state").clicked() {
+                        export_csv = true;
+                        ui.close_menu();
+                    }
+                });
             });
 
+            let description = egui::RichText::new(state.target.description()).small();
+            if state.target == CodeExportTarget::Bevy {
+                ui.label(description.color(egui::Color32::YELLOW));
+            } else {
+                ui.label(description.weak());
+            }
+
             ui.separator();
 
+            if !state.errors.is_empty() {
+                ui.label("Fix these problems to export code:");
+                for error in &state.errors {
+                    ui.colored_label(egui::Color32::from_rgb(255, 180, 80), format!("⚠ {}", error));
+                }
+                return;
+            }
+
             // Code display
             ScrollArea::both()
                 .auto_shrink([false, false])
@@ -90,6 +142,7 @@ pub fn render_export_window(
                 });
         });
     state.open = open;
+    export_csv
 }
 
 /// Render an "Export" button that opens the export panel
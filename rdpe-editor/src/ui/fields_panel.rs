@@ -1,9 +1,22 @@
 //! Fields configuration panel
 
-use crate::config::{FieldConfigEntry, FieldTypeConfig};
+use crate::config::{
+    FieldBoundaryConfig, FieldConfigEntry, FieldTypeConfig, GrayScottConfig, ImageChannel, InitialFieldImage,
+};
 use egui::Ui;
 
-pub fn render_fields_panel(ui: &mut Ui, fields: &mut Vec<FieldConfigEntry>) -> bool {
+/// A request to overwrite a running field's contents with a constant value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldResetRequest {
+    pub name: String,
+    pub value: f32,
+}
+
+pub fn render_fields_panel(
+    ui: &mut Ui,
+    fields: &mut Vec<FieldConfigEntry>,
+    reset_request: &mut Option<FieldResetRequest>,
+) -> bool {
     let mut changed = false;
 
     ui.heading("3D Fields");
@@ -14,15 +27,30 @@ pub fn render_fields_panel(ui: &mut Ui, fields: &mut Vec<FieldConfigEntry>) -> b
             .weak(),
     );
 
-    // Add field button
-    if ui.button("+ Add Field").clicked() {
-        let name = format!("field_{}", fields.len());
-        fields.push(FieldConfigEntry {
-            name,
-            ..Default::default()
-        });
-        changed = true;
-    }
+    ui.horizontal(|ui| {
+        // Add field button
+        if ui.button("+ Add Field").clicked() {
+            let name = format!("field_{}", fields.len());
+            fields.push(FieldConfigEntry {
+                name,
+                ..Default::default()
+            });
+            changed = true;
+        }
+
+        ui.menu_button("+ Gray-Scott", |ui| {
+            for (preset_name, params) in GrayScottConfig::PRESETS {
+                if ui.button(*preset_name).clicked() {
+                    let name = format!("field_{}", fields.len());
+                    fields.push(FieldConfigEntry::gray_scott(name, *params));
+                    changed = true;
+                    ui.close_menu();
+                }
+            }
+        })
+        .response
+        .on_hover_text("Add a reaction-diffusion field; read V with field_read, seed it with field_write");
+    });
 
     ui.separator();
 
@@ -42,6 +70,7 @@ pub fn render_fields_panel(ui: &mut Ui, fields: &mut Vec<FieldConfigEntry>) -> b
             })
             .body(|ui| {
                 changed |= render_field_editor(ui, field);
+                render_field_reset(ui, id, field, reset_request);
             });
     }
 
@@ -56,28 +85,34 @@ pub fn render_fields_panel(ui: &mut Ui, fields: &mut Vec<FieldConfigEntry>) -> b
 fn render_field_editor(ui: &mut Ui, field: &mut FieldConfigEntry) -> bool {
     let mut changed = false;
 
-    // Field type
-    let type_variants = FieldTypeConfig::variants();
-    let mut type_idx = match field.field_type {
-        FieldTypeConfig::Scalar => 0,
-        FieldTypeConfig::Vector => 1,
-    };
+    let is_gray_scott = field.gray_scott.is_some();
 
-    ui.horizontal(|ui| {
-        ui.label("Type:");
-        if egui::ComboBox::from_id_salt("field_type")
-            .selected_text(type_variants[type_idx])
-            .show_index(ui, &mut type_idx, type_variants.len(), |i| type_variants[i])
-            .changed()
-        {
-            field.field_type = match type_idx {
-                0 => FieldTypeConfig::Scalar,
-                1 => FieldTypeConfig::Vector,
-                _ => FieldTypeConfig::Scalar,
-            };
-            changed = true;
-        }
-    });
+    if let Some(gray_scott) = &mut field.gray_scott {
+        changed |= render_gray_scott_editor(ui, gray_scott);
+    } else {
+        // Field type
+        let type_variants = FieldTypeConfig::variants();
+        let mut type_idx = match field.field_type {
+            FieldTypeConfig::Scalar => 0,
+            FieldTypeConfig::Vector => 1,
+        };
+
+        ui.horizontal(|ui| {
+            ui.label("Type:");
+            if egui::ComboBox::from_id_salt("field_type")
+                .selected_text(type_variants[type_idx])
+                .show_index(ui, &mut type_idx, type_variants.len(), |i| type_variants[i])
+                .changed()
+            {
+                field.field_type = match type_idx {
+                    0 => FieldTypeConfig::Scalar,
+                    1 => FieldTypeConfig::Vector,
+                    _ => FieldTypeConfig::Scalar,
+                };
+                changed = true;
+            }
+        });
+    }
 
     // Resolution (power of 2)
     const VALID_RESOLUTIONS: &[u32] = &[8, 16, 32, 64, 128, 256];
@@ -100,11 +135,34 @@ fn render_field_editor(ui: &mut Ui, field: &mut FieldConfigEntry) -> bool {
         }
 
         let total_cells = field.resolution.pow(3);
-        let components = if matches!(field.field_type, FieldTypeConfig::Vector) { 4 } else { 1 };
+        let components = if is_gray_scott {
+            2
+        } else if matches!(field.field_type, FieldTypeConfig::Vector) {
+            4
+        } else {
+            1
+        };
         let memory_kb = (total_cells as u64 * components * 4) / 1024;
         ui.label(egui::RichText::new(format!("(~{} KB)", memory_kb)).small().weak());
     });
 
+    // Boundary
+    let boundary_variants = FieldBoundaryConfig::variants();
+    let boundaries = FieldBoundaryConfig::all();
+    let mut boundary_idx = boundaries.iter().position(|b| *b == field.boundary).unwrap_or(1);
+    ui.horizontal(|ui| {
+        ui.label("Boundary:");
+        if egui::ComboBox::from_id_salt("field_boundary")
+            .selected_text(boundary_variants[boundary_idx])
+            .show_index(ui, &mut boundary_idx, boundary_variants.len(), |i| boundary_variants[i])
+            .on_hover_text("Zero: outside reads 0 | Clamp: repeat the border | Wrap: periodic")
+            .changed()
+        {
+            field.boundary = boundaries[boundary_idx];
+            changed = true;
+        }
+    });
+
     // Extent
     changed |= ui
         .add(
@@ -115,34 +173,47 @@ fn render_field_editor(ui: &mut Ui, field: &mut FieldConfigEntry) -> bool {
         .on_hover_text("The field covers [-extent, extent] in world space")
         .changed();
 
-    // Decay
-    changed |= ui
-        .add(
-            egui::Slider::new(&mut field.decay, 0.0..=1.0)
-                .text("Decay")
-                .fixed_decimals(2),
-        )
-        .on_hover_text("Per-frame decay multiplier (1.0 = no decay)")
-        .changed();
+    if is_gray_scott {
+        // Reaction steps (stored as blur iterations)
+        changed |= ui
+            .add(
+                egui::Slider::new(&mut field.blur_iterations, 1..=32)
+                    .text("Steps per Frame"),
+            )
+            .on_hover_text("Number of reaction-diffusion steps per frame")
+            .changed();
+    } else {
+        // Decay
+        changed |= ui
+            .add(
+                egui::Slider::new(&mut field.decay, 0.0..=1.0)
+                    .text("Decay")
+                    .fixed_decimals(2),
+            )
+            .on_hover_text("Per-frame decay multiplier (1.0 = no decay)")
+            .changed();
 
-    // Blur
-    changed |= ui
-        .add(
-            egui::Slider::new(&mut field.blur, 0.0..=1.0)
-                .text("Blur")
-                .fixed_decimals(2),
-        )
-        .on_hover_text("Per-frame diffusion strength")
-        .changed();
+        // Blur
+        changed |= ui
+            .add(
+                egui::Slider::new(&mut field.blur, 0.0..=1.0)
+                    .text("Blur")
+                    .fixed_decimals(2),
+            )
+            .on_hover_text("Per-frame diffusion strength")
+            .changed();
 
-    // Blur iterations
-    changed |= ui
-        .add(
-            egui::Slider::new(&mut field.blur_iterations, 0..=10)
-                .text("Blur Iterations"),
-        )
-        .on_hover_text("Number of blur passes per frame")
-        .changed();
+        // Blur iterations
+        changed |= ui
+            .add(
+                egui::Slider::new(&mut field.blur_iterations, 0..=10)
+                    .text("Blur Iterations"),
+            )
+            .on_hover_text("Number of blur passes per frame")
+            .changed();
+    }
+
+    changed |= render_initial_image(ui, &mut field.init_from_image);
 
     // Show usage hint
     ui.separator();
@@ -163,6 +234,136 @@ fn render_field_editor(ui: &mut Ui, field: &mut FieldConfigEntry) -> bool {
     changed
 }
 
+fn render_gray_scott_editor(ui: &mut Ui, params: &mut GrayScottConfig) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label("Gray-Scott:");
+        egui::ComboBox::from_id_salt("gray_scott_preset")
+            .selected_text(params.preset_name().unwrap_or("Custom"))
+            .show_ui(ui, |ui| {
+                for (name, preset) in GrayScottConfig::PRESETS {
+                    if ui.selectable_label(params == preset, *name).clicked() {
+                        *params = *preset;
+                        changed = true;
+                    }
+                }
+            });
+    });
+
+    changed |= ui
+        .add(egui::Slider::new(&mut params.feed, 0.0..=0.1).text("Feed").fixed_decimals(4))
+        .on_hover_text("Rate at which U is replenished")
+        .changed();
+    changed |= ui
+        .add(egui::Slider::new(&mut params.kill, 0.0..=0.1).text("Kill").fixed_decimals(4))
+        .on_hover_text("Rate at which V is removed")
+        .changed();
+    changed |= ui
+        .add(egui::Slider::new(&mut params.da, 0.0..=1.0).text("Diffusion U").fixed_decimals(2))
+        .changed();
+    changed |= ui
+        .add(egui::Slider::new(&mut params.db, 0.0..=1.0).text("Diffusion V").fixed_decimals(2))
+        .changed();
+
+    changed
+}
+
+fn render_initial_image(ui: &mut Ui, image: &mut Option<InitialFieldImage>) -> bool {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        #[cfg(not(target_arch = "wasm32"))]
+        if ui
+            .button("Load from Image")
+            .on_hover_text("Initialize the field from an image, stretched over X/Y and repeated along Z")
+            .clicked()
+        {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("Images", &["png", "jpg", "jpeg"])
+                .pick_file()
+            {
+                let path = path.display().to_string();
+                match image {
+                    Some(existing) => existing.path = path,
+                    None => {
+                        *image = Some(InitialFieldImage {
+                            path,
+                            channel: ImageChannel::default(),
+                            range: [0.0, 1.0],
+                        })
+                    }
+                }
+                changed = true;
+            }
+        }
+
+        if image.is_some() && ui.small_button("Clear").clicked() {
+            *image = None;
+            changed = true;
+        }
+    });
+
+    if let Some(image) = image {
+        ui.label(egui::RichText::new(&image.path).small().weak());
+
+        let variants = ImageChannel::variants();
+        let channels = ImageChannel::all();
+        let mut channel_idx = channels.iter().position(|c| *c == image.channel).unwrap_or(3);
+        ui.horizontal(|ui| {
+            ui.label("Channel:");
+            if egui::ComboBox::from_id_salt("field_image_channel")
+                .selected_text(variants[channel_idx])
+                .show_index(ui, &mut channel_idx, variants.len(), |i| variants[i])
+                .changed()
+            {
+                image.channel = channels[channel_idx];
+                changed = true;
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Range:");
+            changed |= ui.add(egui::DragValue::new(&mut image.range[0]).speed(0.01)).changed();
+            changed |= ui.add(egui::DragValue::new(&mut image.range[1]).speed(0.01)).changed();
+        })
+        .response
+        .on_hover_text("Field values for channel values 0 and 255");
+    }
+
+    changed
+}
+
+fn render_field_reset(
+    ui: &mut Ui,
+    id: egui::Id,
+    field: &FieldConfigEntry,
+    reset_request: &mut Option<FieldResetRequest>,
+) {
+    let value_id = id.with("reset_value");
+    let mut value = ui.data_mut(|d| *d.get_temp_mut_or(value_id, 0.0f32));
+
+    ui.horizontal(|ui| {
+        if ui
+            .button("Reset Field")
+            .on_hover_text("Clear this field to zero without restarting")
+            .clicked()
+        {
+            *reset_request = Some(FieldResetRequest { name: field.name.clone(), value: 0.0 });
+        }
+        if ui
+            .button("Reset to Value")
+            .on_hover_text("Fill this field with the given value without restarting")
+            .clicked()
+        {
+            *reset_request = Some(FieldResetRequest { name: field.name.clone(), value });
+        }
+        ui.add(egui::DragValue::new(&mut value).speed(0.01));
+    });
+
+    ui.data_mut(|d| d.insert_temp(value_id, value));
+}
+
 fn idx_from_name(_name: &str) -> &str {
     // Just use 0 as placeholder since actual index depends on registry order
     "0u"
@@ -0,0 +1,241 @@
+//! Interaction matrix editor for per-type-pair forces
+
+use egui::{Color32, Ui};
+use rdpe::InteractionMatrix;
+
+use crate::config::RuleConfig;
+
+/// Radius given to every cell of a new matrix
+const DEFAULT_RADIUS: f32 = 0.2;
+
+/// Most particle types a new matrix can have
+const MAX_TYPES: usize = 16;
+
+/// A matrix file operation requested from the Interactions tab
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatrixFileRequest {
+    Load,
+    Save,
+}
+
+/// State for the interactions panel
+pub struct InteractionsPanelState {
+    /// Number of types for the next "New Matrix"
+    pub new_types: usize,
+    /// Cell being edited, as (self type, other type)
+    selected: Option<(u32, u32)>,
+}
+
+impl Default for InteractionsPanelState {
+    fn default() -> Self {
+        Self {
+            new_types: 3,
+            selected: None,
+        }
+    }
+}
+
+/// The matrix of `rule` if it is a `TypedMulti` rule, switched on or off.
+fn typed_multi(rule: &RuleConfig) -> Option<&InteractionMatrix> {
+    match rule {
+        RuleConfig::TypedMulti(matrix) => Some(matrix),
+        RuleConfig::Disabled(inner) => typed_multi(inner),
+        _ => None,
+    }
+}
+
+/// Mutable version of [`typed_multi`].
+fn typed_multi_mut(rule: &mut RuleConfig) -> Option<&mut InteractionMatrix> {
+    match rule {
+        RuleConfig::TypedMulti(matrix) => Some(matrix),
+        RuleConfig::Disabled(inner) => typed_multi_mut(inner),
+        _ => None,
+    }
+}
+
+/// The matrix of the first `TypedMulti` rule, if there is one.
+///
+/// A disabled rule still counts, so its matrix can be edited and saved.
+pub fn interaction_matrix(rules: &[RuleConfig]) -> Option<&InteractionMatrix> {
+    rules.iter().find_map(typed_multi)
+}
+
+/// Replace the first `TypedMulti` rule's matrix, or add a `TypedMulti` rule if there is none.
+///
+/// A disabled rule keeps its matrix slot and stays disabled.
+pub fn set_interaction_matrix(rules: &mut Vec<RuleConfig>, matrix: InteractionMatrix) {
+    match rules.iter_mut().find_map(typed_multi_mut) {
+        Some(existing) => *existing = matrix,
+        None => rules.push(RuleConfig::TypedMulti(matrix)),
+    }
+}
+
+/// Tile color for `strength`: red attracts, blue repels, white is neutral.
+fn heat_color(strength: f32, max_strength: f32) -> Color32 {
+    let t = (strength.abs() / max_strength).clamp(0.0, 1.0);
+    let target = if strength >= 0.0 {
+        Color32::from_rgb(220, 50, 50)
+    } else {
+        Color32::from_rgb(50, 90, 220)
+    };
+    let channel = |white: u8, end: u8| (white as f32 + (end as f32 - white as f32) * t).round() as u8;
+    Color32::from_rgb(channel(255, target.r()), channel(255, target.g()), channel(255, target.b()))
+}
+
+/// Render the interactions panel.
+///
+/// Loading and saving JSON files is left to the caller through `file_request`.
+pub fn render_interactions_panel(
+    ui: &mut Ui,
+    rules: &mut Vec<RuleConfig>,
+    state: &mut InteractionsPanelState,
+    file_request: &mut Option<MatrixFileRequest>,
+) -> bool {
+    let mut changed = false;
+
+    ui.heading("Interactions");
+    ui.label(
+        egui::RichText::new("How strongly each particle type is pulled toward (red) or pushed from (blue) each other type")
+            .small()
+            .weak(),
+    );
+
+    ui.horizontal(|ui| {
+        if ui.button("New Matrix").clicked() {
+            let mut matrix = InteractionMatrix::new(state.new_types);
+            for self_type in 0..state.new_types as u32 {
+                for other_type in 0..state.new_types as u32 {
+                    matrix.set(self_type, other_type, 0.0, DEFAULT_RADIUS);
+                }
+            }
+            set_interaction_matrix(rules, matrix);
+            state.selected = None;
+            changed = true;
+        }
+        ui.add(egui::DragValue::new(&mut state.new_types).range(1..=MAX_TYPES))
+            .on_hover_text("Number of particle types");
+        ui.label("types");
+    });
+
+    ui.horizontal(|ui| {
+        if ui.button("Load Matrix JSON").clicked() {
+            *file_request = Some(MatrixFileRequest::Load);
+        }
+        let has_matrix = interaction_matrix(rules).is_some();
+        if ui.add_enabled(has_matrix, egui::Button::new("Save Matrix JSON")).clicked() {
+            *file_request = Some(MatrixFileRequest::Save);
+        }
+    });
+
+    ui.separator();
+
+    let Some(rule) = rules.iter_mut().find(|rule| typed_multi(rule).is_some()) else {
+        ui.label("No interaction matrix. Press New Matrix to create one.");
+        return changed;
+    };
+    if !rule.is_enabled() {
+        ui.label(
+            egui::RichText::new("The interaction rule is disabled. Enable it in the Rules tab to apply this matrix.")
+                .small()
+                .color(Color32::YELLOW),
+        );
+    }
+    let matrix = typed_multi_mut(rule).expect("rule was found by typed_multi");
+
+    let n = matrix.num_types() as u32;
+    if state.selected.is_some_and(|(s, o)| s >= n || o >= n) {
+        state.selected = None;
+    }
+    let max_strength = matrix
+        .data()
+        .iter()
+        .map(|&(strength, _)| strength.abs())
+        .fold(1.0, f32::max);
+
+    ui.label(egui::RichText::new("Rows: self type, columns: other type").small().weak());
+    egui::Grid::new("interaction_matrix_grid")
+        .spacing([2.0, 2.0])
+        .show(ui, |ui| {
+            ui.label("");
+            for other_type in 0..n {
+                ui.label(format!("T{}", other_type));
+            }
+            ui.end_row();
+
+            for self_type in 0..n {
+                ui.label(format!("T{}", self_type));
+                for other_type in 0..n {
+                    let (mut strength, radius) = matrix.get(self_type, other_type);
+                    if state.selected == Some((self_type, other_type)) {
+                        let response = ui.add_sized(
+                            [44.0, 24.0],
+                            egui::DragValue::new(&mut strength).speed(0.05).max_decimals(2),
+                        );
+                        if response.changed() {
+                            matrix.set(self_type, other_type, strength, radius);
+                            changed = true;
+                        }
+                    } else {
+                        let text = egui::RichText::new(format!("{:.1}", strength)).color(Color32::BLACK);
+                        let tile = egui::Button::new(text)
+                            .fill(heat_color(strength, max_strength))
+                            .min_size(egui::vec2(44.0, 24.0));
+                        if ui
+                            .add(tile)
+                            .on_hover_text(format!(
+                                "T{} → T{}: strength {:.2}, radius {:.2}",
+                                self_type, other_type, strength, radius
+                            ))
+                            .clicked()
+                        {
+                            state.selected = Some((self_type, other_type));
+                        }
+                    }
+                }
+                ui.end_row();
+            }
+        });
+
+    if let Some((self_type, other_type)) = state.selected {
+        ui.add_space(4.0);
+        let (mut strength, mut radius) = matrix.get(self_type, other_type);
+        ui.horizontal(|ui| {
+            ui.strong(format!("T{} → T{}", self_type, other_type));
+            if ui.small_button("Done").clicked() {
+                state.selected = None;
+            }
+        });
+        let strength_changed = ui
+            .add(egui::Slider::new(&mut strength, -5.0..=5.0).text("Strength"))
+            .changed();
+        let radius_changed = ui
+            .add(egui::Slider::new(&mut radius, 0.01..=1.0).text("Radius"))
+            .changed();
+        if strength_changed || radius_changed {
+            matrix.set(self_type, other_type, strength, radius);
+            changed = true;
+        }
+    } else {
+        ui.label(egui::RichText::new("Click a tile to edit it").small().weak());
+    }
+
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_matrix_replaces_disabled_rule() {
+        let mut rule = RuleConfig::TypedMulti(InteractionMatrix::new(2));
+        rule.set_enabled(false);
+        let mut rules = vec![rule];
+        assert_eq!(interaction_matrix(&rules).map(|m| m.num_types()), Some(2));
+
+        set_interaction_matrix(&mut rules, InteractionMatrix::new(4));
+        assert_eq!(rules.len(), 1);
+        assert!(!rules[0].is_enabled());
+        assert_eq!(interaction_matrix(&rules).map(|m| m.num_types()), Some(4));
+    }
+}
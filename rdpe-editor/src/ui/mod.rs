@@ -4,10 +4,12 @@ mod custom_panel;
 mod effects_panel;
 mod export_panel;
 mod fields_panel;
+mod interactions_panel;
 mod mouse_panel;
 mod particle_fields_panel;
 mod rules_panel;
 mod spawn_panel;
+mod stats_panel;
 mod visuals_panel;
 mod volume_panel;
 mod presets;
@@ -15,11 +17,15 @@ mod presets;
 pub use custom_panel::{render_custom_panel, AddUniformState};
 pub use effects_panel::render_effects_panel;
 pub use export_panel::{render_export_window, render_export_button, ExportPanelState};
-pub use fields_panel::render_fields_panel;
+pub use fields_panel::{render_fields_panel, FieldResetRequest};
+pub use interactions_panel::{
+    interaction_matrix, render_interactions_panel, set_interaction_matrix, InteractionsPanelState, MatrixFileRequest,
+};
 pub use mouse_panel::render_mouse_panel;
 pub use particle_fields_panel::render_particle_fields_panel;
 pub use rules_panel::render_rules_panel;
-pub use spawn_panel::render_spawn_panel;
-pub use visuals_panel::render_visuals_panel;
+pub use spawn_panel::{render_spawn_panel, SpawnFileRequest, SpawnPanelState};
+pub use stats_panel::{render_stats_panel, StatsPanelState};
+pub use visuals_panel::{render_visuals_panel, VisualsFileRequest};
 pub use volume_panel::render_volume_panel;
-pub use presets::{PRESETS, Preset};
+pub use presets::{PresetThumbnails, PRESETS, Preset};
@@ -1,13 +1,70 @@
 //! Simulation presets
 
 use crate::config::{
-    BlendModeConfig, ColorMappingConfig, ColorMode, CustomShaderConfig, Falloff, FieldConfigEntry,
-    FieldTypeConfig, InitialVelocity, MouseConfig, PaletteConfig, ParticleFieldDef,
+    BlendModeConfig, ColorMappingConfig, ColorMode, CustomShaderConfig, Falloff, FieldBoundaryConfig,
+    FieldConfigEntry, FieldTypeConfig, InitialVelocity, MouseConfig, PaletteConfig, ParticleFieldDef,
     ParticleFieldType, ParticleShapeConfig, RuleConfig, SimConfig, SpawnConfig, SpawnShape,
     UniformValueConfig, VertexEffectConfig, VisualsConfig, VolumeRenderConfig,
 };
+use crate::embedded::EmbeddedSimulation;
+use eframe::egui;
 use std::collections::HashMap;
 
+/// Thumbnail size in pixels
+const THUMBNAIL_SIZE: [u32; 2] = [96, 64];
+
+/// Simulation steps run before a thumbnail is captured
+const THUMBNAIL_WARMUP_FRAMES: u32 = 20;
+
+/// Most particles simulated for a thumbnail; a 96x64 image can't show more
+const THUMBNAIL_MAX_PARTICLES: u32 = 4000;
+
+/// Live-rendered preset thumbnails, cached by preset name
+#[derive(Default)]
+pub struct PresetThumbnails {
+    /// `None` marks a preset whose thumbnail failed to render
+    textures: HashMap<&'static str, Option<egui::TextureHandle>>,
+}
+
+impl PresetThumbnails {
+    /// The thumbnail for `name`, if it has been rendered.
+    pub fn get(&self, name: &str) -> Option<&egui::TextureHandle> {
+        self.textures.get(name).and_then(Option::as_ref)
+    }
+
+    /// Render the first preset that has no thumbnail yet.
+    ///
+    /// Only one preset is rendered per call so opening the menu doesn't
+    /// stall for every preset at once, and each runs with at most
+    /// [`THUMBNAIL_MAX_PARTICLES`] for a short warmup. Returns whether any
+    /// are still missing.
+    pub fn render_next(&mut self, ctx: &egui::Context, wgpu_render_state: &egui_wgpu::RenderState) -> bool {
+        let Some(preset) = PRESETS.iter().find(|p| !self.textures.contains_key(p.name)) else {
+            return false;
+        };
+
+        let mut config = (preset.config)();
+        config.particle_count = config.particle_count.min(THUMBNAIL_MAX_PARTICLES);
+
+        let [width, height] = THUMBNAIL_SIZE;
+        let pixels = EmbeddedSimulation::render_to_image(
+            &wgpu_render_state.device,
+            &wgpu_render_state.queue,
+            &config,
+            width,
+            height,
+            THUMBNAIL_WARMUP_FRAMES,
+        );
+        let texture = (!pixels.is_empty()).then(|| {
+            let image = egui::ColorImage::from_rgba_unmultiplied([width as usize, height as usize], &pixels);
+            ctx.load_texture(format!("preset_thumbnail_{}", preset.name), image, egui::TextureOptions::LINEAR)
+        });
+        self.textures.insert(preset.name, texture);
+
+        self.textures.len() < PRESETS.len()
+    }
+}
+
 pub struct Preset {
     pub name: &'static str,
     pub description: &'static str,
@@ -19,6 +76,7 @@ pub static PRESETS: &[Preset] = &[
         name: "Boids Flocking",
         description: "Classic boids algorithm with separation, cohesion, alignment",
         config: || SimConfig {
+            version: SimConfig::CURRENT_VERSION,
             name: "Boids Flocking".into(),
             particle_count: 5000,
             bounds: 1.0,
@@ -26,6 +84,7 @@ pub static PRESETS: &[Preset] = &[
             speed: 1.0,
             spatial_cell_size: 0.15,
             spatial_resolution: 32,
+            skip_dead_neighbors: true,
             spawn: SpawnConfig {
                 shape: SpawnShape::Sphere { radius: 0.5 },
                 velocity: InitialVelocity::RandomDirection { speed: 0.2 },
@@ -55,12 +114,14 @@ pub static PRESETS: &[Preset] = &[
             volume_render: VolumeRenderConfig::default(),
             particle_fields: Vec::new(),
             mouse: MouseConfig::default(),
+            two_d: false,
         },
     },
     Preset {
         name: "Explosion",
         description: "Particles exploding outward with gravity",
         config: || SimConfig {
+            version: SimConfig::CURRENT_VERSION,
             name: "Explosion".into(),
             particle_count: 50000,
             bounds: 2.0,
@@ -68,6 +129,7 @@ pub static PRESETS: &[Preset] = &[
             speed: 1.0,
             spatial_cell_size: 0.1,
             spatial_resolution: 32,
+            skip_dead_neighbors: true,
             spawn: SpawnConfig {
                 shape: SpawnShape::Sphere { radius: 0.1 },
                 velocity: InitialVelocity::Outward { speed: 1.5 },
@@ -90,12 +152,14 @@ pub static PRESETS: &[Preset] = &[
             volume_render: VolumeRenderConfig::default(),
             particle_fields: Vec::new(),
             mouse: MouseConfig::default(),
+            two_d: false,
         },
     },
     Preset {
         name: "Fluid Simulation",
         description: "SPH-like fluid with pressure and viscosity",
         config: || SimConfig {
+            version: SimConfig::CURRENT_VERSION,
             name: "Fluid Simulation".into(),
             particle_count: 10000,
             bounds: 1.0,
@@ -103,6 +167,7 @@ pub static PRESETS: &[Preset] = &[
             speed: 1.0,
             spatial_cell_size: 0.1,
             spatial_resolution: 32,
+            skip_dead_neighbors: true,
             spawn: SpawnConfig {
                 shape: SpawnShape::Sphere { radius: 0.5 },
                 velocity: InitialVelocity::Zero,
@@ -116,9 +181,10 @@ pub static PRESETS: &[Preset] = &[
             rules: vec![
                 RuleConfig::Gravity(1.0),
                 RuleConfig::Pressure {
-                    radius: 0.05,
-                    strength: 1.0,
-                    target_density: 1.0,
+                    rest_density: 4.0,
+                    pressure_constant: 1.0,
+                    viscosity: 0.05,
+                    kernel_radius: 0.05,
                 },
                 RuleConfig::Viscosity {
                     radius: 2.0,
@@ -145,6 +211,9 @@ pub static PRESETS: &[Preset] = &[
                 blur: 0.2,
                 blur_iterations: 2,
                 field_type: FieldTypeConfig::Scalar,
+                boundary: FieldBoundaryConfig::Clamp,
+                gray_scott: None,
+                init_from_image: None,
             }],
             volume_render: VolumeRenderConfig {
                 enabled: true,
@@ -154,9 +223,11 @@ pub static PRESETS: &[Preset] = &[
                 palette: PaletteConfig::Ocean,
                 threshold: 0.02,
                 additive: true,
+                ..Default::default()
             },
             particle_fields: Vec::new(),
             mouse: MouseConfig::default(),
+            two_d: false,
         },
     },
     Preset {
@@ -164,6 +235,7 @@ pub static PRESETS: &[Preset] = &[
         description: "Demonstrates custom uniforms and shader code",
         config: || {
             SimConfig {
+            version: SimConfig::CURRENT_VERSION,
             name: "Custom Shader Demo".into(),
             particle_count: 10000,
             bounds: 1.5,
@@ -171,6 +243,7 @@ pub static PRESETS: &[Preset] = &[
             speed: 1.0,
             spatial_cell_size: 0.1,
             spatial_resolution: 32,
+            skip_dead_neighbors: true,
             spawn: SpawnConfig {
                 shape: SpawnShape::Shell { inner: 0.3, outer: 1.0 },
                 velocity: InitialVelocity::Swirl { speed: 0.3 },
@@ -192,11 +265,13 @@ pub static PRESETS: &[Preset] = &[
             custom_shaders: CustomShaderConfig {
                 vertex_code: "// Pulsing size effect\nsize_mult *= 1.0 + uniforms.pulse_amount * sin(uniforms.time * uniforms.pulse_speed);".to_string(),
                 fragment_code: "// Apply tint color\nfrag_color *= uniforms.tint;".to_string(),
+                ..Default::default()
             },
             fields: Vec::new(),
             volume_render: VolumeRenderConfig::default(),
             particle_fields: Vec::new(),
             mouse: MouseConfig::default(),
+            two_d: false,
         }
         },
     },
@@ -204,6 +279,7 @@ pub static PRESETS: &[Preset] = &[
         name: "Pheromone Trails",
         description: "Particles follow and deposit pheromone trails like ants",
         config: || SimConfig {
+            version: SimConfig::CURRENT_VERSION,
             name: "Pheromone Trails".into(),
             particle_count: 8000,
             bounds: 1.0,
@@ -211,6 +287,7 @@ pub static PRESETS: &[Preset] = &[
             speed: 1.0,
             spatial_cell_size: 0.1,
             spatial_resolution: 32,
+            skip_dead_neighbors: true,
             spawn: SpawnConfig {
                 shape: SpawnShape::Cube { size: 0.8 },
                 velocity: InitialVelocity::RandomDirection { speed: 0.3 },
@@ -264,6 +341,9 @@ field_write(0u, p.position, 0.5);
                 blur: 0.2,
                 blur_iterations: 1,
                 field_type: FieldTypeConfig::Scalar,
+                boundary: FieldBoundaryConfig::Clamp,
+                gray_scott: None,
+                init_from_image: None,
             }],
             volume_render: VolumeRenderConfig {
                 enabled: true,
@@ -273,9 +353,11 @@ field_write(0u, p.position, 0.5);
                 palette: PaletteConfig::Magma,
                 threshold: 0.02,
                 additive: true,
+                ..Default::default()
             },
             particle_fields: Vec::new(),
             mouse: MouseConfig::default(),
+            two_d: false,
         },
     },
     // === New presets from examples ===
@@ -283,6 +365,7 @@ field_write(0u, p.position, 0.5);
         name: "Shockwave",
         description: "Expanding shockwaves that push particles outward with breathing effect",
         config: || SimConfig {
+            version: SimConfig::CURRENT_VERSION,
             name: "Shockwave".into(),
             particle_count: 30000,
             bounds: 1.5,
@@ -290,6 +373,7 @@ field_write(0u, p.position, 0.5);
             speed: 1.0,
             spatial_cell_size: 0.1,
             spatial_resolution: 32,
+            skip_dead_neighbors: true,
             spawn: SpawnConfig {
                 shape: SpawnShape::Shell {
                     inner: 0.3,
@@ -309,7 +393,7 @@ field_write(0u, p.position, 0.5);
                     repeat: 3.0,
                 },
                 // Gentle breathing pulse
-                RuleConfig::Pulse {
+                RuleConfig::RadialPulse {
                     point: [0.0, 0.0, 0.0],
                     strength: 0.5,
                     frequency: 0.3,
@@ -340,12 +424,14 @@ field_write(0u, p.position, 0.5);
             volume_render: VolumeRenderConfig::default(),
             particle_fields: Vec::new(),
             mouse: MouseConfig::default(),
+            two_d: false,
         },
     },
     Preset {
         name: "Galaxy",
         description: "Stars orbiting a central mass with spiral arm dynamics",
         config: || SimConfig {
+            version: SimConfig::CURRENT_VERSION,
             name: "Galaxy".into(),
             particle_count: 100,
             bounds: 2.0,
@@ -353,6 +439,7 @@ field_write(0u, p.position, 0.5);
             speed: 1.0,
             spatial_cell_size: 0.2,
             spatial_resolution: 32,
+            skip_dead_neighbors: true,
             spawn: SpawnConfig {
                 shape: SpawnShape::Shell {
                     inner: 0.5,
@@ -406,12 +493,14 @@ if t > 0.6 {
             volume_render: VolumeRenderConfig::default(),
             particle_fields: Vec::new(),
             mouse: MouseConfig::default(),
+            two_d: false,
         },
     },
     Preset {
         name: "Crystal Growth",
         description: "Diffusion-limited aggregation creating dendritic fractal structures",
         config: || SimConfig {
+            version: SimConfig::CURRENT_VERSION,
             name: "Crystal Growth".into(),
             particle_count: 5000,
             bounds: 1.0,
@@ -419,6 +508,7 @@ if t > 0.6 {
             speed: 1.0,
             spatial_cell_size: 0.1,
             spatial_resolution: 32,
+            skip_dead_neighbors: true,
             particle_fields: vec![ParticleFieldDef {
                 name: "custom".into(),
                 field_type: ParticleFieldType::F32,
@@ -522,12 +612,14 @@ if p.particle_type == 0u && other.particle_type == 1u {
             fields: Vec::new(),
             volume_render: VolumeRenderConfig::default(),
             mouse: MouseConfig::default(),
+            two_d: false,
         },
     },
     Preset {
         name: "Slime Mold",
         description: "Physarum-inspired agents depositing and following pheromone trails",
         config: || SimConfig {
+            version: SimConfig::CURRENT_VERSION,
             name: "Slime Mold".into(),
             particle_count: 25000,
             bounds: 1.0,
@@ -535,6 +627,7 @@ if p.particle_type == 0u && other.particle_type == 1u {
             speed: 1.0,
             spatial_cell_size: 0.1,
             spatial_resolution: 32,
+            skip_dead_neighbors: true,
             particle_fields: vec![ParticleFieldDef {
                 name: "custom".into(),
                 field_type: ParticleFieldType::F32,
@@ -625,6 +718,9 @@ p.velocity = vec3<f32>(0.0, 0.0, 0.0);
                 blur: 0.1,
                 blur_iterations: 1,
                 field_type: FieldTypeConfig::Scalar,
+                boundary: FieldBoundaryConfig::Clamp,
+                gray_scott: None,
+                init_from_image: None,
             }],
             volume_render: VolumeRenderConfig {
                 enabled: true,
@@ -634,14 +730,17 @@ p.velocity = vec3<f32>(0.0, 0.0, 0.0);
                 palette: PaletteConfig::Neon,
                 threshold: 0.01,
                 additive: true,
+                ..Default::default()
             },
             mouse: MouseConfig::default(),
+            two_d: false,
         },
     },
     Preset {
         name: "Aurora",
         description: "Northern lights effect with flowing ribbons of color",
         config: || SimConfig {
+            version: SimConfig::CURRENT_VERSION,
             name: "Aurora".into(),
             particle_count: 15000,
             bounds: 1.5,
@@ -649,6 +748,7 @@ p.velocity = vec3<f32>(0.0, 0.0, 0.0);
             speed: 1.0,
             spatial_cell_size: 0.1,
             spatial_resolution: 32,
+            skip_dead_neighbors: true,
             spawn: SpawnConfig {
                 shape: SpawnShape::Plane {
                     width: 2.5,
@@ -718,12 +818,14 @@ p.color *= smoothstep(0.0, 0.3, height) * (1.0 - smoothstep(0.7, 1.0, height));
             volume_render: VolumeRenderConfig::default(),
             particle_fields: Vec::new(),
             mouse: MouseConfig::default(),
+            two_d: false,
         },
     },
     Preset {
         name: "Fireflies",
         description: "Glowing particles that pulse and wander in the dark",
         config: || SimConfig {
+            version: SimConfig::CURRENT_VERSION,
             name: "Fireflies".into(),
             particle_count: 500,
             bounds: 1.5,
@@ -731,6 +833,7 @@ p.color *= smoothstep(0.0, 0.3, height) * (1.0 - smoothstep(0.7, 1.0, height));
             speed: 1.0,
             spatial_cell_size: 0.2,
             spatial_resolution: 32,
+            skip_dead_neighbors: true,
             spawn: SpawnConfig {
                 shape: SpawnShape::Cube { size: 2.0 },
                 velocity: InitialVelocity::Zero,
@@ -776,12 +879,14 @@ p.scale = 0.5 + glow * 0.5;
             volume_render: VolumeRenderConfig::default(),
             particle_fields: Vec::new(),
             mouse: MouseConfig::default(),
+            two_d: false,
         },
     },
     Preset {
         name: "Tornado",
         description: "Swirling vortex pulling particles upward",
         config: || SimConfig {
+            version: SimConfig::CURRENT_VERSION,
             name: "Tornado".into(),
             particle_count: 20000,
             bounds: 2.0,
@@ -789,6 +894,7 @@ p.scale = 0.5 + glow * 0.5;
             speed: 1.0,
             spatial_cell_size: 0.1,
             spatial_resolution: 32,
+            skip_dead_neighbors: true,
             spawn: SpawnConfig {
                 shape: SpawnShape::Plane {
                     width: 2.0,
@@ -804,6 +910,8 @@ p.scale = 0.5 + glow * 0.5;
                     center: [0.0, 0.0, 0.0],
                     axis: [0.0, 1.0, 0.0],
                     strength: 10.0,
+                    radius: 0.0,
+                    falloff: Falloff::Constant,
                 },
                 // Pull toward center
                 RuleConfig::AttractTo {
@@ -843,12 +951,14 @@ if p.position.y > 1.4 {
             volume_render: VolumeRenderConfig::default(),
             particle_fields: Vec::new(),
             mouse: MouseConfig::default(),
+            two_d: false,
         },
     },
     Preset {
         name: "Plasma Core",
         description: "Pulsating energy core with swirling plasma field and volume rendering",
         config: || SimConfig {
+            version: SimConfig::CURRENT_VERSION,
             name: "Plasma Core".into(),
             particle_count: 15000,
             bounds: 1.5,
@@ -856,6 +966,7 @@ if p.position.y > 1.4 {
             speed: 1.0,
             spatial_cell_size: 0.1,
             spatial_resolution: 32,
+            skip_dead_neighbors: true,
             spawn: SpawnConfig {
                 shape: SpawnShape::Shell {
                     inner: 0.2,
@@ -881,6 +992,8 @@ if p.position.y > 1.4 {
                     center: [0.0, 0.0, 0.0],
                     axis: [0.0, 1.0, 0.0],
                     strength: 2.0,
+                    radius: 0.0,
+                    falloff: Falloff::Constant,
                 },
                 // Turbulent organic motion
                 RuleConfig::Curl {
@@ -888,7 +1001,7 @@ if p.position.y > 1.4 {
                     strength: 0.6,
                 },
                 // Breathing pulse - expand and contract
-                RuleConfig::Pulse {
+                RuleConfig::RadialPulse {
                     point: [0.0, 0.0, 0.0],
                     strength: 0.4,
                     frequency: 0.5,
@@ -956,6 +1069,9 @@ p.scale = 0.5 + core_intensity * 1.0;
                 blur: 0.25,
                 blur_iterations: 2,
                 field_type: FieldTypeConfig::Scalar,
+                boundary: FieldBoundaryConfig::Clamp,
+                gray_scott: None,
+                init_from_image: None,
             }],
             volume_render: VolumeRenderConfig {
                 enabled: true,
@@ -965,15 +1081,18 @@ p.scale = 0.5 + core_intensity * 1.0;
                 palette: PaletteConfig::Plasma,
                 threshold: 0.02,
                 additive: true,
+                ..Default::default()
             },
             particle_fields: Vec::new(),
             mouse: MouseConfig::default(),
+            two_d: false,
         },
     },
     Preset {
         name: "Immortal Jellyfish",
         description: "Bioluminescent jellyfish with pulsing bell and flowing tentacles",
         config: || SimConfig {
+            version: SimConfig::CURRENT_VERSION,
             name: "Jellyfish".into(),
             particle_count: 6000,
             bounds: 2.0,
@@ -981,6 +1100,7 @@ p.scale = 0.5 + core_intensity * 1.0;
             speed: 1.0,
             spatial_cell_size: 0.15,
             spatial_resolution: 32,
+            skip_dead_neighbors: true,
             particle_fields: vec![ParticleFieldDef {
                 name: "custom".into(),
                 field_type: ParticleFieldType::F32,
@@ -1120,12 +1240,14 @@ if is_bell {
             fields: Vec::new(),
             volume_render: VolumeRenderConfig::default(),
             mouse: MouseConfig::default(),
+            two_d: false,
         },
     },
     Preset {
         name: "Water Cycle",
         description: "Evaporating water rises, condenses into clouds, and rains back down",
         config: || SimConfig {
+            version: SimConfig::CURRENT_VERSION,
             name: "Water Cycle".into(),
             particle_count: 5000,
             bounds: 1.5,
@@ -1133,6 +1255,7 @@ if is_bell {
             speed: 1.0,
             spatial_cell_size: 0.1,
             spatial_resolution: 32,
+            skip_dead_neighbors: true,
             particle_fields: vec![ParticleFieldDef {
                 name: "custom".into(),
                 field_type: ParticleFieldType::F32,
@@ -1266,12 +1389,14 @@ if abs(p.position.z) > 1.0 { p.position.z *= 0.95; }
             fields: Vec::new(),
             volume_render: VolumeRenderConfig::default(),
             mouse: MouseConfig::default(),
+            two_d: false,
         },
     },
     Preset {
         name: "Snowfall",
         description: "Gentle snow drifting down with wind gusts",
         config: || SimConfig {
+            version: SimConfig::CURRENT_VERSION,
             name: "Snowfall".into(),
             particle_count: 4000,
             bounds: 2.0,
@@ -1279,6 +1404,7 @@ if abs(p.position.z) > 1.0 { p.position.z *= 0.95; }
             speed: 1.0,
             spatial_cell_size: 0.1,
             spatial_resolution: 32,
+            skip_dead_neighbors: true,
             spawn: SpawnConfig {
                 shape: SpawnShape::Cube { size: 2.5 },
                 velocity: InitialVelocity::Zero,
@@ -1352,6 +1478,7 @@ p.color = vec3<f32>(color_var, color_var, color_var + blue_tint);
             volume_render: VolumeRenderConfig::default(),
             particle_fields: Vec::new(),
             mouse: MouseConfig::default(),
+            two_d: false,
         },
     },
 ];
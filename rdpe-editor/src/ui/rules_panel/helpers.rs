@@ -1,8 +1,26 @@
 //! Helper UI functions for rule rendering
 
-use crate::config::Falloff;
+use crate::config::{Falloff, WaveShape};
 use egui::Ui;
 
+/// Hover text for Custom rule code listing the helper library functions.
+///
+/// A library is added to the shader as soon as the code calls one of its
+/// functions (see `RuleConfig::shader_helpers`).
+pub(super) fn custom_code_tooltip() -> String {
+    let mut text = String::from(
+        "Runs once per particle. Reads/writes p; reads index and uniforms.\n\
+         Helper functions, included automatically when called:",
+    );
+    for helper in rdpe::ShaderHelper::ALL {
+        text.push_str(&format!("\n\n{}:", helper.name()));
+        for signature in helper.functions() {
+            text.push_str(&format!("\n  {}", signature));
+        }
+    }
+    text
+}
+
 /// Renders a vec3 input widget with x, y, z drag values
 pub(super) fn render_vec3(ui: &mut Ui, label: &str, v: &mut [f32; 3]) -> bool {
     let mut changed = false;
@@ -47,3 +65,26 @@ pub(super) fn render_falloff(ui: &mut Ui, falloff: &mut Falloff) -> bool {
     }
     false
 }
+
+/// Renders a wave shape selector combo box
+pub(super) fn render_wave_shape(ui: &mut Ui, wave_shape: &mut WaveShape) -> bool {
+    let variants = WaveShape::variants();
+    let mut idx = match wave_shape {
+        WaveShape::Sine => 0,
+        WaveShape::Square => 1,
+        WaveShape::Triangle => 2,
+    };
+
+    if egui::ComboBox::from_label("Wave Shape")
+        .show_index(ui, &mut idx, variants.len(), |i| variants[i])
+        .changed()
+    {
+        *wave_shape = match idx {
+            0 => WaveShape::Sine,
+            1 => WaveShape::Square,
+            _ => WaveShape::Triangle,
+        };
+        return true;
+    }
+    false
+}
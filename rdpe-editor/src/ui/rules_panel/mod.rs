@@ -12,11 +12,20 @@ pub use templates::RULE_TEMPLATES;
 
 use renderers::render_rule_params;
 
-pub fn render_rules_panel(ui: &mut Ui, rules: &mut Vec<RuleConfig>) -> bool {
+/// Edits requested from a rule's right-click menu
+enum RuleMenuAction {
+    Duplicate,
+    PasteAbove,
+    PasteBelow,
+}
+
+/// `clipboard` holds the rule copied with the "Copy Rule" context menu item.
+pub fn render_rules_panel(ui: &mut Ui, rules: &mut Vec<RuleConfig>, clipboard: &mut Option<RuleConfig>) -> bool {
     let mut changed = false;
     let mut remove_idx = None;
     let mut move_up_idx = None;
     let mut move_down_idx = None;
+    let mut menu_action = None;
 
     ui.heading("Rules");
     ui.separator();
@@ -45,27 +54,77 @@ pub fn render_rules_panel(ui: &mut Ui, rules: &mut Vec<RuleConfig>) -> bool {
         let id = ui.make_persistent_id(format!("rule_{}", idx));
         egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), id, true)
             .show_header(ui, |ui| {
-                ui.horizontal(|ui| {
-                    ui.label(format!("{}.", idx + 1));
-                    ui.strong(rule.name());
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        if ui.small_button("X").clicked() {
-                            remove_idx = Some(idx);
-                        }
-                        if idx < rules_len - 1 && ui.small_button("v").clicked() {
-                            move_down_idx = Some(idx);
+                // Sense clicks on the whole row so a right-click anywhere opens the menu
+                let row = ui.scope_builder(egui::UiBuilder::new().sense(egui::Sense::click()), |ui| {
+                    ui.horizontal(|ui| {
+                        let mut enabled = rule.is_enabled();
+                        if ui.checkbox(&mut enabled, "").on_hover_text("Enable or disable this rule").changed() {
+                            rule.set_enabled(enabled);
+                            changed = true;
                         }
-                        if idx > 0 && ui.small_button("^").clicked() {
-                            move_up_idx = Some(idx);
+                        ui.label(format!("{}.", idx + 1));
+                        if enabled {
+                            ui.strong(rule.name());
+                        } else {
+                            ui.weak(rule.name());
                         }
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.small_button("X").clicked() {
+                                remove_idx = Some(idx);
+                            }
+                            if idx < rules_len - 1 && ui.small_button("v").clicked() {
+                                move_down_idx = Some(idx);
+                            }
+                            if idx > 0 && ui.small_button("^").clicked() {
+                                move_up_idx = Some(idx);
+                            }
+                        });
                     });
                 });
+
+                row.response.context_menu(|ui| {
+                    if ui.button("Duplicate").clicked() {
+                        menu_action = Some((idx, RuleMenuAction::Duplicate));
+                        ui.close_menu();
+                    }
+                    if ui.button("Copy Rule").clicked() {
+                        *clipboard = Some(rule.clone());
+                        ui.close_menu();
+                    }
+                    let has_clipboard = clipboard.is_some();
+                    if ui.add_enabled(has_clipboard, egui::Button::new("Paste Above")).clicked() {
+                        menu_action = Some((idx, RuleMenuAction::PasteAbove));
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(has_clipboard, egui::Button::new("Paste Below")).clicked() {
+                        menu_action = Some((idx, RuleMenuAction::PasteBelow));
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button("Delete").clicked() {
+                        remove_idx = Some(idx);
+                        ui.close_menu();
+                    }
+                });
             })
             .body(|ui| {
                 changed |= render_rule_params(ui, rule);
             });
     }
 
+    // Handle context menu insertions
+    if let Some((idx, action)) = menu_action {
+        let inserted = match action {
+            RuleMenuAction::Duplicate => Some((idx + 1, rules[idx].clone())),
+            RuleMenuAction::PasteAbove => clipboard.clone().map(|rule| (idx, rule)),
+            RuleMenuAction::PasteBelow => clipboard.clone().map(|rule| (idx + 1, rule)),
+        };
+        if let Some((at, rule)) = inserted {
+            rules.insert(at, rule);
+            changed = true;
+        }
+    }
+
     // Handle removals and reordering
     if let Some(idx) = remove_idx {
         rules.remove(idx);
@@ -1,6 +1,6 @@
 //! Rule parameter renderers
 
-use super::helpers::{render_falloff, render_vec3};
+use super::helpers::{custom_code_tooltip, render_falloff, render_vec3, render_wave_shape};
 use crate::config::*;
 use egui::Ui;
 
@@ -23,6 +23,37 @@ pub(super) fn render_rule_params(ui: &mut Ui, rule: &mut RuleConfig) -> bool {
         RuleConfig::Acceleration { direction } => {
             changed |= render_vec3(ui, "Direction", direction);
         }
+        RuleConfig::Pulse {
+            direction,
+            frequency,
+            amplitude,
+            phase,
+            wave_shape,
+        } => {
+            changed |= render_vec3(ui, "Direction", direction);
+            changed |= ui
+                .add(egui::Slider::new(frequency, 0.0..=20.0).text("Frequency (rad/s)"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(amplitude, 0.0..=10.0).text("Amplitude"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(phase, 0.0..=std::f32::consts::TAU).text("Phase"))
+                .changed();
+            changed |= render_wave_shape(ui, wave_shape);
+        }
+        RuleConfig::Magnetic {
+            charge_field,
+            field_strength,
+        } => {
+            ui.horizontal(|ui| {
+                ui.label("Charge Field:");
+                if ui.text_edit_singleline(charge_field).changed() {
+                    changed = true;
+                }
+            });
+            changed |= render_vec3(ui, "Field Strength", field_strength);
+        }
 
         // === Boundaries ===
         RuleConfig::BounceWalls | RuleConfig::WrapWalls => {
@@ -62,18 +93,46 @@ pub(super) fn render_rule_params(ui: &mut Ui, rule: &mut RuleConfig) -> bool {
                 .add(egui::Slider::new(softening, 0.001..=1.0).text("Softening"))
                 .changed();
         }
-        RuleConfig::Orbit { center, strength } => {
+        RuleConfig::Orbit { center, gravity, softening } => {
             changed |= render_vec3(ui, "Center", center);
             changed |= ui
-                .add(egui::Slider::new(strength, 0.0..=5.0).text("Strength"))
+                .add(egui::Slider::new(gravity, 0.0..=5.0).text("Gravity"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(softening, 0.001..=1.0).text("Softening"))
+                .changed();
+        }
+        RuleConfig::OrbitEllipse {
+            center,
+            gravity,
+            central_mass,
+            particle_mass,
+            softening,
+        } => {
+            changed |= render_vec3(ui, "Center", center);
+            changed |= ui
+                .add(egui::Slider::new(gravity, 0.0..=5.0).text("Gravity"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(central_mass, 0.01..=10.0).text("Central Mass"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(particle_mass, 0.0..=10.0).text("Particle Mass"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(softening, 0.001..=1.0).text("Softening"))
                 .changed();
         }
         RuleConfig::Spring {
             anchor,
+            rest_length,
             stiffness,
             damping,
         } => {
             changed |= render_vec3(ui, "Anchor", anchor);
+            changed |= ui
+                .add(egui::Slider::new(rest_length, 0.0..=2.0).text("Rest Length"))
+                .changed();
             changed |= ui
                 .add(egui::Slider::new(stiffness, 0.0..=10.0).text("Stiffness"))
                 .changed();
@@ -100,14 +159,22 @@ pub(super) fn render_rule_params(ui: &mut Ui, rule: &mut RuleConfig) -> bool {
             center,
             axis,
             strength,
+            radius,
+            falloff,
         } => {
             changed |= render_vec3(ui, "Center", center);
             changed |= render_vec3(ui, "Axis", axis);
             changed |= ui
                 .add(egui::Slider::new(strength, -10.0..=10.0).text("Strength"))
                 .changed();
+            changed |= ui
+                .add(egui::Slider::new(radius, 0.0..=5.0).text("Radius (0 = unlimited)"))
+                .changed();
+            if *radius > 0.0 {
+                changed |= render_falloff(ui, falloff);
+            }
         }
-        RuleConfig::Pulse {
+        RuleConfig::RadialPulse {
             point,
             strength,
             frequency,
@@ -134,6 +201,27 @@ pub(super) fn render_rule_params(ui: &mut Ui, rule: &mut RuleConfig) -> bool {
                 .add(egui::Slider::new(strength, 0.0..=5.0).text("Strength"))
                 .changed();
         }
+        RuleConfig::NoiseField {
+            frequency,
+            amplitude,
+            octaves,
+            time_scale,
+            preview,
+        } => {
+            changed |= ui
+                .add(egui::Slider::new(frequency, 0.1..=10.0).text("Frequency"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(amplitude, 0.0..=5.0).text("Amplitude"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(octaves, 1..=8).text("Octaves"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(time_scale, 0.0..=2.0).text("Time Scale"))
+                .changed();
+            changed |= ui.checkbox(preview, "Preview noise").changed();
+        }
         RuleConfig::Curl { scale, strength } => {
             changed |= ui
                 .add(egui::Slider::new(scale, 0.1..=10.0).text("Scale"))
@@ -275,6 +363,44 @@ pub(super) fn render_rule_params(ui: &mut Ui, rule: &mut RuleConfig) -> bool {
                 .add(egui::Slider::new(alignment, 0.0..=5.0).text("Alignment"))
                 .changed();
         }
+        RuleConfig::Boid {
+            separation_radius,
+            cohesion_radius,
+            alignment_radius,
+            separation_weight,
+            cohesion_weight,
+            alignment_weight,
+            self_type,
+        } => {
+            changed |= ui
+                .add(egui::Slider::new(separation_radius, 0.01..=1.0).text("Separation Radius"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(separation_weight, 0.0..=10.0).text("Separation Weight"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(cohesion_radius, 0.01..=1.0).text("Cohesion Radius"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(cohesion_weight, 0.0..=5.0).text("Cohesion Weight"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(alignment_radius, 0.01..=1.0).text("Alignment Radius"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(alignment_weight, 0.0..=5.0).text("Alignment Weight"))
+                .changed();
+            ui.horizontal(|ui| {
+                let mut has_type = self_type.is_some();
+                if ui.checkbox(&mut has_type, "Only Type").changed() {
+                    *self_type = if has_type { Some(0) } else { None };
+                    changed = true;
+                }
+                if let Some(t) = self_type {
+                    changed |= ui.add(egui::Slider::new(t, 0..=7).text("")).changed();
+                }
+            });
+        }
         RuleConfig::Avoid { radius, strength } => {
             changed |= ui
                 .add(egui::Slider::new(radius, 0.01..=1.0).text("Radius"))
@@ -288,6 +414,8 @@ pub(super) fn render_rule_params(ui: &mut Ui, rule: &mut RuleConfig) -> bool {
         RuleConfig::Collide {
             radius,
             restitution,
+            self_type,
+            target_type,
         } => {
             changed |= ui
                 .add(egui::Slider::new(radius, 0.001..=0.5).text("Radius"))
@@ -295,6 +423,18 @@ pub(super) fn render_rule_params(ui: &mut Ui, rule: &mut RuleConfig) -> bool {
             changed |= ui
                 .add(egui::Slider::new(restitution, 0.0..=1.0).text("Restitution"))
                 .changed();
+            for (label, filter) in [("Only Self Type", self_type), ("Only Target Type", target_type)] {
+                ui.horizontal(|ui| {
+                    let mut has_type = filter.is_some();
+                    if ui.checkbox(&mut has_type, label).changed() {
+                        *filter = if has_type { Some(0) } else { None };
+                        changed = true;
+                    }
+                    if let Some(t) = filter {
+                        changed |= ui.add(egui::Slider::new(t, 0..=7).text("")).changed();
+                    }
+                });
+            }
         }
         RuleConfig::NBodyGravity {
             strength,
@@ -335,18 +475,22 @@ pub(super) fn render_rule_params(ui: &mut Ui, rule: &mut RuleConfig) -> bool {
                 .changed();
         }
         RuleConfig::Pressure {
-            radius,
-            strength,
-            target_density,
+            rest_density,
+            pressure_constant,
+            viscosity,
+            kernel_radius,
         } => {
             changed |= ui
-                .add(egui::Slider::new(radius, 0.01..=0.5).text("Radius"))
+                .add(egui::Slider::new(kernel_radius, 0.01..=0.5).text("Kernel Radius"))
                 .changed();
             changed |= ui
-                .add(egui::Slider::new(strength, 0.0..=10.0).text("Strength"))
+                .add(egui::Slider::new(rest_density, 1.0..=30.0).text("Rest Density"))
                 .changed();
             changed |= ui
-                .add(egui::Slider::new(target_density, 1.0..=50.0).text("Target Density"))
+                .add(egui::Slider::new(pressure_constant, 0.0..=5.0).text("Pressure Constant"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(viscosity, 0.0..=0.5).text("Viscosity"))
                 .changed();
         }
         RuleConfig::SurfaceTension {
@@ -420,6 +564,21 @@ pub(super) fn render_rule_params(ui: &mut Ui, rule: &mut RuleConfig) -> bool {
                 .add(egui::Slider::new(t, 0.1..=30.0).text("Lifetime"))
                 .changed();
         }
+        RuleConfig::MaxAge { max_age, fade_out_start, scale_field } => {
+            changed |= ui
+                .add(egui::Slider::new(max_age, 0.1..=30.0).text("Max Age"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(fade_out_start, 0.0..=*max_age).text("Fade Out Start"))
+                .changed();
+            ui.horizontal(|ui| {
+                ui.label("Scale Field:");
+                if ui.text_edit_singleline(scale_field).changed() {
+                    changed = true;
+                }
+            });
+            ui.label("An f32 particle field that stores the scale before fading");
+        }
         RuleConfig::FadeOut(t) => {
             changed |= ui
                 .add(egui::Slider::new(t, 0.1..=30.0).text("Duration"))
@@ -651,8 +810,8 @@ pub(super) fn render_rule_params(ui: &mut Ui, rule: &mut RuleConfig) -> bool {
 
         // === Custom ===
         RuleConfig::Custom { code } => {
-            ui.label("WGSL Code:");
-            if ui.text_edit_multiline(code).changed() {
+            ui.label("WGSL Code:").on_hover_text(custom_code_tooltip());
+            if ui.text_edit_multiline(code).on_hover_text(custom_code_tooltip()).changed() {
                 changed = true;
             }
         }
@@ -1412,6 +1571,15 @@ pub(super) fn render_rule_params(ui: &mut Ui, rule: &mut RuleConfig) -> bool {
                 changed = true;
             }
         }
+        RuleConfig::TypedMulti(matrix) => {
+            let n = matrix.num_types();
+            ui.label(format!("{}×{} interaction matrix", n, n));
+            ui.label(
+                egui::RichText::new("Edit in the Interactions tab")
+                    .small()
+                    .weak(),
+            );
+        }
 
         // Advanced Physics
         RuleConfig::DensityBuoyancy {
@@ -1708,6 +1876,20 @@ pub(super) fn render_rule_params(ui: &mut Ui, rule: &mut RuleConfig) -> bool {
                 .changed();
             changed |= ui.checkbox(ascending, "Ascending").changed();
         }
+        RuleConfig::FieldGradient {
+            field_index,
+            strength,
+            signed,
+        } => {
+            changed |= ui
+                .add(egui::Slider::new(field_index, 0..=7).text("Field Index"))
+                .changed();
+            let range = if *signed { -10.0..=10.0 } else { 0.0..=10.0 };
+            changed |= ui
+                .add(egui::Slider::new(strength, range).text("Strength"))
+                .changed();
+            changed |= ui.checkbox(signed, "Signed").changed();
+        }
 
         // Neighbor Field Operations
         RuleConfig::Accumulate {
@@ -2392,6 +2574,19 @@ pub(super) fn render_rule_params(ui: &mut Ui, rule: &mut RuleConfig) -> bool {
                                 changed = true;
                             }
                         }
+                        UniformValueConfig::I32(v) => {
+                            if ui.add(egui::DragValue::new(v).speed(0.1)).changed() {
+                                changed = true;
+                            }
+                        }
+                        UniformValueConfig::U32(v) => {
+                            if ui.add(egui::DragValue::new(v).speed(0.1)).changed() {
+                                changed = true;
+                            }
+                        }
+                        UniformValueConfig::AnimatedF32 { keyframes } => {
+                            ui.label(format!("curve, {} keyframes", keyframes.len()));
+                        }
                     }
                     if ui.small_button("X").on_hover_text("Remove").clicked() {
                         to_remove = Some(idx);
@@ -2403,6 +2598,9 @@ pub(super) fn render_rule_params(ui: &mut Ui, rule: &mut RuleConfig) -> bool {
                 changed = true;
             }
         }
+        RuleConfig::Disabled(rule) => {
+            changed |= render_rule_params(ui, rule);
+        }
     }
 
     changed
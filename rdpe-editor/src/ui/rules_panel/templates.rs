@@ -12,6 +12,24 @@ pub static RULE_TEMPLATES: &[(&str, &[(&str, fn() -> RuleConfig)])] = &[
             ("Acceleration", || RuleConfig::Acceleration {
                 direction: [0.0, -1.0, 0.0],
             }),
+            ("Pulse", || RuleConfig::Pulse {
+                direction: [0.0, 1.0, 0.0],
+                frequency: 3.0,
+                amplitude: 1.0,
+                phase: 0.0,
+                wave_shape: WaveShape::Sine,
+            }),
+            ("Magnetic", || RuleConfig::Magnetic {
+                charge_field: "charge".into(),
+                field_strength: [0.0, 2.0, 0.0],
+            }),
+            ("Vortex", || RuleConfig::Vortex {
+                center: [0.0, 0.0, 0.0],
+                axis: [0.0, 1.0, 0.0],
+                strength: 2.0,
+                radius: 0.0,
+                falloff: Falloff::Constant,
+            }),
         ],
     ),
     (
@@ -40,10 +58,19 @@ pub static RULE_TEMPLATES: &[(&str, &[(&str, fn() -> RuleConfig)])] = &[
             }),
             ("Orbit", || RuleConfig::Orbit {
                 center: [0.0, 0.0, 0.0],
-                strength: 1.0,
+                gravity: 1.0,
+                softening: 0.05,
+            }),
+            ("Orbit Ellipse", || RuleConfig::OrbitEllipse {
+                center: [0.0, 0.0, 0.0],
+                gravity: 1.0,
+                central_mass: 1.0,
+                particle_mass: 0.1,
+                softening: 0.05,
             }),
             ("Spring", || RuleConfig::Spring {
                 anchor: [0.0, 0.0, 0.0],
+                rest_length: 0.0,
                 stiffness: 1.0,
                 damping: 0.1,
             }),
@@ -53,12 +80,7 @@ pub static RULE_TEMPLATES: &[(&str, &[(&str, fn() -> RuleConfig)])] = &[
                 radius: 1.0,
                 falloff: Falloff::InverseSquare,
             }),
-            ("Vortex", || RuleConfig::Vortex {
-                center: [0.0, 0.0, 0.0],
-                axis: [0.0, 1.0, 0.0],
-                strength: 2.0,
-            }),
-            ("Pulse", || RuleConfig::Pulse {
+            ("Radial Pulse", || RuleConfig::RadialPulse {
                 point: [0.0, 0.0, 0.0],
                 strength: 1.0,
                 frequency: 1.0,
@@ -73,6 +95,13 @@ pub static RULE_TEMPLATES: &[(&str, &[(&str, fn() -> RuleConfig)])] = &[
                 scale: 1.0,
                 strength: 0.5,
             }),
+            ("Noise Field", || RuleConfig::NoiseField {
+                frequency: 2.0,
+                amplitude: 1.0,
+                octaves: 3,
+                time_scale: 0.2,
+                preview: false,
+            }),
             ("Curl", || RuleConfig::Curl {
                 scale: 2.0,
                 strength: 1.0,
@@ -136,6 +165,15 @@ pub static RULE_TEMPLATES: &[(&str, &[(&str, fn() -> RuleConfig)])] = &[
                 cohesion: 1.0,
                 alignment: 1.5,
             }),
+            ("Boid", || RuleConfig::Boid {
+                separation_radius: 0.05,
+                cohesion_radius: 0.2,
+                alignment_radius: 0.1,
+                separation_weight: 3.0,
+                cohesion_weight: 1.0,
+                alignment_weight: 1.5,
+                self_type: None,
+            }),
             ("Avoid", || RuleConfig::Avoid {
                 radius: 0.1,
                 strength: 3.0,
@@ -148,6 +186,8 @@ pub static RULE_TEMPLATES: &[(&str, &[(&str, fn() -> RuleConfig)])] = &[
             ("Collide", || RuleConfig::Collide {
                 radius: 0.05,
                 restitution: 0.8,
+                self_type: None,
+                target_type: None,
             }),
             ("N-Body Gravity", || RuleConfig::NBodyGravity {
                 strength: 0.5,
@@ -159,25 +199,31 @@ pub static RULE_TEMPLATES: &[(&str, &[(&str, fn() -> RuleConfig)])] = &[
                 sigma: 0.05,
                 cutoff: 0.15,
             }),
-            ("Viscosity", || RuleConfig::Viscosity {
-                radius: 0.1,
-                strength: 0.5,
+            ("Magnetism", || RuleConfig::Magnetism {
+                radius: 0.2,
+                strength: 1.0,
+                same_repel: true,
             }),
+        ],
+    ),
+    (
+        "Fluid",
+        &[
             ("Pressure", || RuleConfig::Pressure {
+                rest_density: 8.0,
+                pressure_constant: 0.5,
+                viscosity: 0.02,
+                kernel_radius: 0.1,
+            }),
+            ("Viscosity", || RuleConfig::Viscosity {
                 radius: 0.1,
-                strength: 2.0,
-                target_density: 10.0,
+                strength: 0.5,
             }),
             ("Surface Tension", || RuleConfig::SurfaceTension {
                 radius: 0.1,
                 strength: 1.0,
                 threshold: 5.0,
             }),
-            ("Magnetism", || RuleConfig::Magnetism {
-                radius: 0.2,
-                strength: 1.0,
-                same_repel: true,
-            }),
         ],
     ),
     (
@@ -203,6 +249,11 @@ pub static RULE_TEMPLATES: &[(&str, &[(&str, fn() -> RuleConfig)])] = &[
         &[
             ("Age", || RuleConfig::Age),
             ("Lifetime", || RuleConfig::Lifetime(5.0)),
+            ("Max Age", || RuleConfig::MaxAge {
+                max_age: 5.0,
+                fade_out_start: 4.0,
+                scale_field: "base_scale".into(),
+            }),
             ("Fade Out", || RuleConfig::FadeOut(3.0)),
             ("Shrink Out", || RuleConfig::ShrinkOut(3.0)),
             ("Color Over Life", || RuleConfig::ColorOverLife {
@@ -500,6 +551,11 @@ pub static RULE_TEMPLATES: &[(&str, &[(&str, fn() -> RuleConfig)])] = &[
                 strength: 1.0,
                 ascending: true,
             }),
+            ("Field Gradient", || RuleConfig::FieldGradient {
+                field_index: 0,
+                strength: 1.0,
+                signed: false,
+            }),
         ],
     ),
     (
@@ -3,18 +3,38 @@
 use crate::config::*;
 use egui::Ui;
 
-pub fn render_spawn_panel(ui: &mut Ui, config: &mut SimConfig) -> bool {
+/// A file operation requested from the Spawn tab
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpawnFileRequest {
+    /// Pick an OBJ mesh for the Mesh Surface shape
+    LoadMesh,
+}
+
+/// State for the spawn panel
+#[derive(Default)]
+pub struct SpawnPanelState {
+    /// Whether the particle count slider is held, so growing the particle
+    /// buffer can wait until it is released
+    pub particle_count_dragged: bool,
+}
+
+pub fn render_spawn_panel(
+    ui: &mut Ui,
+    config: &mut SimConfig,
+    state: &mut SpawnPanelState,
+    file_request: &mut Option<SpawnFileRequest>,
+) -> bool {
     let mut changed = false;
 
     ui.heading("Simulation");
 
-    changed |= ui
-        .add(
-            egui::Slider::new(&mut config.particle_count, 100..=100_000)
-                .text("Particles")
-                .logarithmic(true),
-        )
-        .changed();
+    let count_response = ui.add(
+        egui::Slider::new(&mut config.particle_count, 100..=100_000)
+            .text("Particles")
+            .logarithmic(true),
+    );
+    state.particle_count_dragged = count_response.dragged();
+    changed |= count_response.changed();
 
     changed |= ui
         .add(egui::Slider::new(&mut config.bounds, 0.1..=10.0).text("Bounds"))
@@ -28,6 +48,11 @@ pub fn render_spawn_panel(ui: &mut Ui, config: &mut SimConfig) -> bool {
         )
         .changed();
 
+    changed |= ui
+        .checkbox(&mut config.two_d, "2D Mode")
+        .on_hover_text("Keep particles on the XY plane and view them with a top-down orthographic camera")
+        .changed();
+
     // Speed slider - doesn't trigger rebuild, just changes simulation rate
     ui.add(egui::Slider::new(&mut config.speed, 0.01..=100.0).text("Speed"));
 
@@ -43,6 +68,8 @@ pub fn render_spawn_panel(ui: &mut Ui, config: &mut SimConfig) -> bool {
         SpawnShape::Point => 4,
         SpawnShape::Line { .. } => 5,
         SpawnShape::Plane { .. } => 6,
+        SpawnShape::MeshSurface { .. } => 7,
+        SpawnShape::PoissonDisc { .. } => 8,
     };
 
     let shape_changed = ui
@@ -73,6 +100,14 @@ pub fn render_spawn_panel(ui: &mut Ui, config: &mut SimConfig) -> bool {
                 width: 1.0,
                 depth: 1.0,
             },
+            7 => SpawnShape::MeshSurface {
+                mesh: rdpe::MeshData::cube(),
+                offset: 0.0,
+            },
+            8 => SpawnShape::PoissonDisc {
+                min_distance: 0.05,
+                max_attempts: 30,
+            },
             _ => SpawnShape::Sphere { radius: 0.5 },
         };
         changed = true;
@@ -119,6 +154,47 @@ pub fn render_spawn_panel(ui: &mut Ui, config: &mut SimConfig) -> bool {
                 .add(egui::Slider::new(depth, 0.1..=3.0).text("Depth"))
                 .changed();
         }
+        SpawnShape::MeshSurface { mesh, offset } => {
+            ui.label(
+                egui::RichText::new(format!("{} triangles", mesh.indices.len() / 3))
+                    .small()
+                    .weak(),
+            );
+            if ui
+                .button("Load OBJ")
+                .on_hover_text("Spawn on the surface of a Wavefront OBJ mesh")
+                .clicked()
+            {
+                *file_request = Some(SpawnFileRequest::LoadMesh);
+            }
+            changed |= ui
+                .add(egui::Slider::new(offset, -0.5..=0.5).text("Offset"))
+                .on_hover_text("Distance along the surface normal")
+                .changed();
+        }
+        SpawnShape::PoissonDisc { min_distance, max_attempts } => {
+            changed |= ui
+                .add(
+                    egui::Slider::new(min_distance, 0.005..=0.5)
+                        .text("Min Distance")
+                        .logarithmic(true),
+                )
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(max_attempts, 1..=100).text("Attempts"))
+                .on_hover_text("Candidates tried around each point before giving up on it")
+                .changed();
+
+            // Bridson sampling fills about 0.65 points per min_distance² of area
+            let side = 2.0 * config.bounds;
+            let capacity = (0.65 * side * side / (*min_distance * *min_distance)) as u32;
+            if capacity < config.particle_count {
+                ui.colored_label(
+                    egui::Color32::YELLOW,
+                    format!("Only about {} particles fit; the rest start dead", capacity),
+                );
+            }
+        }
     }
 
     ui.separator();
@@ -0,0 +1,273 @@
+//! Particle statistics panel with per-field histograms
+
+use egui::Ui;
+use egui_plot::{Bar, BarChart, Plot};
+
+use crate::config::{ParticleFieldType, ParticleLayout};
+use crate::spawn::{read_f32, read_i32, read_u32};
+
+/// Number of bins in each histogram
+const HISTOGRAM_BINS: usize = 16;
+
+/// Seconds between automatic refreshes
+const AUTO_REFRESH_INTERVAL: f32 = 2.0;
+
+/// Distribution of one scalar value across the alive particles
+pub struct FieldHistogram {
+    pub name: String,
+    pub bins: [u32; HISTOGRAM_BINS],
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    pub std_dev: f32,
+}
+
+impl FieldHistogram {
+    /// Bin the finite values; `None` if there are none.
+    fn from_values(name: String, values: &[f32]) -> Option<Self> {
+        let finite: Vec<f32> = values.iter().copied().filter(|v| v.is_finite()).collect();
+        if finite.is_empty() {
+            return None;
+        }
+
+        let min = finite.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = finite.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let count = finite.len() as f64;
+        let mean = finite.iter().map(|&v| v as f64).sum::<f64>() / count;
+        let variance = finite.iter().map(|&v| (v as f64 - mean).powi(2)).sum::<f64>() / count;
+
+        let mut bins = [0; HISTOGRAM_BINS];
+        let range = max - min;
+        for &v in &finite {
+            let bin = if range > 0.0 {
+                (((v - min) / range) * HISTOGRAM_BINS as f32) as usize
+            } else {
+                0
+            };
+            bins[bin.min(HISTOGRAM_BINS - 1)] += 1;
+        }
+
+        Some(Self {
+            name,
+            bins,
+            min,
+            max,
+            mean: mean as f32,
+            std_dev: variance.sqrt() as f32,
+        })
+    }
+
+    /// Width of one bin in value units (1.0 when all values are equal)
+    fn bin_width(&self) -> f32 {
+        let range = self.max - self.min;
+        if range > 0.0 {
+            range / HISTOGRAM_BINS as f32
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Histograms of every float component and custom field over alive particles.
+///
+/// Vector fields get one histogram per component (`position.x`, ...). The
+/// base `alive` and `particle_type` integers are skipped.
+pub fn compute_histograms(data: &[u8], layout: &ParticleLayout) -> Vec<FieldHistogram> {
+    let alive: Vec<&[u8]> = data
+        .chunks_exact(layout.stride)
+        .filter(|p| read_u32(p, layout.alive_offset) != 0)
+        .collect();
+
+    let mut histograms = Vec::new();
+    for field in &layout.fields {
+        let components: &[&str] = match field.field_type {
+            ParticleFieldType::F32 | ParticleFieldType::U32 | ParticleFieldType::I32 => &[""],
+            ParticleFieldType::Vec2 => &[".x", ".y"],
+            ParticleFieldType::Vec3 => &[".x", ".y", ".z"],
+            ParticleFieldType::Vec4 => &[".x", ".y", ".z", ".w"],
+        };
+        let is_integer = matches!(field.field_type, ParticleFieldType::U32 | ParticleFieldType::I32);
+        if is_integer && field.is_base {
+            continue;
+        }
+
+        for (i, suffix) in components.iter().enumerate() {
+            let offset = field.offset + i * 4;
+            let values: Vec<f32> = alive
+                .iter()
+                .map(|p| match field.field_type {
+                    ParticleFieldType::U32 => read_u32(p, offset) as f32,
+                    ParticleFieldType::I32 => read_i32(p, offset) as f32,
+                    _ => read_f32(p, offset),
+                })
+                .collect();
+            if let Some(histogram) = FieldHistogram::from_values(format!("{}{}", field.name, suffix), &values) {
+                histograms.push(histogram);
+            }
+        }
+    }
+    histograms
+}
+
+/// State for the stats panel
+pub struct StatsPanelState {
+    /// Refresh every [`AUTO_REFRESH_INTERVAL`] seconds while the panel is shown
+    pub auto_refresh: bool,
+    since_refresh: f32,
+    alive_count: usize,
+    histograms: Option<Vec<FieldHistogram>>,
+}
+
+impl Default for StatsPanelState {
+    fn default() -> Self {
+        Self {
+            auto_refresh: true,
+            // Refresh as soon as the panel is first shown
+            since_refresh: AUTO_REFRESH_INTERVAL,
+            alive_count: 0,
+            histograms: None,
+        }
+    }
+}
+
+impl StatsPanelState {
+    /// Recompute the histograms from freshly read particle data.
+    pub fn update(&mut self, data: &[u8], layout: &ParticleLayout) {
+        self.alive_count = data
+            .chunks_exact(layout.stride)
+            .filter(|p| read_u32(p, layout.alive_offset) != 0)
+            .count();
+        self.histograms = Some(compute_histograms(data, layout));
+        self.since_refresh = 0.0;
+    }
+}
+
+/// Render the stats panel.
+///
+/// Returns `true` when particle data should be read back and passed to
+/// [`StatsPanelState::update`]. Only call this while the panel is visible so
+/// the readback stops when it is hidden.
+pub fn render_stats_panel(ui: &mut Ui, state: &mut StatsPanelState, delta_time: f32) -> bool {
+    let mut refresh = false;
+
+    ui.heading("Particle Stats");
+    ui.horizontal(|ui| {
+        if ui.button("Refresh").clicked() {
+            refresh = true;
+        }
+        ui.checkbox(&mut state.auto_refresh, "Auto")
+            .on_hover_text(format!("Refresh every {} seconds", AUTO_REFRESH_INTERVAL));
+    });
+
+    state.since_refresh += delta_time;
+    if state.auto_refresh && state.since_refresh >= AUTO_REFRESH_INTERVAL {
+        refresh = true;
+    }
+    if state.auto_refresh {
+        // Keep repainting so the timer advances without input
+        ui.ctx().request_repaint();
+    }
+
+    ui.separator();
+
+    let Some(histograms) = &state.histograms else {
+        ui.label("Press Refresh to read particle data");
+        return refresh;
+    };
+
+    ui.label(format!("{} alive particles", state.alive_count));
+    if histograms.is_empty() {
+        ui.label("No alive particles");
+        return refresh;
+    }
+
+    for histogram in histograms {
+        ui.add_space(4.0);
+        ui.strong(&histogram.name);
+
+        let width = histogram.bin_width();
+        let bars: Vec<Bar> = histogram
+            .bins
+            .iter()
+            .enumerate()
+            .map(|(i, &count)| {
+                let center = histogram.min + (i as f32 + 0.5) * width;
+                Bar::new(center as f64, count as f64).width(width as f64)
+            })
+            .collect();
+
+        Plot::new(("stats_histogram", &histogram.name))
+            .height(80.0)
+            .allow_zoom(false)
+            .allow_drag(false)
+            .allow_scroll(false)
+            .allow_boxed_zoom(false)
+            .show(ui, |plot_ui| plot_ui.bar_chart(BarChart::new(bars)));
+
+        ui.label(
+            egui::RichText::new(format!(
+                "min {:.3}   max {:.3}   mean {:.3}   std {:.3}",
+                histogram.min, histogram.max, histogram.mean, histogram.std_dev
+            ))
+            .small()
+            .monospace(),
+        );
+    }
+
+    refresh
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ParticleFieldDef;
+    use crate::spawn::{write_f32_pub, write_field_value_pub, write_u32_pub, FieldValue};
+
+    #[test]
+    fn test_histogram_from_values() {
+        let histogram = FieldHistogram::from_values("v".to_string(), &[0.0, 1.0, 2.0, 3.0, f32::NAN]).unwrap();
+        assert_eq!((histogram.min, histogram.max), (0.0, 3.0));
+        assert_eq!(histogram.mean, 1.5);
+        assert!((histogram.std_dev - 1.25f32.sqrt()).abs() < 1e-6);
+        assert_eq!(histogram.bin_width(), 3.0 / HISTOGRAM_BINS as f32);
+        // The maximum lands in the last bin rather than past it
+        assert_eq!(histogram.bins.iter().sum::<u32>(), 4);
+        assert_eq!(histogram.bins[0], 1);
+        assert_eq!(histogram.bins[HISTOGRAM_BINS - 1], 1);
+    }
+
+    #[test]
+    fn test_histogram_degenerate_values() {
+        assert!(FieldHistogram::from_values("v".to_string(), &[]).is_none());
+        assert!(FieldHistogram::from_values("v".to_string(), &[f32::NAN, f32::INFINITY]).is_none());
+
+        let constant = FieldHistogram::from_values("v".to_string(), &[2.0; 5]).unwrap();
+        assert_eq!(constant.bins[0], 5);
+        assert_eq!(constant.std_dev, 0.0);
+        assert_eq!(constant.bin_width(), 1.0);
+    }
+
+    #[test]
+    fn test_compute_histograms_skips_dead_particles() {
+        let layout = ParticleLayout::compute(&[ParticleFieldDef::i32("team")]);
+        let team_offset = layout.field_offset("team").unwrap();
+        let mut data = Vec::new();
+        for (alive, age, team) in [(1, 1.0, -2), (0, 100.0, 50), (1, 3.0, 4)] {
+            let mut particle = layout.zero_bytes();
+            write_u32_pub(&mut particle, layout.alive_offset, alive);
+            write_f32_pub(&mut particle, layout.age_offset, age);
+            write_field_value_pub(&mut particle, team_offset, &FieldValue::I32(team));
+            data.extend(particle);
+        }
+
+        let histograms = compute_histograms(&data, &layout);
+        let names: Vec<&str> = histograms.iter().map(|h| h.name.as_str()).collect();
+        assert!(names.contains(&"position.x") && names.contains(&"color.z"));
+        assert!(!names.contains(&"alive") && !names.contains(&"particle_type"));
+
+        let find = |name: &str| histograms.iter().find(|h| h.name == name).unwrap();
+        assert_eq!((find("age").min, find("age").max), (1.0, 3.0));
+        assert_eq!((find("team").min, find("team").max), (-2.0, 4.0));
+        assert_eq!(find("team").bins.iter().sum::<u32>(), 2);
+    }
+}
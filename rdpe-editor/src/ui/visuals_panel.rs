@@ -2,11 +2,22 @@
 
 use eframe::egui;
 use crate::config::{
-    BlendModeConfig, ColorMappingConfig, ColorMode, PaletteConfig, ParticleShapeConfig,
-    SimConfig, WireframeMeshConfig,
+    sample_gradient, BlendModeConfig, BloomConfig, ColorMappingConfig, ColorMode, LightConfig, MsaaSamplesConfig, PaletteConfig, ParticleShapeConfig,
+    SimConfig, ToneMapModeConfig, WireframeMeshConfig,
 };
 
-pub fn render_visuals_panel(ui: &mut egui::Ui, config: &mut SimConfig) -> bool {
+/// A file operation requested from the Visuals tab
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VisualsFileRequest {
+    /// Pick an OBJ mesh for the wireframe
+    LoadWireframeMesh,
+}
+
+pub fn render_visuals_panel(
+    ui: &mut egui::Ui,
+    config: &mut SimConfig,
+    file_request: &mut Option<VisualsFileRequest>,
+) -> bool {
     let mut changed = false;
     let visuals = &mut config.visuals;
 
@@ -19,7 +30,9 @@ pub fn render_visuals_panel(ui: &mut egui::Ui, config: &mut SimConfig) -> bool {
             ui.selectable_value(&mut visuals.blend_mode, BlendModeConfig::Alpha, "Alpha");
             ui.selectable_value(&mut visuals.blend_mode, BlendModeConfig::Additive, "Additive");
             ui.selectable_value(&mut visuals.blend_mode, BlendModeConfig::Multiply, "Multiply");
-        });
+        })
+        .response
+        .on_hover_text("Applies immediately, without rebuilding the simulation.");
 
     // Particle Shape
     ui.horizontal(|ui| {
@@ -140,6 +153,7 @@ pub fn render_visuals_panel(ui: &mut egui::Ui, config: &mut SimConfig) -> bool {
         ColorMode::ByPosition => 2,
         ColorMode::ByVelocity => 3,
         ColorMode::Gradient { .. } => 4,
+        ColorMode::Rainbow => 5,
     };
 
     if egui::ComboBox::from_label("Color Mode")
@@ -151,7 +165,8 @@ pub fn render_visuals_panel(ui: &mut egui::Ui, config: &mut SimConfig) -> bool {
             1 => ColorMode::RandomHue { saturation: 0.8, value: 0.9 },
             2 => ColorMode::ByPosition,
             3 => ColorMode::ByVelocity,
-            4 => ColorMode::Gradient { start: [1.0, 0.0, 0.0], end: [0.0, 0.0, 1.0] },
+            4 => ColorMode::Gradient { stops: vec![([1.0, 0.0, 0.0], 0.0), ([0.0, 0.0, 1.0], 1.0)] },
+            5 => ColorMode::Rainbow,
             _ => ColorMode::RandomHue { saturation: 0.8, value: 0.9 },
         };
         changed = true;
@@ -171,19 +186,8 @@ pub fn render_visuals_panel(ui: &mut egui::Ui, config: &mut SimConfig) -> bool {
             changed |= ui.add(egui::Slider::new(saturation, 0.0..=1.0).text("Saturation")).changed();
             changed |= ui.add(egui::Slider::new(value, 0.0..=1.0).text("Value")).changed();
         }
-        ColorMode::Gradient { start, end } => {
-            ui.horizontal(|ui| {
-                ui.label("Start:");
-                if ui.color_edit_button_rgb(start).changed() {
-                    changed = true;
-                }
-            });
-            ui.horizontal(|ui| {
-                ui.label("End:");
-                if ui.color_edit_button_rgb(end).changed() {
-                    changed = true;
-                }
-            });
+        ColorMode::Gradient { stops } => {
+            changed |= render_gradient_editor(ui, stops);
         }
         _ => {}
     }
@@ -193,15 +197,47 @@ pub fn render_visuals_panel(ui: &mut egui::Ui, config: &mut SimConfig) -> bool {
 
     // Trail Length
     ui.add(egui::Slider::new(&mut visuals.trail_length, 0..=50).text("Trail Length"));
+    if visuals.trail_length > 0 {
+        ui.horizontal(|ui| {
+            let mut fade = visuals.trail_tail_color.is_some();
+            if ui
+                .checkbox(&mut fade, "Trail Tail Color")
+                .on_hover_text("Blend trails from the particle's color toward this color as they age")
+                .changed()
+            {
+                visuals.trail_tail_color = fade.then_some([0.0, 0.0, 0.0]);
+            }
+            if let Some(color) = &mut visuals.trail_tail_color {
+                ui.color_edit_button_rgb(color);
+            }
+        });
+    }
 
     // Connections
     ui.checkbox(&mut visuals.connections_enabled, "Connections");
     if visuals.connections_enabled {
         ui.add(egui::Slider::new(&mut visuals.connections_radius, 0.01..=0.5).text("Connection Radius"));
         ui.horizontal(|ui| {
-            ui.label("Connection Color:");
-            ui.color_edit_button_rgb(&mut visuals.connections_color);
+            ui.label("Near Color:");
+            ui.color_edit_button_rgb(&mut visuals.connections_near_color);
+        });
+        ui.horizontal(|ui| {
+            let mut gradient = visuals.connections_far_color.is_some();
+            if ui
+                .checkbox(&mut gradient, "Far Color")
+                .on_hover_text("Blend connections toward this color as they approach the radius")
+                .changed()
+            {
+                visuals.connections_far_color = gradient.then_some(visuals.connections_near_color);
+            }
+            if let Some(color) = &mut visuals.connections_far_color {
+                ui.color_edit_button_rgb(color);
+            }
         });
+        ui.checkbox(&mut visuals.connections_directed, "Directed")
+            .on_hover_text("Arrowheads point from the lower-index particle to the higher-index one");
+        ui.add(egui::Slider::new(&mut visuals.connections_flow_speed, 0.0..=5.0).text("Flow Speed"))
+            .on_hover_text("Speed of a dot traveling along each connection (0 = off)");
     }
 
     // Velocity Stretch
@@ -213,23 +249,230 @@ pub fn render_visuals_panel(ui: &mut egui::Ui, config: &mut SimConfig) -> bool {
     // Spatial Grid Debug
     ui.add(egui::Slider::new(&mut visuals.spatial_grid_opacity, 0.0..=1.0).text("Grid Opacity"));
 
+    // Coordinate Axes Debug
+    ui.checkbox(&mut visuals.axes_visible, "Show Axes")
+        .on_hover_text("Draw X (red), Y (green), Z (blue) axes at the origin");
+
     ui.add_space(4.0);
     ui.separator();
 
     // Wireframe
     egui::ComboBox::from_label("Wireframe")
-        .selected_text(format!("{:?}", visuals.wireframe))
+        .selected_text(visuals.wireframe.name())
         .show_ui(ui, |ui| {
-            ui.selectable_value(&mut visuals.wireframe, WireframeMeshConfig::None, "None");
-            ui.selectable_value(&mut visuals.wireframe, WireframeMeshConfig::Tetrahedron, "Tetrahedron");
-            ui.selectable_value(&mut visuals.wireframe, WireframeMeshConfig::Cube, "Cube");
-            ui.selectable_value(&mut visuals.wireframe, WireframeMeshConfig::Octahedron, "Octahedron");
-            ui.selectable_value(&mut visuals.wireframe, WireframeMeshConfig::Icosahedron, "Icosahedron");
+            for option in [
+                WireframeMeshConfig::None,
+                WireframeMeshConfig::Tetrahedron,
+                WireframeMeshConfig::Cube,
+                WireframeMeshConfig::Octahedron,
+                WireframeMeshConfig::Icosahedron,
+                WireframeMeshConfig::Sphere,
+                WireframeMeshConfig::Torus,
+                WireframeMeshConfig::Cylinder,
+            ] {
+                let name = option.name();
+                ui.selectable_value(&mut visuals.wireframe, option, name);
+            }
         });
 
     if visuals.wireframe != WireframeMeshConfig::None {
+        ui.horizontal(|ui| {
+            if ui
+                .button("Load OBJ")
+                .on_hover_text("Draw each particle as the edges of a Wavefront OBJ mesh")
+                .clicked()
+            {
+                *file_request = Some(VisualsFileRequest::LoadWireframeMesh);
+            }
+            if let WireframeMeshConfig::Obj(mesh) = &visuals.wireframe {
+                ui.label(
+                    egui::RichText::new(format!("{} triangles", mesh.indices.len() / 3))
+                        .small()
+                        .weak(),
+                );
+            }
+        });
         ui.add(egui::Slider::new(&mut visuals.wireframe_thickness, 0.001..=0.02).text("Line Thickness"));
     }
 
+    ui.add_space(4.0);
+    ui.separator();
+
+    // Bloom (post-process)
+    let mut bloom_enabled = visuals.bloom.is_some();
+    if ui.checkbox(&mut bloom_enabled, "Bloom")
+        .on_hover_text("Glow around bright particles.")
+        .changed()
+    {
+        visuals.bloom = bloom_enabled.then(BloomConfig::default);
+    }
+    if let Some(ref mut bloom) = visuals.bloom {
+        ui.add(egui::Slider::new(&mut bloom.threshold, 0.0..=2.0).text("Threshold"));
+        ui.add(egui::Slider::new(&mut bloom.intensity, 0.0..=5.0).text("Intensity"));
+        ui.add(egui::Slider::new(&mut bloom.radius, 0.5..=4.0).text("Radius"));
+        ui.add(egui::Slider::new(&mut bloom.iterations, 1..=8).text("Iterations"));
+    }
+
+    // Chromatic aberration (post-process, only applied by the standalone runner)
+    ui.add(egui::Slider::new(&mut visuals.chromatic_aberration, 0.0..=1.0).text("Chromatic Aberration"))
+        .on_hover_text("Separate the color channels toward the screen edges. Shown when running standalone, not in the preview.");
+
+    // HDR + tone mapping (post-process)
+    ui.checkbox(&mut visuals.hdr, "HDR")
+        .on_hover_text("Render to a floating-point target so bright particles can exceed 1.0, then tone-map to the screen.");
+    if visuals.hdr {
+        egui::ComboBox::from_label("Tone Mapping")
+            .selected_text(format!("{:?}", visuals.tone_map))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut visuals.tone_map, ToneMapModeConfig::Reinhard, "Reinhard");
+                ui.selectable_value(&mut visuals.tone_map, ToneMapModeConfig::AcesFast, "ACES (Fast)");
+                ui.selectable_value(&mut visuals.tone_map, ToneMapModeConfig::AcesFilmic, "ACES (Filmic)");
+                ui.selectable_value(&mut visuals.tone_map, ToneMapModeConfig::Linear, "Linear");
+            });
+    }
+
+    // MSAA (only applied by the standalone runner)
+    egui::ComboBox::from_label("MSAA (standalone only)")
+        .selected_text(format!("{:?}", visuals.msaa_samples))
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut visuals.msaa_samples, MsaaSamplesConfig::X1, "Off");
+            ui.selectable_value(&mut visuals.msaa_samples, MsaaSamplesConfig::X2, "2x");
+            ui.selectable_value(&mut visuals.msaa_samples, MsaaSamplesConfig::X4, "4x");
+            ui.selectable_value(&mut visuals.msaa_samples, MsaaSamplesConfig::X8, "8x");
+        })
+        .response
+        .on_hover_text("Anti-aliased particle edges. The preview is not multisampled; this applies when the simulation runs standalone.");
+
+    // Directional light (only applied by the standalone runner)
+    let mut light_enabled = visuals.light.is_some();
+    if ui.checkbox(&mut light_enabled, "Light & Shadows (standalone only)")
+        .on_hover_text("Directional light whose shadows particles cast on each other. The preview is unlit; this applies when the simulation runs standalone.")
+        .changed()
+    {
+        visuals.light = light_enabled.then(LightConfig::default);
+    }
+    if let Some(ref mut light) = visuals.light {
+        ui.horizontal(|ui| {
+            ui.label("Direction");
+            ui.add(egui::DragValue::new(&mut light.direction[0]).speed(0.01).range(-1.0..=1.0));
+            ui.add(egui::DragValue::new(&mut light.direction[1]).speed(0.01).range(-1.0..=1.0));
+            ui.add(egui::DragValue::new(&mut light.direction[2]).speed(0.01).range(-1.0..=1.0));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Color");
+            ui.color_edit_button_rgb(&mut light.color);
+        });
+        egui::ComboBox::from_label("Shadow Map")
+            .selected_text(format!("{}", light.shadow_map_size))
+            .show_ui(ui, |ui| {
+                for size in [512, 1024, 2048, 4096] {
+                    ui.selectable_value(&mut light.shadow_map_size, size, format!("{size}"));
+                }
+            });
+        ui.add(egui::Slider::new(&mut light.shadow_bias, 0.0..=0.05).text("Shadow Bias"));
+    }
+
+    changed
+}
+
+/// Distance in points from a gradient stop's handle within which it can be grabbed
+const STOP_GRAB_RADIUS: f32 = 6.0;
+
+/// Gradient bar with draggable color stops below it.
+///
+/// Drag a handle to move its stop between its neighbors, click it to edit its
+/// color, double-click the bar to add a stop and right-click a handle to
+/// remove it. Stops stay sorted by position.
+fn render_gradient_editor(ui: &mut egui::Ui, stops: &mut Vec<([f32; 3], f32)>) -> bool {
+    let mut changed = false;
+    let selected_id = ui.id().with("selected_gradient_stop");
+    let mut selected: usize = ui.data(|d| d.get_temp(selected_id)).unwrap_or(0);
+
+    ui.label(
+        egui::RichText::new("Drag stops to move them, double-click the bar to add, right-click a stop to remove")
+            .small()
+            .weak(),
+    );
+
+    let width = ui.available_width();
+    let (bar_rect, bar_response) = ui.allocate_exact_size(egui::vec2(width, 20.0), egui::Sense::click());
+    let (handle_rect, _) = ui.allocate_exact_size(egui::vec2(width, 2.0 * STOP_GRAB_RADIUS), egui::Sense::hover());
+    let x_of = |t: f32| bar_rect.left() + t * bar_rect.width();
+    let t_of = |x: f32| ((x - bar_rect.left()) / bar_rect.width()).clamp(0.0, 1.0);
+    let to_color32 = |c: [f32; 3]| -> egui::Color32 { egui::Rgba::from_rgb(c[0], c[1], c[2]).into() };
+
+    // Thin vertical strips approximate the blend
+    let painter = ui.painter();
+    let strips = 64;
+    for i in 0..strips {
+        let (t0, t1) = (i as f32 / strips as f32, (i + 1) as f32 / strips as f32);
+        let strip = egui::Rect::from_x_y_ranges(x_of(t0)..=x_of(t1), bar_rect.y_range());
+        painter.rect_filled(strip, 0.0, to_color32(sample_gradient(stops, (t0 + t1) * 0.5)));
+    }
+    painter.rect_stroke(bar_rect, 0.0, ui.visuals().widgets.noninteractive.bg_stroke, egui::StrokeKind::Inside);
+
+    if bar_response.double_clicked() {
+        if let Some(pos) = bar_response.interact_pointer_pos() {
+            let t = t_of(pos.x);
+            let at = stops.partition_point(|&(_, p)| p <= t);
+            stops.insert(at, (sample_gradient(stops, t), t));
+            selected = at;
+            changed = true;
+        }
+    }
+
+    let mut remove = None;
+    for i in 0..stops.len() {
+        let (color, t) = stops[i];
+        let center = egui::pos2(x_of(t), handle_rect.center().y);
+        let rect = egui::Rect::from_center_size(center, egui::Vec2::splat(2.0 * STOP_GRAB_RADIUS));
+        let response = ui.interact(rect, selected_id.with(i), egui::Sense::click_and_drag());
+
+        if response.clicked() || response.drag_started() {
+            selected = i;
+        }
+        if response.dragged() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                let min_t = if i > 0 { stops[i - 1].1 } else { 0.0 };
+                let max_t = stops.get(i + 1).map_or(1.0, |&(_, p)| p);
+                stops[i].1 = t_of(pos.x).clamp(min_t, max_t);
+                changed = true;
+            }
+        }
+        if response.secondary_clicked() && stops.len() > 2 {
+            remove = Some(i);
+        }
+
+        let outline = if i == selected {
+            ui.visuals().selection.stroke
+        } else {
+            ui.visuals().widgets.inactive.fg_stroke
+        };
+        let painter = ui.painter();
+        painter.add(egui::Shape::convex_polygon(
+            vec![
+                egui::pos2(center.x, handle_rect.top()),
+                egui::pos2(center.x + STOP_GRAB_RADIUS, handle_rect.bottom()),
+                egui::pos2(center.x - STOP_GRAB_RADIUS, handle_rect.bottom()),
+            ],
+            to_color32(color),
+            outline,
+        ));
+    }
+    if let Some(i) = remove {
+        stops.remove(i);
+        changed = true;
+    }
+
+    selected = selected.min(stops.len().saturating_sub(1));
+    if let Some((color, t)) = stops.get_mut(selected) {
+        ui.horizontal(|ui| {
+            ui.label(format!("Stop {}:", selected + 1));
+            changed |= ui.color_edit_button_rgb(color).changed();
+            ui.label(format!("at {:.2}", t));
+        });
+    }
+
+    ui.data_mut(|d| d.insert_temp(selected_id, selected));
     changed
 }
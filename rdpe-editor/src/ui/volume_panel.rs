@@ -1,6 +1,7 @@
 //! Volume rendering configuration panel
 
 use crate::config::{PaletteConfig, VolumeRenderConfig};
+use rdpe::VolumeConfig;
 use egui::Ui;
 
 pub fn render_volume_panel(
@@ -58,7 +59,7 @@ pub fn render_volume_panel(
     // Ray march steps
     let mut steps = volume.steps as i32;
     if ui
-        .add(egui::Slider::new(&mut steps, 16..=256).text("Ray Steps"))
+        .add(egui::Slider::new(&mut steps, 8..=512).text("Ray Steps"))
         .on_hover_text("Number of ray march steps. Higher = better quality, slower.")
         .changed()
     {
@@ -66,6 +67,16 @@ pub fn render_volume_panel(
         changed = true;
     }
 
+    // Step size (0 = auto)
+    changed |= ui
+        .add(
+            egui::Slider::new(&mut volume.step_size, 0.0..=0.2)
+                .text("Step Size")
+                .fixed_decimals(3),
+        )
+        .on_hover_text("Distance between samples. 0 = field width / ray steps.")
+        .changed();
+
     // Density scale
     changed |= ui
         .add(
@@ -76,6 +87,16 @@ pub fn render_volume_panel(
         .on_hover_text("How opaque the volume appears")
         .changed();
 
+    // Density multiplier
+    changed |= ui
+        .add(
+            egui::Slider::new(&mut volume.density_multiplier, 0.1..=10.0)
+                .text("Absorption")
+                .logarithmic(true),
+        )
+        .on_hover_text("Scales opacity without changing the color mapping")
+        .changed();
+
     // Threshold
     changed |= ui
         .add(
@@ -142,6 +163,12 @@ pub fn render_volume_panel(
         changed = true;
     }
 
+    ui.separator();
+    changed |= render_transfer_function_editor(ui, volume);
+
+    ui.separator();
+    changed |= render_clip_plane_editor(ui, volume);
+
     // Info about rebuild requirement
     if changed {
         ui.separator();
@@ -154,3 +181,218 @@ pub fn render_volume_panel(
 
     changed
 }
+
+/// Maximum number of clip planes the volume shader supports.
+const MAX_CLIP_PLANES: usize = 4;
+
+/// List of clip plane equations with axis-aligned presets.
+fn render_clip_plane_editor(ui: &mut Ui, volume: &mut VolumeRenderConfig) -> bool {
+    let mut changed = false;
+
+    ui.label("Clip Planes:");
+    ui.label(
+        egui::RichText::new("ax + by + cz + d = 0, keeping the side where it is positive")
+            .small()
+            .weak(),
+    );
+
+    let mut remove = None;
+    for (i, plane) in volume.clip_planes.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            for (value, label) in plane.iter_mut().zip(["a", "b", "c", "d"]) {
+                changed |= ui
+                    .add(egui::DragValue::new(value).speed(0.01).prefix(format!("{}: ", label)))
+                    .changed();
+            }
+            if ui.small_button("Flip").on_hover_text("Keep the other side").clicked() {
+                *plane = plane.map(|v| -v);
+                changed = true;
+            }
+            if ui.small_button("X").clicked() {
+                remove = Some(i);
+            }
+        });
+    }
+    if let Some(i) = remove {
+        volume.clip_planes.remove(i);
+        changed = true;
+    }
+
+    ui.add_enabled_ui(volume.clip_planes.len() < MAX_CLIP_PLANES, |ui| {
+        ui.horizontal(|ui| {
+            let presets = [
+                ("+ XY", "Cut along the XY plane (keeps z >= 0)", [0.0, 0.0, 1.0, 0.0]),
+                ("+ XZ", "Cut along the XZ plane (keeps y >= 0)", [0.0, 1.0, 0.0, 0.0]),
+                ("+ YZ", "Cut along the YZ plane (keeps x >= 0)", [1.0, 0.0, 0.0, 0.0]),
+                ("+ Custom", "Add a plane equation to edit", [1.0, 1.0, 0.0, 0.0]),
+            ];
+            for (label, hover, plane) in presets {
+                if ui.button(label).on_hover_text(hover).clicked() {
+                    volume.clip_planes.push(plane);
+                    changed = true;
+                }
+            }
+        });
+    });
+
+    changed
+}
+
+fn sort_stops(stops: &mut [([f32; 4], f32)]) {
+    stops.sort_by(|a, b| a.1.total_cmp(&b.1));
+}
+
+/// Gradient bar with draggable stops, plus a color picker and value per stop.
+fn render_transfer_function_editor(ui: &mut Ui, volume: &mut VolumeRenderConfig) -> bool {
+    let mut changed = false;
+
+    ui.label("Transfer Function:");
+
+    if volume.transfer_function.is_empty() {
+        ui.label(
+            egui::RichText::new("Using the palette with opacity rising with density")
+                .small()
+                .weak(),
+        );
+        if ui.button("Customize").clicked() {
+            volume.transfer_function = volume.to_volume_config().transfer_function_stops();
+            changed = true;
+        }
+        return changed;
+    }
+
+    ui.label(
+        egui::RichText::new("Drag stops to move, double-click the bar to add, right-click a stop to remove")
+            .small()
+            .weak(),
+    );
+
+    let domain = volume
+        .transfer_function
+        .iter()
+        .map(|s| s.1)
+        .fold(1.0f32, f32::max);
+    let mut sorted = volume.transfer_function.clone();
+    sort_stops(&mut sorted);
+
+    // Gradient bar
+    let bar_height = 24.0;
+    let handle_size = 10.0;
+    let width = ui.available_width().max(100.0);
+    let (rect, bar_response) = ui.allocate_exact_size(
+        egui::vec2(width, bar_height + handle_size + 2.0),
+        egui::Sense::click(),
+    );
+    let bar_rect = egui::Rect::from_min_size(rect.min, egui::vec2(width, bar_height));
+    let painter = ui.painter_at(rect);
+
+    let segments = 64;
+    for i in 0..segments {
+        let x0 = bar_rect.left() + bar_rect.width() * i as f32 / segments as f32;
+        let x1 = bar_rect.left() + bar_rect.width() * (i + 1) as f32 / segments as f32;
+        let value = (i as f32 + 0.5) / segments as f32 * domain;
+        let [r, g, b, _] = VolumeConfig::sample_stops(&sorted, value);
+        painter.rect_filled(
+            egui::Rect::from_x_y_ranges(x0..=x1, bar_rect.y_range()),
+            0.0,
+            egui::Rgba::from_rgb(r, g, b),
+        );
+    }
+
+    // Opacity curve drawn over the colors
+    let curve: Vec<egui::Pos2> = (0..=segments)
+        .map(|i| {
+            let t = i as f32 / segments as f32;
+            let alpha = VolumeConfig::sample_stops(&sorted, t * domain)[3].clamp(0.0, 1.0);
+            egui::pos2(
+                bar_rect.left() + t * bar_rect.width(),
+                bar_rect.bottom() - alpha * bar_rect.height(),
+            )
+        })
+        .collect();
+    painter.add(egui::Shape::line(curve, egui::Stroke::new(1.5, egui::Color32::WHITE)));
+    painter.rect_stroke(bar_rect, 0.0, egui::Stroke::new(1.0, egui::Color32::GRAY), egui::StrokeKind::Inside);
+
+    // Double-click on the bar adds a stop with the interpolated color
+    if bar_response.double_clicked() {
+        if let Some(pos) = bar_response.interact_pointer_pos() {
+            let value = ((pos.x - bar_rect.left()) / bar_rect.width()).clamp(0.0, 1.0) * domain;
+            volume.transfer_function.push((VolumeConfig::sample_stops(&sorted, value), value));
+            changed = true;
+        }
+    }
+
+    // Stop handles
+    let mut remove = None;
+    let mut drag_stopped = false;
+    let can_remove = volume.transfer_function.len() > 2;
+    for (i, (color, value)) in volume.transfer_function.iter_mut().enumerate() {
+        let x = bar_rect.left() + (*value / domain).clamp(0.0, 1.0) * bar_rect.width();
+        let handle_rect = egui::Rect::from_center_size(
+            egui::pos2(x, bar_rect.bottom() + handle_size * 0.5 + 2.0),
+            egui::vec2(handle_size, handle_size),
+        );
+        let response = ui
+            .interact(handle_rect, ui.id().with(("tf_stop", i)), egui::Sense::click_and_drag())
+            .on_hover_text(format!("{:.3}", value));
+
+        if response.dragged() {
+            *value = (*value + response.drag_delta().x / bar_rect.width() * domain).clamp(0.0, domain);
+            changed = true;
+        }
+        drag_stopped |= response.drag_stopped();
+        if response.secondary_clicked() && can_remove {
+            remove = Some(i);
+        }
+
+        let stroke_color = if response.hovered() || response.dragged() {
+            egui::Color32::WHITE
+        } else {
+            egui::Color32::GRAY
+        };
+        painter.rect_filled(handle_rect, 2.0, egui::Rgba::from_rgb(color[0], color[1], color[2]));
+        painter.rect_stroke(handle_rect, 2.0, egui::Stroke::new(1.5, stroke_color), egui::StrokeKind::Outside);
+        painter.line_segment(
+            [egui::pos2(x, bar_rect.top()), egui::pos2(x, bar_rect.bottom())],
+            egui::Stroke::new(1.0, stroke_color),
+        );
+    }
+
+    if let Some(i) = remove {
+        volume.transfer_function.remove(i);
+        changed = true;
+    }
+    if drag_stopped {
+        sort_stops(&mut volume.transfer_function);
+    }
+
+    // Per-stop color pickers and values
+    let mut remove = None;
+    let can_remove = volume.transfer_function.len() > 2;
+    for (i, (color, value)) in volume.transfer_function.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            changed |= ui.color_edit_button_rgba_unmultiplied(color).changed();
+            changed |= ui
+                .add(egui::DragValue::new(value).speed(0.01).range(0.0..=f32::MAX).prefix("value: "))
+                .changed();
+            if can_remove && ui.small_button("X").on_hover_text("Remove stop").clicked() {
+                remove = Some(i);
+            }
+        });
+    }
+    if let Some(i) = remove {
+        volume.transfer_function.remove(i);
+        changed = true;
+    }
+
+    if ui
+        .button("Reset to Palette")
+        .on_hover_text("Discard the custom curve and use the color palette")
+        .clicked()
+    {
+        volume.transfer_function.clear();
+        changed = true;
+    }
+
+    changed
+}
@@ -12,6 +12,8 @@
 //! | [`Emitter::Cone`] | Directional cone emission |
 //! | [`Emitter::Sphere`] | Spawn on sphere surface |
 //! | [`Emitter::Box`] | Spawn within a box volume |
+//! | [`Emitter::Path`] | Trail particles behind a point moving along a polyline |
+//! | [`Emitter::Texture`] | Spawn on the XZ plane where an image is bright |
 //!
 //! # Velocity Control
 //!
@@ -33,7 +35,10 @@
 //! })
 //! ```
 
-use glam::Vec3;
+use glam::{Vec2, Vec3};
+
+use crate::textures::{TextureConfig, TextureRegistry};
+use crate::uniforms::CustomUniforms;
 
 /// Particle emitter configuration.
 ///
@@ -160,12 +165,241 @@ pub enum Emitter {
         /// Emission rate (particles per second).
         rate: f32,
     },
+
+    /// Emit particles behind a point travelling along a polyline.
+    ///
+    /// The emitter head moves along `points` at `speed` units per second,
+    /// looping back to the start at the end of the path. Each frame the
+    /// simulation advances the head's cursor in `state` on the CPU and
+    /// emits `floor(distance_travelled * emit_per_unit)` particles spread
+    /// over the stretch of path covered, carrying the fraction over to the
+    /// next frame. Exactly that many dead particles respawn, or every dead
+    /// particle when fewer are available. Use [`Emitter::path_head`] to read
+    /// the head from Rust.
+    ///
+    /// # Fields
+    ///
+    /// - `points` - Polyline vertices (at least 2)
+    /// - `speed` - Travel speed in world units per second
+    /// - `emit_per_unit` - Particles emitted per unit of distance
+    /// - `state` - Cursor along the path, starts at [`EmitterState::Path`] with zeros
+    Path {
+        /// Polyline vertices.
+        points: Vec<Vec3>,
+        /// Travel speed in world units per second.
+        speed: f32,
+        /// Particles emitted per unit of distance travelled.
+        emit_per_unit: f32,
+        /// Cursor advanced each frame.
+        state: EmitterState,
+    },
+
+    /// Spawn particles on the XZ plane, weighted by a texture's brightness.
+    ///
+    /// The named texture (added with `.with_texture()`) is stretched over a
+    /// `world_size` rectangle centred on the origin, with the top row of the
+    /// image at `-Z`. Brighter pixels emit more particles and pixels whose
+    /// luminance is below `threshold` never emit. Each pixel at or above the
+    /// threshold contributes `emit_density` particles per second.
+    ///
+    /// The image is reduced to a grid of at most `resolution`×`resolution`
+    /// cells when the shader is built, or the texture's own size when that
+    /// is smaller. Particles are placed uniformly within the chosen cell.
+    ///
+    /// # Fields
+    ///
+    /// - `texture` - Name of a texture in the simulation's texture registry
+    /// - `world_size` - World-space width (X) and depth (Z) of the image
+    /// - `emit_density` - Particles per second per emitting pixel
+    /// - `threshold` - Minimum luminance (0.0-1.0) that emits
+    /// - `resolution` - Grid cells per side, up to [`MAX_TEXTURE_EMITTER_RESOLUTION`]
+    Texture {
+        /// Name of the texture to sample.
+        texture: String,
+        /// World-space size of the image on the XZ plane.
+        world_size: Vec2,
+        /// Particles per second per emitting pixel.
+        emit_density: f32,
+        /// Minimum luminance that emits.
+        threshold: f32,
+        /// Grid cells per side of the brightness CDF.
+        resolution: u32,
+    },
+}
+
+/// Per-frame state an emitter keeps on the CPU.
+///
+/// Emitters that spawn at a fixed place need none and use
+/// [`EmitterState::Stateless`]. A path emitter tracks how far its head has
+/// travelled so the distance covered each frame decides how many particles
+/// it emits.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum EmitterState {
+    /// No state between frames.
+    #[default]
+    Stateless,
+    /// Cursor of a [`Emitter::Path`] head.
+    Path {
+        /// Distance of the head from the first point, in world units.
+        current_t: f32,
+        /// Fraction of a particle left over from earlier frames.
+        carry: f32,
+    },
+}
+
+/// Stretch of path a path emitter covered in one frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct PathStep {
+    /// Distance along the path at the start of the frame.
+    pub from: f32,
+    /// Signed distance travelled during the frame.
+    pub travelled: f32,
+    /// Particles to emit this frame.
+    pub count: u32,
+}
+
+impl PathStep {
+    /// Write this step into the custom uniforms read by path emitter
+    /// `emitter_index`'s shader code.
+    pub(crate) fn write_uniforms(&self, emitter_index: usize, uniforms: &mut CustomUniforms) {
+        uniforms.set(&format!("path_emitter_{emitter_index}_from"), self.from);
+        uniforms.set(&format!("path_emitter_{emitter_index}_travelled"), self.travelled);
+        uniforms.set(&format!("path_emitter_{emitter_index}_count"), self.count as f32);
+    }
 }
 
 impl Emitter {
+    /// Create an emitter that travels along a polyline.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Sparks drawn along a square loop
+    /// .with_emitter(Emitter::along_path(
+    ///     vec![
+    ///         Vec3::new(-0.5, 0.0, -0.5),
+    ///         Vec3::new(0.5, 0.0, -0.5),
+    ///         Vec3::new(0.5, 0.0, 0.5),
+    ///         Vec3::new(-0.5, 0.0, 0.5),
+    ///         Vec3::new(-0.5, 0.0, -0.5),
+    ///     ],
+    ///     0.8,
+    ///     400.0,
+    /// ))
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `points` has fewer than 2 entries.
+    pub fn along_path(points: Vec<Vec3>, speed: f32, emit_per_unit: f32) -> Emitter {
+        assert!(points.len() >= 2, "Path emitter needs at least 2 points");
+        Emitter::Path { points, speed, emit_per_unit, state: EmitterState::Path { current_t: 0.0, carry: 0.0 } }
+    }
+
+    /// Create an emitter that spawns particles where a texture is bright.
+    ///
+    /// The brightness CDF uses a grid of up to
+    /// [`DEFAULT_TEXTURE_EMITTER_RESOLUTION`] cells per side. Use
+    /// [`from_texture_at_resolution`](Self::from_texture_at_resolution) to
+    /// keep finer detail.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Simulation::<Paint>::new()
+    ///     .with_texture("logo", "assets/logo.png")
+    ///     .with_emitter(Emitter::from_texture("logo".into(), Vec2::new(2.0, 1.0), 0.5, 0.2))
+    /// ```
+    pub fn from_texture(texture: String, world_size: Vec2, emit_density: f32, threshold: f32) -> Emitter {
+        Self::from_texture_at_resolution(texture, world_size, emit_density, threshold, DEFAULT_TEXTURE_EMITTER_RESOLUTION)
+    }
+
+    /// Like [`from_texture`](Self::from_texture) with up to `resolution`
+    /// grid cells per side, clamped to [`MAX_TEXTURE_EMITTER_RESOLUTION`].
+    ///
+    /// The CDF is embedded in the compute shader, so its size grows with the
+    /// number of emitting cells.
+    pub fn from_texture_at_resolution(
+        texture: String,
+        world_size: Vec2,
+        emit_density: f32,
+        threshold: f32,
+        resolution: u32,
+    ) -> Emitter {
+        let resolution = resolution.clamp(1, MAX_TEXTURE_EMITTER_RESOLUTION);
+        Emitter::Texture { texture, world_size, emit_density, threshold, resolution }
+    }
+
+    /// Name of the texture a `Texture` emitter samples.
+    pub fn texture_name(&self) -> Option<&str> {
+        match self {
+            Emitter::Texture { texture, .. } => Some(texture),
+            _ => None,
+        }
+    }
+
+    /// Position and travel direction of a path emitter's head after `time`
+    /// seconds, starting from the first point.
+    ///
+    /// Returns `None` for other emitter types and for paths with zero length.
+    pub fn path_position(&self, time: f32) -> Option<(Vec3, Vec3)> {
+        let Emitter::Path { points, speed, .. } = self else {
+            return None;
+        };
+        point_on_path(points, time * speed)
+    }
+
+    /// Current position and travel direction of a path emitter's head,
+    /// from the cursor in its [`EmitterState`].
+    ///
+    /// Returns `None` for other emitter types and for paths with zero length.
+    pub fn path_head(&self) -> Option<(Vec3, Vec3)> {
+        match self {
+            Emitter::Path { points, state: EmitterState::Path { current_t, .. }, .. } => {
+                point_on_path(points, *current_t)
+            }
+            _ => None,
+        }
+    }
+
+    /// Advance the emitter's state by one frame of `delta_time` seconds.
+    ///
+    /// Moves a path emitter's cursor by `speed * delta_time` and returns the
+    /// stretch it covered along with `floor(distance * emit_per_unit)`
+    /// particles to emit, keeping the remainder for the next frame. Returns
+    /// `None` for emitters without state.
+    pub(crate) fn advance(&mut self, delta_time: f32) -> Option<PathStep> {
+        let Emitter::Path { points, speed, emit_per_unit, state } = self else {
+            return None;
+        };
+        let EmitterState::Path { current_t, carry } = state else {
+            return None;
+        };
+        let total = path_lengths(points).last().copied().unwrap_or(0.0);
+        if total <= 0.0 {
+            return Some(PathStep::default());
+        }
+        let travelled = *speed * delta_time;
+        let wanted = travelled.abs() * *emit_per_unit + *carry;
+        let count = wanted.floor().max(0.0);
+        let step = PathStep { from: *current_t, travelled, count: count as u32 };
+        *carry = wanted - count;
+        *current_t = (*current_t + travelled).rem_euclid(total);
+        Some(step)
+    }
+
+    /// Register the custom uniforms this emitter's shader code reads, as
+    /// emitter `emitter_index`.
+    pub(crate) fn register_uniforms(&self, emitter_index: usize, uniforms: &mut CustomUniforms) {
+        if let Emitter::Path { .. } = self {
+            PathStep::default().write_uniforms(emitter_index, uniforms);
+        }
+    }
+
     /// Get the emission rate in particles per second.
     ///
-    /// For `Burst` emitters, returns the count as a one-time rate.
+    /// For `Burst` emitters, returns the count as a one-time rate. For
+    /// `Texture` emitters, returns the rate of a single emitting pixel.
     pub fn rate(&self) -> f32 {
         match self {
             Emitter::Point { rate, .. } => *rate,
@@ -173,6 +407,8 @@ impl Emitter {
             Emitter::Cone { rate, .. } => *rate,
             Emitter::Sphere { rate, .. } => *rate,
             Emitter::Box { rate, .. } => *rate,
+            Emitter::Path { speed, emit_per_unit, .. } => speed.abs() * emit_per_unit,
+            Emitter::Texture { emit_density, .. } => *emit_density,
         }
     }
 
@@ -180,7 +416,9 @@ impl Emitter {
     ///
     /// This code runs at the start of the compute shader for each particle.
     /// Dead particles have a chance to be respawned based on the rate.
-    pub(crate) fn to_wgsl(&self, emitter_index: usize) -> String {
+    /// `textures` supplies the image for `Texture` emitters; one that names
+    /// an unregistered texture emits nothing.
+    pub(crate) fn to_wgsl(&self, emitter_index: usize, textures: &TextureRegistry) -> String {
         match self {
             Emitter::Point { position, rate, speed } => {
                 let speed_code = if *speed > 0.0 {
@@ -360,6 +598,370 @@ impl Emitter {
                     velocity.x, velocity.y, velocity.z,
                 )
             }
+            Emitter::Path { points, .. } => {
+                let lengths = path_lengths(points);
+                let total = lengths.last().copied().unwrap_or(0.0);
+                if points.len() < 2 || total <= 0.0 {
+                    return format!("    // Path emitter {emitter_index} has no length, nothing to emit");
+                }
+                let n = points.len();
+                let point_list = points
+                    .iter()
+                    .map(|p| format!("vec3<f32>({}, {}, {})", p.x, p.y, p.z))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let length_list = lengths
+                    .iter()
+                    .map(|l| format!("{l:?}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    r#"    // Path emitter {emitter_index} ({n} points, length {total})
+    if p.alive == 0u {{
+        // Each dead particle claims a slot; exactly `count` of them spawn
+        let count = u32(uniforms.path_emitter_{emitter_index}_count);
+        let slot = atomicAdd(&counters.emitted[{emitter_index}u], 1u);
+
+        if slot < count {{
+            p.alive = 1u;
+            p.age = 0.0;
+            p.scale = 1.0;
+            p.particle_type = 0u;
+
+            var path_points = array<vec3<f32>, {n}>({point_list});
+            var path_lengths = array<f32, {n}>({length_list});
+
+            // Spread this frame's particles evenly over the stretch the head covered
+            let along = (f32(slot) + 0.5) / f32(count);
+            let travelled = uniforms.path_emitter_{emitter_index}_from + along * uniforms.path_emitter_{emitter_index}_travelled;
+            let d = travelled - floor(travelled / {total}) * {total};
+
+            var segment = 0u;
+            for (var s = 1u; s < {n}u - 1u; s++) {{
+                if path_lengths[s] <= d {{
+                    segment = s;
+                }}
+            }}
+            let seg_len = max(path_lengths[segment + 1u] - path_lengths[segment], 0.000001);
+            let t = clamp((d - path_lengths[segment]) / seg_len, 0.0, 1.0);
+
+            p.position = mix(path_points[segment], path_points[segment + 1u], t);
+            p.velocity = vec3<f32>(0.0);
+        }}
+    }}"#,
+                )
+            }
+            Emitter::Texture { texture, world_size, emit_density, threshold, resolution } => {
+                let Some(config) = textures.get(texture) else {
+                    return format!("    // Texture emitter {emitter_index} uses unknown texture '{texture}'");
+                };
+                let cdf = TextureCdf::build(config, *threshold, *resolution);
+                if cdf.cells.is_empty() {
+                    return format!(
+                        "    // Texture emitter {emitter_index} ('{texture}') has no pixels above the threshold"
+                    );
+                }
+                let rate = cdf.emitting_pixels as f32 * emit_density;
+                let n = cdf.cells.len();
+                let (grid_w, grid_h) = (cdf.grid_width, cdf.grid_height);
+                let cell_list = cdf.cells.iter().map(|(c, _)| format!("{c}u")).collect::<Vec<_>>().join(", ");
+                let cumulative_list = cdf.cells.iter().map(|(_, w)| format!("{w:?}")).collect::<Vec<_>>().join(", ");
+                let (size_x, size_z) = (world_size.x, world_size.y);
+                format!(
+                    r#"    // Texture emitter {emitter_index} ('{texture}', {grid_w}x{grid_h} grid, {n} emitting cells)
+    if p.alive == 0u {{
+        let spawn_hash = (index * 1103515245u + u32(uniforms.time * 10000.0) + {emitter_index}u * 7919u) ^ (index >> 3u);
+        let spawn_chance = f32(spawn_hash & 0xFFFFu) / 65535.0;
+        let spawn_rate = {rate:?} * uniforms.delta_time / f32(num_particles);
+
+        if spawn_chance < spawn_rate {{
+            p.alive = 1u;
+            p.age = 0.0;
+            p.scale = 1.0;
+            p.particle_type = 0u;
+
+            var tex_cells = array<u32, {n}>({cell_list});
+            var tex_cdf = array<f32, {n}>({cumulative_list});
+
+            // Pick a cell by inverting the brightness CDF
+            let vhash = spawn_hash * 0x45d9f3bu;
+            let pick = f32(vhash & 0xFFFFu) / 65536.0;
+            var lo = 0u;
+            var hi = {n}u - 1u;
+            while lo < hi {{
+                let mid = (lo + hi) / 2u;
+                if tex_cdf[mid] <= pick {{
+                    lo = mid + 1u;
+                }} else {{
+                    hi = mid;
+                }}
+            }}
+            let cell = tex_cells[lo];
+
+            // Uniform position within the cell
+            let jhash = vhash * 0x9e3779b9u;
+            let jx = f32(jhash & 0xFFFFu) / 65536.0;
+            let jz = f32((jhash >> 16u) & 0xFFFFu) / 65536.0;
+            let uv = vec2<f32>(
+                (f32(cell % {grid_w}u) + jx) / {grid_w}.0,
+                (f32(cell / {grid_w}u) + jz) / {grid_h}.0
+            );
+
+            p.position = vec3<f32>((uv.x - 0.5) * {size_x:?}, 0.0, (uv.y - 0.5) * {size_z:?});
+            p.velocity = vec3<f32>(0.0);
+        }}
+    }}"#,
+                )
+            }
+        }
+    }
+}
+
+/// Grid cells per side used by [`Emitter::from_texture`].
+pub const DEFAULT_TEXTURE_EMITTER_RESOLUTION: u32 = 32;
+
+/// Largest grid a texture emitter's CDF may use per side.
+pub const MAX_TEXTURE_EMITTER_RESOLUTION: u32 = 128;
+
+/// Brightness distribution of a texture reduced to a coarse grid.
+struct TextureCdf {
+    grid_width: u32,
+    grid_height: u32,
+    /// Emitting cells (row-major index) with their cumulative normalized weight.
+    cells: Vec<(u32, f32)>,
+    /// Number of pixels at or above the threshold.
+    emitting_pixels: u32,
+}
+
+impl TextureCdf {
+    /// Sum pixel luminance above `threshold` into grid cells of at most
+    /// `resolution` per side and accumulate.
+    fn build(config: &TextureConfig, threshold: f32, resolution: u32) -> Self {
+        let grid_width = config.width.clamp(1, resolution.max(1));
+        let grid_height = config.height.clamp(1, resolution.max(1));
+        let mut weights = vec![0.0f32; (grid_width * grid_height) as usize];
+        let mut emitting_pixels = 0;
+
+        for y in 0..config.height {
+            for x in 0..config.width {
+                let i = ((y * config.width + x) * 4) as usize;
+                let [r, g, b] = [config.data[i], config.data[i + 1], config.data[i + 2]].map(|c| c as f32 / 255.0);
+                let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+                if luminance < threshold || luminance <= 0.0 {
+                    continue;
+                }
+                emitting_pixels += 1;
+                let cx = x * grid_width / config.width;
+                let cy = y * grid_height / config.height;
+                weights[(cy * grid_width + cx) as usize] += luminance;
+            }
         }
+
+        let total: f32 = weights.iter().sum();
+        let mut cumulative = 0.0;
+        let cells = weights
+            .iter()
+            .enumerate()
+            .filter(|(_, w)| **w > 0.0)
+            .map(|(i, w)| {
+                cumulative += w / total;
+                (i as u32, cumulative)
+            })
+            .collect();
+
+        Self { grid_width, grid_height, cells, emitting_pixels }
+    }
+}
+
+/// Position and direction `distance` along a polyline, wrapping at the end.
+fn point_on_path(points: &[Vec3], distance: f32) -> Option<(Vec3, Vec3)> {
+    let lengths = path_lengths(points);
+    let total = *lengths.last()?;
+    if total <= 0.0 {
+        return None;
+    }
+    let d = distance.rem_euclid(total);
+    let segment = (1..points.len() - 1).rev().find(|&s| lengths[s] <= d).unwrap_or(0);
+    let seg_len = (lengths[segment + 1] - lengths[segment]).max(1e-6);
+    let t = ((d - lengths[segment]) / seg_len).clamp(0.0, 1.0);
+    let (a, b) = (points[segment], points[segment + 1]);
+    Some((a.lerp(b, t), (b - a).normalize_or_zero()))
+}
+
+/// Cumulative distance from the first point to each point of a polyline.
+fn path_lengths(points: &[Vec3]) -> Vec<f32> {
+    let mut total = 0.0;
+    let mut lengths = Vec::with_capacity(points.len());
+    for (i, point) in points.iter().enumerate() {
+        if i > 0 {
+            total += point.distance(points[i - 1]);
+        }
+        lengths.push(total);
+    }
+    lengths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate_emitter(emitter: &Emitter) {
+        validate_emitter_with(emitter, &TextureRegistry::new());
+    }
+
+    fn validate_emitter_with(emitter: &Emitter, textures: &TextureRegistry) {
+        let source = format!(
+            r#"
+struct Particle {{
+    position: vec3<f32>,
+    velocity: vec3<f32>,
+    age: f32,
+    alive: u32,
+    scale: f32,
+    particle_type: u32,
+}};
+struct Uniforms {{
+    time: f32,
+    delta_time: f32,
+    path_emitter_0_from: f32,
+    path_emitter_0_travelled: f32,
+    path_emitter_0_count: f32,
+}};
+@group(0) @binding(0) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(1) var<uniform> uniforms: Uniforms;
+struct Counters {{
+    alive: atomic<u32>,
+    emitted: array<atomic<u32>>,
+}};
+@group(0) @binding(6) var<storage, read_write> counters: Counters;
+const num_particles: u32 = 1000u;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
+    let index = global_id.x;
+    var p = particles[index];
+{}
+    particles[index] = p;
+}}
+"#,
+            emitter.to_wgsl(0, textures)
+        );
+        let module = naga::front::wgsl::parse_str(&source).expect("emitter WGSL should parse");
+        naga::valid::Validator::new(naga::valid::ValidationFlags::all(), naga::valid::Capabilities::all())
+            .validate(&module)
+            .expect("emitter WGSL should validate");
+    }
+
+    #[test]
+    fn test_path_emitter_position_loops_along_polyline() {
+        let emitter = Emitter::along_path(
+            vec![Vec3::ZERO, Vec3::new(1.0, 0.0, 0.0), Vec3::new(1.0, 2.0, 0.0)],
+            1.0,
+            10.0,
+        );
+        assert_eq!(emitter.rate(), 10.0);
+
+        let (pos, dir) = emitter.path_position(0.5).unwrap();
+        assert!(pos.distance(Vec3::new(0.5, 0.0, 0.0)) < 1e-5);
+        assert_eq!(dir, Vec3::X);
+
+        let (pos, dir) = emitter.path_position(2.0).unwrap();
+        assert!(pos.distance(Vec3::new(1.0, 1.0, 0.0)) < 1e-5);
+        assert_eq!(dir, Vec3::Y);
+
+        // Total length 3, so time 3.5 wraps to 0.5
+        let (pos, _) = emitter.path_position(3.5).unwrap();
+        assert!(pos.distance(Vec3::new(0.5, 0.0, 0.0)) < 1e-5);
+
+        assert!(Emitter::Point { position: Vec3::ZERO, rate: 1.0, speed: 0.0 }.path_position(0.0).is_none());
+    }
+
+    #[test]
+    fn test_path_emitter_wgsl_validates() {
+        validate_emitter(&Emitter::along_path(vec![Vec3::ZERO, Vec3::X], 0.5, 100.0));
+        validate_emitter(&Emitter::along_path(
+            vec![Vec3::ZERO, Vec3::X, Vec3::ONE, Vec3::new(0.0, 1.0, 1.0)],
+            2.0,
+            50.0,
+        ));
+        // Degenerate paths emit nothing but still produce valid code
+        validate_emitter(&Emitter::along_path(vec![Vec3::ONE, Vec3::ONE], 1.0, 1.0));
+    }
+
+    #[test]
+    fn test_path_emitter_advances_cursor() {
+        let mut emitter = Emitter::along_path(vec![Vec3::ZERO, Vec3::X, Vec3::new(1.0, 2.0, 0.0)], 1.0, 10.0);
+        assert_eq!(emitter.path_head(), Some((Vec3::ZERO, Vec3::X)));
+
+        // 0.25 units at 10 per unit is 2.5 particles: emit 2, carry the half
+        let step = emitter.advance(0.25).unwrap();
+        assert_eq!(step, PathStep { from: 0.0, travelled: 0.25, count: 2 });
+        let step = emitter.advance(0.25).unwrap();
+        assert_eq!(step, PathStep { from: 0.25, travelled: 0.25, count: 3 });
+
+        let (pos, _) = emitter.path_head().unwrap();
+        assert!(pos.distance(Vec3::new(0.5, 0.0, 0.0)) < 1e-5);
+
+        // Into the second segment, then wrapping past the end (length 3)
+        emitter.advance(1.5).unwrap();
+        let (pos, dir) = emitter.path_head().unwrap();
+        assert!(pos.distance(Vec3::new(1.0, 1.0, 0.0)) < 1e-5);
+        assert_eq!(dir, Vec3::Y);
+        emitter.advance(1.5).unwrap();
+        let (pos, _) = emitter.path_head().unwrap();
+        assert!(pos.distance(Vec3::new(0.5, 0.0, 0.0)) < 1e-5);
+
+        let mut point = Emitter::Point { position: Vec3::ZERO, rate: 1.0, speed: 0.0 };
+        assert!(point.advance(1.0).is_none());
+        assert!(point.path_head().is_none());
+    }
+
+    /// 4x2 image: left half black, right column bright, one dim pixel.
+    fn test_texture() -> TextureConfig {
+        let mut data = vec![0u8; 4 * 2 * 4];
+        for (x, y, v) in [(3, 0, 255u8), (3, 1, 255), (2, 1, 40)] {
+            let i = (y * 4 + x) * 4;
+            data[i..i + 4].copy_from_slice(&[v, v, v, 255]);
+        }
+        TextureConfig::from_rgba(data, 4, 2)
+    }
+
+    #[test]
+    fn test_texture_cdf_skips_pixels_below_threshold() {
+        let cdf = TextureCdf::build(&test_texture(), 0.5, DEFAULT_TEXTURE_EMITTER_RESOLUTION);
+        assert_eq!((cdf.grid_width, cdf.grid_height), (4, 2));
+        assert_eq!(cdf.emitting_pixels, 2);
+        assert_eq!(cdf.cells.iter().map(|c| c.0).collect::<Vec<_>>(), vec![3, 7]);
+        assert!((cdf.cells[0].1 - 0.5).abs() < 1e-6);
+        assert!((cdf.cells[1].1 - 1.0).abs() < 1e-6);
+
+        // A lower threshold lets the dim pixel emit, weighted by brightness
+        let cdf = TextureCdf::build(&test_texture(), 0.1, DEFAULT_TEXTURE_EMITTER_RESOLUTION);
+        assert_eq!(cdf.emitting_pixels, 3);
+        assert_eq!(cdf.cells.len(), 3);
+        assert!(cdf.cells[1].1 - cdf.cells[0].1 < 0.1);
+    }
+
+    #[test]
+    fn test_texture_emitter_wgsl_validates() {
+        let mut textures = TextureRegistry::new();
+        textures.add("paint", test_texture());
+        validate_emitter_with(&Emitter::from_texture("paint".into(), Vec2::new(2.0, 1.0), 0.5, 0.2), &textures);
+        // Nothing above the threshold still produces valid code
+        validate_emitter_with(&Emitter::from_texture("paint".into(), Vec2::ONE, 0.5, 1.1), &textures);
+        // A missing texture emits nothing instead of panicking
+        validate_emitter_with(&Emitter::from_texture("missing".into(), Vec2::ONE, 0.5, 0.2), &textures);
+    }
+
+    #[test]
+    fn test_texture_cdf_resolution() {
+        // A 2x2 grid merges the two right-hand columns into one cell per row
+        let cdf = TextureCdf::build(&test_texture(), 0.1, 2);
+        assert_eq!((cdf.grid_width, cdf.grid_height), (2, 2));
+        assert_eq!(cdf.emitting_pixels, 3);
+        assert_eq!(cdf.cells.iter().map(|c| c.0).collect::<Vec<_>>(), vec![1, 3]);
+
+        let emitter = Emitter::from_texture_at_resolution("paint".into(), Vec2::ONE, 1.0, 0.1, 10_000);
+        assert!(matches!(emitter, Emitter::Texture { resolution: MAX_TEXTURE_EMITTER_RESOLUTION, .. }));
     }
 }
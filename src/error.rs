@@ -16,6 +16,8 @@ pub enum GpuError {
     DeviceCreation(wgpu::RequestDeviceError),
     /// Failed to map buffer for reading.
     BufferMapping(String),
+    /// The compute shader failed to compile.
+    ShaderCompilation(String),
 }
 
 impl fmt::Display for GpuError {
@@ -25,6 +27,7 @@ impl fmt::Display for GpuError {
             GpuError::NoAdapter => write!(f, "No compatible GPU adapter found. Ensure your system has a GPU with WebGPU/Vulkan/Metal/DX12 support."),
             GpuError::DeviceCreation(e) => write!(f, "Failed to create GPU device: {}", e),
             GpuError::BufferMapping(msg) => write!(f, "Failed to map GPU buffer: {}", msg),
+            GpuError::ShaderCompilation(msg) => write!(f, "Failed to compile compute shader: {}", msg),
         }
     }
 }
@@ -90,6 +93,141 @@ impl From<std::io::Error> for TextureError {
     }
 }
 
+/// Errors that can occur when saving, loading, or restoring a particle snapshot.
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// Failed to read or write the snapshot file.
+    Io(std::io::Error),
+    /// Failed to encode or decode the snapshot.
+    Encoding(bincode::Error),
+    /// The file decoded but its contents are inconsistent.
+    InvalidFormat(String),
+    /// The snapshot doesn't match the particle buffer's layout.
+    LayoutMismatch {
+        /// Particle count and stride of the target buffer.
+        expected: (u32, usize),
+        /// Particle count and stride of the snapshot.
+        found: (u32, usize),
+    },
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(e) => write!(f, "Failed to access snapshot file: {}", e),
+            SnapshotError::Encoding(e) => write!(f, "Failed to encode snapshot: {}", e),
+            SnapshotError::InvalidFormat(msg) => write!(f, "Invalid snapshot file: {}", msg),
+            SnapshotError::LayoutMismatch { expected, found } => write!(
+                f,
+                "Snapshot has {} particles of {} bytes, but the simulation has {} particles of {} bytes",
+                found.0, found.1, expected.0, expected.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SnapshotError::Io(e) => Some(e),
+            SnapshotError::Encoding(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(e: std::io::Error) -> Self {
+        SnapshotError::Io(e)
+    }
+}
+
+impl From<bincode::Error> for SnapshotError {
+    fn from(e: bincode::Error) -> Self {
+        SnapshotError::Encoding(e)
+    }
+}
+
+/// Errors that can occur when recording or loading a particle recording.
+#[derive(Debug)]
+pub enum RecordingError {
+    /// Failed to read or write the recording file.
+    Io(std::io::Error),
+    /// The file is not a valid recording.
+    InvalidFormat(String),
+    /// The recording doesn't match the particle buffer's layout.
+    LayoutMismatch {
+        /// Particle count and stride of the target buffer.
+        expected: (u32, usize),
+        /// Particle count and stride of the recording.
+        found: (u32, usize),
+    },
+}
+
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordingError::Io(e) => write!(f, "Failed to access recording file: {}", e),
+            RecordingError::InvalidFormat(msg) => write!(f, "Invalid recording file: {}", msg),
+            RecordingError::LayoutMismatch { expected, found } => write!(
+                f,
+                "Recording has {} particles of {} bytes, but the simulation has {} particles of {} bytes",
+                found.0, found.1, expected.0, expected.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RecordingError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for RecordingError {
+    fn from(e: std::io::Error) -> Self {
+        RecordingError::Io(e)
+    }
+}
+
+/// Errors that can occur when parsing an OBJ mesh.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObjError {
+    /// The file is not valid UTF-8.
+    InvalidUtf8,
+    /// A `v`, `vn`, or `f` line is malformed.
+    Parse {
+        /// 1-based line number.
+        line: usize,
+    },
+    /// A face references a vertex or normal that doesn't exist.
+    IndexOutOfRange {
+        /// 1-based line number.
+        line: usize,
+    },
+    /// The mesh has more unique vertices than fit in 16-bit indices.
+    TooManyVertices,
+    /// The file contains no faces.
+    NoFaces,
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::InvalidUtf8 => write!(f, "OBJ file is not valid UTF-8"),
+            ObjError::Parse { line } => write!(f, "Malformed OBJ data on line {}", line),
+            ObjError::IndexOutOfRange { line } => write!(f, "OBJ face on line {} references a missing vertex or normal", line),
+            ObjError::TooManyVertices => write!(f, "OBJ mesh has more than 65536 unique vertices"),
+            ObjError::NoFaces => write!(f, "OBJ file contains no faces"),
+        }
+    }
+}
+
+impl std::error::Error for ObjError {}
+
 /// Errors that can occur when running a simulation.
 #[derive(Debug)]
 pub enum SimulationError {
@@ -101,6 +239,20 @@ pub enum SimulationError {
     Gpu(GpuError),
     /// No spawner function provided.
     NoSpawner,
+    /// A sprite sheet is configured but no texture was added.
+    NoSpriteTexture,
+    /// A sprite sheet is configured but several textures were added, so the
+    /// sheet is ambiguous. Holds the number of textures.
+    MultipleSpriteTextures(usize),
+    /// A sub-emitter uses `SpawnTrigger::AgeThreshold` but no rule sets a
+    /// maximum age.
+    NoMaxAge,
+    /// Creating the recorder or validating the playback recording failed.
+    Recording(RecordingError),
+    /// Creating the watched compute shader file failed.
+    ShaderWatch(std::io::Error),
+    /// An emitter names a texture that was not added with `.with_texture()`.
+    UnknownTexture(String),
 }
 
 impl fmt::Display for SimulationError {
@@ -110,6 +262,23 @@ impl fmt::Display for SimulationError {
             SimulationError::Window(e) => write!(f, "Failed to create window: {}", e),
             SimulationError::Gpu(e) => write!(f, "GPU error: {}", e),
             SimulationError::NoSpawner => write!(f, "No spawner function provided. Use .with_spawner() to set one."),
+            SimulationError::NoSpriteTexture => write!(f, "Sprite sheet needs a texture. Add one with .with_texture()."),
+            SimulationError::MultipleSpriteTextures(count) => write!(
+                f,
+                "Sprite sheet needs exactly one texture, but {} were added.",
+                count
+            ),
+            SimulationError::NoMaxAge => write!(
+                f,
+                "SpawnTrigger::AgeThreshold needs a maximum age. Add Rule::Lifetime or Rule::MaxAge."
+            ),
+            SimulationError::Recording(e) => write!(f, "Recording error: {}", e),
+            SimulationError::ShaderWatch(e) => write!(f, "Failed to set up shader watch: {}", e),
+            SimulationError::UnknownTexture(name) => write!(
+                f,
+                "Texture emitter uses unknown texture '{}'. Add it with .with_texture().",
+                name
+            ),
         }
     }
 }
@@ -120,7 +289,13 @@ impl std::error::Error for SimulationError {
             SimulationError::EventLoop(e) => Some(e),
             SimulationError::Window(e) => Some(e),
             SimulationError::Gpu(e) => Some(e),
-            SimulationError::NoSpawner => None,
+            SimulationError::Recording(e) => Some(e),
+            SimulationError::ShaderWatch(e) => Some(e),
+            SimulationError::NoSpawner
+            | SimulationError::NoSpriteTexture
+            | SimulationError::MultipleSpriteTextures(_)
+            | SimulationError::NoMaxAge
+            | SimulationError::UnknownTexture(_) => None,
         }
     }
 }
@@ -142,3 +317,9 @@ impl From<GpuError> for SimulationError {
         SimulationError::Gpu(e)
     }
 }
+
+impl From<RecordingError> for SimulationError {
+    fn from(e: RecordingError) -> Self {
+        SimulationError::Recording(e)
+    }
+}
@@ -8,6 +8,7 @@
 //!
 //! - **Scalar fields** (`f32` per cell): density, temperature, pheromones
 //! - **Vector fields** (`vec3` per cell): velocity, forces, gradients
+//! - **Gray-Scott fields** (two chemicals per cell): reaction-diffusion patterns
 //!
 //! # Use Cases
 //!
@@ -21,14 +22,14 @@
 //! ```ignore
 //! Simulation::<Agent>::new()
 //!     .with_field("pheromone", FieldConfig::new(64).with_decay(0.98).with_blur(0.1))
-//!     .with_rule(Rule::Custom(r#"
+//!     .with_rule(Rule::custom(r#"
 //!         // Deposit pheromone at current position
 //!         field_write(0u, p.position, 0.1);
 //!
 //!         // Sample and follow gradient
 //!         let grad = field_gradient(0u, p.position, 0.05);
 //!         p.velocity += normalize(grad) * 0.5 * uniforms.delta_time;
-//!     "#.into()))
+//!     "#))
 //!     .run();
 //! ```
 //!
@@ -40,7 +41,7 @@
 //! Simulation::<Agent>::new()
 //!     .with_field("food", FieldConfig::new(64).with_decay(0.99))       // Index 0
 //!     .with_field("danger", FieldConfig::new(32).with_decay(0.9))      // Index 1
-//!     .with_rule(Rule::Custom(r#"
+//!     .with_rule(Rule::custom(r#"
 //!         // Read from different fields by index
 //!         let food = field_read(0u, p.position);
 //!         let danger = field_read(1u, p.position);
@@ -49,10 +50,12 @@
 //!         let food_grad = field_gradient(0u, p.position, 0.05);
 //!         let danger_grad = field_gradient(1u, p.position, 0.05);
 //!         p.velocity += food_grad * 2.0 - danger_grad * 5.0;
-//!     "#.into()))
+//!     "#))
 //!     .run();
 //! ```
 
+use std::sync::Arc;
+
 /// Type of field data stored at each cell.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub enum FieldType {
@@ -62,6 +65,11 @@ pub enum FieldType {
     Scalar,
     /// Vector field: one `vec3<f32>` per cell.
     /// Used for: velocity, forces, flow direction, gradients.
+    ///
+    /// Stored like scalar fields, in storage buffers with the three
+    /// components of a cell packed together. Deposits need atomics, which
+    /// WGSL only offers on buffers, so a texture would add a copy pass
+    /// without letting `field_write_vec3` write to it directly.
     Vector,
 }
 
@@ -80,6 +88,56 @@ impl FieldType {
     }
 }
 
+/// How a field treats cells outside its grid.
+///
+/// Applies to sampling, deposits, blur/diffusion and Gray-Scott reaction steps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FieldBoundary {
+    /// Cells outside the grid read as zero (Dirichlet). Values diffuse out
+    /// through the edges, and deposits from outside the grid are dropped.
+    Zero,
+    /// Cells outside the grid repeat the nearest border cell (Neumann).
+    #[default]
+    Clamp,
+    /// The grid wraps around on every axis (toroidal / periodic).
+    Wrap,
+}
+
+impl FieldBoundary {
+    /// Boundary id in `FieldParams` (0 = zero, 1 = clamp, 2 = wrap).
+    pub(crate) fn gpu_id(&self) -> u32 {
+        match self {
+            FieldBoundary::Zero => 0,
+            FieldBoundary::Clamp => 1,
+            FieldBoundary::Wrap => 2,
+        }
+    }
+}
+
+/// Parameters of the Gray-Scott reaction-diffusion model.
+///
+/// Each cell holds two chemical concentrations `U` and `V`, updated every
+/// step with
+///
+/// ```text
+/// dU = Da * ∇²U - U*V² + feed * (1 - U)
+/// dV = Db * ∇²V + U*V² - (feed + kill) * V
+/// ```
+///
+/// The Laplacian is the average of the six face neighbors minus the cell
+/// itself, so `Da = 1.0`, `Db = 0.5` are the usual diffusion rates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GrayScott {
+    /// Rate at which `U` is replenished.
+    pub feed: f32,
+    /// Rate at which `V` is removed.
+    pub kill: f32,
+    /// Diffusion rate of `U`.
+    pub da: f32,
+    /// Diffusion rate of `V`.
+    pub db: f32,
+}
+
 /// Configuration for a 3D spatial field.
 ///
 /// Fields are 3D grids that particles can read from and write to.
@@ -108,6 +166,20 @@ pub struct FieldConfig {
 
     /// Type of field (Scalar or Vector).
     pub field_type: FieldType,
+
+    /// Behavior at the edges of the grid.
+    pub boundary: FieldBoundary,
+
+    /// Gray-Scott reaction-diffusion parameters.
+    /// When set, the field stores `U` and `V` per cell and the reaction
+    /// replaces blur and decay; `blur_iterations` is the number of
+    /// simulation steps per frame.
+    pub gray_scott: Option<GrayScott>,
+
+    /// Initial value of each cell, in x-major order (`x + y*res + z*res²`).
+    /// Expanded per component like
+    /// [`FieldSystemGpu::reset_field_to`](crate::FieldSystemGpu::reset_field_to).
+    pub initial_values: Option<Arc<[f32]>>,
 }
 
 impl FieldConfig {
@@ -140,6 +212,9 @@ impl FieldConfig {
             blur: 0.1,
             blur_iterations: 1,
             field_type: FieldType::Scalar,
+            boundary: FieldBoundary::Clamp,
+            gray_scott: None,
+            initial_values: None,
         }
     }
 
@@ -160,6 +235,30 @@ impl FieldConfig {
         }
     }
 
+    /// Create a Gray-Scott reaction-diffusion field.
+    ///
+    /// The field starts with `U = 1`, `V = 0` everywhere except a small seed
+    /// in the center. `field_write` deposits into `V`, so particles can seed
+    /// new growth, and `field_read` returns `V`. Runs 8 steps per frame at
+    /// resolution 64; change `resolution` or use
+    /// [`with_blur_iterations`](Self::with_blur_iterations) to adjust.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Spots
+    /// .with_field("pattern", FieldConfig::gray_scott(0.0367, 0.0649, 1.0, 0.5))
+    /// ```
+    pub fn gray_scott(feed: f32, kill: f32, da: f32, db: f32) -> Self {
+        Self {
+            decay: 1.0,
+            blur: 0.0,
+            blur_iterations: 8,
+            gray_scott: Some(GrayScott { feed, kill, da, db }),
+            ..Self::new(64)
+        }
+    }
+
     /// Convert this field to a vector field.
     ///
     /// Vector fields store `vec3<f32>` per cell for velocity/force data.
@@ -203,6 +302,19 @@ impl FieldConfig {
         self
     }
 
+    /// Set the boundary condition.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Periodic field for patterns that tile across the edges
+    /// FieldConfig::new(64).with_boundary(FieldBoundary::Wrap)
+    /// ```
+    pub fn with_boundary(mut self, boundary: FieldBoundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
     /// Set the number of blur iterations per frame.
     ///
     /// More iterations = smoother diffusion but more expensive.
@@ -212,14 +324,53 @@ impl FieldConfig {
         self
     }
 
+    /// Set the initial value of every cell.
+    ///
+    /// `values` holds one value per cell, indexed `x + y*res + z*res²`.
+    /// Vector fields get the value in every component; Gray-Scott fields
+    /// get `U = 1` and `V = value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values.len()` is not `resolution³`.
+    pub fn with_initial_values(mut self, values: Vec<f32>) -> Self {
+        assert_eq!(
+            values.len(),
+            self.total_cells() as usize,
+            "Initial field values must have one entry per cell"
+        );
+        self.initial_values = Some(values.into());
+        self
+    }
+
     /// Total number of cells in the field.
     pub fn total_cells(&self) -> u32 {
         self.resolution * self.resolution * self.resolution
     }
 
+    /// Number of f32 components per cell.
+    pub fn components(&self) -> u32 {
+        if self.gray_scott.is_some() {
+            2
+        } else {
+            self.field_type.components()
+        }
+    }
+
     /// Memory size in bytes (for the main field buffer).
     pub fn memory_size(&self) -> usize {
-        self.total_cells() as usize * 4 * self.field_type.components() as usize
+        self.total_cells() as usize * 4 * self.components() as usize
+    }
+
+    /// Field type id in `FieldParams` (0 = scalar, 1 = vector, 2 = Gray-Scott).
+    pub(crate) fn gpu_type(&self) -> u32 {
+        if self.gray_scott.is_some() {
+            2
+        } else if self.is_vector() {
+            1
+        } else {
+            0
+        }
     }
 
     /// Whether this is a vector field.
@@ -282,14 +433,17 @@ impl FieldRegistry {
         // Generate buffer bindings for each field
         // Scalar fields: 2 bindings (write atomic, read f32)
         // Vector fields: 2 bindings (write atomic x3 interleaved, read f32 x3 interleaved)
+        // Gray-Scott fields: 2 bindings (U and V interleaved)
         let mut binding = base_binding;
         for (i, (name, config)) in self.fields.iter().enumerate() {
-            let type_str = if config.is_vector() { "vector" } else { "scalar" };
-            let buffer_size = if config.is_vector() {
-                config.total_cells() * 3
+            let type_str = if config.gray_scott.is_some() {
+                "gray-scott"
+            } else if config.is_vector() {
+                "vector"
             } else {
-                config.total_cells()
+                "scalar"
             };
+            let buffer_size = config.total_cells() * config.components();
 
             code.push_str(&format!(
                 "// Field {}: '{}' ({}, {}³ = {} cells, {} buffer elements)\n",
@@ -322,7 +476,7 @@ impl FieldRegistry {
     decay: f32,
     blur: f32,
     field_type: u32,
-    _pad1: f32,
+    boundary: u32,
     _pad2: f32,
 };
 "#);
@@ -351,16 +505,64 @@ const FIELD_SCALE: f32 = 65536.0;
 // Field type constants
 const FIELD_TYPE_SCALAR: u32 = 0u;
 const FIELD_TYPE_VECTOR: u32 = 1u;
+const FIELD_TYPE_GRAY_SCOTT: u32 = 2u;
+
+// Field boundary constants
+const FIELD_BOUNDARY_ZERO: u32 = 0u;
+const FIELD_BOUNDARY_CLAMP: u32 = 1u;
+const FIELD_BOUNDARY_WRAP: u32 = 2u;
+
+// Cell index for positions outside a Zero-boundary field (writes are dropped)
+const FIELD_OUT_OF_BOUNDS: u32 = 0xffffffffu;
+
+// Normalized field coordinate used for sampling. Clamp pins it inside the
+// grid; Zero and Wrap leave it alone and handle it per cell.
+fn field_boundary_coord(params: FieldParams, normalized: vec3<f32>) -> vec3<f32> {
+    if params.boundary == FIELD_BOUNDARY_CLAMP {
+        return clamp(normalized, vec3<f32>(0.0), vec3<f32>(0.999));
+    }
+    return normalized;
+}
+
+// Flat index of a cell after applying the field's boundary condition.
+// Returns -1 for cells outside a Zero-boundary field.
+fn field_cell_index(params: FieldParams, cell: vec3<i32>) -> i32 {
+    let res = i32(params.resolution);
+    var c: vec3<i32>;
+    switch params.boundary {
+        case FIELD_BOUNDARY_ZERO: {
+            if any(cell < vec3<i32>(0)) || any(cell >= vec3<i32>(res)) {
+                return -1;
+            }
+            c = cell;
+        }
+        case FIELD_BOUNDARY_WRAP: {
+            c = ((cell % res) + res) % res;
+        }
+        default: {
+            c = clamp(cell, vec3<i32>(0), vec3<i32>(res - 1));
+        }
+    }
+    return c.x + c.y * res + c.z * res * res;
+}
 
-// Convert world position to field cell index for a specific field
+// Convert world position to field cell index for a specific field.
+// Returns FIELD_OUT_OF_BOUNDS outside a Zero-boundary field.
 fn field_pos_to_idx(field_idx: u32, pos: vec3<f32>) -> u32 {
     let params = field_params[field_idx];
     let resolution = params.resolution;
     let extent = params.extent;
 
-    // Map world position to 0..resolution
+    // Map world position to 0..resolution (wrapping fields wrap deposits too)
     let half_size = extent;
-    let normalized = (pos + vec3<f32>(half_size)) / (2.0 * half_size);
+    var normalized = (pos + vec3<f32>(half_size)) / (2.0 * half_size);
+    if params.boundary == FIELD_BOUNDARY_WRAP {
+        normalized = fract(normalized);
+    }
+    if params.boundary == FIELD_BOUNDARY_ZERO
+        && (any(normalized < vec3<f32>(0.0)) || any(normalized >= vec3<f32>(1.0))) {
+        return FIELD_OUT_OF_BOUNDS;
+    }
     let clamped = clamp(normalized, vec3<f32>(0.0), vec3<f32>(0.999));
     let cell = vec3<u32>(clamped * f32(resolution));
 
@@ -373,13 +575,22 @@ fn field_pos_to_idx(field_idx: u32, pos: vec3<f32>) -> u32 {
         code.push_str(r#"// Write a scalar value to a field at the given world position (atomic accumulate)
 fn field_write(field_idx: u32, pos: vec3<f32>, value: f32) {
     let idx = field_pos_to_idx(field_idx, pos);
+    if idx == FIELD_OUT_OF_BOUNDS {
+        return;
+    }
     let scaled = i32(clamp(value, -32768.0, 32767.0) * FIELD_SCALE);
 
     switch field_idx {
 "#);
 
         for (i, (_, config)) in self.fields.iter().enumerate() {
-            if !config.is_vector() {
+            if config.gray_scott.is_some() {
+                // Gray-Scott fields deposit into V
+                code.push_str(&format!(
+                    "        case {}u: {{ atomicAdd(&field_{}_write[idx * 2u + 1u], scaled); }}\n",
+                    i, i
+                ));
+            } else if !config.is_vector() {
                 code.push_str(&format!(
                     "        case {}u: {{ atomicAdd(&field_{}_write[idx], scaled); }}\n",
                     i, i
@@ -403,6 +614,9 @@ fn field_write(field_idx: u32, pos: vec3<f32>, value: f32) {
         code.push_str(r#"// Write a vec3 value to a vector field at the given world position (atomic accumulate)
 fn field_write_vec3(field_idx: u32, pos: vec3<f32>, value: vec3<f32>) {
     let idx = field_pos_to_idx(field_idx, pos);
+    if idx == FIELD_OUT_OF_BOUNDS {
+        return;
+    }
     let scaled_x = i32(clamp(value.x, -32768.0, 32767.0) * FIELD_SCALE);
     let scaled_y = i32(clamp(value.y, -32768.0, 32767.0) * FIELD_SCALE);
     let scaled_z = i32(clamp(value.z, -32768.0, 32767.0) * FIELD_SCALE);
@@ -425,6 +639,25 @@ fn field_write_vec3(field_idx: u32, pos: vec3<f32>, value: vec3<f32>) {
 
 "#);
 
+        // Generate per-field cell accessors (index -1 reads as zero)
+        for (i, (_, config)) in self.fields.iter().enumerate() {
+            if config.gray_scott.is_some() {
+                // Gray-Scott fields read V
+                code.push_str(&format!(
+                    "fn field_{i}_cell(c: i32) -> f32 {{\n    if c < 0 {{ return 0.0; }}\n    return field_{i}_read[u32(c) * 2u + 1u];\n}}\n\n"
+                ));
+            } else if config.is_vector() {
+                // For vector fields, read 3 consecutive floats per cell
+                code.push_str(&format!(
+                    "fn field_{i}_cell_vec3(c: i32) -> vec3<f32> {{\n    if c < 0 {{ return vec3<f32>(0.0); }}\n    let base = u32(c) * 3u;\n    return vec3<f32>(field_{i}_read[base], field_{i}_read[base + 1u], field_{i}_read[base + 2u]);\n}}\n\n"
+                ));
+            } else {
+                code.push_str(&format!(
+                    "fn field_{i}_cell(c: i32) -> f32 {{\n    if c < 0 {{ return 0.0; }}\n    return field_{i}_read[u32(c)];\n}}\n\n"
+                ));
+            }
+        }
+
         // Generate field_read function for scalar fields (returns magnitude for vector fields)
         code.push_str(r#"// Read a scalar value from the field (trilinear interpolation)
 // For vector fields, returns the magnitude of the vector
@@ -442,22 +675,21 @@ fn field_read(field_idx: u32, pos: vec3<f32>) -> f32 {
     // Map to float cell coordinates
     let half_size = extent;
     let normalized = (pos + vec3<f32>(half_size)) / (2.0 * half_size);
-    let float_cell = clamp(normalized, vec3<f32>(0.0), vec3<f32>(0.999)) * f32(resolution);
+    let float_cell = field_boundary_coord(params, normalized) * f32(resolution);
 
     // Get integer cell and fraction
-    let cell = vec3<u32>(floor(float_cell));
+    let cell = vec3<i32>(floor(float_cell));
     let frac = fract(float_cell);
 
     // Sample 8 corners for trilinear interpolation
-    let res = resolution;
-    let c000 = cell.x + cell.y * res + cell.z * res * res;
-    let c100 = min(cell.x + 1u, res - 1u) + cell.y * res + cell.z * res * res;
-    let c010 = cell.x + min(cell.y + 1u, res - 1u) * res + cell.z * res * res;
-    let c110 = min(cell.x + 1u, res - 1u) + min(cell.y + 1u, res - 1u) * res + cell.z * res * res;
-    let c001 = cell.x + cell.y * res + min(cell.z + 1u, res - 1u) * res * res;
-    let c101 = min(cell.x + 1u, res - 1u) + cell.y * res + min(cell.z + 1u, res - 1u) * res * res;
-    let c011 = cell.x + min(cell.y + 1u, res - 1u) * res + min(cell.z + 1u, res - 1u) * res * res;
-    let c111 = min(cell.x + 1u, res - 1u) + min(cell.y + 1u, res - 1u) * res + min(cell.z + 1u, res - 1u) * res * res;
+    let c000 = field_cell_index(params, cell);
+    let c100 = field_cell_index(params, cell + vec3<i32>(1, 0, 0));
+    let c010 = field_cell_index(params, cell + vec3<i32>(0, 1, 0));
+    let c110 = field_cell_index(params, cell + vec3<i32>(1, 1, 0));
+    let c001 = field_cell_index(params, cell + vec3<i32>(0, 0, 1));
+    let c101 = field_cell_index(params, cell + vec3<i32>(1, 0, 1));
+    let c011 = field_cell_index(params, cell + vec3<i32>(0, 1, 1));
+    let c111 = field_cell_index(params, cell + vec3<i32>(1, 1, 1));
 
     var v000: f32; var v100: f32; var v010: f32; var v110: f32;
     var v001: f32; var v101: f32; var v011: f32; var v111: f32;
@@ -468,14 +700,13 @@ fn field_read(field_idx: u32, pos: vec3<f32>) -> f32 {
         for (i, (_, config)) in self.fields.iter().enumerate() {
             if !config.is_vector() {
                 code.push_str(&format!(
-                    r#"        case {}u: {{
-            v000 = field_{}_read[c000]; v100 = field_{}_read[c100];
-            v010 = field_{}_read[c010]; v110 = field_{}_read[c110];
-            v001 = field_{}_read[c001]; v101 = field_{}_read[c101];
-            v011 = field_{}_read[c011]; v111 = field_{}_read[c111];
+                    r#"        case {i}u: {{
+            v000 = field_{i}_cell(c000); v100 = field_{i}_cell(c100);
+            v010 = field_{i}_cell(c010); v110 = field_{i}_cell(c110);
+            v001 = field_{i}_cell(c001); v101 = field_{i}_cell(c101);
+            v011 = field_{i}_cell(c011); v111 = field_{i}_cell(c111);
         }}
-"#,
-                    i, i, i, i, i, i, i, i, i
+"#
                 ));
             }
         }
@@ -508,22 +739,21 @@ fn field_read_vec3(field_idx: u32, pos: vec3<f32>) -> vec3<f32> {
     // Map to float cell coordinates
     let half_size = extent;
     let normalized = (pos + vec3<f32>(half_size)) / (2.0 * half_size);
-    let float_cell = clamp(normalized, vec3<f32>(0.0), vec3<f32>(0.999)) * f32(resolution);
+    let float_cell = field_boundary_coord(params, normalized) * f32(resolution);
 
     // Get integer cell and fraction
-    let cell = vec3<u32>(floor(float_cell));
+    let cell = vec3<i32>(floor(float_cell));
     let frac = fract(float_cell);
 
     // Sample 8 corners for trilinear interpolation
-    let res = resolution;
-    let c000 = cell.x + cell.y * res + cell.z * res * res;
-    let c100 = min(cell.x + 1u, res - 1u) + cell.y * res + cell.z * res * res;
-    let c010 = cell.x + min(cell.y + 1u, res - 1u) * res + cell.z * res * res;
-    let c110 = min(cell.x + 1u, res - 1u) + min(cell.y + 1u, res - 1u) * res + cell.z * res * res;
-    let c001 = cell.x + cell.y * res + min(cell.z + 1u, res - 1u) * res * res;
-    let c101 = min(cell.x + 1u, res - 1u) + cell.y * res + min(cell.z + 1u, res - 1u) * res * res;
-    let c011 = cell.x + min(cell.y + 1u, res - 1u) * res + min(cell.z + 1u, res - 1u) * res * res;
-    let c111 = min(cell.x + 1u, res - 1u) + min(cell.y + 1u, res - 1u) * res + min(cell.z + 1u, res - 1u) * res * res;
+    let c000 = field_cell_index(params, cell);
+    let c100 = field_cell_index(params, cell + vec3<i32>(1, 0, 0));
+    let c010 = field_cell_index(params, cell + vec3<i32>(0, 1, 0));
+    let c110 = field_cell_index(params, cell + vec3<i32>(1, 1, 0));
+    let c001 = field_cell_index(params, cell + vec3<i32>(0, 0, 1));
+    let c101 = field_cell_index(params, cell + vec3<i32>(1, 0, 1));
+    let c011 = field_cell_index(params, cell + vec3<i32>(0, 1, 1));
+    let c111 = field_cell_index(params, cell + vec3<i32>(1, 1, 1));
 
     var v000: vec3<f32>; var v100: vec3<f32>; var v010: vec3<f32>; var v110: vec3<f32>;
     var v001: vec3<f32>; var v101: vec3<f32>; var v011: vec3<f32>; var v111: vec3<f32>;
@@ -533,20 +763,14 @@ fn field_read_vec3(field_idx: u32, pos: vec3<f32>) -> vec3<f32> {
 
         for (i, (_, config)) in self.fields.iter().enumerate() {
             if config.is_vector() {
-                // For vector fields, read 3 consecutive floats per cell
                 code.push_str(&format!(
-                    r#"        case {}u: {{
-            v000 = vec3<f32>(field_{}_read[c000 * 3u], field_{}_read[c000 * 3u + 1u], field_{}_read[c000 * 3u + 2u]);
-            v100 = vec3<f32>(field_{}_read[c100 * 3u], field_{}_read[c100 * 3u + 1u], field_{}_read[c100 * 3u + 2u]);
-            v010 = vec3<f32>(field_{}_read[c010 * 3u], field_{}_read[c010 * 3u + 1u], field_{}_read[c010 * 3u + 2u]);
-            v110 = vec3<f32>(field_{}_read[c110 * 3u], field_{}_read[c110 * 3u + 1u], field_{}_read[c110 * 3u + 2u]);
-            v001 = vec3<f32>(field_{}_read[c001 * 3u], field_{}_read[c001 * 3u + 1u], field_{}_read[c001 * 3u + 2u]);
-            v101 = vec3<f32>(field_{}_read[c101 * 3u], field_{}_read[c101 * 3u + 1u], field_{}_read[c101 * 3u + 2u]);
-            v011 = vec3<f32>(field_{}_read[c011 * 3u], field_{}_read[c011 * 3u + 1u], field_{}_read[c011 * 3u + 2u]);
-            v111 = vec3<f32>(field_{}_read[c111 * 3u], field_{}_read[c111 * 3u + 1u], field_{}_read[c111 * 3u + 2u]);
+                    r#"        case {i}u: {{
+            v000 = field_{i}_cell_vec3(c000); v100 = field_{i}_cell_vec3(c100);
+            v010 = field_{i}_cell_vec3(c010); v110 = field_{i}_cell_vec3(c110);
+            v001 = field_{i}_cell_vec3(c001); v101 = field_{i}_cell_vec3(c101);
+            v011 = field_{i}_cell_vec3(c011); v111 = field_{i}_cell_vec3(c111);
         }}
-"#,
-                    i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i, i
+"#
                 ));
             }
         }
@@ -645,6 +869,10 @@ mod tests {
     fn test_field_config_memory_size() {
         let config = FieldConfig::new(32);
         assert_eq!(config.memory_size(), 32 * 32 * 32 * 4); // f32 = 4 bytes
+
+        // Vector cells pack xyz with no unused w
+        let config = FieldConfig::new_vector(32);
+        assert_eq!(config.memory_size(), 32 * 32 * 32 * 12);
     }
 
     #[test]
@@ -844,7 +1072,9 @@ struct Uniforms {{
 
 {field_wgsl}
 
-@compute @workgroup_size(256)
+override WORKGROUP_SIZE: u32 = 256u;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
     let index = global_id.x;
     var p = particles[index];
@@ -899,7 +1129,9 @@ struct Uniforms {{
 
 {wgsl}
 
-@compute @workgroup_size(256)
+override WORKGROUP_SIZE: u32 = 256u;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
     let index = global_id.x;
     var p = particles[index];
@@ -926,6 +1158,71 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
         validate_wgsl(&shader).expect("Multi-field WGSL should be valid");
     }
 
+    #[test]
+    fn test_vector_field_gradient_rule_validates() {
+        let mut registry = FieldRegistry::new();
+        registry.add("wind", FieldConfig::new_vector(32));
+
+        let rule = crate::Rule::FieldGradient {
+            field_index: 0,
+            strength: 1.0,
+            signed: false,
+        };
+        let shader = wrap_field_wgsl(&registry.to_wgsl_declarations(0))
+            .replace("    particles[index] = p;", &format!("{}\n    particles[index] = p;", rule.to_wgsl(1.0)));
+
+        validate_wgsl(&shader).expect("Vector field gradient WGSL should be valid");
+    }
+
+    #[test]
+    fn test_field_config_initial_values() {
+        let config = FieldConfig::new(8).with_initial_values(vec![0.5; 512]);
+        assert_eq!(config.initial_values.as_deref().map(<[f32]>::len), Some(512));
+    }
+
+    #[test]
+    #[should_panic(expected = "one entry per cell")]
+    fn test_field_config_initial_values_wrong_length() {
+        let _ = FieldConfig::new(8).with_initial_values(vec![0.5; 64]);
+    }
+
+    #[test]
+    fn test_field_boundary() {
+        assert_eq!(FieldConfig::new(32).boundary, FieldBoundary::Clamp);
+        let config = FieldConfig::new(32).with_boundary(FieldBoundary::Wrap);
+        assert_eq!(config.boundary.gpu_id(), 2);
+
+        let mut registry = FieldRegistry::new();
+        registry.add("wrapped", config);
+        registry.add("open", FieldConfig::new_vector(16).with_boundary(FieldBoundary::Zero));
+        let wgsl = registry.to_wgsl_declarations(0);
+        assert!(wgsl.contains("fn field_cell_index"));
+        assert!(wgsl.contains("boundary: u32"));
+        // Zero-boundary deposits outside the grid are dropped, not clamped onto the edge
+        assert!(wgsl.contains("return FIELD_OUT_OF_BOUNDS;"));
+        assert_eq!(wgsl.matches("if idx == FIELD_OUT_OF_BOUNDS {").count(), 2);
+
+        validate_wgsl(&wrap_field_wgsl(&wgsl)).expect("Boundary WGSL should be valid");
+    }
+
+    #[test]
+    fn test_gray_scott_field() {
+        let config = FieldConfig::gray_scott(0.0367, 0.0649, 1.0, 0.5);
+        assert_eq!(config.components(), 2);
+        assert_eq!(config.gpu_type(), 2);
+        assert_eq!(config.memory_size(), 64 * 64 * 64 * 4 * 2);
+        assert_eq!(config.gray_scott.unwrap().kill, 0.0649);
+
+        let mut registry = FieldRegistry::new();
+        registry.add("pattern", config);
+        registry.add("heat", FieldConfig::new(32));
+        let wgsl = registry.to_wgsl_declarations(0);
+        assert!(wgsl.contains("field_0_write[idx * 2u + 1u]"));
+        assert!(wgsl.contains("field_0_read[u32(c) * 2u + 1u]"));
+
+        validate_wgsl(&wrap_field_wgsl(&wgsl)).expect("Gray-Scott field WGSL should be valid");
+    }
+
     #[test]
     fn test_field_names_in_comments() {
         let mut registry = FieldRegistry::new();
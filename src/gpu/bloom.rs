@@ -0,0 +1,504 @@
+//! Bloom post-processing effect.
+//!
+//! Extracts pixels brighter than a threshold into a half-resolution HDR
+//! texture, blurs them with a few passes of the Kawase kernel, and adds the
+//! result back onto the scene before any custom post-process shader runs.
+
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+use crate::visuals::BloomConfig;
+
+/// Format of the intermediate bloom textures.
+const BLOOM_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// Per-pass parameters (matches `BloomParams` in the shader).
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct BloomParams {
+    /// Size of one texel of the texture being sampled.
+    texel_size: [f32; 2],
+    /// Kawase sample offset in texels (blur passes only).
+    offset: f32,
+    threshold: f32,
+    /// Width of the soft transition around `threshold`.
+    knee: f32,
+    intensity: f32,
+    _pad: [f32; 2],
+}
+
+/// Resources that depend on the render target size.
+struct BloomTargets {
+    /// Half-resolution ping-pong textures.
+    _textures: [wgpu::Texture; 2],
+    views: [wgpu::TextureView; 2],
+    extract_bind_group: wgpu::BindGroup,
+    blur_bind_groups: Vec<wgpu::BindGroup>,
+    composite_bind_group: wgpu::BindGroup,
+}
+
+/// GPU resources for the bloom passes.
+pub struct BloomState {
+    config: BloomConfig,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    extract_pipeline: wgpu::RenderPipeline,
+    blur_pipeline: wgpu::RenderPipeline,
+    composite_pipeline: wgpu::RenderPipeline,
+    targets: BloomTargets,
+}
+
+impl BloomState {
+    /// Create the bloom passes for a scene texture of the given size.
+    pub fn new(
+        device: &wgpu::Device,
+        config: &BloomConfig,
+        scene_view: &wgpu::TextureView,
+        width: u32,
+        height: u32,
+        scene_format: wgpu::TextureFormat,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Bloom Shader"),
+            source: wgpu::ShaderSource::Wgsl(BLOOM_SHADER.into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Bloom Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Bloom Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Bloom Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let extract_pipeline = create_pipeline(
+            device,
+            &pipeline_layout,
+            &shader,
+            "Bloom Extract Pipeline",
+            "fs_extract",
+            BLOOM_FORMAT,
+            None,
+        );
+        let blur_pipeline = create_pipeline(
+            device,
+            &pipeline_layout,
+            &shader,
+            "Bloom Blur Pipeline",
+            "fs_blur",
+            BLOOM_FORMAT,
+            None,
+        );
+        // Add the blurred highlights onto the scene, keeping its alpha
+        let composite_pipeline = create_pipeline(
+            device,
+            &pipeline_layout,
+            &shader,
+            "Bloom Composite Pipeline",
+            "fs_composite",
+            scene_format,
+            Some(wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::One,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::Zero,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+            }),
+        );
+
+        let targets = create_targets(
+            device,
+            config,
+            &bind_group_layout,
+            &sampler,
+            scene_view,
+            width,
+            height,
+        );
+
+        Self {
+            config: *config,
+            bind_group_layout,
+            sampler,
+            extract_pipeline,
+            blur_pipeline,
+            composite_pipeline,
+            targets,
+        }
+    }
+
+    /// Recreate the bloom textures after the scene texture was resized.
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        scene_view: &wgpu::TextureView,
+        width: u32,
+        height: u32,
+    ) {
+        self.targets = create_targets(
+            device,
+            &self.config,
+            &self.bind_group_layout,
+            &self.sampler,
+            scene_view,
+            width,
+            height,
+        );
+    }
+
+    /// Record the extract, blur and composite passes.
+    ///
+    /// Reads the scene through the view given at creation/resize and adds
+    /// the bloom onto `scene_view`.
+    pub fn execute(&self, encoder: &mut wgpu::CommandEncoder, scene_view: &wgpu::TextureView) {
+        let targets = &self.targets;
+
+        fullscreen_pass(
+            encoder,
+            "Bloom Extract Pass",
+            &targets.views[0],
+            wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+            &self.extract_pipeline,
+            &targets.extract_bind_group,
+        );
+
+        // Ping-pong between the two half-resolution textures
+        for (i, bind_group) in targets.blur_bind_groups.iter().enumerate() {
+            fullscreen_pass(
+                encoder,
+                "Bloom Blur Pass",
+                &targets.views[(i + 1) % 2],
+                wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                &self.blur_pipeline,
+                bind_group,
+            );
+        }
+
+        fullscreen_pass(
+            encoder,
+            "Bloom Composite Pass",
+            scene_view,
+            wgpu::LoadOp::Load,
+            &self.composite_pipeline,
+            &targets.composite_bind_group,
+        );
+    }
+}
+
+fn create_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    label: &str,
+    fragment_entry: &str,
+    format: wgpu::TextureFormat,
+    blend: Option<wgpu::BlendState>,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(label),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: Some("vs_main"),
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: Some(fragment_entry),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend,
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            ..Default::default()
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+        cache: None,
+    })
+}
+
+fn create_targets(
+    device: &wgpu::Device,
+    config: &BloomConfig,
+    layout: &wgpu::BindGroupLayout,
+    sampler: &wgpu::Sampler,
+    scene_view: &wgpu::TextureView,
+    scene_width: u32,
+    scene_height: u32,
+) -> BloomTargets {
+    let width = (scene_width / 2).max(1);
+    let height = (scene_height / 2).max(1);
+
+    let create_texture = || {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Bloom Texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: BLOOM_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        })
+    };
+    let textures = [create_texture(), create_texture()];
+    let views = [
+        textures[0].create_view(&wgpu::TextureViewDescriptor::default()),
+        textures[1].create_view(&wgpu::TextureViewDescriptor::default()),
+    ];
+
+    let base_params = BloomParams {
+        texel_size: [1.0 / width as f32, 1.0 / height as f32],
+        offset: 0.0,
+        threshold: config.threshold,
+        knee: config.threshold * 0.5,
+        intensity: config.intensity,
+        _pad: [0.0; 2],
+    };
+    let create_bind_group = |view: &wgpu::TextureView, params: BloomParams| {
+        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Bloom Params Buffer"),
+            contents: bytemuck::bytes_of(&params),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Bloom Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: params_buffer.as_entire_binding(),
+                },
+            ],
+        })
+    };
+
+    // The extract pass samples the full-resolution scene
+    let extract_bind_group = create_bind_group(
+        scene_view,
+        BloomParams {
+            texel_size: [1.0 / scene_width.max(1) as f32, 1.0 / scene_height.max(1) as f32],
+            ..base_params
+        },
+    );
+
+    // Each Kawase pass samples further out than the last
+    let blur_bind_groups = (0..config.iterations)
+        .map(|i| {
+            create_bind_group(
+                &views[i as usize % 2],
+                BloomParams {
+                    offset: i as f32 * config.radius,
+                    ..base_params
+                },
+            )
+        })
+        .collect();
+
+    let final_view = &views[config.iterations as usize % 2];
+    let composite_bind_group = create_bind_group(final_view, base_params);
+
+    BloomTargets {
+        _textures: textures,
+        views,
+        extract_bind_group,
+        blur_bind_groups,
+        composite_bind_group,
+    }
+}
+
+/// Draw a fullscreen triangle into `target`.
+fn fullscreen_pass(
+    encoder: &mut wgpu::CommandEncoder,
+    label: &str,
+    target: &wgpu::TextureView,
+    load: wgpu::LoadOp<wgpu::Color>,
+    pipeline: &wgpu::RenderPipeline,
+    bind_group: &wgpu::BindGroup,
+) {
+    let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some(label),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: target,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load,
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+
+    render_pass.set_pipeline(pipeline);
+    render_pass.set_bind_group(0, bind_group, &[]);
+    render_pass.draw(0..3, 0..1); // Fullscreen triangle
+}
+
+const BLOOM_SHADER: &str = r#"
+struct BloomParams {
+    texel_size: vec2<f32>,
+    offset: f32,
+    threshold: f32,
+    knee: f32,
+    intensity: f32,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var source: texture_2d<f32>;
+@group(0) @binding(1)
+var source_sampler: sampler;
+@group(0) @binding(2)
+var<uniform> params: BloomParams;
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    var positions = array<vec2<f32>, 3>(
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(3.0, -1.0),
+        vec2<f32>(-1.0, 3.0),
+    );
+    var uvs = array<vec2<f32>, 3>(
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(2.0, 1.0),
+        vec2<f32>(0.0, -1.0),
+    );
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(positions[vertex_index], 0.0, 1.0);
+    out.uv = uvs[vertex_index];
+    return out;
+}
+
+// Keep the part of a color above the threshold, ramping in smoothly
+// between (threshold - knee) and (threshold + knee)
+fn bright_part(color: vec3<f32>) -> vec3<f32> {
+    let brightness = max(color.r, max(color.g, color.b));
+    var soft = clamp(brightness - params.threshold + params.knee, 0.0, 2.0 * params.knee);
+    soft = soft * soft / (4.0 * params.knee + 0.0001);
+    let contribution = max(soft, brightness - params.threshold) / max(brightness, 0.0001);
+    return color * contribution;
+}
+
+// Downsample 2x with a 4-tap box filter, thresholding each tap
+@fragment
+fn fs_extract(in: VertexOutput) -> @location(0) vec4<f32> {
+    let o = params.texel_size * 0.5;
+    var color = bright_part(textureSample(source, source_sampler, in.uv + vec2<f32>(-o.x, -o.y)).rgb);
+    color += bright_part(textureSample(source, source_sampler, in.uv + vec2<f32>(o.x, -o.y)).rgb);
+    color += bright_part(textureSample(source, source_sampler, in.uv + vec2<f32>(-o.x, o.y)).rgb);
+    color += bright_part(textureSample(source, source_sampler, in.uv + vec2<f32>(o.x, o.y)).rgb);
+    return vec4<f32>(color * 0.25, 1.0);
+}
+
+// One Kawase blur pass: average four diagonal taps at the pass offset
+@fragment
+fn fs_blur(in: VertexOutput) -> @location(0) vec4<f32> {
+    let o = (params.offset + 0.5) * params.texel_size;
+    var color = textureSample(source, source_sampler, in.uv + vec2<f32>(-o.x, -o.y)).rgb;
+    color += textureSample(source, source_sampler, in.uv + vec2<f32>(o.x, -o.y)).rgb;
+    color += textureSample(source, source_sampler, in.uv + vec2<f32>(-o.x, o.y)).rgb;
+    color += textureSample(source, source_sampler, in.uv + vec2<f32>(o.x, o.y)).rgb;
+    return vec4<f32>(color * 0.25, 1.0);
+}
+
+@fragment
+fn fs_composite(in: VertexOutput) -> @location(0) vec4<f32> {
+    let bloom = textureSample(source, source_sampler, in.uv).rgb;
+    return vec4<f32>(bloom * params.intensity, 0.0);
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bloom_shader_validates() {
+        let module = naga::front::wgsl::parse_str(BLOOM_SHADER)
+            .unwrap_or_else(|e| panic!("Bloom shader failed to parse: {e:?}"));
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .unwrap_or_else(|e| panic!("Bloom shader failed to validate: {e:?}"));
+    }
+
+    #[test]
+    fn test_bloom_params_match_uniform_layout() {
+        // Six f32 fields padded to a 16-byte multiple
+        assert_eq!(std::mem::size_of::<BloomParams>(), 32);
+    }
+}
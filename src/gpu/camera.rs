@@ -1,4 +1,4 @@
-//! Camera for 3D orbit view with smooth controls.
+//! Camera for 3D orbit view with smooth controls, or a flat 2D view.
 #![allow(dead_code)]
 
 use glam::{Mat4, Vec3};
@@ -15,6 +15,10 @@ use glam::{Mat4, Vec3};
 /// - **R** - Reset to default position
 /// - **Left click** - Reserved
 /// - **Middle click** - Reserved
+///
+/// An [`orthographic`](Self::orthographic) camera looks straight down the Z
+/// axis instead. It can't orbit, zooming scales the visible area, and
+/// forward/backward movement pans up and down.
 pub struct Camera {
     // Current interpolated state
     /// Horizontal rotation angle in radians.
@@ -56,6 +60,10 @@ pub struct Camera {
     /// Maximum zoom distance.
     pub distance_max: f32,
 
+    /// Half the visible width at the default distance, for an orthographic
+    /// projection. `None` uses a perspective projection.
+    pub ortho_half_width: Option<f32>,
+
     // Default values for reset
     default_yaw: f32,
     default_pitch: f32,
@@ -94,6 +102,8 @@ impl Camera {
             distance_min: 0.5,
             distance_max: 50.0,
 
+            ortho_half_width: None,
+
             default_yaw,
             default_pitch,
             default_distance,
@@ -101,6 +111,36 @@ impl Camera {
         }
     }
 
+    /// Create a camera with an orthographic projection looking down -Z at the XY plane.
+    ///
+    /// `half_width` is half the visible width in world units at the default
+    /// zoom; the visible height follows the aspect ratio.
+    pub fn orthographic(half_width: f32) -> Self {
+        let mut camera = Self::new();
+        camera.ortho_half_width = Some(half_width);
+        camera.set_default(0.0, 0.0, camera.default_distance, Vec3::ZERO);
+        camera.reset_instant();
+        camera
+    }
+
+    /// Whether this camera uses an orthographic projection.
+    pub fn is_orthographic(&self) -> bool {
+        self.ortho_half_width.is_some()
+    }
+
+    /// Projection matrix for a viewport with the given width / height ratio.
+    pub fn projection_matrix(&self, aspect: f32) -> Mat4 {
+        match self.ortho_half_width {
+            Some(half_width) => {
+                // Zooming moves the camera, which scales the visible area
+                let half_width = half_width * self.distance / self.default_distance;
+                let half_height = half_width / aspect;
+                Mat4::orthographic_rh(-half_width, half_width, -half_height, half_height, 0.1, 100.0)
+            }
+            None => Mat4::perspective_rh(45.0_f32.to_radians(), aspect, 0.1, 100.0),
+        }
+    }
+
     /// Update camera state with smooth interpolation.
     ///
     /// Call this every frame with the delta time.
@@ -117,6 +157,9 @@ impl Camera {
     ///
     /// `dx` and `dy` are typically mouse delta in pixels.
     pub fn orbit(&mut self, dx: f32, dy: f32) {
+        if self.is_orthographic() {
+            return;
+        }
         self.yaw_target -= dx * self.rotate_speed;
         self.pitch_target += dy * self.rotate_speed;
         self.pitch_target = self.pitch_target.clamp(self.pitch_min, self.pitch_max);
@@ -148,6 +191,11 @@ impl Camera {
     ///
     /// `amount` is in units (will be scaled by move_speed and dt externally).
     pub fn move_forward(&mut self, amount: f32) {
+        if self.is_orthographic() {
+            // Looking straight down Z, "forward" is up the screen
+            self.target_target += self.up() * amount;
+            return;
+        }
         // Forward is the direction from camera to target, projected onto XZ plane
         let forward = self.forward_xz();
         self.target_target += forward * amount;
@@ -1,15 +1,25 @@
 //! Connection rendering between nearby particles.
 //!
 //! Draws lines between particles that are within a specified radius,
-//! using spatial hashing for efficient neighbor queries.
+//! using spatial hashing for efficient neighbor queries. Line color blends
+//! from a near color to a far color by distance relative to the radius.
+//!
+//! Directed connections point from the lower-index particle to the higher
+//! one with an arrowhead, and can animate a dot flowing along the line.
 
 use bytemuck::{Pod, Zeroable};
 use glam::Vec3;
 use wgpu::util::DeviceExt;
 
-use super::{blend_mode_to_state, SpatialGpu, DEPTH_FORMAT};
+use super::{blend_mode_to_state, workgroup_size_constants, SpatialGpu, DEPTH_FORMAT};
 use crate::visuals::BlendMode;
 
+/// Vertices per connection for a plain line quad.
+const LINE_VERTICES: u32 = 6;
+
+/// Vertices per connection with the arrowhead triangle and flow dot quad.
+const DIRECTED_VERTICES: u32 = 15;
+
 /// Parameters for connection rendering (compute shader).
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable)]
@@ -24,8 +34,10 @@ struct ConnectionParams {
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable)]
 struct RenderParams {
-    color: [f32; 3],
-    _pad: f32,
+    near_color: [f32; 3],
+    _pad0: f32,
+    far_color: [f32; 3],
+    _pad1: f32,
 }
 
 /// GPU resources for connection rendering.
@@ -33,6 +45,8 @@ struct RenderParams {
 pub struct ConnectionState {
     /// Buffer storing connection line segments.
     pub buffer: wgpu::Buffer,
+    /// Per-connection `distance / radius`, read as an instance vertex attribute.
+    pub data_buffer: wgpu::Buffer,
     /// Atomic counter for number of connections found.
     pub count_buffer: wgpu::Buffer,
     /// Compute pipeline for finding connections.
@@ -45,6 +59,8 @@ pub struct ConnectionState {
     pub render_bind_group: wgpu::BindGroup,
     /// Maximum number of connections.
     pub max_connections: u32,
+    /// Vertices drawn per connection instance.
+    pub vertices_per_connection: u32,
     /// Connection radius.
     pub radius: f32,
     /// Params buffer (kept alive for bind group).
@@ -62,22 +78,19 @@ impl ConnectionState {
         uniform_buffer: &wgpu::Buffer,
         spatial: &SpatialGpu,
         num_particles: u32,
+        workgroup_size: u32,
         radius: f32,
-        color: Vec3,
+        near_color: Vec3,
+        far_color: Vec3,
+        directed: bool,
+        flow_speed: f32,
         particle_stride: usize,
         blend_mode: BlendMode,
         surface_format: wgpu::TextureFormat,
+        sample_count: u32,
     ) -> Self {
-        let max_connections = num_particles * 8;
-
-        // Connection buffer: stores line segments as vec4 pairs
-        let buffer_size = (max_connections as usize) * 32; // 2 vec4s per connection
-        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Connection Buffer"),
-            size: buffer_size as u64,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::VERTEX,
-            mapped_at_creation: false,
-        });
+        let (buffer, data_buffer, params_buffer, max_connections) =
+            create_connection_buffers(device, num_particles, radius);
 
         // Atomic counter for number of connections
         let count_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -86,23 +99,12 @@ impl ConnectionState {
             usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
         });
 
-        // Connection params (compute shader)
-        let conn_params = ConnectionParams {
-            radius,
-            max_connections,
-            num_particles,
-            _pad: 0,
-        };
-        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Connection Params Buffer"),
-            contents: bytemuck::bytes_of(&conn_params),
-            usage: wgpu::BufferUsages::UNIFORM,
-        });
-
         // Render params (render shader)
         let render_params = RenderParams {
-            color: color.to_array(),
-            _pad: 0.0,
+            near_color: near_color.to_array(),
+            _pad0: 0.0,
+            far_color: far_color.to_array(),
+            _pad1: 0.0,
         };
         let render_params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Connection Render Params Buffer"),
@@ -115,10 +117,12 @@ impl ConnectionState {
             device,
             particle_buffer,
             &buffer,
+            &data_buffer,
             &count_buffer,
             &params_buffer,
             spatial,
             particle_stride,
+            workgroup_size,
         );
 
         // Create render pipeline
@@ -127,38 +131,214 @@ impl ConnectionState {
             uniform_buffer,
             &buffer,
             &render_params_buffer,
+            directed,
+            flow_speed,
             blend_mode,
             surface_format,
+            sample_count,
         );
 
+        let vertices_per_connection = if directed || flow_speed > 0.0 {
+            DIRECTED_VERTICES
+        } else {
+            LINE_VERTICES
+        };
+
         Self {
             buffer,
+            data_buffer,
             count_buffer,
             compute_pipeline,
             compute_bind_group,
             render_pipeline,
             render_bind_group,
             max_connections,
+            vertices_per_connection,
             radius,
             params_buffer,
             render_params_buffer,
         }
     }
+
+    /// Reallocate the connection buffer for a new particle count.
+    ///
+    /// Pipelines are kept; call after [`SpatialGpu::resize`] so the new
+    /// sort buffers are bound.
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        particle_buffer: &wgpu::Buffer,
+        uniform_buffer: &wgpu::Buffer,
+        spatial: &SpatialGpu,
+        num_particles: u32,
+    ) {
+        let (buffer, data_buffer, params_buffer, max_connections) =
+            create_connection_buffers(device, num_particles, self.radius);
+        self.compute_bind_group = create_compute_bind_group(
+            device,
+            &self.compute_pipeline.get_bind_group_layout(0),
+            particle_buffer,
+            &buffer,
+            &data_buffer,
+            &self.count_buffer,
+            &params_buffer,
+            spatial,
+        );
+        self.render_bind_group = create_render_bind_group(
+            device,
+            &self.render_pipeline.get_bind_group_layout(0),
+            uniform_buffer,
+            &buffer,
+            &self.render_params_buffer,
+        );
+        self.buffer = buffer;
+        self.data_buffer = data_buffer;
+        self.params_buffer = params_buffer;
+        self.max_connections = max_connections;
+    }
 }
 
+/// Create the connection segment and data buffers and the compute params uniform.
+///
+/// Returns the buffers and the maximum number of connections they hold.
+fn create_connection_buffers(
+    device: &wgpu::Device,
+    num_particles: u32,
+    radius: f32,
+) -> (wgpu::Buffer, wgpu::Buffer, wgpu::Buffer, u32) {
+    let max_connections = num_particles * 8;
+
+    // Connection buffer: stores line segments as vec4 pairs
+    let buffer_size = (max_connections as usize) * 32; // 2 vec4s per connection
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Connection Buffer"),
+        size: buffer_size as u64,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::VERTEX,
+        mapped_at_creation: false,
+    });
+
+    // Connection data: one f32 (distance / radius) per connection
+    let data_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Connection Data Buffer"),
+        size: max_connections as u64 * 4,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::VERTEX,
+        mapped_at_creation: false,
+    });
+
+    // Connection params (compute shader)
+    let conn_params = ConnectionParams {
+        radius,
+        max_connections,
+        num_particles,
+        _pad: 0,
+    };
+    let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Connection Params Buffer"),
+        contents: bytemuck::bytes_of(&conn_params),
+        usage: wgpu::BufferUsages::UNIFORM,
+    });
+
+    (buffer, data_buffer, params_buffer, max_connections)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_compute_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    particle_buffer: &wgpu::Buffer,
+    connection_buffer: &wgpu::Buffer,
+    data_buffer: &wgpu::Buffer,
+    count_buffer: &wgpu::Buffer,
+    params_buffer: &wgpu::Buffer,
+    spatial: &SpatialGpu,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Connection Compute Bind Group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: particle_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: connection_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: count_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 3,
+                resource: params_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 4,
+                resource: spatial.particle_indices_a.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 5,
+                resource: spatial.cell_start.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 6,
+                resource: spatial.cell_end.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 7,
+                resource: spatial.spatial_params_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 8,
+                resource: data_buffer.as_entire_binding(),
+            },
+        ],
+    })
+}
+
+fn create_render_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    uniform_buffer: &wgpu::Buffer,
+    connection_buffer: &wgpu::Buffer,
+    render_params_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Connection Render Bind Group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: connection_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: render_params_buffer.as_entire_binding(),
+            },
+        ],
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
 fn create_compute_pipeline(
     device: &wgpu::Device,
     particle_buffer: &wgpu::Buffer,
     connection_buffer: &wgpu::Buffer,
+    data_buffer: &wgpu::Buffer,
     count_buffer: &wgpu::Buffer,
     params_buffer: &wgpu::Buffer,
     spatial: &SpatialGpu,
     particle_stride: usize,
+    workgroup_size: u32,
 ) -> (wgpu::ComputePipeline, wgpu::BindGroup) {
     let shader_src = generate_compute_shader(particle_stride);
     let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
         label: Some("Connection Compute Shader"),
-        source: wgpu::ShaderSource::Wgsl(shader_src.into()),
+        source: wgpu::ShaderSource::Wgsl(shader_src.as_str().into()),
     });
 
     let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -252,48 +432,31 @@ fn create_compute_pipeline(
                 },
                 count: None,
             },
-        ],
-    });
-
-    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-        label: Some("Connection Compute Bind Group"),
-        layout: &bind_group_layout,
-        entries: &[
-            wgpu::BindGroupEntry {
-                binding: 0,
-                resource: particle_buffer.as_entire_binding(),
-            },
-            wgpu::BindGroupEntry {
-                binding: 1,
-                resource: connection_buffer.as_entire_binding(),
-            },
-            wgpu::BindGroupEntry {
-                binding: 2,
-                resource: count_buffer.as_entire_binding(),
-            },
-            wgpu::BindGroupEntry {
-                binding: 3,
-                resource: params_buffer.as_entire_binding(),
-            },
-            wgpu::BindGroupEntry {
-                binding: 4,
-                resource: spatial.particle_indices_a.as_entire_binding(),
-            },
-            wgpu::BindGroupEntry {
-                binding: 5,
-                resource: spatial.cell_start.as_entire_binding(),
-            },
-            wgpu::BindGroupEntry {
-                binding: 6,
-                resource: spatial.cell_end.as_entire_binding(),
-            },
-            wgpu::BindGroupEntry {
-                binding: 7,
-                resource: spatial.spatial_params_buffer.as_entire_binding(),
+            // connection_data
+            wgpu::BindGroupLayoutEntry {
+                binding: 8,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: false },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
             },
         ],
     });
 
+    let bind_group = create_compute_bind_group(
+        device,
+        &bind_group_layout,
+        particle_buffer,
+        connection_buffer,
+        data_buffer,
+        count_buffer,
+        params_buffer,
+        spatial,
+    );
+
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
         label: Some("Connection Compute Pipeline Layout"),
         bind_group_layouts: &[&bind_group_layout],
@@ -305,24 +468,31 @@ fn create_compute_pipeline(
         layout: Some(&pipeline_layout),
         module: &shader,
         entry_point: Some("main"),
-        compilation_options: Default::default(),
+        compilation_options: wgpu::PipelineCompilationOptions {
+            constants: &workgroup_size_constants(&shader_src, workgroup_size),
+            ..Default::default()
+        },
         cache: None,
     });
 
     (pipeline, bind_group)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_render_pipeline(
     device: &wgpu::Device,
     uniform_buffer: &wgpu::Buffer,
     connection_buffer: &wgpu::Buffer,
     render_params_buffer: &wgpu::Buffer,
+    directed: bool,
+    flow_speed: f32,
     blend_mode: BlendMode,
     surface_format: wgpu::TextureFormat,
+    sample_count: u32,
 ) -> (wgpu::RenderPipeline, wgpu::BindGroup) {
     let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
         label: Some("Connection Render Shader"),
-        source: wgpu::ShaderSource::Wgsl(RENDER_SHADER.into()),
+        source: wgpu::ShaderSource::Wgsl(generate_render_shader(directed, flow_speed).into()),
     });
 
     let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -361,24 +531,13 @@ fn create_render_pipeline(
         ],
     });
 
-    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-        label: Some("Connection Render Bind Group"),
-        layout: &bind_group_layout,
-        entries: &[
-            wgpu::BindGroupEntry {
-                binding: 0,
-                resource: uniform_buffer.as_entire_binding(),
-            },
-            wgpu::BindGroupEntry {
-                binding: 1,
-                resource: connection_buffer.as_entire_binding(),
-            },
-            wgpu::BindGroupEntry {
-                binding: 2,
-                resource: render_params_buffer.as_entire_binding(),
-            },
-        ],
-    });
+    let bind_group = create_render_bind_group(
+        device,
+        &bind_group_layout,
+        uniform_buffer,
+        connection_buffer,
+        render_params_buffer,
+    );
 
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
         label: Some("Connection Render Pipeline Layout"),
@@ -392,7 +551,16 @@ fn create_render_pipeline(
         vertex: wgpu::VertexState {
             module: &shader,
             entry_point: Some("vs_main"),
-            buffers: &[],
+            // Per-connection distance factor
+            buffers: &[wgpu::VertexBufferLayout {
+                array_stride: 4,
+                step_mode: wgpu::VertexStepMode::Instance,
+                attributes: &[wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32,
+                }],
+            }],
             compilation_options: Default::default(),
         },
         fragment: Some(wgpu::FragmentState {
@@ -421,7 +589,10 @@ fn create_render_pipeline(
             stencil: wgpu::StencilState::default(),
             bias: wgpu::DepthBiasState::default(),
         }),
-        multisample: wgpu::MultisampleState::default(),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
         multiview: None,
         cache: None,
     });
@@ -487,8 +658,11 @@ fn pos_to_cell(pos: vec3<f32>, cell_size: f32, grid_res: u32) -> vec3<i32> {{
 @group(0) @binding(5) var<storage, read> cell_start: array<u32>;
 @group(0) @binding(6) var<storage, read> cell_end: array<u32>;
 @group(0) @binding(7) var<uniform> spatial: SpatialParams;
+@group(0) @binding(8) var<storage, read_write> connection_data: array<f32>;
+
+override WORKGROUP_SIZE: u32 = 256u;
 
-@compute @workgroup_size(256)
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
     let idx = global_id.x;
     if idx >= params.num_particles {{
@@ -536,6 +710,7 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
                             let alpha = 1.0 - dist / params.radius;
                             connections[conn_idx * 2u] = vec4<f32>(my_pos, alpha);
                             connections[conn_idx * 2u + 1u] = vec4<f32>(other_pos, 0.0);
+                            connection_data[conn_idx] = dist / params.radius;
                         }}
                     }}
                 }}
@@ -547,7 +722,16 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
     )
 }
 
+/// Render shader with the direction options baked in as constants.
+fn generate_render_shader(directed: bool, flow_speed: f32) -> String {
+    format!("const DIRECTED: bool = {directed};\nconst FLOW_SPEED: f32 = {flow_speed:?};\n{RENDER_SHADER}")
+}
+
 const RENDER_SHADER: &str = r#"
+// Screen-space sizes of the arrowhead and flow dot
+const ARROW_SIZE: f32 = 0.015;
+const DOT_SIZE: f32 = 0.006;
+
 struct Uniforms {
     view_proj: mat4x4<f32>,
     time: f32,
@@ -555,7 +739,8 @@ struct Uniforms {
 };
 
 struct RenderParams {
-    color: vec3<f32>,
+    near_color: vec3<f32>,
+    far_color: vec3<f32>,
 };
 
 @group(0) @binding(0) var<uniform> uniforms: Uniforms;
@@ -565,12 +750,73 @@ struct RenderParams {
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) alpha: f32,
+    @location(1) distance_t: f32,
+    @location(2) uv: vec2<f32>,
 };
 
+fn hidden_vertex() -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(0.0, 0.0, -1000.0, 1.0);
+    out.alpha = 0.0;
+    out.distance_t = 0.0;
+    out.uv = vec2<f32>(0.0);
+    return out;
+}
+
+// Vertices 6-8: arrowhead at 60% from A to B. Vertices 9-14: flow dot quad.
+fn directed_vertex(vertex_index: u32, pos_a: vec3<f32>, pos_b: vec3<f32>) -> VertexOutput {
+    var out: VertexOutput;
+    out.uv = vec2<f32>(0.0);
+
+    let clip_a = uniforms.view_proj * vec4<f32>(pos_a, 1.0);
+    let clip_b = uniforms.view_proj * vec4<f32>(pos_b, 1.0);
+    var dir = clip_b.xy / clip_b.w - clip_a.xy / clip_a.w;
+    if length(dir) < 0.000001 {
+        dir = vec2<f32>(1.0, 0.0);
+    }
+    dir = normalize(dir);
+    let normal = vec2<f32>(-dir.y, dir.x);
+
+    var center: vec4<f32>;
+    var offset: vec2<f32>;
+    if vertex_index < 9u {
+        if !DIRECTED {
+            return hidden_vertex();
+        }
+        center = uniforms.view_proj * vec4<f32>(mix(pos_a, pos_b, 0.6), 1.0);
+        switch vertex_index {
+            case 6u: { offset = dir * ARROW_SIZE; }
+            case 7u: { offset = (-dir + normal * 0.6) * ARROW_SIZE; }
+            default: { offset = (-dir - normal * 0.6) * ARROW_SIZE; }
+        }
+    } else {
+        if FLOW_SPEED <= 0.0 {
+            return hidden_vertex();
+        }
+        var quad = array<vec2<f32>, 6>(
+            vec2<f32>(-1.0, -1.0),
+            vec2<f32>( 1.0, -1.0),
+            vec2<f32>(-1.0,  1.0),
+            vec2<f32>(-1.0,  1.0),
+            vec2<f32>( 1.0, -1.0),
+            vec2<f32>( 1.0,  1.0),
+        );
+        let corner = quad[vertex_index - 9u];
+        let t = fract(uniforms.time * FLOW_SPEED);
+        center = uniforms.view_proj * vec4<f32>(mix(pos_a, pos_b, t), 1.0);
+        offset = corner * DOT_SIZE;
+        out.uv = corner;
+    }
+
+    out.clip_position = vec4<f32>(center.xy + offset * center.w, center.zw);
+    return out;
+}
+
 @vertex
 fn vs_main(
     @builtin(vertex_index) vertex_index: u32,
     @builtin(instance_index) instance_index: u32,
+    @location(0) distance_t: f32,
 ) -> VertexOutput {
     var out: VertexOutput;
 
@@ -582,8 +828,13 @@ fn vs_main(
     let alpha = conn_data_a.w;
 
     if alpha < 0.001 {
-        out.clip_position = vec4<f32>(0.0, 0.0, -1000.0, 1.0);
-        out.alpha = 0.0;
+        return hidden_vertex();
+    }
+
+    if vertex_index >= 6u {
+        out = directed_vertex(vertex_index, pos_a, pos_b);
+        out.alpha = alpha;
+        out.distance_t = distance_t;
         return out;
     }
 
@@ -607,12 +858,48 @@ fn vs_main(
 
     out.clip_position = uniforms.view_proj * vec4<f32>(pos, 1.0);
     out.alpha = alpha * 0.5;
+    out.distance_t = distance_t;
+    out.uv = vec2<f32>(0.0);
 
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return vec4<f32>(render_params.color, in.alpha);
+    // Round the flow dot; lines and arrowheads have zero uv
+    if length(in.uv) > 1.0 {
+        discard;
+    }
+    let color = mix(render_params.near_color, render_params.far_color, clamp(in.distance_t, 0.0, 1.0));
+    return vec4<f32>(color, in.alpha);
 }
 "#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(src: &str) {
+        let module = naga::front::wgsl::parse_str(src)
+            .unwrap_or_else(|e| panic!("Connection shader failed to parse: {e:?}"));
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .unwrap_or_else(|e| panic!("Connection shader failed to validate: {e:?}"));
+    }
+
+    #[test]
+    fn test_connection_shaders_validate() {
+        validate(&generate_compute_shader(64));
+        validate(&generate_render_shader(false, 0.0));
+        validate(&generate_render_shader(true, 1.5));
+    }
+
+    #[test]
+    fn test_render_params_match_wgsl_layout() {
+        // Two vec3 colors, each padded to 16 bytes
+        assert_eq!(std::mem::size_of::<RenderParams>(), 32);
+    }
+}
@@ -3,7 +3,8 @@
 use bytemuck::{Pod, Zeroable};
 use wgpu::util::DeviceExt;
 
-use crate::field::{FieldConfig, FieldRegistry};
+use super::workgroup_size_constants;
+use crate::field::{FieldConfig, FieldRegistry, GrayScott};
 
 /// Parameters for a single field, uploaded to GPU.
 #[repr(C)]
@@ -14,9 +15,73 @@ pub struct FieldParamsGpu {
     pub extent: f32,
     pub decay: f32,
     pub blur: f32,
-    /// Field type: 0 = scalar, 1 = vector
+    /// Field type: 0 = scalar, 1 = vector, 2 = Gray-Scott
     pub field_type: u32,
-    pub _pad: [f32; 2],
+    /// Boundary: 0 = zero, 1 = clamp, 2 = wrap
+    pub boundary: u32,
+    pub _pad: f32,
+}
+
+/// Parameters for one Gray-Scott reaction step, uploaded to GPU.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct ReactionParamsGpu {
+    resolution: u32,
+    total_cells: u32,
+    feed: f32,
+    kill: f32,
+    da: f32,
+    db: f32,
+    boundary: u32,
+    _pad: f32,
+}
+
+impl ReactionParamsGpu {
+    fn new(config: &FieldConfig, gray_scott: GrayScott) -> Self {
+        Self {
+            resolution: config.resolution,
+            total_cells: config.total_cells(),
+            feed: gray_scott.feed,
+            kill: gray_scott.kill,
+            da: gray_scott.da,
+            db: gray_scott.db,
+            boundary: config.boundary.gpu_id(),
+            _pad: 0.0,
+        }
+    }
+}
+
+/// Expand one value per cell to the field's component layout.
+///
+/// Vector fields repeat the value in every component; Gray-Scott fields
+/// store it as `V` with `U = 1`.
+fn expand_cell_values(config: &FieldConfig, values: impl Iterator<Item = f32>) -> Vec<f32> {
+    if config.gray_scott.is_some() {
+        values.flat_map(|v| [1.0, v]).collect()
+    } else {
+        let components = config.components() as usize;
+        values.flat_map(|v| std::iter::repeat_n(v, components)).collect()
+    }
+}
+
+/// Initial Gray-Scott state: `U = 1`, `V = 0`, with a seeded cube in the center.
+fn gray_scott_initial_state(resolution: u32) -> Vec<f32> {
+    let center = resolution / 2;
+    let radius = (resolution / 16).max(1);
+    let mut values = Vec::with_capacity((resolution * resolution * resolution * 2) as usize);
+    for z in 0..resolution {
+        for y in 0..resolution {
+            for x in 0..resolution {
+                let seeded = [x, y, z].iter().all(|&c| c.abs_diff(center) <= radius);
+                if seeded {
+                    values.extend_from_slice(&[0.5, 0.25]);
+                } else {
+                    values.extend_from_slice(&[1.0, 0.0]);
+                }
+            }
+        }
+    }
+    values
 }
 
 /// GPU state for a single 3D field.
@@ -40,7 +105,7 @@ impl SingleFieldGpu {
     pub fn new(device: &wgpu::Device, config: &FieldConfig, index: usize) -> Self {
         let total_cells = config.total_cells() as usize;
         // Vector fields need 3x the storage (one f32 per component)
-        let components = config.field_type.components() as usize;
+        let components = config.components() as usize;
         let buffer_elements = total_cells * components;
 
         // Write buffer: atomic i32 for parallel particle deposits
@@ -52,19 +117,30 @@ impl SingleFieldGpu {
         });
 
         // Read buffers: f32 for particle sampling (double-buffered for blur)
-        let read_buffer_a = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some(&format!("Field {} Read Buffer A", index)),
-            size: (buffer_elements * 4) as u64,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-
-        let read_buffer_b = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some(&format!("Field {} Read Buffer B", index)),
-            size: (buffer_elements * 4) as u64,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        let initial_state = match (&config.initial_values, config.gray_scott) {
+            (Some(values), _) => Some(expand_cell_values(config, values.iter().copied())),
+            // Gray-Scott fields start from a seeded U = 1, V = 0 state
+            (None, Some(_)) => Some(gray_scott_initial_state(config.resolution)),
+            (None, None) => None,
+        };
+        let create_read_buffer = |label: String| {
+            if let Some(initial_state) = &initial_state {
+                device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some(&label),
+                    contents: bytemuck::cast_slice(initial_state),
+                    usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                })
+            } else {
+                device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some(&label),
+                    size: (buffer_elements * 4) as u64,
+                    usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                })
+            }
+        };
+        let read_buffer_a = create_read_buffer(format!("Field {} Read Buffer A", index));
+        let read_buffer_b = create_read_buffer(format!("Field {} Read Buffer B", index));
 
         Self {
             write_buffer,
@@ -108,6 +184,8 @@ pub struct FieldSystemGpu {
     pub params_buffer: wgpu::Buffer,
     /// Number of fields
     pub field_count: usize,
+    /// Field names, in registry order
+    pub field_names: Vec<String>,
     /// Merge pipeline (atomic writes → float field)
     pub merge_pipeline: wgpu::ComputePipeline,
     pub merge_bind_group_layout: wgpu::BindGroupLayout,
@@ -117,11 +195,20 @@ pub struct FieldSystemGpu {
     /// Clear pipeline (reset atomic buffers to zero)
     pub clear_pipeline: wgpu::ComputePipeline,
     pub clear_bind_group_layout: wgpu::BindGroupLayout,
+    /// Gray-Scott reaction-diffusion step pipeline (shares the blur layout)
+    pub reaction_pipeline: wgpu::ComputePipeline,
+    /// Compute workgroup size the pipelines were built with
+    workgroup_size: u32,
 }
 
 impl FieldSystemGpu {
-    pub fn new(device: &wgpu::Device, registry: &FieldRegistry) -> Self {
+    /// Create GPU state for every registered field.
+    ///
+    /// `workgroup_size` is the compute workgroup size for the merge, blur,
+    /// clear and reaction passes.
+    pub fn new(device: &wgpu::Device, registry: &FieldRegistry, workgroup_size: u32) -> Self {
         let field_count = registry.fields.len();
+        let field_names = registry.fields.iter().map(|(name, _)| name.clone()).collect();
 
         // Create GPU state for each field
         let fields: Vec<_> = registry
@@ -141,8 +228,9 @@ impl FieldSystemGpu {
                 extent: config.world_extent,
                 decay: config.decay,
                 blur: config.blur,
-                field_type: if config.is_vector() { 1 } else { 0 },
-                _pad: [0.0; 2],
+                field_type: config.gpu_type(),
+                boundary: config.boundary.gpu_id(),
+                _pad: 0.0,
             })
             .collect();
 
@@ -163,23 +251,61 @@ impl FieldSystemGpu {
         };
 
         // Create pipelines
-        let (merge_pipeline, merge_bind_group_layout) = create_merge_pipeline(device);
-        let (blur_decay_pipeline, blur_decay_bind_group_layout) = create_blur_decay_pipeline(device);
-        let (clear_pipeline, clear_bind_group_layout) = create_clear_pipeline(device);
+        let (merge_pipeline, merge_bind_group_layout) = create_merge_pipeline(device, workgroup_size);
+        let (blur_decay_pipeline, blur_decay_bind_group_layout) = create_blur_decay_pipeline(device, workgroup_size);
+        let (clear_pipeline, clear_bind_group_layout) = create_clear_pipeline(device, workgroup_size);
+        let reaction_pipeline = create_reaction_pipeline(device, &blur_decay_bind_group_layout, workgroup_size);
 
         Self {
             fields,
             params_buffer,
             field_count,
+            field_names,
             merge_pipeline,
             merge_bind_group_layout,
             blur_decay_pipeline,
             blur_decay_bind_group_layout,
             clear_pipeline,
             clear_bind_group_layout,
+            reaction_pipeline,
+            workgroup_size,
         }
     }
 
+    /// Get the index of a field by name.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.field_names.iter().position(|n| n == name)
+    }
+
+    /// Clear the named field to zero without restarting the simulation.
+    ///
+    /// Returns `false` if no field with that name exists.
+    pub fn reset_field(&mut self, name: &str, queue: &wgpu::Queue) -> bool {
+        self.reset_field_to(name, 0.0, queue)
+    }
+
+    /// Fill the named field with a constant value.
+    ///
+    /// Both read buffers are overwritten and any pending deposits in the
+    /// write buffer are discarded. For vector fields every component is set
+    /// to `value`; Gray-Scott fields get `U = 1` and `V = value`. The writes are queued and take effect before the next
+    /// submitted frame. Returns `false` if no field with that name exists.
+    pub fn reset_field_to(&mut self, name: &str, value: f32, queue: &wgpu::Queue) -> bool {
+        let Some(index) = self.index_of(name) else {
+            return false;
+        };
+        let field = &self.fields[index];
+        let cells = field.config.total_cells() as usize;
+
+        let values = expand_cell_values(&field.config, std::iter::repeat_n(value, cells));
+        queue.write_buffer(&field.read_buffer_a, 0, bytemuck::cast_slice(&values));
+        queue.write_buffer(&field.read_buffer_b, 0, bytemuck::cast_slice(&values));
+
+        let zeros = vec![0i32; values.len()];
+        queue.write_buffer(&field.write_buffer, 0, bytemuck::cast_slice(&zeros));
+        true
+    }
+
     /// Run field processing: merge deposits, blur, decay, clear write buffer
     pub fn process(
         &mut self,
@@ -189,12 +315,12 @@ impl FieldSystemGpu {
     ) {
         for field in &mut self.fields {
             let total_cells = field.config.total_cells();
-            let components = field.config.field_type.components();
+            let components = field.config.components();
             let buffer_elements = total_cells * components;
             // Workgroups for merge/clear (process buffer elements)
-            let element_workgroups = buffer_elements.div_ceil(256);
+            let element_workgroups = buffer_elements.div_ceil(self.workgroup_size);
             // Workgroups for blur (process cells, loop over components internally)
-            let cell_workgroups = total_cells.div_ceil(256);
+            let cell_workgroups = total_cells.div_ceil(self.workgroup_size);
 
             // Create params for this field
             let params = FieldParamsGpu {
@@ -203,8 +329,9 @@ impl FieldSystemGpu {
                 extent: field.config.world_extent,
                 decay: field.config.decay,
                 blur: field.config.blur,
-                field_type: if field.config.is_vector() { 1 } else { 0 },
-                _pad: [0.0; 2],
+                field_type: field.config.gpu_type(),
+                boundary: field.config.boundary.gpu_id(),
+                _pad: 0.0,
             };
             let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
                 label: Some("Field Process Params"),
@@ -242,11 +369,18 @@ impl FieldSystemGpu {
                 pass.dispatch_workgroups(element_workgroups, 1, 1);
             }
 
-            // Step 2: Blur and decay (if enabled)
-            for _ in 0..field.config.blur_iterations {
-                if field.config.blur > 0.0 || field.config.decay < 1.0 {
-                    let blur_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-                        label: Some("Field Blur Bind Group"),
+            // Step 2: Gray-Scott reaction, or blur and decay (if enabled)
+            if let Some(gray_scott) = field.config.gray_scott {
+                let reaction_params = ReactionParamsGpu::new(&field.config, gray_scott);
+                let reaction_params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Field Reaction Params"),
+                    contents: bytemuck::bytes_of(&reaction_params),
+                    usage: wgpu::BufferUsages::UNIFORM,
+                });
+
+                for _ in 0..field.config.blur_iterations {
+                    let reaction_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                        label: Some("Field Reaction Bind Group"),
                         layout: &self.blur_decay_bind_group_layout,
                         entries: &[
                             wgpu::BindGroupEntry {
@@ -259,23 +393,58 @@ impl FieldSystemGpu {
                             },
                             wgpu::BindGroupEntry {
                                 binding: 2,
-                                resource: params_buffer.as_entire_binding(),
+                                resource: reaction_params_buffer.as_entire_binding(),
                             },
                         ],
                     });
 
                     {
                         let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-                            label: Some("Field Blur/Decay Pass"),
+                            label: Some("Field Reaction Pass"),
                             timestamp_writes: None,
                         });
-                        pass.set_pipeline(&self.blur_decay_pipeline);
-                        pass.set_bind_group(0, &blur_bind_group, &[]);
+                        pass.set_pipeline(&self.reaction_pipeline);
+                        pass.set_bind_group(0, &reaction_bind_group, &[]);
                         pass.dispatch_workgroups(cell_workgroups, 1, 1);
                     }
 
                     field.swap_buffers();
                 }
+            } else {
+                for _ in 0..field.config.blur_iterations {
+                    if field.config.blur > 0.0 || field.config.decay < 1.0 {
+                        let blur_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                            label: Some("Field Blur Bind Group"),
+                            layout: &self.blur_decay_bind_group_layout,
+                            entries: &[
+                                wgpu::BindGroupEntry {
+                                    binding: 0,
+                                    resource: field.current_read_buffer().as_entire_binding(),
+                                },
+                                wgpu::BindGroupEntry {
+                                    binding: 1,
+                                    resource: field.blur_target_buffer().as_entire_binding(),
+                                },
+                                wgpu::BindGroupEntry {
+                                    binding: 2,
+                                    resource: params_buffer.as_entire_binding(),
+                                },
+                            ],
+                        });
+
+                        {
+                            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                                label: Some("Field Blur/Decay Pass"),
+                                timestamp_writes: None,
+                            });
+                            pass.set_pipeline(&self.blur_decay_pipeline);
+                            pass.set_bind_group(0, &blur_bind_group, &[]);
+                            pass.dispatch_workgroups(cell_workgroups, 1, 1);
+                        }
+
+                        field.swap_buffers();
+                    }
+                }
             }
 
             // Step 3: Clear write buffer for next frame
@@ -352,6 +521,7 @@ impl FieldSystemGpu {
 
 fn create_merge_pipeline(
     device: &wgpu::Device,
+    workgroup_size: u32,
 ) -> (wgpu::ComputePipeline, wgpu::BindGroupLayout) {
     let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
         label: Some("Field Merge Shader"),
@@ -408,7 +578,10 @@ fn create_merge_pipeline(
         layout: Some(&pipeline_layout),
         module: &shader,
         entry_point: Some("main"),
-        compilation_options: Default::default(),
+        compilation_options: wgpu::PipelineCompilationOptions {
+            constants: &workgroup_size_constants(MERGE_SHADER, workgroup_size),
+            ..Default::default()
+        },
         cache: None,
     });
 
@@ -417,6 +590,7 @@ fn create_merge_pipeline(
 
 fn create_blur_decay_pipeline(
     device: &wgpu::Device,
+    workgroup_size: u32,
 ) -> (wgpu::ComputePipeline, wgpu::BindGroupLayout) {
     let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
         label: Some("Field Blur/Decay Shader"),
@@ -473,7 +647,10 @@ fn create_blur_decay_pipeline(
         layout: Some(&pipeline_layout),
         module: &shader,
         entry_point: Some("main"),
-        compilation_options: Default::default(),
+        compilation_options: wgpu::PipelineCompilationOptions {
+            constants: &workgroup_size_constants(BLUR_DECAY_SHADER, workgroup_size),
+            ..Default::default()
+        },
         cache: None,
     });
 
@@ -482,6 +659,7 @@ fn create_blur_decay_pipeline(
 
 fn create_clear_pipeline(
     device: &wgpu::Device,
+    workgroup_size: u32,
 ) -> (wgpu::ComputePipeline, wgpu::BindGroupLayout) {
     let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
         label: Some("Field Clear Shader"),
@@ -527,13 +705,45 @@ fn create_clear_pipeline(
         layout: Some(&pipeline_layout),
         module: &shader,
         entry_point: Some("main"),
-        compilation_options: Default::default(),
+        compilation_options: wgpu::PipelineCompilationOptions {
+            constants: &workgroup_size_constants(CLEAR_SHADER, workgroup_size),
+            ..Default::default()
+        },
         cache: None,
     });
 
     (pipeline, bind_group_layout)
 }
 
+fn create_reaction_pipeline(
+    device: &wgpu::Device,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    workgroup_size: u32,
+) -> wgpu::ComputePipeline {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Field Reaction Shader"),
+        source: wgpu::ShaderSource::Wgsl(REACTION_SHADER.into()),
+    });
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Field Reaction Pipeline Layout"),
+        bind_group_layouts: &[bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("Field Reaction Pipeline"),
+        layout: Some(&pipeline_layout),
+        module: &shader,
+        entry_point: Some("main"),
+        compilation_options: wgpu::PipelineCompilationOptions {
+            constants: &workgroup_size_constants(REACTION_SHADER, workgroup_size),
+            ..Default::default()
+        },
+        cache: None,
+    })
+}
+
 /// Shader to merge atomic writes into the float field
 const MERGE_SHADER: &str = r#"
 struct Params {
@@ -542,8 +752,8 @@ struct Params {
     extent: f32,
     decay: f32,
     blur: f32,
-    field_type: u32,  // 0 = scalar, 1 = vector
-    _pad1: f32,
+    field_type: u32,  // 0 = scalar, 1 = vector, 2 = Gray-Scott
+    boundary: u32,    // 0 = zero, 1 = clamp, 2 = wrap
     _pad2: f32,
 };
 
@@ -558,11 +768,13 @@ var<storage, read_write> read_buffer: array<f32>;
 @group(0) @binding(2)
 var<uniform> params: Params;
 
-@compute @workgroup_size(256)
+override WORKGROUP_SIZE: u32 = 256u;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let idx = global_id.x;
-    // Buffer size is total_cells * components (1 for scalar, 3 for vector)
-    let components = select(1u, 3u, params.field_type == 1u);
+    // Buffer size is total_cells * components (1 for scalar, 3 for vector, 2 for Gray-Scott)
+    let components = select(select(1u, 2u, params.field_type == 2u), 3u, params.field_type == 1u);
     let buffer_size = params.total_cells * components;
     if idx >= buffer_size {
         return;
@@ -584,8 +796,8 @@ struct Params {
     extent: f32,
     decay: f32,
     blur: f32,
-    field_type: u32,  // 0 = scalar, 1 = vector
-    _pad1: f32,
+    field_type: u32,  // 0 = scalar, 1 = vector, 2 = Gray-Scott
+    boundary: u32,    // 0 = zero, 1 = clamp, 2 = wrap
     _pad2: f32,
 };
 
@@ -598,10 +810,6 @@ var<storage, read_write> dst: array<f32>;
 @group(0) @binding(2)
 var<uniform> params: Params;
 
-fn idx_3d(x: u32, y: u32, z: u32) -> u32 {
-    return x + y * params.resolution + z * params.resolution * params.resolution;
-}
-
 fn idx_to_3d(idx: u32) -> vec3<u32> {
     let res = params.resolution;
     let z = idx / (res * res);
@@ -611,16 +819,35 @@ fn idx_to_3d(idx: u32) -> vec3<u32> {
     return vec3<u32>(x, y, z);
 }
 
-@compute @workgroup_size(256)
+// Flat index of a neighbor cell after applying the boundary condition.
+// Returns -1 outside the grid for the zero and clamp boundaries.
+fn neighbor_idx(pos: vec3<i32>) -> i32 {
+    let res = i32(params.resolution);
+    var c = pos;
+    if params.boundary == 2u {
+        c = ((pos % res) + res) % res;
+    } else if any(pos < vec3<i32>(0)) || any(pos >= vec3<i32>(res)) {
+        return -1;
+    }
+    return c.x + c.y * res + c.z * res * res;
+}
+
+override WORKGROUP_SIZE: u32 = 256u;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let cell_idx = global_id.x;
     if cell_idx >= params.total_cells {
         return;
     }
 
-    let pos = idx_to_3d(cell_idx);
-    let res = params.resolution;
+    let pos = vec3<i32>(idx_to_3d(cell_idx));
     let components = select(1u, 3u, params.field_type == 1u);
+    var offsets = array<vec3<i32>, 6>(
+        vec3<i32>(-1, 0, 0), vec3<i32>(1, 0, 0),
+        vec3<i32>(0, -1, 0), vec3<i32>(0, 1, 0),
+        vec3<i32>(0, 0, -1), vec3<i32>(0, 0, 1),
+    );
 
     // Process all components for this cell
     for (var c = 0u; c < components; c = c + 1u) {
@@ -632,34 +859,16 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
 
         // Only blur if blur > 0
         if params.blur > 0.0 {
-            // X neighbors
-            if pos.x > 0u {
-                sum += src[idx_3d(pos.x - 1u, pos.y, pos.z) * components + c] * params.blur;
-                count += params.blur;
-            }
-            if pos.x < res - 1u {
-                sum += src[idx_3d(pos.x + 1u, pos.y, pos.z) * components + c] * params.blur;
-                count += params.blur;
-            }
-
-            // Y neighbors
-            if pos.y > 0u {
-                sum += src[idx_3d(pos.x, pos.y - 1u, pos.z) * components + c] * params.blur;
-                count += params.blur;
-            }
-            if pos.y < res - 1u {
-                sum += src[idx_3d(pos.x, pos.y + 1u, pos.z) * components + c] * params.blur;
-                count += params.blur;
-            }
-
-            // Z neighbors
-            if pos.z > 0u {
-                sum += src[idx_3d(pos.x, pos.y, pos.z - 1u) * components + c] * params.blur;
-                count += params.blur;
-            }
-            if pos.z < res - 1u {
-                sum += src[idx_3d(pos.x, pos.y, pos.z + 1u) * components + c] * params.blur;
-                count += params.blur;
+            for (var i = 0u; i < 6u; i = i + 1u) {
+                let n = neighbor_idx(pos + offsets[i]);
+                if n >= 0 {
+                    sum += src[u32(n) * components + c] * params.blur;
+                    count += params.blur;
+                } else if params.boundary == 0u {
+                    // Zero boundary: outside cells contribute zero
+                    count += params.blur;
+                }
+                // Clamp boundary: outside cells are skipped (zero flux)
             }
         }
 
@@ -669,6 +878,73 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
 }
 "#;
 
+/// Shader for one Gray-Scott reaction-diffusion step
+const REACTION_SHADER: &str = r#"
+struct Params {
+    resolution: u32,
+    total_cells: u32,
+    feed: f32,
+    kill: f32,
+    da: f32,
+    db: f32,
+    boundary: u32,  // 0 = zero, 1 = clamp, 2 = wrap
+    _pad: f32,
+};
+
+// (U, V) per cell
+@group(0) @binding(0)
+var<storage, read> src: array<vec2<f32>>;
+
+@group(0) @binding(1)
+var<storage, read_write> dst: array<vec2<f32>>;
+
+@group(0) @binding(2)
+var<uniform> params: Params;
+
+// Value of a neighbor cell after applying the boundary condition
+fn neighbor(pos: vec3<i32>) -> vec2<f32> {
+    let res = i32(params.resolution);
+    var c = clamp(pos, vec3<i32>(0), vec3<i32>(res - 1));
+    if params.boundary == 2u {
+        c = ((pos % res) + res) % res;
+    } else if params.boundary == 0u && any(c != pos) {
+        return vec2<f32>(0.0);
+    }
+    return src[u32(c.x + c.y * res + c.z * res * res)];
+}
+
+override WORKGROUP_SIZE: u32 = 256u;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let cell_idx = global_id.x;
+    if cell_idx >= params.total_cells {
+        return;
+    }
+
+    let res = params.resolution;
+    let pos = vec3<i32>(
+        i32(cell_idx % res),
+        i32((cell_idx / res) % res),
+        i32(cell_idx / (res * res)),
+    );
+
+    let center = src[cell_idx];
+    let neighbors = neighbor(pos + vec3<i32>(1, 0, 0)) + neighbor(pos - vec3<i32>(1, 0, 0))
+                  + neighbor(pos + vec3<i32>(0, 1, 0)) + neighbor(pos - vec3<i32>(0, 1, 0))
+                  + neighbor(pos + vec3<i32>(0, 0, 1)) + neighbor(pos - vec3<i32>(0, 0, 1));
+    let laplacian = neighbors / 6.0 - center;
+
+    let u = center.x;
+    let v = center.y;
+    let uvv = u * v * v;
+    let du = params.da * laplacian.x - uvv + params.feed * (1.0 - u);
+    let dv = params.db * laplacian.y + uvv - (params.feed + params.kill) * v;
+
+    dst[cell_idx] = clamp(center + vec2<f32>(du, dv), vec2<f32>(0.0), vec2<f32>(1.0));
+}
+"#;
+
 /// Shader to clear atomic write buffer
 const CLEAR_SHADER: &str = r#"
 struct Params {
@@ -677,8 +953,8 @@ struct Params {
     extent: f32,
     decay: f32,
     blur: f32,
-    field_type: u32,  // 0 = scalar, 1 = vector
-    _pad1: f32,
+    field_type: u32,  // 0 = scalar, 1 = vector, 2 = Gray-Scott
+    boundary: u32,    // 0 = zero, 1 = clamp, 2 = wrap
     _pad2: f32,
 };
 
@@ -688,11 +964,13 @@ var<storage, read_write> write_buffer: array<atomic<i32>>;
 @group(0) @binding(1)
 var<uniform> params: Params;
 
-@compute @workgroup_size(256)
+override WORKGROUP_SIZE: u32 = 256u;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let idx = global_id.x;
-    // Buffer size is total_cells * components (1 for scalar, 3 for vector)
-    let components = select(1u, 3u, params.field_type == 1u);
+    // Buffer size is total_cells * components (1 for scalar, 3 for vector, 2 for Gray-Scott)
+    let components = select(select(1u, 2u, params.field_type == 2u), 3u, params.field_type == 1u);
     let buffer_size = params.total_cells * components;
     if idx >= buffer_size {
         return;
@@ -758,3 +1036,51 @@ pub fn create_particle_field_bind_group_layout(
         entries: &entries,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate_wgsl(code: &str) {
+        let module = naga::front::wgsl::parse_str(code).expect("WGSL should parse");
+        naga::valid::Validator::new(naga::valid::ValidationFlags::all(), naga::valid::Capabilities::all())
+            .validate(&module)
+            .expect("WGSL should validate");
+    }
+
+    #[test]
+    fn test_field_shaders_validate() {
+        for shader in [MERGE_SHADER, BLUR_DECAY_SHADER, REACTION_SHADER, CLEAR_SHADER] {
+            validate_wgsl(shader);
+        }
+    }
+
+    #[test]
+    fn test_expand_cell_values() {
+        let values = [0.25, 0.5];
+        let scalar = FieldConfig::new(8);
+        assert_eq!(expand_cell_values(&scalar, values.into_iter()), vec![0.25, 0.5]);
+
+        let vector = FieldConfig::new_vector(8);
+        assert_eq!(
+            expand_cell_values(&vector, values.into_iter()),
+            vec![0.25, 0.25, 0.25, 0.5, 0.5, 0.5]
+        );
+
+        let gray_scott = FieldConfig::gray_scott(0.03, 0.06, 1.0, 0.5);
+        assert_eq!(
+            expand_cell_values(&gray_scott, values.into_iter()),
+            vec![1.0, 0.25, 1.0, 0.5]
+        );
+    }
+
+    #[test]
+    fn test_gray_scott_initial_state() {
+        let state = gray_scott_initial_state(16);
+        assert_eq!(state.len(), 16 * 16 * 16 * 2);
+        assert_eq!(&state[..2], &[1.0, 0.0]);
+
+        let center = (8 + 8 * 16 + 8 * 16 * 16) * 2;
+        assert_eq!(&state[center..center + 2], &[0.5, 0.25]);
+    }
+}
@@ -0,0 +1,311 @@
+//! Compute-only GPU state for running simulations without a window.
+//!
+//! [`HeadlessState`] owns just the pieces of [`GpuState`](super::GpuState)
+//! that advance the simulation: the particle and uniform buffers, spatial
+//! hashing, the inbox, fields, and sub-emitters. No surface is created, so
+//! it works on servers, in CI, and anywhere else without a display.
+
+use wgpu::util::DeviceExt;
+
+use crate::error::GpuError;
+use crate::field::FieldRegistry;
+use crate::spatial::SpatialConfig;
+use crate::sub_emitter::SubEmitter;
+
+use super::{
+    create_compute_bind_group, create_compute_bind_group_layout, create_compute_pipeline_layout,
+    counter_buffer_size, create_inbox_bind_group, create_particle_field_bind_group_layout, read_buffer_sync,
+    select_workgroup_size, set_compute_bind_groups, split_seed, uniform_buffer_size, uniform_bytes,
+    workgroup_size_constants, FieldSystemGpu, SpatialGpu, SubEmitterGpu, Uniforms,
+};
+
+/// GPU state that runs the compute pipeline without rendering.
+///
+/// Some buffers are stored but not directly read - they must remain alive
+/// because bind groups hold references to them.
+#[allow(dead_code)]
+pub struct HeadlessState {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    compute_pipeline: wgpu::ComputePipeline,
+    particle_buffer: wgpu::Buffer,
+    uniform_buffer: wgpu::Buffer,
+    uniform_buffer_size: usize,
+    compute_bind_group: wgpu::BindGroup,
+    num_particles: u32,
+    bounds: f32,
+    workgroup_size: u32,
+    seed: [u32; 2],
+    spatial: Option<SpatialGpu>,
+    inbox_buffer: Option<wgpu::Buffer>,
+    inbox_bind_group: Option<wgpu::BindGroup>,
+    field_system: Option<FieldSystemGpu>,
+    field_bind_group_layout: Option<wgpu::BindGroupLayout>,
+    empty_bind_group: Option<wgpu::BindGroup>,
+    sub_emitter: Option<SubEmitterGpu>,
+    alive_count_buffer: wgpu::Buffer,
+}
+
+impl HeadlessState {
+    /// Create compute-only GPU state.
+    ///
+    /// Requests an adapter without a compatible surface, falling back to the
+    /// first adapter `enumerate_adapters` reports.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new(
+        particle_data: &[u8],
+        num_particles: u32,
+        bounds: f32,
+        compute_shader_src: &str,
+        has_neighbors: bool,
+        spatial_config: SpatialConfig,
+        custom_uniform_size: usize,
+        inbox_enabled: bool,
+        field_registry: &FieldRegistry,
+        sub_emitters: &[SubEmitter],
+        emitter_count: usize,
+        particle_wgsl_struct: &str,
+    ) -> Result<Self, GpuError> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::PRIMARY,
+            ..Default::default()
+        });
+
+        let adapter = match instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: None,
+                force_fallback_adapter: false,
+            })
+            .await
+        {
+            Some(adapter) => adapter,
+            None => instance
+                .enumerate_adapters(wgpu::Backends::all())
+                .into_iter()
+                .next()
+                .ok_or(GpuError::NoAdapter)?,
+        };
+
+        let limits = adapter.limits();
+        let workgroup_size = select_workgroup_size(&limits);
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("Headless Device"),
+                    required_features: wgpu::Features::empty(),
+                    required_limits: limits,
+                    memory_hints: Default::default(),
+                },
+                None, // trace path
+            )
+            .await?;
+
+        let particle_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Particle Buffer"),
+            contents: particle_data,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let uniform_buffer_size = uniform_buffer_size(custom_uniform_size);
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Uniform Buffer"),
+            size: uniform_buffer_size as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let spatial = has_neighbors.then(|| {
+            SpatialGpu::new(&device, &particle_buffer, num_particles, spatial_config, particle_wgsl_struct, workgroup_size)
+        });
+
+        // 4 i32 inbox channels per particle = 16 bytes per particle
+        let inbox_buffer = inbox_enabled.then(|| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Inbox Buffer"),
+                size: (num_particles as u64) * 16,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        });
+
+        let alive_count_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Alive Count Buffer"),
+            size: counter_buffer_size(emitter_count),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let compute_bind_group_layout = create_compute_bind_group_layout(&device, spatial.is_some());
+        let compute_bind_group = create_compute_bind_group(
+            &device,
+            &compute_bind_group_layout,
+            &particle_buffer,
+            &uniform_buffer,
+            spatial.as_ref(),
+            &alive_count_buffer,
+        );
+
+        let (inbox_bind_group_layout, inbox_bind_group) = match inbox_buffer {
+            Some(ref inbox_buf) => {
+                let (layout, bind_group) = create_inbox_bind_group(&device, inbox_buf);
+                (Some(layout), Some(bind_group))
+            }
+            None => (None, None),
+        };
+
+        let (field_system, field_bind_group_layout) = if !field_registry.is_empty() {
+            let system = FieldSystemGpu::new(&device, field_registry, workgroup_size);
+            let layout = create_particle_field_bind_group_layout(&device, system.field_count);
+            (Some(system), Some(layout))
+        } else {
+            (None, None)
+        };
+
+        let sub_emitter = (!sub_emitters.is_empty()).then(|| {
+            SubEmitterGpu::new(&device, &particle_buffer, num_particles, sub_emitters, particle_wgsl_struct, workgroup_size)
+        });
+
+        let (compute_pipeline_layout, empty_bind_group) = create_compute_pipeline_layout(
+            &device,
+            &compute_bind_group_layout,
+            inbox_bind_group_layout.as_ref(),
+            field_bind_group_layout.as_ref(),
+            sub_emitter.as_ref(),
+        );
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let compute_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Compute Shader"),
+            source: wgpu::ShaderSource::Wgsl(compute_shader_src.into()),
+        });
+        if let Some(error) = device.pop_error_scope().await {
+            return Err(GpuError::ShaderCompilation(crate::rules::describe_shader_error(
+                compute_shader_src,
+                &error.to_string(),
+            )));
+        }
+
+        let compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Compute Pipeline"),
+            layout: Some(&compute_pipeline_layout),
+            module: &compute_shader,
+            entry_point: Some("main"),
+            compilation_options: wgpu::PipelineCompilationOptions {
+                constants: &workgroup_size_constants(compute_shader_src, workgroup_size),
+                ..Default::default()
+            },
+            cache: None,
+        });
+
+        Ok(Self {
+            device,
+            queue,
+            compute_pipeline,
+            particle_buffer,
+            uniform_buffer,
+            uniform_buffer_size,
+            compute_bind_group,
+            num_particles,
+            bounds,
+            workgroup_size,
+            seed: [0; 2],
+            spatial,
+            inbox_buffer,
+            inbox_bind_group,
+            field_system,
+            field_bind_group_layout,
+            empty_bind_group,
+            sub_emitter,
+            alive_count_buffer,
+        })
+    }
+
+    /// Set the seed mixed into the compute shader's random number generator.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = split_seed(seed);
+    }
+
+    /// Advance the simulation by one step.
+    ///
+    /// Runs the same passes as a rendered frame minus the drawing: spatial
+    /// hashing, the particle compute pass, sub-emitter spawning, and field
+    /// processing. The view-projection uniform is left as the identity.
+    pub fn step(&mut self, time: f32, delta_time: f32, custom_uniform_bytes: Option<&[u8]>) {
+        let uniforms = Uniforms {
+            view_proj: glam::Mat4::IDENTITY.to_cols_array_2d(),
+            time,
+            delta_time,
+            seed: self.seed,
+            particle_count: self.num_particles,
+            bounds: self.bounds,
+            _pad: [0; 2],
+        };
+        let bytes = uniform_bytes(&uniforms, custom_uniform_bytes, self.uniform_buffer_size);
+        self.queue.write_buffer(&self.uniform_buffer, 0, &bytes);
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Headless Step Encoder"),
+        });
+
+        if let Some(ref spatial) = self.spatial {
+            spatial.execute(&mut encoder, &self.queue);
+        }
+
+        if let Some(ref inbox_buf) = self.inbox_buffer {
+            self.queue.write_buffer(inbox_buf, 0, &vec![0u8; (self.num_particles as usize) * 16]);
+        }
+        if let Some(ref se) = self.sub_emitter {
+            se.clear_buffers(&self.queue);
+        }
+        self.queue.write_buffer(&self.alive_count_buffer, 0, &vec![0u8; self.alive_count_buffer.size() as usize]);
+
+        // Recreate field bind group each step (buffers may have been swapped during blur)
+        let field_bind_group = if let (Some(ref field_sys), Some(ref layout)) =
+            (&self.field_system, &self.field_bind_group_layout)
+        {
+            field_sys.create_particle_bind_group(&self.device, layout)
+        } else {
+            None
+        };
+
+        {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Compute Pass"),
+                timestamp_writes: None,
+            });
+            compute_pass.set_pipeline(&self.compute_pipeline);
+            set_compute_bind_groups(
+                &mut compute_pass,
+                &self.compute_bind_group,
+                self.inbox_bind_group.as_ref(),
+                field_bind_group.as_ref(),
+                self.field_system.is_some(),
+                self.sub_emitter.as_ref(),
+                self.empty_bind_group.as_ref(),
+            );
+            compute_pass.dispatch_workgroups(self.num_particles.div_ceil(self.workgroup_size), 1, 1);
+        }
+
+        if let Some(ref se) = self.sub_emitter {
+            se.spawn_children(&mut encoder);
+        }
+
+        if let Some(ref mut field_sys) = self.field_system {
+            field_sys.process(&self.device, &mut encoder, &self.queue);
+        }
+
+        self.queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    /// Read the particle buffer back to the CPU, blocking until it is ready.
+    pub fn read_particles(&self) -> Result<Vec<u8>, GpuError> {
+        let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Headless Readback Buffer"),
+            size: self.particle_buffer.size(),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        read_buffer_sync(&self.device, &self.queue, &self.particle_buffer, &staging)
+    }
+}
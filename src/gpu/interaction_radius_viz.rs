@@ -0,0 +1,388 @@
+//! Interaction radius visualization.
+//!
+//! Draws a thin wireframe sphere (a circle in 2D mode) around every particle
+//! of one type, sized to the smallest neighbor rule radius. Useful for
+//! checking rule radii against each other and against the spatial cell size.
+
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+use super::DEPTH_FORMAT;
+
+/// Line segments per circle.
+const CIRCLE_SEGMENTS: u32 = 32;
+
+/// Line width as a fraction of the radius.
+const LINE_WIDTH: f32 = 0.015;
+
+/// GPU parameters for radius rendering.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct RadiusParams {
+    /// Sphere radius in world units.
+    radius: f32,
+    /// Particle type to draw spheres around.
+    particle_type: u32,
+    /// Number of line segments per particle.
+    lines_per_particle: u32,
+    /// Line half-width in world units.
+    line_width: f32,
+}
+
+/// GPU state for interaction radius visualization.
+pub struct InteractionRadiusViz {
+    /// Buffer storing unit sphere line segment endpoints.
+    line_buffer: wgpu::Buffer,
+    /// Number of line segments per particle.
+    lines_per_particle: u32,
+    /// Render pipeline.
+    pipeline: wgpu::RenderPipeline,
+    /// Bind group for rendering.
+    bind_group: wgpu::BindGroup,
+    /// Radius params buffer (kept alive for the bind group).
+    params_buffer: wgpu::Buffer,
+    /// Number of particles.
+    num_particles: u32,
+}
+
+impl InteractionRadiusViz {
+    /// Create a radius visualization around particles of `particle_type`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        device: &wgpu::Device,
+        particle_buffer: &wgpu::Buffer,
+        uniform_buffer: &wgpu::Buffer,
+        particle_wgsl_struct: &str,
+        num_particles: u32,
+        particle_type: u32,
+        radius: f32,
+        two_d: bool,
+        surface_format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Self {
+        let lines = generate_sphere_lines(two_d);
+        let lines_per_particle = lines.len() as u32 / 2; // 2 vec4s per line
+
+        let line_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Interaction Radius Line Buffer"),
+            contents: bytemuck::cast_slice(&lines),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+
+        let params = RadiusParams {
+            radius,
+            particle_type,
+            lines_per_particle,
+            line_width: radius * LINE_WIDTH,
+        };
+        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Interaction Radius Params Buffer"),
+            contents: bytemuck::bytes_of(&params),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Interaction Radius Shader"),
+            source: wgpu::ShaderSource::Wgsl(generate_radius_shader(particle_wgsl_struct).into()),
+        });
+
+        let buffer_entry = |binding: u32, ty: wgpu::BufferBindingType| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::VERTEX,
+            ty: wgpu::BindingType::Buffer {
+                ty,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Interaction Radius Bind Group Layout"),
+            entries: &[
+                // Uniforms (view_proj)
+                buffer_entry(0, wgpu::BufferBindingType::Uniform),
+                // Particles
+                buffer_entry(1, wgpu::BufferBindingType::Storage { read_only: true }),
+                // Unit sphere line segments
+                buffer_entry(2, wgpu::BufferBindingType::Storage { read_only: true }),
+                // Radius params
+                buffer_entry(3, wgpu::BufferBindingType::Uniform),
+            ],
+        });
+
+        let bind_group = create_bind_group(
+            device,
+            &bind_group_layout,
+            uniform_buffer,
+            particle_buffer,
+            &line_buffer,
+            &params_buffer,
+        );
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Interaction Radius Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Interaction Radius Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: DEPTH_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            line_buffer,
+            lines_per_particle,
+            pipeline,
+            bind_group,
+            params_buffer,
+            num_particles,
+        }
+    }
+
+    /// Get the render pipeline.
+    pub fn pipeline(&self) -> &wgpu::RenderPipeline {
+        &self.pipeline
+    }
+
+    /// Get the bind group.
+    pub fn bind_group(&self) -> &wgpu::BindGroup {
+        &self.bind_group
+    }
+
+    /// Get the number of line instances to draw (lines_per_particle * num_particles).
+    pub fn line_count(&self) -> u32 {
+        self.lines_per_particle * self.num_particles
+    }
+
+    /// Rebind to a new particle buffer after the particle count changed.
+    pub fn set_particle_buffer(
+        &mut self,
+        device: &wgpu::Device,
+        particle_buffer: &wgpu::Buffer,
+        uniform_buffer: &wgpu::Buffer,
+        num_particles: u32,
+    ) {
+        self.bind_group = create_bind_group(
+            device,
+            &self.pipeline.get_bind_group_layout(0),
+            uniform_buffer,
+            particle_buffer,
+            &self.line_buffer,
+            &self.params_buffer,
+        );
+        self.num_particles = num_particles;
+    }
+}
+
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    uniform_buffer: &wgpu::Buffer,
+    particle_buffer: &wgpu::Buffer,
+    line_buffer: &wgpu::Buffer,
+    params_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Interaction Radius Bind Group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: particle_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: line_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 3,
+                resource: params_buffer.as_entire_binding(),
+            },
+        ],
+    })
+}
+
+/// Line segments of a unit sphere: three great circles, or one circle on
+/// the XY plane in 2D mode.
+fn generate_sphere_lines(two_d: bool) -> Vec<[f32; 4]> {
+    let planes: &[fn(f32, f32) -> [f32; 4]] = if two_d {
+        &[|a, b| [a, b, 0.0, 1.0]]
+    } else {
+        &[
+            |a, b| [a, b, 0.0, 1.0],
+            |a, b| [a, 0.0, b, 1.0],
+            |a, b| [0.0, a, b, 1.0],
+        ]
+    };
+
+    let mut lines = Vec::new();
+    for point in planes {
+        for i in 0..CIRCLE_SEGMENTS {
+            let angle_a = i as f32 / CIRCLE_SEGMENTS as f32 * std::f32::consts::TAU;
+            let angle_b = (i + 1) as f32 / CIRCLE_SEGMENTS as f32 * std::f32::consts::TAU;
+            lines.push(point(angle_a.cos(), angle_a.sin()));
+            lines.push(point(angle_b.cos(), angle_b.sin()));
+        }
+    }
+    lines
+}
+
+/// Generate the radius rendering shader for the given particle struct.
+fn generate_radius_shader(particle_wgsl_struct: &str) -> String {
+    format!(
+        r#"struct Uniforms {{
+    view_proj: mat4x4<f32>,
+    time: f32,
+    delta_time: f32,
+}};
+
+{particle_wgsl_struct}
+
+struct RadiusParams {{
+    radius: f32,
+    particle_type: u32,
+    lines_per_particle: u32,
+    line_width: f32,
+}};
+
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+@group(0) @binding(1) var<storage, read> particles: array<Particle>;
+@group(0) @binding(2) var<storage, read> lines: array<vec4<f32>>;
+@group(0) @binding(3) var<uniform> params: RadiusParams;
+
+struct VertexOutput {{
+    @builtin(position) clip_position: vec4<f32>,
+}};
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    @builtin(instance_index) instance_index: u32,
+) -> VertexOutput {{
+    var out: VertexOutput;
+
+    let particle_idx = instance_index / params.lines_per_particle;
+    let line_idx = instance_index % params.lines_per_particle;
+    let p = particles[particle_idx];
+
+    // Only living particles of the chosen type get a sphere
+    if p.alive == 0u || p.particle_type != params.particle_type {{
+        out.clip_position = vec4<f32>(0.0, 0.0, -1000.0, 1.0);
+        return out;
+    }}
+
+    let pos_a = p.position + lines[line_idx * 2u].xyz * params.radius;
+    let pos_b = p.position + lines[line_idx * 2u + 1u].xyz * params.radius;
+    let dir = normalize(pos_b - pos_a);
+
+    // Widen the line along two perpendiculars so it shows from every angle
+    var perp = cross(dir, vec3<f32>(0.0, 1.0, 0.0));
+    if length(perp) < 0.001 {{
+        perp = cross(dir, vec3<f32>(1.0, 0.0, 0.0));
+    }}
+    perp = normalize(perp) * params.line_width;
+    let perp2 = normalize(cross(dir, perp)) * params.line_width;
+
+    // Build quad vertices (2 triangles, 6 vertices)
+    var pos: vec3<f32>;
+    switch vertex_index {{
+        case 0u: {{ pos = pos_a - perp - perp2; }}
+        case 1u: {{ pos = pos_a + perp + perp2; }}
+        case 2u: {{ pos = pos_b - perp - perp2; }}
+        case 3u: {{ pos = pos_a + perp + perp2; }}
+        case 4u: {{ pos = pos_b - perp - perp2; }}
+        default: {{ pos = pos_b + perp + perp2; }}
+    }}
+
+    out.clip_position = uniforms.view_proj * vec4<f32>(pos, 1.0);
+    return out;
+}}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {{
+    return vec4<f32>(1.0, 0.85, 0.3, 0.35);
+}}
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_radius_shader_validates() {
+        let particle_struct = "struct Particle {
+    position: vec3<f32>,
+    velocity: vec3<f32>,
+    particle_type: u32,
+    alive: u32,
+    scale: f32,
+}";
+        let module = naga::front::wgsl::parse_str(&generate_radius_shader(particle_struct))
+            .unwrap_or_else(|e| panic!("Radius shader failed to parse: {e:?}"));
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .unwrap_or_else(|e| panic!("Radius shader failed to validate: {e:?}"));
+    }
+
+    #[test]
+    fn test_sphere_lines_lie_on_unit_sphere() {
+        let sphere = generate_sphere_lines(false);
+        let circle = generate_sphere_lines(true);
+
+        assert_eq!(sphere.len() as u32, 3 * CIRCLE_SEGMENTS * 2);
+        assert_eq!(circle.len() as u32, CIRCLE_SEGMENTS * 2);
+        for p in sphere.iter().chain(&circle) {
+            let length = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+            assert!((length - 1.0).abs() < 1e-5);
+        }
+        assert!(circle.iter().all(|p| p[2] == 0.0));
+    }
+}
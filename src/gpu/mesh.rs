@@ -0,0 +1,385 @@
+//! Instanced triangle mesh rendering for particles.
+//!
+//! Renders particles as 3D meshes instead of flat billboards. The mesh
+//! geometry is a per-vertex buffer and the particle buffer is bound as a
+//! per-instance buffer, so each particle draws one copy of the mesh.
+#![allow(clippy::too_many_arguments)]
+
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+use super::{blend_mode_to_state, DEPTH_FORMAT};
+use crate::visuals::{BlendMode, MeshData};
+
+/// GPU parameters for mesh rendering.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct MeshParams {
+    /// Base particle size (mesh scale multiplier).
+    base_size: f32,
+    /// Non-zero to rotate meshes along particle velocity.
+    align_to_velocity: u32,
+    /// Padding for alignment.
+    _pad: [f32; 2],
+}
+
+/// GPU state for instanced mesh rendering.
+pub struct MeshState {
+    /// Interleaved mesh positions and normals (6 floats per vertex).
+    vertex_buffer: wgpu::Buffer,
+    /// Triangle list indices (u16).
+    index_buffer: wgpu::Buffer,
+    /// Number of indices to draw per instance.
+    index_count: u32,
+    /// Render pipeline.
+    pipeline: wgpu::RenderPipeline,
+    /// Bind group for rendering.
+    bind_group: wgpu::BindGroup,
+    /// Mesh params buffer (kept alive for bind group).
+    params_buffer: wgpu::Buffer,
+}
+
+impl MeshState {
+    /// Create a new mesh rendering state.
+    pub fn new(
+        device: &wgpu::Device,
+        uniform_buffer: &wgpu::Buffer,
+        mesh: &MeshData,
+        align_to_velocity: bool,
+        particle_size: f32,
+        particle_stride: usize,
+        color_offset: Option<u32>,
+        velocity_offset: Option<u32>,
+        alive_offset: u32,
+        scale_offset: u32,
+        blend_mode: BlendMode,
+        surface_format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Self {
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Mesh Vertex Buffer"),
+            contents: bytemuck::cast_slice(&mesh.to_vertices()),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Mesh Index Buffer"),
+            contents: bytemuck::cast_slice(&mesh.indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+
+        let params = MeshParams {
+            base_size: particle_size,
+            align_to_velocity: align_to_velocity as u32,
+            _pad: [0.0; 2],
+        };
+        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Mesh Params Buffer"),
+            contents: bytemuck::bytes_of(&params),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Mesh Shader"),
+            source: wgpu::ShaderSource::Wgsl(
+                generate_mesh_shader(color_offset.is_some(), velocity_offset.is_some()).into(),
+            ),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Mesh Bind Group Layout"),
+            entries: &[
+                // Uniforms (view_proj, time)
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Mesh params
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Mesh Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: params_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Mesh Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        // Mesh geometry advances per vertex
+        let mesh_attributes = [
+            wgpu::VertexAttribute {
+                offset: 0,
+                shader_location: 0,
+                format: wgpu::VertexFormat::Float32x3, // local position
+            },
+            wgpu::VertexAttribute {
+                offset: 12,
+                shader_location: 1,
+                format: wgpu::VertexFormat::Float32x3, // local normal
+            },
+        ];
+
+        // Particle data advances per instance
+        let mut particle_attributes = vec![
+            wgpu::VertexAttribute {
+                offset: 0,
+                shader_location: 2,
+                format: wgpu::VertexFormat::Float32x3, // position
+            },
+            wgpu::VertexAttribute {
+                offset: alive_offset as wgpu::BufferAddress,
+                shader_location: 4,
+                format: wgpu::VertexFormat::Uint32, // alive
+            },
+            wgpu::VertexAttribute {
+                offset: scale_offset as wgpu::BufferAddress,
+                shader_location: 5,
+                format: wgpu::VertexFormat::Float32, // scale
+            },
+        ];
+        if let Some(offset) = velocity_offset {
+            particle_attributes.push(wgpu::VertexAttribute {
+                offset: offset as wgpu::BufferAddress,
+                shader_location: 3,
+                format: wgpu::VertexFormat::Float32x3, // velocity
+            });
+        }
+        if let Some(offset) = color_offset {
+            particle_attributes.push(wgpu::VertexAttribute {
+                offset: offset as wgpu::BufferAddress,
+                shader_location: 6,
+                format: wgpu::VertexFormat::Float32x3, // color
+            });
+        }
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Mesh Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[
+                    wgpu::VertexBufferLayout {
+                        array_stride: 24,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        attributes: &mesh_attributes,
+                    },
+                    wgpu::VertexBufferLayout {
+                        array_stride: particle_stride as wgpu::BufferAddress,
+                        step_mode: wgpu::VertexStepMode::Instance,
+                        attributes: &particle_attributes,
+                    },
+                ],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_format,
+                    blend: Some(blend_mode_to_state(blend_mode)),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                // OBJ winding isn't reliable enough to cull
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: DEPTH_FORMAT,
+                // Disable depth writes for additive blending
+                depth_write_enabled: !matches!(blend_mode, BlendMode::Additive),
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            vertex_buffer,
+            index_buffer,
+            index_count: mesh.index_count(),
+            pipeline,
+            bind_group,
+            params_buffer,
+        }
+    }
+
+    /// Draw one mesh instance per particle.
+    pub fn draw(
+        &self,
+        render_pass: &mut wgpu::RenderPass<'_>,
+        particle_buffer: &wgpu::Buffer,
+        num_particles: u32,
+    ) {
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.set_vertex_buffer(1, particle_buffer.slice(..));
+        render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+        render_pass.draw_indexed(0..self.index_count, 0, 0..num_particles);
+    }
+}
+
+/// Generate the mesh rendering shader.
+fn generate_mesh_shader(has_color: bool, has_velocity: bool) -> String {
+    let (color_input, color_expr) = if has_color {
+        ("\n    @location(6) particle_color: vec3<f32>,", "particle_color")
+    } else {
+        // Default color based on position
+        ("", "normalize(particle_pos) * 0.5 + 0.5")
+    };
+    let (velocity_input, velocity_expr) = if has_velocity {
+        ("\n    @location(3) particle_vel: vec3<f32>,", "particle_vel")
+    } else {
+        // Nothing to align with
+        ("", "vec3<f32>(0.0)")
+    };
+
+    format!(
+        r#"struct Uniforms {{
+    view_proj: mat4x4<f32>,
+    time: f32,
+    delta_time: f32,
+}};
+
+struct MeshParams {{
+    base_size: f32,
+    align_to_velocity: u32,
+}};
+
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+@group(0) @binding(1) var<uniform> params: MeshParams;
+
+struct VertexOutput {{
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+}};
+
+// Rotation taking the mesh's +Y axis onto `forward`
+fn align_basis(forward: vec3<f32>) -> mat3x3<f32> {{
+    var reference = vec3<f32>(0.0, 0.0, 1.0);
+    if abs(forward.z) > 0.99 {{
+        reference = vec3<f32>(1.0, 0.0, 0.0);
+    }}
+    let right = normalize(cross(forward, reference));
+    let up = cross(right, forward);
+    return mat3x3<f32>(right, forward, up);
+}}
+
+@vertex
+fn vs_main(
+    @location(0) local_pos: vec3<f32>,
+    @location(1) local_normal: vec3<f32>,
+    @location(2) particle_pos: vec3<f32>,{velocity_input}
+    @location(4) alive: u32,
+    @location(5) scale: f32,{color_input}
+) -> VertexOutput {{
+    var out: VertexOutput;
+
+    if alive == 0u {{
+        out.clip_position = vec4<f32>(0.0, 0.0, -1000.0, 1.0);
+        out.color = vec3<f32>(0.0);
+        out.normal = vec3<f32>(0.0, 1.0, 0.0);
+        return out;
+    }}
+
+    var rotation = mat3x3<f32>(
+        vec3<f32>(1.0, 0.0, 0.0),
+        vec3<f32>(0.0, 1.0, 0.0),
+        vec3<f32>(0.0, 0.0, 1.0)
+    );
+    let velocity = {velocity_expr};
+    let speed = length(velocity);
+    if params.align_to_velocity != 0u && speed > 0.0001 {{
+        rotation = align_basis(velocity / speed);
+    }}
+
+    let world_pos = particle_pos + rotation * local_pos * (params.base_size * scale);
+    out.clip_position = uniforms.view_proj * vec4<f32>(world_pos, 1.0);
+    out.color = {color_expr};
+    out.normal = rotation * local_normal;
+    return out;
+}}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {{
+    // Fixed directional light with an ambient floor
+    let light_dir = normalize(vec3<f32>(0.4, 1.0, 0.6));
+    let diffuse = max(dot(normalize(in.normal), light_dir), 0.0);
+    return vec4<f32>(in.color * (0.3 + 0.7 * diffuse), 1.0);
+}}
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(src: &str) {
+        let module = naga::front::wgsl::parse_str(src)
+            .unwrap_or_else(|e| panic!("Mesh shader failed to parse: {e:?}"));
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .unwrap_or_else(|e| panic!("Mesh shader failed to validate: {e:?}"));
+    }
+
+    #[test]
+    fn test_mesh_shader_validates() {
+        validate(&generate_mesh_shader(true, true));
+        validate(&generate_mesh_shader(false, true));
+        validate(&generate_mesh_shader(true, false));
+    }
+
+    #[test]
+    fn test_mesh_params_match_uniform_layout() {
+        assert_eq!(std::mem::size_of::<MeshParams>(), 16);
+    }
+}
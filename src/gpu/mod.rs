@@ -13,8 +13,15 @@
 mod camera;
 mod connections;
 mod field_gpu;
+mod headless;
+mod interaction_radius_viz;
+mod mesh;
 mod picking;
+mod pipeline_cache;
+mod bloom;
 mod post_process;
+mod profiler;
+mod shadow;
 mod spatial_gpu;
 mod spatial_grid_viz;
 pub mod sub_emitter_gpu;
@@ -29,15 +36,22 @@ mod egui_integration;
 pub use camera::Camera;
 pub use connections::ConnectionState;
 pub use field_gpu::{FieldSystemGpu, create_particle_field_bind_group_layout};
+pub use headless::HeadlessState;
+pub use interaction_radius_viz::InteractionRadiusViz;
+pub use mesh::MeshState;
 pub use picking::PickingState;
 pub use post_process::PostProcessState;
+pub use profiler::{FrameTimings, GpuProfiler, ProfiledPass};
+pub use shadow::ShadowState;
 pub use spatial_grid_viz::SpatialGridViz;
 pub use sub_emitter_gpu::SubEmitterGpu;
 pub use trails::TrailState;
 pub use volume_render::{VolumeConfig, VolumeRenderState};
 pub use wireframe::WireframeState;
 
-use crate::error::GpuError;
+use crate::error::{GpuError, SnapshotError};
+use crate::snapshot::ParticleSnapshot;
+use pipeline_cache::PipelineCacheFile;
 use crate::field::FieldRegistry;
 #[cfg(feature = "egui")]
 use crate::selection::{PendingParticleWrite, SelectedParticle, SelectedParticleData};
@@ -45,10 +59,11 @@ use crate::selection::{PendingParticleWrite, SelectedParticle, SelectedParticleD
 #[cfg(feature = "egui")]
 pub use egui_integration::EguiIntegration;
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use bytemuck::{Pod, Zeroable};
-use glam::{Mat4, Vec3};
+use glam::Vec3;
 use wgpu::util::DeviceExt;
 use winit::window::Window;
 
@@ -59,6 +74,35 @@ use crate::visuals::BlendMode;
 const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
 const WORKGROUP_SIZE: u32 = 256;
 
+/// Pick the compute workgroup size for a device.
+///
+/// Returns the largest power of two that fits within the device's
+/// `max_compute_invocations_per_workgroup` and
+/// `max_compute_workgroup_size_x`, and 256 (the size the shaders were
+/// tuned for). Every compute pass dispatches with this size.
+pub fn select_workgroup_size(limits: &wgpu::Limits) -> u32 {
+    let max = limits
+        .max_compute_invocations_per_workgroup
+        .min(limits.max_compute_workgroup_size_x)
+        .min(WORKGROUP_SIZE);
+    if max == 0 {
+        return 1;
+    }
+    1 << (31 - max.leading_zeros())
+}
+
+/// Pipeline constants overriding `WORKGROUP_SIZE` in a compute shader.
+///
+/// Shaders that don't declare the override (e.g. hand-written ones) get no
+/// constants, since wgpu rejects unknown override names.
+fn workgroup_size_constants(compute_shader_src: &str, workgroup_size: u32) -> HashMap<String, f64> {
+    let mut constants = HashMap::new();
+    if compute_shader_src.contains("override WORKGROUP_SIZE") {
+        constants.insert("WORKGROUP_SIZE".to_string(), workgroup_size as f64);
+    }
+    constants
+}
+
 /// Convert BlendMode to wgpu BlendState
 fn blend_mode_to_state(mode: BlendMode) -> wgpu::BlendState {
     match mode {
@@ -92,6 +136,44 @@ struct Uniforms {
     view_proj: [[f32; 4]; 4],
     time: f32,
     delta_time: f32,
+    /// Random seed as `[low, high]` 32-bit halves.
+    seed: [u32; 2],
+    particle_count: u32,
+    /// Half-size of the simulation cube.
+    bounds: f32,
+    _pad: [u32; 2],
+}
+
+/// WGSL fields of the built-in uniforms, declared first in every `Uniforms`
+/// struct that binds the main uniform buffer. Custom uniform fields follow,
+/// so their names must not repeat these.
+pub const UNIFORM_FIELDS_WGSL: &str = "    view_proj: mat4x4<f32>,
+    time: f32,
+    delta_time: f32,
+    seed_lo: u32,
+    seed_hi: u32,
+    particle_count: u32,
+    bounds: f32,
+    _pad0: u32,
+    _pad1: u32,";
+
+/// An in-flight particle readback started by [`GpuState::begin_readback`].
+///
+/// Owns its own staging buffer, so several readbacks can be in flight at once
+/// and none of them interferes with [`GpuState::read_particles_sync`].
+pub struct ReadbackHandle {
+    staging: wgpu::Buffer,
+    receiver: std::sync::mpsc::Receiver<Result<(), wgpu::BufferAsyncError>>,
+    finished: bool,
+}
+
+impl ReadbackHandle {
+    /// Whether the readback has completed (successfully or not).
+    ///
+    /// Once finished, [`GpuState::poll_readback`] always returns `None`.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
 }
 
 /// GPU state for particle simulation and rendering.
@@ -104,6 +186,12 @@ pub struct GpuState {
     device: wgpu::Device,
     queue: wgpu::Queue,
     pub config: wgpu::SurfaceConfiguration,
+    /// Color format of scene passes (`Rgba16Float` with HDR, else the surface format).
+    scene_format: wgpu::TextureFormat,
+    /// MSAA sample count of scene passes (1 = off).
+    sample_count: u32,
+    /// Multisampled color target resolved into the scene target (None without MSAA).
+    msaa_view: Option<wgpu::TextureView>,
     render_pipeline: wgpu::RenderPipeline,
     compute_pipeline: wgpu::ComputePipeline,
     particle_buffer: wgpu::Buffer,
@@ -113,6 +201,14 @@ pub struct GpuState {
     compute_bind_group: wgpu::BindGroup,
     depth_texture: wgpu::TextureView,
     num_particles: u32,
+    /// Half-size of the simulation cube, written to the uniforms.
+    bounds: f32,
+    /// Simulation time last written to the uniforms.
+    time: f32,
+    /// Random seed written to the uniforms as `[low, high]`.
+    seed: [u32; 2],
+    /// Skip the compute, sub-emitter, and field passes while rendering.
+    compute_paused: bool,
     pub camera: Camera,
     // Optional spatial hashing
     spatial: Option<SpatialGpu>,
@@ -152,11 +248,26 @@ pub struct GpuState {
     sub_emitter: Option<SubEmitterGpu>,
     // Spatial grid visualization
     spatial_grid_viz: Option<SpatialGridViz>,
+    // Interaction radius visualization
+    interaction_radius_viz: Option<InteractionRadiusViz>,
     // Wireframe mesh rendering
     wireframe_state: Option<WireframeState>,
+    // Instanced triangle mesh rendering
+    mesh_state: Option<MeshState>,
+    // Directional light shadow mapping
+    shadow_state: Option<ShadowState>,
     // CPU readback support
     particle_stride: usize,
     readback_staging: Option<wgpu::Buffer>,
+    // Alive particle counter (atomic, reset every compute pass) and its readback
+    alive_count_buffer: wgpu::Buffer,
+    alive_count_staging: wgpu::Buffer,
+    alive_count_map: Option<std::sync::mpsc::Receiver<Result<(), wgpu::BufferAsyncError>>>,
+    alive_count: Option<u32>,
+    // Per-pass timestamp profiling (None if the device lacks TIMESTAMP_QUERY)
+    profiler: Option<GpuProfiler>,
+    // Particle compute workgroup size, chosen from the device limits
+    workgroup_size: u32,
     // GPU picking for particle selection
     picking: PickingState,
     // Pipeline rebuild support - store layouts and config
@@ -187,26 +298,46 @@ impl GpuState {
         color_offset: Option<u32>,
         alive_offset: u32,
         scale_offset: u32,
+        velocity_offset: Option<u32>,
         custom_uniform_size: usize,
         blend_mode: BlendMode,
         trail_length: u32,
+        trail_tail_color: Option<Vec3>,
+        trail_config: &crate::visuals::TrailConfig,
         particle_size: f32,
         connections_enabled: bool,
         connections_radius: f32,
         connections_color: Vec3,
+        connections_far_color: Vec3,
+        connections_directed: bool,
+        connections_flow_speed: f32,
         inbox_enabled: bool,
         background_color: Vec3,
         post_process_shader: Option<&str>,
+        post_process_chain: &[String],
+        chromatic_aberration: f32,
+        bloom: Option<&crate::visuals::BloomConfig>,
+        hdr: bool,
+        tone_map: crate::visuals::ToneMapMode,
+        msaa_samples: crate::visuals::MsaaSamples,
         custom_uniform_fields: &str,
         texture_registry: &crate::textures::TextureRegistry,
         _texture_declarations: &str,
         field_registry: &FieldRegistry,
         volume_config: Option<&VolumeConfig>,
         sub_emitters: &[crate::sub_emitter::SubEmitter],
+        emitter_count: usize,
         spatial_grid_opacity: f32,
+        interaction_radius_viz: Option<(u32, f32)>,
         particle_wgsl_struct: &str,
         wireframe_mesh: Option<&crate::visuals::WireframeMesh>,
         wireframe_thickness: f32,
+        mesh: Option<&crate::visuals::MeshData>,
+        mesh_align_to_velocity: bool,
+        light: Option<&crate::visuals::LightConfig>,
+        bounds: f32,
+        two_d: bool,
+        pipeline_cache_dir: Option<&std::path::Path>,
         #[cfg(feature = "egui")] egui_enabled: bool,
     ) -> Result<Self, GpuError> {
         let size = window.inner_size();
@@ -227,18 +358,47 @@ impl GpuState {
             .await
             .ok_or(GpuError::NoAdapter)?;
 
+        // Pipeline caching is opt-in and only available on some backends
+        let use_pipeline_cache =
+            pipeline_cache_dir.is_some() && PipelineCacheFile::supported(&adapter);
+
+        // Request what the adapter supports so the workgroup size can follow it
+        let limits = adapter.limits();
+        let workgroup_size = select_workgroup_size(&limits);
+
         let (device, queue) = adapter
             .request_device(
                 &wgpu::DeviceDescriptor {
                     label: Some("Device"),
-                    required_features: wgpu::Features::empty(),
-                    required_limits: wgpu::Limits::default(),
+                    // Timestamp queries are requested whenever available so
+                    // profiling can be toggled at runtime
+                    required_features: (adapter.features() & wgpu::Features::TIMESTAMP_QUERY)
+                        | if use_pipeline_cache {
+                            wgpu::Features::PIPELINE_CACHE
+                        } else {
+                            wgpu::Features::empty()
+                        },
+                    required_limits: limits,
                     memory_hints: Default::default(),
                 },
                 None, // trace path
             )
             .await?;
 
+        let pipeline_cache = pipeline_cache_dir
+            .filter(|_| use_pipeline_cache)
+            .and_then(|dir| {
+                PipelineCacheFile::load(
+                    &device,
+                    &adapter.get_info(),
+                    dir,
+                    compute_shader_src,
+                    render_shader_src,
+                )
+            });
+
+        let profiler = GpuProfiler::new(&device, &queue);
+
         let surface_caps = surface.get_capabilities(&adapter);
         let surface_format = surface_caps
             .formats
@@ -259,7 +419,28 @@ impl GpuState {
         };
         surface.configure(&device, &config);
 
-        let depth_texture = create_depth_texture(&device, &config);
+        // Scene passes render into an HDR offscreen target when enabled
+        let scene_format = if hdr { post_process::HDR_FORMAT } else { surface_format };
+
+        // Use the highest supported sample count not above the requested one
+        let color_flags = adapter.get_texture_format_features(scene_format).flags;
+        let depth_flags = adapter.get_texture_format_features(DEPTH_FORMAT).flags;
+        let sample_count = [8, 4, 2]
+            .into_iter()
+            .filter(|&n| n <= msaa_samples.count())
+            .find(|&n| color_flags.sample_count_supported(n) && depth_flags.sample_count_supported(n))
+            .unwrap_or(1);
+        if sample_count != msaa_samples.count() {
+            eprintln!(
+                "MSAA {}x is not supported for {:?}, using {}x",
+                msaa_samples.count(),
+                scene_format,
+                sample_count
+            );
+        }
+
+        let depth_texture = create_depth_texture(&device, &config, sample_count);
+        let msaa_view = create_msaa_view(&device, &config, scene_format, sample_count);
 
         let particle_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Particle Buffer"),
@@ -267,24 +448,27 @@ impl GpuState {
             usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
         });
 
-        let camera = Camera::new();
+        // 2D mode frames the whole XY extent of the bounds
+        let camera = if two_d { Camera::orthographic(bounds * 1.1) } else { Camera::new() };
         let aspect = config.width as f32 / config.height as f32;
         let view = camera.view_matrix();
-        let proj = Mat4::perspective_rh(45.0_f32.to_radians(), aspect, 0.1, 100.0);
+        let proj = camera.projection_matrix(aspect);
         let view_proj = proj * view;
 
         let uniforms = Uniforms {
             view_proj: view_proj.to_cols_array_2d(),
             time: 0.0,
             delta_time: 0.0,
+            seed: [0; 2],
+            particle_count: num_particles,
+            bounds,
+            _pad: [0; 2],
         };
 
         // Base uniform size + custom uniforms (aligned to 16 bytes for uniform buffer)
         // Note: We pad base_size to 16-byte alignment before adding custom uniforms
         // to ensure vec3/vec4 custom uniforms are properly aligned
-        let base_size = std::mem::size_of::<Uniforms>();
-        let padded_base_size = (base_size + 15) & !15;
-        let total_size = ((padded_base_size + custom_uniform_size) + 15) & !15;
+        let total_size = uniform_buffer_size(custom_uniform_size);
 
         // Create buffer with base uniforms + space for custom uniforms
         let mut uniform_data = bytemuck::bytes_of(&uniforms).to_vec();
@@ -305,6 +489,7 @@ impl GpuState {
                 num_particles,
                 spatial_config,
                 particle_wgsl_struct,
+                workgroup_size,
             ))
         } else {
             None
@@ -324,6 +509,23 @@ impl GpuState {
             None
         };
 
+        // Alive particle counter and per-emitter spawn counters, incremented
+        // atomically by the compute shader
+        let alive_count_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Alive Count Buffer"),
+            size: counter_buffer_size(emitter_count),
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_SRC
+                | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let alive_count_staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Alive Count Staging Buffer"),
+            size: 4,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
         // Render bind group layout (visible to both vertex and fragment for custom shaders)
         let uniform_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -349,152 +551,16 @@ impl GpuState {
             }],
         });
 
-        // Compute bind group layout - different depending on whether we have neighbors
-        let (compute_bind_group_layout, compute_bind_group) = if let Some(ref spatial) = spatial {
-            // With neighbors: particles, uniforms, sorted_indices, cell_start, cell_end, spatial_params
-            let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                label: Some("Compute Bind Group Layout (with neighbors)"),
-                entries: &[
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 0,
-                        visibility: wgpu::ShaderStages::COMPUTE,
-                        ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Storage { read_only: false },
-                            has_dynamic_offset: false,
-                            min_binding_size: None,
-                        },
-                        count: None,
-                    },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 1,
-                        visibility: wgpu::ShaderStages::COMPUTE,
-                        ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Uniform,
-                            has_dynamic_offset: false,
-                            min_binding_size: None,
-                        },
-                        count: None,
-                    },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 2,
-                        visibility: wgpu::ShaderStages::COMPUTE,
-                        ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Storage { read_only: true },
-                            has_dynamic_offset: false,
-                            min_binding_size: None,
-                        },
-                        count: None,
-                    },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 3,
-                        visibility: wgpu::ShaderStages::COMPUTE,
-                        ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Storage { read_only: true },
-                            has_dynamic_offset: false,
-                            min_binding_size: None,
-                        },
-                        count: None,
-                    },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 4,
-                        visibility: wgpu::ShaderStages::COMPUTE,
-                        ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Storage { read_only: true },
-                            has_dynamic_offset: false,
-                            min_binding_size: None,
-                        },
-                        count: None,
-                    },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 5,
-                        visibility: wgpu::ShaderStages::COMPUTE,
-                        ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Uniform,
-                            has_dynamic_offset: false,
-                            min_binding_size: None,
-                        },
-                        count: None,
-                    },
-                ],
-            });
-
-            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("Compute Bind Group (with neighbors)"),
-                layout: &layout,
-                entries: &[
-                    wgpu::BindGroupEntry {
-                        binding: 0,
-                        resource: particle_buffer.as_entire_binding(),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 1,
-                        resource: uniform_buffer.as_entire_binding(),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 2,
-                        resource: spatial.particle_indices_a.as_entire_binding(),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 3,
-                        resource: spatial.cell_start.as_entire_binding(),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 4,
-                        resource: spatial.cell_end.as_entire_binding(),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 5,
-                        resource: spatial.spatial_params_buffer.as_entire_binding(),
-                    },
-                ],
-            });
-
-            (layout, bind_group)
-        } else {
-            // Without neighbors: just particles and uniforms
-            let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                label: Some("Compute Bind Group Layout"),
-                entries: &[
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 0,
-                        visibility: wgpu::ShaderStages::COMPUTE,
-                        ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Storage { read_only: false },
-                            has_dynamic_offset: false,
-                            min_binding_size: None,
-                        },
-                        count: None,
-                    },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 1,
-                        visibility: wgpu::ShaderStages::COMPUTE,
-                        ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Uniform,
-                            has_dynamic_offset: false,
-                            min_binding_size: None,
-                        },
-                        count: None,
-                    },
-                ],
-            });
-
-            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("Compute Bind Group"),
-                layout: &layout,
-                entries: &[
-                    wgpu::BindGroupEntry {
-                        binding: 0,
-                        resource: particle_buffer.as_entire_binding(),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 1,
-                        resource: uniform_buffer.as_entire_binding(),
-                    },
-                ],
-            });
-
-            (layout, bind_group)
-        };
+        // Compute bind group (group 0) - spatial buffers are only bound with neighbors
+        let compute_bind_group_layout = create_compute_bind_group_layout(&device, spatial.is_some());
+        let compute_bind_group = create_compute_bind_group(
+            &device,
+            &compute_bind_group_layout,
+            &particle_buffer,
+            &uniform_buffer,
+            spatial.as_ref(),
+            &alive_count_buffer,
+        );
 
         // Create texture bind group layout early (needed for render pipeline layout)
         let texture_bind_group_layout = if !texture_registry.textures.is_empty() {
@@ -531,11 +597,25 @@ impl GpuState {
             source: wgpu::ShaderSource::Wgsl(render_shader_src.into()),
         });
 
-        // Build bind group layouts vec, including texture layout if present
+        // Shadow mapping (if a directional light is configured)
+        let shadow_state = light.map(|light| ShadowState::new(
+            &device,
+            light,
+            bounds,
+            particle_size,
+            particle_stride,
+            alive_offset,
+            scale_offset,
+        ));
+
+        // Build bind group layouts vec, including texture and shadow layouts if present
         let mut bind_group_layouts_vec: Vec<&wgpu::BindGroupLayout> = vec![&uniform_bind_group_layout];
         if let Some(ref tex_layout) = texture_bind_group_layout {
             bind_group_layouts_vec.push(tex_layout);
         }
+        if let Some(ref shadow) = shadow_state {
+            bind_group_layouts_vec.push(&shadow.render_bind_group_layout);
+        }
 
         let render_pipeline_layout =
             device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
@@ -544,49 +624,7 @@ impl GpuState {
                 push_constant_ranges: &[],
             });
 
-        // Build vertex attributes: position, optional color, alive, scale
-        let vertex_attributes: Vec<wgpu::VertexAttribute> = if let Some(offset) = color_offset {
-            vec![
-                wgpu::VertexAttribute {
-                    offset: 0,
-                    shader_location: 0,
-                    format: wgpu::VertexFormat::Float32x3, // position
-                },
-                wgpu::VertexAttribute {
-                    offset: offset as wgpu::BufferAddress,
-                    shader_location: 1,
-                    format: wgpu::VertexFormat::Float32x3, // color
-                },
-                wgpu::VertexAttribute {
-                    offset: alive_offset as wgpu::BufferAddress,
-                    shader_location: 2,
-                    format: wgpu::VertexFormat::Uint32, // alive
-                },
-                wgpu::VertexAttribute {
-                    offset: scale_offset as wgpu::BufferAddress,
-                    shader_location: 3,
-                    format: wgpu::VertexFormat::Float32, // scale
-                },
-            ]
-        } else {
-            vec![
-                wgpu::VertexAttribute {
-                    offset: 0,
-                    shader_location: 0,
-                    format: wgpu::VertexFormat::Float32x3, // position
-                },
-                wgpu::VertexAttribute {
-                    offset: alive_offset as wgpu::BufferAddress,
-                    shader_location: 2,
-                    format: wgpu::VertexFormat::Uint32, // alive
-                },
-                wgpu::VertexAttribute {
-                    offset: scale_offset as wgpu::BufferAddress,
-                    shader_location: 3,
-                    format: wgpu::VertexFormat::Float32, // scale
-                },
-            ]
-        };
+        let vertex_attributes = particle_vertex_attributes(color_offset, alive_offset, scale_offset);
 
         let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Render Pipeline"),
@@ -605,7 +643,7 @@ impl GpuState {
                 module: &render_shader,
                 entry_point: Some("fs_main"),
                 targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
+                    format: scene_format,
                     blend: Some(blend_mode_to_state(blend_mode)),
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
@@ -628,50 +666,40 @@ impl GpuState {
                 stencil: wgpu::StencilState::default(),
                 bias: wgpu::DepthBiasState::default(),
             }),
-            multisample: wgpu::MultisampleState::default(),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
             multiview: None,
-            cache: None,
+            cache: pipeline_cache.as_ref().map(|c| &c.cache),
         });
 
-        // Compute pipeline
+        // Compute pipeline. Errors are captured so they can name the
+        // custom code section they come from.
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
         let compute_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Compute Shader"),
             source: wgpu::ShaderSource::Wgsl(compute_shader_src.into()),
         });
+        if let Some(error) = device.pop_error_scope().await {
+            return Err(GpuError::ShaderCompilation(crate::rules::describe_shader_error(
+                compute_shader_src,
+                &error.to_string(),
+            )));
+        }
 
         // Create inbox bind group layout and bind group if enabled
-        let (inbox_bind_group_layout, inbox_bind_group) = if let Some(ref inbox_buf) = inbox_buffer {
-            let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                label: Some("Inbox Bind Group Layout"),
-                entries: &[wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::COMPUTE,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Storage { read_only: false },
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
-                }],
-            });
-
-            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("Inbox Bind Group"),
-                layout: &layout,
-                entries: &[wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: inbox_buf.as_entire_binding(),
-                }],
-            });
-
-            (Some(layout), Some(bind_group))
-        } else {
-            (None, None)
+        let (inbox_bind_group_layout, inbox_bind_group) = match inbox_buffer {
+            Some(ref inbox_buf) => {
+                let (layout, bind_group) = create_inbox_bind_group(&device, inbox_buf);
+                (Some(layout), Some(bind_group))
+            }
+            None => (None, None),
         };
 
         // Create field system if fields are registered
         let (field_system, field_bind_group_layout, field_bind_group) = if !field_registry.is_empty() {
-            let system = FieldSystemGpu::new(&device, field_registry);
+            let system = FieldSystemGpu::new(&device, field_registry, workgroup_size);
             let layout = create_particle_field_bind_group_layout(&device, system.field_count);
             let bind_group = system.create_particle_bind_group(&device, &layout);
             (Some(system), Some(layout), bind_group)
@@ -681,7 +709,7 @@ impl GpuState {
 
         // Create volume render state if configured and fields exist
         let volume_render = if let (Some(config), Some(ref fs)) = (&volume_config, &field_system) {
-            Some(VolumeRenderState::new(&device, fs, config, surface_format))
+            Some(VolumeRenderState::new(&device, &queue, fs, config, scene_format))
         } else {
             None
         };
@@ -694,73 +722,37 @@ impl GpuState {
                 num_particles,
                 sub_emitters,
                 particle_wgsl_struct,
+                workgroup_size,
             ))
         } else {
             None
         };
 
-        // Build compute pipeline layout with optional inbox, field, and sub-emitter bind groups
-        // Group 0: particles/uniforms/spatial
-        // Group 1: inbox (if enabled)
-        // Group 2: fields (if enabled)
-        // Group 3: sub-emitter death buffers (if enabled)
-        let (compute_pipeline_layout, empty_bind_group) = {
-            // Create empty layout/bind group for gaps
-            let empty_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                label: Some("Empty Bind Group Layout"),
-                entries: &[],
-            });
-            let empty_bg = device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("Empty Bind Group"),
-                layout: &empty_layout,
-                entries: &[],
-            });
-
-            // Build layouts vec dynamically
-            let mut layouts: Vec<&wgpu::BindGroupLayout> = vec![&compute_bind_group_layout];
-
-            // Group 1: inbox or empty
-            if let Some(ref inbox_layout) = inbox_bind_group_layout {
-                layouts.push(inbox_layout);
-            } else if field_bind_group_layout.is_some() || sub_emitter.is_some() {
-                // Need placeholder at group 1 if we have group 2 or 3
-                layouts.push(&empty_layout);
-            }
-
-            // Group 2: fields or empty
-            if let Some(ref field_layout) = field_bind_group_layout {
-                layouts.push(field_layout);
-            } else if sub_emitter.is_some() {
-                // Need placeholder at group 2 if we have group 3
-                layouts.push(&empty_layout);
-            }
-
-            // Group 3: sub-emitter death buffers
-            if let Some(ref se) = sub_emitter {
-                layouts.push(&se.death_bind_group_layout);
-            }
-
-            let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Compute Pipeline Layout"),
-                bind_group_layouts: &layouts,
-                push_constant_ranges: &[],
-            });
-
-            // Only keep empty_bg if we need it
-            let keep_empty = (inbox_bind_group_layout.is_none() && (field_bind_group_layout.is_some() || sub_emitter.is_some()))
-                || (field_bind_group_layout.is_none() && sub_emitter.is_some());
-            (layout, if keep_empty { Some(empty_bg) } else { None })
-        };
+        let (compute_pipeline_layout, empty_bind_group) = create_compute_pipeline_layout(
+            &device,
+            &compute_bind_group_layout,
+            inbox_bind_group_layout.as_ref(),
+            field_bind_group_layout.as_ref(),
+            sub_emitter.as_ref(),
+        );
 
         let compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
             label: Some("Compute Pipeline"),
             layout: Some(&compute_pipeline_layout),
             module: &compute_shader,
             entry_point: Some("main"),
-            compilation_options: Default::default(),
-            cache: None,
+            compilation_options: wgpu::PipelineCompilationOptions {
+                constants: &workgroup_size_constants(compute_shader_src, workgroup_size),
+                ..Default::default()
+            },
+            cache: pipeline_cache.as_ref().map(|c| &c.cache),
         });
 
+        // Persist compiled pipelines so the next run can skip compilation
+        if let Some(ref cache) = pipeline_cache {
+            cache.save();
+        }
+
         // Trail system (if trail_length > 0)
         let trail_state = if trail_length > 0 {
             Some(TrailState::new(
@@ -768,12 +760,17 @@ impl GpuState {
                 &particle_buffer,
                 &uniform_buffer,
                 num_particles,
+                workgroup_size,
                 trail_length,
                 particle_stride,
                 color_offset,
+                velocity_offset,
                 particle_size,
+                trail_tail_color,
+                trail_config,
                 blend_mode,
-                config.format,
+                scene_format,
+                sample_count,
             ))
         } else {
             None
@@ -787,25 +784,46 @@ impl GpuState {
                 &uniform_buffer,
                 spatial_ref,
                 num_particles,
+                workgroup_size,
                 connections_radius,
                 connections_color,
+                connections_far_color,
+                connections_directed,
+                connections_flow_speed,
                 particle_stride,
                 blend_mode,
-                config.format,
+                scene_format,
+                sample_count,
             ))
         } else {
             None
         };
 
-        // Post-processing setup
-        let post_process = if let Some(shader_code) = post_process_shader {
-            Some(PostProcessState::new(
+        // Post-processing setup (bloom or HDR alone still need the offscreen target)
+        let aberration_pass = (chromatic_aberration > 0.0).then(|| {
+            format!(
+                "let aberration_strength = {:.4};\n{}",
+                chromatic_aberration,
+                crate::shader_utils::CHROMATIC_ABERRATION_WGSL
+            )
+        });
+        let post_process_shaders: Vec<&str> = aberration_pass
+            .as_deref()
+            .into_iter()
+            .chain(post_process_shader)
+            .chain(post_process_chain.iter().map(String::as_str))
+            .collect();
+        let post_process = if !post_process_shaders.is_empty() || bloom.is_some() || hdr {
+            Some(PostProcessState::new_chain(
                 &device,
                 &uniform_buffer,
-                shader_code,
+                &post_process_shaders,
                 custom_uniform_fields,
+                bloom,
+                hdr.then_some(tone_map),
                 config.width,
                 config.height,
+                scene_format,
                 config.format,
             ))
         } else {
@@ -920,7 +938,22 @@ impl GpuState {
             &uniform_buffer,
             &spatial_config,
             spatial_grid_opacity,
-            surface_format,
+            scene_format,
+            sample_count,
+        ));
+
+        // Interaction radius spheres (if configured)
+        let interaction_radius_viz = interaction_radius_viz.map(|(particle_type, radius)| InteractionRadiusViz::new(
+            &device,
+            &particle_buffer,
+            &uniform_buffer,
+            particle_wgsl_struct,
+            num_particles,
+            particle_type,
+            radius,
+            two_d,
+            scene_format,
+            sample_count,
         ));
 
         // Wireframe mesh rendering (if configured)
@@ -937,7 +970,25 @@ impl GpuState {
             alive_offset,
             scale_offset,
             blend_mode,
-            surface_format,
+            scene_format,
+            sample_count,
+        ));
+
+        // Instanced mesh rendering (if the particle shape is a mesh)
+        let mesh_state = mesh.map(|mesh| MeshState::new(
+            &device,
+            &uniform_buffer,
+            mesh,
+            mesh_align_to_velocity,
+            particle_size,
+            particle_stride,
+            color_offset,
+            velocity_offset,
+            alive_offset,
+            scale_offset,
+            blend_mode,
+            scene_format,
+            sample_count,
         ));
 
         // GPU picking for particle selection
@@ -956,6 +1007,9 @@ impl GpuState {
             device,
             queue,
             config,
+            scene_format,
+            sample_count,
+            msaa_view,
             render_pipeline,
             compute_pipeline,
             particle_buffer,
@@ -965,6 +1019,10 @@ impl GpuState {
             compute_bind_group,
             depth_texture,
             num_particles,
+            bounds,
+            time: 0.0,
+            seed: [0; 2],
+            compute_paused: false,
             camera,
             spatial,
             trail_state,
@@ -991,9 +1049,18 @@ impl GpuState {
             window,
             sub_emitter,
             spatial_grid_viz,
+            interaction_radius_viz,
             wireframe_state,
+            mesh_state,
+            shadow_state,
             particle_stride,
             readback_staging: None,
+            alive_count_buffer,
+            alive_count_staging,
+            alive_count_map: None,
+            alive_count: None,
+            profiler,
+            workgroup_size,
             picking,
             // Pipeline rebuild support
             render_pipeline_layout,
@@ -1011,7 +1078,8 @@ impl GpuState {
             self.config.width = new_size.width;
             self.config.height = new_size.height;
             self.surface.configure(&self.device, &self.config);
-            self.depth_texture = create_depth_texture(&self.device, &self.config);
+            self.depth_texture = create_depth_texture(&self.device, &self.config, self.sample_count);
+            self.msaa_view = create_msaa_view(&self.device, &self.config, self.scene_format, self.sample_count);
 
             // Resize post-processing if enabled
             if let Some(ref mut pp) = self.post_process {
@@ -1020,7 +1088,6 @@ impl GpuState {
                     &self.uniform_buffer,
                     self.config.width,
                     self.config.height,
-                    self.config.format,
                 );
             }
 
@@ -1057,49 +1124,8 @@ impl GpuState {
             source: wgpu::ShaderSource::Wgsl(render_shader_src.into()),
         });
 
-        // Build vertex attributes
-        let vertex_attributes: Vec<wgpu::VertexAttribute> = if let Some(offset) = self.color_offset {
-            vec![
-                wgpu::VertexAttribute {
-                    offset: 0,
-                    shader_location: 0,
-                    format: wgpu::VertexFormat::Float32x3, // position
-                },
-                wgpu::VertexAttribute {
-                    offset: offset as wgpu::BufferAddress,
-                    shader_location: 1,
-                    format: wgpu::VertexFormat::Float32x3, // color
-                },
-                wgpu::VertexAttribute {
-                    offset: self.alive_offset as wgpu::BufferAddress,
-                    shader_location: 2,
-                    format: wgpu::VertexFormat::Uint32, // alive
-                },
-                wgpu::VertexAttribute {
-                    offset: self.scale_offset as wgpu::BufferAddress,
-                    shader_location: 3,
-                    format: wgpu::VertexFormat::Float32, // scale
-                },
-            ]
-        } else {
-            vec![
-                wgpu::VertexAttribute {
-                    offset: 0,
-                    shader_location: 0,
-                    format: wgpu::VertexFormat::Float32x3, // position
-                },
-                wgpu::VertexAttribute {
-                    offset: self.alive_offset as wgpu::BufferAddress,
-                    shader_location: 2,
-                    format: wgpu::VertexFormat::Uint32, // alive
-                },
-                wgpu::VertexAttribute {
-                    offset: self.scale_offset as wgpu::BufferAddress,
-                    shader_location: 3,
-                    format: wgpu::VertexFormat::Float32, // scale
-                },
-            ]
-        };
+        let vertex_attributes =
+            particle_vertex_attributes(self.color_offset, self.alive_offset, self.scale_offset);
 
         // Create new render pipeline
         let new_pipeline = self.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
@@ -1119,7 +1145,7 @@ impl GpuState {
                 module: &render_shader,
                 entry_point: Some("fs_main"),
                 targets: &[Some(wgpu::ColorTargetState {
-                    format: self.config.format,
+                    format: self.scene_format,
                     blend: Some(blend_mode_to_state(blend_mode)),
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
@@ -1141,7 +1167,10 @@ impl GpuState {
                 stencil: wgpu::StencilState::default(),
                 bias: wgpu::DepthBiasState::default(),
             }),
-            multisample: wgpu::MultisampleState::default(),
+            multisample: wgpu::MultisampleState {
+                count: self.sample_count,
+                ..Default::default()
+            },
             multiview: None,
             cache: None,
         });
@@ -1167,13 +1196,130 @@ impl GpuState {
             layout: Some(&self.compute_pipeline_layout),
             module: &compute_shader,
             entry_point: Some("main"),
-            compilation_options: Default::default(),
+            compilation_options: wgpu::PipelineCompilationOptions {
+                constants: &workgroup_size_constants(compute_shader_src, self.workgroup_size),
+                ..Default::default()
+            },
             cache: None,
         });
 
         self.compute_pipeline = new_pipeline;
     }
 
+    /// Rebuild the compute pipeline, keeping the old one if the shader is invalid.
+    ///
+    /// Like [`rebuild_compute_pipeline`](Self::rebuild_compute_pipeline), but
+    /// shader and pipeline validation errors are captured in an error scope
+    /// and returned instead of reaching the device's uncaptured error handler.
+    pub fn try_rebuild_compute_pipeline(&mut self, compute_shader_src: &str) -> Result<(), String> {
+        let previous = self.compute_pipeline.clone();
+        self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        self.rebuild_compute_pipeline(compute_shader_src);
+        match pollster::block_on(self.device.pop_error_scope()) {
+            Some(error) => {
+                self.compute_pipeline = previous;
+                Err(crate::rules::describe_shader_error(compute_shader_src, &error.to_string()))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Change the particle count without rebuilding any pipelines.
+    ///
+    /// Allocates a new particle buffer, copies the first
+    /// `min(old_count, new_count)` particles across on the GPU and calls
+    /// `spawner(index, bytes)` to fill each new slot with one particle's
+    /// raw bytes (`particle_stride` long). Only the per-particle buffers and
+    /// the bind groups that reference them are recreated - spatial hashing,
+    /// the inbox, trails, connections, sub-emitters and wireframes follow
+    /// the new count. Trail history is cleared.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_count` is zero.
+    pub fn resize_particles(&mut self, new_count: u32, mut spawner: impl FnMut(u32, &mut [u8])) {
+        assert!(new_count > 0, "particle count must be non-zero");
+        let old_count = self.num_particles;
+        if new_count == old_count {
+            return;
+        }
+
+        let stride = self.particle_stride as u64;
+        let particle_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Particle Buffer"),
+            size: new_count as u64 * stride,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Particle Resize Encoder"),
+        });
+        let kept = old_count.min(new_count) as u64;
+        encoder.copy_buffer_to_buffer(&self.particle_buffer, 0, &particle_buffer, 0, kept * stride);
+
+        if new_count > old_count {
+            let mut data = vec![0u8; (new_count - old_count) as usize * self.particle_stride];
+            for (i, bytes) in data.chunks_exact_mut(self.particle_stride).enumerate() {
+                spawner(old_count + i as u32, bytes);
+            }
+            self.queue.write_buffer(&particle_buffer, kept * stride, &data);
+        }
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        self.particle_buffer = particle_buffer;
+        self.num_particles = new_count;
+        // The staging buffer is sized for the old count
+        self.readback_staging = None;
+
+        if let Some(ref mut spatial) = self.spatial {
+            spatial.resize(&self.device, &self.queue, &self.particle_buffer, new_count);
+        }
+
+        self.compute_bind_group = create_compute_bind_group(
+            &self.device,
+            &self.compute_pipeline.get_bind_group_layout(0),
+            &self.particle_buffer,
+            &self.uniform_buffer,
+            self.spatial.as_ref(),
+            &self.alive_count_buffer,
+        );
+
+        if self.inbox_buffer.is_some() {
+            let inbox_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Inbox Buffer"),
+                size: new_count as u64 * 16,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            self.inbox_bind_group = Some(self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Inbox Bind Group"),
+                layout: &self.compute_pipeline.get_bind_group_layout(1),
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: inbox_buffer.as_entire_binding(),
+                }],
+            }));
+            self.inbox_buffer = Some(inbox_buffer);
+        }
+
+        if let Some(ref mut trail) = self.trail_state {
+            trail.resize(&self.device, &self.particle_buffer, &self.uniform_buffer, new_count);
+        }
+        if let (Some(conn), Some(spatial)) = (self.connection_state.as_mut(), self.spatial.as_ref()) {
+            conn.resize(&self.device, &self.particle_buffer, &self.uniform_buffer, spatial, new_count);
+        }
+        if let Some(ref mut se) = self.sub_emitter {
+            se.set_particle_buffer(&self.device, &self.particle_buffer, new_count);
+        }
+        if let Some(ref mut wireframe) = self.wireframe_state {
+            wireframe.set_particle_buffer(&self.device, &self.particle_buffer, &self.uniform_buffer, new_count);
+        }
+        if let Some(ref mut radius_viz) = self.interaction_radius_viz {
+            radius_viz.set_particle_buffer(&self.device, &self.particle_buffer, &self.uniform_buffer, new_count);
+        }
+    }
+
     /// Request particle picking at the given screen coordinates.
     ///
     /// The pick will be performed on the next render, and the result
@@ -1196,6 +1342,8 @@ impl GpuState {
     ///
     /// This is an expensive operation that stalls the GPU pipeline.
     /// Use sparingly (e.g., once per second, or on user request).
+    /// Prefer [`begin_readback`](Self::begin_readback) when the data can
+    /// arrive a few frames late.
     ///
     /// Returns raw bytes that can be cast to your particle's GPU type:
     /// ```ignore
@@ -1219,65 +1367,197 @@ impl GpuState {
             })
         });
 
-        // Copy particle buffer to staging
+        read_buffer_sync(&self.device, &self.queue, &self.particle_buffer, staging)
+    }
+
+    /// Start reading particle data from GPU to CPU without blocking.
+    ///
+    /// Records a copy of the particle buffer into a fresh staging buffer,
+    /// submits it, and requests a mapping. Call [`poll_readback`](Self::poll_readback)
+    /// on later frames until it returns the data:
+    /// ```ignore
+    /// let mut handle = gpu_state.begin_readback();
+    /// // ... later, once per frame:
+    /// if let Some(bytes) = gpu_state.poll_readback(&mut handle) {
+    ///     let particles: &[MyParticleGpu] = bytemuck::cast_slice(&bytes);
+    /// }
+    /// ```
+    pub fn begin_readback(&mut self) -> ReadbackHandle {
+        let buffer_size = (self.num_particles as usize * self.particle_stride) as u64;
+
+        let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Async Readback Staging Buffer"),
+            size: buffer_size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
         let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
-            label: Some("Readback Encoder"),
+            label: Some("Async Readback Encoder"),
         });
-        encoder.copy_buffer_to_buffer(
-            &self.particle_buffer,
-            0,
-            staging,
-            0,
-            buffer_size as u64,
-        );
+        encoder.copy_buffer_to_buffer(&self.particle_buffer, 0, &staging, 0, buffer_size);
         self.queue.submit(std::iter::once(encoder.finish()));
 
-        // Map and read
-        let buffer_slice = staging.slice(..);
+        // The mapping resolves once the copy above has executed
         let (tx, rx) = std::sync::mpsc::channel();
-        buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
-            // Ignore send errors - receiver may have been dropped
+        staging.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+            // Ignore send errors - the handle may have been dropped
             let _ = tx.send(result);
         });
 
-        // Wait for mapping to complete
-        self.device.poll(wgpu::Maintain::Wait);
-        rx.recv()
-            .map_err(|_| GpuError::BufferMapping("Channel receive failed".to_string()))?
-            .map_err(|e| GpuError::BufferMapping(format!("Buffer mapping failed: {}", e)))?;
+        ReadbackHandle {
+            staging,
+            receiver: rx,
+            finished: false,
+        }
+    }
 
-        // Copy data
-        let data = buffer_slice.get_mapped_range();
-        let result = data.to_vec();
-        drop(data);
-        staging.unmap();
+    /// Check whether a readback started with [`begin_readback`](Self::begin_readback)
+    /// has completed, without waiting for the GPU.
+    ///
+    /// Returns the raw particle bytes (same format as
+    /// [`read_particles_sync`](Self::read_particles_sync)) once the copy is
+    /// done, or `None` if it is still in flight. If the mapping fails, the
+    /// error is logged and the handle is marked finished.
+    pub fn poll_readback(&self, handle: &mut ReadbackHandle) -> Option<Vec<u8>> {
+        if handle.finished {
+            return None;
+        }
 
-        Ok(result)
+        self.device.poll(wgpu::Maintain::Poll);
+        match handle.receiver.try_recv() {
+            Ok(Ok(())) => {
+                let data = handle.staging.slice(..).get_mapped_range().to_vec();
+                handle.staging.unmap();
+                handle.finished = true;
+                Some(data)
+            }
+            Ok(Err(e)) => {
+                eprintln!("Particle readback failed: Buffer mapping failed: {}", e);
+                handle.finished = true;
+                None
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => None,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                eprintln!("Particle readback failed: Channel receive failed");
+                handle.finished = true;
+                None
+            }
+        }
     }
 
-    /// Write particle data from CPU to GPU.
-    ///
-    /// This is used to restore particle state after a pipeline rebuild.
-    /// The data should be the same format returned by `read_particles_sync()`.
+    /// Number of alive particles as of the most recently completed readback.
     ///
-    /// # Panics
+    /// The compute shader counts alive particles every frame; the count is
+    /// read back asynchronously, so this never stalls and typically lags a
+    /// frame or two behind. Returns `None` until the first read completes.
+    pub fn alive_count(&self) -> Option<u32> {
+        self.alive_count
+    }
+
+    /// Turn per-pass GPU timing on or off.
     ///
-    /// Panics if the data size doesn't match the particle buffer size.
-    pub fn write_particles(&mut self, data: &[u8]) {
-        let expected_size = (self.num_particles as usize) * self.particle_stride;
-        assert_eq!(
-            data.len(),
-            expected_size,
-            "Particle data size mismatch: expected {} bytes, got {}",
-            expected_size,
-            data.len()
-        );
-        self.queue.write_buffer(&self.particle_buffer, 0, data);
+    /// Has no effect if the adapter doesn't support
+    /// [`wgpu::Features::TIMESTAMP_QUERY`].
+    pub fn enable_profiling(&mut self, enabled: bool) {
+        if let Some(ref mut profiler) = self.profiler {
+            profiler.set_enabled(enabled);
+        }
     }
 
-    /// Get the number of particles.
-    pub fn num_particles(&self) -> u32 {
-        self.num_particles
+    /// GPU time per pass for the most recent profiled frame.
+    ///
+    /// Returns `None` if profiling is off, unsupported, or no frame has
+    /// finished reading back yet.
+    pub fn last_frame_timings(&self) -> Option<FrameTimings> {
+        self.profiler.as_ref().and_then(|p| p.last_frame_timings())
+    }
+
+    /// Record a copy of the alive counter into its staging buffer.
+    ///
+    /// Returns `false` if the staging buffer is still waiting to be mapped.
+    fn copy_alive_count(&self, encoder: &mut wgpu::CommandEncoder) -> bool {
+        if self.alive_count_map.is_some() {
+            return false;
+        }
+        encoder.copy_buffer_to_buffer(&self.alive_count_buffer, 0, &self.alive_count_staging, 0, 4);
+        true
+    }
+
+    /// Request a mapping of the alive count staging buffer after submit.
+    fn map_alive_count(&mut self) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.alive_count_staging.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+            // Ignore send errors - receiver may have been dropped
+            let _ = tx.send(result);
+        });
+        self.alive_count_map = Some(rx);
+    }
+
+    /// Pick up the alive count if its mapping has completed, without waiting.
+    fn poll_alive_count(&mut self) {
+        let Some(ref rx) = self.alive_count_map else {
+            return;
+        };
+        self.device.poll(wgpu::Maintain::Poll);
+        match rx.try_recv() {
+            Ok(Ok(())) => {
+                let data = self.alive_count_staging.slice(..).get_mapped_range();
+                self.alive_count = Some(u32::from_le_bytes([data[0], data[1], data[2], data[3]]));
+                drop(data);
+                self.alive_count_staging.unmap();
+                self.alive_count_map = None;
+            }
+            Ok(Err(_)) | Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                self.alive_count_map = None;
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => {}
+        }
+    }
+
+    /// Write particle data from CPU to GPU.
+    ///
+    /// This is used to restore particle state after a pipeline rebuild.
+    /// The data should be the same format returned by `read_particles_sync()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the data size doesn't match the particle buffer size.
+    pub fn write_particles(&mut self, data: &[u8]) {
+        let expected_size = (self.num_particles as usize) * self.particle_stride;
+        assert_eq!(
+            data.len(),
+            expected_size,
+            "Particle data size mismatch: expected {} bytes, got {}",
+            expected_size,
+            data.len()
+        );
+        self.queue.write_buffer(&self.particle_buffer, 0, data);
+    }
+
+    /// Read the particle buffer back into a [`ParticleSnapshot`].
+    ///
+    /// Blocks until the GPU has finished, like [`read_particles_sync`](Self::read_particles_sync).
+    pub fn snapshot(&mut self) -> Result<ParticleSnapshot, GpuError> {
+        let data = self.read_particles_sync()?;
+        Ok(ParticleSnapshot::new(data, self.particle_stride))
+    }
+
+    /// Write a snapshot back into the particle buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SnapshotError::LayoutMismatch` if the snapshot was taken from
+    /// a simulation with a different particle count or layout.
+    pub fn restore(&mut self, snapshot: &ParticleSnapshot) -> Result<(), SnapshotError> {
+        snapshot.check_layout(self.num_particles, self.particle_stride)?;
+        self.write_particles(&snapshot.data);
+        Ok(())
+    }
+
+    /// Get the number of particles.
+    pub fn num_particles(&self) -> u32 {
+        self.num_particles
     }
 
     /// Get the particle stride (bytes per particle).
@@ -1312,34 +1592,143 @@ impl GpuState {
     }
 
     fn update_uniforms(&mut self, time: f32, delta_time: f32, custom_uniform_bytes: Option<&[u8]>) {
+        self.time = time;
         let aspect = self.config.width as f32 / self.config.height as f32;
         let view = self.camera.view_matrix();
-        let proj = Mat4::perspective_rh(45.0_f32.to_radians(), aspect, 0.1, 100.0);
+        let proj = self.camera.projection_matrix(aspect);
         let view_proj = proj * view;
 
         let uniforms = Uniforms {
             view_proj: view_proj.to_cols_array_2d(),
             time,
             delta_time,
+            seed: self.seed,
+            particle_count: self.num_particles,
+            bounds: self.bounds,
+            _pad: [0; 2],
         };
 
-        // Write base uniforms
-        let base_bytes = bytemuck::bytes_of(&uniforms);
+        let bytes = uniform_bytes(&uniforms, custom_uniform_bytes, self.uniform_buffer_size);
+        self.queue.write_buffer(&self.uniform_buffer, 0, &bytes);
+    }
 
-        if let Some(custom_bytes) = custom_uniform_bytes {
-            // Combine base and custom uniforms
-            let mut combined = base_bytes.to_vec();
-            // Pad to 16-byte alignment before appending custom uniforms
-            // This ensures vec3/vec4 custom uniforms are properly aligned
-            while !combined.len().is_multiple_of(16) {
-                combined.push(0);
-            }
-            combined.extend_from_slice(custom_bytes);
-            // Pad to buffer size
-            combined.resize(self.uniform_buffer_size, 0);
-            self.queue.write_buffer(&self.uniform_buffer, 0, &combined);
+    /// Record spatial hashing and the particle compute pass into `encoder`.
+    ///
+    /// Clears the inbox, sub-emitter death buffers, and alive counter first.
+    /// With `timed`, the passes are recorded by the profiler.
+    fn encode_compute(&mut self, encoder: &mut wgpu::CommandEncoder, timed: bool) {
+        if self.compute_paused {
+            return;
+        }
+
+        // Spatial hashing pass (if enabled)
+        if let Some(ref spatial) = self.spatial {
+            let timestamps = self.profiler.as_mut()
+                .filter(|_| timed)
+                .and_then(|p| p.compute_timestamps(ProfiledPass::SpatialHash));
+            spatial.execute_timed(encoder, &self.queue, timestamps);
+        }
+
+        // Clear inbox buffer before compute pass
+        if let Some(ref inbox_buf) = self.inbox_buffer {
+            let inbox_size = (self.num_particles as usize) * 16;
+            let zeros = vec![0u8; inbox_size];
+            self.queue.write_buffer(inbox_buf, 0, &zeros);
+        }
+
+        // Clear sub-emitter death buffers before compute pass
+        if let Some(ref se) = self.sub_emitter {
+            se.clear_buffers(&self.queue);
+        }
+
+        // Reset alive and emitter counters before compute pass
+        self.queue.write_buffer(&self.alive_count_buffer, 0, &vec![0u8; self.alive_count_buffer.size() as usize]);
+
+        // Recreate field bind group each frame (buffers may have been swapped during blur)
+        let field_bind_group = if let (Some(ref field_sys), Some(ref layout)) =
+            (&self.field_system, &self.field_bind_group_layout)
+        {
+            field_sys.create_particle_bind_group(&self.device, layout)
         } else {
-            self.queue.write_buffer(&self.uniform_buffer, 0, base_bytes);
+            None
+        };
+
+        // Compute pass
+        {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Compute Pass"),
+                timestamp_writes: self.profiler.as_mut()
+                    .filter(|_| timed)
+                    .and_then(|p| p.compute_timestamps(ProfiledPass::Compute)),
+            });
+
+            compute_pass.set_pipeline(&self.compute_pipeline);
+            set_compute_bind_groups(
+                &mut compute_pass,
+                &self.compute_bind_group,
+                self.inbox_bind_group.as_ref(),
+                field_bind_group.as_ref(),
+                self.field_system.is_some(),
+                self.sub_emitter.as_ref(),
+                self.empty_bind_group.as_ref(),
+            );
+
+            let workgroups = self.num_particles.div_ceil(self.workgroup_size);
+            compute_pass.dispatch_workgroups(workgroups, 1, 1);
+        }
+    }
+
+    /// Set the seed mixed into the compute shader's random number generator.
+    ///
+    /// Takes effect from the next step or frame. A seed of 0 leaves the
+    /// generator unseeded.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = split_seed(seed);
+    }
+
+    /// Stop or resume simulating while frames keep rendering.
+    ///
+    /// While paused, the particle compute pass, sub-emitter spawning, and
+    /// field processing are skipped, so the particle buffer only changes
+    /// through [`write_particles`](Self::write_particles). Used for playing
+    /// back recordings.
+    pub fn set_compute_paused(&mut self, paused: bool) {
+        self.compute_paused = paused;
+    }
+
+    /// Advance the simulation `n` steps of `fixed_delta_time` without rendering.
+    ///
+    /// Each step runs spatial hashing, the particle compute pass, sub-emitter
+    /// spawning, and field processing, with no render pass or presentation.
+    /// `uniforms.time` continues from the last frame. Use it to pre-warm a
+    /// simulation before it is shown or to sub-step a frame.
+    pub fn step(&mut self, n: u32, fixed_delta_time: f32, custom_uniform_bytes: Option<&[u8]>) {
+        if self.compute_paused {
+            return;
+        }
+
+        for _ in 0..n {
+            let time = self.time + fixed_delta_time;
+            self.update_uniforms(time, fixed_delta_time, custom_uniform_bytes);
+
+            let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Step Encoder"),
+            });
+
+            self.encode_compute(&mut encoder, false);
+
+            if let Some(ref se) = self.sub_emitter {
+                se.spawn_children(&mut encoder);
+            }
+
+            if let Some(ref mut field_sys) = self.field_system {
+                field_sys.process(&self.device, &mut encoder, &self.queue);
+                if let Some(ref mut vol) = self.volume_render {
+                    vol.update_bind_group(&self.device, field_sys);
+                }
+            }
+
+            self.queue.submit(std::iter::once(encoder.finish()));
         }
     }
 
@@ -1424,70 +1813,8 @@ impl GpuState {
             egui.prepare(&self.device, &self.queue, &mut encoder, egui_out, &screen_descriptor);
         }
 
-        // Spatial hashing pass (if enabled)
-        if let Some(ref spatial) = self.spatial {
-            spatial.execute(&mut encoder, &self.queue);
-        }
-
-        // Clear inbox buffer before compute pass
-        if let Some(ref inbox_buf) = self.inbox_buffer {
-            let inbox_size = (self.num_particles as usize) * 16;
-            let zeros = vec![0u8; inbox_size];
-            self.queue.write_buffer(inbox_buf, 0, &zeros);
-        }
-
-        // Clear sub-emitter death buffers before compute pass
-        if let Some(ref se) = self.sub_emitter {
-            se.clear_buffers(&self.queue);
-        }
-
-        // Recreate field bind group each frame (buffers may have been swapped during blur)
-        let field_bind_group = if let (Some(ref field_sys), Some(ref layout)) =
-            (&self.field_system, &self.field_bind_group_layout)
-        {
-            field_sys.create_particle_bind_group(&self.device, layout)
-        } else {
-            None
-        };
-
-        // Compute pass
-        {
-            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-                label: Some("Compute Pass"),
-                timestamp_writes: None,
-            });
-
-            compute_pass.set_pipeline(&self.compute_pipeline);
-            compute_pass.set_bind_group(0, &self.compute_bind_group, &[]);
-
-            // Set inbox bind group if enabled (group 1)
-            if let Some(ref inbox_bg) = self.inbox_bind_group {
-                compute_pass.set_bind_group(1, inbox_bg, &[]);
-            } else if self.field_system.is_some() || self.sub_emitter.is_some() {
-                // Need placeholder at group 1 if we have group 2 or 3
-                if let Some(ref empty_bg) = self.empty_bind_group {
-                    compute_pass.set_bind_group(1, empty_bg, &[]);
-                }
-            }
-
-            // Set field bind group if enabled (group 2)
-            if let Some(ref field_bg) = field_bind_group {
-                compute_pass.set_bind_group(2, field_bg, &[]);
-            } else if self.sub_emitter.is_some() {
-                // Need placeholder at group 2 if we have group 3
-                if let Some(ref empty_bg) = self.empty_bind_group {
-                    compute_pass.set_bind_group(2, empty_bg, &[]);
-                }
-            }
-
-            // Set sub-emitter death buffer bind group if enabled (group 3)
-            if let Some(ref se) = self.sub_emitter {
-                compute_pass.set_bind_group(3, &se.death_bind_group, &[]);
-            }
-
-            let workgroups = self.num_particles.div_ceil(WORKGROUP_SIZE);
-            compute_pass.dispatch_workgroups(workgroups, 1, 1);
-        }
+        // Spatial hashing and particle compute pass
+        self.encode_compute(&mut encoder, true);
 
         // Apply pending particle write AFTER compute pass (so edits aren't overwritten)
         #[cfg(feature = "egui")]
@@ -1496,14 +1823,21 @@ impl GpuState {
             self.queue.write_buffer(&self.particle_buffer, offset as u64, &bytes);
         }
 
+        // Copy alive counter to staging (skipped while a previous read is still mapping)
+        let alive_count_copied = self.copy_alive_count(&mut encoder);
+
         // Sub-emitter spawn pass (spawn children from death events)
         if let Some(ref se) = self.sub_emitter {
-            se.spawn_children(&mut encoder);
+            if !self.compute_paused {
+                se.spawn_children(&mut encoder);
+            }
         }
 
         // Field processing pass (merge deposits, blur/decay, clear write buffer)
         if let Some(ref mut field_sys) = self.field_system {
-            field_sys.process(&self.device, &mut encoder, &self.queue);
+            if !self.compute_paused {
+                field_sys.process(&self.device, &mut encoder, &self.queue);
+            }
 
             // Update volume render bind group after field processing (buffers may have swapped)
             if let Some(ref mut vol) = self.volume_render {
@@ -1511,7 +1845,7 @@ impl GpuState {
 
                 // Get camera matrices for ray reconstruction
                 let aspect = self.config.width as f32 / self.config.height as f32;
-                let proj = Mat4::perspective_rh(std::f32::consts::FRAC_PI_4, aspect, 0.1, 100.0);
+                let proj = self.camera.projection_matrix(aspect);
                 let view = self.camera.view_matrix();
                 let view_proj = proj * view;
                 let inv_view_proj = view_proj.inverse();
@@ -1536,13 +1870,19 @@ impl GpuState {
         if let Some(ref trail) = self.trail_state {
             let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Trail Compute Pass"),
-                timestamp_writes: None,
+                timestamp_writes: self.profiler.as_mut()
+                    .and_then(|p| p.compute_timestamps(ProfiledPass::TrailCompute)),
             });
 
             compute_pass.set_pipeline(&trail.compute_pipeline);
             compute_pass.set_bind_group(0, &trail.compute_bind_group, &[]);
 
-            let workgroups = self.num_particles.div_ceil(WORKGROUP_SIZE);
+            let workgroups = self.num_particles.div_ceil(self.workgroup_size);
+            compute_pass.dispatch_workgroups(workgroups, 1, 1);
+
+            // Interpolate the updated history into ribbon samples
+            compute_pass.set_pipeline(&trail.ribbon_pipeline);
+            compute_pass.set_bind_group(0, &trail.ribbon_bind_group, &[]);
             compute_pass.dispatch_workgroups(workgroups, 1, 1);
         }
 
@@ -1553,13 +1893,14 @@ impl GpuState {
 
             let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Connection Compute Pass"),
-                timestamp_writes: None,
+                timestamp_writes: self.profiler.as_mut()
+                    .and_then(|p| p.compute_timestamps(ProfiledPass::ConnectionCompute)),
             });
 
             compute_pass.set_pipeline(&conn.compute_pipeline);
             compute_pass.set_bind_group(0, &conn.compute_bind_group, &[]);
 
-            let workgroups = self.num_particles.div_ceil(WORKGROUP_SIZE);
+            let workgroups = self.num_particles.div_ceil(self.workgroup_size);
             compute_pass.dispatch_workgroups(workgroups, 1, 1);
         }
 
@@ -1579,18 +1920,28 @@ impl GpuState {
         } else {
             &view
         };
-        let depth_target = if let Some(ref pp) = self.post_process {
-            &pp.depth_view
-        } else {
-            &self.depth_texture
+        // Shadow depth pass from the light, sampled by the billboard pass below
+        if let Some(ref shadow) = self.shadow_state {
+            shadow.render(&mut encoder, &self.particle_buffer, self.num_particles);
+        }
+
+        // With MSAA the scene draws into the multisampled target and resolves
+        // into render_target, which volume rendering and post-processing use
+        let (scene_view, resolve_target) = match self.msaa_view {
+            Some(ref msaa) => (msaa, Some(render_target)),
+            None => (render_target, None),
+        };
+        let depth_target = match self.post_process {
+            Some(ref pp) if self.msaa_view.is_none() => &pp.depth_view,
+            _ => &self.depth_texture,
         };
 
         {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: render_target,
-                    resolve_target: None,
+                    view: scene_view,
+                    resolve_target,
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Clear(wgpu::Color {
                             r: self.background_color.x as f64,
@@ -1609,7 +1960,8 @@ impl GpuState {
                     }),
                     stencil_ops: None,
                 }),
-                timestamp_writes: None,
+                timestamp_writes: self.profiler.as_mut()
+                    .and_then(|p| p.render_timestamps(ProfiledPass::Render)),
                 occlusion_query_set: None,
             });
 
@@ -1617,8 +1969,9 @@ impl GpuState {
             if let Some(ref conn) = self.connection_state {
                 render_pass.set_pipeline(&conn.render_pipeline);
                 render_pass.set_bind_group(0, &conn.render_bind_group, &[]);
-                // Draw up to max_connections line quads (6 vertices each)
-                render_pass.draw(0..6, 0..conn.max_connections);
+                render_pass.set_vertex_buffer(0, conn.data_buffer.slice(..));
+                // Draw up to max_connections lines (plus arrowheads/flow dots when directed)
+                render_pass.draw(0..conn.vertices_per_connection, 0..conn.max_connections);
             }
 
             // Draw spatial grid (debug visualization) if opacity > 0
@@ -1631,29 +1984,40 @@ impl GpuState {
                 }
             }
 
+            // Draw interaction radius spheres (debug visualization)
+            if let Some(ref radius_viz) = self.interaction_radius_viz {
+                render_pass.set_pipeline(radius_viz.pipeline());
+                render_pass.set_bind_group(0, radius_viz.bind_group(), &[]);
+                render_pass.draw(0..6, 0..radius_viz.line_count());
+            }
+
             // Draw trails (behind particles)
             if let Some(ref trail) = self.trail_state {
-                render_pass.set_pipeline(&trail.render_pipeline);
-                render_pass.set_bind_group(0, &trail.render_bind_group, &[]);
-                // Draw all trail points: num_particles * trail_length instances, 6 vertices each
-                let total_trail_instances = self.num_particles * trail.trail_length;
-                render_pass.draw(0..6, 0..total_trail_instances);
+                trail.draw(&mut render_pass);
             }
 
-            // Draw particles on top (or wireframe if configured)
+            // Draw particles on top (or wireframe/mesh if configured)
             if let Some(ref wireframe) = self.wireframe_state {
                 // Render as wireframe meshes
                 render_pass.set_pipeline(wireframe.pipeline());
                 render_pass.set_bind_group(0, wireframe.bind_group(), &[]);
                 // 6 vertices per line quad, total_line_count instances
                 render_pass.draw(0..6, 0..wireframe.total_line_count());
+            } else if let Some(ref mesh) = self.mesh_state {
+                // Render as instanced triangle meshes
+                mesh.draw(&mut render_pass, &self.particle_buffer, self.num_particles);
             } else {
                 // Render as billboards
                 render_pass.set_pipeline(&self.render_pipeline);
                 render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
                 // Bind textures if available
+                let mut next_group = 1;
                 if let Some(ref tex_bind_group) = self.texture_bind_group {
-                    render_pass.set_bind_group(1, tex_bind_group, &[]);
+                    render_pass.set_bind_group(next_group, tex_bind_group, &[]);
+                    next_group += 1;
+                }
+                if let Some(ref shadow) = self.shadow_state {
+                    render_pass.set_bind_group(next_group, &shadow.render_bind_group, &[]);
                 }
                 render_pass.set_vertex_buffer(0, self.particle_buffer.slice(..));
                 render_pass.draw(0..6, 0..self.num_particles);
@@ -1673,7 +2037,8 @@ impl GpuState {
                     },
                 })],
                 depth_stencil_attachment: None, // No depth for fullscreen volume
-                timestamp_writes: None,
+                timestamp_writes: self.profiler.as_mut()
+                    .and_then(|p| p.render_timestamps(ProfiledPass::VolumeRender)),
                 occlusion_query_set: None,
             });
 
@@ -1684,24 +2049,11 @@ impl GpuState {
 
         // Post-processing pass (if enabled)
         if let Some(ref pp) = self.post_process {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Post-Process Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                timestamp_writes: None,
-                occlusion_query_set: None,
-            });
+            pp.apply_bloom(&mut encoder);
 
-            render_pass.set_pipeline(&pp.pipeline);
-            render_pass.set_bind_group(0, &pp.bind_group, &[]);
-            render_pass.draw(0..3, 0..1); // Fullscreen triangle
+            let timestamps = self.profiler.as_mut()
+                .and_then(|p| p.render_timestamps(ProfiledPass::PostProcess));
+            pp.execute(&mut encoder, &view, timestamps);
         }
 
         // Render egui on top of everything (separate render pass for proper blending)
@@ -1730,6 +2082,11 @@ impl GpuState {
             egui.renderer().render(render_pass, &egui_out.paint_jobs, &screen_descriptor);
         }
 
+        // Resolve pass timestamps for this frame
+        if let Some(ref mut profiler) = self.profiler {
+            profiler.resolve(&mut encoder);
+        }
+
         // Copy picked pixel to staging buffer before submit
         self.picking.copy_pixel(&mut encoder);
 
@@ -1741,6 +2098,16 @@ impl GpuState {
         self.queue.submit(std::iter::once(encoder.finish()));
         output.present();
 
+        // Request the alive count without waiting; poll for any earlier request
+        if alive_count_copied {
+            self.map_alive_count();
+        }
+        self.poll_alive_count();
+        if let Some(ref mut profiler) = self.profiler {
+            profiler.after_submit();
+            profiler.poll(&self.device);
+        }
+
         // Read back picked pixel after submit
         self.picking.read_result(&self.device);
 
@@ -1771,79 +2138,24 @@ impl GpuState {
                 label: Some("Render Encoder"),
             });
 
-        // Spatial hashing pass (if enabled)
-        if let Some(ref spatial) = self.spatial {
-            spatial.execute(&mut encoder, &self.queue);
-        }
-
-        // Clear inbox buffer before compute pass
-        if let Some(ref inbox_buf) = self.inbox_buffer {
-            let inbox_size = (self.num_particles as usize) * 16;
-            let zeros = vec![0u8; inbox_size];
-            self.queue.write_buffer(inbox_buf, 0, &zeros);
-        }
-
-        // Clear sub-emitter death buffers before compute pass
-        if let Some(ref se) = self.sub_emitter {
-            se.clear_buffers(&self.queue);
-        }
-
-        // Recreate field bind group each frame (buffers may have been swapped during blur)
-        let field_bind_group = if let (Some(ref field_sys), Some(ref layout)) =
-            (&self.field_system, &self.field_bind_group_layout)
-        {
-            field_sys.create_particle_bind_group(&self.device, layout)
-        } else {
-            None
-        };
+        // Spatial hashing and particle compute pass
+        self.encode_compute(&mut encoder, true);
 
-        // Compute pass
-        {
-            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-                label: Some("Compute Pass"),
-                timestamp_writes: None,
-            });
-
-            compute_pass.set_pipeline(&self.compute_pipeline);
-            compute_pass.set_bind_group(0, &self.compute_bind_group, &[]);
-
-            // Set inbox bind group if enabled (group 1)
-            if let Some(ref inbox_bg) = self.inbox_bind_group {
-                compute_pass.set_bind_group(1, inbox_bg, &[]);
-            } else if self.field_system.is_some() || self.sub_emitter.is_some() {
-                // Need placeholder at group 1 if we have group 2 or 3
-                if let Some(ref empty_bg) = self.empty_bind_group {
-                    compute_pass.set_bind_group(1, empty_bg, &[]);
-                }
-            }
-
-            // Set field bind group if enabled (group 2)
-            if let Some(ref field_bg) = field_bind_group {
-                compute_pass.set_bind_group(2, field_bg, &[]);
-            } else if self.sub_emitter.is_some() {
-                // Need placeholder at group 2 if we have group 3
-                if let Some(ref empty_bg) = self.empty_bind_group {
-                    compute_pass.set_bind_group(2, empty_bg, &[]);
-                }
-            }
-
-            // Set sub-emitter death buffer bind group if enabled (group 3)
-            if let Some(ref se) = self.sub_emitter {
-                compute_pass.set_bind_group(3, &se.death_bind_group, &[]);
-            }
-
-            let workgroups = self.num_particles.div_ceil(WORKGROUP_SIZE);
-            compute_pass.dispatch_workgroups(workgroups, 1, 1);
-        }
+        // Copy alive counter to staging (skipped while a previous read is still mapping)
+        let alive_count_copied = self.copy_alive_count(&mut encoder);
 
         // Sub-emitter spawn pass (spawn children from death events)
         if let Some(ref se) = self.sub_emitter {
-            se.spawn_children(&mut encoder);
+            if !self.compute_paused {
+                se.spawn_children(&mut encoder);
+            }
         }
 
         // Field processing pass (merge deposits, blur/decay, clear write buffer)
         if let Some(ref mut field_sys) = self.field_system {
-            field_sys.process(&self.device, &mut encoder, &self.queue);
+            if !self.compute_paused {
+                field_sys.process(&self.device, &mut encoder, &self.queue);
+            }
 
             // Update volume render bind group after field processing (buffers may have swapped)
             if let Some(ref mut vol) = self.volume_render {
@@ -1851,7 +2163,7 @@ impl GpuState {
 
                 // Get camera matrices for ray reconstruction
                 let aspect = self.config.width as f32 / self.config.height as f32;
-                let proj = Mat4::perspective_rh(std::f32::consts::FRAC_PI_4, aspect, 0.1, 100.0);
+                let proj = self.camera.projection_matrix(aspect);
                 let view = self.camera.view_matrix();
                 let view_proj = proj * view;
                 let inv_view_proj = view_proj.inverse();
@@ -1876,13 +2188,19 @@ impl GpuState {
         if let Some(ref trail) = self.trail_state {
             let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Trail Compute Pass"),
-                timestamp_writes: None,
+                timestamp_writes: self.profiler.as_mut()
+                    .and_then(|p| p.compute_timestamps(ProfiledPass::TrailCompute)),
             });
 
             compute_pass.set_pipeline(&trail.compute_pipeline);
             compute_pass.set_bind_group(0, &trail.compute_bind_group, &[]);
 
-            let workgroups = self.num_particles.div_ceil(WORKGROUP_SIZE);
+            let workgroups = self.num_particles.div_ceil(self.workgroup_size);
+            compute_pass.dispatch_workgroups(workgroups, 1, 1);
+
+            // Interpolate the updated history into ribbon samples
+            compute_pass.set_pipeline(&trail.ribbon_pipeline);
+            compute_pass.set_bind_group(0, &trail.ribbon_bind_group, &[]);
             compute_pass.dispatch_workgroups(workgroups, 1, 1);
         }
 
@@ -1893,13 +2211,14 @@ impl GpuState {
 
             let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Connection Compute Pass"),
-                timestamp_writes: None,
+                timestamp_writes: self.profiler.as_mut()
+                    .and_then(|p| p.compute_timestamps(ProfiledPass::ConnectionCompute)),
             });
 
             compute_pass.set_pipeline(&conn.compute_pipeline);
             compute_pass.set_bind_group(0, &conn.compute_bind_group, &[]);
 
-            let workgroups = self.num_particles.div_ceil(WORKGROUP_SIZE);
+            let workgroups = self.num_particles.div_ceil(self.workgroup_size);
             compute_pass.dispatch_workgroups(workgroups, 1, 1);
         }
 
@@ -1919,18 +2238,28 @@ impl GpuState {
         } else {
             &view
         };
-        let depth_target = if let Some(ref pp) = self.post_process {
-            &pp.depth_view
-        } else {
-            &self.depth_texture
+        // Shadow depth pass from the light, sampled by the billboard pass below
+        if let Some(ref shadow) = self.shadow_state {
+            shadow.render(&mut encoder, &self.particle_buffer, self.num_particles);
+        }
+
+        // With MSAA the scene draws into the multisampled target and resolves
+        // into render_target, which volume rendering and post-processing use
+        let (scene_view, resolve_target) = match self.msaa_view {
+            Some(ref msaa) => (msaa, Some(render_target)),
+            None => (render_target, None),
+        };
+        let depth_target = match self.post_process {
+            Some(ref pp) if self.msaa_view.is_none() => &pp.depth_view,
+            _ => &self.depth_texture,
         };
 
         {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: render_target,
-                    resolve_target: None,
+                    view: scene_view,
+                    resolve_target,
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Clear(wgpu::Color {
                             r: self.background_color.x as f64,
@@ -1949,7 +2278,8 @@ impl GpuState {
                     }),
                     stencil_ops: None,
                 }),
-                timestamp_writes: None,
+                timestamp_writes: self.profiler.as_mut()
+                    .and_then(|p| p.render_timestamps(ProfiledPass::Render)),
                 occlusion_query_set: None,
             });
 
@@ -1957,8 +2287,9 @@ impl GpuState {
             if let Some(ref conn) = self.connection_state {
                 render_pass.set_pipeline(&conn.render_pipeline);
                 render_pass.set_bind_group(0, &conn.render_bind_group, &[]);
-                // Draw up to max_connections line quads (6 vertices each)
-                render_pass.draw(0..6, 0..conn.max_connections);
+                render_pass.set_vertex_buffer(0, conn.data_buffer.slice(..));
+                // Draw up to max_connections lines (plus arrowheads/flow dots when directed)
+                render_pass.draw(0..conn.vertices_per_connection, 0..conn.max_connections);
             }
 
             // Draw spatial grid (debug visualization) if opacity > 0
@@ -1971,29 +2302,40 @@ impl GpuState {
                 }
             }
 
+            // Draw interaction radius spheres (debug visualization)
+            if let Some(ref radius_viz) = self.interaction_radius_viz {
+                render_pass.set_pipeline(radius_viz.pipeline());
+                render_pass.set_bind_group(0, radius_viz.bind_group(), &[]);
+                render_pass.draw(0..6, 0..radius_viz.line_count());
+            }
+
             // Draw trails (behind particles)
             if let Some(ref trail) = self.trail_state {
-                render_pass.set_pipeline(&trail.render_pipeline);
-                render_pass.set_bind_group(0, &trail.render_bind_group, &[]);
-                // Draw all trail points: num_particles * trail_length instances, 6 vertices each
-                let total_trail_instances = self.num_particles * trail.trail_length;
-                render_pass.draw(0..6, 0..total_trail_instances);
+                trail.draw(&mut render_pass);
             }
 
-            // Draw particles on top (or wireframe if configured)
+            // Draw particles on top (or wireframe/mesh if configured)
             if let Some(ref wireframe) = self.wireframe_state {
                 // Render as wireframe meshes
                 render_pass.set_pipeline(wireframe.pipeline());
                 render_pass.set_bind_group(0, wireframe.bind_group(), &[]);
                 // 6 vertices per line quad, total_line_count instances
                 render_pass.draw(0..6, 0..wireframe.total_line_count());
+            } else if let Some(ref mesh) = self.mesh_state {
+                // Render as instanced triangle meshes
+                mesh.draw(&mut render_pass, &self.particle_buffer, self.num_particles);
             } else {
                 // Render as billboards
                 render_pass.set_pipeline(&self.render_pipeline);
                 render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
                 // Bind textures if available
+                let mut next_group = 1;
                 if let Some(ref tex_bind_group) = self.texture_bind_group {
-                    render_pass.set_bind_group(1, tex_bind_group, &[]);
+                    render_pass.set_bind_group(next_group, tex_bind_group, &[]);
+                    next_group += 1;
+                }
+                if let Some(ref shadow) = self.shadow_state {
+                    render_pass.set_bind_group(next_group, &shadow.render_bind_group, &[]);
                 }
                 render_pass.set_vertex_buffer(0, self.particle_buffer.slice(..));
                 render_pass.draw(0..6, 0..self.num_particles);
@@ -2013,7 +2355,8 @@ impl GpuState {
                     },
                 })],
                 depth_stencil_attachment: None, // No depth for fullscreen volume
-                timestamp_writes: None,
+                timestamp_writes: self.profiler.as_mut()
+                    .and_then(|p| p.render_timestamps(ProfiledPass::VolumeRender)),
                 occlusion_query_set: None,
             });
 
@@ -2024,24 +2367,16 @@ impl GpuState {
 
         // Post-processing pass (if enabled)
         if let Some(ref pp) = self.post_process {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Post-Process Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                timestamp_writes: None,
-                occlusion_query_set: None,
-            });
+            pp.apply_bloom(&mut encoder);
 
-            render_pass.set_pipeline(&pp.pipeline);
-            render_pass.set_bind_group(0, &pp.bind_group, &[]);
-            render_pass.draw(0..3, 0..1); // Fullscreen triangle
+            let timestamps = self.profiler.as_mut()
+                .and_then(|p| p.render_timestamps(ProfiledPass::PostProcess));
+            pp.execute(&mut encoder, &view, timestamps);
+        }
+
+        // Resolve pass timestamps for this frame
+        if let Some(ref mut profiler) = self.profiler {
+            profiler.resolve(&mut encoder);
         }
 
         // Copy picked pixel to staging buffer before submit
@@ -2055,6 +2390,16 @@ impl GpuState {
         self.queue.submit(std::iter::once(encoder.finish()));
         output.present();
 
+        // Request the alive count without waiting; poll for any earlier request
+        if alive_count_copied {
+            self.map_alive_count();
+        }
+        self.poll_alive_count();
+        if let Some(ref mut profiler) = self.profiler {
+            profiler.after_submit();
+            profiler.poll(&self.device);
+        }
+
         // Read back picked pixel after submit
         self.picking.read_result(&self.device);
 
@@ -2065,9 +2410,51 @@ impl GpuState {
     }
 }
 
+/// Per-instance vertex attributes the billboard render shader reads from a particle.
+///
+/// Locations: 0 position, 1 color (if the particle has one), 2 alive, 3 scale,
+/// 4 age. The derive macros always inject `age` directly before `alive`.
+fn particle_vertex_attributes(
+    color_offset: Option<u32>,
+    alive_offset: u32,
+    scale_offset: u32,
+) -> Vec<wgpu::VertexAttribute> {
+    let mut attributes = vec![wgpu::VertexAttribute {
+        offset: 0,
+        shader_location: 0,
+        format: wgpu::VertexFormat::Float32x3, // position
+    }];
+    if let Some(offset) = color_offset {
+        attributes.push(wgpu::VertexAttribute {
+            offset: offset as wgpu::BufferAddress,
+            shader_location: 1,
+            format: wgpu::VertexFormat::Float32x3, // color
+        });
+    }
+    attributes.extend([
+        wgpu::VertexAttribute {
+            offset: alive_offset as wgpu::BufferAddress,
+            shader_location: 2,
+            format: wgpu::VertexFormat::Uint32, // alive
+        },
+        wgpu::VertexAttribute {
+            offset: scale_offset as wgpu::BufferAddress,
+            shader_location: 3,
+            format: wgpu::VertexFormat::Float32, // scale
+        },
+        wgpu::VertexAttribute {
+            offset: (alive_offset - 4) as wgpu::BufferAddress,
+            shader_location: 4,
+            format: wgpu::VertexFormat::Float32, // age
+        },
+    ]);
+    attributes
+}
+
 fn create_depth_texture(
     device: &wgpu::Device,
     config: &wgpu::SurfaceConfiguration,
+    sample_count: u32,
 ) -> wgpu::TextureView {
     let texture = device.create_texture(&wgpu::TextureDescriptor {
         label: Some("Depth Texture"),
@@ -2077,7 +2464,7 @@ fn create_depth_texture(
             depth_or_array_layers: 1,
         },
         mip_level_count: 1,
-        sample_count: 1,
+        sample_count,
         dimension: wgpu::TextureDimension::D2,
         format: DEPTH_FORMAT,
         usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
@@ -2085,3 +2472,384 @@ fn create_depth_texture(
     });
     texture.create_view(&wgpu::TextureViewDescriptor::default())
 }
+
+/// Create the multisampled color target for the scene pass (None when `sample_count` is 1).
+fn create_msaa_view(
+    device: &wgpu::Device,
+    config: &wgpu::SurfaceConfiguration,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+) -> Option<wgpu::TextureView> {
+    if sample_count <= 1 {
+        return None;
+    }
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("MSAA Color Texture"),
+        size: wgpu::Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    Some(texture.create_view(&wgpu::TextureViewDescriptor::default()))
+}
+
+/// Split a 64-bit seed into the `[low, high]` halves stored in the uniforms.
+fn split_seed(seed: u64) -> [u32; 2] {
+    [seed as u32, (seed >> 32) as u32]
+}
+
+/// Size of the counter buffer: the alive count, then one spawn counter per
+/// emitter (at least one, since the shader's runtime array can't be empty).
+fn counter_buffer_size(emitter_count: usize) -> u64 {
+    4 * (1 + emitter_count.max(1)) as u64
+}
+
+/// Size of the uniform buffer: base uniforms padded to 16 bytes, then the
+/// custom uniforms, padded again to 16 bytes.
+fn uniform_buffer_size(custom_uniform_size: usize) -> usize {
+    let base_size = std::mem::size_of::<Uniforms>();
+    let padded_base_size = (base_size + 15) & !15;
+    ((padded_base_size + custom_uniform_size) + 15) & !15
+}
+
+/// Pack base and custom uniforms into the bytes written to the uniform buffer.
+///
+/// Custom uniforms start at a 16-byte boundary so vec3/vec4 values are
+/// properly aligned. Without custom uniforms only the base bytes are returned.
+fn uniform_bytes(uniforms: &Uniforms, custom_uniform_bytes: Option<&[u8]>, buffer_size: usize) -> Vec<u8> {
+    let mut bytes = bytemuck::bytes_of(uniforms).to_vec();
+    if let Some(custom_bytes) = custom_uniform_bytes {
+        bytes.resize((bytes.len() + 15) & !15, 0);
+        bytes.extend_from_slice(custom_bytes);
+        bytes.resize(buffer_size, 0);
+    }
+    bytes
+}
+
+/// Copy `source` into `staging` and block until the bytes can be read back.
+fn read_buffer_sync(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    source: &wgpu::Buffer,
+    staging: &wgpu::Buffer,
+) -> Result<Vec<u8>, GpuError> {
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Readback Encoder"),
+    });
+    encoder.copy_buffer_to_buffer(source, 0, staging, 0, staging.size());
+    queue.submit(std::iter::once(encoder.finish()));
+
+    // Map and read
+    let buffer_slice = staging.slice(..);
+    let (tx, rx) = std::sync::mpsc::channel();
+    buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+        // Ignore send errors - receiver may have been dropped
+        let _ = tx.send(result);
+    });
+
+    // Wait for mapping to complete
+    device.poll(wgpu::Maintain::Wait);
+    rx.recv()
+        .map_err(|_| GpuError::BufferMapping("Channel receive failed".to_string()))?
+        .map_err(|e| GpuError::BufferMapping(format!("Buffer mapping failed: {}", e)))?;
+
+    // Copy data
+    let data = buffer_slice.get_mapped_range();
+    let result = data.to_vec();
+    drop(data);
+    staging.unmap();
+
+    Ok(result)
+}
+
+/// Create the main compute bind group layout (group 0).
+///
+/// Bindings: particles (0), uniforms (1), the spatial hashing buffers (2-5,
+/// only with neighbors) and the alive counter (6).
+fn create_compute_bind_group_layout(device: &wgpu::Device, with_spatial: bool) -> wgpu::BindGroupLayout {
+    let buffer_entry = |binding, ty| wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::Buffer {
+            ty,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    };
+    let storage = |read_only| wgpu::BufferBindingType::Storage { read_only };
+
+    let mut entries = vec![
+        buffer_entry(0, storage(false)),
+        buffer_entry(1, wgpu::BufferBindingType::Uniform),
+    ];
+    if with_spatial {
+        entries.extend([
+            buffer_entry(2, storage(true)),
+            buffer_entry(3, storage(true)),
+            buffer_entry(4, storage(true)),
+            buffer_entry(5, wgpu::BufferBindingType::Uniform),
+        ]);
+    }
+    entries.push(buffer_entry(6, storage(false)));
+
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some(if with_spatial {
+            "Compute Bind Group Layout (with neighbors)"
+        } else {
+            "Compute Bind Group Layout"
+        }),
+        entries: &entries,
+    })
+}
+
+/// Create the inbox bind group (group 1) for particle communication.
+fn create_inbox_bind_group(
+    device: &wgpu::Device,
+    inbox_buffer: &wgpu::Buffer,
+) -> (wgpu::BindGroupLayout, wgpu::BindGroup) {
+    let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Inbox Bind Group Layout"),
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: false },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }],
+    });
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Inbox Bind Group"),
+        layout: &layout,
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: inbox_buffer.as_entire_binding(),
+        }],
+    });
+
+    (layout, bind_group)
+}
+
+/// Build the compute pipeline layout with optional inbox, field, and
+/// sub-emitter bind groups.
+///
+/// - Group 0: particles/uniforms/spatial
+/// - Group 1: inbox (if enabled)
+/// - Group 2: fields (if enabled)
+/// - Group 3: sub-emitter death buffers (if enabled)
+///
+/// Also returns the empty bind group used to fill gaps, if one is needed.
+fn create_compute_pipeline_layout(
+    device: &wgpu::Device,
+    compute_layout: &wgpu::BindGroupLayout,
+    inbox_layout: Option<&wgpu::BindGroupLayout>,
+    field_layout: Option<&wgpu::BindGroupLayout>,
+    sub_emitter: Option<&SubEmitterGpu>,
+) -> (wgpu::PipelineLayout, Option<wgpu::BindGroup>) {
+    // Create empty layout/bind group for gaps
+    let empty_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Empty Bind Group Layout"),
+        entries: &[],
+    });
+    let empty_bg = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Empty Bind Group"),
+        layout: &empty_layout,
+        entries: &[],
+    });
+
+    // Build layouts vec dynamically
+    let mut layouts: Vec<&wgpu::BindGroupLayout> = vec![compute_layout];
+
+    // Group 1: inbox or empty
+    if let Some(inbox_layout) = inbox_layout {
+        layouts.push(inbox_layout);
+    } else if field_layout.is_some() || sub_emitter.is_some() {
+        // Need placeholder at group 1 if we have group 2 or 3
+        layouts.push(&empty_layout);
+    }
+
+    // Group 2: fields or empty
+    if let Some(field_layout) = field_layout {
+        layouts.push(field_layout);
+    } else if sub_emitter.is_some() {
+        // Need placeholder at group 2 if we have group 3
+        layouts.push(&empty_layout);
+    }
+
+    // Group 3: sub-emitter death buffers
+    if let Some(se) = sub_emitter {
+        layouts.push(&se.death_bind_group_layout);
+    }
+
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Compute Pipeline Layout"),
+        bind_group_layouts: &layouts,
+        push_constant_ranges: &[],
+    });
+
+    // Only keep empty_bg if we need it
+    let keep_empty = (inbox_layout.is_none() && (field_layout.is_some() || sub_emitter.is_some()))
+        || (field_layout.is_none() && sub_emitter.is_some());
+    (layout, if keep_empty { Some(empty_bg) } else { None })
+}
+
+/// Set the compute pass bind groups laid out by [`create_compute_pipeline_layout`].
+fn set_compute_bind_groups(
+    compute_pass: &mut wgpu::ComputePass,
+    compute_bind_group: &wgpu::BindGroup,
+    inbox_bind_group: Option<&wgpu::BindGroup>,
+    field_bind_group: Option<&wgpu::BindGroup>,
+    has_fields: bool,
+    sub_emitter: Option<&SubEmitterGpu>,
+    empty_bind_group: Option<&wgpu::BindGroup>,
+) {
+    compute_pass.set_bind_group(0, compute_bind_group, &[]);
+
+    // Set inbox bind group if enabled (group 1)
+    if let Some(inbox_bg) = inbox_bind_group {
+        compute_pass.set_bind_group(1, inbox_bg, &[]);
+    } else if has_fields || sub_emitter.is_some() {
+        // Need placeholder at group 1 if we have group 2 or 3
+        if let Some(empty_bg) = empty_bind_group {
+            compute_pass.set_bind_group(1, empty_bg, &[]);
+        }
+    }
+
+    // Set field bind group if enabled (group 2)
+    if let Some(field_bg) = field_bind_group {
+        compute_pass.set_bind_group(2, field_bg, &[]);
+    } else if sub_emitter.is_some() {
+        // Need placeholder at group 2 if we have group 3
+        if let Some(empty_bg) = empty_bind_group {
+            compute_pass.set_bind_group(2, empty_bg, &[]);
+        }
+    }
+
+    // Set sub-emitter death buffer bind group if enabled (group 3)
+    if let Some(se) = sub_emitter {
+        compute_pass.set_bind_group(3, &se.death_bind_group, &[]);
+    }
+}
+
+/// Create the main compute bind group (group 0).
+///
+/// With spatial hashing the spatial buffers are bound at 2-5; the alive
+/// counter is always at binding 6.
+fn create_compute_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    particle_buffer: &wgpu::Buffer,
+    uniform_buffer: &wgpu::Buffer,
+    spatial: Option<&SpatialGpu>,
+    alive_count_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    let mut entries = vec![
+        wgpu::BindGroupEntry {
+            binding: 0,
+            resource: particle_buffer.as_entire_binding(),
+        },
+        wgpu::BindGroupEntry {
+            binding: 1,
+            resource: uniform_buffer.as_entire_binding(),
+        },
+    ];
+    if let Some(spatial) = spatial {
+        entries.extend([
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: spatial.particle_indices_a.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 3,
+                resource: spatial.cell_start.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 4,
+                resource: spatial.cell_end.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 5,
+                resource: spatial.spatial_params_buffer.as_entire_binding(),
+            },
+        ]);
+    }
+    entries.push(wgpu::BindGroupEntry {
+        binding: 6,
+        resource: alive_count_buffer.as_entire_binding(),
+    });
+
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some(if spatial.is_some() {
+            "Compute Bind Group (with neighbors)"
+        } else {
+            "Compute Bind Group"
+        }),
+        layout,
+        entries: &entries,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_workgroup_size() {
+        let limits = |invocations, size_x| wgpu::Limits {
+            max_compute_invocations_per_workgroup: invocations,
+            max_compute_workgroup_size_x: size_x,
+            ..wgpu::Limits::default()
+        };
+
+        assert_eq!(select_workgroup_size(&limits(1024, 1024)), 256);
+        assert_eq!(select_workgroup_size(&limits(256, 256)), 256);
+        assert_eq!(select_workgroup_size(&limits(192, 256)), 128);
+        assert_eq!(select_workgroup_size(&limits(64, 256)), 64);
+        assert_eq!(select_workgroup_size(&limits(0, 256)), 1);
+
+        // The X dimension limit applies too, since dispatches are 1D
+        assert_eq!(select_workgroup_size(&limits(256, 128)), 128);
+        assert_eq!(select_workgroup_size(&limits(1024, 100)), 64);
+
+        let low = wgpu::Limits {
+            max_compute_invocations_per_workgroup: 128,
+            max_compute_workgroup_size_x: 128,
+            ..wgpu::Limits::downlevel_defaults()
+        };
+        assert_eq!(select_workgroup_size(&low), 128);
+    }
+
+    #[test]
+    fn test_workgroup_size_constants_only_for_override() {
+        let constants = workgroup_size_constants("override WORKGROUP_SIZE: u32 = 256u;", 64);
+        assert_eq!(constants.get("WORKGROUP_SIZE"), Some(&64.0));
+
+        let constants = workgroup_size_constants("@compute @workgroup_size(256)", 64);
+        assert!(constants.is_empty());
+    }
+
+    #[test]
+    fn test_uniform_fields_wgsl_matches_uniforms() {
+        // Custom uniforms are written right after the base uniforms
+        let source = format!("struct Uniforms {{\n{UNIFORM_FIELDS_WGSL}\n    custom: f32,\n}}");
+        let module = naga::front::wgsl::parse_str(&source).unwrap();
+        let (_, ty) = module.types.iter().find(|(_, ty)| ty.name.as_deref() == Some("Uniforms")).unwrap();
+        let naga::TypeInner::Struct { members, .. } = &ty.inner else {
+            panic!("Uniforms is not a struct");
+        };
+        let custom = members.iter().find(|m| m.name.as_deref() == Some("custom")).unwrap();
+        assert_eq!(std::mem::size_of::<Uniforms>() % 16, 0);
+        assert_eq!(custom.offset as usize, std::mem::size_of::<Uniforms>());
+    }
+}
@@ -0,0 +1,163 @@
+//! On-disk pipeline cache to skip shader compilation on later runs.
+//!
+//! Only backends that support [`wgpu::Features::PIPELINE_CACHE`] (currently
+//! Vulkan) produce cache data. The cache file name is a hash of the adapter
+//! and both shader sources, so editing a shader or switching GPUs/drivers
+//! simply misses and writes a fresh file. Only the most recently written
+//! [`MAX_CACHE_FILES`] files are kept.
+
+use std::path::{Path, PathBuf};
+
+/// Number of cache files kept in the cache directory; older ones are deleted on save.
+const MAX_CACHE_FILES: usize = 8;
+
+/// 64-bit FNV-1a hash of several byte strings.
+///
+/// Unlike `DefaultHasher`, the result is stable across Rust versions and
+/// runs, so cache file names stay valid. Each part is followed by a zero
+/// byte so ("ab", "c") and ("a", "bc") hash differently.
+fn fnv1a_64<'a>(parts: impl IntoIterator<Item = &'a [u8]>) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = OFFSET_BASIS;
+    for part in parts {
+        for &byte in part.iter().chain(&[0]) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    hash
+}
+
+/// Delete all but the `keep` most recently modified `.bin` files in `dir`.
+fn prune_cache_dir(dir: &Path, keep: usize) -> std::io::Result<()> {
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = std::fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "bin"))
+        .filter_map(|path| Some((std::fs::metadata(&path).ok()?.modified().ok()?, path)))
+        .collect();
+
+    // Newest first
+    files.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    for (_, path) in files.into_iter().skip(keep) {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// A wgpu pipeline cache backed by a file in the cache directory.
+pub(crate) struct PipelineCacheFile {
+    path: PathBuf,
+    pub(crate) cache: wgpu::PipelineCache,
+}
+
+impl PipelineCacheFile {
+    /// Whether the device was created with pipeline cache support.
+    pub(crate) fn supported(adapter: &wgpu::Adapter) -> bool {
+        adapter.features().contains(wgpu::Features::PIPELINE_CACHE)
+            && wgpu::util::pipeline_cache_key(&adapter.get_info()).is_some()
+    }
+
+    /// Open (or start) the cache for this adapter and shader pair.
+    ///
+    /// Returns `None` if the backend has no pipeline cache support. The
+    /// device must have been created with [`wgpu::Features::PIPELINE_CACHE`].
+    pub(crate) fn load(
+        device: &wgpu::Device,
+        adapter_info: &wgpu::AdapterInfo,
+        cache_dir: &Path,
+        compute_shader_src: &str,
+        render_shader_src: &str,
+    ) -> Option<Self> {
+        let adapter_key = wgpu::util::pipeline_cache_key(adapter_info)?;
+
+        let hash = fnv1a_64([
+            adapter_key.as_bytes(),
+            adapter_info.driver.as_bytes(),
+            adapter_info.driver_info.as_bytes(),
+            compute_shader_src.as_bytes(),
+            render_shader_src.as_bytes(),
+        ]);
+        let path = cache_dir.join(format!("{:016x}.bin", hash));
+
+        let data = std::fs::read(&path).ok();
+
+        // SAFETY: the data was written by `save` from `PipelineCache::get_data`
+        // for the same adapter (the adapter is part of the file name), and
+        // `fallback` makes wgpu discard it if the driver rejects it anyway.
+        let cache = unsafe {
+            device.create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                label: Some("Pipeline Cache"),
+                data: data.as_deref(),
+                fallback: true,
+            })
+        };
+
+        Some(Self { path, cache })
+    }
+
+    /// Write the cache data to disk.
+    ///
+    /// Failures are reported but not fatal - the next run just compiles again.
+    pub(crate) fn save(&self) {
+        let Some(data) = self.cache.get_data() else {
+            return;
+        };
+
+        let result = (|| {
+            if let Some(dir) = self.path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            // Write to a temporary file first so a crash never leaves a truncated cache
+            let temp_path = self.path.with_extension("tmp");
+            std::fs::write(&temp_path, &data)?;
+            std::fs::rename(&temp_path, &self.path)?;
+            match self.path.parent() {
+                Some(dir) => prune_cache_dir(dir, MAX_CACHE_FILES),
+                None => Ok(()),
+            }
+        })();
+
+        if let Err(e) = result {
+            eprintln!("Failed to write pipeline cache {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fnv1a_is_stable() {
+        assert_eq!(fnv1a_64([]), 0xcbf2_9ce4_8422_2325);
+        // FNV-1a of "rdpe\0"
+        assert_eq!(fnv1a_64([&b"rdpe"[..]]), 0x5583_bef9_3105_502c);
+        assert_ne!(fnv1a_64([&b"ab"[..], b"c"]), fnv1a_64([&b"a"[..], b"bc"]));
+    }
+
+    #[test]
+    fn test_prune_keeps_newest_files() {
+        let dir = std::env::temp_dir().join(format!("rdpe_pipeline_cache_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let start = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        for i in 0..5u64 {
+            let file = std::fs::File::create(dir.join(format!("{i}.bin"))).unwrap();
+            file.set_modified(start + std::time::Duration::from_secs(i)).unwrap();
+        }
+        std::fs::write(dir.join("other.txt"), b"kept").unwrap();
+
+        prune_cache_dir(&dir, 2).unwrap();
+        let mut names: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(names, ["3.bin", "4.bin", "other.txt"]);
+    }
+}
@@ -1,9 +1,29 @@
 //! Post-processing effects for screen-space rendering.
 //!
-//! Renders the scene to an offscreen texture, then applies a custom
-//! fragment shader as a fullscreen pass.
+//! Renders the scene to an offscreen texture, optionally adds bloom, then
+//! applies a chain of custom fragment shaders as fullscreen passes. With HDR
+//! enabled the offscreen texture is `Rgba16Float` and the final pass
+//! tone-maps its output.
 
-use super::DEPTH_FORMAT;
+use super::bloom::BloomState;
+use super::{DEPTH_FORMAT, UNIFORM_FIELDS_WGSL};
+use crate::visuals::{BloomConfig, ToneMapMode};
+
+/// Format of the offscreen texture when HDR rendering is enabled.
+pub(crate) const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// Post-process shader used when only bloom is enabled.
+pub(crate) const PASSTHROUGH_SHADER: &str =
+    "    return textureSample(scene, scene_sampler, in.uv);";
+
+/// Format of the intermediate textures between chained post-process passes.
+const CHAIN_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// One fullscreen shader pass in the post-process chain.
+struct PostProcessPass {
+    pipeline: wgpu::RenderPipeline,
+    bind_group: wgpu::BindGroup,
+}
 
 /// GPU resources for post-processing.
 #[allow(dead_code)]
@@ -16,60 +36,105 @@ pub struct PostProcessState {
     pub depth_texture: wgpu::Texture,
     /// View into the depth buffer.
     pub depth_view: wgpu::TextureView,
-    /// Pipeline for the post-process pass.
-    pub pipeline: wgpu::RenderPipeline,
-    /// Bind group for the post-process shader.
-    pub bind_group: wgpu::BindGroup,
+    /// Shader passes, run in order; the last one writes to the surface.
+    passes: Vec<PostProcessPass>,
+    /// Ping-pong textures between passes (empty for a single pass).
+    intermediates: Vec<(wgpu::Texture, wgpu::TextureView)>,
     /// Bind group layout (needed for recreation on resize).
     pub bind_group_layout: wgpu::BindGroupLayout,
     /// Sampler for the scene texture.
     pub sampler: wgpu::Sampler,
+    /// Bloom passes run on the offscreen texture before the custom shader.
+    pub bloom: Option<BloomState>,
+    /// Format of the offscreen texture (surface format, or HDR).
+    pub scene_format: wgpu::TextureFormat,
 }
 
 impl PostProcessState {
-    /// Create a new post-processing system.
+    /// Create a new post-processing system with a single shader.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         device: &wgpu::Device,
         uniform_buffer: &wgpu::Buffer,
         shader_code: &str,
         custom_uniform_fields: &str,
+        bloom: Option<&BloomConfig>,
+        tone_map: Option<ToneMapMode>,
         width: u32,
         height: u32,
+        scene_format: wgpu::TextureFormat,
         surface_format: wgpu::TextureFormat,
     ) -> Self {
-        // Create offscreen render target
-        let texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Offscreen Texture"),
-            size: wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: surface_format,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
-            view_formats: &[],
-        });
-        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-
-        // Offscreen depth buffer
-        let depth_texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Offscreen Depth Texture"),
-            size: wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: DEPTH_FORMAT,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            view_formats: &[],
-        });
-        let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        Self::new_chain(
+            device,
+            uniform_buffer,
+            &[shader_code],
+            custom_uniform_fields,
+            bloom,
+            tone_map,
+            width,
+            height,
+            scene_format,
+            surface_format,
+        )
+    }
+
+    /// Create a post-processing system running `shaders` one after another.
+    ///
+    /// Each pass samples the previous pass's output as `scene`; passes
+    /// between the first and last write to alternating `Rgba16Float`
+    /// textures, so HDR values survive until the final pass, which writes
+    /// to the surface and applies tone mapping. Every pass sees the same
+    /// uniforms. An empty `shaders` list runs a single passthrough pass.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_chain(
+        device: &wgpu::Device,
+        uniform_buffer: &wgpu::Buffer,
+        shaders: &[&str],
+        custom_uniform_fields: &str,
+        bloom: Option<&BloomConfig>,
+        tone_map: Option<ToneMapMode>,
+        width: u32,
+        height: u32,
+        scene_format: wgpu::TextureFormat,
+        surface_format: wgpu::TextureFormat,
+    ) -> Self {
+        Self::with_uniforms(
+            device,
+            uniform_buffer,
+            shaders,
+            &uniforms_struct(custom_uniform_fields),
+            bloom,
+            tone_map,
+            width,
+            height,
+            scene_format,
+            surface_format,
+        )
+    }
+
+    /// Like [`new_chain`](Self::new_chain), for a uniform buffer with its own layout.
+    ///
+    /// `uniforms_wgsl` declares `struct Uniforms` (and any structs it uses)
+    /// matching `uniform_buffer`; the passes see it as `uniforms`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_uniforms(
+        device: &wgpu::Device,
+        uniform_buffer: &wgpu::Buffer,
+        shaders: &[&str],
+        uniforms_wgsl: &str,
+        bloom: Option<&BloomConfig>,
+        tone_map: Option<ToneMapMode>,
+        width: u32,
+        height: u32,
+        scene_format: wgpu::TextureFormat,
+        surface_format: wgpu::TextureFormat,
+    ) -> Self {
+        let shaders = if shaders.is_empty() { &[PASSTHROUGH_SHADER][..] } else { shaders };
+
+        let (texture, view) = create_color_texture(device, "Offscreen Texture", scene_format, width, height);
+        let (depth_texture, depth_view) = create_depth_texture(device, width, height);
+        let intermediates = create_intermediates(device, shaders.len(), width, height);
 
         // Sampler for the scene texture
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
@@ -83,13 +148,6 @@ impl PostProcessState {
             ..Default::default()
         });
 
-        // Create shader and pipeline
-        let shader_src = generate_shader(shader_code, custom_uniform_fields);
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Post-Process Shader"),
-            source: wgpu::ShaderSource::Wgsl(shader_src.into()),
-        });
-
         // Bind group layout
         let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("Post-Process Bind Group Layout"),
@@ -123,58 +181,66 @@ impl PostProcessState {
             ],
         });
 
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Post-Process Bind Group"),
-            layout: &bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&sampler),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: uniform_buffer.as_entire_binding(),
-                },
-            ],
-        });
-
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Post-Process Pipeline Layout"),
             bind_group_layouts: &[&bind_group_layout],
             push_constant_ranges: &[],
         });
 
-        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Post-Process Pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: Some("vs_main"),
-                buffers: &[],
-                compilation_options: Default::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: surface_format,
-                    blend: None,
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: Default::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                ..Default::default()
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-            cache: None,
+        let last = shaders.len() - 1;
+        let passes = shaders
+            .iter()
+            .enumerate()
+            .map(|(i, shader_code)| {
+                // Only the final pass writes to the display, so only it tone-maps
+                let (pass_tone_map, target_format) = if i == last {
+                    (tone_map, surface_format)
+                } else {
+                    (None, CHAIN_FORMAT)
+                };
+                let shader_src = generate_shader(shader_code, uniforms_wgsl, pass_tone_map);
+                let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: Some("Post-Process Shader"),
+                    source: wgpu::ShaderSource::Wgsl(shader_src.into()),
+                });
+
+                let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some("Post-Process Pipeline"),
+                    layout: Some(&pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module: &shader,
+                        entry_point: Some("vs_main"),
+                        buffers: &[],
+                        compilation_options: Default::default(),
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: &shader,
+                        entry_point: Some("fs_main"),
+                        targets: &[Some(wgpu::ColorTargetState {
+                            format: target_format,
+                            blend: None,
+                            write_mask: wgpu::ColorWrites::ALL,
+                        })],
+                        compilation_options: Default::default(),
+                    }),
+                    primitive: wgpu::PrimitiveState {
+                        topology: wgpu::PrimitiveTopology::TriangleList,
+                        ..Default::default()
+                    },
+                    depth_stencil: None,
+                    multisample: wgpu::MultisampleState::default(),
+                    multiview: None,
+                    cache: None,
+                });
+
+                let input = pass_input(&view, &intermediates, i);
+                let bind_group = create_bind_group(device, &bind_group_layout, input, &sampler, uniform_buffer);
+                PostProcessPass { pipeline, bind_group }
+            })
+            .collect();
+
+        let bloom = bloom.map(|config| {
+            BloomState::new(device, config, &view, width, height, scene_format)
         });
 
         Self {
@@ -182,87 +248,275 @@ impl PostProcessState {
             view,
             depth_texture,
             depth_view,
-            pipeline,
-            bind_group,
+            passes,
+            intermediates,
             bind_group_layout,
             sampler,
+            bloom,
+            scene_format,
         }
     }
 
-    /// Recreate textures and bind group after window resize.
+    /// Record the bloom passes, if enabled, onto the offscreen texture.
+    ///
+    /// Call after the scene has been rendered and before the post-process pass.
+    pub fn apply_bloom(&self, encoder: &mut wgpu::CommandEncoder) {
+        if let Some(ref bloom) = self.bloom {
+            bloom.execute(encoder, &self.view);
+        }
+    }
+
+    /// Record every shader pass, the last one writing to `target`.
+    ///
+    /// `timestamps` brackets the whole chain: the first pass writes the
+    /// beginning timestamp and the last pass the end one.
+    pub fn execute(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        timestamps: Option<wgpu::RenderPassTimestampWrites<'_>>,
+    ) {
+        let last = self.passes.len() - 1;
+        for i in 0..=last {
+            let view = match pass_output_slot(i, self.passes.len()) {
+                Some(slot) => &self.intermediates[slot].1,
+                None => target,
+            };
+            let timestamp_writes = timestamps.as_ref().map(|t| wgpu::RenderPassTimestampWrites {
+                query_set: t.query_set,
+                beginning_of_pass_write_index: t.beginning_of_pass_write_index.filter(|_| i == 0),
+                end_of_pass_write_index: t.end_of_pass_write_index.filter(|_| i == last),
+            });
+            self.record_pass(encoder, i, view, timestamp_writes);
+        }
+    }
+
+    /// Record every shader pass but the last.
+    ///
+    /// For targets owned by another renderer (such as an egui paint
+    /// callback): render the scene into [`view`](Self::view), call this,
+    /// then [`draw_final`](Self::draw_final) inside the target's render pass.
+    pub fn execute_intermediate(&self, encoder: &mut wgpu::CommandEncoder) {
+        let pass_count = self.passes.len();
+        for i in 0..pass_count - 1 {
+            let slot = pass_output_slot(i, pass_count).expect("only the last pass writes the target");
+            self.record_pass(encoder, i, &self.intermediates[slot].1, None);
+        }
+    }
+
+    /// Draw the last shader pass into an already open render pass.
+    pub fn draw_final(&self, render_pass: &mut wgpu::RenderPass<'_>) {
+        let pass = self.passes.last().expect("chain has at least one pass");
+        render_pass.set_pipeline(&pass.pipeline);
+        render_pass.set_bind_group(0, &pass.bind_group, &[]);
+        render_pass.draw(0..3, 0..1); // Fullscreen triangle
+    }
+
+    fn record_pass(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        index: usize,
+        view: &wgpu::TextureView,
+        timestamp_writes: Option<wgpu::RenderPassTimestampWrites<'_>>,
+    ) {
+        let pass = &self.passes[index];
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Post-Process Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_pipeline(&pass.pipeline);
+        render_pass.set_bind_group(0, &pass.bind_group, &[]);
+        render_pass.draw(0..3, 0..1); // Fullscreen triangle
+    }
+
+    /// Recreate textures and bind groups after window resize.
     pub fn resize(
         &mut self,
         device: &wgpu::Device,
         uniform_buffer: &wgpu::Buffer,
         width: u32,
         height: u32,
-        surface_format: wgpu::TextureFormat,
     ) {
-        // Recreate offscreen texture
-        self.texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Offscreen Texture"),
-            size: wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
+        (self.texture, self.view) =
+            create_color_texture(device, "Offscreen Texture", self.scene_format, width, height);
+        (self.depth_texture, self.depth_view) = create_depth_texture(device, width, height);
+        self.intermediates = create_intermediates(device, self.passes.len(), width, height);
+
+        // Recreate bind groups with the new texture views
+        for (i, pass) in self.passes.iter_mut().enumerate() {
+            let input = pass_input(&self.view, &self.intermediates, i);
+            pass.bind_group = create_bind_group(device, &self.bind_group_layout, input, &self.sampler, uniform_buffer);
+        }
+
+        if let Some(ref mut bloom) = self.bloom {
+            bloom.resize(device, &self.view, width, height);
+        }
+    }
+}
+
+/// Texture pass `index` samples: the scene for the first pass, otherwise
+/// the ping-pong texture the previous pass wrote.
+fn pass_input<'a>(
+    scene: &'a wgpu::TextureView,
+    intermediates: &'a [(wgpu::Texture, wgpu::TextureView)],
+    index: usize,
+) -> &'a wgpu::TextureView {
+    match pass_input_slot(index) {
+        Some(slot) => &intermediates[slot].1,
+        None => scene,
+    }
+}
+
+/// Intermediate texture pass `index` reads, or `None` for the scene.
+fn pass_input_slot(index: usize) -> Option<usize> {
+    index.checked_sub(1).map(|prev| prev % 2)
+}
+
+/// Intermediate texture pass `index` of `pass_count` writes, or `None`
+/// for the final pass, which writes to the surface.
+fn pass_output_slot(index: usize, pass_count: usize) -> Option<usize> {
+    (index + 1 < pass_count).then_some(index % 2)
+}
+
+/// Number of ping-pong textures a chain of `pass_count` passes needs.
+fn intermediate_count(pass_count: usize) -> usize {
+    pass_count.saturating_sub(1).min(2)
+}
+
+fn create_color_texture(
+    device: &wgpu::Device,
+    label: &str,
+    format: wgpu::TextureFormat,
+    width: u32,
+    height: u32,
+) -> (wgpu::Texture, wgpu::TextureView) {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    (texture, view)
+}
+
+fn create_depth_texture(device: &wgpu::Device, width: u32, height: u32) -> (wgpu::Texture, wgpu::TextureView) {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Offscreen Depth Texture"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: DEPTH_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    (texture, view)
+}
+
+/// Ping-pong textures for a chain of `pass_count` passes (one is enough for two passes).
+fn create_intermediates(
+    device: &wgpu::Device,
+    pass_count: usize,
+    width: u32,
+    height: u32,
+) -> Vec<(wgpu::Texture, wgpu::TextureView)> {
+    (0..intermediate_count(pass_count))
+        .map(|_| create_color_texture(device, "Post-Process Chain Texture", CHAIN_FORMAT, width, height))
+        .collect()
+}
+
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    input: &wgpu::TextureView,
+    sampler: &wgpu::Sampler,
+    uniform_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Post-Process Bind Group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(input),
             },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: surface_format,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
-            view_formats: &[],
-        });
-        self.view = self.texture.create_view(&wgpu::TextureViewDescriptor::default());
-
-        // Recreate depth texture
-        self.depth_texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Offscreen Depth Texture"),
-            size: wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
             },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: DEPTH_FORMAT,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            view_formats: &[],
-        });
-        self.depth_view = self.depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: uniform_buffer.as_entire_binding(),
+            },
+        ],
+    })
+}
 
-        // Recreate bind group with new texture view
-        self.bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Post-Process Bind Group"),
-            layout: &self.bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&self.view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&self.sampler),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: uniform_buffer.as_entire_binding(),
-                },
-            ],
-        });
-    }
+/// The core `Uniforms` struct with `custom_uniform_fields` appended.
+fn uniforms_struct(custom_uniform_fields: &str) -> String {
+    format!(
+        r#"struct Uniforms {{
+{UNIFORM_FIELDS_WGSL}
+{custom_uniform_fields}
+}};"#
+    )
 }
 
-fn generate_shader(shader_code: &str, custom_uniform_fields: &str) -> String {
+fn generate_shader(
+    shader_code: &str,
+    uniforms_wgsl: &str,
+    tone_map: Option<ToneMapMode>,
+) -> String {
+    // With tone mapping the user code becomes a helper whose output is mapped
+    let fragment = match tone_map {
+        Some(mode) => format!(
+            r#"fn post_process(in: VertexOutput) -> vec4<f32> {{
+{shader_code}
+}}
+{tone_map_wgsl}
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {{
+    let color = post_process(in);
+    return vec4<f32>(tone_map(color.rgb), color.a);
+}}"#,
+            tone_map_wgsl = mode.to_wgsl()
+        ),
+        None => format!(
+            r#"@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {{
+{shader_code}
+}}"#
+        ),
+    };
+
     format!(
         r#"
-struct Uniforms {{
-    view_proj: mat4x4<f32>,
-    time: f32,
-    delta_time: f32,
-{custom_uniform_fields}
-}};
+{uniforms_wgsl}
 
 struct VertexOutput {{
     @builtin(position) clip_position: vec4<f32>,
@@ -295,10 +549,105 @@ fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {{
     return out;
 }}
 
-@fragment
-fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {{
-{shader_code}
-}}
+{fragment}
 "#
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tone_mapped_shader_validates() {
+        for mode in [
+            ToneMapMode::Reinhard,
+            ToneMapMode::AcesFast,
+            ToneMapMode::AcesFilmic,
+            ToneMapMode::Linear,
+        ] {
+            let src = generate_shader(PASSTHROUGH_SHADER, &uniforms_struct(""), Some(mode));
+            let module = naga::front::wgsl::parse_str(&src)
+                .unwrap_or_else(|e| panic!("{mode:?} shader failed to parse: {e:?}"));
+            naga::valid::Validator::new(
+                naga::valid::ValidationFlags::all(),
+                naga::valid::Capabilities::empty(),
+            )
+            .validate(&module)
+            .unwrap_or_else(|e| panic!("{mode:?} shader failed to validate: {e:?}"));
+        }
+    }
+
+    #[test]
+    fn test_chain_ping_pong_indexing() {
+        for pass_count in [2, 3, 4] {
+            let slots = intermediate_count(pass_count);
+            assert_eq!(pass_input_slot(0), None, "first pass reads the scene");
+            assert_eq!(pass_output_slot(pass_count - 1, pass_count), None, "last pass writes the surface");
+
+            for i in 1..pass_count {
+                let input = pass_input_slot(i).expect("later passes read an intermediate");
+                let written = pass_output_slot(i - 1, pass_count);
+                assert_eq!(Some(input), written, "{pass_count} passes: pass {i} reads what pass {} wrote", i - 1);
+                assert!(input < slots, "{pass_count} passes: slot {input} out of {slots}");
+                assert_ne!(Some(input), pass_output_slot(i, pass_count), "pass {i} reads and writes one texture");
+            }
+        }
+        assert_eq!(intermediate_count(1), 0);
+        assert_eq!(intermediate_count(2), 1);
+        assert_eq!(intermediate_count(3), 2);
+        assert_eq!(intermediate_count(4), 2);
+    }
+
+    #[test]
+    fn test_chain_pass_shader_validates() {
+        // Passes before the last skip tone mapping and read the previous pass as `scene`
+        let src = generate_shader(
+            "let c = textureSample(scene, scene_sampler, in.uv);\nreturn vec4<f32>(c.rgb * 2.0, c.a);",
+            &uniforms_struct(""),
+            None,
+        );
+        let module = naga::front::wgsl::parse_str(&src).expect("chain pass failed to parse");
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .expect("chain pass failed to validate");
+    }
+
+    #[test]
+    fn test_own_uniforms_struct_validates() {
+        // Callers with their own uniform layout declare the structs themselves
+        let uniforms = "struct Mouse {\n    pos: vec4<f32>,\n};\nstruct Uniforms {\n    time: f32,\n    mouse: Mouse,\n};";
+        let src = generate_shader(
+            "return textureSample(scene, scene_sampler, in.uv) * (0.5 + 0.5 * sin(uniforms.time));",
+            uniforms,
+            None,
+        );
+        let module = naga::front::wgsl::parse_str(&src).expect("own uniforms failed to parse");
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .expect("own uniforms failed to validate");
+    }
+
+    #[test]
+    fn test_chromatic_aberration_shader_validates() {
+        let body = format!(
+            "let aberration_strength = {:.4};\n{}",
+            0.5,
+            crate::shader_utils::CHROMATIC_ABERRATION_WGSL
+        );
+        let src = generate_shader(&body, &uniforms_struct(""), None);
+        let module = naga::front::wgsl::parse_str(&src).expect("chromatic aberration failed to parse");
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .expect("chromatic aberration failed to validate");
+    }
+}
@@ -0,0 +1,326 @@
+//! GPU timestamp queries for per-pass frame profiling.
+//!
+//! Each profiled pass gets a begin/end timestamp pair in a [`wgpu::QuerySet`].
+//! After the frame's passes are recorded, the queries are resolved and copied
+//! to a staging buffer that is mapped asynchronously, so profiling never
+//! stalls the pipeline - timings arrive a frame or two late.
+//!
+//! Requires [`wgpu::Features::TIMESTAMP_QUERY`] on the device.
+
+use std::sync::{Arc, Mutex};
+
+/// A GPU pass that can be timed by [`GpuProfiler`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfiledPass {
+    /// Spatial hashing (Morton codes, radix sort, cell table).
+    SpatialHash,
+    /// Main particle compute pass.
+    Compute,
+    /// Trail history update.
+    TrailCompute,
+    /// Connection pair search.
+    ConnectionCompute,
+    /// Main particle render pass.
+    Render,
+    /// Volumetric field render pass.
+    VolumeRender,
+    /// Post-processing pass.
+    PostProcess,
+}
+
+impl ProfiledPass {
+    /// All passes, in pipeline order.
+    pub const ALL: [ProfiledPass; 7] = [
+        ProfiledPass::SpatialHash,
+        ProfiledPass::Compute,
+        ProfiledPass::TrailCompute,
+        ProfiledPass::ConnectionCompute,
+        ProfiledPass::Render,
+        ProfiledPass::VolumeRender,
+        ProfiledPass::PostProcess,
+    ];
+
+    /// Human-readable name for display.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProfiledPass::SpatialHash => "Spatial Hash",
+            ProfiledPass::Compute => "Compute",
+            ProfiledPass::TrailCompute => "Trail Compute",
+            ProfiledPass::ConnectionCompute => "Connection Compute",
+            ProfiledPass::Render => "Render",
+            ProfiledPass::VolumeRender => "Volume Render",
+            ProfiledPass::PostProcess => "Post-Process",
+        }
+    }
+
+    fn index(self) -> u32 {
+        self as u32
+    }
+}
+
+const PASS_COUNT: u32 = ProfiledPass::ALL.len() as u32;
+const QUERY_COUNT: u32 = PASS_COUNT * 2;
+const RESOLVE_SIZE: u64 = QUERY_COUNT as u64 * 8;
+
+/// GPU time spent in each pass of one frame, in microseconds.
+///
+/// A field is `None` when that pass didn't run (e.g. no trails configured).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameTimings {
+    /// Spatial hashing passes.
+    pub spatial_hash_us: Option<f32>,
+    /// Main particle compute pass.
+    pub compute_us: Option<f32>,
+    /// Trail compute pass.
+    pub trail_compute_us: Option<f32>,
+    /// Connection compute pass.
+    pub connection_compute_us: Option<f32>,
+    /// Main render pass.
+    pub render_us: Option<f32>,
+    /// Volume render pass.
+    pub volume_render_us: Option<f32>,
+    /// Post-processing pass.
+    pub post_process_us: Option<f32>,
+}
+
+impl FrameTimings {
+    /// Timing for a single pass.
+    pub fn get(&self, pass: ProfiledPass) -> Option<f32> {
+        match pass {
+            ProfiledPass::SpatialHash => self.spatial_hash_us,
+            ProfiledPass::Compute => self.compute_us,
+            ProfiledPass::TrailCompute => self.trail_compute_us,
+            ProfiledPass::ConnectionCompute => self.connection_compute_us,
+            ProfiledPass::Render => self.render_us,
+            ProfiledPass::VolumeRender => self.volume_render_us,
+            ProfiledPass::PostProcess => self.post_process_us,
+        }
+    }
+
+    fn set(&mut self, pass: ProfiledPass, us: f32) {
+        let slot = match pass {
+            ProfiledPass::SpatialHash => &mut self.spatial_hash_us,
+            ProfiledPass::Compute => &mut self.compute_us,
+            ProfiledPass::TrailCompute => &mut self.trail_compute_us,
+            ProfiledPass::ConnectionCompute => &mut self.connection_compute_us,
+            ProfiledPass::Render => &mut self.render_us,
+            ProfiledPass::VolumeRender => &mut self.volume_render_us,
+            ProfiledPass::PostProcess => &mut self.post_process_us,
+        };
+        *slot = Some(us);
+    }
+
+    /// Sum of all passes that ran.
+    pub fn total_us(&self) -> f32 {
+        ProfiledPass::ALL.iter().filter_map(|&pass| self.get(pass)).sum()
+    }
+}
+
+/// Where a `map_async` callback leaves its result (a Mutex keeps this `Sync`).
+type MapResultSlot = Arc<Mutex<Option<Result<(), wgpu::BufferAsyncError>>>>;
+
+/// Per-pass GPU timer built on timestamp queries.
+///
+/// Usage per frame:
+/// 1. Pass [`compute_timestamps`](Self::compute_timestamps) /
+///    [`render_timestamps`](Self::render_timestamps) as each pass's
+///    `timestamp_writes`.
+/// 2. Call [`resolve`](Self::resolve) on the encoder after the last pass.
+/// 3. After submitting, call [`after_submit`](Self::after_submit) then
+///    [`poll`](Self::poll) to pick up finished timings.
+pub struct GpuProfiler {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    staging_buffer: wgpu::Buffer,
+    /// Nanoseconds per timestamp tick.
+    period_ns: f32,
+    enabled: bool,
+    /// Passes written this frame (bit per pass).
+    written: u8,
+    /// Passes copied to staging, waiting for the submit before mapping.
+    copied: Option<u8>,
+    /// Passes in the staging buffer currently being mapped.
+    pending: Option<(u8, MapResultSlot)>,
+    last: Option<FrameTimings>,
+}
+
+impl GpuProfiler {
+    /// Create a profiler, or `None` if the device lacks
+    /// [`wgpu::Features::TIMESTAMP_QUERY`]. Starts disabled.
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self> {
+        if !device.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+            return None;
+        }
+
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("Profiler Query Set"),
+            ty: wgpu::QueryType::Timestamp,
+            count: QUERY_COUNT,
+        });
+        let resolve_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Profiler Resolve Buffer"),
+            size: RESOLVE_SIZE,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Profiler Staging Buffer"),
+            size: RESOLVE_SIZE,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Some(Self {
+            query_set,
+            resolve_buffer,
+            staging_buffer,
+            period_ns: queue.get_timestamp_period(),
+            enabled: false,
+            written: 0,
+            copied: None,
+            pending: None,
+            last: None,
+        })
+    }
+
+    /// Turn timing on or off. Disabling clears the last timings.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.last = None;
+        }
+    }
+
+    /// Whether timing is on.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Timings from the most recent frame that finished reading back.
+    pub fn last_frame_timings(&self) -> Option<FrameTimings> {
+        self.last
+    }
+
+    /// Query indices for a pass, or `None` if this frame isn't being timed.
+    ///
+    /// Frames are skipped while an earlier frame's results are still in flight.
+    fn claim(&mut self, pass: ProfiledPass) -> Option<(u32, u32)> {
+        if !self.enabled || self.copied.is_some() || self.pending.is_some() {
+            return None;
+        }
+        self.written |= 1 << pass.index();
+        Some((pass.index() * 2, pass.index() * 2 + 1))
+    }
+
+    /// Timestamp writes for a compute pass (or span of passes).
+    pub fn compute_timestamps(
+        &mut self,
+        pass: ProfiledPass,
+    ) -> Option<wgpu::ComputePassTimestampWrites<'_>> {
+        let (begin, end) = self.claim(pass)?;
+        Some(wgpu::ComputePassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(begin),
+            end_of_pass_write_index: Some(end),
+        })
+    }
+
+    /// Timestamp writes for a render pass.
+    pub fn render_timestamps(
+        &mut self,
+        pass: ProfiledPass,
+    ) -> Option<wgpu::RenderPassTimestampWrites<'_>> {
+        let (begin, end) = self.claim(pass)?;
+        Some(wgpu::RenderPassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(begin),
+            end_of_pass_write_index: Some(end),
+        })
+    }
+
+    /// Resolve this frame's queries into the staging buffer.
+    pub fn resolve(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        if self.written == 0 {
+            return;
+        }
+        encoder.resolve_query_set(&self.query_set, 0..QUERY_COUNT, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(&self.resolve_buffer, 0, &self.staging_buffer, 0, RESOLVE_SIZE);
+        self.copied = Some(self.written);
+        self.written = 0;
+    }
+
+    /// Request mapping of the staging buffer once the resolve has been submitted.
+    pub fn after_submit(&mut self) {
+        let Some(mask) = self.copied.take() else {
+            return;
+        };
+        let slot: MapResultSlot = Arc::new(Mutex::new(None));
+        let callback_slot = slot.clone();
+        self.staging_buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+            if let Ok(mut slot) = callback_slot.lock() {
+                *slot = Some(result);
+            }
+        });
+        self.pending = Some((mask, slot));
+    }
+
+    /// Pick up finished timings without waiting.
+    pub fn poll(&mut self, device: &wgpu::Device) {
+        let Some((mask, ref slot)) = self.pending else {
+            return;
+        };
+        device.poll(wgpu::Maintain::Poll);
+        let result = slot.lock().ok().and_then(|mut slot| slot.take());
+        match result {
+            Some(Ok(())) => {
+                let data = self.staging_buffer.slice(..).get_mapped_range();
+                let ticks: Vec<u64> = data
+                    .chunks_exact(8)
+                    .map(|b| u64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+                    .collect();
+                drop(data);
+                self.staging_buffer.unmap();
+                self.pending = None;
+
+                let mut timings = FrameTimings::default();
+                for pass in ProfiledPass::ALL {
+                    if mask & (1 << pass.index()) == 0 {
+                        continue;
+                    }
+                    let i = pass.index() as usize * 2;
+                    let elapsed = ticks[i + 1].saturating_sub(ticks[i]);
+                    timings.set(pass, elapsed as f32 * self.period_ns / 1000.0);
+                }
+                if self.enabled {
+                    self.last = Some(timings);
+                }
+            }
+            Some(Err(_)) => self.pending = None,
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_timings_skip_passes_that_did_not_run() {
+        let mut timings = FrameTimings::default();
+        timings.set(ProfiledPass::Compute, 120.0);
+        timings.set(ProfiledPass::Render, 80.5);
+
+        assert_eq!(timings.get(ProfiledPass::Compute), Some(120.0));
+        assert_eq!(timings.get(ProfiledPass::TrailCompute), None);
+        assert_eq!(timings.total_us(), 200.5);
+    }
+
+    #[test]
+    fn test_profiled_pass_query_indices_are_unique() {
+        for (i, pass) in ProfiledPass::ALL.iter().enumerate() {
+            assert_eq!(pass.index() as usize, i);
+        }
+        assert_eq!(QUERY_COUNT, 14);
+    }
+}
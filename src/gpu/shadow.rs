@@ -0,0 +1,355 @@
+//! Shadow mapping from a directional light.
+//!
+//! Particles are drawn from the light's point of view into a depth texture
+//! before the main render pass. The main render shader samples that texture
+//! through a comparison sampler (bound as its own group) to shade particles
+//! that other particles occlude.
+#![allow(clippy::too_many_arguments)]
+
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+use crate::shader_utils::LIGHT_STRUCT_WGSL;
+use crate::visuals::LightConfig;
+
+/// Depth format of the shadow map.
+const SHADOW_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+/// GPU light uniforms. Matches `LIGHT_STRUCT_WGSL`.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct LightUniforms {
+    view_proj: [[f32; 4]; 4],
+    color: [f32; 3],
+    bias: f32,
+    texel_size: f32,
+    particle_size: f32,
+    _pad: [f32; 2],
+}
+
+/// GPU state for the shadow depth pass and the shadow lookup bind group.
+pub struct ShadowState {
+    /// Light-space depth target view.
+    shadow_view: wgpu::TextureView,
+    /// Depth-only pipeline drawing particle billboards from the light.
+    pipeline: wgpu::RenderPipeline,
+    /// Light uniforms for the depth pass.
+    light_bind_group: wgpu::BindGroup,
+    /// Layout of the bind group the main render shader samples shadows with.
+    pub render_bind_group_layout: wgpu::BindGroupLayout,
+    /// Shadow map, comparison sampler, and light uniforms for the main pass.
+    pub render_bind_group: wgpu::BindGroup,
+}
+
+impl ShadowState {
+    /// Create a new shadow mapping state.
+    pub fn new(
+        device: &wgpu::Device,
+        light: &LightConfig,
+        bounds: f32,
+        particle_size: f32,
+        particle_stride: usize,
+        alive_offset: u32,
+        scale_offset: u32,
+    ) -> Self {
+        let size = light.shadow_map_size.clamp(1, device.limits().max_texture_dimension_2d);
+
+        let shadow_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Shadow Map"),
+            size: wgpu::Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: SHADOW_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let shadow_view = shadow_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let shadow_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Shadow Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            ..Default::default()
+        });
+
+        let uniforms = LightUniforms {
+            view_proj: light.view_proj(bounds).to_cols_array_2d(),
+            color: light.color.to_array(),
+            bias: light.shadow_bias,
+            texel_size: 1.0 / size as f32,
+            particle_size,
+            _pad: [0.0; 2],
+        };
+        let light_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Light Uniform Buffer"),
+            contents: bytemuck::bytes_of(&uniforms),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        let light_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Shadow Pass Bind Group Layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let light_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Shadow Pass Bind Group"),
+            layout: &light_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: light_buffer.as_entire_binding(),
+            }],
+        });
+
+        let render_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Shadow Lookup Bind Group Layout"),
+            entries: &[
+                // Shadow map
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Depth,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // Comparison sampler
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+                    count: None,
+                },
+                // Light uniforms
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let render_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Shadow Lookup Bind Group"),
+            layout: &render_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&shadow_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&shadow_sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: light_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Shadow Depth Shader"),
+            source: wgpu::ShaderSource::Wgsl(generate_shadow_shader().into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Shadow Pipeline Layout"),
+            bind_group_layouts: &[&light_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        // Same locations as the billboard render shader
+        let vertex_attributes = [
+            wgpu::VertexAttribute {
+                offset: 0,
+                shader_location: 0,
+                format: wgpu::VertexFormat::Float32x3, // position
+            },
+            wgpu::VertexAttribute {
+                offset: alive_offset as wgpu::BufferAddress,
+                shader_location: 2,
+                format: wgpu::VertexFormat::Uint32, // alive
+            },
+            wgpu::VertexAttribute {
+                offset: scale_offset as wgpu::BufferAddress,
+                shader_location: 3,
+                format: wgpu::VertexFormat::Float32, // scale
+            },
+        ];
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Shadow Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: particle_stride as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &vertex_attributes,
+                }],
+                compilation_options: Default::default(),
+            },
+            // Fragment stage only discards outside the particle disc
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: SHADOW_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            shadow_view,
+            pipeline,
+            light_bind_group,
+            render_bind_group_layout,
+            render_bind_group,
+        }
+    }
+
+    /// Render particle depth from the light into the shadow map.
+    pub fn render(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        particle_buffer: &wgpu::Buffer,
+        num_particles: u32,
+    ) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Shadow Pass"),
+            color_attachments: &[],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.shadow_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.light_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, particle_buffer.slice(..));
+        render_pass.draw(0..6, 0..num_particles);
+    }
+}
+
+/// Generate the depth-only shader for the shadow pass.
+fn generate_shadow_shader() -> String {
+    format!(
+        r#"{LIGHT_STRUCT_WGSL}
+@group(0) @binding(0) var<uniform> light: Light;
+
+struct VertexOutput {{
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}};
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    @location(0) particle_pos: vec3<f32>,
+    @location(2) alive: u32,
+    @location(3) scale: f32,
+) -> VertexOutput {{
+    var out: VertexOutput;
+
+    if alive == 0u {{
+        out.clip_position = vec4<f32>(0.0, 0.0, -1000.0, 1.0);
+        out.uv = vec2<f32>(0.0);
+        return out;
+    }}
+
+    var quad_vertices = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>( 1.0, -1.0),
+        vec2<f32>(-1.0,  1.0),
+        vec2<f32>(-1.0,  1.0),
+        vec2<f32>( 1.0, -1.0),
+        vec2<f32>( 1.0,  1.0),
+    );
+    let quad_pos = quad_vertices[vertex_index];
+
+    // Orthographic light: w is 1, so the offset is a fixed light-space size
+    var clip_pos = light.view_proj * vec4<f32>(particle_pos, 1.0);
+    clip_pos.x += quad_pos.x * light.particle_size * scale;
+    clip_pos.y += quad_pos.y * light.particle_size * scale;
+
+    out.clip_position = clip_pos;
+    out.uv = quad_pos;
+    return out;
+}}
+
+@fragment
+fn fs_main(in: VertexOutput) {{
+    if length(in.uv) > 1.0 {{
+        discard;
+    }}
+}}
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shadow_shader_validates() {
+        let module = naga::front::wgsl::parse_str(&generate_shadow_shader())
+            .unwrap_or_else(|e| panic!("Shadow shader failed to parse: {e:?}"));
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .unwrap_or_else(|e| panic!("Shadow shader failed to validate: {e:?}"));
+    }
+
+    #[test]
+    fn test_light_uniforms_match_wgsl_layout() {
+        // mat4 (64) + vec3/bias (16) + texel_size/particle_size padded to 16
+        assert_eq!(std::mem::size_of::<LightUniforms>(), 96);
+    }
+}
@@ -2,12 +2,14 @@
 //!
 //! Handles Morton code computation, radix sort, and cell table building.
 
+use std::collections::HashMap;
+
 use bytemuck::{Pod, Zeroable};
 use wgpu::util::DeviceExt;
 
+use super::workgroup_size_constants;
 use crate::spatial::{SpatialConfig, MORTON_WGSL};
 
-const WORKGROUP_SIZE: u32 = 256;
 const RADIX_BITS: u32 = 4;
 const RADIX_SIZE: u32 = 16; // 2^4
 
@@ -78,47 +80,27 @@ pub struct SpatialGpu {
     pub config: SpatialConfig,
     num_particles: u32,
     sort_passes: u32,
+    workgroup_size: u32,
 }
 
 impl SpatialGpu {
     /// Create a new spatial hashing system for the given particle buffer and configuration.
+    ///
+    /// `workgroup_size` is the compute workgroup size, usually from
+    /// [`select_workgroup_size`](super::select_workgroup_size).
     pub fn new(
         device: &wgpu::Device,
         particle_buffer: &wgpu::Buffer,
         num_particles: u32,
         config: SpatialConfig,
         particle_wgsl_struct: &str,
+        workgroup_size: u32,
     ) -> Self {
         // Create buffers
-        let buffer_size = (num_particles as usize * std::mem::size_of::<u32>()) as u64;
-
-        let morton_codes_a = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Morton Codes A"),
-            size: buffer_size,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-
-        let morton_codes_b = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Morton Codes B"),
-            size: buffer_size,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-
-        let particle_indices_a = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Particle Indices A"),
-            size: buffer_size,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-
-        let particle_indices_b = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Particle Indices B"),
-            size: buffer_size,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        let morton_codes_a = create_per_particle_buffer(device, "Morton Codes A", num_particles);
+        let morton_codes_b = create_per_particle_buffer(device, "Morton Codes B", num_particles);
+        let particle_indices_a = create_per_particle_buffer(device, "Particle Indices A", num_particles);
+        let particle_indices_b = create_per_particle_buffer(device, "Particle Indices B", num_particles);
 
         let histogram = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Radix Histogram"),
@@ -173,7 +155,7 @@ impl SpatialGpu {
             build_cells_pipeline,
             clear_histogram_pipeline,
             clear_cells_pipeline,
-        ) = create_pipelines(device, particle_wgsl_struct);
+        ) = create_pipelines(device, particle_wgsl_struct, workgroup_size);
 
         // Create bind groups
         let morton_bind_group = create_morton_bind_group(
@@ -283,18 +265,120 @@ impl SpatialGpu {
             config,
             num_particles,
             sort_passes,
+            workgroup_size,
         }
     }
 
+    /// Resize the per-particle sort buffers for a new particle count.
+    ///
+    /// Pipelines and the cell table are kept; the bind groups that reference
+    /// the particle or sort buffers are recreated.
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        particle_buffer: &wgpu::Buffer,
+        num_particles: u32,
+    ) {
+        self.morton_codes_a = create_per_particle_buffer(device, "Morton Codes A", num_particles);
+        self.morton_codes_b = create_per_particle_buffer(device, "Morton Codes B", num_particles);
+        self.particle_indices_a = create_per_particle_buffer(device, "Particle Indices A", num_particles);
+        self.particle_indices_b = create_per_particle_buffer(device, "Particle Indices B", num_particles);
+
+        let spatial_params = SpatialParams {
+            cell_size: self.config.cell_size,
+            grid_resolution: self.config.grid_resolution,
+            num_particles,
+            max_neighbors: self.config.max_neighbors,
+        };
+        queue.write_buffer(&self.spatial_params_buffer, 0, bytemuck::cast_slice(&[spatial_params]));
+
+        self.morton_bind_group = create_morton_bind_group(
+            device,
+            &self.compute_morton_pipeline,
+            particle_buffer,
+            &self.morton_codes_a,
+            &self.particle_indices_a,
+            &self.spatial_params_buffer,
+        );
+        self.histogram_bind_group_a = create_histogram_bind_group(
+            device,
+            &self.histogram_pipeline,
+            &self.morton_codes_a,
+            &self.histogram,
+            &self.sort_params_buffer,
+        );
+        self.histogram_bind_group_b = create_histogram_bind_group(
+            device,
+            &self.histogram_pipeline,
+            &self.morton_codes_b,
+            &self.histogram,
+            &self.sort_params_buffer,
+        );
+        self.scatter_bind_group_a_to_b = create_scatter_bind_group(
+            device,
+            &self.scatter_pipeline,
+            &self.morton_codes_a,
+            &self.particle_indices_a,
+            &self.morton_codes_b,
+            &self.particle_indices_b,
+            &self.histogram,
+            &self.sort_params_buffer,
+        );
+        self.scatter_bind_group_b_to_a = create_scatter_bind_group(
+            device,
+            &self.scatter_pipeline,
+            &self.morton_codes_b,
+            &self.particle_indices_b,
+            &self.morton_codes_a,
+            &self.particle_indices_a,
+            &self.histogram,
+            &self.sort_params_buffer,
+        );
+        self.build_cells_bind_group = create_build_cells_bind_group(
+            device,
+            &self.build_cells_pipeline,
+            &self.morton_codes_a,
+            &self.cell_start,
+            &self.cell_end,
+            &self.spatial_params_buffer,
+        );
+
+        self.num_particles = num_particles;
+    }
+
     /// Execute spatial hashing passes
     pub fn execute(&self, encoder: &mut wgpu::CommandEncoder, queue: &wgpu::Queue) {
-        let workgroups = self.num_particles.div_ceil(WORKGROUP_SIZE);
+        self.execute_timed(encoder, queue, None);
+    }
+
+    /// Execute spatial hashing passes, timing the whole sequence.
+    ///
+    /// The beginning timestamp is written by the first pass and the end
+    /// timestamp by the last, so the span covers every sort pass in between.
+    pub fn execute_timed(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        queue: &wgpu::Queue,
+        timestamps: Option<wgpu::ComputePassTimestampWrites<'_>>,
+    ) {
+        let workgroups = self.num_particles.div_ceil(self.workgroup_size);
+        let begin_timestamp = timestamps.as_ref().map(|t| wgpu::ComputePassTimestampWrites {
+            query_set: t.query_set,
+            beginning_of_pass_write_index: t.beginning_of_pass_write_index,
+            end_of_pass_write_index: None,
+        });
+        let end_timestamp = timestamps.map(|t| wgpu::ComputePassTimestampWrites {
+            query_set: t.query_set,
+            beginning_of_pass_write_index: None,
+            end_of_pass_write_index: t.end_of_pass_write_index,
+        });
 
         // Step 1: Compute Morton codes
         {
             let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Compute Morton"),
-                timestamp_writes: None,
+                timestamp_writes: begin_timestamp,
             });
             pass.set_pipeline(&self.compute_morton_pipeline);
             pass.set_bind_group(0, &self.morton_bind_group, &[]);
@@ -324,7 +408,7 @@ impl SpatialGpu {
                 });
                 pass.set_pipeline(&self.clear_histogram_pipeline);
                 pass.set_bind_group(0, &self.clear_histogram_bind_group, &[]);
-                pass.dispatch_workgroups(1, 1, 1);
+                pass.dispatch_workgroups(RADIX_SIZE.div_ceil(self.workgroup_size), 1, 1);
             }
 
             // Histogram pass
@@ -380,7 +464,7 @@ impl SpatialGpu {
             });
             pass.set_pipeline(&self.clear_cells_pipeline);
             pass.set_bind_group(0, &self.clear_cells_bind_group, &[]);
-            let cell_workgroups = self.config.total_cells().div_ceil(WORKGROUP_SIZE);
+            let cell_workgroups = self.config.total_cells().div_ceil(self.workgroup_size);
             pass.dispatch_workgroups(cell_workgroups, 1, 1);
         }
 
@@ -388,7 +472,7 @@ impl SpatialGpu {
         {
             let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Build Cell Table"),
-                timestamp_writes: None,
+                timestamp_writes: end_timestamp,
             });
             pass.set_pipeline(&self.build_cells_pipeline);
             pass.set_bind_group(0, &self.build_cells_bind_group, &[]);
@@ -400,6 +484,7 @@ impl SpatialGpu {
 fn create_pipelines(
     device: &wgpu::Device,
     particle_wgsl_struct: &str,
+    workgroup_size: u32,
 ) -> (
     wgpu::ComputePipeline,
     wgpu::ComputePipeline,
@@ -427,7 +512,9 @@ struct SpatialParams {{
 @group(0) @binding(2) var<storage, read_write> particle_indices: array<u32>;
 @group(0) @binding(3) var<uniform> params: SpatialParams;
 
-@compute @workgroup_size(256)
+override WORKGROUP_SIZE: u32 = 256u;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
     let idx = global_id.x;
     if idx >= params.num_particles {{
@@ -445,7 +532,7 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
 
     let morton_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
         label: Some("Morton Shader"),
-        source: wgpu::ShaderSource::Wgsl(morton_shader_src.into()),
+        source: wgpu::ShaderSource::Wgsl(morton_shader_src.as_str().into()),
     });
 
     // Histogram shader
@@ -463,7 +550,9 @@ struct SortParams {
 
 const RADIX_SIZE: u32 = 16u;
 
-@compute @workgroup_size(256)
+override WORKGROUP_SIZE: u32 = 256u;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let idx = global_id.x;
     if idx >= params.num_elements {
@@ -539,7 +628,9 @@ struct SortParams {
 
 const RADIX_SIZE: u32 = 16u;
 
-@compute @workgroup_size(256)
+override WORKGROUP_SIZE: u32 = 256u;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let idx = global_id.x;
     if idx >= params.num_elements {
@@ -576,7 +667,9 @@ struct SpatialParams {
 @group(0) @binding(2) var<storage, read_write> cell_end: array<u32>;
 @group(0) @binding(3) var<uniform> params: SpatialParams;
 
-@compute @workgroup_size(256)
+override WORKGROUP_SIZE: u32 = 256u;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let idx = global_id.x;
     if idx >= params.num_particles {
@@ -610,7 +703,9 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let clear_shader_src = r#"
 @group(0) @binding(0) var<storage, read_write> data: array<u32>;
 
-@compute @workgroup_size(256)
+override WORKGROUP_SIZE: u32 = 256u;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let idx = global_id.x;
     if idx < arrayLength(&data) {
@@ -624,14 +719,16 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
         source: wgpu::ShaderSource::Wgsl(clear_shader_src.into()),
     });
 
-    // Create pipeline layouts and pipelines
-    let morton_pipeline = create_compute_pipeline(device, &morton_shader, "main", "Morton Pipeline");
-    let histogram_pipeline = create_compute_pipeline(device, &histogram_shader, "main", "Histogram Pipeline");
-    let prefix_sum_pipeline = create_compute_pipeline(device, &prefix_sum_shader, "main", "Prefix Sum Pipeline");
-    let scatter_pipeline = create_compute_pipeline(device, &scatter_shader, "main", "Scatter Pipeline");
-    let build_cells_pipeline = create_compute_pipeline(device, &build_cells_shader, "main", "Build Cells Pipeline");
-    let clear_histogram_pipeline = create_compute_pipeline(device, &clear_shader, "main", "Clear Histogram Pipeline");
-    let clear_cells_pipeline = create_compute_pipeline(device, &clear_shader, "main", "Clear Cells Pipeline");
+    // Create pipeline layouts and pipelines. Every shader but the prefix sum
+    // takes the workgroup size as an override.
+    let constants = workgroup_size_constants(&morton_shader_src, workgroup_size);
+    let morton_pipeline = create_compute_pipeline(device, &morton_shader, &constants, "Morton Pipeline");
+    let histogram_pipeline = create_compute_pipeline(device, &histogram_shader, &constants, "Histogram Pipeline");
+    let prefix_sum_pipeline = create_compute_pipeline(device, &prefix_sum_shader, &Default::default(), "Prefix Sum Pipeline");
+    let scatter_pipeline = create_compute_pipeline(device, &scatter_shader, &constants, "Scatter Pipeline");
+    let build_cells_pipeline = create_compute_pipeline(device, &build_cells_shader, &constants, "Build Cells Pipeline");
+    let clear_histogram_pipeline = create_compute_pipeline(device, &clear_shader, &constants, "Clear Histogram Pipeline");
+    let clear_cells_pipeline = create_compute_pipeline(device, &clear_shader, &constants, "Clear Cells Pipeline");
 
     (
         morton_pipeline,
@@ -647,19 +744,32 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
 fn create_compute_pipeline(
     device: &wgpu::Device,
     shader: &wgpu::ShaderModule,
-    entry_point: &str,
+    constants: &HashMap<String, f64>,
     label: &str,
 ) -> wgpu::ComputePipeline {
     device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
         label: Some(label),
         layout: None, // Auto layout
         module: shader,
-        entry_point: Some(entry_point),
-        compilation_options: Default::default(),
+        entry_point: Some("main"),
+        compilation_options: wgpu::PipelineCompilationOptions {
+            constants,
+            ..Default::default()
+        },
         cache: None,
     })
 }
 
+/// A `u32`-per-particle storage buffer (Morton codes or particle indices).
+fn create_per_particle_buffer(device: &wgpu::Device, label: &str, num_particles: u32) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(label),
+        size: (num_particles as usize * std::mem::size_of::<u32>()) as u64,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
 fn create_morton_bind_group(
     device: &wgpu::Device,
     pipeline: &wgpu::ComputePipeline,
@@ -46,6 +46,7 @@ impl SpatialGridViz {
         spatial_config: &SpatialConfig,
         opacity: f32,
         surface_format: wgpu::TextureFormat,
+        sample_count: u32,
     ) -> Self {
         // Generate grid line segments
         let lines = generate_grid_lines(spatial_config);
@@ -174,7 +175,10 @@ impl SpatialGridViz {
                 stencil: wgpu::StencilState::default(),
                 bias: wgpu::DepthBiasState::default(),
             }),
-            multisample: wgpu::MultisampleState::default(),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
             multiview: None,
             cache: None,
         });
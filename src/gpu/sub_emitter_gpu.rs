@@ -6,6 +6,7 @@
 use bytemuck::{Pod, Zeroable};
 use wgpu::util::DeviceExt;
 
+use super::workgroup_size_constants;
 use crate::sub_emitter::{SubEmitter, MAX_DEATH_EVENTS};
 
 /// GPU representation of a death event.
@@ -15,7 +16,8 @@ pub struct DeathEventGpu {
     pub position: [f32; 3],
     pub parent_type: u32,
     pub velocity: [f32; 3],
-    pub _pad0: u32,
+    /// Index of the sub-emitter that recorded the event.
+    pub emitter: u32,
     pub color: [f32; 3],
     pub _pad1: u32,
 }
@@ -40,6 +42,8 @@ pub struct SubEmitterGpu {
     /// Number of particles (kept for potential debug/stats).
     #[allow(dead_code)]
     pub num_particles: u32,
+    /// Compute workgroup size of the spawn pipeline.
+    workgroup_size: u32,
 }
 
 impl SubEmitterGpu {
@@ -50,6 +54,7 @@ impl SubEmitterGpu {
         num_particles: u32,
         sub_emitters: &[SubEmitter],
         particle_wgsl_struct: &str,
+        workgroup_size: u32,
     ) -> Self {
         // Create death buffer
         let death_buffer = device.create_buffer(&wgpu::BufferDescriptor {
@@ -139,7 +144,7 @@ impl SubEmitterGpu {
 
         let spawn_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Sub-Emitter Spawn Shader"),
-            source: wgpu::ShaderSource::Wgsl(spawn_shader_src.into()),
+            source: wgpu::ShaderSource::Wgsl(spawn_shader_src.as_str().into()),
         });
 
         // Create spawn pipeline layout
@@ -206,33 +211,22 @@ impl SubEmitterGpu {
             layout: Some(&spawn_pipeline_layout),
             module: &spawn_shader,
             entry_point: Some("main"),
-            compilation_options: Default::default(),
+            compilation_options: wgpu::PipelineCompilationOptions {
+                constants: &workgroup_size_constants(&spawn_shader_src, workgroup_size),
+                ..Default::default()
+            },
             cache: None,
         });
 
         // Create spawn bind group
-        let spawn_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Spawn Bind Group"),
-            layout: &spawn_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: particle_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: death_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: death_count_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 3,
-                    resource: child_slot_buffer.as_entire_binding(),
-                },
-            ],
-        });
+        let spawn_bind_group = create_spawn_bind_group(
+            device,
+            &spawn_bind_group_layout,
+            particle_buffer,
+            &death_buffer,
+            &death_count_buffer,
+            &child_slot_buffer,
+        );
 
         Self {
             death_buffer,
@@ -243,9 +237,28 @@ impl SubEmitterGpu {
             spawn_pipeline,
             spawn_bind_group,
             num_particles,
+            workgroup_size,
         }
     }
 
+    /// Rebind the spawn pass to a new particle buffer after the particle count changed.
+    pub fn set_particle_buffer(
+        &mut self,
+        device: &wgpu::Device,
+        particle_buffer: &wgpu::Buffer,
+        num_particles: u32,
+    ) {
+        self.spawn_bind_group = create_spawn_bind_group(
+            device,
+            &self.spawn_pipeline.get_bind_group_layout(0),
+            particle_buffer,
+            &self.death_buffer,
+            &self.death_count_buffer,
+            &self.child_slot_buffer,
+        );
+        self.num_particles = num_particles;
+    }
+
     /// Clear death buffers before frame.
     pub fn clear_buffers(&self, queue: &wgpu::Queue) {
         // Clear death count
@@ -264,12 +277,44 @@ impl SubEmitterGpu {
         compute_pass.set_pipeline(&self.spawn_pipeline);
         compute_pass.set_bind_group(0, &self.spawn_bind_group, &[]);
 
-        // Dispatch one workgroup per potential death event
-        let workgroups = MAX_DEATH_EVENTS.div_ceil(256);
+        // Dispatch one invocation per potential death event
+        let workgroups = MAX_DEATH_EVENTS.div_ceil(self.workgroup_size);
         compute_pass.dispatch_workgroups(workgroups, 1, 1);
     }
 }
 
+fn create_spawn_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    particle_buffer: &wgpu::Buffer,
+    death_buffer: &wgpu::Buffer,
+    death_count_buffer: &wgpu::Buffer,
+    child_slot_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Spawn Bind Group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: particle_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: death_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: death_count_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 3,
+                resource: child_slot_buffer.as_entire_binding(),
+            },
+        ],
+    })
+}
+
 /// Generate the child spawning compute shader.
 fn generate_spawn_shader(particle_wgsl_struct: &str, sub_emitters: &[SubEmitter]) -> String {
     let mut spawn_code = String::new();
@@ -288,7 +333,7 @@ struct DeathEvent {{
     position: vec3<f32>,
     parent_type: u32,
     velocity: vec3<f32>,
-    _pad0: u32,
+    emitter: u32,
     color: vec3<f32>,
     _pad1: u32,
 }};
@@ -339,7 +384,9 @@ fn rand_sphere(seed: u32) -> vec3<f32> {{
     return v / len;
 }}
 
-@compute @workgroup_size(256)
+override WORKGROUP_SIZE: u32 = 256u;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
     let death_idx = global_id.x;
     let total_deaths = death_count_buf.count;
@@ -364,7 +411,16 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
 /// This handles both death-triggered and condition-triggered sub-emitters:
 /// - `OnDeath`: Records when `was_alive == 1u && p.alive == 0u`
 /// - `OnCondition`: Records when the custom WGSL condition is true
-pub fn death_recording_wgsl(sub_emitters: &[SubEmitter]) -> String {
+/// - `AgeThreshold`: Records once per life when `p.age >= fraction * max_age`,
+///   using the particle's `triggered` flag
+///
+/// Each event stores the index of the sub-emitter that recorded it, so
+/// emitters sharing a parent type only spawn for their own trigger.
+///
+/// `AgeThreshold` triggers are skipped when `max_age` is `None`; the
+/// simulation reports [`SimulationError::NoMaxAge`](crate::SimulationError::NoMaxAge)
+/// before building the shader in that case.
+pub fn death_recording_wgsl(sub_emitters: &[SubEmitter], max_age: Option<f32>) -> String {
     use crate::sub_emitter::SpawnTrigger;
 
     if sub_emitters.is_empty() {
@@ -374,34 +430,27 @@ pub fn death_recording_wgsl(sub_emitters: &[SubEmitter]) -> String {
     let mut code = String::new();
     code.push_str("\n    // Sub-emitter spawn event recording\n");
 
-    // Collect death-triggered emitters (group by parent type)
-    let death_emitters: Vec<_> = sub_emitters
-        .iter()
-        .filter(|se| matches!(se.trigger, SpawnTrigger::OnDeath))
-        .collect();
-
-    if !death_emitters.is_empty() {
-        let type_checks: Vec<String> = death_emitters
-            .iter()
-            .map(|se| format!("p.particle_type == {}u", se.parent_type))
-            .collect();
-        let type_condition = type_checks.join(" || ");
-
-        code.push_str(&format!(
-            r#"    // Death-triggered spawn recording
-    if was_alive == 1u && p.alive == 0u && ({type_condition}) {{
+    // Death-triggered emitters (each records its own event)
+    for (i, se) in sub_emitters.iter().enumerate() {
+        if matches!(se.trigger, SpawnTrigger::OnDeath) {
+            code.push_str(&format!(
+                r#"
+    // Death-triggered spawn recording (sub-emitter {i})
+    if was_alive == 1u && p.alive == 0u && p.particle_type == {parent_type}u {{
         let spawn_idx = atomicAdd(&sub_emitter_death_count, 1u);
         if spawn_idx < {max_events}u {{
             sub_emitter_death_buffer[spawn_idx].position = p.position;
             sub_emitter_death_buffer[spawn_idx].velocity = p.velocity;
             sub_emitter_death_buffer[spawn_idx].color = p.color;
             sub_emitter_death_buffer[spawn_idx].parent_type = p.particle_type;
+            sub_emitter_death_buffer[spawn_idx].emitter = {i}u;
         }}
     }}
 "#,
-            type_condition = type_condition,
-            max_events = MAX_DEATH_EVENTS,
-        ));
+                parent_type = se.parent_type,
+                max_events = MAX_DEATH_EVENTS,
+            ));
+        }
     }
 
     // Handle condition-triggered emitters (each gets its own check)
@@ -418,6 +467,7 @@ pub fn death_recording_wgsl(sub_emitters: &[SubEmitter]) -> String {
             sub_emitter_death_buffer[spawn_idx].velocity = p.velocity;
             sub_emitter_death_buffer[spawn_idx].color = p.color;
             sub_emitter_death_buffer[spawn_idx].parent_type = p.particle_type;
+            sub_emitter_death_buffer[spawn_idx].emitter = {i}u;
         }}
     }}
 "#,
@@ -429,6 +479,46 @@ pub fn death_recording_wgsl(sub_emitters: &[SubEmitter]) -> String {
         }
     }
 
+    // Handle age-triggered emitters (fire once per life, tracked by p.triggered)
+    let Some(max_age) = max_age else {
+        return code;
+    };
+    let mut has_age_triggers = false;
+    for (i, se) in sub_emitters.iter().enumerate() {
+        if let SpawnTrigger::AgeThreshold { fraction } = se.trigger {
+            has_age_triggers = true;
+            let threshold = fraction.min(1.0) * max_age;
+            code.push_str(&format!(
+                r#"
+    // Age-triggered spawn recording (sub-emitter {i}, {fraction} of {max_age}s)
+    if p.particle_type == {parent_type}u && was_alive == 1u && p.triggered == 0u && p.age >= {threshold:?} {{
+        p.triggered = 1u;
+        let spawn_idx = atomicAdd(&sub_emitter_death_count, 1u);
+        if spawn_idx < {max_events}u {{
+            sub_emitter_death_buffer[spawn_idx].position = p.position;
+            sub_emitter_death_buffer[spawn_idx].velocity = p.velocity;
+            sub_emitter_death_buffer[spawn_idx].color = p.color;
+            sub_emitter_death_buffer[spawn_idx].parent_type = p.particle_type;
+            sub_emitter_death_buffer[spawn_idx].emitter = {i}u;
+        }}
+    }}
+"#,
+                parent_type = se.parent_type,
+                max_events = MAX_DEATH_EVENTS,
+            ));
+        }
+    }
+    if has_age_triggers {
+        code.push_str(
+            r#"
+    // Dead slots forget their age trigger, so respawned particles fire again
+    if p.alive == 0u {
+        p.triggered = 0u;
+    }
+"#,
+        );
+    }
+
     code
 }
 
@@ -439,7 +529,7 @@ struct SubEmitterDeathEvent {
     position: vec3<f32>,
     parent_type: u32,
     velocity: vec3<f32>,
-    _pad0: u32,
+    emitter: u32,
     color: vec3<f32>,
     _pad1: u32,
 };
@@ -471,7 +561,7 @@ mod tests {
                 .child_lifetime(1.0)
         ];
         
-        let death_recording = death_recording_wgsl(&sub_emitters);
+        let death_recording = death_recording_wgsl(&sub_emitters, None);
         println!("\n=== Death Recording WGSL ===");
         println!("{}", death_recording);
         
@@ -513,8 +603,37 @@ mod tests {
         println!("{}", spawn_shader);
         
         // Verify expected content
-        assert!(spawn_shader.contains("death.parent_type == 0u"));
+        assert!(spawn_shader.contains("death.emitter == 0u"));
         assert!(spawn_shader.contains("child.alive = 1u"));
         assert!(spawn_shader.contains("child.particle_type = 1u"));
     }
+
+    #[test]
+    fn test_age_threshold_recording_fires_once() {
+        let sub_emitters = vec![SubEmitter::new(0, 1).on_age(0.5).count(1)];
+        let recording = death_recording_wgsl(&sub_emitters, Some(4.0));
+        assert!(recording.contains("was_alive == 1u && p.triggered == 0u && p.age >= 2.0"));
+        assert!(recording.contains("p.triggered = 1u;"));
+        assert!(recording.contains("p.triggered = 0u;"));
+    }
+
+    #[test]
+    fn test_events_record_their_emitter() {
+        // Two emitters on the same parent type must not fire for each other's events
+        let sub_emitters = vec![SubEmitter::new(0, 1).on_age(0.5), SubEmitter::new(0, 2)];
+        let recording = death_recording_wgsl(&sub_emitters, Some(4.0));
+        assert!(recording.contains("sub_emitter_death_buffer[spawn_idx].emitter = 0u;"));
+        assert!(recording.contains("sub_emitter_death_buffer[spawn_idx].emitter = 1u;"));
+
+        let spawn_shader = generate_spawn_shader("struct Particle { position: vec3<f32> }", &sub_emitters);
+        assert!(spawn_shader.contains("if death.emitter == 0u"));
+        assert!(spawn_shader.contains("if death.emitter == 1u"));
+        assert!(!spawn_shader.contains("death.parent_type =="));
+    }
+
+    #[test]
+    fn test_age_threshold_without_max_age_records_nothing() {
+        let recording = death_recording_wgsl(&[SubEmitter::new(0, 1).on_age(0.5)], None);
+        assert!(!recording.contains("triggered"));
+    }
 }
@@ -1,13 +1,21 @@
 //! Trail rendering system for particle motion history.
 //!
-//! Stores position and color history for each particle and renders fading trails
-//! behind moving particles.
+//! Stores position, color, and velocity history for each particle. A ribbon
+//! pass then interpolates that history with cubic Hermite splines into a
+//! smooth ribbon, which is rendered as one triangle strip per particle.
 
 use bytemuck::{Pod, Zeroable};
+use glam::Vec3;
 use wgpu::util::DeviceExt;
 
-use super::{blend_mode_to_state, DEPTH_FORMAT};
-use crate::visuals::BlendMode;
+use super::{blend_mode_to_state, workgroup_size_constants, DEPTH_FORMAT};
+use crate::visuals::{BlendMode, TrailConfig};
+
+/// Ribbon samples generated per span between consecutive trail points.
+const TRAIL_SUBDIVISIONS: u32 = 4;
+
+/// Size of one `TrailRibbonVertex` in bytes (3 vec4s).
+const RIBBON_VERTEX_SIZE: u64 = 48;
 
 /// Parameters for trail rendering.
 #[repr(C)]
@@ -18,16 +26,27 @@ struct TrailParams {
     _pad: [u32; 2],
 }
 
+/// Number of ribbon samples generated for each particle's trail.
+fn ribbon_samples(trail_length: u32) -> u32 {
+    trail_length.saturating_sub(1) * TRAIL_SUBDIVISIONS + 1
+}
+
 /// GPU resources for trail rendering.
 #[allow(dead_code)]
 pub struct TrailState {
-    /// Buffer storing trail position and color history.
-    /// Each trail point is 2 vec4s: (position.xyz, valid), (color.rgb, _)
+    /// Buffer storing trail history.
+    /// Each trail point is 3 vec4s: (position.xyz, valid), (color.rgb, gradient key), (velocity.xyz, _)
     pub buffer: wgpu::Buffer,
+    /// Buffer of interpolated `TrailRibbonVertex` samples.
+    pub ribbon_buffer: wgpu::Buffer,
     /// Compute pipeline for updating trails.
     pub compute_pipeline: wgpu::ComputePipeline,
     /// Bind group for compute shader.
     pub compute_bind_group: wgpu::BindGroup,
+    /// Compute pipeline generating ribbon samples from trail history.
+    pub ribbon_pipeline: wgpu::ComputePipeline,
+    /// Bind group for the ribbon shader.
+    pub ribbon_bind_group: wgpu::BindGroup,
     /// Render pipeline for drawing trails.
     pub render_pipeline: wgpu::RenderPipeline,
     /// Bind group for render shader.
@@ -48,34 +67,20 @@ impl TrailState {
         particle_buffer: &wgpu::Buffer,
         uniform_buffer: &wgpu::Buffer,
         num_particles: u32,
+        workgroup_size: u32,
         trail_length: u32,
         particle_stride: usize,
         color_offset: Option<u32>,
+        velocity_offset: Option<u32>,
         particle_size: f32,
+        tail_color: Option<Vec3>,
+        trail_config: &TrailConfig,
         blend_mode: BlendMode,
         surface_format: wgpu::TextureFormat,
+        sample_count: u32,
     ) -> Self {
-        // Trail buffer: stores position AND color history for each particle
-        // Each entry is 2 * vec4<f32>: (xyz = position, w = validity) + (rgb = color, w = unused)
-        let buffer_size = (num_particles as usize) * (trail_length as usize) * 32; // 2 vec4s = 32 bytes
-        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Trail Buffer"),
-            size: buffer_size as u64,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::VERTEX,
-            mapped_at_creation: false,
-        });
-
-        // Trail params uniform
-        let trail_params = TrailParams {
-            num_particles,
-            trail_length,
-            _pad: [0; 2],
-        };
-        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Trail Params Buffer"),
-            contents: bytemuck::bytes_of(&trail_params),
-            usage: wgpu::BufferUsages::UNIFORM,
-        });
+        let (buffer, ribbon_buffer, params_buffer) =
+            create_trail_buffers(device, num_particles, trail_length);
 
         // Create compute pipeline
         let (compute_pipeline, compute_bind_group) = create_compute_pipeline(
@@ -85,23 +90,35 @@ impl TrailState {
             &params_buffer,
             particle_stride,
             color_offset,
+            velocity_offset,
+            workgroup_size,
         );
 
+        // Create ribbon generation pipeline
+        let (ribbon_pipeline, ribbon_bind_group) =
+            create_ribbon_pipeline(device, &buffer, &ribbon_buffer, &params_buffer, workgroup_size);
+
         // Create render pipeline
         let (render_pipeline, render_bind_group) = create_render_pipeline(
             device,
             uniform_buffer,
-            &buffer,
+            &ribbon_buffer,
             &params_buffer,
             particle_size,
+            tail_color,
+            trail_config,
             blend_mode,
             surface_format,
+            sample_count,
         );
 
         Self {
             buffer,
+            ribbon_buffer,
             compute_pipeline,
             compute_bind_group,
+            ribbon_pipeline,
+            ribbon_bind_group,
             render_pipeline,
             render_bind_group,
             num_particles,
@@ -109,24 +126,182 @@ impl TrailState {
             params_buffer,
         }
     }
+
+    /// Reallocate the trail history for a new particle count.
+    ///
+    /// Pipelines are kept; existing trail history is discarded.
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        particle_buffer: &wgpu::Buffer,
+        uniform_buffer: &wgpu::Buffer,
+        num_particles: u32,
+    ) {
+        let (buffer, ribbon_buffer, params_buffer) =
+            create_trail_buffers(device, num_particles, self.trail_length);
+        self.compute_bind_group = create_compute_bind_group(
+            device,
+            &self.compute_pipeline.get_bind_group_layout(0),
+            particle_buffer,
+            &buffer,
+            &params_buffer,
+        );
+        self.ribbon_bind_group = create_ribbon_bind_group(
+            device,
+            &self.ribbon_pipeline.get_bind_group_layout(0),
+            &buffer,
+            &ribbon_buffer,
+            &params_buffer,
+        );
+        self.render_bind_group = create_render_bind_group(
+            device,
+            &self.render_pipeline.get_bind_group_layout(0),
+            uniform_buffer,
+            &ribbon_buffer,
+            &params_buffer,
+        );
+        self.buffer = buffer;
+        self.ribbon_buffer = ribbon_buffer;
+        self.params_buffer = params_buffer;
+        self.num_particles = num_particles;
+    }
+
+    /// Draw one ribbon triangle strip per particle.
+    pub fn draw(&self, render_pass: &mut wgpu::RenderPass<'_>) {
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, &self.render_bind_group, &[]);
+        // Two vertices (one per ribbon edge) per sample
+        let vertices_per_ribbon = ribbon_samples(self.trail_length) * 2;
+        render_pass.draw(0..vertices_per_ribbon, 0..self.num_particles);
+    }
 }
 
-fn create_compute_pipeline(
+/// Create the trail history buffer, ribbon buffer, and params uniform.
+fn create_trail_buffers(
     device: &wgpu::Device,
+    num_particles: u32,
+    trail_length: u32,
+) -> (wgpu::Buffer, wgpu::Buffer, wgpu::Buffer) {
+    // Trail buffer: stores position, color, AND velocity history for each particle
+    // Each entry is 3 * vec4<f32>: (xyz = position, w = validity) + (rgb = color, w = gradient key)
+    // + (xyz = velocity, w = unused)
+    let buffer_size = (num_particles as usize) * (trail_length as usize) * 48; // 3 vec4s = 48 bytes
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Trail Buffer"),
+        size: buffer_size as u64,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::VERTEX,
+        mapped_at_creation: false,
+    });
+
+    // Ribbon buffer: interpolated samples along each trail
+    let ribbon_size = num_particles as u64 * ribbon_samples(trail_length) as u64 * RIBBON_VERTEX_SIZE;
+    let ribbon_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Trail Ribbon Buffer"),
+        size: ribbon_size,
+        usage: wgpu::BufferUsages::STORAGE,
+        mapped_at_creation: false,
+    });
+
+    // Trail params uniform
+    let trail_params = TrailParams {
+        num_particles,
+        trail_length,
+        _pad: [0; 2],
+    };
+    let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Trail Params Buffer"),
+        contents: bytemuck::bytes_of(&trail_params),
+        usage: wgpu::BufferUsages::UNIFORM,
+    });
+
+    (buffer, ribbon_buffer, params_buffer)
+}
+
+fn create_compute_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
     particle_buffer: &wgpu::Buffer,
     trail_buffer: &wgpu::Buffer,
     params_buffer: &wgpu::Buffer,
-    particle_stride: usize,
-    color_offset: Option<u32>,
-) -> (wgpu::ComputePipeline, wgpu::BindGroup) {
-    let shader_src = generate_compute_shader(particle_stride, color_offset);
-    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-        label: Some("Trail Compute Shader"),
-        source: wgpu::ShaderSource::Wgsl(shader_src.into()),
-    });
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Trail Compute Bind Group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: particle_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: trail_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: params_buffer.as_entire_binding(),
+            },
+        ],
+    })
+}
 
-    let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-        label: Some("Trail Compute Bind Group Layout"),
+fn create_ribbon_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    trail_buffer: &wgpu::Buffer,
+    ribbon_buffer: &wgpu::Buffer,
+    params_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Trail Ribbon Bind Group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: trail_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: ribbon_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: params_buffer.as_entire_binding(),
+            },
+        ],
+    })
+}
+
+fn create_render_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    uniform_buffer: &wgpu::Buffer,
+    ribbon_buffer: &wgpu::Buffer,
+    params_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Trail Render Bind Group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: ribbon_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: params_buffer.as_entire_binding(),
+            },
+        ],
+    })
+}
+
+/// Bind group layout with (storage read, storage read/write, uniform) buffers.
+fn create_storage_compute_layout(device: &wgpu::Device, label: &str) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some(label),
         entries: &[
             wgpu::BindGroupLayoutEntry {
                 binding: 0,
@@ -159,27 +334,36 @@ fn create_compute_pipeline(
                 count: None,
             },
         ],
-    });
+    })
+}
 
-    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-        label: Some("Trail Compute Bind Group"),
-        layout: &bind_group_layout,
-        entries: &[
-            wgpu::BindGroupEntry {
-                binding: 0,
-                resource: particle_buffer.as_entire_binding(),
-            },
-            wgpu::BindGroupEntry {
-                binding: 1,
-                resource: trail_buffer.as_entire_binding(),
-            },
-            wgpu::BindGroupEntry {
-                binding: 2,
-                resource: params_buffer.as_entire_binding(),
-            },
-        ],
+#[allow(clippy::too_many_arguments)]
+fn create_compute_pipeline(
+    device: &wgpu::Device,
+    particle_buffer: &wgpu::Buffer,
+    trail_buffer: &wgpu::Buffer,
+    params_buffer: &wgpu::Buffer,
+    particle_stride: usize,
+    color_offset: Option<u32>,
+    velocity_offset: Option<u32>,
+    workgroup_size: u32,
+) -> (wgpu::ComputePipeline, wgpu::BindGroup) {
+    let shader_src = generate_compute_shader(particle_stride, color_offset, velocity_offset);
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Trail Compute Shader"),
+        source: wgpu::ShaderSource::Wgsl(shader_src.as_str().into()),
     });
 
+    let bind_group_layout = create_storage_compute_layout(device, "Trail Compute Bind Group Layout");
+
+    let bind_group = create_compute_bind_group(
+        device,
+        &bind_group_layout,
+        particle_buffer,
+        trail_buffer,
+        params_buffer,
+    );
+
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
         label: Some("Trail Compute Pipeline Layout"),
         bind_group_layouts: &[&bind_group_layout],
@@ -191,23 +375,74 @@ fn create_compute_pipeline(
         layout: Some(&pipeline_layout),
         module: &shader,
         entry_point: Some("main"),
-        compilation_options: Default::default(),
+        compilation_options: wgpu::PipelineCompilationOptions {
+            constants: &workgroup_size_constants(&shader_src, workgroup_size),
+            ..Default::default()
+        },
         cache: None,
     });
 
     (pipeline, bind_group)
 }
 
+fn create_ribbon_pipeline(
+    device: &wgpu::Device,
+    trail_buffer: &wgpu::Buffer,
+    ribbon_buffer: &wgpu::Buffer,
+    params_buffer: &wgpu::Buffer,
+    workgroup_size: u32,
+) -> (wgpu::ComputePipeline, wgpu::BindGroup) {
+    let shader_src = generate_ribbon_shader();
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Trail Ribbon Shader"),
+        source: wgpu::ShaderSource::Wgsl(shader_src.as_str().into()),
+    });
+
+    let bind_group_layout = create_storage_compute_layout(device, "Trail Ribbon Bind Group Layout");
+
+    let bind_group = create_ribbon_bind_group(
+        device,
+        &bind_group_layout,
+        trail_buffer,
+        ribbon_buffer,
+        params_buffer,
+    );
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Trail Ribbon Pipeline Layout"),
+        bind_group_layouts: &[&bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("Trail Ribbon Pipeline"),
+        layout: Some(&pipeline_layout),
+        module: &shader,
+        entry_point: Some("main"),
+        compilation_options: wgpu::PipelineCompilationOptions {
+            constants: &workgroup_size_constants(&shader_src, workgroup_size),
+            ..Default::default()
+        },
+        cache: None,
+    });
+
+    (pipeline, bind_group)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn create_render_pipeline(
     device: &wgpu::Device,
     uniform_buffer: &wgpu::Buffer,
-    trail_buffer: &wgpu::Buffer,
+    ribbon_buffer: &wgpu::Buffer,
     params_buffer: &wgpu::Buffer,
     particle_size: f32,
+    tail_color: Option<Vec3>,
+    trail_config: &TrailConfig,
     blend_mode: BlendMode,
     surface_format: wgpu::TextureFormat,
+    sample_count: u32,
 ) -> (wgpu::RenderPipeline, wgpu::BindGroup) {
-    let shader_src = generate_render_shader(particle_size);
+    let shader_src = generate_render_shader(particle_size, tail_color, trail_config);
     let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
         label: Some("Trail Render Shader"),
         source: wgpu::ShaderSource::Wgsl(shader_src.into()),
@@ -249,24 +484,13 @@ fn create_render_pipeline(
         ],
     });
 
-    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-        label: Some("Trail Render Bind Group"),
-        layout: &bind_group_layout,
-        entries: &[
-            wgpu::BindGroupEntry {
-                binding: 0,
-                resource: uniform_buffer.as_entire_binding(),
-            },
-            wgpu::BindGroupEntry {
-                binding: 1,
-                resource: trail_buffer.as_entire_binding(),
-            },
-            wgpu::BindGroupEntry {
-                binding: 2,
-                resource: params_buffer.as_entire_binding(),
-            },
-        ],
-    });
+    let bind_group = create_render_bind_group(
+        device,
+        &bind_group_layout,
+        uniform_buffer,
+        ribbon_buffer,
+        params_buffer,
+    );
 
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
         label: Some("Trail Render Pipeline Layout"),
@@ -294,7 +518,7 @@ fn create_render_pipeline(
             compilation_options: Default::default(),
         }),
         primitive: wgpu::PrimitiveState {
-            topology: wgpu::PrimitiveTopology::TriangleList,
+            topology: wgpu::PrimitiveTopology::TriangleStrip,
             strip_index_format: None,
             front_face: wgpu::FrontFace::Ccw,
             cull_mode: None,
@@ -309,7 +533,10 @@ fn create_render_pipeline(
             stencil: wgpu::StencilState::default(),
             bias: wgpu::DepthBiasState::default(),
         }),
-        multisample: wgpu::MultisampleState::default(),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
         multiview: None,
         cache: None,
     });
@@ -317,8 +544,16 @@ fn create_render_pipeline(
     (pipeline, bind_group)
 }
 
-fn generate_compute_shader(particle_stride: usize, color_offset: Option<u32>) -> String {
+fn generate_compute_shader(particle_stride: usize, color_offset: Option<u32>, velocity_offset: Option<u32>) -> String {
     let particle_stride_vec4 = particle_stride / 16;
+    // Without a velocity field the ribbon falls back to point-to-point tangents
+    let velocity_code = match velocity_offset {
+        Some(offset) => format!(
+            "    let velocity = particles[particle_idx * {particle_stride_vec4}u + {}u].xyz;",
+            offset as usize / 16
+        ),
+        None => "    let velocity = vec3<f32>(0.0);".to_string(),
+    };
 
     // Color offset in vec4 units (if available)
     let color_code = if let Some(offset) = color_offset {
@@ -352,30 +587,37 @@ var<storage, read_write> trails: array<vec4<f32>>;
 @group(0) @binding(2)
 var<uniform> params: TrailParams;
 
-@compute @workgroup_size(256)
+override WORKGROUP_SIZE: u32 = 256u;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
     let particle_idx = global_id.x;
     if particle_idx >= params.num_particles {{
         return;
     }}
 
-    // Each trail point is 2 vec4s: (position, valid) + (color, unused)
-    let trail_base = particle_idx * params.trail_length * 2u;
+    // Each trail point is 3 vec4s: (position, valid) + (color, gradient key) + (velocity, unused)
+    let trail_base = particle_idx * params.trail_length * 3u;
+    let key_scale = 1.0 / f32(max(params.trail_length - 1u, 1u));
 
     // Shift trail positions back (from end to start)
     for (var i = params.trail_length - 1u; i > 0u; i--) {{
-        let dst = trail_base + i * 2u;
-        let src = trail_base + (i - 1u) * 2u;
+        let dst = trail_base + i * 3u;
+        let src = trail_base + (i - 1u) * 3u;
         trails[dst] = trails[src];         // position
-        trails[dst + 1u] = trails[src + 1u]; // color
+        // Color, keyed by normalized age position along the trail
+        trails[dst + 1u] = vec4<f32>(trails[src + 1u].xyz, f32(i) * key_scale);
+        trails[dst + 2u] = trails[src + 2u]; // velocity
     }}
 
     // Store current position at front with full alpha
     let pos = particles[particle_idx * {particle_stride_vec4}u];
+{velocity_code}
 {color_code}
 
     trails[trail_base] = vec4<f32>(pos.xyz, 1.0);
-    trails[trail_base + 1u] = vec4<f32>(color, 1.0);
+    trails[trail_base + 1u] = vec4<f32>(color, 0.0);
+    trails[trail_base + 2u] = vec4<f32>(velocity, 0.0);
 }}
 "#,
         particle_stride_vec4 = particle_stride_vec4,
@@ -383,9 +625,149 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
     )
 }
 
-fn generate_render_shader(particle_size: f32) -> String {
+/// WGSL layout of one interpolated ribbon sample. Matches `RIBBON_VERTEX_SIZE`.
+const RIBBON_VERTEX_WGSL: &str = r#"
+struct TrailRibbonVertex {
+    position: vec3<f32>,
+    // Position along the trail in trail points / trail_length
+    progress: f32,
+    color: vec3<f32>,
+    gradient_key: f32,
+    tangent: vec3<f32>,
+    valid: f32,
+};
+"#;
+
+/// Ribbon generation shader: cubic Hermite interpolation between trail points.
+///
+/// Tangents are Catmull-Rom (half the difference of the neighboring points).
+/// The newest point has no newer neighbor, so its tangent follows the
+/// particle's velocity instead.
+fn generate_ribbon_shader() -> String {
     format!(
-        r#"
+        r#"{RIBBON_VERTEX_WGSL}
+struct TrailParams {{
+    num_particles: u32,
+    trail_length: u32,
+}};
+
+const SUBDIVISIONS: u32 = {TRAIL_SUBDIVISIONS}u;
+
+@group(0) @binding(0)
+var<storage, read> trails: array<vec4<f32>>;
+
+@group(0) @binding(1)
+var<storage, read_write> ribbon: array<TrailRibbonVertex>;
+
+@group(0) @binding(2)
+var<uniform> params: TrailParams;
+
+override WORKGROUP_SIZE: u32 = 256u;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
+    let particle_idx = global_id.x;
+    if particle_idx >= params.num_particles {{
+        return;
+    }}
+
+    let len = params.trail_length;
+    let trail_base = particle_idx * len * 3u;
+    let last = len - 1u;
+    let samples = last * SUBDIVISIONS + 1u;
+    let ribbon_base = particle_idx * samples;
+
+    // A single point has no span to draw
+    if len < 2u {{
+        var empty: TrailRibbonVertex;
+        ribbon[ribbon_base] = empty;
+        return;
+    }}
+
+    for (var s = 0u; s < samples; s++) {{
+        let seg = min(s / SUBDIVISIONS, last - 1u);
+        let f = f32(s - seg * SUBDIVISIONS) / f32(SUBDIVISIONS);
+
+        let p0 = trails[trail_base + select(seg - 1u, 0u, seg == 0u) * 3u];
+        let p1 = trails[trail_base + seg * 3u];
+        let p2 = trails[trail_base + (seg + 1u) * 3u];
+        let p3 = trails[trail_base + min(seg + 2u, last) * 3u];
+
+        // Tangent at p1: velocity for the newest point (trail runs against it)
+        var m1 = (p2.xyz - p0.xyz) * 0.5;
+        if seg == 0u {{
+            let velocity = trails[trail_base + 2u].xyz;
+            let span = length(p2.xyz - p1.xyz);
+            m1 = select(p2.xyz - p1.xyz, -normalize(velocity) * span, length(velocity) > 0.000001);
+        }}
+        // Tangent at p2: one-sided at the oldest or last valid point
+        var m2 = (p3.xyz - p1.xyz) * 0.5;
+        if seg + 2u > last || p3.w < 0.5 {{
+            m2 = p2.xyz - p1.xyz;
+        }}
+
+        // Cubic Hermite basis and derivatives
+        let f2 = f * f;
+        let f3 = f2 * f;
+        let h00 = 2.0 * f3 - 3.0 * f2 + 1.0;
+        let h10 = f3 - 2.0 * f2 + f;
+        let h01 = -2.0 * f3 + 3.0 * f2;
+        let h11 = f3 - f2;
+        let d00 = 6.0 * f2 - 6.0 * f;
+        let d10 = 3.0 * f2 - 4.0 * f + 1.0;
+        let d01 = -6.0 * f2 + 6.0 * f;
+        let d11 = 3.0 * f2 - 2.0 * f;
+
+        let c1 = trails[trail_base + seg * 3u + 1u];
+        let c2 = trails[trail_base + (seg + 1u) * 3u + 1u];
+
+        var v: TrailRibbonVertex;
+        v.position = h00 * p1.xyz + h10 * m1 + h01 * p2.xyz + h11 * m2;
+        v.tangent = d00 * p1.xyz + d10 * m1 + d01 * p2.xyz + d11 * m2;
+        v.progress = (f32(seg) + f) / f32(len);
+        v.color = mix(c1.xyz, c2.xyz, f);
+        v.gradient_key = mix(c1.w, c2.w, f);
+        // The sample on p1 itself only needs p1
+        v.valid = select(min(p1.w, p2.w), p1.w, s == seg * SUBDIVISIONS);
+        ribbon[ribbon_base + s] = v;
+    }}
+}}
+"#
+    )
+}
+
+fn generate_render_shader(particle_size: f32, tail_color: Option<Vec3>, trail_config: &TrailConfig) -> String {
+    // Trails keep the particle's color unless a tail color is set
+    let tail_code = match tail_color {
+        Some(tail) => format!(
+            r#"    // Blend from the particle's color toward the tail color as the trail ages
+    let tail_color = vec3<f32>({:.6}, {:.6}, {:.6});
+    out.color = mix(tail_color, v.color, alpha_factor);"#,
+            tail.x, tail.y, tail.z
+        ),
+        None => "    out.color = v.color;".to_string(),
+    };
+
+    // Gradient stops replace the particle color to tail color blend
+    let (gradient_fn, color_code) = match trail_config.to_wgsl() {
+        Some(gradient_fn) => (
+            gradient_fn,
+            r#"    let gradient = trail_gradient(v.gradient_key);
+    out.color = gradient.rgb;
+    out.alpha = gradient.a * 0.5;"#
+                .to_string(),
+        ),
+        None => (
+            String::new(),
+            format!("    out.alpha = alpha_factor * 0.5;\n{tail_code}"),
+        ),
+    };
+
+    let width_taper = trail_config.width_taper;
+    let width_head_scale = trail_config.width_head_scale;
+
+    format!(
+        r#"{RIBBON_VERTEX_WGSL}
 struct Uniforms {{
     view_proj: mat4x4<f32>,
     time: f32,
@@ -401,16 +783,18 @@ struct TrailParams {{
 var<uniform> uniforms: Uniforms;
 
 @group(0) @binding(1)
-var<storage, read> trails: array<vec4<f32>>;
+var<storage, read> ribbon: array<TrailRibbonVertex>;
 
 @group(0) @binding(2)
 var<uniform> params: TrailParams;
 
+{gradient_fn}
 struct VertexOutput {{
     @builtin(position) clip_position: vec4<f32>,
     @location(0) alpha: f32,
-    @location(1) uv: vec2<f32>,
+    @location(1) edge: f32,
     @location(2) color: vec3<f32>,
+    @location(3) valid: f32,
 }};
 
 @vertex
@@ -420,72 +804,110 @@ fn vs_main(
 ) -> VertexOutput {{
     var out: VertexOutput;
 
-    // Decode particle and trail index from instance
-    let particle_idx = instance_index / params.trail_length;
-    let trail_idx = instance_index % params.trail_length;
-
-    // Get trail data (2 vec4s per point: position + color)
-    let trail_base = particle_idx * params.trail_length * 2u;
-    let point_base = trail_base + trail_idx * 2u;
-    let trail_pos_data = trails[point_base];
-    let trail_color_data = trails[point_base + 1u];
+    // One strip per particle, two vertices (left/right edge) per sample
+    let samples = (params.trail_length - 1u) * {TRAIL_SUBDIVISIONS}u + 1u;
+    let v = ribbon[instance_index * samples + vertex_index / 2u];
+    let side = select(-1.0, 1.0, vertex_index % 2u == 1u);
 
-    let pos = trail_pos_data.xyz;
-    let valid = trail_pos_data.w;
-    let color = trail_color_data.xyz;
-
-    // Skip invalid trail points
-    if valid < 0.5 {{
+    // Skip invalid samples; strip triangles touching them are discarded
+    if v.valid < 0.5 {{
         out.clip_position = vec4<f32>(0.0, 0.0, -1000.0, 1.0);
         out.alpha = 0.0;
-        out.uv = vec2<f32>(0.0);
+        out.edge = 0.0;
         out.color = vec3<f32>(0.0);
+        out.valid = 0.0;
         return out;
     }}
 
-    // Quad vertices
-    var quad_vertices = array<vec2<f32>, 6>(
-        vec2<f32>(-1.0, -1.0),
-        vec2<f32>( 1.0, -1.0),
-        vec2<f32>(-1.0,  1.0),
-        vec2<f32>(-1.0,  1.0),
-        vec2<f32>( 1.0, -1.0),
-        vec2<f32>( 1.0,  1.0),
-    );
-
-    let quad_pos = quad_vertices[vertex_index];
-
-    // Size decreases along trail, alpha also fades
-    let trail_progress = f32(trail_idx) / f32(params.trail_length);
-    let size_factor = 1.0 - trail_progress * 0.7;
-    let alpha_factor = 1.0 - trail_progress;
+    // Width tapers along trail, alpha also fades
+    let taper_factor = 1.0 - v.progress * {width_taper:.6};
+    let alpha_factor = 1.0 - v.progress;
 
     let base_size = {particle_size};
-    let trail_size = base_size * size_factor * 0.5;
-
-    let world_pos = vec4<f32>(pos, 1.0);
-    var clip_pos = uniforms.view_proj * world_pos;
+    let trail_size = base_size * taper_factor * {width_head_scale:.6};
+
+    // Expand perpendicular to the ribbon's screen-space direction
+    var clip_pos = uniforms.view_proj * vec4<f32>(v.position, 1.0);
+    let tangent = v.tangent / max(length(v.tangent), 0.000001);
+    let ahead = uniforms.view_proj * vec4<f32>(v.position + tangent * 0.001, 1.0);
+    var dir = ahead.xy / ahead.w - clip_pos.xy / clip_pos.w;
+    if length(dir) < 0.0000001 {{
+        dir = vec2<f32>(1.0, 0.0);
+    }}
+    let normal = normalize(vec2<f32>(-dir.y, dir.x));
 
-    clip_pos.x += quad_pos.x * trail_size * clip_pos.w;
-    clip_pos.y += quad_pos.y * trail_size * clip_pos.w;
+    clip_pos.x += normal.x * side * trail_size * clip_pos.w;
+    clip_pos.y += normal.y * side * trail_size * clip_pos.w;
 
     out.clip_position = clip_pos;
-    out.alpha = alpha_factor * 0.5;
-    out.uv = quad_pos;
-    out.color = color;
+    out.edge = side;
+    out.valid = 1.0;
+{color_code}
 
     return out;
 }}
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {{
-    let dist = length(in.uv);
-    if dist > 1.0 {{
+    if in.valid < 0.999 {{
         discard;
     }}
-    let circle_alpha = 1.0 - smoothstep(0.3, 1.0, dist);
-    return vec4<f32>(in.color, circle_alpha * in.alpha);
+    let edge_alpha = 1.0 - smoothstep(0.3, 1.0, abs(in.edge));
+    return vec4<f32>(in.color, edge_alpha * in.alpha);
 }}
 "#
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(src: &str) {
+        let module = naga::front::wgsl::parse_str(src)
+            .unwrap_or_else(|e| panic!("Trail shader failed to parse: {e:?}\n{src}"));
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .unwrap_or_else(|e| panic!("Trail shader failed to validate: {e:?}"));
+    }
+
+    #[test]
+    fn test_trail_shaders_validate() {
+        validate(&generate_compute_shader(64, Some(32), Some(16)));
+        validate(&generate_compute_shader(64, None, None));
+        validate(&generate_ribbon_shader());
+        validate(&generate_render_shader(0.01, None, &TrailConfig::default()));
+        validate(&generate_render_shader(0.01, Some(Vec3::ZERO), &TrailConfig::default()));
+    }
+
+    #[test]
+    fn test_trail_keeps_particle_color_without_tail_color() {
+        let plain = generate_render_shader(0.01, None, &TrailConfig::default());
+        assert!(plain.contains("out.color = v.color;"));
+        assert!(!plain.contains("tail_color"));
+
+        let faded = generate_render_shader(0.01, Some(Vec3::new(0.1, 0.3, 1.0)), &TrailConfig::default());
+        assert!(faded.contains("mix(tail_color, v.color, alpha_factor)"));
+    }
+
+    #[test]
+    fn test_trail_gradient_shader_validates() {
+        let config = TrailConfig {
+            color_gradient: vec![([1.0, 0.8, 0.2, 1.0], 0.0), ([1.0, 0.2, 0.0, 1.0], 0.5)],
+            fade_to_transparent: true,
+            ..Default::default()
+        };
+        let src = generate_render_shader(0.01, None, &config);
+        assert!(src.contains("fn trail_gradient"));
+        validate(&src);
+    }
+
+    #[test]
+    fn test_ribbon_sample_count() {
+        assert_eq!(ribbon_samples(1), 1);
+        assert_eq!(ribbon_samples(10), 9 * TRAIL_SUBDIVISIONS + 1);
+    }
+}
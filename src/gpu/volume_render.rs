@@ -15,12 +15,37 @@ use crate::visuals::Palette;
 pub struct VolumeConfig {
     /// Which field index to render (default: 0).
     pub field_index: u32,
-    /// Number of ray march steps (higher = better quality, slower).
-    pub steps: u32,
+    /// Maximum number of ray march steps (higher = better quality, slower).
+    pub step_count: u32,
+    /// Distance between ray march samples in world units.
+    ///
+    /// `0.0` (the default) spreads `step_count` samples across the full
+    /// width of the field (`2 * world_extent / step_count`).
+    pub step_size: f32,
     /// Density multiplier (higher = more opaque).
     pub density_scale: f32,
+    /// Absorption multiplier applied to each sample's opacity.
+    ///
+    /// Unlike `density_scale`, this does not change which palette color a
+    /// density maps to, only how quickly the volume becomes opaque.
+    pub density_multiplier: f32,
     /// Color palette for density mapping.
+    ///
+    /// Used when `transfer_function` is empty.
     pub palette: Palette,
+    /// Color and opacity curve as `(rgba, value)` stops.
+    ///
+    /// Values are in units of `density * density_scale`; the curve is
+    /// sampled from `0` to the largest stop value and clamped outside it.
+    /// When empty, the palette is used with opacity rising linearly from
+    /// 0 at value 0 to 1 at value 1.
+    pub transfer_function: Vec<([f32; 4], f32)>,
+    /// Clip planes as `[a, b, c, d]` equations of `ax + by + cz + d = 0`.
+    ///
+    /// Samples where `ax + by + cz + d < 0` are skipped, so only the
+    /// positive side of every plane is drawn. At most four planes are
+    /// used; extra planes are ignored.
+    pub clip_planes: Vec<[f32; 4]>,
     /// Minimum density threshold (values below are transparent).
     pub threshold: f32,
     /// Whether to use additive blending (glow effect).
@@ -31,9 +56,13 @@ impl Default for VolumeConfig {
     fn default() -> Self {
         Self {
             field_index: 0,
-            steps: 64,
+            step_count: 64,
+            step_size: 0.0,
             density_scale: 5.0,
+            density_multiplier: 1.0,
             palette: Palette::Inferno,
+            transfer_function: Vec::new(),
+            clip_planes: Vec::new(),
             threshold: 0.01,
             additive: true,
         }
@@ -52,9 +81,17 @@ impl VolumeConfig {
         self
     }
 
-    /// Set the number of ray march steps.
+    /// Set the maximum number of ray march steps (clamped to 8–512).
     pub fn with_steps(mut self, steps: u32) -> Self {
-        self.steps = steps.clamp(8, 256);
+        self.step_count = steps.clamp(8, 512);
+        self
+    }
+
+    /// Set the distance between ray march samples in world units.
+    ///
+    /// Pass `0.0` to derive it from the field extent and step count.
+    pub fn with_step_size(mut self, step_size: f32) -> Self {
+        self.step_size = step_size.max(0.0);
         self
     }
 
@@ -64,12 +101,93 @@ impl VolumeConfig {
         self
     }
 
+    /// Set the absorption multiplier.
+    pub fn with_density_multiplier(mut self, multiplier: f32) -> Self {
+        self.density_multiplier = multiplier.max(0.0);
+        self
+    }
+
+    /// Step size actually used for a field of the given extent.
+    pub fn effective_step_size(&self, world_extent: f32) -> f32 {
+        if self.step_size > 0.0 {
+            self.step_size
+        } else {
+            2.0 * world_extent / self.step_count.max(1) as f32
+        }
+    }
+
     /// Set the color palette.
     pub fn with_palette(mut self, palette: Palette) -> Self {
         self.palette = palette;
         self
     }
 
+    /// Set the transfer function as `(rgba, value)` stops.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Transparent below 0.2, blue haze, then opaque white cores
+    /// VolumeConfig::new().with_transfer_function(vec![
+    ///     ([0.0, 0.0, 0.0, 0.0], 0.2),
+    ///     ([0.2, 0.4, 1.0, 0.3], 0.5),
+    ///     ([1.0, 1.0, 1.0, 1.0], 1.0),
+    /// ])
+    /// ```
+    pub fn with_transfer_function(mut self, stops: Vec<([f32; 4], f32)>) -> Self {
+        self.transfer_function = stops;
+        self
+    }
+
+    /// Transfer function stops sorted by value, derived from the palette
+    /// when no custom stops are set.
+    pub fn transfer_function_stops(&self) -> Vec<([f32; 4], f32)> {
+        let mut stops: Vec<([f32; 4], f32)> = if self.transfer_function.is_empty() {
+            self.palette
+                .colors()
+                .iter()
+                .enumerate()
+                .map(|(i, c)| {
+                    let t = i as f32 / 4.0;
+                    ([c.x, c.y, c.z, t], t)
+                })
+                .collect()
+        } else {
+            self.transfer_function.clone()
+        };
+        stops.sort_by(|a, b| a.1.total_cmp(&b.1));
+        stops
+    }
+
+    /// Interpolate sorted transfer function stops at `value`, clamping to
+    /// the first and last stop.
+    pub fn sample_stops(stops: &[([f32; 4], f32)], value: f32) -> [f32; 4] {
+        match stops.iter().position(|s| s.1 >= value) {
+            None => stops.last().map_or([0.0; 4], |s| s.0),
+            Some(0) => stops[0].0,
+            Some(i) => {
+                let (c0, v0) = stops[i - 1];
+                let (c1, v1) = stops[i];
+                let t = (value - v0) / (v1 - v0).max(1e-6);
+                std::array::from_fn(|k| c0[k] + (c1[k] - c0[k]) * t)
+            }
+        }
+    }
+
+    /// Add a clip plane `ax + by + cz + d = 0`, keeping the side where the
+    /// equation is positive.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Cut the volume open along the XY plane, showing only z >= 0
+    /// VolumeConfig::new().with_clip_plane([0.0, 0.0, 1.0, 0.0])
+    /// ```
+    pub fn with_clip_plane(mut self, plane: [f32; 4]) -> Self {
+        self.clip_planes.push(plane);
+        self
+    }
+
     /// Set the minimum density threshold.
     pub fn with_threshold(mut self, threshold: f32) -> Self {
         self.threshold = threshold.max(0.0);
@@ -91,8 +209,8 @@ struct VolumeParams {
     inv_view_proj: [[f32; 4]; 4],
     /// Camera position in world space.
     camera_pos: [f32; 3],
-    /// Number of ray march steps.
-    steps: u32,
+    /// Maximum number of ray march steps.
+    step_count: u32,
     /// Field extent (world space bounds).
     field_extent: f32,
     /// Field resolution.
@@ -101,8 +219,47 @@ struct VolumeParams {
     density_scale: f32,
     /// Minimum density threshold.
     threshold: f32,
-    /// Palette colors (5 stops).
-    palette: [[f32; 4]; 5],
+    /// Distance between samples in world units.
+    step_size: f32,
+    /// Absorption multiplier.
+    density_multiplier: f32,
+    /// Components per cell (1 = scalar, 2 = Gray-Scott, 3 = vector).
+    field_components: u32,
+    /// Value at the right edge of the transfer function texture.
+    tf_max_value: f32,
+    /// Clip plane equations, one per column.
+    clip_planes: [[f32; 4]; MAX_CLIP_PLANES],
+    /// Number of active clip planes.
+    clip_plane_count: u32,
+    _padding: [u32; 3],
+}
+
+/// Maximum number of clip planes passed to the volume shader.
+const MAX_CLIP_PLANES: usize = 4;
+
+/// Pack clip planes into the shader's matrix (one plane per column).
+fn pack_clip_planes(planes: &[[f32; 4]]) -> ([[f32; 4]; MAX_CLIP_PLANES], u32) {
+    let mut packed = [[0.0; 4]; MAX_CLIP_PLANES];
+    let count = planes.len().min(MAX_CLIP_PLANES);
+    packed[..count].copy_from_slice(&planes[..count]);
+    (packed, count as u32)
+}
+
+/// Width of the transfer function lookup texture.
+const TRANSFER_FUNCTION_SIZE: u32 = 256;
+
+/// Bake sorted transfer function stops into RGBA8 texels.
+///
+/// Returns the texels and the value mapped to the last texel.
+fn bake_transfer_function(stops: &[([f32; 4], f32)]) -> (Vec<u8>, f32) {
+    let max_value = stops.last().map_or(1.0, |s| s.1).max(1e-6);
+    let mut texels = Vec::with_capacity(TRANSFER_FUNCTION_SIZE as usize * 4);
+    for i in 0..TRANSFER_FUNCTION_SIZE {
+        let value = i as f32 / (TRANSFER_FUNCTION_SIZE - 1) as f32 * max_value;
+        let color = VolumeConfig::sample_stops(stops, value);
+        texels.extend(color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8));
+    }
+    (texels, max_value)
 }
 
 /// GPU state for volume rendering.
@@ -116,16 +273,25 @@ pub struct VolumeRenderState {
     pub bind_group: wgpu::BindGroup,
     /// Volume parameters buffer.
     pub params_buffer: wgpu::Buffer,
+    /// Transfer function lookup texture, rewritten in place when the stops change.
+    tf_texture: wgpu::Texture,
+    /// Transfer function lookup texture view.
+    tf_view: wgpu::TextureView,
+    /// Value mapped to the right edge of the transfer function.
+    tf_max_value: f32,
     /// Configuration.
     pub config: VolumeConfig,
     /// Field index being rendered.
     pub field_index: usize,
+    /// Components per cell of the rendered field.
+    field_components: u32,
 }
 
 impl VolumeRenderState {
     /// Create a new volume render system.
     pub fn new(
         device: &wgpu::Device,
+        queue: &wgpu::Queue,
         field_system: &FieldSystemGpu,
         config: &VolumeConfig,
         surface_format: wgpu::TextureFormat,
@@ -136,24 +302,48 @@ impl VolumeRenderState {
         let field = &field_system.fields[field_index];
         let field_extent = field.config.world_extent;
         let field_resolution = field.config.resolution;
+        let field_components = field.config.components();
+
+        // Bake the transfer function into a 1D lookup texture
+        let (tf_texels, tf_max_value) = bake_transfer_function(&config.transfer_function_stops());
+        let tf_texture = device.create_texture_with_data(
+            queue,
+            &wgpu::TextureDescriptor {
+                label: Some("Volume Transfer Function"),
+                size: wgpu::Extent3d {
+                    width: TRANSFER_FUNCTION_SIZE,
+                    height: 1,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D1,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            },
+            wgpu::util::TextureDataOrder::LayerMajor,
+            &tf_texels,
+        );
+        let tf_view = tf_texture.create_view(&wgpu::TextureViewDescriptor::default());
 
         // Create params buffer with placeholder values (updated each frame)
-        let palette_colors = config.palette.colors();
+        let (clip_planes, clip_plane_count) = pack_clip_planes(&config.clip_planes);
         let params = VolumeParams {
             inv_view_proj: glam::Mat4::IDENTITY.to_cols_array_2d(),
             camera_pos: [0.0, 0.0, 3.0],
-            steps: config.steps,
+            step_count: config.step_count,
             field_extent,
             field_resolution,
             density_scale: config.density_scale,
             threshold: config.threshold,
-            palette: [
-                [palette_colors[0].x, palette_colors[0].y, palette_colors[0].z, 1.0],
-                [palette_colors[1].x, palette_colors[1].y, palette_colors[1].z, 1.0],
-                [palette_colors[2].x, palette_colors[2].y, palette_colors[2].z, 1.0],
-                [palette_colors[3].x, palette_colors[3].y, palette_colors[3].z, 1.0],
-                [palette_colors[4].x, palette_colors[4].y, palette_colors[4].z, 1.0],
-            ],
+            step_size: config.effective_step_size(field_extent),
+            density_multiplier: config.density_multiplier,
+            field_components,
+            tf_max_value,
+            clip_planes,
+            clip_plane_count,
+            _padding: [0; 3],
         };
 
         let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -188,6 +378,17 @@ impl VolumeRenderState {
                     },
                     count: None,
                 },
+                // Transfer function texture
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D1,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
             ],
         });
 
@@ -204,6 +405,10 @@ impl VolumeRenderState {
                     binding: 1,
                     resource: field.current_read_buffer().as_entire_binding(),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(&tf_view),
+                },
             ],
         });
 
@@ -271,8 +476,12 @@ impl VolumeRenderState {
             bind_group_layout,
             bind_group,
             params_buffer,
+            tf_texture,
+            tf_view,
+            tf_max_value,
             config: config.clone(),
             field_index,
+            field_components,
         }
     }
 
@@ -296,10 +505,35 @@ impl VolumeRenderState {
                     binding: 1,
                     resource: field.current_read_buffer().as_entire_binding(),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(&self.tf_view),
+                },
             ],
         });
     }
 
+    /// Replace the transfer function without rebuilding the pipeline.
+    ///
+    /// Rewrites the lookup texture in place; `config` supplies the stops
+    /// (or the palette when it has none).
+    pub fn set_transfer_function(&mut self, queue: &wgpu::Queue, config: &VolumeConfig) {
+        let (tf_texels, tf_max_value) = bake_transfer_function(&config.transfer_function_stops());
+        queue.write_texture(
+            self.tf_texture.as_image_copy(),
+            &tf_texels,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(TRANSFER_FUNCTION_SIZE * 4),
+                rows_per_image: None,
+            },
+            self.tf_texture.size(),
+        );
+        self.tf_max_value = tf_max_value;
+        self.config.transfer_function = config.transfer_function.clone();
+        self.config.palette = config.palette;
+    }
+
     /// Update parameters with field info.
     pub fn update_params_with_field(
         &self,
@@ -309,23 +543,22 @@ impl VolumeRenderState {
         field_extent: f32,
         field_resolution: u32,
     ) {
-        let palette_colors = self.config.palette.colors();
-
+        let (clip_planes, clip_plane_count) = pack_clip_planes(&self.config.clip_planes);
         let params = VolumeParams {
             inv_view_proj: inv_view_proj.to_cols_array_2d(),
             camera_pos: camera_pos.to_array(),
-            steps: self.config.steps,
+            step_count: self.config.step_count,
             field_extent,
             field_resolution,
             density_scale: self.config.density_scale,
             threshold: self.config.threshold,
-            palette: [
-                [palette_colors[0].x, palette_colors[0].y, palette_colors[0].z, 1.0],
-                [palette_colors[1].x, palette_colors[1].y, palette_colors[1].z, 1.0],
-                [palette_colors[2].x, palette_colors[2].y, palette_colors[2].z, 1.0],
-                [palette_colors[3].x, palette_colors[3].y, palette_colors[3].z, 1.0],
-                [palette_colors[4].x, palette_colors[4].y, palette_colors[4].z, 1.0],
-            ],
+            step_size: self.config.effective_step_size(field_extent),
+            density_multiplier: self.config.density_multiplier,
+            field_components: self.field_components,
+            tf_max_value: self.tf_max_value,
+            clip_planes,
+            clip_plane_count,
+            _padding: [0; 3],
         };
 
         queue.write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&params));
@@ -346,12 +579,20 @@ const VOLUME_SHADER: &str = r#"
 struct VolumeParams {
     inv_view_proj: mat4x4<f32>,
     camera_pos: vec3<f32>,
-    steps: u32,
+    step_count: u32,
     field_extent: f32,
     field_resolution: u32,
     density_scale: f32,
     threshold: f32,
-    palette: array<vec4<f32>, 5>,
+    step_size: f32,
+    density_multiplier: f32,
+    field_components: u32,
+    tf_max_value: f32,
+    clip_planes: mat4x4<f32>,
+    clip_plane_count: u32,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
 };
 
 struct VertexOutput {
@@ -365,6 +606,9 @@ var<uniform> params: VolumeParams;
 @group(0) @binding(1)
 var<storage, read> field: array<f32>;
 
+@group(0) @binding(2)
+var tf_texture: texture_1d<f32>;
+
 // Fullscreen triangle vertex shader
 @vertex
 fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
@@ -386,6 +630,17 @@ fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
     return out;
 }
 
+// Value of one cell (vector fields are rendered as magnitude, Gray-Scott fields as V)
+fn cell_value(idx: u32) -> f32 {
+    if params.field_components == 2u {
+        return field[idx * 2u + 1u];
+    }
+    if params.field_components == 3u {
+        return length(vec3<f32>(field[idx * 3u], field[idx * 3u + 1u], field[idx * 3u + 2u]));
+    }
+    return field[idx];
+}
+
 // Sample the field at a world position
 fn sample_field(pos: vec3<f32>) -> f32 {
     let extent = params.field_extent;
@@ -416,14 +671,14 @@ fn sample_field(pos: vec3<f32>) -> f32 {
     let idx011 = cell.x + min(cell.y + 1u, res - 1u) * res + min(cell.z + 1u, res - 1u) * res * res;
     let idx111 = min(cell.x + 1u, res - 1u) + min(cell.y + 1u, res - 1u) * res + min(cell.z + 1u, res - 1u) * res * res;
 
-    let v000 = field[idx000];
-    let v100 = field[idx100];
-    let v010 = field[idx010];
-    let v110 = field[idx110];
-    let v001 = field[idx001];
-    let v101 = field[idx101];
-    let v011 = field[idx011];
-    let v111 = field[idx111];
+    let v000 = cell_value(idx000);
+    let v100 = cell_value(idx100);
+    let v010 = cell_value(idx010);
+    let v110 = cell_value(idx110);
+    let v001 = cell_value(idx001);
+    let v101 = cell_value(idx101);
+    let v011 = cell_value(idx011);
+    let v111 = cell_value(idx111);
 
     let v00 = mix(v000, v100, frac.x);
     let v10 = mix(v010, v110, frac.x);
@@ -435,17 +690,25 @@ fn sample_field(pos: vec3<f32>) -> f32 {
     return mix(v0, v1, frac.z);
 }
 
-// Sample palette color from normalized value (0-1)
-fn sample_palette(t: f32) -> vec3<f32> {
-    let tc = clamp(t, 0.0, 1.0);
-    let scaled = tc * 4.0;
-    let idx = u32(floor(scaled));
-    let frac = fract(scaled);
-
-    let c0 = params.palette[min(idx, 4u)].rgb;
-    let c1 = params.palette[min(idx + 1u, 4u)].rgb;
+// Look up color and opacity for a value in the transfer function,
+// filtering linearly between neighboring texels
+fn sample_transfer_function(value: f32) -> vec4<f32> {
+    let last = textureDimensions(tf_texture) - 1u;
+    let x = clamp(value / params.tf_max_value, 0.0, 1.0) * f32(last);
+    let i = min(u32(x), last);
+    let a = textureLoad(tf_texture, i, 0);
+    let b = textureLoad(tf_texture, min(i + 1u, last), 0);
+    return mix(a, b, fract(x));
+}
 
-    return mix(c0, c1, frac);
+// Whether a position lies on the negative side of any clip plane
+fn is_clipped(pos: vec3<f32>) -> bool {
+    for (var i = 0u; i < params.clip_plane_count; i++) {
+        if (dot(params.clip_planes[i], vec4<f32>(pos, 1.0)) < 0.0) {
+            return true;
+        }
+    }
+    return false;
 }
 
 // Ray-box intersection for AABB
@@ -483,29 +746,33 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // Ray march parameters
     let t_start = t_range.x;
     let t_end = t_range.y;
-    let step_size = (t_end - t_start) / f32(params.steps);
+    let step_size = params.step_size;
 
     // Accumulate color and opacity
     var accumulated_color = vec3<f32>(0.0);
     var accumulated_alpha = 0.0;
     var t = t_start;
 
-    for (var i = 0u; i < params.steps; i++) {
-        if (accumulated_alpha >= 0.99) {
+    for (var i = 0u; i < params.step_count; i++) {
+        if (accumulated_alpha >= 0.99 || t > t_end) {
             break;
         }
 
         let pos = ray_origin + ray_dir * t;
+        if (is_clipped(pos)) {
+            t += step_size;
+            continue;
+        }
         let density = sample_field(pos);
 
         if (density > params.threshold) {
-            // Map density to color
-            let normalized_density = clamp(density * params.density_scale, 0.0, 1.0);
-            let color = sample_palette(normalized_density);
+            // Map density to color and opacity
+            let tf = sample_transfer_function(density * params.density_scale);
 
             // Accumulate with front-to-back compositing
-            let sample_alpha = normalized_density * (1.0 - accumulated_alpha) * 0.5;
-            accumulated_color += color * sample_alpha;
+            let absorption = clamp(tf.a * params.density_multiplier * 0.5, 0.0, 1.0);
+            let sample_alpha = absorption * (1.0 - accumulated_alpha);
+            accumulated_color += tf.rgb * sample_alpha;
             accumulated_alpha += sample_alpha;
         }
 
@@ -515,3 +782,67 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     return vec4<f32>(accumulated_color, accumulated_alpha);
 }
 "#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_volume_shader_validates() {
+        let module = naga::front::wgsl::parse_str(VOLUME_SHADER).expect("volume shader should parse");
+        naga::valid::Validator::new(naga::valid::ValidationFlags::all(), naga::valid::Capabilities::all())
+            .validate(&module)
+            .expect("volume shader should validate");
+    }
+
+    #[test]
+    fn test_transfer_function_bake_interpolates_stops() {
+        let stops = [([0.0, 0.0, 0.0, 0.0], 0.0), ([1.0, 0.0, 0.0, 1.0], 2.0)];
+        let (texels, max_value) = bake_transfer_function(&stops);
+        assert_eq!(texels.len(), TRANSFER_FUNCTION_SIZE as usize * 4);
+        assert_eq!(max_value, 2.0);
+        assert_eq!(&texels[..4], &[0, 0, 0, 0]);
+        assert_eq!(&texels[texels.len() - 4..], &[255, 0, 0, 255]);
+        let mid = (TRANSFER_FUNCTION_SIZE as usize / 2) * 4;
+        assert!((texels[mid + 3] as i32 - 128).abs() <= 1);
+    }
+
+    #[test]
+    fn test_sample_stops() {
+        let stops = [([0.0, 0.0, 0.0, 0.0], 0.2), ([1.0, 0.5, 0.0, 1.0], 1.0)];
+        assert_eq!(VolumeConfig::sample_stops(&stops, 0.0), [0.0; 4]);
+        assert_eq!(VolumeConfig::sample_stops(&stops, 2.0), [1.0, 0.5, 0.0, 1.0]);
+        let mid = VolumeConfig::sample_stops(&stops, 0.6);
+        assert!(mid.iter().zip([0.5, 0.25, 0.0, 0.5]).all(|(a, b)| (a - b).abs() < 1e-5));
+        assert_eq!(VolumeConfig::sample_stops(&[], 0.5), [0.0; 4]);
+    }
+
+    #[test]
+    fn test_clip_planes_pack_up_to_four() {
+        assert_eq!(std::mem::size_of::<VolumeParams>(), 192);
+
+        let (packed, count) = pack_clip_planes(&[[0.0, 0.0, 1.0, 0.0]]);
+        assert_eq!(count, 1);
+        assert_eq!(packed[0], [0.0, 0.0, 1.0, 0.0]);
+        assert_eq!(packed[1], [0.0; 4]);
+
+        let planes = vec![[1.0, 0.0, 0.0, 0.0]; 6];
+        let (_, count) = pack_clip_planes(&planes);
+        assert_eq!(count, MAX_CLIP_PLANES as u32);
+    }
+
+    #[test]
+    fn test_default_transfer_function_follows_palette() {
+        let config = VolumeConfig::new();
+        let stops = config.transfer_function_stops();
+        assert_eq!(stops.len(), 5);
+        assert_eq!(stops[0].0[3], 0.0);
+        assert_eq!(stops[4].1, 1.0);
+        assert_eq!(stops[4].0[3], 1.0);
+
+        let custom = config.with_transfer_function(vec![([1.0; 4], 0.8), ([0.0; 4], 0.1)]);
+        let stops = custom.transfer_function_stops();
+        assert_eq!(stops[0].1, 0.1);
+        assert_eq!(stops[1].1, 0.8);
+    }
+}
@@ -59,6 +59,7 @@ impl WireframeState {
         scale_offset: u32,
         blend_mode: BlendMode,
         surface_format: wgpu::TextureFormat,
+        sample_count: u32,
     ) -> Self {
         // Convert mesh lines to flat f32 array
         let mesh_data = mesh.to_vertices();
@@ -147,28 +148,14 @@ impl WireframeState {
             ],
         });
 
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Wireframe Bind Group"),
-            layout: &bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: uniform_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: particle_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: mesh_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 3,
-                    resource: params_buffer.as_entire_binding(),
-                },
-            ],
-        });
+        let bind_group = create_bind_group(
+            device,
+            &bind_group_layout,
+            uniform_buffer,
+            particle_buffer,
+            &mesh_buffer,
+            &params_buffer,
+        );
 
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Wireframe Pipeline Layout"),
@@ -237,7 +224,10 @@ impl WireframeState {
                 stencil: wgpu::StencilState::default(),
                 bias: wgpu::DepthBiasState::default(),
             }),
-            multisample: wgpu::MultisampleState::default(),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
             multiview: None,
             cache: None,
         });
@@ -263,6 +253,25 @@ impl WireframeState {
         &self.bind_group
     }
 
+    /// Rebind to a new particle buffer after the particle count changed.
+    pub fn set_particle_buffer(
+        &mut self,
+        device: &wgpu::Device,
+        particle_buffer: &wgpu::Buffer,
+        uniform_buffer: &wgpu::Buffer,
+        num_particles: u32,
+    ) {
+        self.bind_group = create_bind_group(
+            device,
+            &self.pipeline.get_bind_group_layout(0),
+            uniform_buffer,
+            particle_buffer,
+            &self.mesh_buffer,
+            &self.params_buffer,
+        );
+        self.num_particles = num_particles;
+    }
+
     /// Get the total number of line instances to draw (lines_per_mesh * num_particles).
     pub fn total_line_count(&self) -> u32 {
         self.lines_per_mesh * self.num_particles
@@ -280,6 +289,38 @@ impl WireframeState {
     }
 }
 
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    uniform_buffer: &wgpu::Buffer,
+    particle_buffer: &wgpu::Buffer,
+    mesh_buffer: &wgpu::Buffer,
+    params_buffer: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Wireframe Bind Group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: particle_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: mesh_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 3,
+                resource: params_buffer.as_entire_binding(),
+            },
+        ],
+    })
+}
+
 /// Generate the wireframe rendering shader with correct byte offsets.
 fn generate_wireframe_shader(
     particle_stride: usize,
@@ -22,12 +22,17 @@
 //!     .run();
 //! ```
 
+use serde::{Deserialize, Serialize};
+
 /// Interaction matrix storing force relationships between particle types.
 ///
 /// Each cell `[self_type][other_type]` contains:
 /// - `strength`: positive = attract, negative = repel, zero = ignore
 /// - `radius`: interaction range
-#[derive(Clone, Debug)]
+///
+/// Besides [`Simulation::with_interactions`](crate::Simulation::with_interactions),
+/// a matrix can be used as a rule with [`Rule::TypedMulti`](crate::Rule::TypedMulti).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct InteractionMatrix {
     /// Flattened matrix: [self_type * num_types + other_type] = (strength, radius)
     interactions: Vec<(f32, f32)>,
@@ -73,9 +78,19 @@ impl InteractionMatrix {
         let o = other_type.into() as usize;
         if s < self.num_types && o < self.num_types {
             self.interactions[s * self.num_types + o] = (strength, radius);
-            if radius > self.max_radius {
-                self.max_radius = radius;
-            }
+            self.max_radius = self.interactions.iter().map(|&(_, r)| r).fold(0.0, f32::max);
+        }
+    }
+
+    /// Get the `(strength, radius)` of `self_type` encountering `other_type`.
+    ///
+    /// Returns `(0.0, 0.0)` for types outside the matrix.
+    pub fn get(&self, self_type: u32, other_type: u32) -> (f32, f32) {
+        let (s, o) = (self_type as usize, other_type as usize);
+        if s < self.num_types && o < self.num_types {
+            self.interactions[s * self.num_types + o]
+        } else {
+            (0.0, 0.0)
         }
     }
 
@@ -129,65 +144,160 @@ impl InteractionMatrix {
         self.max_radius
     }
 
+    /// Get the smallest radius among interactions with a non-zero strength.
+    ///
+    /// `None` if every interaction is ignored.
+    pub fn min_radius(&self) -> Option<f32> {
+        self.interactions
+            .iter()
+            .filter(|&&(strength, radius)| strength != 0.0 && radius > 0.0)
+            .map(|&(_, radius)| radius)
+            .reduce(f32::min)
+    }
+
     /// Get raw interaction data for GPU upload.
     pub fn data(&self) -> &[(f32, f32)] {
         &self.interactions
     }
 
-    /// Generate WGSL code for initializing interaction variables.
+    /// Serialize the matrix to pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("interaction matrix serializes to JSON")
+    }
+
+    /// Load a matrix saved with [`to_json`](Self::to_json).
     ///
-    /// This goes before the neighbor loop.
-    pub(crate) fn to_wgsl_init(&self) -> String {
-        // Generate the lookup table as WGSL constants
-        let mut table_entries = Vec::new();
-        for s in 0..self.num_types {
-            for o in 0..self.num_types {
-                let (strength, radius) = self.interactions[s * self.num_types + o];
-                table_entries.push(format!("vec2<f32>({strength}, {radius})"));
-            }
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is malformed or the number of cells
+    /// doesn't match `num_types * num_types`.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let mut matrix: Self = serde_json::from_str(json)?;
+        if matrix.interactions.len() != matrix.num_types * matrix.num_types {
+            return Err(serde::de::Error::custom(format!(
+                "expected {} cells for {} types, found {}",
+                matrix.num_types * matrix.num_types,
+                matrix.num_types,
+                matrix.interactions.len()
+            )));
         }
+        matrix.max_radius = matrix.interactions.iter().map(|&(_, r)| r).fold(0.0, f32::max);
+        Ok(matrix)
+    }
 
-        let table_str = table_entries.join(", ");
-        let num_types = self.num_types;
-        let total = self.num_types * self.num_types;
+    /// Generate module-level WGSL for looking up and applying the matrix.
+    ///
+    /// Declares `name` as an `array<array<f32, N>, N>` constant of strengths
+    /// indexed `[self_type][other_type]`, a matching `{name}_RADIUS` constant,
+    /// and these functions:
+    ///
+    /// - `interaction_strength(a, b) -> f32` and `interaction_radius(a, b) -> f32`,
+    ///   which return `0.0` for types outside the matrix
+    /// - `apply_typed_interaction(p, neighbor, uniforms)`, which applies the
+    ///   force `neighbor` exerts on the particle behind `p`
+    ///
+    /// The declarations can go anywhere in the shader, as long as `Particle`
+    /// and `Uniforms` are defined somewhere in it.
+    pub fn to_wgsl(&self, name: &str) -> String {
+        let num_types = self.num_types.max(1);
+        let rows = |value: fn((f32, f32)) -> f32| -> String {
+            (0..num_types)
+                .map(|s| {
+                    let row: Vec<String> = (0..num_types)
+                        .map(|o| format!("{:?}", value(self.get(s as u32, o as u32))))
+                        .collect();
+                    format!("    array<f32, {num_types}>({})", row.join(", "))
+                })
+                .collect::<Vec<_>>()
+                .join(",\n")
+        };
+        let strengths = rows(|(strength, _)| strength);
+        let radii = rows(|(_, radius)| radius);
 
         format!(
-            r#"    // Interaction matrix lookup table
-    let interaction_table = array<vec2<f32>, {total}>(
-        {table_str}
-    );
-    let my_type = p.particle_type;
-    var interaction_force = vec3<f32>(0.0);
-    let interaction_num_types = {num_types}u;"#
+            r#"// Interaction matrix ({num_types} types), indexed [self_type][other_type]
+const {name}: array<array<f32, {num_types}>, {num_types}> = array<array<f32, {num_types}>, {num_types}>(
+{strengths}
+);
+const {name}_RADIUS: array<array<f32, {num_types}>, {num_types}> = array<array<f32, {num_types}>, {num_types}>(
+{radii}
+);
+
+fn interaction_strength(a: u32, b: u32) -> f32 {{
+    if a >= {num_types}u || b >= {num_types}u {{
+        return 0.0;
+    }}
+    return {name}[a][b];
+}}
+
+fn interaction_radius(a: u32, b: u32) -> f32 {{
+    if a >= {num_types}u || b >= {num_types}u {{
+        return 0.0;
+    }}
+    return {name}_RADIUS[a][b];
+}}
+
+fn apply_typed_interaction(p: ptr<function, Particle>, neighbor: Particle, uniforms: Uniforms) {{
+    let radius = interaction_radius((*p).particle_type, neighbor.particle_type);
+    let diff = neighbor.position - (*p).position;
+    let dist = length(diff);
+    if radius <= 0.0 || dist >= radius || dist <= 0.001 {{
+        return;
+    }}
+    let falloff = 1.0 - dist / radius;
+    let strength = interaction_strength((*p).particle_type, neighbor.particle_type);
+    // Positive strength pulls toward the neighbor
+    (*p).velocity += diff / dist * strength * falloff * falloff * uniforms.delta_time;
+}}
+"#
         )
     }
+}
 
-    /// Generate WGSL code for the neighbor loop body.
-    ///
-    /// This runs inside the neighbor loop with access to:
-    /// - `other` - the neighbor particle
-    /// - `neighbor_dist` - distance to neighbor
-    /// - `neighbor_dir` - direction toward self from neighbor
-    pub(crate) fn to_wgsl_neighbor(&self) -> String {
-        r#"            // Interaction matrix force
-            let other_type = other.particle_type;
-            let lookup_idx = my_type * interaction_num_types + other_type;
-            let interaction = interaction_table[lookup_idx];
-            let int_strength = interaction.x;
-            let int_radius = interaction.y;
-
-            if int_radius > 0.0 && neighbor_dist < int_radius && neighbor_dist > 0.001 {
-                let falloff = 1.0 - (neighbor_dist / int_radius);
-                let force_mag = int_strength * falloff * falloff;
-                interaction_force += neighbor_dir * force_mag;
-            }"#
-        .to_string()
-    }
-
-    /// Generate WGSL code for applying accumulated interaction forces.
-    ///
-    /// This goes after the neighbor loop.
-    pub(crate) fn to_wgsl_post(&self) -> String {
-        "    // Apply interaction matrix forces\n    p.velocity += interaction_force * uniforms.delta_time;".to_string()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut matrix = InteractionMatrix::new(2);
+        matrix.set(0u32, 1u32, 1.5, 0.3);
+        matrix.set(1u32, 0u32, -0.5, 0.2);
+
+        let loaded = InteractionMatrix::from_json(&matrix.to_json()).unwrap();
+        assert_eq!(loaded, matrix);
+        assert_eq!(loaded.get(0, 1), (1.5, 0.3));
+        assert_eq!(loaded.max_radius(), 0.3);
+    }
+
+    #[test]
+    fn test_from_json_rejects_wrong_cell_count() {
+        let json = r#"{"interactions": [[1.0, 0.1]], "num_types": 2, "max_radius": 0.1}"#;
+        assert!(InteractionMatrix::from_json(json).is_err());
+        assert!(InteractionMatrix::from_json("not json").is_err());
+    }
+
+    #[test]
+    fn test_max_radius_follows_edits() {
+        let mut matrix = InteractionMatrix::new(2);
+        matrix.set(0u32, 0u32, 1.0, 0.4);
+        matrix.set(0u32, 1u32, 1.0, 0.2);
+        matrix.set(0u32, 0u32, 1.0, 0.1);
+        assert_eq!(matrix.max_radius(), 0.2);
+        assert_eq!(matrix.get(5, 0), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_to_wgsl_lays_out_rows_by_self_type() {
+        let mut matrix = InteractionMatrix::new(2);
+        matrix.set(0u32, 1u32, 1.5, 0.3);
+        matrix.set(1u32, 0u32, -0.5, 0.2);
+
+        let wgsl = matrix.to_wgsl("INTERACTION_MATRIX");
+        assert!(wgsl.contains("const INTERACTION_MATRIX: array<array<f32, 2>, 2>"));
+        assert!(wgsl.contains("array<f32, 2>(0.0, 1.5),\n    array<f32, 2>(-0.5, 0.0)"));
+        assert!(wgsl.contains("const INTERACTION_MATRIX_RADIUS"));
+        assert!(wgsl.contains("fn interaction_strength(a: u32, b: u32) -> f32"));
+        assert!(wgsl.contains("fn apply_typed_interaction("));
     }
 }
@@ -41,7 +41,7 @@
 //! Optional fields:
 //! - `#[color] color: Vec3` - custom particle color (RGB, 0.0-1.0)
 //! - `particle_type: u32` - for typed interactions (auto-added if not present)
-//! - Any other `f32`, `u32`, `i32`, `Vec2`, `Vec3`, `Vec4` fields
+//! - Any other `f32`, `u32`, `i32`, `bool`, `Vec2`, `Vec3`, `Vec4` fields
 //!
 //! ### Rules
 //!
@@ -109,7 +109,11 @@ mod interactions;
 pub mod lifecycle;
 pub mod rules;
 pub mod shader_utils;
+mod recording;
+#[cfg(not(target_arch = "wasm32"))]
+mod shader_watch;
 mod simulation;
+mod snapshot;
 mod spawn;
 mod spatial;
 pub mod sub_emitter;
@@ -120,24 +124,29 @@ pub mod visuals;
 pub mod selection;
 
 pub use bytemuck;
-pub use emitter::Emitter;
-pub use error::{GpuError, SimulationError, TextureError};
-pub use field::{FieldConfig, FieldRegistry, FieldType};
+pub use emitter::{Emitter, EmitterState, DEFAULT_TEXTURE_EMITTER_RESOLUTION, MAX_TEXTURE_EMITTER_RESOLUTION};
+pub use error::{GpuError, ObjError, RecordingError, SimulationError, SnapshotError, TextureError};
+pub use field::{FieldBoundary, FieldConfig, FieldRegistry, FieldType, GrayScott};
 pub use glam::{Vec2, Vec3, Vec4};
 pub use gpu::VolumeConfig;
 pub use gpu::VolumeRenderState;
+pub use gpu::PostProcessState;
 pub use gpu::{FieldSystemGpu, create_particle_field_bind_group_layout};
 pub use interactions::InteractionMatrix;
 pub use lifecycle::Lifecycle;
 pub use rdpe_derive::{MultiParticle, Particle, ParticleType};
-pub use rules::{AgentState, CustomRuleBuilder, Falloff, Rule, Transition};
+pub use rules::{AgentState, CustomRuleBuilder, Falloff, Rule, Transition, WaveShape};
+pub use recording::ParticleRecording;
 pub use simulation::Simulation;
-pub use spawn::SpawnContext;
+pub use snapshot::ParticleSnapshot;
+pub use spawn::{poisson_disc_xz, MeshSurfaceSampler, SpawnContext};
+pub use shader_utils::ShaderHelper;
 pub use sub_emitter::{SpawnTrigger, SubEmitter};
 pub use textures::{AddressMode, FilterMode, TextureConfig, TextureRegistry};
 pub use uniforms::{CustomUniforms, UniformValue, UpdateContext};
-pub use visuals::{BlendMode, ColorMapping, ConfigDiff, HotSwapChange, Palette, ParticleShape, VertexEffect, VisualConfig, WireframeMesh};
-pub use gpu::SpatialGpu;
+pub use visuals::{BlendMode, BloomConfig, ColorMapping, ConfigDiff, HotSwapChange, LightConfig, LoopMode, MeshData, MsaaSamples, Palette, ParticleShape, Primitive, SpriteSheetConfig, ToneMapMode, TrailConfig, VertexEffect, VisualConfig, WireframeMesh};
+pub use gpu::{select_workgroup_size, SpatialGpu, UNIFORM_FIELDS_WGSL};
+pub use gpu::{FrameTimings, GpuProfiler, ProfiledPass};
 pub use gpu::SpatialGridViz;
 pub use spatial::SpatialConfig;
 
@@ -202,6 +211,17 @@ pub trait ParticleTrait: Clone + Send + Sync {
     /// Always present since lifecycle fields are auto-injected.
     const SCALE_OFFSET: u32;
 
+    /// Byte offset of the `velocity` field within the GPU struct, if any.
+    ///
+    /// Used to align mesh particles and trail ribbons with their direction
+    /// of travel. Particles without a `velocity` field skip the alignment.
+    const VELOCITY_OFFSET: Option<u32> = None;
+
+    /// Size of one particle in the GPU buffer, in bytes.
+    ///
+    /// Equal to `size_of::<Self::Gpu>()`, usable in const contexts.
+    const GPU_STRIDE: usize = std::mem::size_of::<Self::Gpu>();
+
     /// Additional WGSL code prepended to shaders.
     ///
     /// Used by `MultiParticle` enums to inject type constants and helper functions.
@@ -263,20 +283,23 @@ pub trait ParticleTrait: Clone + Send + Sync {
 /// - [`Vec2`], [`Vec3`], [`Vec4`] - glam vector types
 /// - [`ParticleTrait`] - the particle trait (rarely needed directly)
 pub mod prelude {
-    pub use crate::emitter::Emitter;
-    pub use crate::field::{FieldConfig, FieldRegistry, FieldType};
+    pub use crate::emitter::{Emitter, EmitterState};
+    pub use crate::field::{FieldBoundary, FieldConfig, FieldRegistry, FieldType, GrayScott};
     pub use crate::gpu::VolumeConfig;
     pub use crate::input::{Input, KeyCode, MouseButton};
     pub use crate::interactions::InteractionMatrix;
     pub use crate::lifecycle::Lifecycle;
-    pub use crate::rules::{AgentState, CustomRuleBuilder, Falloff, Rule, Transition};
+    pub use crate::rules::{AgentState, CustomRuleBuilder, Falloff, Rule, Transition, WaveShape};
+    pub use crate::recording::ParticleRecording;
     pub use crate::simulation::Simulation;
-    pub use crate::spawn::SpawnContext;
+    pub use crate::shader_utils::ShaderHelper;
+    pub use crate::snapshot::ParticleSnapshot;
+    pub use crate::spawn::{poisson_disc_xz, MeshSurfaceSampler, SpawnContext};
     pub use crate::sub_emitter::{SpawnTrigger, SubEmitter};
     pub use crate::textures::{AddressMode, FilterMode, TextureConfig, TextureRegistry};
     pub use crate::time::Time;
     pub use crate::uniforms::{CustomUniforms, UpdateContext};
-    pub use crate::visuals::{BlendMode, ColorMapping, ConfigDiff, HotSwapChange, Palette, ParticleShape, VertexEffect, VisualConfig, WireframeMesh};
+    pub use crate::visuals::{BlendMode, BloomConfig, ColorMapping, ConfigDiff, HotSwapChange, LightConfig, LoopMode, MeshData, MsaaSamples, Palette, ParticleShape, Primitive, SpriteSheetConfig, ToneMapMode, TrailConfig, VertexEffect, VisualConfig, WireframeMesh};
     pub use crate::ParticleTrait;
     pub use crate::{Vec2, Vec3, Vec4};
     pub use rdpe_derive::{MultiParticle, Particle, ParticleType};
@@ -75,6 +75,10 @@ pub struct Lifecycle {
     fade_out: bool,
     /// Whether to shrink scale over lifetime.
     shrink_out: bool,
+    /// Seconds over which new particles grow from zero scale (0.0 = no fade-in).
+    fade_in_duration: f32,
+    /// Particle field holding the spawn scale for the fade-in.
+    fade_in_scale_field: String,
     /// Color transition over lifetime (start, end).
     color_over_life: Option<(Vec3, Vec3)>,
     /// Emitters to add.
@@ -364,6 +368,32 @@ impl Lifecycle {
         self
     }
 
+    /// Enable fade in effect.
+    ///
+    /// Newly spawned particles grow from zero to their spawn scale over
+    /// the first `seconds` of their life. `scale_field` names an `f32`
+    /// field of your particle that keeps the spawn scale while it fades in.
+    /// With [`shrink_out`](Self::shrink_out) the fade scales the shrinking
+    /// size instead and the field is left untouched.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// #[derive(Particle, Clone)]
+    /// struct Spark {
+    ///     position: Vec3,
+    ///     velocity: Vec3,
+    ///     base_scale: f32,
+    /// }
+    ///
+    /// l.lifetime(2.0).fade_in(0.3, "base_scale")
+    /// ```
+    pub fn fade_in(mut self, seconds: f32, scale_field: impl Into<String>) -> Self {
+        self.fade_in_duration = seconds.max(0.0);
+        self.fade_in_scale_field = scale_field.into();
+        self
+    }
+
     /// Set color gradient over lifetime.
     ///
     /// Particle color transitions from `start` to `end` over its lifetime.
@@ -425,6 +455,10 @@ impl Lifecycle {
     /// Build the lifecycle configuration into rules and emitters.
     ///
     /// Returns a tuple of (rules, emitters, start_dead).
+    ///
+    /// # Panics
+    ///
+    /// Panics if a fade-in has neither shrink out nor a scale field.
     pub(crate) fn build(self) -> (Vec<Rule>, Vec<Emitter>, bool) {
         let mut rules = Vec::new();
 
@@ -432,6 +466,7 @@ impl Lifecycle {
         let has_lifecycle = self.lifetime_fixed.is_some()
             || self.fade_out
             || self.shrink_out
+            || self.fade_in_duration > 0.0
             || self.color_over_life.is_some();
 
         if has_lifecycle {
@@ -458,6 +493,16 @@ impl Lifecycle {
             }
         }
 
+        if self.fade_in_duration > 0.0 {
+            // ShrinkOut sets the scale each frame, so the fade can scale that;
+            // otherwise it grows from the spawn scale kept in the field
+            let shrinks = self.shrink_out && self.lifetime_fixed.is_some();
+            rules.push(Rule::FadeIn {
+                duration: self.fade_in_duration,
+                scale_field: (!shrinks).then_some(self.fade_in_scale_field),
+            });
+        }
+
         (rules, self.emitters, self.start_dead)
     }
 }
@@ -497,4 +542,31 @@ mod tests {
         assert!(rules.iter().any(|r| matches!(r, Rule::Lifetime(2.0))));
         assert!(rules.iter().any(|r| matches!(r, Rule::FadeOut(2.0))));
     }
+
+    #[test]
+    fn test_fade_in() {
+        let (rules, _, _) = Lifecycle::new().fade_in(0.5, "base_scale").build();
+        assert!(rules.iter().any(|r| matches!(r, Rule::Age)));
+        assert!(rules.iter().any(|r| matches!(
+            r,
+            Rule::FadeIn { duration, scale_field: Some(f) } if *duration == 0.5 && f == "base_scale"
+        )));
+
+        // Shrinking keeps ShrinkOut, with a fade-in after it that scales its result
+        let (rules, _, _) = Lifecycle::new().lifetime(2.0).shrink_out().fade_in(0.5, "base_scale").build();
+        let shrink = rules.iter().position(|r| matches!(r, Rule::ShrinkOut(_))).unwrap();
+        let fade = rules.iter().position(|r| matches!(r, Rule::FadeIn { scale_field: None, .. })).unwrap();
+        assert!(shrink < fade);
+
+        let (rules, _, _) = Lifecycle::new().lifetime(2.0).build();
+        assert!(!rules.iter().any(|r| matches!(r, Rule::FadeIn { .. })));
+    }
+
+    #[test]
+    fn test_fade_in_shrink_out_without_lifetime() {
+        // Shrinking needs a lifetime, so without one the fade keeps its field
+        let (rules, _, _) = Lifecycle::new().shrink_out().fade_in(0.5, "base_scale").build();
+        assert!(!rules.iter().any(|r| matches!(r, Rule::ShrinkOut(_))));
+        assert!(rules.iter().any(|r| matches!(r, Rule::FadeIn { scale_field: Some(_), .. })));
+    }
 }
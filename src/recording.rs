@@ -0,0 +1,301 @@
+//! Recording particle state to disk and playing it back.
+//!
+//! [`Simulation::with_recorder`](crate::Simulation::with_recorder) saves the
+//! particle buffer at a fixed rate while the simulation runs, and
+//! [`Simulation::play_recording`](crate::Simulation::play_recording) shows a
+//! saved [`ParticleRecording`] instead of simulating.
+//!
+//! # File Format
+//!
+//! All integers and floats are little-endian.
+//!
+//! | Bytes | Contents |
+//! |-------|----------|
+//! | 8 | Magic `RDPEREC\0` |
+//! | 4 | Format version (`u32`, currently 1) |
+//! | 4 | Particle count (`u32`) |
+//! | 4 | Bytes per particle (`u32`) |
+//! | 4 | Frame count (`u32`) |
+//!
+//! Each frame follows as a `f32` timestamp in seconds (the first frame is at
+//! 0.0) and then `count * stride` bytes of raw particle data.
+//!
+//! # Example
+//!
+//! ```ignore
+//! // Record ten frames per second
+//! Simulation::<Ball>::new()
+//!     .with_recorder(Path::new("balls.rec"), 10)
+//!     // ...
+//!     .run()?;
+//!
+//! // Later: replay it with the same particle type
+//! let recording = ParticleRecording::open("balls.rec")?;
+//! Simulation::<Ball>::new()
+//!     .with_particle_count(recording.num_particles())
+//!     .play_recording(&recording)
+//!     // ...
+//!     .run()?;
+//! ```
+
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::error::RecordingError;
+
+/// Magic bytes at the start of every recording file.
+const MAGIC: &[u8; 8] = b"RDPEREC\0";
+
+/// Current recording format version.
+const VERSION: u32 = 1;
+
+/// Byte offset of the frame count in the header.
+const FRAME_COUNT_OFFSET: u64 = 20;
+
+/// Size of the file header in bytes.
+const HEADER_SIZE: usize = 24;
+
+/// A recorded sequence of particle buffer frames, loaded into memory.
+#[derive(Clone, Debug)]
+pub struct ParticleRecording {
+    num_particles: u32,
+    stride: usize,
+    timestamps: Vec<f32>,
+    data: Vec<u8>,
+}
+
+impl ParticleRecording {
+    /// Load a recording written by [`Simulation::with_recorder`](crate::Simulation::with_recorder).
+    ///
+    /// # Errors
+    ///
+    /// Returns `RecordingError::InvalidFormat` if the file is not a recording,
+    /// is truncated, or has no frames.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, RecordingError> {
+        let mut bytes = Vec::new();
+        File::open(path)?.read_to_end(&mut bytes)?;
+        Self::from_bytes(&bytes)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, RecordingError> {
+        let invalid = |msg: &str| RecordingError::InvalidFormat(msg.to_string());
+        if bytes.len() < HEADER_SIZE || &bytes[..8] != MAGIC {
+            return Err(invalid("missing recording header"));
+        }
+        let read_u32 = |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        let version = read_u32(8);
+        if version != VERSION {
+            return Err(RecordingError::InvalidFormat(format!("unsupported version {}", version)));
+        }
+        let num_particles = read_u32(12);
+        let stride = read_u32(16) as usize;
+        let frame_count = read_u32(20) as usize;
+        if frame_count == 0 {
+            return Err(invalid("recording has no frames"));
+        }
+
+        // Header values are untrusted, so a huge layout must fail instead of overflowing
+        let overflow = || invalid("recording layout is too large");
+        let frame_size = (num_particles as usize).checked_mul(stride).ok_or_else(overflow)?;
+        let record_size = frame_size.checked_add(4).ok_or_else(overflow)?;
+        let body_size = frame_count.checked_mul(record_size).ok_or_else(overflow)?;
+        let body = &bytes[HEADER_SIZE..];
+        if body.len() < body_size {
+            return Err(invalid("recording is truncated"));
+        }
+
+        let mut timestamps = Vec::with_capacity(frame_count);
+        let mut data = Vec::with_capacity(frame_count * frame_size);
+        for frame in body.chunks_exact(record_size).take(frame_count) {
+            timestamps.push(f32::from_le_bytes(frame[..4].try_into().unwrap()));
+            data.extend_from_slice(&frame[4..]);
+        }
+
+        Ok(Self { num_particles, stride, timestamps, data })
+    }
+
+    /// Number of particles in each frame.
+    pub fn num_particles(&self) -> u32 {
+        self.num_particles
+    }
+
+    /// Bytes per particle.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Number of recorded frames.
+    pub fn frame_count(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    /// Timestamp of the last frame in seconds.
+    pub fn duration(&self) -> f32 {
+        self.timestamps.last().copied().unwrap_or(0.0)
+    }
+
+    /// Raw particle bytes of frame `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than [`frame_count`](Self::frame_count).
+    pub fn frame(&self, index: usize) -> &[u8] {
+        assert!(index < self.frame_count(), "Frame {} out of range ({} frames)", index, self.frame_count());
+        let frame_size = self.num_particles as usize * self.stride;
+        &self.data[index * frame_size..(index + 1) * frame_size]
+    }
+
+    /// Raw particle bytes of the last frame recorded at or before `t` seconds.
+    ///
+    /// Times before the first frame return the first frame and times past
+    /// the end return the last.
+    pub fn frame_at_time(&self, t: f32) -> &[u8] {
+        let index = self.timestamps.partition_point(|&ts| ts <= t).saturating_sub(1);
+        self.frame(index)
+    }
+}
+
+/// Writes frames to a recording file as the simulation runs.
+///
+/// The frame count in the header is filled in when the recorder is dropped.
+pub(crate) struct ParticleRecorder {
+    writer: BufWriter<File>,
+    frame_size: usize,
+    interval: f32,
+    start_time: Option<f32>,
+    next_time: f32,
+    frame_count: u32,
+}
+
+impl ParticleRecorder {
+    /// Create the file and write the header.
+    pub(crate) fn create(
+        path: &Path,
+        num_particles: u32,
+        stride: usize,
+        record_fps: u32,
+    ) -> Result<Self, RecordingError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        for value in [VERSION, num_particles, stride as u32, 0] {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        Ok(Self {
+            writer,
+            frame_size: num_particles as usize * stride,
+            interval: 1.0 / record_fps.max(1) as f32,
+            start_time: None,
+            next_time: f32::NEG_INFINITY,
+            frame_count: 0,
+        })
+    }
+
+    /// Whether a frame is due at simulation time `time`.
+    pub(crate) fn is_due(&self, time: f32) -> bool {
+        time >= self.next_time
+    }
+
+    /// Append one frame of particle data captured at simulation time `time`.
+    pub(crate) fn write_frame(&mut self, time: f32, data: &[u8]) -> Result<(), RecordingError> {
+        assert_eq!(data.len(), self.frame_size, "Recorded frame has the wrong size");
+        let start = *self.start_time.get_or_insert(time);
+        self.writer.write_all(&(time - start).to_le_bytes())?;
+        self.writer.write_all(data)?;
+        self.frame_count += 1;
+        self.next_time = start + self.frame_count as f32 * self.interval;
+        Ok(())
+    }
+
+    /// Write the final frame count into the header and flush.
+    fn finish(&mut self) -> Result<(), RecordingError> {
+        self.writer.seek(SeekFrom::Start(FRAME_COUNT_OFFSET))?;
+        self.writer.write_all(&self.frame_count.to_le_bytes())?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+impl Drop for ParticleRecorder {
+    fn drop(&mut self) {
+        if let Err(e) = self.finish() {
+            eprintln!("Failed to finish particle recording: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("rdpe_{}_{}.rec", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_recording_round_trips_through_file() {
+        let path = temp_path("round_trip");
+        {
+            let mut recorder = ParticleRecorder::create(&path, 2, 4, 10).unwrap();
+            let mut time = 5.0;
+            for frame in 0..3u8 {
+                while !recorder.is_due(time) {
+                    time += 0.01;
+                }
+                recorder.write_frame(time, &[frame; 8]).unwrap();
+            }
+        }
+
+        let recording = ParticleRecording::open(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(recording.num_particles(), 2);
+        assert_eq!(recording.stride(), 4);
+        assert_eq!(recording.frame_count(), 3);
+        assert_eq!(recording.frame(0), &[0; 8]);
+        assert_eq!(recording.frame(2), &[2; 8]);
+        assert!((recording.duration() - 0.2).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_frame_at_time_picks_latest_frame() {
+        let recording = ParticleRecording {
+            num_particles: 1,
+            stride: 1,
+            timestamps: vec![0.0, 0.5, 1.0],
+            data: vec![10, 20, 30],
+        };
+        assert_eq!(recording.frame_at_time(-1.0), &[10]);
+        assert_eq!(recording.frame_at_time(0.49), &[10]);
+        assert_eq!(recording.frame_at_time(0.5), &[20]);
+        assert_eq!(recording.frame_at_time(7.0), &[30]);
+    }
+
+    #[test]
+    fn test_open_rejects_invalid_files() {
+        assert!(matches!(
+            ParticleRecording::from_bytes(b"not a recording at all!!"),
+            Err(RecordingError::InvalidFormat(_))
+        ));
+
+        let header = |values: [u32; 4]| {
+            let mut bytes = MAGIC.to_vec();
+            for value in values {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            bytes
+        };
+
+        // Header claims a frame that isn't there
+        assert!(matches!(
+            ParticleRecording::from_bytes(&header([VERSION, 1, 4, 1])),
+            Err(RecordingError::InvalidFormat(_))
+        ));
+
+        // Layout sizes whose product overflows fail cleanly
+        assert!(matches!(
+            ParticleRecording::from_bytes(&header([VERSION, u32::MAX, u32::MAX, u32::MAX])),
+            Err(RecordingError::InvalidFormat(_))
+        ));
+    }
+}
@@ -5,18 +5,18 @@
 //!
 //! # Rule Categories
 //!
-//! - **Basic Physics**: Gravity, Drag, Acceleration, SpeedLimit, Wander
+//! - **Basic Physics**: Gravity, Drag, Acceleration, Pulse, SpeedLimit, Wander
 //! - **Boundaries**: BounceWalls, WrapWalls
 //! - **Point Forces**: AttractTo, RepelFrom, PointGravity, Spring
-//! - **Field Effects**: Vortex, Turbulence, Orbit, Curl
+//! - **Field Effects**: Vortex, Turbulence, NoiseField, Orbit, Curl
 //! - **Wave/Modulation**: Oscillate, PositionNoise
 //! - **Flocking** (neighbor): Collide, Separate, Cohere, Align, Avoid
 //! - **Fluid** (neighbor): NBodyGravity, Viscosity, Pressure, SurfaceTension
 //! - **Electromagnetic** (neighbor): Magnetism
 //! - **Type-Based** (neighbor): Typed, Convert, Chase, Evade
-//! - **Lifecycle**: Age, Lifetime, FadeOut, ShrinkOut, ColorOverLife
+//! - **Lifecycle**: Age, Lifetime, FadeOut, ShrinkOut, FadeIn, ColorOverLife
 //! - **Visual**: ColorBySpeed, ColorByAge, ScaleBySpeed
-//! - **Custom**: Custom (raw WGSL), NeighborCustom (WGSL in neighbor loop)
+//! - **Custom**: Custom (raw WGSL), CustomMulti (named WGSL sections), NeighborCustom (WGSL in neighbor loop)
 //!
 //! # Neighbor Rules
 //!
@@ -68,6 +68,32 @@ impl Falloff {
     }
 }
 
+/// Periodic waveform used by oscillating rules like [`Rule::Pulse`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum WaveShape {
+    /// Smooth sine wave.
+    #[default]
+    Sine,
+
+    /// Hard switch between +1 and -1 each half period.
+    Square,
+
+    /// Linear ramps between -1 and +1, peaking where the sine would.
+    Triangle,
+}
+
+impl WaveShape {
+    /// Generate a WGSL expression evaluating this waveform at angle `x`
+    /// (radians). The result is in `[-1, 1]`.
+    pub fn to_wgsl_expr(&self, x: &str) -> String {
+        match self {
+            WaveShape::Sine => format!("sin({x})"),
+            WaveShape::Square => format!("sign(sin({x}))"),
+            WaveShape::Triangle => format!("(asin(sin({x})) * 0.63661977)"),
+        }
+    }
+}
+
 /// A transition between agent states.
 ///
 /// Transitions are checked in order of priority (highest first).
@@ -279,6 +305,46 @@ pub enum Rule {
     /// ```
     Acceleration(Vec3),
 
+    /// Oscillating acceleration along a fixed direction.
+    ///
+    /// Applies `wave(time * frequency + phase) * amplitude` along `direction`,
+    /// where `wave` is the chosen [`WaveShape`]. Every particle feels the same
+    /// force at the same time, so the whole system sways together. Good for
+    /// tidal forces, alternating electric fields, or heartbeat-like motion.
+    ///
+    /// # Fields
+    ///
+    /// - `direction` - Force axis (normalized at code-gen time)
+    /// - `frequency` - Angular frequency in radians per second
+    /// - `amplitude` - Peak acceleration
+    /// - `phase` - Phase offset in radians
+    /// - `wave_shape` - Sine, Square, or Triangle waveform
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Heartbeat: sharp up/down kicks about once per second
+    /// Rule::Pulse {
+    ///     direction: Vec3::Y,
+    ///     frequency: 6.28,
+    ///     amplitude: 2.0,
+    ///     phase: 0.0,
+    ///     wave_shape: WaveShape::Square,
+    /// }
+    /// ```
+    Pulse {
+        /// Direction of the force.
+        direction: Vec3,
+        /// Angular frequency (radians per second).
+        frequency: f32,
+        /// Peak acceleration.
+        amplitude: f32,
+        /// Phase offset (radians).
+        phase: f32,
+        /// Waveform of the oscillation.
+        wave_shape: WaveShape,
+    },
+
     /// Attract particles toward a fixed point.
     ///
     /// All particles steer toward the target point. Force is constant
@@ -444,6 +510,8 @@ pub enum Rule {
     /// - `center` - Point on the rotation axis
     /// - `axis` - Direction of rotation axis (will be normalized)
     /// - `strength` - Rotational force (positive = counter-clockwise when looking down axis)
+    /// - `radius` - Maximum distance from the axis (0.0 = unlimited, no falloff)
+    /// - `falloff` - How force decreases with distance from the axis
     ///
     /// # Example
     ///
@@ -453,13 +521,17 @@ pub enum Rule {
     ///     center: Vec3::ZERO,
     ///     axis: Vec3::Y,         // Rotate around Y axis
     ///     strength: 2.0,
+    ///     radius: 0.0,
+    ///     falloff: Falloff::Constant,
     /// }
     ///
-    /// // Horizontal whirlpool
+    /// // Whirlpool that fades out towards its rim
     /// Rule::Vortex {
     ///     center: Vec3::new(0.0, -0.5, 0.0),
     ///     axis: Vec3::Y,
     ///     strength: 3.0,
+    ///     radius: 0.8,
+    ///     falloff: Falloff::Smooth,
     /// }
     /// ```
     Vortex {
@@ -469,6 +541,10 @@ pub enum Rule {
         axis: Vec3,
         /// Rotational strength.
         strength: f32,
+        /// Maximum effect distance from the axis (0.0 = unlimited).
+        radius: f32,
+        /// Falloff over distance from the axis.
+        falloff: Falloff,
     },
 
     /// Noise-based chaotic force field.
@@ -501,34 +577,120 @@ pub enum Rule {
         strength: f32,
     },
 
-    /// Circular orbit around a center point.
+    /// Fractal value noise force field.
+    ///
+    /// Samples hash-based 3D value noise at each particle's position and
+    /// adds the resulting vector to its velocity. Unlike `Turbulence`, each
+    /// sample yields a full vector in one lookup and the number of octaves
+    /// and the speed of evolution are configurable.
+    ///
+    /// # Fields
+    ///
+    /// - `frequency` - Spatial frequency of the first octave (higher = finer detail)
+    /// - `amplitude` - Force magnitude
+    /// - `octaves` - Number of noise layers summed (1 = smooth blobs, 4+ = detailed)
+    /// - `time_scale` - How fast the field drifts over time (0.0 = static)
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Rule::NoiseField {
+    ///     frequency: 3.0,
+    ///     amplitude: 1.5,
+    ///     octaves: 3,
+    ///     time_scale: 0.2,
+    /// }
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// The noise functions (`value_noise3`, `fbm_value3`) from
+    /// [`VALUE_NOISE_WGSL`](crate::shader_utils::VALUE_NOISE_WGSL) are added
+    /// to the shader automatically when this rule is present.
+    NoiseField {
+        /// Spatial frequency of the first octave.
+        frequency: f32,
+        /// Force magnitude.
+        amplitude: f32,
+        /// Number of octaves.
+        octaves: u32,
+        /// Speed of the field's evolution over time.
+        time_scale: f32,
+    },
+
+    /// Inverse-square gravitational attraction toward a center point.
     ///
-    /// Applies forces to make particles orbit around a point. Combines
-    /// centripetal attraction with tangential velocity to maintain orbit.
+    /// Pulls particles toward `center` with force `gravity / (r² + softening²)`.
+    /// Particles with tangential velocity settle into orbits; particles at
+    /// rest fall straight in.
     ///
     /// # Fields
     ///
     /// - `center` - Point to orbit around
-    /// - `strength` - Orbital force (higher = tighter orbits)
+    /// - `gravity` - Gravitational parameter (higher = faster orbits)
+    /// - `softening` - Prevents infinite force at zero distance
     ///
     /// # Example
     ///
     /// ```ignore
     /// Rule::Orbit {
     ///     center: Vec3::ZERO,
-    ///     strength: 2.0,
+    ///     gravity: 2.0,
+    ///     softening: 0.05,
     /// }
     /// ```
     ///
     /// # Note
     ///
-    /// For stable orbits, balance with `Rule::Drag`. Without drag,
-    /// particles may spiral inward or outward.
+    /// Only velocity is changed, so give particles an initial tangential
+    /// velocity of about `sqrt(gravity / r)` for circular orbits.
     Orbit {
         /// Center of orbit.
         center: Vec3,
-        /// Orbital strength.
-        strength: f32,
+        /// Gravitational parameter.
+        gravity: f32,
+        /// Softening length.
+        softening: f32,
+    },
+
+    /// Two-body gravitational orbit using the reduced mass.
+    ///
+    /// Like [`Rule::Orbit`], but treats each particle and the central body as
+    /// a two-body system. The particle's motion relative to `center` then
+    /// follows the relative acceleration `G * (M + m) / r²`, which gives the
+    /// correct period for Keplerian ellipses when the particle's own mass is
+    /// not negligible.
+    ///
+    /// # Fields
+    ///
+    /// - `center` - Position of the central body
+    /// - `gravity` - Gravitational constant `G`
+    /// - `central_mass` - Mass of the central body `M`
+    /// - `particle_mass` - Mass of each orbiting particle `m`
+    /// - `softening` - Prevents infinite force at zero distance
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Rule::OrbitEllipse {
+    ///     center: Vec3::ZERO,
+    ///     gravity: 1.0,
+    ///     central_mass: 1.0,
+    ///     particle_mass: 0.1,
+    ///     softening: 0.01,
+    /// }
+    /// ```
+    OrbitEllipse {
+        /// Position of the central body.
+        center: Vec3,
+        /// Gravitational constant.
+        gravity: f32,
+        /// Mass of the central body.
+        central_mass: f32,
+        /// Mass of each orbiting particle.
+        particle_mass: f32,
+        /// Softening length.
+        softening: f32,
     },
 
     /// Curl noise for fluid-like, divergence-free flow.
@@ -594,28 +756,32 @@ pub enum Rule {
 
     /// Spring force tethering particles to a point.
     ///
-    /// Applies Hooke's law: force proportional to displacement from rest
-    /// position. Good for bouncy effects, soft bodies, and cloth-like
-    /// behavior.
+    /// Applies Hooke's law along the line to the anchor: particles closer
+    /// than `rest_length` are pushed out, farther ones are pulled in.
+    /// Good for bouncy effects, soft bodies, and cloth-like behavior.
     ///
     /// # Fields
     ///
-    /// - `anchor` - Rest position (or use `Vec3::ZERO` for origin)
+    /// - `anchor` - Fixed end of the spring (or use `Vec3::ZERO` for origin)
+    /// - `rest_length` - Distance from the anchor at which no force is applied
     /// - `stiffness` - Spring constant (higher = stiffer, snappier)
-    /// - `damping` - Velocity damping (prevents endless oscillation)
+    /// - `damping` - Damps velocity along the spring (prevents endless oscillation)
     ///
     /// # Example
     ///
     /// ```ignore
     /// Rule::Spring {
     ///     anchor: Vec3::ZERO,
+    ///     rest_length: 0.3,
     ///     stiffness: 5.0,
     ///     damping: 0.5,
     /// }
     /// ```
     Spring {
-        /// Rest position.
+        /// Fixed end of the spring.
         anchor: Vec3,
+        /// Distance at which the spring is relaxed.
+        rest_length: f32,
         /// Spring stiffness.
         stiffness: f32,
         /// Damping factor.
@@ -717,6 +883,7 @@ pub enum Rule {
     ///
     /// Combines explosion and implosion in a smooth oscillation.
     /// Creates organic "breathing" motion where particles expand and contract.
+    /// For a uniform oscillating force along one axis, use [`Rule::Pulse`].
     ///
     /// # Fields
     ///
@@ -728,14 +895,14 @@ pub enum Rule {
     /// # Example
     ///
     /// ```ignore
-    /// Rule::Pulse {
+    /// Rule::RadialPulse {
     ///     point: Vec3::ZERO,
     ///     strength: 2.0,
     ///     frequency: 0.5,  // One breath every 2 seconds
     ///     radius: 1.5,
     /// }
     /// ```
-    Pulse {
+    RadialPulse {
         /// Center of pulse.
         point: Vec3,
         /// Maximum force strength.
@@ -832,6 +999,8 @@ pub enum Rule {
     ///   - `0.0` = perfectly inelastic (particles stick together)
     ///   - `1.0` = perfectly elastic (full energy preserved)
     ///   - `0.5` = typical bouncy collision
+    /// - `self_type` - Only particles of this type respond (`None` = all)
+    /// - `target_type` - Only collide with neighbors of this type (`None` = all)
     ///
     /// # Example
     ///
@@ -840,6 +1009,8 @@ pub enum Rule {
     /// .with_rule(Rule::Collide {
     ///     radius: 0.05,          // Collision distance
     ///     restitution: 0.8,      // Bouncy collision
+    ///     self_type: None,
+    ///     target_type: Some(Species::Wall.into()), // Only bounce off walls
     /// })
     /// ```
     Collide {
@@ -847,6 +1018,10 @@ pub enum Rule {
         radius: f32,
         /// Coefficient of restitution (0.0 = inelastic, 1.0 = elastic).
         restitution: f32,
+        /// Only particles of this type respond to collisions (`None` = all).
+        self_type: Option<u32>,
+        /// Only neighbors of this type are collided with (`None` = all).
+        target_type: Option<u32>,
     },
 
     /// N-body gravitational attraction between particles.
@@ -999,40 +1174,55 @@ pub enum Rule {
         strength: f32,
     },
 
-    /// Density-based repulsion (SPH-style pressure).
+    /// Smoothed Particle Hydrodynamics pressure and viscosity.
+    ///
+    /// **Requires spatial hashing.** In one pass over neighbors within
+    /// `kernel_radius`, this accumulates density with the poly6 kernel, the
+    /// pressure gradient with the spiky kernel, and the viscosity Laplacian
+    /// with the viscosity kernel (Müller et al. 2003). Pressure comes from
+    /// the equation of state `P = pressure_constant * (density - rest_density)`,
+    /// clamped at zero so particles never pull each other together.
     ///
-    /// **Requires spatial hashing.** Particles in crowded areas get pushed
-    /// outward. Creates incompressible fluid behavior where particles spread
-    /// to fill space evenly.
+    /// Each particle has mass `kernel_radius³`, which keeps densities in a
+    /// similar range whatever the kernel size: a lone particle has density
+    /// of about 1.6, and a packed neighborhood sits around 5-20.
     ///
     /// # Fields
     ///
-    /// - `radius` - Kernel radius for density estimation
-    /// - `strength` - Pressure force magnitude
-    /// - `target_density` - Desired neighbor count (particles push when above this)
+    /// - `rest_density` - Density the fluid relaxes toward
+    /// - `pressure_constant` - Stiffness of the equation of state
+    /// - `viscosity` - Viscosity coefficient (0 = inviscid)
+    /// - `kernel_radius` - Smoothing length `h`; set the spatial cell size to at least this
     ///
     /// # Example
     ///
     /// ```ignore
-    /// .with_spatial_config(0.15, 32)
+    /// .with_spatial_config(0.1, 32)
+    /// .with_rule(Rule::Gravity(2.0))
     /// .with_rule(Rule::Pressure {
-    ///     radius: 0.1,
-    ///     strength: 2.0,
-    ///     target_density: 8.0,   // Comfortable with ~8 neighbors
+    ///     rest_density: 8.0,
+    ///     pressure_constant: 0.5,
+    ///     viscosity: 0.02,
+    ///     kernel_radius: 0.1,
     /// })
+    /// .with_rule(Rule::BounceWalls)
     /// ```
     ///
     /// # Physics Note
     ///
-    /// This is a simplified SPH pressure. For realistic fluids, combine with
-    /// `Viscosity` and `Gravity`.
+    /// Neighbor densities aren't available in a single pass, so the
+    /// symmetric pressure term uses this particle's own pressure for both
+    /// sides. The viscosity step is clamped so it never overshoots the
+    /// neighbors' velocities.
     Pressure {
-        /// Kernel radius for density calculation.
-        radius: f32,
-        /// Pressure force strength.
-        strength: f32,
-        /// Target neighbor density (pushes when exceeded).
-        target_density: f32,
+        /// Density the fluid relaxes toward.
+        rest_density: f32,
+        /// Equation of state stiffness.
+        pressure_constant: f32,
+        /// Viscosity coefficient.
+        viscosity: f32,
+        /// SPH smoothing length.
+        kernel_radius: f32,
     },
 
     /// Charge-based attraction and repulsion (magnetism/electrostatics).
@@ -1073,6 +1263,46 @@ pub enum Rule {
         same_repel: bool,
     },
 
+    /// Lorentz force from a uniform magnetic field.
+    ///
+    /// Reads each particle's signed charge from a custom `f32` field and
+    /// applies `F = charge * cross(velocity, field_strength)`. The force is
+    /// always perpendicular to velocity, so charged particles curve into
+    /// helices around the field direction without gaining speed; opposite
+    /// charges spiral opposite ways.
+    ///
+    /// # Fields
+    ///
+    /// - `charge_field` - Name of the particle's `f32` charge field
+    /// - `field_strength` - Magnetic field vector `B`
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// #[derive(Particle, Clone)]
+    /// struct Ion {
+    ///     position: Vec3,
+    ///     velocity: Vec3,
+    ///     charge: f32,
+    /// }
+    ///
+    /// .with_rule(Rule::Magnetic {
+    ///     charge_field: "charge".into(),
+    ///     field_strength: Vec3::new(0.0, 2.0, 0.0),
+    /// })
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// [`Simulation::with_rule`](crate::Simulation::with_rule) panics if the
+    /// particle has no `f32` field named `charge_field`.
+    Magnetic {
+        /// Particle field holding the signed charge.
+        charge_field: String,
+        /// Magnetic field vector.
+        field_strength: Vec3,
+    },
+
     /// Surface tension keeping fluid blobs together.
     ///
     /// **Requires spatial hashing.** Particles with fewer neighbors (at the
@@ -1278,25 +1508,65 @@ pub enum Rule {
     /// - `index` - Particle index (`u32`)
     /// - `uniforms.time` - Elapsed time (`f32`)
     /// - `uniforms.delta_time` - Frame delta time (`f32`)
+    /// - `uniforms.particle_count` - Total number of particles (`u32`)
+    /// - `uniforms.bounds` - Half-size of the simulation cube (`f32`)
+    ///
+    /// Besides the always-available functions in [`crate::shader_utils`],
+    /// the code can call the optional libraries listed in `helpers` (see
+    /// [`ShaderHelper`](crate::ShaderHelper)). Use [`Rule::custom`] when no
+    /// helpers are needed.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// Rule::Custom(r#"
+    /// Rule::custom(r#"
     ///     // Oscillate Y velocity based on time
     ///     p.velocity.y += sin(uniforms.time * 2.0) * 0.1;
     ///
     ///     // Color based on speed
     ///     let speed = length(p.velocity);
     ///     p.color = vec3<f32>(speed, 0.5, 1.0 - speed);
-    /// "#.to_string())
+    /// "#)
+    ///
+    /// // Push particles out of a sphere using a helper library
+    /// Rule::Custom {
+    ///     wgsl: r#"
+    ///         let d = sdf_sphere(p.position, 0.3);
+    ///         if d < 0.0 { p.velocity += normalize(p.position) * -d; }
+    ///     "#.into(),
+    ///     helpers: vec![ShaderHelper::Sdf],
+    /// }
     /// ```
     ///
     /// # Note
     ///
     /// Custom rules don't have access to neighbor data. For neighbor-aware
     /// custom behavior, use [`Rule::NeighborCustom`].
-    Custom(String),
+    Custom {
+        /// The WGSL code to execute.
+        wgsl: String,
+        /// Optional helper libraries to include in the shader.
+        helpers: Vec<crate::shader_utils::ShaderHelper>,
+    },
+
+    /// Raw WGSL code split into named sections.
+    ///
+    /// Each entry is a `(name, wgsl_code)` pair. The sections are concatenated
+    /// in order, exactly as if they were a single [`Rule::Custom`], so a `let`
+    /// declared in one section is visible in the sections after it. The name
+    /// is emitted as a comment around each section, so when shader compilation
+    /// fails, the offending line can be traced back to its section.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Rule::CustomMulti(vec![
+    ///     ("declarations".into(), "let speed = length(p.velocity);".into()),
+    ///     ("steering".into(), "p.velocity.y += sin(uniforms.time) * 0.1;".into()),
+    ///     ("coloring".into(), "p.color = vec3<f32>(speed, 0.5, 1.0 - speed);".into()),
+    /// ])
+    /// ```
+    CustomMulti(Vec<(String, String)>),
 
     /// Raw WGSL code that runs inside the neighbor iteration loop.
     ///
@@ -1883,7 +2153,12 @@ pub enum Rule {
     /// Rule::Typed {
     ///     self_type: Species::Predator.into(),
     ///     other_type: None,  // All types
-    ///     rule: Box::new(Rule::Collide { radius: 0.05, restitution: 0.8 }),
+    ///     rule: Box::new(Rule::Collide {
+    ///         radius: 0.05,
+    ///         restitution: 0.8,
+    ///         self_type: None,
+    ///         target_type: None,
+    ///     }),
     /// }
     /// ```
     Typed {
@@ -1895,6 +2170,25 @@ pub enum Rule {
         rule: Box<Rule>,
     },
 
+    /// Attraction and repulsion between every pair of particle types.
+    ///
+    /// **Requires spatial hashing.** One rule covering a whole
+    /// [`InteractionMatrix`](crate::InteractionMatrix) instead of a
+    /// [`Rule::Typed`] per pair. A neighbor within a cell's radius pulls the
+    /// particle toward it (positive strength) or pushes it away (negative),
+    /// with quadratic falloff to zero at the radius. Particle types outside
+    /// the matrix are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut matrix = InteractionMatrix::new(3);
+    /// matrix.set(0u32, 1u32, 1.0, 0.3);   // Red chases Green
+    /// matrix.set(1u32, 0u32, -0.5, 0.2);  // Green flees Red
+    /// .with_rule(Rule::TypedMulti(matrix))
+    /// ```
+    TypedMulti(crate::InteractionMatrix),
+
     /// Change particle type on proximity to trigger type.
     ///
     /// **Requires spatial hashing.** When a particle of `from_type` is
@@ -2028,11 +2322,11 @@ pub enum Rule {
     ///
     /// ```ignore
     /// .with_rule(Rule::Age)
-    /// .with_rule(Rule::Custom(r#"
+    /// .with_rule(Rule::custom(r#"
     ///     // Fade out as particle ages
     ///     let fade = 1.0 - (p.age / 2.0);
     ///     p.color = p.color * fade;
-    /// "#.to_string()))
+    /// "#))
     /// ```
     Age,
 
@@ -2058,6 +2352,55 @@ pub enum Rule {
     /// never age and will never die.
     Lifetime(f32),
 
+    /// Kill particles at a maximum age, shrinking them to nothing first.
+    ///
+    /// Between `fade_out_start` and `max_age` the particle's scale ramps
+    /// linearly from its scale at the start of the window down to zero:
+    /// `scale = original_scale * (1.0 - saturate((age - fade_out_start) / (max_age - fade_out_start)))`.
+    /// Once `age >= max_age`, `alive` is set to 0.
+    ///
+    /// Until the fade starts, the rule copies `scale` into `scale_field`
+    /// every frame; the fade then shrinks that stored value, so it never
+    /// compounds with the previous frame's result.
+    ///
+    /// # Fields
+    ///
+    /// - `max_age` - Age in seconds at which the particle dies
+    /// - `fade_out_start` - Age in seconds at which shrinking begins
+    /// - `scale_field` - An `f32` particle field that holds the unfaded scale
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// #[derive(Particle, Clone)]
+    /// struct Spark {
+    ///     position: Vec3,
+    ///     velocity: Vec3,
+    ///     base_scale: f32,
+    /// }
+    ///
+    /// .with_rule(Rule::Age)
+    /// .with_rule(Rule::MaxAge {
+    ///     max_age: 3.0,
+    ///     fade_out_start: 2.0,  // Shrink over the last second
+    ///     scale_field: "base_scale".into(),
+    /// })
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// Requires [`Rule::Age`] to be active. Place this rule after any rules
+    /// that modify `scale` (such as [`Rule::ScaleBySpeed`]), otherwise they
+    /// overwrite the fade.
+    MaxAge {
+        /// Age at which the particle dies (seconds).
+        max_age: f32,
+        /// Age at which the scale starts shrinking (seconds).
+        fade_out_start: f32,
+        /// Particle field storing the scale the fade starts from.
+        scale_field: String,
+    },
+
     /// Fade out particle color over its lifetime.
     ///
     /// Multiplies particle color by `(1.0 - age / duration)`, creating a
@@ -2103,6 +2446,43 @@ pub enum Rule {
     /// Requires [`Rule::Age`] to be active.
     ShrinkOut(f32),
 
+    /// Grow newly spawned particles in from zero scale.
+    ///
+    /// Scales particles by `saturate(age / duration)`, so a particle reaches
+    /// its full scale `duration` seconds after birth.
+    ///
+    /// With a `scale_field`, the rule copies `scale` into that field on the
+    /// particle's first frame and grows from the stored value. Without one,
+    /// it multiplies the scale already set this frame, so it must come after
+    /// a rule that sets scale outright, like [`Rule::ShrinkOut`]; otherwise
+    /// the factor compounds from frame to frame.
+    ///
+    /// # Fields
+    ///
+    /// - `duration` - Time in seconds over which to grow
+    /// - `scale_field` - An `f32` particle field that holds the spawn scale
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// .with_rule(Rule::Age)
+    /// .with_rule(Rule::FadeIn {
+    ///     duration: 0.3, // Grow over the first 0.3 seconds
+    ///     scale_field: Some("base_scale".into()),
+    /// })
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// Requires [`Rule::Age`] to be active.
+    FadeIn {
+        /// Time in seconds over which to grow.
+        duration: f32,
+        /// Particle field storing the spawn scale, or `None` to scale the
+        /// value set earlier in the frame.
+        scale_field: Option<String>,
+    },
+
     /// Lerp particle color from start to end over its lifetime.
     ///
     /// Smoothly transitions particle color based on age. At age 0, color
@@ -2456,9 +2836,9 @@ pub enum Rule {
     /// ```ignore
     /// // Particles emit pheromones and follow the gradient
     /// .with_field("pheromone", FieldConfig::new(64).with_decay(0.98))
-    /// .with_rule(Rule::Custom(r#"
+    /// .with_rule(Rule::custom(r#"
     ///     field_write(0u, p.position, 0.1);  // Emit pheromone
-    /// "#.into()))
+    /// "#))
     /// .with_rule(Rule::Gradient {
     ///     field: 0,
     ///     strength: 2.0,
@@ -2494,6 +2874,52 @@ pub enum Rule {
         ascending: bool,
     },
 
+    /// Push particles along the spatial gradient of a field.
+    ///
+    /// Samples the field at six neighboring positions `(±h, 0, 0)`,
+    /// `(0, ±h, 0)`, `(0, 0, ±h)` around each particle, where `h` is one
+    /// grid cell of that field, and adds `gradient * strength` to velocity.
+    /// Unlike [`Rule::Gradient`], the gradient is not normalized, so
+    /// particles move faster where the field changes steeply and stop on
+    /// plateaus.
+    ///
+    /// For vector fields ([`FieldConfig::vector`](crate::FieldConfig::vector))
+    /// the stored vector is used directly as the direction, so a wind or
+    /// flow field pushes particles along itself.
+    ///
+    /// # Fields
+    ///
+    /// - `field_index` - Index of the field to sample (registration order)
+    /// - `strength` - Velocity gain applied to the gradient
+    /// - `signed` - If false, `strength` is taken as its magnitude and
+    ///   particles always climb uphill; if true, a negative `strength`
+    ///   repels particles from high values
+    ///
+    /// # Panics
+    ///
+    /// Building a simulation that uses this rule panics if no fields are
+    /// registered with [`Simulation::with_field`](crate::Simulation::with_field),
+    /// or if `field_index` is out of range.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// .with_field("heat", FieldConfig::new(64).with_decay(0.99))
+    /// .with_rule(Rule::FieldGradient {
+    ///     field_index: 0,
+    ///     strength: -4.0,
+    ///     signed: true,  // Flee from hot regions
+    /// })
+    /// ```
+    FieldGradient {
+        /// Index of the field to sample.
+        field_index: usize,
+        /// Velocity gain applied to the gradient.
+        strength: f32,
+        /// Allow negative strength to push particles toward lower values.
+        signed: bool,
+    },
+
     /// Smoothly interpolate a particle field toward a target value.
     ///
     /// Exponential decay toward the target: `field = lerp(field, target, rate * dt)`.
@@ -2587,6 +3013,57 @@ pub enum Rule {
         alignment: f32,
     },
 
+    /// Reynolds boids with a separate radius and weight per behavior.
+    ///
+    /// **Requires spatial hashing.** Separation, cohesion, and alignment are
+    /// all accumulated in a single neighbor loop and combined afterwards, so
+    /// this replaces a [`Rule::Separate`] + [`Rule::Cohere`] + [`Rule::Align`]
+    /// stack while saving two neighbor traversals per frame. Unlike
+    /// [`Rule::Flock`], each behavior has its own detection radius.
+    ///
+    /// # Fields
+    ///
+    /// - `separation_radius` - Distance below which neighbors push apart
+    /// - `cohesion_radius` - Distance within which neighbors pull together
+    /// - `alignment_radius` - Distance within which velocities are matched
+    /// - `separation_weight` - Strength of the separation force
+    /// - `cohesion_weight` - Strength of the steer toward the local center
+    /// - `alignment_weight` - Strength of velocity matching
+    /// - `self_type` - If set, only particles of this type flock, and only
+    ///   with each other
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// .with_spatial_config(0.2, 32)
+    /// .with_rule(Rule::Boid {
+    ///     separation_radius: 0.05,
+    ///     cohesion_radius: 0.2,
+    ///     alignment_radius: 0.1,
+    ///     separation_weight: 3.0,
+    ///     cohesion_weight: 1.0,
+    ///     alignment_weight: 1.5,
+    ///     self_type: None,
+    /// })
+    /// .with_rule(Rule::SpeedLimit { min: 0.5, max: 2.0 })
+    /// ```
+    Boid {
+        /// Separation radius.
+        separation_radius: f32,
+        /// Cohesion radius.
+        cohesion_radius: f32,
+        /// Alignment radius.
+        alignment_radius: f32,
+        /// Separation weight.
+        separation_weight: f32,
+        /// Cohesion weight.
+        cohesion_weight: f32,
+        /// Alignment weight.
+        alignment_weight: f32,
+        /// Restrict flocking to this particle type (None = all particles).
+        self_type: Option<u32>,
+    },
+
     /// Conditional particle death.
     ///
     /// Evaluates a WGSL condition and "kills" the particle if true by setting
@@ -4461,6 +4938,20 @@ impl From<CustomRuleBuilder> for Rule {
 }
 
 impl Rule {
+    /// Create a [`Rule::Custom`] without helper libraries.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// .with_rule(Rule::custom("p.velocity.y += sin(uniforms.time) * 0.1;"))
+    /// ```
+    pub fn custom(wgsl: impl Into<String>) -> Rule {
+        Rule::Custom {
+            wgsl: wgsl.into(),
+            helpers: Vec::new(),
+        }
+    }
+
     /// Create a custom rule with dynamic, editable parameters.
     ///
     /// # Example
@@ -4535,6 +5026,7 @@ macro_rules! define_accumulator_checker {
 
 define_accumulator_checker!(needs_cohesion_accumulator, Cohere, Flock);
 define_accumulator_checker!(needs_alignment_accumulator, Align, Flock);
+define_accumulator_checker!(needs_boid_accumulator, Boid);
 define_accumulator_checker!(needs_chase_accumulator, Chase);
 define_accumulator_checker!(needs_evade_accumulator, Evade);
 define_accumulator_checker!(needs_viscosity_accumulator, Viscosity);
@@ -4546,6 +5038,54 @@ define_accumulator_checker!(needs_accumulate_accumulator, Accumulate);
 define_accumulator_checker!(needs_signal_accumulator, Signal);
 define_accumulator_checker!(needs_absorb_accumulator, Absorb);
 
+/// WGSL condition restricting a collision to the given self and neighbor types.
+fn collide_type_check(self_type: Option<u32>, target_type: Option<u32>) -> String {
+    let mut checks = Vec::new();
+    if let Some(t) = self_type {
+        checks.push(format!("p.particle_type == {t}u"));
+    }
+    if let Some(t) = target_type {
+        checks.push(format!("other.particle_type == {t}u"));
+    }
+    if checks.is_empty() {
+        "true".to_string()
+    } else {
+        checks.join(" && ")
+    }
+}
+
+/// The [`Rule::CustomMulti`] section that 1-based `line` of a generated
+/// shader falls in, as `(section name, 1-based line within the section)`.
+pub fn custom_section_at_line(shader: &str, line: usize) -> Option<(&str, usize)> {
+    let mut section = None;
+    for (i, text) in shader.lines().take(line).enumerate() {
+        let text = text.trim();
+        if let Some(name) = text.strip_prefix("// --- section: ").and_then(|s| s.strip_suffix(" ---")) {
+            section = Some((name, i + 1));
+        } else if text.starts_with("// --- end section: ") {
+            section = None;
+        }
+    }
+    section
+        .filter(|&(_, marker_line)| marker_line < line)
+        .map(|(name, marker_line)| (name, line - marker_line))
+}
+
+/// Add the [`Rule::CustomMulti`] section a shader error points into to its message.
+///
+/// The line is taken from the first `wgsl:LINE:COLUMN` location in
+/// `message`, the form wgpu and naga use in their error reports.
+pub(crate) fn describe_shader_error(shader: &str, message: &str) -> String {
+    let line = message.split("wgsl:").skip(1).find_map(|rest| {
+        let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        digits.parse().ok()
+    });
+    match line.and_then(|line| custom_section_at_line(shader, line)) {
+        Some((name, section_line)) => format!("{}\n\nIn custom section '{}', line {}", message, name, section_line),
+        None => message.to_string(),
+    }
+}
+
 impl Rule {
     /// Returns `true` if this is an OnDeath rule.
     pub fn is_on_death(&self) -> bool {
@@ -4604,6 +5144,7 @@ impl Rule {
             | Rule::Cohere { .. }
             | Rule::Align { .. }
             | Rule::Flock { .. }
+            | Rule::Boid { .. }
             | Rule::Convert { .. }
             | Rule::Chase { .. }
             | Rule::Evade { .. }
@@ -4611,6 +5152,7 @@ impl Rule {
             | Rule::Accumulate { .. }
             | Rule::Signal { .. }
             | Rule::Absorb { .. }
+            | Rule::TypedMulti(_)
             | Rule::NeighborCustom(_) => true,
             Rule::Typed { rule, .. } => rule.requires_neighbors(),
             Rule::Switch { then_rule, else_rule, .. } => {
@@ -4621,33 +5163,185 @@ impl Rule {
         }
     }
 
-    needs_cohesion_accumulator!(self);
-    needs_alignment_accumulator!(self);
-    needs_chase_accumulator!(self);
-    needs_evade_accumulator!(self);
-    needs_viscosity_accumulator!(self);
-    needs_pressure_accumulator!(self);
-    needs_surface_tension_accumulator!(self);
-    needs_avoid_accumulator!(self);
-    needs_diffuse_accumulator!(self);
-    needs_accumulate_accumulator!(self);
-    needs_signal_accumulator!(self);
-    needs_absorb_accumulator!(self);
+    /// Returns the smallest neighbor query radius this rule uses.
+    ///
+    /// `None` for rules without neighbor queries and for
+    /// [`Rule::NeighborCustom`], whose reach is up to its WGSL code.
+    pub fn neighbor_radius(&self) -> Option<f32> {
+        match self {
+            Rule::Collide { radius, .. }
+            | Rule::OnCollision { radius, .. }
+            | Rule::NBodyGravity { radius, .. }
+            | Rule::Viscosity { radius, .. }
+            | Rule::Magnetism { radius, .. }
+            | Rule::SurfaceTension { radius, .. }
+            | Rule::Avoid { radius, .. }
+            | Rule::Separate { radius, .. }
+            | Rule::Cohere { radius, .. }
+            | Rule::Align { radius, .. }
+            | Rule::Flock { radius, .. }
+            | Rule::Convert { radius, .. }
+            | Rule::Chase { radius, .. }
+            | Rule::Evade { radius, .. }
+            | Rule::Diffuse { radius, .. }
+            | Rule::Accumulate { radius, .. }
+            | Rule::Signal { radius, .. }
+            | Rule::Absorb { radius, .. } => Some(*radius),
+            Rule::LennardJones { cutoff, .. } => Some(*cutoff),
+            Rule::DLA { stick_radius, .. } => Some(*stick_radius),
+            Rule::Pressure { kernel_radius, .. } => Some(*kernel_radius),
+            Rule::Boid {
+                separation_radius,
+                cohesion_radius,
+                alignment_radius,
+                ..
+            } => Some(separation_radius.min(*cohesion_radius).min(*alignment_radius)),
+            Rule::TypedMulti(matrix) => matrix.min_radius(),
+            Rule::Typed { rule, .. } => rule.neighbor_radius(),
+            Rule::Switch { then_rule, else_rule, .. } => {
+                let else_radius = else_rule.as_ref().and_then(|r| r.neighbor_radius());
+                match (then_rule.neighbor_radius(), else_radius) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                }
+            }
+            _ => None,
+        }
+    }
 
-    /// Generate WGSL code for non-neighbor rules.
-    pub fn to_wgsl(&self, bounds: f32) -> String {
+    /// Returns the field indices this rule samples through the field system.
+    ///
+    /// Used by the simulation to check that the fields exist before the
+    /// compute shader is generated.
+    pub fn required_fields(&self) -> Vec<usize> {
         match self {
-            Rule::Gravity(g) => format!(
-                "    // Gravity\n    p.velocity.y -= {} * uniforms.delta_time;",
-                g
-            ),
+            Rule::FieldGradient { field_index, .. } => vec![*field_index],
+            Rule::Typed { rule, .. } => rule.required_fields(),
+            Rule::Switch { then_rule, else_rule, .. } => {
+                let mut fields = then_rule.required_fields();
+                if let Some(r) = else_rule {
+                    fields.extend(r.required_fields());
+                }
+                fields
+            }
+            _ => Vec::new(),
+        }
+    }
 
-            Rule::BounceWalls => format!(
-                r#"    // Bounce off walls
-    if p.position.x < -{bounds} {{
-        p.position.x = -{bounds};
-        p.velocity.x = abs(p.velocity.x);
-    }} else if p.position.x > {bounds} {{
+    /// Returns the particle fields this rule reads as `f32`.
+    ///
+    /// Used by the simulation to check those fields exist on the particle
+    /// before the compute shader is generated.
+    pub fn required_f32_fields(&self) -> Vec<&str> {
+        match self {
+            Rule::Magnetic { charge_field, .. } => vec![charge_field.as_str()],
+            Rule::Typed { rule, .. } => rule.required_f32_fields(),
+            Rule::Switch { then_rule, else_rule, .. } => {
+                let mut fields = then_rule.required_f32_fields();
+                if let Some(r) = else_rule {
+                    fields.extend(r.required_f32_fields());
+                }
+                fields
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns true if this rule calls the SPH kernel functions from
+    /// [`SPH_KERNELS_WGSL`](crate::shader_utils::SPH_KERNELS_WGSL).
+    pub fn requires_sph_kernels(&self) -> bool {
+        match self {
+            Rule::Pressure { .. } => true,
+            Rule::Typed { rule, .. } => rule.requires_sph_kernels(),
+            Rule::Switch { then_rule, else_rule, .. } => {
+                then_rule.requires_sph_kernels()
+                    || else_rule.as_ref().map(|r| r.requires_sph_kernels()).unwrap_or(false)
+            }
+            _ => false,
+        }
+    }
+
+    /// Helper libraries this rule asks to have included in the shader.
+    ///
+    /// See [`ShaderHelper`](crate::ShaderHelper). Shader generators pass
+    /// the helpers of all rules to
+    /// [`helpers_wgsl`](crate::shader_utils::helpers_wgsl).
+    pub fn required_helpers(&self) -> Vec<crate::shader_utils::ShaderHelper> {
+        match self {
+            Rule::Custom { helpers, .. } => helpers.clone(),
+            Rule::Typed { rule, .. } => rule.required_helpers(),
+            Rule::Switch { then_rule, else_rule, .. } => {
+                let mut helpers = then_rule.required_helpers();
+                if let Some(r) = else_rule {
+                    helpers.extend(r.required_helpers());
+                }
+                helpers
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// The interaction matrix this rule applies, if it is a
+    /// [`Rule::TypedMulti`] or wraps one.
+    ///
+    /// Shader generators declare it at module level with
+    /// [`InteractionMatrix::to_wgsl`](crate::InteractionMatrix::to_wgsl)
+    /// under the name `INTERACTION_MATRIX`. A shader holds one matrix.
+    pub fn interaction_matrix(&self) -> Option<&crate::InteractionMatrix> {
+        match self {
+            Rule::TypedMulti(matrix) => Some(matrix),
+            Rule::Typed { rule, .. } => rule.interaction_matrix(),
+            Rule::Switch { then_rule, else_rule, .. } => then_rule
+                .interaction_matrix()
+                .or_else(|| else_rule.as_ref().and_then(|r| r.interaction_matrix())),
+            _ => None,
+        }
+    }
+
+    /// Returns true if this rule calls the value noise functions from
+    /// [`VALUE_NOISE_WGSL`](crate::shader_utils::VALUE_NOISE_WGSL).
+    ///
+    /// Shader generators use this to include those functions only when needed.
+    pub fn requires_value_noise(&self) -> bool {
+        match self {
+            Rule::NoiseField { .. } => true,
+            Rule::Typed { rule, .. } => rule.requires_value_noise(),
+            Rule::Switch { then_rule, else_rule, .. } => {
+                then_rule.requires_value_noise()
+                    || else_rule.as_ref().map(|r| r.requires_value_noise()).unwrap_or(false)
+            }
+            _ => false,
+        }
+    }
+
+    needs_cohesion_accumulator!(self);
+    needs_alignment_accumulator!(self);
+    needs_boid_accumulator!(self);
+    needs_chase_accumulator!(self);
+    needs_evade_accumulator!(self);
+    needs_viscosity_accumulator!(self);
+    needs_pressure_accumulator!(self);
+    needs_surface_tension_accumulator!(self);
+    needs_avoid_accumulator!(self);
+    needs_diffuse_accumulator!(self);
+    needs_accumulate_accumulator!(self);
+    needs_signal_accumulator!(self);
+    needs_absorb_accumulator!(self);
+
+    /// Generate WGSL code for non-neighbor rules.
+    pub fn to_wgsl(&self, bounds: f32) -> String {
+        match self {
+            Rule::Gravity(g) => format!(
+                "    // Gravity\n    p.velocity.y -= {} * uniforms.delta_time;",
+                g
+            ),
+
+            Rule::BounceWalls => format!(
+                r#"    // Bounce off walls
+    if p.position.x < -{bounds} {{
+        p.position.x = -{bounds};
+        p.velocity.x = abs(p.velocity.x);
+    }} else if p.position.x > {bounds} {{
         p.position.x = {bounds};
         p.velocity.x = -abs(p.velocity.x);
     }}
@@ -4702,6 +5396,19 @@ impl Rule {
                 acc.x, acc.y, acc.z
             ),
 
+            Rule::Pulse { direction, frequency, amplitude, phase, wave_shape } => {
+                let dir = direction.normalize_or_zero();
+                let wave = wave_shape.to_wgsl_expr(&format!("uniforms.time * {frequency:?} + {phase:?}"));
+                format!(
+                    r#"    // Pulse ({wave_shape:?} wave)
+    {{
+        let pulse = {wave} * {amplitude:?};
+        p.velocity += vec3<f32>({:?}, {:?}, {:?}) * pulse * uniforms.delta_time;
+    }}"#,
+                    dir.x, dir.y, dir.z
+                )
+            }
+
             Rule::AttractTo { point, strength } => format!(
                 r#"    // Attract to point
     {{
@@ -4797,7 +5504,7 @@ impl Rule {
                 max_speed = max_speed, max_force = max_force, slowing_radius = slowing_radius
             ),
 
-            Rule::Vortex { center, axis, strength } => {
+            Rule::Vortex { center, axis, strength, radius, falloff } => {
                 // Normalize axis at compile time for the shader
                 let axis_len = (axis.x * axis.x + axis.y * axis.y + axis.z * axis.z).sqrt();
                 let (ax, ay, az) = if axis_len > 0.0001 {
@@ -4805,6 +5512,18 @@ impl Rule {
                 } else {
                     (0.0, 1.0, 0.0) // Default to Y axis
                 };
+                // Unlimited range means no falloff
+                let (radius_check, falloff_code) = if *radius > 0.0 {
+                    (
+                        format!("dist < {radius} && "),
+                        format!(
+                            "let radius = {radius:?};\n            let falloff = {};",
+                            falloff.to_wgsl_expr()
+                        ),
+                    )
+                } else {
+                    (String::new(), "let falloff = 1.0;".to_string())
+                };
                 format!(
                     r#"    // Vortex
     {{
@@ -4815,15 +5534,18 @@ impl Rule {
         let along_axis = dot(to_particle, vortex_axis) * vortex_axis;
         let radial = to_particle - along_axis;
         let dist = length(radial);
-        if dist > 0.001 {{
+        if {radius_check}dist > 0.001 {{
+            {falloff_code}
             // Tangent is perpendicular to both axis and radial
             let tangent = cross(vortex_axis, radial) / dist;
-            p.velocity += tangent * {strength} * uniforms.delta_time;
+            p.velocity += tangent * {strength} * falloff * uniforms.delta_time;
         }}
     }}"#,
                     cx = center.x, cy = center.y, cz = center.z,
                     ax = ax, ay = ay, az = az,
-                    strength = strength
+                    strength = strength,
+                    radius_check = radius_check,
+                    falloff_code = falloff_code
                 )
             }
 
@@ -4841,26 +5563,45 @@ impl Rule {
                 scale = scale, strength = strength
             ),
 
-            Rule::Orbit { center, strength } => format!(
-                r#"    // Orbit
+            Rule::NoiseField { frequency, amplitude, octaves, time_scale } => format!(
+                r#"    // Noise field (fractal value noise)
+    {{
+        let noise_pos = p.position * {frequency} + vec3<f32>(uniforms.time * {time_scale});
+        p.velocity += fbm_value3(noise_pos, {octaves}u) * {amplitude} * uniforms.delta_time;
+    }}"#
+            ),
+
+            Rule::Orbit { center, gravity, softening } => format!(
+                r#"    // Orbit (inverse-square gravity)
     {{
         let orbit_center = vec3<f32>({cx}, {cy}, {cz});
         let to_center = orbit_center - p.position;
-        let dist = length(to_center);
-        if dist > 0.001 {{
-            // Centripetal force toward center
-            let centripetal = normalize(to_center) * {strength};
-            // Tangential direction (perpendicular in XZ plane)
-            let tangent = vec3<f32>(-to_center.z, 0.0, to_center.x) / dist;
-            // Adjust tangent velocity to maintain orbit
-            let orbital_speed = sqrt({strength} * dist);
-            let current_tangent_speed = dot(p.velocity, tangent);
-            p.velocity += centripetal * uniforms.delta_time;
-            p.velocity += tangent * (orbital_speed - current_tangent_speed) * 0.1 * uniforms.delta_time;
+        let r_sq = dot(to_center, to_center);
+        if r_sq > 0.000001 {{
+            let dist_sq = r_sq + {softening} * {softening};
+            p.velocity += to_center * inverseSqrt(r_sq) * ({gravity} / dist_sq) * uniforms.delta_time;
+        }}
+    }}"#,
+                cx = center.x, cy = center.y, cz = center.z,
+                gravity = gravity, softening = softening
+            ),
+
+            Rule::OrbitEllipse { center, gravity, central_mass, particle_mass, softening } => format!(
+                r#"    // Orbit (two-body, reduced mass)
+    {{
+        let orbit_center = vec3<f32>({cx}, {cy}, {cz});
+        let to_center = orbit_center - p.position;
+        let r_sq = dot(to_center, to_center);
+        if r_sq > 0.000001 {{
+            // Relative acceleration of the two-body problem: mu = G * (M + m)
+            let mu = {gravity} * ({central_mass} + {particle_mass});
+            let dist_sq = r_sq + {softening} * {softening};
+            p.velocity += to_center * inverseSqrt(r_sq) * (mu / dist_sq) * uniforms.delta_time;
         }}
     }}"#,
                 cx = center.x, cy = center.y, cz = center.z,
-                strength = strength
+                gravity = gravity, central_mass = central_mass,
+                particle_mass = particle_mass, softening = softening
             ),
 
             Rule::Curl { scale, strength } => format!(
@@ -4917,7 +5658,17 @@ impl Rule {
     }}"#
             ),
 
-            Rule::Custom(code) => format!("    // Custom rule\n{}", code),
+            Rule::Custom { wgsl, .. } => format!("    // Custom rule\n{}", wgsl),
+
+            Rule::CustomMulti(sections) => {
+                let mut wgsl = String::from("    // Custom rule (multi-section)");
+                for (name, code) in sections {
+                    wgsl.push_str(&format!(
+                        "\n    // --- section: {name} ---\n{code}\n    // --- end section: {name} ---"
+                    ));
+                }
+                wgsl
+            }
 
             // CustomDynamic uses static values when not using rule inspector
             Rule::CustomDynamic { code, .. } => format!("    // Custom rule (dynamic)\n{}", code),
@@ -5309,6 +6060,28 @@ impl Rule {
     }}"#
             ),
 
+            Rule::MaxAge { max_age, fade_out_start, scale_field } => format!(
+                r#"    // Max age (shrink over the fade window, then die)
+    {{
+        // Keep the unfaded scale until the fade has started
+        if p.age - uniforms.delta_time <= {fade_out_start:?} {{
+            p.{scale_field} = p.scale;
+        }}
+        let fade_len = max({max_age:?} - {fade_out_start:?}, 0.0001);
+        let fade_now = 1.0 - saturate((p.age - {fade_out_start:?}) / fade_len);
+        p.scale = p.{scale_field} * fade_now;
+        if p.age >= {max_age:?} {{
+            p.alive = 0u;
+        }}
+    }}"#
+            ),
+
+            Rule::Magnetic { charge_field, field_strength: b } => format!(
+                r#"    // Magnetic (Lorentz force)
+    p.velocity += p.{charge_field} * cross(p.velocity, vec3<f32>({:?}, {:?}, {:?})) * uniforms.delta_time;"#,
+                b.x, b.y, b.z
+            ),
+
             Rule::FadeOut(duration) => format!(
                 r#"    // Fade out
     {{
@@ -5322,6 +6095,24 @@ impl Rule {
     p.scale = clamp(1.0 - p.age / {duration}, 0.0, 1.0);"#
             ),
 
+            Rule::FadeIn { duration, scale_field: Some(scale_field) } => format!(
+                r#"    // Fade in (grow from the stored spawn scale)
+    {{
+        // Keep the spawn scale from the first frame
+        if p.age - uniforms.delta_time <= 0.0 {{
+            p.{scale_field} = p.scale;
+        }}
+        if p.age - uniforms.delta_time < {duration:?} {{
+            p.scale = p.{scale_field} * saturate(p.age / max({duration:?}, 0.0001));
+        }}
+    }}"#
+            ),
+
+            Rule::FadeIn { duration, scale_field: None } => format!(
+                r#"    // Fade in (scale the value set this frame)
+    p.scale *= saturate(p.age / max({duration:?}, 0.0001));"#
+            ),
+
             Rule::Refractory {
                 trigger,
                 charge,
@@ -5362,14 +6153,18 @@ impl Rule {
                 point.x, point.y, point.z
             ),
 
-            Rule::Spring { anchor, stiffness, damping } => format!(
+            Rule::Spring { anchor, rest_length, stiffness, damping } => format!(
                 r#"    // Spring (Hooke's law)
     {{
         let anchor_pos = vec3<f32>({}, {}, {});
-        let displacement = anchor_pos - p.position;
-        let spring_force = displacement * {stiffness};
-        let damping_force = -p.velocity * {damping};
-        p.velocity += (spring_force + damping_force) * uniforms.delta_time;
+        let displacement = p.position - anchor_pos;
+        let dist = length(displacement);
+        if dist > 0.0001 {{
+            let spring_dir = displacement / dist;
+            let spring_force = spring_dir * ({stiffness} * ({rest_length} - dist));
+            let damping_force = spring_dir * (dot(p.velocity, spring_dir) * {damping});
+            p.velocity += (spring_force - damping_force) * uniforms.delta_time;
+        }}
     }}"#,
                 anchor.x, anchor.y, anchor.z
             ),
@@ -5436,14 +6231,14 @@ impl Rule {
                 )
             }
 
-            Rule::Pulse { point, strength, frequency, radius } => {
+            Rule::RadialPulse { point, strength, frequency, radius } => {
                 let radius_check = if *radius > 0.0 {
                     format!("dist < {} && ", radius)
                 } else {
                     String::new()
                 };
                 format!(
-                    r#"    // Pulse (breathing force, freq={frequency}Hz)
+                    r#"    // Radial pulse (breathing force, freq={frequency}Hz)
     {{
         let pulse_center = vec3<f32>({px}, {py}, {pz});
         let to_particle = p.position - pulse_center;
@@ -5596,6 +6391,27 @@ impl Rule {
                 )
             }
 
+            Rule::FieldGradient { field_index, strength, signed } => {
+                let strength = if *signed { *strength } else { strength.abs() };
+                format!(
+                    r#"    // Field gradient (central differences, one cell apart)
+    {{
+        let fg_params = field_params[{field_index}u];
+        var grad: vec3<f32>;
+        if fg_params.field_type == FIELD_TYPE_VECTOR {{
+            grad = field_read_vec3({field_index}u, p.position);
+        }} else {{
+            let h = 2.0 * fg_params.extent / f32(fg_params.resolution);
+            let dx = field_read({field_index}u, p.position + vec3<f32>(h, 0.0, 0.0)) - field_read({field_index}u, p.position - vec3<f32>(h, 0.0, 0.0));
+            let dy = field_read({field_index}u, p.position + vec3<f32>(0.0, h, 0.0)) - field_read({field_index}u, p.position - vec3<f32>(0.0, h, 0.0));
+            let dz = field_read({field_index}u, p.position + vec3<f32>(0.0, 0.0, h)) - field_read({field_index}u, p.position - vec3<f32>(0.0, 0.0, h));
+            grad = vec3<f32>(dx, dy, dz) / (2.0 * h);
+        }}
+        p.velocity += grad * {strength:?} * uniforms.delta_time;
+    }}"#
+                )
+            }
+
             Rule::Lerp { field, target, rate } => format!(
                 r#"    // Lerp (smooth interpolation)
     p.{field} = mix(p.{field}, {target}, clamp({rate} * uniforms.delta_time, 0.0, 1.0));"#
@@ -5951,7 +6767,9 @@ impl Rule {
             | Rule::Cohere { .. }
             | Rule::Align { .. }
             | Rule::Flock { .. }
+            | Rule::Boid { .. }
             | Rule::Typed { .. }
+            | Rule::TypedMulti(_)
             | Rule::Convert { .. }
             | Rule::Chase { .. }
             | Rule::Evade { .. }
@@ -6052,9 +6870,9 @@ impl Rule {
     /// Generate WGSL code for neighbor-based rules (inside neighbor loop).
     pub fn to_neighbor_wgsl(&self) -> String {
         match self {
-            Rule::Collide { radius, restitution } => format!(
+            Rule::Collide { radius, restitution, self_type, target_type } => format!(
                 r#"            // Elastic collision
-            if neighbor_dist < {radius} && neighbor_dist > 0.0001 {{
+            if {type_check} && neighbor_dist < {radius} && neighbor_dist > 0.0001 {{
                 // Relative velocity along collision normal (positive = approaching)
                 let rel_vel = dot(neighbor_vel - p.velocity, neighbor_dir);
 
@@ -6069,7 +6887,8 @@ impl Rule {
                 // Position correction to resolve overlap
                 let overlap = {radius} - neighbor_dist;
                 p.velocity += neighbor_dir * overlap * 2.0;
-            }}"#
+            }}"#,
+                type_check = collide_type_check(*self_type, *target_type)
             ),
 
             Rule::OnCollision { radius, response } => format!(
@@ -6143,15 +6962,17 @@ impl Rule {
             }}"#
             ),
 
-            Rule::Pressure { radius, strength, target_density: _ } => format!(
-                r#"            // Pressure (accumulate density and force)
-            if neighbor_dist < {radius} && neighbor_dist > 0.001 {{
-                let weight = 1.0 - neighbor_dist / {radius};
-                pressure_density += weight;
-                // Accumulate weighted push direction
-                pressure_force += neighbor_dir * weight * {strength};
+            Rule::Pressure { kernel_radius, .. } => {
+                let mass = kernel_radius * kernel_radius * kernel_radius;
+                format!(
+                    r#"            // SPH pressure (accumulate density, pressure gradient, viscosity)
+            if neighbor_dist < {kernel_radius:?} {{
+                pressure_density += {mass:?} * sph_poly6(neighbor_dist, {kernel_radius:?});
+                pressure_grad += neighbor_dir * ({mass:?} * sph_spiky_grad(neighbor_dist, {kernel_radius:?}));
+                pressure_visc += (neighbor_vel - p.velocity) * ({mass:?} * sph_viscosity_laplacian(neighbor_dist, {kernel_radius:?}));
             }}"#
-            ),
+                )
+            }
 
             Rule::Magnetism { radius, strength, same_repel } => {
                 let same_sign = if *same_repel { "1.0" } else { "-1.0" };
@@ -6224,6 +7045,39 @@ impl Rule {
             }}"#
             ),
 
+            Rule::Boid {
+                separation_radius,
+                cohesion_radius,
+                alignment_radius,
+                self_type,
+                ..
+            } => {
+                let type_check = match self_type {
+                    Some(t) => format!("p.particle_type == {t}u && other.particle_type == {t}u"),
+                    None => "true".to_string(),
+                };
+                format!(
+                    r#"            // Boid: separation, cohesion, and alignment in one pass
+            if {type_check} {{
+                if neighbor_dist < {separation_radius} && neighbor_dist > 0.0001 {{
+                    boid_separation += neighbor_dir * ({separation_radius} - neighbor_dist) / {separation_radius};
+                }}
+                if neighbor_dist < {cohesion_radius} {{
+                    boid_cohesion_sum += neighbor_pos;
+                    boid_cohesion_count += 1.0;
+                }}
+                if neighbor_dist < {alignment_radius} {{
+                    boid_alignment_sum += neighbor_vel;
+                    boid_alignment_count += 1.0;
+                }}
+            }}"#
+                )
+            }
+
+            Rule::TypedMulti(_) => {
+                "            // Interaction matrix, declared at module level\n            apply_typed_interaction(&p, other, uniforms);".to_string()
+            }
+
             Rule::Typed { self_type, other_type, rule } => {
                 let inner = rule.to_neighbor_wgsl();
                 if inner.is_empty() {
@@ -6414,6 +7268,23 @@ impl Rule {
     }}"#
             ),
 
+            Rule::Boid { separation_weight, cohesion_weight, alignment_weight, .. } => format!(
+                r#"    // Apply boid steering
+    {{
+        var boid_steer = boid_separation * {separation_weight};
+        if boid_cohesion_count > 0.0 {{
+            let to_center = boid_cohesion_sum / boid_cohesion_count - p.position;
+            if length(to_center) > 0.001 {{
+                boid_steer += normalize(to_center) * {cohesion_weight};
+            }}
+        }}
+        if boid_alignment_count > 0.0 {{
+            boid_steer += (boid_alignment_sum / boid_alignment_count - p.velocity) * {alignment_weight};
+        }}
+        p.velocity += boid_steer * uniforms.delta_time;
+    }}"#
+            ),
+
             Rule::Typed { self_type, rule, .. } => {
                 let inner = rule.to_post_neighbor_wgsl();
                 if inner.is_empty() {
@@ -6458,13 +7329,20 @@ impl Rule {
     }}"#
             ),
 
-            Rule::Pressure { target_density, .. } => format!(
-                r#"    // Apply pressure
-    if pressure_density > {target_density} {{
-        let excess = pressure_density - {target_density};
-        p.velocity += pressure_force * excess * uniforms.delta_time;
+            Rule::Pressure { rest_density, pressure_constant, viscosity, kernel_radius } => {
+                let mass = kernel_radius * kernel_radius * kernel_radius;
+                format!(
+                    r#"    // Apply SPH pressure and viscosity
+    {{
+        // Include this particle's own contribution to its density
+        let density = pressure_density + {mass:?} * sph_poly6(0.0, {kernel_radius:?});
+        let pressure = max({pressure_constant:?} * (density - {rest_density:?}), 0.0);
+        p.velocity -= pressure_grad * (2.0 * pressure / (density * density)) * uniforms.delta_time;
+        let visc_step = min({viscosity:?} * uniforms.delta_time / density, 1.0);
+        p.velocity += pressure_visc * visc_step;
     }}"#
-            ),
+                )
+            }
 
             Rule::SurfaceTension { strength, threshold, .. } => format!(
                 r#"    // Apply surface tension
@@ -6545,6 +7423,7 @@ impl Rule {
             Rule::WrapWalls => "Wrap Walls",
             Rule::Drag(_) => "Drag",
             Rule::Acceleration(_) => "Acceleration",
+            Rule::Pulse { .. } => "Pulse",
             Rule::AttractTo { .. } => "Attract To",
             Rule::RepelFrom { .. } => "Repel From",
             Rule::Seek { .. } => "Seek",
@@ -6552,13 +7431,15 @@ impl Rule {
             Rule::Arrive { .. } => "Arrive",
             Rule::Vortex { .. } => "Vortex",
             Rule::Turbulence { .. } => "Turbulence",
+            Rule::NoiseField { .. } => "Noise Field",
             Rule::Orbit { .. } => "Orbit",
+            Rule::OrbitEllipse { .. } => "Orbit Ellipse",
             Rule::Curl { .. } => "Curl",
             Rule::PointGravity { .. } => "Point Gravity",
             Rule::Spring { .. } => "Spring",
             Rule::Radial { .. } => "Radial",
             Rule::Shockwave { .. } => "Shockwave",
-            Rule::Pulse { .. } => "Pulse",
+            Rule::RadialPulse { .. } => "Radial Pulse",
             Rule::Oscillate { .. } => "Oscillate",
             Rule::PositionNoise { .. } => "Position Noise",
             Rule::SpeedLimit { .. } => "Speed Limit",
@@ -6574,20 +7455,25 @@ impl Rule {
             Rule::Viscosity { .. } => "Viscosity",
             Rule::Pressure { .. } => "Pressure",
             Rule::Magnetism { .. } => "Magnetism",
+            Rule::Magnetic { .. } => "Magnetic",
             Rule::SurfaceTension { .. } => "Surface Tension",
             Rule::Typed { .. } => "Typed",
+            Rule::TypedMulti(_) => "Typed Multi",
             Rule::Convert { .. } => "Convert",
             Rule::Chase { .. } => "Chase",
             Rule::Evade { .. } => "Evade",
             Rule::Age => "Age",
             Rule::Lifetime(_) => "Lifetime",
+            Rule::MaxAge { .. } => "Max Age",
             Rule::FadeOut { .. } => "Fade Out",
             Rule::ShrinkOut { .. } => "Shrink Out",
+            Rule::FadeIn { .. } => "Fade In",
             Rule::ColorOverLife { .. } => "Color Over Life",
             Rule::ColorBySpeed { .. } => "Color By Speed",
             Rule::ColorByAge { .. } => "Color By Age",
             Rule::ScaleBySpeed { .. } => "Scale By Speed",
-            Rule::Custom(_) => "Custom",
+            Rule::Custom { .. } => "Custom",
+            Rule::CustomMulti(_) => "Custom (Multi)",
             Rule::NeighborCustom(_) => "Neighbor Custom",
             Rule::CustomDynamic { .. } => "Custom (Dynamic)",
             Rule::NeighborCustomDynamic { .. } => "Neighbor Custom (Dynamic)",
@@ -6599,6 +7485,8 @@ impl Rule {
             Rule::Agent { .. } => "Agent",
             Rule::Signal { .. } => "Signal",
             Rule::Absorb { .. } => "Absorb",
+            Rule::Boid { .. } => "Boid",
+            Rule::FieldGradient { .. } => "Field Gradient",
             // Catch-all for any other variants
             _ => "Rule",
         }
@@ -6622,6 +7510,12 @@ impl Rule {
             Rule::Acceleration(acc) => vec![
                 (format!("{}_acceleration", prefix), UniformValue::Vec3(*acc)),
             ],
+            Rule::Pulse { direction, frequency, amplitude, phase, .. } => vec![
+                (format!("{}_direction", prefix), UniformValue::Vec3(direction.normalize_or_zero())),
+                (format!("{}_frequency", prefix), UniformValue::F32(*frequency)),
+                (format!("{}_amplitude", prefix), UniformValue::F32(*amplitude)),
+                (format!("{}_phase", prefix), UniformValue::F32(*phase)),
+            ],
             Rule::AttractTo { point, strength } => vec![
                 (format!("{}_point", prefix), UniformValue::Vec3(*point)),
                 (format!("{}_strength", prefix), UniformValue::F32(*strength)),
@@ -6648,18 +7542,33 @@ impl Rule {
                 (format!("{}_max_force", prefix), UniformValue::F32(*max_force)),
                 (format!("{}_slowing_radius", prefix), UniformValue::F32(*slowing_radius)),
             ],
-            Rule::Vortex { center, axis, strength } => vec![
+            Rule::Vortex { center, axis, strength, radius, .. } => vec![
                 (format!("{}_center", prefix), UniformValue::Vec3(*center)),
                 (format!("{}_axis", prefix), UniformValue::Vec3(*axis)),
                 (format!("{}_strength", prefix), UniformValue::F32(*strength)),
+                (format!("{}_radius", prefix), UniformValue::F32(*radius)),
+            ],
+            Rule::NoiseField { frequency, amplitude, octaves, time_scale } => vec![
+                (format!("{}_frequency", prefix), UniformValue::F32(*frequency)),
+                (format!("{}_amplitude", prefix), UniformValue::F32(*amplitude)),
+                (format!("{}_octaves", prefix), UniformValue::U32(*octaves)),
+                (format!("{}_time_scale", prefix), UniformValue::F32(*time_scale)),
             ],
             Rule::Turbulence { scale, strength } => vec![
                 (format!("{}_scale", prefix), UniformValue::F32(*scale)),
                 (format!("{}_strength", prefix), UniformValue::F32(*strength)),
             ],
-            Rule::Orbit { center, strength } => vec![
+            Rule::Orbit { center, gravity, softening } => vec![
                 (format!("{}_center", prefix), UniformValue::Vec3(*center)),
-                (format!("{}_strength", prefix), UniformValue::F32(*strength)),
+                (format!("{}_gravity", prefix), UniformValue::F32(*gravity)),
+                (format!("{}_softening", prefix), UniformValue::F32(*softening)),
+            ],
+            Rule::OrbitEllipse { center, gravity, central_mass, particle_mass, softening } => vec![
+                (format!("{}_center", prefix), UniformValue::Vec3(*center)),
+                (format!("{}_gravity", prefix), UniformValue::F32(*gravity)),
+                (format!("{}_central_mass", prefix), UniformValue::F32(*central_mass)),
+                (format!("{}_particle_mass", prefix), UniformValue::F32(*particle_mass)),
+                (format!("{}_softening", prefix), UniformValue::F32(*softening)),
             ],
             Rule::Curl { scale, strength } => vec![
                 (format!("{}_scale", prefix), UniformValue::F32(*scale)),
@@ -6670,12 +7579,13 @@ impl Rule {
                 (format!("{}_strength", prefix), UniformValue::F32(*strength)),
                 (format!("{}_softening", prefix), UniformValue::F32(*softening)),
             ],
-            Rule::Spring { anchor, stiffness, damping } => vec![
+            Rule::Spring { anchor, rest_length, stiffness, damping } => vec![
                 (format!("{}_anchor", prefix), UniformValue::Vec3(*anchor)),
+                (format!("{}_rest_length", prefix), UniformValue::F32(*rest_length)),
                 (format!("{}_stiffness", prefix), UniformValue::F32(*stiffness)),
                 (format!("{}_damping", prefix), UniformValue::F32(*damping)),
             ],
-            Rule::Pulse { point, strength, frequency, radius } => vec![
+            Rule::RadialPulse { point, strength, frequency, radius } => vec![
                 (format!("{}_point", prefix), UniformValue::Vec3(*point)),
                 (format!("{}_strength", prefix), UniformValue::F32(*strength)),
                 (format!("{}_frequency", prefix), UniformValue::F32(*frequency)),
@@ -6707,7 +7617,7 @@ impl Rule {
                 (format!("{}_radius", prefix), UniformValue::F32(*radius)),
                 (format!("{}_strength", prefix), UniformValue::F32(*strength)),
             ],
-            Rule::Collide { radius, restitution } => vec![
+            Rule::Collide { radius, restitution, .. } => vec![
                 (format!("{}_radius", prefix), UniformValue::F32(*radius)),
                 (format!("{}_restitution", prefix), UniformValue::F32(*restitution)),
             ],
@@ -6726,6 +7636,10 @@ impl Rule {
             Rule::Lifetime(duration) => vec![
                 (format!("{}_duration", prefix), UniformValue::F32(*duration)),
             ],
+            Rule::MaxAge { max_age, fade_out_start, .. } => vec![
+                (format!("{}_max_age", prefix), UniformValue::F32(*max_age)),
+                (format!("{}_fade_out_start", prefix), UniformValue::F32(*fade_out_start)),
+            ],
             Rule::Radial { point, strength, radius, .. } => vec![
                 (format!("{}_point", prefix), UniformValue::Vec3(*point)),
                 (format!("{}_strength", prefix), UniformValue::F32(*strength)),
@@ -6763,10 +7677,14 @@ impl Rule {
                 (format!("{}_radius", prefix), UniformValue::F32(*radius)),
                 (format!("{}_strength", prefix), UniformValue::F32(*strength)),
             ],
-            Rule::Pressure { radius, strength, target_density } => vec![
-                (format!("{}_radius", prefix), UniformValue::F32(*radius)),
-                (format!("{}_strength", prefix), UniformValue::F32(*strength)),
-                (format!("{}_target_density", prefix), UniformValue::F32(*target_density)),
+            Rule::Pressure { rest_density, pressure_constant, viscosity, kernel_radius } => vec![
+                (format!("{}_rest_density", prefix), UniformValue::F32(*rest_density)),
+                (format!("{}_pressure_constant", prefix), UniformValue::F32(*pressure_constant)),
+                (format!("{}_viscosity", prefix), UniformValue::F32(*viscosity)),
+                (format!("{}_kernel_radius", prefix), UniformValue::F32(*kernel_radius)),
+            ],
+            Rule::Magnetic { field_strength, .. } => vec![
+                (format!("{}_field_strength", prefix), UniformValue::Vec3(*field_strength)),
             ],
             Rule::Magnetism { radius, strength, .. } => vec![
                 (format!("{}_radius", prefix), UniformValue::F32(*radius)),
@@ -6812,6 +7730,9 @@ impl Rule {
             Rule::ShrinkOut(duration) => vec![
                 (format!("{}_duration", prefix), UniformValue::F32(*duration)),
             ],
+            Rule::FadeIn { duration, .. } => vec![
+                (format!("{}_duration", prefix), UniformValue::F32(*duration)),
+            ],
             Rule::ColorOverLife { start, end, duration } => vec![
                 (format!("{}_start", prefix), UniformValue::Vec3(*start)),
                 (format!("{}_end", prefix), UniformValue::Vec3(*end)),
@@ -6832,6 +7753,22 @@ impl Rule {
                 (format!("{}_max_scale", prefix), UniformValue::F32(*max_scale)),
                 (format!("{}_max_speed", prefix), UniformValue::F32(*max_speed)),
             ],
+            Rule::Boid {
+                separation_radius,
+                cohesion_radius,
+                alignment_radius,
+                separation_weight,
+                cohesion_weight,
+                alignment_weight,
+                ..
+            } => vec![
+                (format!("{}_separation_radius", prefix), UniformValue::F32(*separation_radius)),
+                (format!("{}_cohesion_radius", prefix), UniformValue::F32(*cohesion_radius)),
+                (format!("{}_alignment_radius", prefix), UniformValue::F32(*alignment_radius)),
+                (format!("{}_separation_weight", prefix), UniformValue::F32(*separation_weight)),
+                (format!("{}_cohesion_weight", prefix), UniformValue::F32(*cohesion_weight)),
+                (format!("{}_alignment_weight", prefix), UniformValue::F32(*alignment_weight)),
+            ],
             Rule::Flock { radius, separation, cohesion, alignment } => vec![
                 (format!("{}_radius", prefix), UniformValue::F32(*radius)),
                 (format!("{}_separation", prefix), UniformValue::F32(*separation)),
@@ -6847,6 +7784,9 @@ impl Rule {
                 (format!("{}_field", prefix), UniformValue::U32(*field)),
                 (format!("{}_strength", prefix), UniformValue::F32(*strength)),
             ],
+            Rule::FieldGradient { strength, .. } => vec![
+                (format!("{}_strength", prefix), UniformValue::F32(*strength)),
+            ],
             Rule::ChainSprings { stiffness, damping, rest_length, .. } => vec![
                 (format!("{}_stiffness", prefix), UniformValue::F32(*stiffness)),
                 (format!("{}_damping", prefix), UniformValue::F32(*damping)),
@@ -6899,6 +7839,22 @@ impl Rule {
             Rule::Acceleration(_) => format!(
                 "    // Acceleration (dynamic)\n    p.velocity += uniforms.{prefix}_acceleration * uniforms.delta_time;"
             ),
+            Rule::Magnetic { charge_field, .. } => format!(
+                r#"    // Magnetic (dynamic)
+    p.velocity += p.{charge_field} * cross(p.velocity, uniforms.{prefix}_field_strength) * uniforms.delta_time;"#
+            ),
+            Rule::Pulse { wave_shape, .. } => {
+                let wave = wave_shape.to_wgsl_expr(&format!(
+                    "uniforms.time * uniforms.{prefix}_frequency + uniforms.{prefix}_phase"
+                ));
+                format!(
+                    r#"    // Pulse (dynamic)
+    {{
+        let pulse = {wave} * uniforms.{prefix}_amplitude;
+        p.velocity += uniforms.{prefix}_direction * pulse * uniforms.delta_time;
+    }}"#
+                )
+            }
             Rule::AttractTo { .. } => format!(
                 r#"    // Attract to point (dynamic)
     {{
@@ -6920,7 +7876,31 @@ impl Rule {
         }}
     }}"#
             ),
-            Rule::Vortex { .. } => format!(
+            Rule::FieldGradient { field_index, signed, .. } => {
+                let strength = if *signed {
+                    format!("uniforms.{prefix}_strength")
+                } else {
+                    format!("abs(uniforms.{prefix}_strength)")
+                };
+                format!(
+                    r#"    // Field gradient (dynamic)
+    {{
+        let fg_params = field_params[{field_index}u];
+        var grad: vec3<f32>;
+        if fg_params.field_type == FIELD_TYPE_VECTOR {{
+            grad = field_read_vec3({field_index}u, p.position);
+        }} else {{
+            let h = 2.0 * fg_params.extent / f32(fg_params.resolution);
+            let dx = field_read({field_index}u, p.position + vec3<f32>(h, 0.0, 0.0)) - field_read({field_index}u, p.position - vec3<f32>(h, 0.0, 0.0));
+            let dy = field_read({field_index}u, p.position + vec3<f32>(0.0, h, 0.0)) - field_read({field_index}u, p.position - vec3<f32>(0.0, h, 0.0));
+            let dz = field_read({field_index}u, p.position + vec3<f32>(0.0, 0.0, h)) - field_read({field_index}u, p.position - vec3<f32>(0.0, 0.0, h));
+            grad = vec3<f32>(dx, dy, dz) / (2.0 * h);
+        }}
+        p.velocity += grad * {strength} * uniforms.delta_time;
+    }}"#
+                )
+            }
+            Rule::Vortex { falloff, .. } => format!(
                 r#"    // Vortex (dynamic)
     {{
         let to_particle = p.position - uniforms.{prefix}_center;
@@ -6928,11 +7908,17 @@ impl Rule {
         let proj = dot(to_particle, axis_norm) * axis_norm;
         let radial = to_particle - proj;
         let dist = length(radial);
-        if dist > 0.001 {{
+        let radius = uniforms.{prefix}_radius;
+        if (radius <= 0.0 || dist < radius) && dist > 0.001 {{
+            var falloff = 1.0;
+            if radius > 0.0 {{
+                falloff = {falloff_expr};
+            }}
             let tangent = cross(axis_norm, normalize(radial));
-            p.velocity += tangent * uniforms.{prefix}_strength * uniforms.delta_time;
+            p.velocity += tangent * uniforms.{prefix}_strength * falloff * uniforms.delta_time;
         }}
-    }}"#
+    }}"#,
+                falloff_expr = falloff.to_wgsl_expr()
             ),
             Rule::Turbulence { .. } => format!(
                 r#"    // Turbulence (dynamic)
@@ -6944,17 +7930,35 @@ impl Rule {
             noise3(noise_pos + vec3<f32>(0.0, 100.0, 0.0))
         );
         p.velocity += force * uniforms.{prefix}_strength * uniforms.delta_time;
+    }}"#
+            ),
+            Rule::NoiseField { .. } => format!(
+                r#"    // Noise field (dynamic)
+    {{
+        let noise_pos = p.position * uniforms.{prefix}_frequency + vec3<f32>(uniforms.time * uniforms.{prefix}_time_scale);
+        p.velocity += fbm_value3(noise_pos, uniforms.{prefix}_octaves) * uniforms.{prefix}_amplitude * uniforms.delta_time;
     }}"#
             ),
             Rule::Orbit { .. } => format!(
                 r#"    // Orbit (dynamic)
     {{
         let to_center = uniforms.{prefix}_center - p.position;
-        let dist = length(to_center);
-        if dist > 0.001 {{
-            let tangent = normalize(cross(vec3<f32>(0.0, 1.0, 0.0), to_center));
-            p.velocity += tangent * uniforms.{prefix}_strength * uniforms.delta_time;
-            p.velocity += normalize(to_center) * uniforms.{prefix}_strength * 0.1 * uniforms.delta_time;
+        let r_sq = dot(to_center, to_center);
+        if r_sq > 0.000001 {{
+            let dist_sq = r_sq + uniforms.{prefix}_softening * uniforms.{prefix}_softening;
+            p.velocity += to_center * inverseSqrt(r_sq) * (uniforms.{prefix}_gravity / dist_sq) * uniforms.delta_time;
+        }}
+    }}"#
+            ),
+            Rule::OrbitEllipse { .. } => format!(
+                r#"    // Orbit ellipse (dynamic)
+    {{
+        let to_center = uniforms.{prefix}_center - p.position;
+        let r_sq = dot(to_center, to_center);
+        if r_sq > 0.000001 {{
+            let mu = uniforms.{prefix}_gravity * (uniforms.{prefix}_central_mass + uniforms.{prefix}_particle_mass);
+            let dist_sq = r_sq + uniforms.{prefix}_softening * uniforms.{prefix}_softening;
+            p.velocity += to_center * inverseSqrt(r_sq) * (mu / dist_sq) * uniforms.delta_time;
         }}
     }}"#
             ),
@@ -6970,14 +7974,18 @@ impl Rule {
             Rule::Spring { .. } => format!(
                 r#"    // Spring (dynamic)
     {{
-        let displacement = uniforms.{prefix}_anchor - p.position;
-        let spring_force = displacement * uniforms.{prefix}_stiffness;
-        let damping_force = -p.velocity * uniforms.{prefix}_damping;
-        p.velocity += (spring_force + damping_force) * uniforms.delta_time;
+        let displacement = p.position - uniforms.{prefix}_anchor;
+        let dist = length(displacement);
+        if dist > 0.0001 {{
+            let spring_dir = displacement / dist;
+            let spring_force = spring_dir * (uniforms.{prefix}_stiffness * (uniforms.{prefix}_rest_length - dist));
+            let damping_force = spring_dir * (dot(p.velocity, spring_dir) * uniforms.{prefix}_damping);
+            p.velocity += (spring_force - damping_force) * uniforms.delta_time;
+        }}
     }}"#
             ),
-            Rule::Pulse { .. } => format!(
-                r#"    // Pulse (dynamic)
+            Rule::RadialPulse { .. } => format!(
+                r#"    // Radial pulse (dynamic)
     {{
         let to_particle = p.position - uniforms.{prefix}_point;
         let dist = length(to_particle);
@@ -7004,6 +8012,20 @@ impl Rule {
                 r#"    // Lifetime (dynamic)
     if p.age >= uniforms.{prefix}_duration {{
         p.alive = 0u;
+    }}"#
+            ),
+            Rule::MaxAge { scale_field, .. } => format!(
+                r#"    // Max age (dynamic)
+    {{
+        if p.age - uniforms.delta_time <= uniforms.{prefix}_fade_out_start {{
+            p.{scale_field} = p.scale;
+        }}
+        let fade_len = max(uniforms.{prefix}_max_age - uniforms.{prefix}_fade_out_start, 0.0001);
+        let fade_now = 1.0 - saturate((p.age - uniforms.{prefix}_fade_out_start) / fade_len);
+        p.scale = p.{scale_field} * fade_now;
+        if p.age >= uniforms.{prefix}_max_age {{
+            p.alive = 0u;
+        }}
     }}"#
             ),
             Rule::Curl { .. } => format!(
@@ -7149,6 +8171,21 @@ impl Rule {
     {{
         p.scale = max(0.0, 1.0 - p.age / uniforms.{prefix}_duration);
     }}"#
+            ),
+            Rule::FadeIn { scale_field: Some(scale_field), .. } => format!(
+                r#"    // Fade in (dynamic)
+    {{
+        if p.age - uniforms.delta_time <= 0.0 {{
+            p.{scale_field} = p.scale;
+        }}
+        if p.age - uniforms.delta_time < uniforms.{prefix}_duration {{
+            p.scale = p.{scale_field} * saturate(p.age / max(uniforms.{prefix}_duration, 0.0001));
+        }}
+    }}"#
+            ),
+            Rule::FadeIn { scale_field: None, .. } => format!(
+                r#"    // Fade in (dynamic)
+    p.scale *= saturate(p.age / max(uniforms.{prefix}_duration, 0.0001));"#
             ),
             Rule::ColorOverLife { .. } => format!(
                 r#"    // Color over life (dynamic)
@@ -7316,15 +8353,16 @@ impl Rule {
         align_count += 1.0;
     }}"#
             ),
-            Rule::Collide { .. } => format!(
-                r#"if neighbor_dist < uniforms.{prefix}_radius && neighbor_dist > 0.001 {{
+            Rule::Collide { self_type, target_type, .. } => format!(
+                r#"if {type_check} && neighbor_dist < uniforms.{prefix}_radius && neighbor_dist > 0.001 {{
         let overlap = uniforms.{prefix}_radius - neighbor_dist;
         let rel_vel = dot(p.velocity - neighbor_vel, neighbor_dir);
         if rel_vel < 0.0 {{
             p.velocity -= neighbor_dir * rel_vel * (1.0 + uniforms.{prefix}_restitution) * 0.5;
         }}
         p.position += neighbor_dir * overlap * 0.5;
-    }}"#
+    }}"#,
+                type_check = collide_type_check(*self_type, *target_type)
             ),
             Rule::Avoid { .. } => format!(
                 r#"if neighbor_dist < uniforms.{prefix}_radius && neighbor_dist > 0.001 {{
@@ -7337,11 +8375,6 @@ impl Rule {
                 r#"if neighbor_dist < uniforms.{prefix}_radius {{
         let weight = 1.0 - neighbor_dist / uniforms.{prefix}_radius;
         p.velocity = mix(p.velocity, neighbor_vel, weight * uniforms.{prefix}_strength * uniforms.delta_time);
-    }}"#
-            ),
-            Rule::Pressure { .. } => format!(
-                r#"if neighbor_dist < uniforms.{prefix}_radius {{
-        pressure_density += 1.0;
     }}"#
             ),
             Rule::Chase { .. } => format!(
@@ -7578,12 +8611,14 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
     fn test_spring_wgsl() {
         let rule = Rule::Spring {
             anchor: Vec3::new(0.0, 1.0, 0.0),
+            rest_length: 0.25,
             stiffness: 10.0,
             damping: 0.5,
         };
         let wgsl = rule.to_wgsl(1.0);
 
         assert!(wgsl.contains("Spring"));
+        assert!(wgsl.contains("0.25 - dist"));
 
         let shader = wrap_in_shader(&wgsl);
         validate_wgsl(&shader).expect("Spring WGSL should be valid");
@@ -7597,6 +8632,8 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
             center: Vec3::ZERO,
             axis: Vec3::Y,
             strength: 5.0,
+            radius: 0.0,
+            falloff: Falloff::Constant,
         };
         let wgsl = rule.to_wgsl(1.0);
 
@@ -7607,6 +8644,34 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
         validate_wgsl(&shader).expect("Vortex WGSL should be valid");
     }
 
+    #[test]
+    fn test_vortex_falloff_wgsl() {
+        for falloff in [
+            Falloff::Constant,
+            Falloff::Linear,
+            Falloff::Inverse,
+            Falloff::InverseSquare,
+            Falloff::Smooth,
+        ] {
+            let rule = Rule::Vortex {
+                center: Vec3::ZERO,
+                axis: Vec3::new(0.0, 2.0, 0.0),
+                strength: 5.0,
+                radius: 1.0,
+                falloff,
+            };
+            let wgsl = rule.to_wgsl(1.0);
+
+            // Axis is normalized at generation time
+            assert!(wgsl.contains("vec3<f32>(0, 1, 0)"));
+            assert!(wgsl.contains("dist < 1 &&"));
+
+            let shader = wrap_in_shader(&wgsl);
+            validate_wgsl(&shader)
+                .unwrap_or_else(|e| panic!("Vortex {:?} WGSL should be valid: {}", falloff, e));
+        }
+    }
+
     #[test]
     fn test_turbulence_wgsl() {
         let rule = Rule::Turbulence {
@@ -7622,21 +8687,110 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
         validate_wgsl(&shader).expect("Turbulence WGSL should be valid");
     }
 
+    #[test]
+    fn test_noise_field_wgsl() {
+        let rule = Rule::NoiseField {
+            frequency: 3.0,
+            amplitude: 1.5,
+            octaves: 4,
+            time_scale: -0.2,
+        };
+        let wgsl = rule.to_wgsl(1.0);
+
+        assert!(wgsl.contains("Noise field"));
+        assert!(wgsl.contains("fbm_value3(noise_pos, 4u)"));
+        assert!(rule.requires_value_noise());
+        assert!(!rule.requires_neighbors());
+
+        let shader = format!(
+            "{}\n{}",
+            crate::shader_utils::VALUE_NOISE_WGSL,
+            wrap_in_shader(&wgsl)
+        );
+        validate_wgsl(&shader).expect("NoiseField WGSL should be valid");
+    }
+
+    #[test]
+    fn test_noise_field_dynamic_wgsl() {
+        let rule = Rule::NoiseField {
+            frequency: 1.0,
+            amplitude: 1.0,
+            octaves: 2,
+            time_scale: 0.5,
+        };
+        let wgsl = rule.to_wgsl_dynamic(0, 1.0);
+        let params = rule.params(0);
+
+        assert!(wgsl.contains("uniforms.rule_0_octaves"));
+        assert_eq!(params.len(), 4);
+        assert!(params.iter().any(|(name, v)| name == "rule_0_octaves" && matches!(v, crate::uniforms::UniformValue::U32(2))));
+    }
+
     #[test]
     fn test_orbit_wgsl() {
         let rule = Rule::Orbit {
             center: Vec3::ZERO,
-            strength: 5.0,
+            gravity: 5.0,
+            softening: 0.05,
         };
         let wgsl = rule.to_wgsl(1.0);
 
         assert!(wgsl.contains("Orbit"));
-        assert!(wgsl.contains("centripetal"));
+        assert!(wgsl.contains("0.05 * 0.05"));
+        assert!(!rule.requires_neighbors());
 
         let shader = wrap_in_shader(&wgsl);
         validate_wgsl(&shader).expect("Orbit WGSL should be valid");
     }
 
+    #[test]
+    fn test_orbit_ellipse_wgsl() {
+        let rule = Rule::OrbitEllipse {
+            center: Vec3::new(0.0, 0.5, 0.0),
+            gravity: 1.0,
+            central_mass: 2.0,
+            particle_mass: 0.5,
+            softening: 0.01,
+        };
+        let wgsl = rule.to_wgsl(1.0);
+
+        assert!(wgsl.contains("reduced mass"));
+        assert!(wgsl.contains("(2 + 0.5)"));
+        assert!(!rule.requires_neighbors());
+
+        let shader = wrap_in_shader(&wgsl);
+        validate_wgsl(&shader).expect("OrbitEllipse WGSL should be valid");
+    }
+
+    #[test]
+    fn test_field_gradient_wgsl() {
+        let uphill = Rule::FieldGradient {
+            field_index: 1,
+            strength: -2.0,
+            signed: false,
+        };
+        let wgsl = uphill.to_wgsl(1.0);
+        assert!(wgsl.contains("field_params[1u]"));
+        assert!(wgsl.contains("grad * 2.0"));
+        assert!(wgsl.contains("field_read_vec3(1u, p.position)"));
+        assert_eq!(uphill.required_fields(), vec![1]);
+
+        let signed = Rule::FieldGradient {
+            field_index: 0,
+            strength: -2.0,
+            signed: true,
+        };
+        assert!(signed.to_wgsl(1.0).contains("grad * -2.0"));
+
+        let typed = Rule::Typed {
+            self_type: 0,
+            other_type: None,
+            rule: Box::new(signed),
+        };
+        assert_eq!(typed.required_fields(), vec![0]);
+        assert!(Rule::Gravity(1.0).required_fields().is_empty());
+    }
+
     #[test]
     fn test_curl_wgsl() {
         let rule = Rule::Curl {
@@ -7654,6 +8808,30 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
 
     // ========== Wave/Modulation Rules ==========
 
+    #[test]
+    fn test_pulse_wave_shapes_wgsl() {
+        for (shape, marker) in [
+            (WaveShape::Sine, "sin(uniforms.time"),
+            (WaveShape::Square, "sign(sin("),
+            (WaveShape::Triangle, "asin(sin("),
+        ] {
+            let rule = Rule::Pulse {
+                direction: Vec3::new(0.0, 2.0, 0.0),
+                frequency: 3.0,
+                amplitude: 0.5,
+                phase: 1.0,
+                wave_shape: shape,
+            };
+            let wgsl = rule.to_wgsl(1.0);
+            assert!(wgsl.contains(marker), "{shape:?}: {wgsl}");
+            assert!(wgsl.contains("vec3<f32>(0.0, 1.0, 0.0)"));
+            assert!(!rule.requires_neighbors());
+
+            let shader = wrap_in_shader(&wgsl);
+            validate_wgsl(&shader).expect("Pulse WGSL should be valid");
+        }
+    }
+
     #[test]
     fn test_oscillate_wgsl() {
         let rule = Rule::Oscillate {
@@ -7687,6 +8865,43 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
         validate_wgsl(&shader).expect("PositionNoise WGSL should be valid");
     }
 
+    #[test]
+    fn test_pressure_sph_wgsl() {
+        let rule = Rule::Pressure {
+            rest_density: 8.0,
+            pressure_constant: 0.5,
+            viscosity: 0.02,
+            kernel_radius: 0.5,
+        };
+        assert!(rule.requires_neighbors());
+        assert!(rule.requires_sph_kernels());
+        assert!(rule.to_wgsl(1.0).is_empty());
+
+        let neighbor = rule.to_neighbor_wgsl();
+        assert!(neighbor.contains("0.125 * sph_poly6(neighbor_dist, 0.5)"));
+        assert!(neighbor.contains("sph_spiky_grad"));
+        assert!(neighbor.contains("sph_viscosity_laplacian"));
+
+        let post = rule.to_post_neighbor_wgsl();
+        assert!(post.contains("max(0.5 * (density - 8.0), 0.0)"));
+        assert!(!Rule::Gravity(1.0).requires_sph_kernels());
+    }
+
+    #[test]
+    fn test_collide_type_filters() {
+        let unfiltered = Rule::Collide { radius: 0.1, restitution: 0.5, self_type: None, target_type: None };
+        let code = unfiltered.to_neighbor_wgsl();
+        assert!(code.contains("if true && neighbor_dist < 0.1"));
+        assert!(code.contains("(1.0 + 0.5)"));
+
+        let filtered = Rule::Collide { radius: 0.1, restitution: 0.5, self_type: Some(0), target_type: Some(2) };
+        let code = filtered.to_neighbor_wgsl();
+        assert!(code.contains("if p.particle_type == 0u && other.particle_type == 2u && neighbor_dist < 0.1"));
+
+        let target_only = Rule::Collide { radius: 0.1, restitution: 0.5, self_type: None, target_type: Some(1) };
+        assert!(target_only.to_neighbor_wgsl().contains("if other.particle_type == 1u && "));
+    }
+
     // ========== Lifecycle Rules ==========
 
     #[test]
@@ -7700,11 +8915,70 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
         validate_wgsl(&shader).expect("Age WGSL should be valid");
     }
 
+    #[test]
+    fn test_max_age_wgsl() {
+        let rule = Rule::MaxAge {
+            max_age: 3.0,
+            fade_out_start: 2.0,
+            scale_field: "base_scale".into(),
+        };
+        let wgsl = rule.to_wgsl(1.0);
+
+        assert!(wgsl.contains("p.age >= 3.0"));
+        assert!(wgsl.contains("p.base_scale = p.scale;"));
+        assert!(wgsl.contains("p.scale = p.base_scale * fade_now"));
+        assert!(!rule.requires_neighbors());
+    }
+
+    #[test]
+    fn test_fade_in_wgsl() {
+        let rule = Rule::FadeIn { duration: 0.5, scale_field: Some("base_scale".into()) };
+        let wgsl = rule.to_wgsl(1.0);
+
+        assert!(wgsl.contains("Fade in"));
+        assert!(wgsl.contains("p.base_scale = p.scale;"));
+        assert!(wgsl.contains("p.scale = p.base_scale * saturate(p.age / max(0.5, 0.0001))"));
+        assert!(!rule.requires_neighbors());
+
+        let wgsl = Rule::FadeIn { duration: 0.5, scale_field: None }.to_wgsl(1.0);
+        assert!(wgsl.contains("p.scale *= saturate(p.age / max(0.5, 0.0001))"));
+        assert!(!wgsl.contains("base_scale"));
+    }
+
+    #[test]
+    fn test_neighbor_radius() {
+        assert_eq!(Rule::Separate { radius: 0.1, strength: 1.0 }.neighbor_radius(), Some(0.1));
+        assert_eq!(Rule::Gravity(9.8).neighbor_radius(), None);
+
+        let boid = Rule::Boid {
+            separation_radius: 0.05,
+            cohesion_radius: 0.2,
+            alignment_radius: 0.1,
+            separation_weight: 1.0,
+            cohesion_weight: 1.0,
+            alignment_weight: 1.0,
+            self_type: None,
+        };
+        assert_eq!(boid.neighbor_radius(), Some(0.05));
+
+        let typed = Rule::Typed {
+            self_type: 0,
+            other_type: Some(1),
+            rule: Box::new(Rule::Cohere { radius: 0.3, strength: 1.0 }),
+        };
+        assert_eq!(typed.neighbor_radius(), Some(0.3));
+
+        let mut matrix = crate::InteractionMatrix::new(2);
+        matrix.set(0u32, 1u32, 1.0, 0.4);
+        matrix.set(1u32, 0u32, -1.0, 0.25);
+        assert_eq!(Rule::TypedMulti(matrix).neighbor_radius(), Some(0.25));
+    }
+
     // ========== Custom Rules ==========
 
     #[test]
     fn test_custom_wgsl() {
-        let rule = Rule::Custom("p.velocity.x += 1.0;".into());
+        let rule = Rule::custom("p.velocity.x += 1.0;");
         let wgsl = rule.to_wgsl(1.0);
 
         assert!(wgsl.contains("velocity.x += 1.0"));
@@ -7713,6 +8987,41 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
         validate_wgsl(&shader).expect("Custom WGSL should be valid");
     }
 
+    #[test]
+    fn test_custom_multi_wgsl() {
+        let rule = Rule::CustomMulti(vec![
+            ("declarations".into(), "    let speed = length(p.velocity);".into()),
+            ("integration".into(), "    p.velocity.x += speed * 0.1;".into()),
+        ]);
+        let wgsl = rule.to_wgsl(1.0);
+
+        // Sections appear in order, each labelled by name
+        let decl = wgsl.find("section: declarations").unwrap();
+        let integ = wgsl.find("section: integration").unwrap();
+        assert!(decl < integ);
+        assert!(wgsl.find("let speed").unwrap() < wgsl.find("speed * 0.1").unwrap());
+
+        let shader = wrap_in_shader(&wgsl);
+        validate_wgsl(&shader).expect("CustomMulti WGSL should be valid");
+    }
+
+    #[test]
+    fn test_custom_multi_error_names_section() {
+        let rule = Rule::CustomMulti(vec![
+            ("declarations".into(), "    let speed = length(p.velocity);".into()),
+            ("steering".into(), "    p.velocity.x += speed * 0.1;\n    p.velocity.y += missing;".into()),
+        ]);
+        let shader = wrap_in_shader(&rule.to_wgsl(1.0));
+
+        let error = naga::front::wgsl::parse_str(&shader).expect_err("undefined identifier should fail");
+        let described = describe_shader_error(&shader, &error.emit_to_string(&shader));
+        assert!(described.contains("In custom section 'steering', line 2"), "{}", described);
+
+        // Lines outside any section are left alone
+        assert_eq!(custom_section_at_line(&shader, 1), None);
+        assert_eq!(describe_shader_error(&shader, "no location"), "no location");
+    }
+
     // ========== Falloff ==========
 
     #[test]
@@ -7741,7 +9050,7 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
             Rule::Separate { radius: 0.1, strength: 1.0 },
             Rule::Cohere { radius: 0.5, strength: 1.0 },
             Rule::Align { radius: 0.3, strength: 1.0 },
-            Rule::Collide { radius: 0.1, restitution: 0.8 },
+            Rule::Collide { radius: 0.1, restitution: 0.8, self_type: None, target_type: None },
             Rule::NBodyGravity { radius: 1.0, strength: 1.0, softening: 0.01 },
         ];
 
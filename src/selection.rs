@@ -98,8 +98,8 @@ pub fn selected_particle_data<P: ParticleTrait>(ctx: &egui::Context) -> Option<P
         .and_then(|spd| spd.0)
         .and_then(|bytes| {
             // Convert raw bytes to GPU struct, then to Rust struct
-            if bytes.len() >= std::mem::size_of::<P::Gpu>() {
-                let gpu_particle: &P::Gpu = bytemuck::from_bytes(&bytes[..std::mem::size_of::<P::Gpu>()]);
+            if bytes.len() >= P::GPU_STRIDE {
+                let gpu_particle: &P::Gpu = bytemuck::from_bytes(&bytes[..P::GPU_STRIDE]);
                 Some(P::from_gpu(gpu_particle))
             } else {
                 None
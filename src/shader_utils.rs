@@ -7,7 +7,8 @@
 //! # Available Functions
 //!
 //! ## Random & Hash
-//! - `hash(n: u32) -> u32` - Hash a u32 to pseudo-random u32
+//! - `hash(n: u32) -> u32` - Hash a u32 to pseudo-random u32 (mixed with the
+//!   simulation seed, see [`crate::Simulation::deterministic`])
 //! - `hash2(p: vec2<u32>) -> u32` - Hash a 2D coordinate to pseudo-random u32
 //! - `hash3(p: vec3<u32>) -> u32` - Hash a 3D coordinate to pseudo-random u32
 //! - `rand(seed: u32) -> f32` - Returns random float in [0, 1)
@@ -20,6 +21,29 @@
 //! - `fbm2(p: vec2<f32>, octaves: i32) -> f32` - 2D fractal Brownian motion
 //! - `fbm3(p: vec3<f32>, octaves: i32) -> f32` - 3D fractal Brownian motion
 //!
+//! ## Value Noise
+//! Only included when a rule needs it (e.g. [`crate::Rule::NoiseField`]),
+//! see [`VALUE_NOISE_WGSL`].
+//! - `pcg3d(v: vec3<u32>) -> vec3<u32>` - PCG3D integer hash
+//! - `value_noise3(p: vec3<f32>) -> vec3<f32>` - 3D vector value noise in [-1, 1]
+//! - `fbm_value3(p: vec3<f32>, octaves: u32) -> vec3<f32>` - Fractal value noise
+//!
+//! ## SPH Kernels
+//! Only included when a rule needs it (e.g. [`crate::Rule::Pressure`]),
+//! see [`SPH_KERNELS_WGSL`].
+//! - `sph_poly6(r: f32, h: f32) -> f32` - Poly6 density kernel
+//! - `sph_spiky_grad(r: f32, h: f32) -> f32` - Radial derivative of the spiky kernel
+//! - `sph_viscosity_laplacian(r: f32, h: f32) -> f32` - Laplacian of the viscosity kernel
+//!
+//! ## Optional Helpers
+//! Only included when a [`crate::Rule::Custom`] lists them in `helpers`,
+//! see [`ShaderHelper`].
+//! - [`ShaderHelper::Hash`] - `pcg_hash`, `pcg_hash_f32` (unseeded PCG hash)
+//! - [`ShaderHelper::Noise`] - `value_noise(p: vec3<f32>) -> f32`
+//! - [`ShaderHelper::Easing`] - `ease_smoothstep`, `ease_smootherstep`, `ease_elastic`, `ease_bounce`
+//! - [`ShaderHelper::Sdf`] - `sdf_sphere`, `sdf_box`, `sdf_capsule`
+//! - [`ShaderHelper::Quaternion`] - `quat_mul`, `quat_rotate`, `quat_slerp`, `quat_from_to`
+//!
 //! ## Color
 //! - `hsv_to_rgb(h: f32, s: f32, v: f32) -> vec3<f32>` - Convert HSV to RGB
 //! - `rgb_to_hsv(rgb: vec3<f32>) -> vec3<f32>` - Convert RGB to HSV
@@ -34,7 +58,7 @@
 //! # Example
 //!
 //! ```ignore
-//! .with_rule(Rule::Custom(r#"
+//! .with_rule(Rule::custom(r#"
 //!     // Random color based on particle index
 //!     let seed = index * 12345u;
 //!     p.color = hsv_to_rgb(rand(seed), 0.8, 1.0);
@@ -46,14 +70,18 @@
 //!         noise3(p.position * 3.0 + uniforms.time + vec3(0.0, 100.0, 0.0))
 //!     );
 //!     p.velocity += noise_force * 0.1;
-//! "#.into()))
+//! "#))
 //! ```
 
 /// WGSL code for random/hash functions.
 pub const RANDOM_WGSL: &str = r#"
+// Seed mixed into every hash; set from the seed uniforms at the start of the
+// compute shader (0 leaves the hash unseeded)
+var<private> rng_seed: u32 = 0u;
+
 // Hash functions for pseudo-random number generation
 fn hash(n: u32) -> u32 {
-    var x = n;
+    var x = n ^ rng_seed;
     x = x ^ (x >> 17u);
     x = x * 0xed5ad4bbu;
     x = x ^ (x >> 11u);
@@ -213,6 +241,74 @@ fn fbm2(p: vec2<f32>, octaves: i32) -> f32 {
 }
 "#;
 
+/// WGSL code for hash-based 3D value noise.
+///
+/// Uses the PCG3D integer hash (Jarzynski & Olano, 2020) to assign a random
+/// vector to every lattice point, then blends them with smoothstep weights.
+/// Each noise sample therefore yields a full `vec3` in one evaluation,
+/// rather than three offset scalar lookups.
+///
+/// Not part of [`all_utils_wgsl`]; shader generators append it when a rule
+/// reports [`crate::Rule::requires_value_noise`].
+pub const VALUE_NOISE_WGSL: &str = r#"
+// PCG3D integer hash
+fn pcg3d(v_in: vec3<u32>) -> vec3<u32> {
+    var v = v_in * 1664525u + 1013904223u;
+    v.x += v.y * v.z;
+    v.y += v.z * v.x;
+    v.z += v.x * v.y;
+    v = v ^ (v >> vec3<u32>(16u));
+    v.x += v.y * v.z;
+    v.y += v.z * v.x;
+    v.z += v.x * v.y;
+    return v;
+}
+
+// Random vector in [-1, 1] for a lattice cell
+fn pcg3d_signed(cell: vec3<i32>) -> vec3<f32> {
+    let h = pcg3d(bitcast<vec3<u32>>(cell));
+    return vec3<f32>(h) / 4294967295.0 * 2.0 - 1.0;
+}
+
+// 3D value noise with a vector value per lattice point, in [-1, 1]
+fn value_noise3(p: vec3<f32>) -> vec3<f32> {
+    let cell = vec3<i32>(floor(p));
+    let f = fract(p);
+    let u = f * f * (3.0 - 2.0 * f);
+
+    let c000 = pcg3d_signed(cell);
+    let c100 = pcg3d_signed(cell + vec3<i32>(1, 0, 0));
+    let c010 = pcg3d_signed(cell + vec3<i32>(0, 1, 0));
+    let c110 = pcg3d_signed(cell + vec3<i32>(1, 1, 0));
+    let c001 = pcg3d_signed(cell + vec3<i32>(0, 0, 1));
+    let c101 = pcg3d_signed(cell + vec3<i32>(1, 0, 1));
+    let c011 = pcg3d_signed(cell + vec3<i32>(0, 1, 1));
+    let c111 = pcg3d_signed(cell + vec3<i32>(1, 1, 1));
+
+    let x00 = mix(c000, c100, u.x);
+    let x10 = mix(c010, c110, u.x);
+    let x01 = mix(c001, c101, u.x);
+    let x11 = mix(c011, c111, u.x);
+    return mix(mix(x00, x10, u.y), mix(x01, x11, u.y), u.z);
+}
+
+// Fractal value noise, normalized back to roughly [-1, 1]
+fn fbm_value3(p: vec3<f32>, octaves: u32) -> vec3<f32> {
+    var sum = vec3<f32>(0.0);
+    var amplitude = 0.5;
+    var total = 0.0;
+    var pos = p;
+    for (var i = 0u; i < octaves; i++) {
+        sum += amplitude * value_noise3(pos);
+        total += amplitude;
+        // Offset each octave so lattice points don't line up
+        pos = pos * 2.0 + vec3<f32>(17.0, 31.0, 47.0);
+        amplitude *= 0.5;
+    }
+    return sum / max(total, 0.0001);
+}
+"#;
+
 /// WGSL code for color conversion functions.
 pub const COLOR_WGSL: &str = r#"
 // HSV to RGB conversion
@@ -287,7 +383,7 @@ fn rgb_to_hsv(rgb: vec3<f32>) -> vec3<f32> {
 /// # Example
 ///
 /// ```ignore
-/// .with_rule(Rule::Custom(r#"
+/// .with_rule(Rule::custom(r#"
 ///     // Kill particles that go too far
 ///     if length(p.position) > 2.0 {
 ///         kill_particle(&p);
@@ -297,7 +393,7 @@ fn rgb_to_hsv(rgb: vec3<f32>) -> vec3<f32> {
 ///     if is_dead(p) {
 ///         respawn_at(&p, vec3<f32>(0.0), rand_vec3(index));
 ///     }
-/// "#.into()))
+/// "#))
 /// ```
 pub const LIFECYCLE_WGSL: &str = r#"
 // Particle lifecycle helpers
@@ -335,6 +431,44 @@ fn respawn_at(p: ptr<function, Particle>, pos: vec3<f32>, vel: vec3<f32>) {
 }
 "#;
 
+/// WGSL code for Smoothed Particle Hydrodynamics kernels.
+///
+/// The standard kernels from Müller et al., "Particle-Based Fluid Simulation
+/// for Interactive Applications" (2003). All take the distance `r` between
+/// two particles and the smoothing length `h`, and return zero for `r >= h`.
+/// `sph_spiky_grad` returns the scalar derivative; multiply it by the unit
+/// vector between the particles to get the gradient.
+///
+/// Not part of [`all_utils_wgsl`]; shader generators append it when a rule
+/// reports [`crate::Rule::requires_sph_kernels`].
+pub const SPH_KERNELS_WGSL: &str = r#"
+// Poly6 kernel, used for density
+fn sph_poly6(r: f32, h: f32) -> f32 {
+    if r >= h {
+        return 0.0;
+    }
+    let d = h * h - r * r;
+    return 315.0 / (64.0 * 3.14159265 * pow(h, 9.0)) * d * d * d;
+}
+
+// Derivative of the spiky kernel, used for the pressure gradient
+fn sph_spiky_grad(r: f32, h: f32) -> f32 {
+    if r >= h || r <= 0.0 {
+        return 0.0;
+    }
+    let d = h - r;
+    return -45.0 / (3.14159265 * pow(h, 6.0)) * d * d;
+}
+
+// Laplacian of the viscosity kernel
+fn sph_viscosity_laplacian(r: f32, h: f32) -> f32 {
+    if r >= h {
+        return 0.0;
+    }
+    return 45.0 / (3.14159265 * pow(h, 6.0)) * (h - r);
+}
+"#;
+
 /// Get all built-in utility functions combined.
 pub fn all_utils_wgsl() -> String {
     format!(
@@ -342,3 +476,387 @@ pub fn all_utils_wgsl() -> String {
         RANDOM_WGSL, NOISE_WGSL, COLOR_WGSL, LIFECYCLE_WGSL
     )
 }
+
+/// WGSL PCG hash helpers, opt-in via [`ShaderHelper::Hash`].
+///
+/// Unlike the built-in `hash`, these ignore the simulation seed, so the
+/// same input always gives the same output.
+pub const HASH_FN: &str = r#"
+// PCG hash (Jarzynski & Olano, "Hash Functions for GPU Rendering")
+fn pcg_hash(n: u32) -> u32 {
+    let state = n * 747796405u + 2891336453u;
+    let word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    return (word >> 22u) ^ word;
+}
+
+// Pseudo-random float in [0, 1]
+fn pcg_hash_f32(n: u32) -> f32 {
+    return f32(pcg_hash(n)) / 4294967295.0;
+}
+"#;
+
+/// WGSL 3D value noise, opt-in via [`ShaderHelper::Noise`].
+///
+/// Calls `pcg_hash`, so [`HASH_FN`] is always included with it.
+pub const NOISE_FN: &str = r#"
+// Random value in [-1, 1] for a lattice point
+fn value_noise_lattice(c: vec3<i32>) -> f32 {
+    let h = pcg_hash(bitcast<u32>(c.x) ^ pcg_hash(bitcast<u32>(c.y) ^ pcg_hash(bitcast<u32>(c.z))));
+    return f32(h) / 4294967295.0 * 2.0 - 1.0;
+}
+
+// Smoothly interpolated 3D value noise in [-1, 1]
+fn value_noise(p: vec3<f32>) -> f32 {
+    let i = vec3<i32>(floor(p));
+    let f = fract(p);
+    let u = f * f * (3.0 - 2.0 * f);
+
+    let x00 = mix(value_noise_lattice(i), value_noise_lattice(i + vec3<i32>(1, 0, 0)), u.x);
+    let x10 = mix(value_noise_lattice(i + vec3<i32>(0, 1, 0)), value_noise_lattice(i + vec3<i32>(1, 1, 0)), u.x);
+    let x01 = mix(value_noise_lattice(i + vec3<i32>(0, 0, 1)), value_noise_lattice(i + vec3<i32>(1, 0, 1)), u.x);
+    let x11 = mix(value_noise_lattice(i + vec3<i32>(0, 1, 1)), value_noise_lattice(i + vec3<i32>(1, 1, 1)), u.x);
+    return mix(mix(x00, x10, u.y), mix(x01, x11, u.y), u.z);
+}
+"#;
+
+/// WGSL easing curves mapping [0, 1] to [0, 1], opt-in via [`ShaderHelper::Easing`].
+///
+/// Inputs are clamped to [0, 1].
+pub const EASING_FNS: &str = r#"
+// Cubic Hermite ease in-out
+fn ease_smoothstep(t: f32) -> f32 {
+    let x = clamp(t, 0.0, 1.0);
+    return x * x * (3.0 - 2.0 * x);
+}
+
+// Quintic ease in-out with zero first and second derivatives at the ends
+fn ease_smootherstep(t: f32) -> f32 {
+    let x = clamp(t, 0.0, 1.0);
+    return x * x * x * (x * (x * 6.0 - 15.0) + 10.0);
+}
+
+// Overshoots and springs back before settling at 1
+fn ease_elastic(t: f32) -> f32 {
+    let x = clamp(t, 0.0, 1.0);
+    if x <= 0.0 || x >= 1.0 {
+        return x;
+    }
+    return pow(2.0, -10.0 * x) * sin((x * 10.0 - 0.75) * 2.0943951) + 1.0;
+}
+
+// Bounces off 1 like a dropped ball
+fn ease_bounce(t: f32) -> f32 {
+    let x = clamp(t, 0.0, 1.0);
+    let n = 7.5625;
+    let d = 2.75;
+    if x < 1.0 / d {
+        return n * x * x;
+    } else if x < 2.0 / d {
+        let y = x - 1.5 / d;
+        return n * y * y + 0.75;
+    } else if x < 2.5 / d {
+        let y = x - 2.25 / d;
+        return n * y * y + 0.9375;
+    }
+    let y = x - 2.625 / d;
+    return n * y * y + 0.984375;
+}
+"#;
+
+/// WGSL signed distance functions, opt-in via [`ShaderHelper::Sdf`].
+///
+/// Each returns the distance from `p` to the shape's surface, negative inside.
+pub const SDF_PRIMITIVES: &str = r#"
+// Sphere of `radius` at the origin
+fn sdf_sphere(p: vec3<f32>, radius: f32) -> f32 {
+    return length(p) - radius;
+}
+
+// Axis-aligned box at the origin
+fn sdf_box(p: vec3<f32>, half_extents: vec3<f32>) -> f32 {
+    let q = abs(p) - half_extents;
+    return length(max(q, vec3<f32>(0.0))) + min(max(q.x, max(q.y, q.z)), 0.0);
+}
+
+// Capsule around the segment from `a` to `b`
+fn sdf_capsule(p: vec3<f32>, a: vec3<f32>, b: vec3<f32>, radius: f32) -> f32 {
+    let pa = p - a;
+    let ba = b - a;
+    let h = clamp(dot(pa, ba) / max(dot(ba, ba), 0.000001), 0.0, 1.0);
+    return length(pa - ba * h) - radius;
+}
+"#;
+
+/// WGSL quaternion functions, opt-in via [`ShaderHelper::Quaternion`].
+///
+/// Quaternions are `vec4<f32>` with the vector part in `xyz` and the scalar
+/// part in `w`.
+pub const QUATERNION_FNS: &str = r#"
+// Hamilton product: rotation `b` followed by rotation `a`
+fn quat_mul(a: vec4<f32>, b: vec4<f32>) -> vec4<f32> {
+    return vec4<f32>(a.w * b.xyz + b.w * a.xyz + cross(a.xyz, b.xyz), a.w * b.w - dot(a.xyz, b.xyz));
+}
+
+// Rotate `v` by unit quaternion `q`
+fn quat_rotate(q: vec4<f32>, v: vec3<f32>) -> vec3<f32> {
+    let t = 2.0 * cross(q.xyz, v);
+    return v + q.w * t + cross(q.xyz, t);
+}
+
+// Spherical interpolation between unit quaternions along the shorter arc
+fn quat_slerp(a: vec4<f32>, b: vec4<f32>, t: f32) -> vec4<f32> {
+    var b_near = b;
+    var cos_theta = dot(a, b);
+    if cos_theta < 0.0 {
+        b_near = -b;
+        cos_theta = -cos_theta;
+    }
+    // Nearly parallel: fall back to normalized lerp
+    if cos_theta > 0.9995 {
+        return normalize(mix(a, b_near, t));
+    }
+    let theta = acos(cos_theta);
+    return (a * sin((1.0 - t) * theta) + b_near * sin(t * theta)) / sin(theta);
+}
+
+// Shortest rotation taking direction `from_dir` to direction `to_dir`
+fn quat_from_to(from_dir: vec3<f32>, to_dir: vec3<f32>) -> vec4<f32> {
+    let f = normalize(from_dir);
+    let t = normalize(to_dir);
+    let d = dot(f, t);
+    if d < -0.999999 {
+        // Opposite directions: half turn around any perpendicular axis
+        var axis = cross(vec3<f32>(1.0, 0.0, 0.0), f);
+        if length(axis) < 0.000001 {
+            axis = cross(vec3<f32>(0.0, 1.0, 0.0), f);
+        }
+        return vec4<f32>(normalize(axis), 0.0);
+    }
+    return normalize(vec4<f32>(cross(f, t), 1.0 + d));
+}
+"#;
+
+/// Optional WGSL helper libraries a [`crate::Rule::Custom`] can ask for.
+///
+/// The compute shader generator prepends each requested library once, no
+/// matter how many rules ask for it, along with anything it depends on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ShaderHelper {
+    /// Adds [`HASH_FN`]: `pcg_hash`, `pcg_hash_f32`
+    Hash,
+    /// Adds [`NOISE_FN`]: `value_noise` (includes [`ShaderHelper::Hash`])
+    Noise,
+    /// Adds [`EASING_FNS`]: `ease_smoothstep`, `ease_smootherstep`, `ease_elastic`, `ease_bounce`
+    Easing,
+    /// Adds [`SDF_PRIMITIVES`]: `sdf_sphere`, `sdf_box`, `sdf_capsule`
+    Sdf,
+    /// Adds [`QUATERNION_FNS`]: `quat_mul`, `quat_rotate`, `quat_slerp`, `quat_from_to`
+    Quaternion,
+}
+
+impl ShaderHelper {
+    /// Every helper library, in the order they are emitted.
+    pub const ALL: [ShaderHelper; 5] = [
+        ShaderHelper::Hash,
+        ShaderHelper::Noise,
+        ShaderHelper::Easing,
+        ShaderHelper::Sdf,
+        ShaderHelper::Quaternion,
+    ];
+
+    /// Display name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShaderHelper::Hash => "Hash",
+            ShaderHelper::Noise => "Noise",
+            ShaderHelper::Easing => "Easing",
+            ShaderHelper::Sdf => "SDF",
+            ShaderHelper::Quaternion => "Quaternion",
+        }
+    }
+
+    /// The library's WGSL source.
+    pub fn wgsl(&self) -> &'static str {
+        match self {
+            ShaderHelper::Hash => HASH_FN,
+            ShaderHelper::Noise => NOISE_FN,
+            ShaderHelper::Easing => EASING_FNS,
+            ShaderHelper::Sdf => SDF_PRIMITIVES,
+            ShaderHelper::Quaternion => QUATERNION_FNS,
+        }
+    }
+
+    /// Signatures of the functions meant to be called from rule code.
+    pub fn functions(&self) -> &'static [&'static str] {
+        match self {
+            ShaderHelper::Hash => &["pcg_hash(n: u32) -> u32", "pcg_hash_f32(n: u32) -> f32"],
+            ShaderHelper::Noise => &["value_noise(p: vec3<f32>) -> f32"],
+            ShaderHelper::Easing => &[
+                "ease_smoothstep(t: f32) -> f32",
+                "ease_smootherstep(t: f32) -> f32",
+                "ease_elastic(t: f32) -> f32",
+                "ease_bounce(t: f32) -> f32",
+            ],
+            ShaderHelper::Sdf => &[
+                "sdf_sphere(p: vec3<f32>, radius: f32) -> f32",
+                "sdf_box(p: vec3<f32>, half_extents: vec3<f32>) -> f32",
+                "sdf_capsule(p: vec3<f32>, a: vec3<f32>, b: vec3<f32>, radius: f32) -> f32",
+            ],
+            ShaderHelper::Quaternion => &[
+                "quat_mul(a: vec4<f32>, b: vec4<f32>) -> vec4<f32>",
+                "quat_rotate(q: vec4<f32>, v: vec3<f32>) -> vec3<f32>",
+                "quat_slerp(a: vec4<f32>, b: vec4<f32>, t: f32) -> vec4<f32>",
+                "quat_from_to(from_dir: vec3<f32>, to_dir: vec3<f32>) -> vec4<f32>",
+            ],
+        }
+    }
+
+    /// Other libraries this one calls into.
+    fn dependencies(&self) -> &'static [ShaderHelper] {
+        match self {
+            ShaderHelper::Noise => &[ShaderHelper::Hash],
+            _ => &[],
+        }
+    }
+}
+
+/// WGSL for the requested helper libraries and their dependencies.
+///
+/// Each library appears at most once, in [`ShaderHelper::ALL`] order.
+/// Returns an empty string when `helpers` is empty.
+pub fn helpers_wgsl(helpers: &[ShaderHelper]) -> String {
+    let needed = |helper: &ShaderHelper| {
+        helpers
+            .iter()
+            .any(|h| h == helper || h.dependencies().contains(helper))
+    };
+    ShaderHelper::ALL
+        .iter()
+        .filter(|h| needed(h))
+        .map(|h| format!("// Helper library: {}\n{}", h.name(), h.wgsl()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// WGSL tone-mapping operator: Reinhard (`c / (1 + c)`).
+///
+/// Like the other `TONE_MAP_*` snippets, this defines
+/// `tone_map(color: vec3<f32>) -> vec3<f32>`, mapping HDR color to [0, 1].
+/// These are used by the final post-process pass when HDR rendering is
+/// enabled (see [`crate::VisualConfig::hdr`]) and are not part of
+/// [`all_utils_wgsl`].
+pub const TONE_MAP_REINHARD_WGSL: &str = r#"
+fn tone_map(color: vec3<f32>) -> vec3<f32> {
+    return color / (vec3<f32>(1.0) + color);
+}
+"#;
+
+/// WGSL tone-mapping operator: Krzysztof Narkowicz's fast ACES curve fit.
+pub const TONE_MAP_ACES_FAST_WGSL: &str = r#"
+fn tone_map(color: vec3<f32>) -> vec3<f32> {
+    let a = 2.51;
+    let b = 0.03;
+    let c = 2.43;
+    let d = 0.59;
+    let e = 0.14;
+    let x = color * 0.6;
+    return clamp((x * (a * x + b)) / (x * (c * x + d) + e), vec3<f32>(0.0), vec3<f32>(1.0));
+}
+"#;
+
+/// WGSL tone-mapping operator: Stephen Hill's fitted ACES RRT + ODT.
+///
+/// More accurate hue handling than the fast fit, at the cost of two
+/// matrix multiplies per pixel.
+pub const TONE_MAP_ACES_FILMIC_WGSL: &str = r#"
+fn aces_rrt_odt_fit(v: vec3<f32>) -> vec3<f32> {
+    let a = v * (v + 0.0245786) - 0.000090537;
+    let b = v * (0.983729 * v + 0.4329510) + 0.238081;
+    return a / b;
+}
+
+fn tone_map(color: vec3<f32>) -> vec3<f32> {
+    // sRGB -> ACES AP1 with the RRT saturation adjustment (column-major)
+    let aces_input = mat3x3<f32>(
+        vec3<f32>(0.59719, 0.07600, 0.02840),
+        vec3<f32>(0.35458, 0.90834, 0.13383),
+        vec3<f32>(0.04823, 0.01566, 0.83777),
+    );
+    // ODT output back to sRGB (column-major)
+    let aces_output = mat3x3<f32>(
+        vec3<f32>(1.60475, -0.10208, -0.00327),
+        vec3<f32>(-0.53108, 1.10813, -0.07276),
+        vec3<f32>(-0.07367, -0.00605, 1.07602),
+    );
+    let mapped = aces_output * aces_rrt_odt_fit(aces_input * color);
+    return clamp(mapped, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+"#;
+
+/// WGSL tone-mapping operator: no curve, values above 1.0 are clipped.
+pub const TONE_MAP_LINEAR_WGSL: &str = r#"
+fn tone_map(color: vec3<f32>) -> vec3<f32> {
+    return clamp(color, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+"#;
+
+/// WGSL post-process body: radial chromatic aberration.
+///
+/// Samples red, green and blue at `uv ± aberration_offset * uv_from_center *
+/// channel_weight`, so red is pushed outward, blue inward and green stays
+/// put, with the separation growing toward the screen edges. Expects an
+/// `aberration_strength: f32` to be declared in front of it (0.0 = off,
+/// 1.0 = strong). Prepended to the post-process chain when
+/// [`crate::VisualConfig::chromatic_aberration`] is non-zero.
+pub const CHROMATIC_ABERRATION_WGSL: &str = r#"
+    let aberration_offset = aberration_strength * 0.03;
+    let uv_from_center = in.uv - vec2<f32>(0.5);
+    let channel_weight = vec3<f32>(1.0, 0.0, -1.0);
+    let r = textureSample(scene, scene_sampler, in.uv + aberration_offset * uv_from_center * channel_weight.r).r;
+    let g = textureSample(scene, scene_sampler, in.uv + aberration_offset * uv_from_center * channel_weight.g);
+    let b = textureSample(scene, scene_sampler, in.uv + aberration_offset * uv_from_center * channel_weight.b).b;
+    return vec4<f32>(r, g.g, b, g.a);
+"#;
+
+/// WGSL struct for the directional light used by shadow mapping.
+///
+/// Layout matches the light uniform buffer written by the shadow pass.
+/// Declared by both the shadow depth pass and the main render shader when
+/// a light is configured (see [`crate::VisualConfig::light`]).
+pub const LIGHT_STRUCT_WGSL: &str = r#"
+struct Light {
+    view_proj: mat4x4<f32>,
+    color: vec3<f32>,
+    bias: f32,
+    texel_size: f32,
+    particle_size: f32,
+};
+"#;
+
+/// WGSL shadow lookup with 3x3 percentage-closer filtering.
+///
+/// Expects `light`, `shadow_map` (`texture_depth_2d`), and `shadow_sampler`
+/// (`sampler_comparison`) bindings. `shadow_factor` returns 1.0 for fully
+/// lit and 0.0 for fully shadowed; points outside the shadow map are lit.
+pub const SHADOW_PCF_WGSL: &str = r#"
+fn shadow_coords(world_pos: vec3<f32>) -> vec3<f32> {
+    let clip = light.view_proj * vec4<f32>(world_pos, 1.0);
+    let ndc = clip.xyz / clip.w;
+    return vec3<f32>(ndc.x * 0.5 + 0.5, 0.5 - ndc.y * 0.5, ndc.z);
+}
+
+fn shadow_factor(coords: vec3<f32>) -> f32 {
+    if coords.x < 0.0 || coords.x > 1.0 || coords.y < 0.0 || coords.y > 1.0 || coords.z > 1.0 {
+        return 1.0;
+    }
+    let depth = coords.z - light.bias;
+    var lit = 0.0;
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            let offset = vec2<f32>(f32(x), f32(y)) * light.texel_size;
+            lit += textureSampleCompareLevel(shadow_map, shadow_sampler, coords.xy + offset, depth);
+        }
+    }
+    return lit / 9.0;
+}
+"#;
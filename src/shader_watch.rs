@@ -0,0 +1,162 @@
+//! Reloading the compute shader from a file while the simulation runs.
+//!
+//! Set up with [`Simulation::with_shader_watch`](crate::Simulation::with_shader_watch).
+//! The file's modification time is checked every [`CHECK_INTERVAL`] and
+//! the shader is re-read whenever it changes. With the `shader-watch`
+//! feature, OS file notifications from `notify` tell the watcher when to
+//! look instead, and the modification time is only consulted if they fail
+//! to start.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Time between modification time checks.
+const CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Watches a WGSL file holding the full compute shader.
+pub(crate) struct ShaderWatcher {
+    path: PathBuf,
+    last_modified: Option<SystemTime>,
+    last_check: Option<Instant>,
+    #[cfg(feature = "shader-watch")]
+    notifications: Option<Notifications>,
+}
+
+impl ShaderWatcher {
+    /// Start watching `path`.
+    ///
+    /// A missing file is created with `generated_shader`, so there is a
+    /// working shader to start editing from. An existing file is loaded on
+    /// the first [`poll`](Self::poll), replacing the generated shader.
+    pub(crate) fn new(path: &Path, generated_shader: &str) -> io::Result<Self> {
+        let last_modified = if path.exists() {
+            None
+        } else {
+            fs::write(path, generated_shader)?;
+            Some(fs::metadata(path)?.modified()?)
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            last_modified,
+            last_check: None,
+            #[cfg(feature = "shader-watch")]
+            notifications: Notifications::start(path)
+                .map_err(|e| eprintln!("File notifications unavailable, polling {}: {}", path.display(), e))
+                .ok(),
+        })
+    }
+
+    /// The watched file.
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The file's contents if it changed since the last check.
+    ///
+    /// Call every frame; the file is looked at no more than once per
+    /// [`CHECK_INTERVAL`] of wall-clock time.
+    pub(crate) fn poll(&mut self) -> Option<io::Result<String>> {
+        let now = Instant::now();
+        if self.last_check.is_some_and(|last| now - last < CHECK_INTERVAL) {
+            return None;
+        }
+        self.last_check = Some(now);
+
+        #[cfg(feature = "shader-watch")]
+        if let Some(notifications) = &self.notifications {
+            if !notifications.take() && self.last_modified.is_some() {
+                return None;
+            }
+        }
+
+        // Editors that save by replacing the file can leave it briefly missing
+        let modified = fs::metadata(&self.path).and_then(|m| m.modified()).ok()?;
+        if self.last_modified == Some(modified) {
+            return None;
+        }
+        self.last_modified = Some(modified);
+        Some(fs::read_to_string(&self.path))
+    }
+}
+
+/// Change events for the watched file, delivered by `notify`.
+#[cfg(feature = "shader-watch")]
+struct Notifications {
+    // Events stop when the watcher is dropped
+    _watcher: notify::RecommendedWatcher,
+    changed: std::sync::Arc<std::sync::atomic::AtomicBool>,
+}
+
+#[cfg(feature = "shader-watch")]
+impl Notifications {
+    fn start(path: &Path) -> notify::Result<Self> {
+        use notify::Watcher;
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        let changed = Arc::new(AtomicBool::new(false));
+        let flag = changed.clone();
+        let file_name = path.file_name().map(|n| n.to_os_string());
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if let Ok(event) = event {
+                if event.paths.iter().any(|p| p.file_name().map(|n| n.to_os_string()) == file_name) {
+                    flag.store(true, Ordering::Relaxed);
+                }
+            }
+        })?;
+
+        // Watch the directory so saves that replace the file are still seen
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        watcher.watch(dir, notify::RecursiveMode::NonRecursive)?;
+        Ok(Self { _watcher: watcher, changed })
+    }
+
+    /// Whether a change was reported since the last call.
+    fn take(&self) -> bool {
+        self.changed.swap(false, std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("rdpe_{}_{}.wgsl", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_missing_file_is_created_with_generated_shader() {
+        let path = temp_path("watch_create");
+        fs::remove_file(&path).ok();
+        let mut watcher = ShaderWatcher::new(&path, "// generated").unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        let first = watcher.poll();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(contents, "// generated");
+        assert!(first.is_none(), "unchanged file should not reload");
+    }
+
+    #[test]
+    fn test_existing_file_loads_and_respects_interval() {
+        let path = temp_path("watch_existing");
+        fs::write(&path, "// edited").unwrap();
+        let mut watcher = ShaderWatcher::new(&path, "// generated").unwrap();
+
+        let loaded = watcher.poll().map(|r| r.unwrap());
+        // Within the check interval the file isn't looked at again
+        fs::write(&path, "// edited again").unwrap();
+        let too_soon = watcher.poll();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.as_deref(), Some("// edited"));
+        assert!(too_soon.is_none());
+    }
+}
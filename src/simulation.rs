@@ -51,19 +51,25 @@
 
 use crate::emitter::Emitter;
 use crate::field::{FieldConfig, FieldRegistry};
-use crate::gpu::GpuState;
+use crate::gpu::{GpuState, HeadlessState, UNIFORM_FIELDS_WGSL};
 use crate::input::Input;
 use crate::interactions::InteractionMatrix;
+use crate::recording::{ParticleRecorder, ParticleRecording};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::shader_watch::ShaderWatcher;
 use crate::spawn::SpawnContext;
 use crate::rules::Rule;
 use crate::shader_utils;
+use crate::snapshot::ParticleSnapshot;
 use crate::spatial::{SpatialConfig, MORTON_WGSL, NEIGHBOR_UTILS_WGSL};
 use crate::textures::{TextureConfig, TextureRegistry};
 use crate::time::Time;
 use crate::uniforms::{CustomUniforms, UniformValue, UpdateContext};
 use crate::visuals::{VertexEffect, VisualConfig};
 use crate::ParticleTrait;
+use glam::Vec3;
 use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use winit::{
     application::ApplicationHandler,
@@ -169,6 +175,22 @@ pub struct Simulation<P: ParticleTrait> {
     custom_vertex_shader: Option<String>,
     /// Pre-built vertex effects (composable).
     vertex_effects: Vec<VertexEffect>,
+    /// Directory for the on-disk GPU pipeline cache (opt-in).
+    pipeline_cache_dir: Option<PathBuf>,
+    /// Steps (count, fixed delta time) to simulate before the first frame.
+    warmup_frames: Option<(u32, f32)>,
+    /// Random seed and fixed delta time for reproducible runs.
+    deterministic: Option<(u64, f32)>,
+    /// Keep particles on the XY plane and view them with an orthographic camera.
+    two_d: bool,
+    /// Particle type to draw interaction radius spheres around.
+    interaction_radius_viz: Option<u32>,
+    /// Recording output file and frames per second.
+    recorder: Option<(PathBuf, u32)>,
+    /// Recording to play back instead of simulating.
+    playback: Option<ParticleRecording>,
+    /// WGSL file to reload the compute shader from while running.
+    shader_watch: Option<PathBuf>,
     /// Whether egui UI is enabled.
     #[cfg(feature = "egui")]
     egui_enabled: bool,
@@ -223,6 +245,14 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
             custom_fragment_shader: None,
             custom_vertex_shader: None,
             vertex_effects: Vec::new(),
+            pipeline_cache_dir: None,
+            warmup_frames: None,
+            deterministic: None,
+            two_d: false,
+            interaction_radius_viz: None,
+            recorder: None,
+            playback: None,
+            shader_watch: None,
             #[cfg(feature = "egui")]
             egui_enabled: false,
             #[cfg(feature = "egui")]
@@ -316,6 +346,216 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
         self
     }
 
+    /// Cache compiled GPU pipelines on disk to speed up later launches.
+    ///
+    /// On backends that support pipeline caching (currently Vulkan), the
+    /// first run writes the driver's compiled pipelines to
+    /// `<path>/<hash>.bin` and later runs load them, cutting seconds off
+    /// startup. The hash covers both shaders and the GPU/driver, so any
+    /// change to rules, visuals, or hardware simply produces a new file.
+    /// Elsewhere this is a no-op.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Simulation::<Ball>::new()
+    ///     .with_pipeline_cache(Path::new("/home/me/.cache/rdpe"))
+    ///     // ...
+    /// ```
+    pub fn with_pipeline_cache(mut self, path: &Path) -> Self {
+        self.pipeline_cache_dir = Some(path.to_path_buf());
+        self
+    }
+
+    /// Simulate `frames` steps of `delta_time` before the first frame is shown.
+    ///
+    /// The steps run right after GPU initialization, with no rendering, so
+    /// the window opens on an already settled simulation (e.g. a galaxy that
+    /// has formed its arms, or a fountain that is already flowing). Custom
+    /// uniforms keep their initial values during warmup and the update
+    /// callback is not called.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Simulation::<Star>::new()
+    ///     .with_warmup_frames(600, 1.0 / 60.0)  // Ten seconds of history
+    ///     // ...
+    /// ```
+    pub fn with_warmup_frames(mut self, frames: u32, delta_time: f32) -> Self {
+        self.warmup_frames = Some((frames, delta_time));
+        self
+    }
+
+    /// Make runs reproducible with a fixed random seed and time step.
+    ///
+    /// The 64-bit `seed` is passed to the compute shader as two `u32`
+    /// uniforms and mixed into the built-in `hash` function, so `rand`,
+    /// `noise3` and every rule built on them follow the seed. Each frame
+    /// advances by exactly `fixed_dt` regardless of the wall clock, and
+    /// `uniforms.time` counts those steps, so time-dependent rules replay
+    /// identically too.
+    ///
+    /// The seed also drives the spawner's `SpawnContext` random helpers, so
+    /// two simulations with the same configuration and seed produce the
+    /// same particle data frame by frame. Rules whose results depend on the
+    /// order of GPU atomics (neighbor queries, inbox messages, field
+    /// deposits) may still differ in the last bits of floating-point sums.
+    /// The update callback is not seeded, so keep it free of wall-clock
+    /// input for fully reproducible runs.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let run = || Simulation::<Ball>::new()
+    ///     .with_spawner(|ctx| Ball { position: ctx.random_in_sphere(0.5), velocity: Vec3::ZERO })
+    ///     .with_rule(Rule::Wander { strength: 0.5, frequency: 2.0 })
+    ///     .deterministic(42, 1.0 / 60.0)
+    ///     .run_headless(300, 1.0 / 60.0);
+    /// assert_eq!(run()?, run()?);
+    /// ```
+    pub fn deterministic(mut self, seed: u64, fixed_dt: f32) -> Self {
+        self.deterministic = Some((seed, fixed_dt));
+        self
+    }
+
+    /// Run as a flat 2D simulation on the XY plane.
+    ///
+    /// At the end of every frame the compute shader zeroes each particle's
+    /// `position.z` and `velocity.z`, so spawn positions and rules that push
+    /// along Z are flattened. Neighbor queries only search the 9 grid cells
+    /// around a particle in its own Z layer instead of all 27. The camera
+    /// looks straight down the Z axis with an orthographic projection;
+    /// scrolling zooms and dragging no longer orbits.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Simulation::<Cell>::new()
+    ///     .with_2d_mode()
+    ///     .with_spawner(|ctx| Cell { position: ctx.random_in_sphere(0.8), velocity: Vec3::ZERO })
+    ///     .with_rule(Rule::Separate { radius: 0.05, strength: 1.0 })
+    ///     .run();
+    /// ```
+    pub fn with_2d_mode(mut self) -> Self {
+        self.two_d = true;
+        self
+    }
+
+    /// Draw a wireframe sphere around every particle of `particle_type`
+    /// showing how far its neighbor queries reach.
+    ///
+    /// The radius is the smallest one among the neighbor rules (Separate,
+    /// Cohere, Chase, interaction matrices, ...), drawn as three great
+    /// circles, or as a single circle in [2D mode](Self::with_2d_mode).
+    /// Compare it with the spatial cell size to check that neighbor
+    /// queries cover the distances your rules care about.
+    ///
+    /// # Panics
+    ///
+    /// When run, if no rule queries neighbors with a radius.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Simulation::<Boid>::new()
+    ///     .with_spatial_config(0.1, 32)
+    ///     .with_rule(Rule::Separate { radius: 0.05, strength: 2.0 })
+    ///     .with_rule(Rule::Cohere { radius: 0.15, strength: 0.5 })
+    ///     .with_interaction_radius_visualizer(0)
+    ///     .run();
+    /// ```
+    pub fn with_interaction_radius_visualizer(mut self, particle_type: u32) -> Self {
+        self.interaction_radius_viz = Some(particle_type);
+        self
+    }
+
+    /// Smallest neighbor query radius across all rules and the interaction matrix.
+    fn interaction_radius(&self) -> Option<f32> {
+        self.rules
+            .iter()
+            .filter_map(Rule::neighbor_radius)
+            .chain(self.interaction_matrix.as_ref().and_then(InteractionMatrix::min_radius))
+            .reduce(f32::min)
+    }
+
+    /// Record the particle buffer to `path` while the simulation runs.
+    ///
+    /// One frame is read back from the GPU every `1.0 / record_fps` seconds
+    /// of simulation time and appended to the file, so keep the rate modest
+    /// for large particle counts. The file is finished when the window
+    /// closes. Load it with [`ParticleRecording::open`] and show it with
+    /// [`play_recording`](Self::play_recording).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Simulation::<Ball>::new()
+    ///     .with_recorder(Path::new("balls.rec"), 30)
+    ///     // ...
+    ///     .run()?;
+    /// ```
+    pub fn with_recorder(mut self, path: &Path, record_fps: u32) -> Self {
+        self.recorder = Some((path.to_path_buf(), record_fps));
+        self
+    }
+
+    /// Play back a recording instead of simulating.
+    ///
+    /// Each frame, the recorded frame for the current time is uploaded with
+    /// `write_particles` and the compute pass is skipped, so rules, emitters,
+    /// and fields have no effect. The recording loops once it reaches the
+    /// end, and pausing or scaling time applies to playback as well. Sets
+    /// the particle count to the recording's; the particle type must be the
+    /// one that was recorded.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let recording = ParticleRecording::open("balls.rec")?;
+    /// Simulation::<Ball>::new()
+    ///     .with_spawner(|_| Ball::default())
+    ///     .play_recording(&recording)
+    ///     .run()?;
+    /// ```
+    pub fn play_recording(mut self, recording: &ParticleRecording) -> Self {
+        self.particle_count = recording.num_particles();
+        self.playback = Some(recording.clone());
+        self
+    }
+
+    /// Reload the compute shader from `path` whenever the file changes.
+    ///
+    /// A development aid for iterating on shader code without restarting.
+    /// If the file doesn't exist, it is created with the generated compute
+    /// shader; an existing file replaces the generated shader on the first
+    /// frame. The file's modification time is checked every 100ms, and a
+    /// changed shader is validated and swapped into a new compute pipeline
+    /// while particle buffers, uniforms and bindings stay as they are.
+    ///
+    /// The window title shows "Shader reloaded ✓" after a successful reload.
+    /// An invalid shader leaves the previous pipeline running, prints the
+    /// error to stderr and shows "Shader error (see console)". The shader
+    /// must keep the bindings of the generated one.
+    ///
+    /// Enable the `shader-watch` feature to be told about changes by the
+    /// operating system (via `notify`) instead of polling. This is a no-op
+    /// on WASM.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Simulation::<Ball>::new()
+    ///     .with_rule(Rule::Gravity(9.8))
+    ///     .with_shader_watch(Path::new("balls_compute.wgsl"))
+    ///     // ...
+    ///     .run()?;
+    /// ```
+    pub fn with_shader_watch(mut self, path: &Path) -> Self {
+        self.shader_watch = Some(path.to_path_buf());
+        self
+    }
+
     /// Set the particle spawner function.
     ///
     /// The spawner is called once for each particle at simulation startup.
@@ -423,8 +663,15 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
     /// # See Also
     ///
     /// See [`Rule`] for all available rules and their parameters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rule reads a particle field (such as the charge field of
+    /// [`Rule::Magnetic`]) that the particle struct does not declare as `f32`.
     pub fn with_rule(mut self, rule: impl Into<Rule>) -> Self {
-        self.rules.push(rule.into());
+        let rule = rule.into();
+        check_particle_fields::<P>(&rule);
+        self.rules.push(rule);
         self
     }
 
@@ -492,6 +739,29 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
         self
     }
 
+    /// Control whether dead particles are visible to neighbor rules.
+    ///
+    /// By default, neighbors with `alive == 0` are skipped in the neighbor
+    /// loop, so dead particles (often parked at the origin) don't repel,
+    /// attract or flock with living ones. Disable this only if your custom
+    /// neighbor code intentionally reacts to dead particles.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Simulation::<Ghost>::new()
+    ///     .with_skip_dead_neighbors(false)  // Living particles sense corpses
+    ///     .with_rule(Rule::NeighborCustom(r#"
+    ///         if other.alive == 0u && neighbor_dist < 0.1 {
+    ///             p.color = vec3<f32>(1.0, 0.0, 0.0);
+    ///         }
+    ///     "#.into()))
+    /// ```
+    pub fn with_skip_dead_neighbors(mut self, skip: bool) -> Self {
+        self.spatial_config.skip_dead_neighbors = skip;
+        self
+    }
+
     /// Enable particle-to-particle communication via inbox buffers.
     ///
     /// When enabled, particles can send values to other particles' "inbox"
@@ -502,6 +772,14 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
     ///
     /// - `inbox_send(target_idx, channel, value)` - Send float to particle's inbox
     /// - `inbox_receive_at(index, channel)` - Read inbox channel for particle (returns f32)
+    /// - `send_message(target_id, channel, value)` - Add a raw `i32` to a particle's inbox
+    /// - `get_message(particle_id, channel)` - Read a raw `i32` inbox channel
+    ///
+    /// These functions are the public inbox API. The underlying `inbox` buffer
+    /// layout and its atomics are an implementation detail and may change, so
+    /// custom shaders should not index it directly. The float and integer
+    /// forms share the same channels: `inbox_send` stores values in 16.16
+    /// fixed point, so mixing the two on one channel is rarely what you want.
     ///
     /// # Example
     ///
@@ -516,10 +794,10 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
     ///             p.energy *= 0.9;
     ///         }
     ///     "#.into()))
-    ///     .with_rule(Rule::Custom(r#"
+    ///     .with_rule(Rule::custom(r#"
     ///         // Receive accumulated energy
     ///         p.energy += inbox_receive_at(index, 0u);
-    ///     "#.into()))
+    ///     "#))
     ///     .run();
     /// ```
     ///
@@ -555,14 +833,14 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
     /// ```ignore
     /// Simulation::<Agent>::new()
     ///     .with_field("pheromone", FieldConfig::new(64).with_decay(0.98).with_blur(0.1))
-    ///     .with_rule(Rule::Custom(r#"
+    ///     .with_rule(Rule::custom(r#"
     ///         // Deposit pheromone at current position
     ///         field_write(0u, p.position, 0.1);
     ///
     ///         // Steer toward higher concentrations
     ///         let gradient = field_gradient(0u, p.position, 0.05);
     ///         p.velocity += normalize(gradient) * 0.5;
-    ///     "#.into()))
+    ///     "#))
     ///     .run();
     /// ```
     ///
@@ -596,9 +874,9 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
     ///         .with_palette(Palette::Inferno)
     ///         .with_density_scale(5.0)
     ///         .with_steps(64))
-    ///     .with_rule(Rule::Custom(r#"
+    ///     .with_rule(Rule::custom(r#"
     ///         field_write(0u, p.position, 0.1);
-    ///     "#.into()))
+    ///     "#))
     ///     .run();
     /// ```
     pub fn with_volume_render(mut self, config: crate::gpu::VolumeConfig) -> Self {
@@ -769,10 +1047,16 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
     /// When using emitters, particles should start dead (will be spawned by emitter)
     /// or use a spawner that sets some particles alive initially.
     pub fn with_emitter(mut self, emitter: Emitter) -> Self {
-        self.emitters.push(emitter);
+        self.push_emitter(emitter);
         self
     }
 
+    /// Add an emitter and the custom uniforms its shader code reads.
+    fn push_emitter(&mut self, emitter: Emitter) {
+        emitter.register_uniforms(self.emitters.len(), &mut self.custom_uniforms);
+        self.emitters.push(emitter);
+    }
+
     /// Configure particle lifecycle with a builder.
     ///
     /// Lifecycle configuration handles aging, death, visual effects (fade, shrink),
@@ -785,6 +1069,7 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
     /// - `age: f32` - time since spawn (seconds)
     /// - `alive: u32` - 0 = dead, 1 = alive
     /// - `scale: f32` - visual size multiplier
+    /// - `triggered: u32` - 1 once an age-triggered sub-emitter has fired
     ///
     /// # Example: Custom Configuration
     ///
@@ -818,6 +1103,7 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
     /// - `Rule::Lifetime(duration)` - kill particles after duration
     /// - `Rule::FadeOut(duration)` - dim color over lifetime (if enabled)
     /// - `Rule::ShrinkOut(duration)` - shrink scale over lifetime (if enabled)
+    /// - `Rule::FadeIn { ... }` - grow new particles from zero scale (if enabled)
     /// - `Rule::ColorOverLife { ... }` - color gradient (if enabled)
     /// - Emitters for respawning dead particles
     pub fn with_lifecycle<F>(mut self, configure: F) -> Self
@@ -834,7 +1120,7 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
 
         // Add emitters
         for emitter in emitters {
-            self.emitters.push(emitter);
+            self.push_emitter(emitter);
         }
 
         // Set start_dead flag
@@ -862,7 +1148,7 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
         }
 
         for emitter in emitters {
-            self.emitters.push(emitter);
+            self.push_emitter(emitter);
         }
 
         // Set start_dead flag
@@ -988,10 +1274,10 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
     /// Simulation::<Particle>::new()
     ///     .with_uniform("attractor", Vec3::ZERO)
     ///     .with_uniform("strength", 1.0f32)
-    ///     .with_rule(Rule::Custom(r#"
+    ///     .with_rule(Rule::custom(r#"
     ///         let dir = uniforms.attractor - p.position;
     ///         p.velocity += normalize(dir) * uniforms.strength;
-    ///     "#.into()))
+    ///     "#))
     ///     .run();
     /// ```
     pub fn with_uniform<V: Into<UniformValue>>(mut self, name: &str, value: V) -> Self {
@@ -1085,7 +1371,7 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
     ///             return vec3(-pos.z, 0.0, pos.x) * strength / (d + 0.1);
     ///         }
     ///     "#)
-    ///     .with_rule(Rule::Custom("p.velocity += swirl(p.position, 2.0);".into()))
+    ///     .with_rule(Rule::custom("p.velocity += swirl(p.position, 2.0);"))
     ///     .run();
     /// ```
     ///
@@ -1135,6 +1421,37 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
         self
     }
 
+    /// Set the background clear color.
+    ///
+    /// Shorthand for `with_visuals(|v| { v.background(color); })`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// Simulation::<Ball>::new()
+    ///     .with_background(Vec3::new(0.05, 0.0, 0.1))
+    ///     .run();
+    /// ```
+    pub fn with_background(mut self, color: Vec3) -> Self {
+        self.visual_config.background_color = color;
+        self
+    }
+
+    /// Change the background clear color.
+    ///
+    /// Background color is hot-swappable: it only changes the render pass
+    /// clear value, so no pipelines are rebuilt. To change it while the
+    /// simulation is running, use [`UpdateContext::set_background`] from a
+    /// [`with_update`](Self::with_update) callback.
+    pub fn set_background(&mut self, color: Vec3) {
+        self.visual_config.background_color = color;
+    }
+
+    /// Get the current background clear color.
+    pub fn background(&self) -> Vec3 {
+        self.visual_config.background_color
+    }
+
     /// Enable egui UI overlay.
     ///
     /// When enabled, you can use the `with_ui` method to add interactive
@@ -1275,6 +1592,41 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
         self.rules.iter().any(|r| r.requires_neighbors()) || self.interaction_matrix.is_some()
     }
 
+    /// Maximum particle age set by a `Lifetime` or `MaxAge` rule, if any.
+    fn max_age(&self) -> Option<f32> {
+        self.rules.iter().find_map(|r| match r {
+            Rule::Lifetime(seconds) => Some(*seconds),
+            Rule::MaxAge { max_age, .. } => Some(*max_age),
+            _ => None,
+        })
+    }
+
+    /// Check that every field a rule samples has been registered.
+    ///
+    /// Rules like [`Rule::FieldGradient`] read field buffers directly, so a
+    /// missing field would otherwise surface as an opaque shader error.
+    fn validate_field_rules(&self) {
+        for rule in &self.rules {
+            for field_index in rule.required_fields() {
+                if self.field_registry.is_empty() {
+                    panic!(
+                        "{} rule samples field {} but no fields are registered. Add one with .with_field(name, FieldConfig::new(resolution))",
+                        rule.display_name(),
+                        field_index
+                    );
+                }
+                if field_index >= self.field_registry.len() {
+                    panic!(
+                        "{} rule samples field {} but only {} field(s) are registered",
+                        rule.display_name(),
+                        field_index,
+                        self.field_registry.len()
+                    );
+                }
+            }
+        }
+    }
+
     /// Generate the compute shader WGSL code.
     fn generate_compute_shader(&self) -> String {
         self.generate_compute_shader_impl(false)
@@ -1288,11 +1640,35 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
 
     /// Generate the compute shader WGSL code (implementation).
     fn generate_compute_shader_impl(&self, dynamic_rules: bool) -> String {
-        let extra_wgsl = P::EXTRA_WGSL;
+        self.validate_field_rules();
+
+        let mut extra_wgsl = P::EXTRA_WGSL.to_string();
+        let mut matrices = self
+            .interaction_matrix
+            .iter()
+            .chain(self.rules.iter().filter_map(|r| r.interaction_matrix()));
+        if let Some(matrix) = matrices.next() {
+            if matrices.next().is_some() {
+                panic!(
+                    "A simulation can have only one interaction matrix, but several were given through with_interactions and Rule::TypedMulti"
+                );
+            }
+            extra_wgsl.push('\n');
+            extra_wgsl.push_str(&matrix.to_wgsl("INTERACTION_MATRIX"));
+        }
         let particle_struct = P::WGSL_STRUCT;
         let has_neighbors = self.has_neighbor_rules();
         let has_sub_emitters = !self.sub_emitters.is_empty();
 
+        // 2D mode: pin particles to the XY plane and only search the
+        // neighbor cells in the same Z layer (NEIGHBOR_OFFSETS 9..18)
+        let flatten_code = if self.two_d {
+            "\n    // 2D mode: stay on the XY plane\n    p.position.z = 0.0;\n    p.velocity.z = 0.0;\n"
+        } else {
+            ""
+        };
+        let (first_offset, end_offset) = if self.two_d { (9, 18) } else { (0, 27) };
+
 
         // Generate non-neighbor rules (static or dynamic)
         let simple_rules_code: String = self
@@ -1315,7 +1691,7 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
             .emitters
             .iter()
             .enumerate()
-            .map(|(i, e)| e.to_wgsl(i))
+            .map(|(i, e)| e.to_wgsl(i, &self.texture_registry))
             .collect::<Vec<_>>()
             .join("\n\n");
 
@@ -1326,14 +1702,25 @@ impl<P: ParticleTrait + 'static> Simulation<P> {
         let custom_uniform_fields = if self.custom_uniforms.is_empty() {
             String::new()
         } else {
-            format!(
-                "\n    _pad0: f32,\n    _pad1: f32,\n{}",
-                self.custom_uniforms.to_wgsl_fields()
-            )
+            format!("\n{}", self.custom_uniforms.to_wgsl_fields())
         };
 
         // Built-in utility functions (always included)
-        let builtin_utils = shader_utils::all_utils_wgsl();
+        let mut builtin_utils = shader_utils::all_utils_wgsl();
+
+        // Value noise is only pulled in by rules that use it
+        if self.rules.iter().any(|r| r.requires_value_noise()) {
+            builtin_utils.push_str(shader_utils::VALUE_NOISE_WGSL);
+        }
+        if self.rules.iter().any(|r| r.requires_sph_kernels()) {
+            builtin_utils.push_str(shader_utils::SPH_KERNELS_WGSL);
+        }
+
+        // Helper libraries requested by custom rules, each included once
+        let helpers: Vec<_> = self.rules.iter().flat_map(|r| r.required_helpers()).collect();
+        if !helpers.is_empty() {
+            builtin_utils.push_str(&shader_utils::helpers_wgsl(&helpers));
+        }
 
         // Generate custom functions
         let custom_functions_code = if self.custom_functions.is_empty() {
@@ -1374,8 +1761,16 @@ fn inbox_receive_at(my_idx: u32, channel: u32) -> f32 {
     return f32(scaled) / INBOX_SCALE;
 }
 
-// Convenience macro-like variable that will be replaced with actual index
-// Note: Use inbox_receive_at(index, channel) for the full form
+// Raw integer message API (no fixed-point conversion)
+// Add an integer value to a particle's inbox channel
+fn send_message(target_id: u32, channel: u32, value: i32) {
+    atomicAdd(&inbox[target_id][channel], value);
+}
+
+// Read the accumulated integer value of a particle's inbox channel
+fn get_message(particle_id: u32, channel: u32) -> i32 {
+    return atomicLoad(&inbox[particle_id][channel]);
+}
 "#;
             (binding.to_string(), helpers.to_string())
         } else {
@@ -1393,7 +1788,7 @@ fn inbox_receive_at(my_idx: u32, channel: u32) -> f32 {
         let (sub_emitter_bindings, sub_emitter_death_recording) = if has_sub_emitters {
             (
                 crate::gpu::sub_emitter_gpu::death_buffer_bindings_wgsl().to_string(),
-                crate::gpu::sub_emitter_gpu::death_recording_wgsl(&self.sub_emitters),
+                crate::gpu::sub_emitter_gpu::death_recording_wgsl(&self.sub_emitters, self.max_age()),
             )
         } else {
             (String::new(), String::new())
@@ -1461,9 +1856,7 @@ fn inbox_receive_at(my_idx: u32, channel: u32) -> f32 {
 {particle_struct}
 
 struct Uniforms {{
-    view_proj: mat4x4<f32>,
-    time: f32,
-    delta_time: f32,{custom_uniform_fields}
+{UNIFORM_FIELDS_WGSL}{custom_uniform_fields}
 }};
 
 @group(0) @binding(0)
@@ -1471,12 +1864,25 @@ var<storage, read_write> particles: array<Particle>;
 
 @group(0) @binding(1)
 var<uniform> uniforms: Uniforms;
+
+struct Counters {{
+    // Survivors, read back by GpuState::alive_count()
+    alive: atomic<u32>,
+    // Dead particles claimed by each emitter this frame
+    emitted: array<atomic<u32>>,
+}};
+
+@group(0) @binding(6)
+var<storage, read_write> counters: Counters;
 {inbox_binding}
 {field_wgsl}
 {sub_emitter_bindings}
 {inbox_helpers}
 {custom_functions_code}
-@compute @workgroup_size(256)
+// Overridden at pipeline creation to fit the device's workgroup limit
+override WORKGROUP_SIZE: u32 = 256u;
+
+@compute @workgroup_size(WORKGROUP_SIZE, 1, 1)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
     let index = global_id.x;
     let num_particles = arrayLength(&particles);
@@ -1485,6 +1891,8 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
         return;
     }}
 
+    rng_seed = uniforms.seed_lo ^ hash(uniforms.seed_hi);
+
     var p = particles[index];
 {was_alive_tracking}
 {emitter_code}
@@ -1498,13 +1906,29 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
 
     // Integrate velocity
     p.position += p.velocity * uniforms.delta_time;
-{on_death_code}{sub_emitter_death_recording}
+{flatten_code}{on_death_code}{sub_emitter_death_recording}
+    // Count survivors for GpuState::alive_count()
+    if p.alive == 1u {{
+        atomicAdd(&counters.alive, 1u);
+    }}
+
     particles[index] = p;
 }}
 "#
             )
         } else {
             // Complex shader with neighbor queries
+            let dead_neighbor_check = if self.spatial_config.skip_dead_neighbors {
+                "
+            // Skip dead neighbors
+            if other.alive == 0u {
+                continue;
+            }
+"
+            } else {
+                ""
+            };
+
             let neighbor_rules_code: String = self
                 .rules
                 .iter()
@@ -1525,6 +1949,7 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
             // Check if we need various accumulators
             let needs_cohesion = self.rules.iter().any(|r| r.needs_cohesion_accumulator());
             let needs_alignment = self.rules.iter().any(|r| r.needs_alignment_accumulator());
+            let needs_boid = self.rules.iter().any(|r| r.needs_boid_accumulator());
             let needs_chase = self.rules.iter().any(|r| r.needs_chase_accumulator());
             let needs_evade = self.rules.iter().any(|r| r.needs_evade_accumulator());
             let needs_viscosity = self.rules.iter().any(|r| r.needs_viscosity_accumulator());
@@ -1536,18 +1961,6 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
             let needs_signal = self.rules.iter().any(|r| r.needs_signal_accumulator());
             let needs_absorb = self.rules.iter().any(|r| r.needs_absorb_accumulator());
 
-            // Generate interaction matrix code if present
-            let (interaction_init, interaction_neighbor, interaction_post) =
-                if let Some(ref matrix) = self.interaction_matrix {
-                    (
-                        matrix.to_wgsl_init(),
-                        matrix.to_wgsl_neighbor(),
-                        matrix.to_wgsl_post(),
-                    )
-                } else {
-                    (String::new(), String::new(), String::new())
-                };
-
             let accumulator_vars = {
                 let mut vars = String::new();
                 if needs_cohesion {
@@ -1556,6 +1969,9 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
                 if needs_alignment {
                     vars.push_str("    var alignment_sum = vec3<f32>(0.0);\n    var alignment_count = 0.0;\n");
                 }
+                if needs_boid {
+                    vars.push_str("    var boid_separation = vec3<f32>(0.0);\n    var boid_cohesion_sum = vec3<f32>(0.0);\n    var boid_cohesion_count = 0.0;\n    var boid_alignment_sum = vec3<f32>(0.0);\n    var boid_alignment_count = 0.0;\n");
+                }
                 if needs_chase {
                     vars.push_str("    var chase_nearest_dist = 1000.0;\n    var chase_nearest_pos = vec3<f32>(0.0);\n");
                 }
@@ -1566,7 +1982,7 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
                     vars.push_str("    var viscosity_sum = vec3<f32>(0.0);\n    var viscosity_weight = 0.0;\n");
                 }
                 if needs_pressure {
-                    vars.push_str("    var pressure_density = 0.0;\n    var pressure_force = vec3<f32>(0.0);\n");
+                    vars.push_str("    var pressure_density = 0.0;\n    var pressure_grad = vec3<f32>(0.0);\n    var pressure_visc = vec3<f32>(0.0);\n");
                 }
                 if needs_surface_tension {
                     vars.push_str("    var surface_neighbor_count = 0.0;\n    var surface_center_sum = vec3<f32>(0.0);\n");
@@ -1586,35 +2002,14 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
                 if needs_absorb {
                     vars.push_str("    var absorb_sum = 0.0;\n    var absorb_found = false;\n    var absorb_target_idx = 0u;\n");
                 }
-                // Add interaction matrix init
-                if !interaction_init.is_empty() {
-                    vars.push('\n');
-                    vars.push_str(&interaction_init);
-                    vars.push('\n');
-                }
                 vars
             };
 
-            // Combine neighbor rules with interaction matrix neighbor code
-            let neighbor_rules_code = {
-                let mut code = neighbor_rules_code;
-                if !interaction_neighbor.is_empty() {
-                    code.push('\n');
-                    code.push_str(&interaction_neighbor);
-                }
-                code
-            };
-
-            // Combine post-neighbor code with interaction matrix post code
-            let post_neighbor_code = {
-                let mut code = post_neighbor_code;
-                if !interaction_post.is_empty() {
-                    if !code.is_empty() {
-                        code.push_str("\n\n");
-                    }
-                    code.push_str(&interaction_post);
-                }
-                code
+            // Interaction matrix lookup, declared alongside EXTRA_WGSL
+            let neighbor_rules_code = if self.interaction_matrix.is_some() {
+                format!("{}\n            apply_typed_interaction(&p, other, uniforms);", neighbor_rules_code)
+            } else {
+                neighbor_rules_code
             };
 
             format!(
@@ -1626,9 +2021,7 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
 {NEIGHBOR_UTILS_WGSL}
 
 struct Uniforms {{
-    view_proj: mat4x4<f32>,
-    time: f32,
-    delta_time: f32,{custom_uniform_fields}
+{UNIFORM_FIELDS_WGSL}{custom_uniform_fields}
 }};
 
 struct SpatialParams {{
@@ -1655,12 +2048,25 @@ var<storage, read> cell_end: array<u32>;
 
 @group(0) @binding(5)
 var<uniform> spatial: SpatialParams;
+
+struct Counters {{
+    // Survivors, read back by GpuState::alive_count()
+    alive: atomic<u32>,
+    // Dead particles claimed by each emitter this frame
+    emitted: array<atomic<u32>>,
+}};
+
+@group(0) @binding(6)
+var<storage, read_write> counters: Counters;
 {inbox_binding}
 {field_wgsl}
 {sub_emitter_bindings}
 {inbox_helpers}
 {custom_functions_code}
-@compute @workgroup_size(256)
+// Overridden at pipeline creation to fit the device's workgroup limit
+override WORKGROUP_SIZE: u32 = 256u;
+
+@compute @workgroup_size(WORKGROUP_SIZE, 1, 1)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
     let index = global_id.x;
     let num_particles = arrayLength(&particles);
@@ -1669,6 +2075,8 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
         return;
     }}
 
+    rng_seed = uniforms.seed_lo ^ hash(uniforms.seed_hi);
+
     var p = particles[index];
 {was_alive_tracking}
 {emitter_code}
@@ -1685,7 +2093,7 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
     // Neighbor iteration with optional max limit
     var neighbor_count = 0u;
     let max_neighbors = spatial.max_neighbors;
-    for (var offset_idx = 0u; offset_idx < 27u; offset_idx++) {{
+    for (var offset_idx = {first_offset}u; offset_idx < {end_offset}u; offset_idx++) {{
         // Early exit if max neighbors reached (0 = unlimited)
         if max_neighbors > 0u && neighbor_count >= max_neighbors {{
             break;
@@ -1717,12 +2125,7 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
             }}
 
             let other = particles[other_idx];
-
-            // Skip dead neighbors
-            if other.alive == 0u {{
-                continue;
-            }}
-
+{dead_neighbor_check}
             let neighbor_pos = other.position;
             let neighbor_vel = other.velocity;
             let diff = my_pos - neighbor_pos;
@@ -1741,7 +2144,12 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
 
     // Integrate velocity
     p.position += p.velocity * uniforms.delta_time;
-{on_death_code}{sub_emitter_death_recording}
+{flatten_code}{on_death_code}{sub_emitter_death_recording}
+    // Count survivors for GpuState::alive_count()
+    if p.alive == 1u {{
+        atomicAdd(&counters.alive, 1u);
+    }}
+
     particles[index] = p;
 }}
 "#
@@ -1753,7 +2161,7 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {{
 
     /// Generate the render shader WGSL code.
     fn generate_render_shader(&self) -> String {
-        use crate::visuals::{combine_vertex_effects, ColorMapping, Palette};
+        use crate::visuals::{combine_vertex_effects, ColorMapping, Palette, SpriteSheetConfig};
 
         // Determine if we're using a palette
         let use_palette = !matches!(self.visual_config.palette, Palette::None);
@@ -1870,11 +2278,76 @@ fn sample_palette(t: f32) -> vec3<f32> {{
             )
         };
 
+        // Sprite sheet: frame UVs from age, sampled from the first texture
+        let sprite_texture = self.texture_registry.textures.first().map(|(name, _)| name.as_str());
+        let (sprite_output, sprite_vertex, sprite_fragment) =
+            match (&self.visual_config.sprite_sheet, sprite_texture) {
+                (Some(sheet), Some(texture)) => (
+                    "    @location(2) sprite_uv: vec2<f32>,\n",
+                    sheet.to_wgsl_vertex(),
+                    Some(SpriteSheetConfig::to_wgsl_fragment(texture)),
+                ),
+                _ => ("", String::new(), None),
+            };
+        let default_fragment = sprite_fragment
+            .as_deref()
+            .unwrap_or_else(|| self.visual_config.shape.to_wgsl_fragment());
+        let fragment_body = self.custom_fragment_shader.as_deref().unwrap_or(default_fragment);
+
+        // Directional light: shadow lookup group after the texture group, and
+        // the fragment body wrapped so its result can be lit and shadowed.
+        // The lit shader reads the base size from the light uniform so the
+        // billboards match the ones the shadow pass draws.
+        let (shadow_declarations, shadow_output, shadow_vertex, fragment, base_size) =
+            if self.visual_config.light.is_some() {
+                let group = if self.texture_registry.textures.is_empty() { 1 } else { 2 };
+                (
+                    format!(
+                        r#"{light_struct}
+@group({group}) @binding(0) var shadow_map: texture_depth_2d;
+@group({group}) @binding(1) var shadow_sampler: sampler_comparison;
+@group({group}) @binding(2) var<uniform> light: Light;
+
+// Fraction of light reaching fully shadowed particles
+const SHADOW_AMBIENT: f32 = 0.3;
+{shadow_pcf}"#,
+                        light_struct = shader_utils::LIGHT_STRUCT_WGSL,
+                        shadow_pcf = shader_utils::SHADOW_PCF_WGSL,
+                    ),
+                    "    @location(3) shadow_pos: vec3<f32>,\n",
+                    "    out.shadow_pos = shadow_coords(particle_pos);\n",
+                    format!(
+                        r#"fn shade(in: VertexOutput) -> vec4<f32> {{
+{fragment_body}
+}}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {{
+    let c = shade(in);
+    let lit = mix(SHADOW_AMBIENT, 1.0, shadow_factor(in.shadow_pos));
+    return vec4<f32>(c.rgb * light.color * lit, c.a);
+}}"#
+                    ),
+                    "light.particle_size".to_string(),
+                )
+            } else {
+                (
+                    String::new(),
+                    "",
+                    "",
+                    format!(
+                        r#"@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {{
+{fragment_body}
+}}"#
+                    ),
+                    self.particle_size.to_string(),
+                )
+            };
+
         format!(
             r#"struct Uniforms {{
-    view_proj: mat4x4<f32>,
-    time: f32,
-    delta_time: f32,
+{UNIFORM_FIELDS_WGSL}
 {custom_uniform_fields}
 }};
 
@@ -1883,12 +2356,13 @@ var<uniform> uniforms: Uniforms;
 
 // Custom textures
 {texture_declarations}
+{shadow_declarations}
 {palette_code}
 struct VertexOutput {{
     @builtin(position) clip_position: vec4<f32>,
     @location(0) color: vec3<f32>,
     @location(1) uv: vec2<f32>,
-}};
+{sprite_output}{shadow_output}}};
 
 @vertex
 fn vs_main(
@@ -1898,6 +2372,7 @@ fn vs_main(
     {color_input}
     @location(2) alive: u32,
     @location(3) scale: f32,
+    @location(4) age: f32,
 ) -> VertexOutput {{
     var out: VertexOutput;
 
@@ -1919,22 +2394,16 @@ fn vs_main(
     );
 
     let quad_pos = quad_vertices[vertex_index];
-    let base_size = {particle_size};
+    let base_size = {base_size};
     let particle_size = base_size * scale;
-
+{sprite_vertex}{shadow_vertex}
     // Custom or default vertex transformation
 {vertex_body}
 }}
 
-@fragment
-fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {{
-{fragment_body}
-}}
+{fragment}
 "#,
-            particle_size = self.particle_size,
             vertex_body = vertex_body,
-            fragment_body = self.custom_fragment_shader.as_deref()
-                .unwrap_or_else(|| self.visual_config.shape.to_wgsl_fragment())
         )
     }
 
@@ -1984,14 +2453,30 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {{
     ///
     /// Returns `SimulationError` if:
     /// - No spawner function was provided (use `.with_spawner()`)
+    /// - A sprite sheet is configured without exactly one texture (use `.with_texture()`)
     /// - Event loop creation fails
     /// - Window creation fails
     /// - GPU initialization fails
+    /// - The file for [`with_shader_watch`](Self::with_shader_watch) can't be created
     pub fn run(mut self) -> Result<(), crate::error::SimulationError> {
         let spawner = self
             .spawner
             .take()
             .ok_or(crate::error::SimulationError::NoSpawner)?;
+        self.check_sprite_texture()?;
+        self.check_age_triggers()?;
+        self.check_emitter_textures()?;
+        if let Some(ref recording) = self.playback {
+            let expected = (self.particle_count, P::GPU_STRIDE);
+            let found = (recording.num_particles(), recording.stride());
+            if expected != found {
+                return Err(crate::error::RecordingError::LayoutMismatch { expected, found }.into());
+            }
+        }
+        let recorder = match self.recorder.take() {
+            Some((path, fps)) => Some(ParticleRecorder::create(&path, self.particle_count, P::GPU_STRIDE, fps)?),
+            None => None,
+        };
 
         let has_neighbors = self.has_neighbor_rules();
 
@@ -2016,19 +2501,34 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {{
         let compute_shader = self.generate_compute_shader();
         let render_shader = self.generate_render_shader();
 
+        #[cfg(not(target_arch = "wasm32"))]
+        let shader_watcher = match &self.shader_watch {
+            Some(path) => Some(
+                ShaderWatcher::new(path, &compute_shader).map_err(crate::error::SimulationError::ShaderWatch)?,
+            ),
+            None => None,
+        };
+
         // Calculate custom uniform buffer size and generate WGSL fields
         let custom_uniform_size = self.custom_uniforms.byte_size();
         let custom_uniform_fields = self.custom_uniforms.to_wgsl_fields();
 
-        // Generate particles using SpawnContext
-        let bounds = self.bounds;
-        let count = self.particle_count;
-        let particles: Vec<P> = (0..self.particle_count)
-            .map(|i| {
-                let mut ctx = SpawnContext::new(i, count, bounds);
-                spawner(&mut ctx)
-            })
-            .collect();
+        let interaction_radius_viz = self.interaction_radius_viz.map(|particle_type| {
+            let radius = self.interaction_radius().unwrap_or_else(|| {
+                panic!("with_interaction_radius_visualizer needs a neighbor rule with a radius, like Rule::Separate")
+            });
+            (particle_type, radius)
+        });
+
+        let seed = self.deterministic.map(|(seed, _)| seed);
+        let gpu_particles = spawn_gpu_particles(
+            &*spawner,
+            self.particle_count,
+            self.bounds,
+            self.start_dead,
+            seed,
+            self.emitter_head(),
+        );
 
         let config = SimConfig {
             particle_count: self.particle_count,
@@ -2041,18 +2541,24 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {{
             color_offset: P::COLOR_OFFSET,
             alive_offset: P::ALIVE_OFFSET,
             scale_offset: P::SCALE_OFFSET,
+            velocity_offset: P::VELOCITY_OFFSET,
             custom_uniform_size,
             custom_uniform_fields,
             particle_size: self.particle_size,
             inbox_enabled: self.inbox_enabled,
-            start_dead: self.start_dead,
             #[cfg(feature = "egui")]
             egui_enabled: self.egui_enabled,
+            pipeline_cache_dir: self.pipeline_cache_dir,
+            warmup_frames: self.warmup_frames,
+            deterministic: self.deterministic,
+            two_d: self.two_d,
+            interaction_radius_viz,
             texture_declarations: self.texture_registry.to_wgsl_declarations(0),
             texture_registry: self.texture_registry,
             field_registry: self.field_registry,
             volume_config: self.volume_config,
             sub_emitters: self.sub_emitters,
+            emitters: self.emitters,
             particle_wgsl_struct: P::WGSL_STRUCT.to_string(),
         };
 
@@ -2061,7 +2567,7 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {{
 
         #[cfg(feature = "egui")]
         let mut app = App::<P>::new(
-            particles,
+            gpu_particles,
             config,
             self.custom_uniforms,
             self.update_callback,
@@ -2072,36 +2578,318 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {{
         );
         #[cfg(not(feature = "egui"))]
         let mut app = App::<P>::new(
-            particles,
+            gpu_particles,
             config,
             self.custom_uniforms,
             self.update_callback,
         );
+        app.recorder = recorder;
+        app.playback = self.playback;
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            app.shader_watcher = shader_watcher;
+        }
         event_loop.run_app(&mut app)?;
         Ok(())
     }
-}
 
-impl<P: ParticleTrait + 'static> Default for Simulation<P> {
-    /// Creates a new simulation with default settings.
+    /// Run the spawner for every particle, without touching the GPU.
     ///
-    /// Equivalent to [`Simulation::new()`].
-    fn default() -> Self {
-        Self::new()
+    /// Returns the initial particle data [`run`](Self::run) would upload,
+    /// honoring [`deterministic`](Self::deterministic) seeds and emitters
+    /// that start dead. Handy for inspecting or timing a spawner.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SimulationError::NoSpawner` if no spawner was provided.
+    pub fn initial_particles(&self) -> Result<Vec<P::Gpu>, crate::error::SimulationError> {
+        let spawner = self
+            .spawner
+            .as_deref()
+            .ok_or(crate::error::SimulationError::NoSpawner)?;
+        Ok(spawn_gpu_particles(
+            spawner,
+            self.particle_count,
+            self.bounds,
+            self.start_dead,
+            self.deterministic.map(|(seed, _)| seed),
+            self.emitter_head(),
+        ))
     }
-}
 
-/// Internal configuration passed to the GPU renderer.
-///
-/// Contains all the computed values needed to initialize the GPU state.
-pub(crate) struct SimConfig {
-    /// Total number of particles.
-    pub particle_count: u32,
-    /// Simulation bounds (half-size of bounding cube).
-    pub bounds: f32,
-    /// Generated WGSL compute shader source.
-    pub compute_shader: String,
-    /// Generated WGSL render shader source.
+    /// Run the simulation without a window and return the final particle buffer.
+    ///
+    /// Creates a GPU device without a window surface, runs the compute
+    /// pipeline for `frames` steps of exactly `delta_time` seconds, and reads
+    /// the particle buffer back. Spatial hashing, the inbox, fields, and
+    /// sub-emitters all run as usual; rendering, update callbacks, and UI do
+    /// not. Warmup frames set with [`with_warmup_frames`](Self::with_warmup_frames)
+    /// run first, and the seed from [`deterministic`](Self::deterministic)
+    /// is applied.
+    ///
+    /// The returned bytes are `particle_count` particles in the GPU layout
+    /// (`P::Gpu`), ready for `bytemuck::cast_slice`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let bytes = Simulation::<Ball>::new()
+    ///     .with_particle_count(10_000)
+    ///     .with_spawner(|ctx| Ball { position: ctx.random_in_sphere(0.5), velocity: Vec3::ZERO })
+    ///     .with_rule(Rule::Gravity(9.8))
+    ///     .with_rule(Rule::BounceWalls)
+    ///     .run_headless(600, 1.0 / 60.0)?;
+    /// let particles: &[BallGpu] = bytemuck::cast_slice(&bytes);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `SimulationError` if:
+    /// - No spawner function was provided (use `.with_spawner()`)
+    /// - GPU initialization or readback fails
+    pub fn run_headless(mut self, frames: u32, delta_time: f32) -> Result<Vec<u8>, crate::error::SimulationError> {
+        let spawner = self
+            .spawner
+            .take()
+            .ok_or(crate::error::SimulationError::NoSpawner)?;
+
+        self.check_age_triggers()?;
+        self.check_emitter_textures()?;
+
+        let seed = self.deterministic.map(|(seed, _)| seed);
+        let gpu_particles = spawn_gpu_particles(
+            &*spawner,
+            self.particle_count,
+            self.bounds,
+            self.start_dead,
+            seed,
+            self.emitter_head(),
+        );
+
+        let mut state = pollster::block_on(HeadlessState::new(
+            bytemuck::cast_slice(&gpu_particles),
+            self.particle_count,
+            self.bounds,
+            &self.generate_compute_shader(),
+            self.has_neighbor_rules(),
+            self.spatial_config,
+            self.custom_uniforms.byte_size(),
+            self.inbox_enabled,
+            &self.field_registry,
+            &self.sub_emitters,
+            self.emitters.len(),
+            P::WGSL_STRUCT,
+        ))?;
+        if let Some((seed, _)) = self.deterministic {
+            state.set_seed(seed);
+        }
+
+        let (warmup_count, warmup_dt) = self.warmup_frames.unwrap_or((0, 0.0));
+        let steps = std::iter::repeat_n(warmup_dt, warmup_count as usize)
+            .chain(std::iter::repeat_n(delta_time, frames as usize));
+        let mut time = 0.0;
+        run_steps(&mut self.emitters, &mut self.custom_uniforms, steps, |dt, custom_bytes| {
+            time += dt;
+            state.step(time, dt, custom_bytes);
+        });
+
+        Ok(state.read_particles()?)
+    }
+}
+
+impl<P: ParticleTrait + 'static> Simulation<P> {
+    /// Head position and direction of the first path emitter, for the
+    /// spawner's [`SpawnContext`].
+    fn emitter_head(&self) -> Option<(Vec3, Vec3)> {
+        self.emitters.iter().find_map(Emitter::path_head)
+    }
+
+    /// Age-triggered sub-emitters need a rule that sets a maximum age.
+    fn check_age_triggers(&self) -> Result<(), crate::error::SimulationError> {
+        let has_age_trigger = self
+            .sub_emitters
+            .iter()
+            .any(|se| matches!(se.trigger, crate::sub_emitter::SpawnTrigger::AgeThreshold { .. }));
+        if has_age_trigger && self.max_age().is_none() {
+            return Err(crate::error::SimulationError::NoMaxAge);
+        }
+        Ok(())
+    }
+
+    /// Texture emitters need their texture in the registry.
+    fn check_sprite_texture(&self) -> Result<(), crate::error::SimulationError> {
+        if self.visual_config.sprite_sheet.is_none() {
+            return Ok(());
+        }
+        match self.texture_registry.textures.len() {
+            0 => Err(crate::error::SimulationError::NoSpriteTexture),
+            1 => Ok(()),
+            count => Err(crate::error::SimulationError::MultipleSpriteTextures(count)),
+        }
+    }
+
+    fn check_emitter_textures(&self) -> Result<(), crate::error::SimulationError> {
+        match self
+            .emitters
+            .iter()
+            .filter_map(Emitter::texture_name)
+            .find(|name| self.texture_registry.get(name).is_none())
+        {
+            Some(name) => Err(crate::error::SimulationError::UnknownTexture(name.to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Check that every particle field `rule` reads as `f32` is declared on `P`
+/// with that type.
+///
+/// The field types come from parsing `P::WGSL_STRUCT`, so this sees the same
+/// layout the compute shader is built against.
+fn check_particle_fields<P: ParticleTrait>(rule: &Rule) {
+    for name in rule.required_f32_fields() {
+        match particle_field_type(P::WGSL_STRUCT, name) {
+            Some(ty) if ty == "f32" => {}
+            Some(ty) => panic!(
+                "{} rule reads particle field '{}' as f32, but it is declared as {}",
+                rule.display_name(),
+                name,
+                ty
+            ),
+            None => panic!(
+                "{} rule reads particle field '{}', but the particle struct has no such field. Add `{}: f32` to your particle",
+                rule.display_name(),
+                name,
+                name
+            ),
+        }
+    }
+}
+
+/// WGSL type of the member `name` of the `Particle` struct in `wgsl_struct`,
+/// or `None` if there is no such member.
+fn particle_field_type(wgsl_struct: &str, name: &str) -> Option<String> {
+    use wgpu::naga::{Scalar, ScalarKind, TypeInner};
+
+    fn scalar_name(scalar: Scalar) -> String {
+        match scalar.kind {
+            ScalarKind::Float => format!("f{}", scalar.width * 8),
+            ScalarKind::Uint => format!("u{}", scalar.width * 8),
+            ScalarKind::Sint => format!("i{}", scalar.width * 8),
+            ScalarKind::Bool => "bool".to_string(),
+            kind => format!("{:?}", kind),
+        }
+    }
+
+    let module = wgpu::naga::front::wgsl::parse_str(wgsl_struct).ok()?;
+    let members = module.types.iter().find_map(|(_, ty)| match &ty.inner {
+        TypeInner::Struct { members, .. } if ty.name.as_deref() == Some("Particle") => Some(members),
+        _ => None,
+    })?;
+    let member = members.iter().find(|m| m.name.as_deref() == Some(name))?;
+    Some(match module.types[member.ty].inner {
+        TypeInner::Scalar(scalar) => scalar_name(scalar),
+        TypeInner::Vector { size, scalar } => format!("vec{}<{}>", size as u8, scalar_name(scalar)),
+        TypeInner::Array { .. } => "an array".to_string(),
+        _ => "a non-scalar type".to_string(),
+    })
+}
+
+/// Run off-screen steps of the given delta times, advancing the emitters
+/// before each one so warmup frames emit just like rendered frames.
+fn run_steps(
+    emitters: &mut [Emitter],
+    custom_uniforms: &mut CustomUniforms,
+    steps: impl IntoIterator<Item = f32>,
+    mut step: impl FnMut(f32, Option<&[u8]>),
+) {
+    for dt in steps {
+        advance_emitters(emitters, custom_uniforms, dt);
+        let custom_bytes = (!custom_uniforms.is_empty()).then(|| custom_uniforms.to_bytes());
+        step(dt, custom_bytes.as_deref());
+    }
+}
+
+/// Advance every emitter's state by one frame and write the result into the
+/// custom uniforms its shader code reads.
+fn advance_emitters(emitters: &mut [Emitter], custom_uniforms: &mut CustomUniforms, delta_time: f32) {
+    for (i, emitter) in emitters.iter_mut().enumerate() {
+        if let Some(step) = emitter.advance(delta_time) {
+            step.write_uniforms(i, custom_uniforms);
+        }
+    }
+}
+
+/// Spawn the initial particles and convert them to the GPU layout.
+///
+/// With `start_dead`, every particle's `alive` flag is cleared so emitters
+/// can fill the pool. A `seed` makes the spawner's random helpers repeat
+/// between runs.
+///
+/// With the `rayon` feature the spawner runs on all CPU cores. Each particle
+/// gets its own `SpawnContext`, so the result doesn't depend on the order
+/// the calls happen in.
+fn spawn_gpu_particles<P: ParticleTrait>(
+    spawner: &(dyn Fn(&mut SpawnContext) -> P + Sync),
+    count: u32,
+    bounds: f32,
+    start_dead: bool,
+    seed: Option<u64>,
+    emitter_head: Option<(Vec3, Vec3)>,
+) -> Vec<P::Gpu> {
+    // One seed for the whole spawn, so noise jitter lines up between particles
+    let seed = seed.unwrap_or_else(SpawnContext::time_seed);
+    let spawn_one = |i: u32| {
+        let mut ctx = SpawnContext::with_seed(i, count, bounds, seed);
+        if let Some((position, direction)) = emitter_head {
+            ctx.emitter_position = position;
+            ctx.emitter_direction = direction;
+        }
+        spawner(&mut ctx).to_gpu()
+    };
+
+    #[cfg(feature = "rayon")]
+    let mut gpu_particles: Vec<P::Gpu> = {
+        use rayon::prelude::*;
+        (0..count).into_par_iter().map(spawn_one).collect()
+    };
+    #[cfg(not(feature = "rayon"))]
+    let mut gpu_particles: Vec<P::Gpu> = (0..count).map(spawn_one).collect();
+
+    if start_dead {
+        let alive_offset = P::ALIVE_OFFSET as usize;
+
+        // Cast to bytes and set alive = 0 for each particle
+        let bytes: &mut [u8] = bytemuck::cast_slice_mut(&mut gpu_particles);
+        for particle in bytes.chunks_exact_mut(P::GPU_STRIDE) {
+            // alive is a u32, so write 4 bytes of zeros
+            particle[alive_offset..alive_offset + 4].copy_from_slice(&0u32.to_ne_bytes());
+        }
+    }
+
+    gpu_particles
+}
+
+impl<P: ParticleTrait + 'static> Default for Simulation<P> {
+    /// Creates a new simulation with default settings.
+    ///
+    /// Equivalent to [`Simulation::new()`].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Internal configuration passed to the GPU renderer.
+///
+/// Contains all the computed values needed to initialize the GPU state.
+pub(crate) struct SimConfig {
+    /// Total number of particles.
+    pub particle_count: u32,
+    /// Simulation bounds (half-size of bounding cube).
+    pub bounds: f32,
+    /// Generated WGSL compute shader source.
+    pub compute_shader: String,
+    /// Generated WGSL render shader source.
     pub render_shader: String,
     /// Whether neighbor queries are needed.
     pub has_neighbors: bool,
@@ -2115,6 +2903,8 @@ pub(crate) struct SimConfig {
     pub alive_offset: u32,
     /// Byte offset of scale field in particle struct.
     pub scale_offset: u32,
+    /// Byte offset of velocity field in particle struct.
+    pub velocity_offset: Option<u32>,
     /// Size of custom uniforms in bytes.
     pub custom_uniform_size: usize,
     /// WGSL struct fields for custom uniforms.
@@ -2123,11 +2913,19 @@ pub(crate) struct SimConfig {
     pub particle_size: f32,
     /// Whether particle inbox communication is enabled.
     pub inbox_enabled: bool,
-    /// Whether particles should start dead (for emitter-only spawning).
-    pub start_dead: bool,
     /// Whether egui is enabled.
     #[cfg(feature = "egui")]
     pub egui_enabled: bool,
+    /// Directory for the on-disk GPU pipeline cache, if enabled.
+    pub pipeline_cache_dir: Option<PathBuf>,
+    /// Steps (count, fixed delta time) to simulate before the first frame.
+    pub warmup_frames: Option<(u32, f32)>,
+    /// Random seed and fixed delta time for reproducible runs.
+    pub deterministic: Option<(u64, f32)>,
+    /// Whether the simulation is flattened onto the XY plane.
+    pub two_d: bool,
+    /// Particle type and radius for the interaction radius spheres.
+    pub interaction_radius_viz: Option<(u32, f32)>,
     /// Custom textures for shaders.
     pub texture_registry: TextureRegistry,
     /// WGSL declarations for texture bindings.
@@ -2138,6 +2936,8 @@ pub(crate) struct SimConfig {
     pub volume_config: Option<crate::gpu::VolumeConfig>,
     /// Sub-emitters for spawning particles on death.
     pub sub_emitters: Vec<crate::sub_emitter::SubEmitter>,
+    /// Emitters whose state advances each frame.
+    pub emitters: Vec<Emitter>,
     /// WGSL struct definition for particles (needed for spawn shader).
     pub particle_wgsl_struct: String,
 }
@@ -2163,13 +2963,26 @@ struct App<P: ParticleTrait> {
     time: Time,
     // Grid opacity change requested by update callback (None = no change)
     pending_grid_opacity: Option<f32>,
+    // Background color change requested by update callback (None = no change)
+    pending_background: Option<Vec3>,
     // CPU readback - stores data from previous frame's readback request
     readback_data: Option<Vec<u8>>,
+    // Snapshot requested by update callback, written before the next render
+    pending_restore: Option<ParticleSnapshot>,
+    // Writes a frame to the recording file every recording interval
+    recorder: Option<ParticleRecorder>,
+    // Recording uploaded each frame in place of the compute pass
+    playback: Option<ParticleRecording>,
+    // Reloads the compute shader when its file changes
+    #[cfg(not(target_arch = "wasm32"))]
+    shader_watcher: Option<ShaderWatcher>,
+    // Result of the last shader reload, shown in the window title
+    shader_status: Option<&'static str>,
 }
 
 impl<P: ParticleTrait + 'static> App<P> {
     fn new(
-        particles: Vec<P>,
+        gpu_particles: Vec<P::Gpu>,
         config: SimConfig,
         custom_uniforms: CustomUniforms,
         update_callback: Option<UpdateCallback>,
@@ -2178,22 +2991,9 @@ impl<P: ParticleTrait + 'static> App<P> {
         #[cfg(feature = "egui")] rule_inspector_enabled: bool,
         #[cfg(feature = "egui")] rules: Vec<Rule>,
     ) -> Self {
-        // Convert user particles to GPU format
-        let mut gpu_particles: Vec<P::Gpu> = particles.iter().map(|p| p.to_gpu()).collect();
-
-        // If start_dead is set, set all particles' alive field to 0
-        if config.start_dead {
-            let particle_size = std::mem::size_of::<P::Gpu>();
-            let alive_offset = config.alive_offset as usize;
-
-            // Cast to bytes and set alive = 0 for each particle
-            let bytes: &mut [u8] = bytemuck::cast_slice_mut(&mut gpu_particles);
-            for i in 0..particles.len() {
-                let particle_start = i * particle_size;
-                // alive is a u32, so write 4 bytes of zeros
-                bytes[particle_start + alive_offset..particle_start + alive_offset + 4]
-                    .copy_from_slice(&0u32.to_ne_bytes());
-            }
+        let mut time = Time::new();
+        if let Some((_, fixed_dt)) = config.deterministic {
+            time.set_fixed_step(Some(fixed_dt));
         }
 
         Self {
@@ -2212,9 +3012,16 @@ impl<P: ParticleTrait + 'static> App<P> {
             rule_inspector_enabled,
             #[cfg(feature = "egui")]
             rules,
-            time: Time::new(),
+            time,
             pending_grid_opacity: None,
+            pending_background: None,
             readback_data: None,
+            pending_restore: None,
+            recorder: None,
+            playback: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            shader_watcher: None,
+            shader_status: None,
         }
     }
 }
@@ -2241,7 +3048,7 @@ impl<P: ParticleTrait + 'static> ApplicationHandler for App<P> {
                 window,
                 particle_bytes,
                 self.config.particle_count,
-                std::mem::size_of::<P::Gpu>(),
+                P::GPU_STRIDE,
                 &self.config.compute_shader,
                 &self.config.render_shader,
                 self.config.has_neighbors,
@@ -2249,30 +3056,71 @@ impl<P: ParticleTrait + 'static> ApplicationHandler for App<P> {
                 self.config.color_offset,
                 self.config.alive_offset,
                 self.config.scale_offset,
+                self.config.velocity_offset,
                 self.config.custom_uniform_size,
                 self.config.visual_config.blend_mode,
                 self.config.visual_config.trail_length,
+                self.config.visual_config.trail_tail_color,
+                &self.config.visual_config.trail,
                 self.config.particle_size,
                 self.config.visual_config.connections_enabled,
                 self.config.visual_config.connections_radius,
                 self.config.visual_config.connections_color,
+                self.config
+                    .visual_config
+                    .connections_far_color
+                    .unwrap_or(self.config.visual_config.connections_color),
+                self.config.visual_config.connections_directed,
+                self.config.visual_config.connections_flow_speed,
                 self.config.inbox_enabled,
                 self.config.visual_config.background_color,
                 self.config.visual_config.post_process_shader.as_deref(),
+                &self.config.visual_config.post_process_chain,
+                self.config.visual_config.chromatic_aberration,
+                self.config.visual_config.bloom.as_ref(),
+                self.config.visual_config.hdr,
+                self.config.visual_config.tone_map,
+                self.config.visual_config.msaa_samples,
                 &self.config.custom_uniform_fields,
                 &self.config.texture_registry,
                 &self.config.texture_declarations,
                 &self.config.field_registry,
                 self.config.volume_config.as_ref(),
                 &self.config.sub_emitters,
+                self.config.emitters.len(),
                 self.config.visual_config.spatial_grid_opacity,
+                self.config.interaction_radius_viz,
                 &self.config.particle_wgsl_struct,
                 self.config.visual_config.wireframe_mesh.as_ref(),
                 self.config.visual_config.wireframe_thickness,
+                self.config.visual_config.particle_mesh(),
+                self.config.visual_config.mesh_align_to_velocity,
+                self.config.visual_config.light.as_ref(),
+                self.config.bounds,
+                self.config.two_d,
+                self.config.pipeline_cache_dir.as_deref(),
                 #[cfg(feature = "egui")]
                 self.config.egui_enabled,
             )) {
-                Ok(state) => self.gpu_state = Some(state),
+                Ok(mut state) => {
+                    if let Some((seed, _)) = self.config.deterministic {
+                        state.set_seed(seed);
+                    }
+                    if self.playback.is_some() {
+                        state.set_compute_paused(true);
+                    }
+                    if let Some((frames, delta_time)) = self.config.warmup_frames {
+                        run_steps(
+                            &mut self.config.emitters,
+                            &mut self.custom_uniforms,
+                            std::iter::repeat_n(delta_time, frames as usize),
+                            |dt, custom_bytes| state.step(1, dt, custom_bytes),
+                        );
+                        // Carry on from the warmup's time instead of restarting at zero
+                        self.time.skip_ahead(frames as f32 * delta_time);
+                    }
+                    self.gpu_state = Some(state);
+                }
                 Err(e) => {
                     eprintln!("Failed to initialize GPU: {}", e);
                     event_loop.exit();
@@ -2391,16 +3239,37 @@ impl<P: ParticleTrait + 'static> ApplicationHandler for App<P> {
                     gpu_state.camera.update(delta_time);
                 }
 
+                // Swap in the watched compute shader when its file changes
+                #[cfg(not(target_arch = "wasm32"))]
+                if let (Some(watcher), Some(gpu_state)) = (&mut self.shader_watcher, &mut self.gpu_state) {
+                    if let Some(source) = watcher.poll() {
+                        let reloaded = source
+                            .map_err(|e| e.to_string())
+                            .and_then(|source| gpu_state.try_rebuild_compute_pipeline(&source));
+                        self.shader_status = Some(match reloaded {
+                            Ok(()) => "Shader reloaded ✓",
+                            Err(e) => {
+                                eprintln!("Shader error in {}:\n{}", watcher.path().display(), e);
+                                "Shader error (see console)"
+                            }
+                        });
+                    }
+                }
+
                 // Update window title with FPS (Time handles the update interval internally)
                 let fps = self.time.fps();
                 if fps > 0.0 {
                     if let Some(window) = &self.window {
-                        let title = format!(
+                        let mut title = format!(
                             "RDPE | {} particles | {:.1} FPS | {:.2}ms",
                             self.config.particle_count,
                             fps,
                             1000.0 / fps.max(0.001)
                         );
+                        if let Some(status) = self.shader_status {
+                            title.push_str(" | ");
+                            title.push_str(status);
+                        }
                         window.set_title(&title);
                     }
                 }
@@ -2419,12 +3288,17 @@ impl<P: ParticleTrait + 'static> ApplicationHandler for App<P> {
                         self.config.bounds,
                         self.input.aspect_ratio(),
                         &mut self.pending_grid_opacity,
+                        &mut self.pending_background,
                         &mut pending_readback,
                         self.readback_data.as_deref(),
+                        P::GPU_STRIDE,
+                        &mut self.pending_restore,
                     );
                     callback(&mut ctx);
                 }
 
+                advance_emitters(&mut self.config.emitters, &mut self.custom_uniforms, delta_time);
+
                 // Get custom uniform bytes
                 let custom_bytes = if !self.custom_uniforms.is_empty() {
                     Some(self.custom_uniforms.to_bytes())
@@ -2438,6 +3312,26 @@ impl<P: ParticleTrait + 'static> ApplicationHandler for App<P> {
                         gpu_state.set_grid_opacity(opacity);
                     }
 
+                    // Apply pending background color change
+                    if let Some(color) = self.pending_background.take() {
+                        self.config.visual_config.background_color = color;
+                        gpu_state.set_background_color(color);
+                    }
+
+                    // Apply pending snapshot restore
+                    if let Some(snapshot) = self.pending_restore.take() {
+                        if let Err(e) = gpu_state.restore(&snapshot) {
+                            eprintln!("Snapshot restore failed: {}", e);
+                        }
+                    }
+
+                    // Upload the playback frame for the current time (looping)
+                    if let Some(ref recording) = self.playback {
+                        let duration = recording.duration();
+                        let t = if duration > 0.0 { time % duration } else { 0.0 };
+                        gpu_state.write_particles(recording.frame_at_time(t));
+                    }
+
                     let bytes_ref = custom_bytes.as_deref();
 
                     #[cfg(feature = "egui")]
@@ -2479,6 +3373,20 @@ impl<P: ParticleTrait + 'static> ApplicationHandler for App<P> {
                                     Err(e) => eprintln!("Particle readback failed: {}", e),
                                 }
                             }
+
+                            // Append a recording frame when one is due
+                            if let Some(ref mut recorder) = self.recorder {
+                                if recorder.is_due(time) {
+                                    let written = gpu_state
+                                        .read_particles_sync()
+                                        .map_err(|e| e.to_string())
+                                        .and_then(|data| recorder.write_frame(time, &data).map_err(|e| e.to_string()));
+                                    if let Err(e) = written {
+                                        eprintln!("Recording stopped: {}", e);
+                                        self.recorder = None;
+                                    }
+                                }
+                            }
                         }
                         Err(wgpu::SurfaceError::Lost) => {
                             gpu_state.resize(winit::dpi::PhysicalSize {
@@ -2719,7 +3627,7 @@ fn render_particle_inspector<P: ParticleTrait>(ctx: &egui::Context) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use glam::Vec3;
+    use crate::Falloff;
 
     // Test particle for simulation tests
     #[derive(Clone)]
@@ -2841,6 +3749,10 @@ mod tests {
         assert!(!sim.inbox_enabled);
         assert!(sim.field_registry.is_empty());
         assert!(sim.custom_fragment_shader.is_none());
+        assert!(sim.warmup_frames.is_none());
+        assert!(sim.deterministic.is_none());
+        assert!(sim.recorder.is_none());
+        assert!(sim.playback.is_none());
     }
 
     // ========== Builder Pattern Tests ==========
@@ -2889,6 +3801,22 @@ mod tests {
         assert_eq!(sim.rules.len(), 2);
     }
 
+    #[test]
+    fn test_with_background() {
+        let color = Vec3::new(0.1, 0.2, 0.3);
+        let sim = Simulation::<TestParticle>::new().with_background(color);
+        assert_eq!(sim.background(), color);
+    }
+
+    #[test]
+    fn test_set_background() {
+        let mut sim = Simulation::<TestParticle>::new();
+        assert_eq!(sim.background(), VisualConfig::default().background_color);
+
+        sim.set_background(Vec3::ONE);
+        assert_eq!(sim.background(), Vec3::ONE);
+    }
+
     #[test]
     fn test_with_inbox() {
         let sim = Simulation::<TestParticle>::new()
@@ -3011,6 +3939,24 @@ mod tests {
         // Default spatial config should have sensible values
         assert!(sim.spatial_config.cell_size > 0.0);
         assert!(sim.spatial_config.grid_resolution > 0);
+        assert!(sim.spatial_config.skip_dead_neighbors);
+    }
+
+    #[test]
+    fn test_skip_dead_neighbors_toggle() {
+        let rules = || {
+            Simulation::<TestParticle>::new()
+                .with_rule(Rule::Separate { radius: 0.1, strength: 1.0 })
+                .with_rule(Rule::Evade { self_type: 0, threat_type: 1, radius: 0.3, strength: 1.0 })
+        };
+
+        let shader = rules().generate_compute_shader();
+        assert!(shader.contains("if other.alive == 0u"));
+        validate_wgsl(&shader).expect("Shader with dead-neighbor skip should be valid");
+
+        let shader = rules().with_skip_dead_neighbors(false).generate_compute_shader();
+        assert!(!shader.contains("if other.alive == 0u"));
+        validate_wgsl(&shader).expect("Shader without dead-neighbor skip should be valid");
     }
 
     // ========== Visual Config Tests ==========
@@ -3092,6 +4038,30 @@ mod tests {
         validate_wgsl(&shader).expect("Attractor shader should be valid");
     }
 
+    #[test]
+    fn test_noise_field_shader_validates() {
+        let sim = Simulation::<TestParticle>::new()
+            .with_particle_count(1000)
+            .with_rule(Rule::Typed {
+                self_type: 0,
+                other_type: None,
+                rule: Box::new(Rule::NoiseField {
+                    frequency: 2.0,
+                    amplitude: 1.0,
+                    octaves: 3,
+                    time_scale: 0.1,
+                }),
+            })
+            .with_rule(Rule::WrapWalls);
+
+        let shader = sim.generate_compute_shader();
+        assert!(shader.contains("fn fbm_value3"));
+        validate_wgsl(&shader).expect("Noise field shader should be valid");
+
+        let plain = Simulation::<TestParticle>::new().with_rule(Rule::Gravity(1.0));
+        assert!(!plain.generate_compute_shader().contains("fn fbm_value3"));
+    }
+
     #[test]
     fn test_vortex_curl_shader_validates() {
         // Simulation with vortex and curl forces
@@ -3102,6 +4072,8 @@ mod tests {
                 center: Vec3::ZERO,
                 axis: Vec3::Y,
                 strength: 2.0,
+                radius: 0.0,
+                falloff: Falloff::Constant,
             })
             .with_rule(Rule::Curl {
                 scale: 0.5,
@@ -3166,6 +4138,95 @@ mod tests {
         validate_wgsl(&shader).expect("Boids shader should be valid");
     }
 
+    #[test]
+    fn test_boid_rule_shader_validates() {
+        // Single-pass boids, mixed with a Cohere that has its own accumulator
+        let sim = Simulation::<TestParticle>::new()
+            .with_spatial_config(0.2, 32)
+            .with_rule(Rule::Boid {
+                separation_radius: 0.05,
+                cohesion_radius: 0.2,
+                alignment_radius: 0.1,
+                separation_weight: 3.0,
+                cohesion_weight: 1.0,
+                alignment_weight: 1.5,
+                self_type: Some(1),
+            })
+            .with_rule(Rule::Cohere {
+                radius: 0.15,
+                strength: 1.0,
+            })
+            .with_rule(Rule::WrapWalls);
+
+        let shader = sim.generate_compute_shader();
+        assert_eq!(shader.matches("var boid_cohesion_sum").count(), 1);
+        assert!(shader.contains("other.particle_type == 1u"));
+        validate_wgsl(&shader).expect("Boid shader should be valid");
+    }
+
+    #[test]
+    fn test_max_age_shader_validates() {
+        // The test wrapper in rules.rs has no scale field, so check here.
+        // Any f32 field can hold the unfaded scale; reuse the padding.
+        let sim = Simulation::<TestParticle>::new()
+            .with_rule(Rule::Age)
+            .with_rule(Rule::MaxAge {
+                max_age: 3.0,
+                fade_out_start: 2.0,
+                scale_field: "_pad0".into(),
+            });
+
+        let shader = sim.generate_compute_shader();
+        validate_wgsl(&shader).expect("MaxAge shader should be valid");
+    }
+
+    #[test]
+    fn test_magnetic_shader_validates() {
+        // TestParticle has no charge field; any f32 field passes validation
+        let sim = Simulation::<TestParticle>::new().with_rule(Rule::Magnetic {
+            charge_field: "scale".into(),
+            field_strength: Vec3::new(0.0, 2.0, 0.0),
+        });
+
+        let shader = sim.generate_compute_shader();
+        validate_wgsl(&shader).expect("Magnetic shader should be valid");
+    }
+
+    #[test]
+    #[should_panic(expected = "particle struct has no such field")]
+    fn test_magnetic_missing_charge_field() {
+        // Rejected as soon as the rule is added
+        let _ = Simulation::<TestParticle>::new().with_rule(Rule::Magnetic {
+            charge_field: "charge".into(),
+            field_strength: Vec3::Y,
+        });
+    }
+
+    #[test]
+    fn test_particle_field_type() {
+        let wgsl = TestParticle::WGSL_STRUCT;
+        assert_eq!(particle_field_type(wgsl, "scale").as_deref(), Some("f32"));
+        assert_eq!(particle_field_type(wgsl, "alive").as_deref(), Some("u32"));
+        assert_eq!(particle_field_type(wgsl, "position").as_deref(), Some("vec3<f32>"));
+        // Prefixes and padding don't match a longer or shorter name
+        assert_eq!(particle_field_type(wgsl, "scal"), None);
+        assert_eq!(particle_field_type(wgsl, "_pad"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "it is declared as u32")]
+    fn test_magnetic_charge_field_wrong_type() {
+        let sim = Simulation::<TestParticle>::new().with_rule(Rule::Typed {
+            self_type: 0,
+            other_type: None,
+            rule: Box::new(Rule::Magnetic {
+                charge_field: "alive".into(),
+                field_strength: Vec3::Y,
+            }),
+        });
+        sim.generate_compute_shader();
+    }
+
     #[test]
     fn test_collision_shader_validates() {
         // Particle collision simulation
@@ -3176,6 +4237,8 @@ mod tests {
             .with_rule(Rule::Collide {
                 radius: 0.03,
                 restitution: 0.8,
+                self_type: None,
+                target_type: None,
             })
             .with_rule(Rule::Gravity(2.0))
             .with_rule(Rule::BounceWalls);
@@ -3184,6 +4247,38 @@ mod tests {
         validate_wgsl(&shader).expect("Collision shader should be valid");
     }
 
+    #[test]
+    fn test_alive_count_binding_in_both_shaders() {
+        let simple = Simulation::<TestParticle>::new().with_rule(Rule::Gravity(1.0));
+        let neighbors = Simulation::<TestParticle>::new()
+            .with_spatial_config(0.1, 32)
+            .with_rule(Rule::Separate { radius: 0.05, strength: 1.0 });
+
+        for sim in [simple, neighbors] {
+            let shader = sim.generate_compute_shader();
+            assert!(shader.contains("@group(0) @binding(6)\nvar<storage, read_write> counters: Counters;"));
+            assert!(shader.contains("atomicAdd(&counters.alive, 1u);"));
+            validate_wgsl(&shader).expect("Shader with alive counter should be valid");
+        }
+    }
+
+    #[test]
+    fn test_collision_type_filter_shader_validates() {
+        let sim = Simulation::<TestParticle>::new()
+            .with_spatial_config(0.1, 32)
+            .with_rule(Rule::Collide {
+                radius: 0.03,
+                restitution: 0.2,
+                self_type: Some(0),
+                target_type: Some(1),
+            })
+            .with_rule(Rule::BounceWalls);
+
+        let shader = sim.generate_compute_shader();
+        assert!(shader.contains("p.particle_type == 0u && other.particle_type == 1u"));
+        validate_wgsl(&shader).expect("Filtered collision shader should be valid");
+    }
+
     #[test]
     fn test_nbody_shader_validates() {
         // N-body gravity simulation
@@ -3210,6 +4305,7 @@ mod tests {
             .with_bounds(1.0)
             .with_rule(Rule::Spring {
                 anchor: Vec3::ZERO,
+                rest_length: 0.0,
                 stiffness: 5.0,
                 damping: 0.1,
             })
@@ -3236,6 +4332,34 @@ mod tests {
         validate_wgsl(&shader).expect("Age lifecycle shader should be valid");
     }
 
+    #[test]
+    fn test_interaction_radius_is_smallest_neighbor_radius() {
+        let sim = Simulation::<TestParticle>::new()
+            .with_rule(Rule::Gravity(9.8))
+            .with_rule(Rule::Separate { radius: 0.08, strength: 1.0 })
+            .with_rule(Rule::Cohere { radius: 0.2, strength: 0.5 });
+        assert_eq!(sim.interaction_radius(), Some(0.08));
+
+        let sim = sim.with_interactions(|m| m.set(0u32, 1u32, 1.0, 0.05));
+        assert_eq!(sim.interaction_radius(), Some(0.05));
+
+        let sim = Simulation::<TestParticle>::new().with_rule(Rule::Drag(1.0));
+        assert_eq!(sim.interaction_radius(), None);
+    }
+
+    #[test]
+    fn test_fade_in_lifecycle_shader_validates() {
+        // Fade-in combined with shrink-out
+        let sim = Simulation::<TestParticle>::new()
+            .with_particle_count(5000)
+            .with_bounds(1.0)
+            .with_lifecycle(|l| l.lifetime(2.0).shrink_out().fade_in(0.3, "base_scale"))
+            .with_rule(Rule::Gravity(9.8));
+
+        let shader = sim.generate_compute_shader();
+        validate_wgsl(&shader).expect("Fade-in lifecycle shader should be valid");
+    }
+
     #[test]
     fn test_wander_shader_validates() {
         // Random wandering motion
@@ -3261,7 +4385,8 @@ mod tests {
             .with_bounds(2.0)
             .with_rule(Rule::Orbit {
                 center: Vec3::ZERO,
-                strength: 3.0,
+                gravity: 3.0,
+                softening: 0.05,
             })
             .with_rule(Rule::PointGravity {
                 point: Vec3::ZERO,
@@ -3288,6 +4413,37 @@ mod tests {
         validate_wgsl(&shader).expect("Inbox shader should be valid");
     }
 
+    #[test]
+    fn test_inbox_message_helpers_validate() {
+        let sim = Simulation::<TestParticle>::new()
+            .with_particle_count(1000)
+            .with_bounds(1.0)
+            .with_inbox()
+            .with_rule(Rule::custom(r#"
+                send_message((index + 1u) % 1000u, 1u, 3);
+                let count = get_message(index, 1u);
+                if count > 0 {
+                    p.velocity.y += 0.01;
+                }
+            "#));
+
+        let shader = sim.generate_compute_shader();
+        assert!(shader.contains("fn get_message(particle_id: u32, channel: u32) -> i32"));
+        assert!(shader.contains("fn send_message(target_id: u32, channel: u32, value: i32)"));
+        validate_wgsl(&shader).expect("Inbox message helpers should be valid");
+    }
+
+    #[test]
+    fn test_message_helpers_absent_without_inbox() {
+        let sim = Simulation::<TestParticle>::new()
+            .with_particle_count(1000)
+            .with_rule(Rule::Gravity(9.8));
+
+        let shader = sim.generate_compute_shader();
+        assert!(!shader.contains("fn get_message"));
+        assert!(!shader.contains("fn send_message"));
+    }
+
     #[test]
     fn test_single_field_shader_validates() {
         // Single spatial field
@@ -3318,6 +4474,46 @@ mod tests {
         validate_wgsl(&shader).expect("Multi-field shader should be valid");
     }
 
+    #[test]
+    fn test_field_gradient_shader_validates() {
+        let sim = Simulation::<TestParticle>::new()
+            .with_field("food", FieldConfig::new(32))
+            .with_field("heat", FieldConfig::new(16))
+            .with_rule(Rule::FieldGradient {
+                field_index: 1,
+                strength: 3.0,
+                signed: true,
+            })
+            .with_rule(Rule::WrapWalls);
+
+        let shader = sim.generate_compute_shader();
+        validate_wgsl(&shader).expect("Field gradient shader should be valid");
+    }
+
+    #[test]
+    #[should_panic(expected = "no fields are registered")]
+    fn test_field_gradient_requires_field() {
+        let sim = Simulation::<TestParticle>::new().with_rule(Rule::FieldGradient {
+            field_index: 0,
+            strength: 1.0,
+            signed: false,
+        });
+        sim.generate_compute_shader();
+    }
+
+    #[test]
+    #[should_panic(expected = "only 1 field(s) are registered")]
+    fn test_field_gradient_index_out_of_range() {
+        let sim = Simulation::<TestParticle>::new()
+            .with_field("food", FieldConfig::new(32))
+            .with_rule(Rule::FieldGradient {
+                field_index: 2,
+                strength: 1.0,
+                signed: false,
+            });
+        sim.generate_compute_shader();
+    }
+
     #[test]
     fn test_custom_function_shader_validates() {
         // Custom WGSL function
@@ -3329,7 +4525,7 @@ fn my_force(pos: vec3<f32>) -> vec3<f32> {
     return normalize(-pos) * 0.1;
 }
 "#)
-            .with_rule(Rule::Custom("p.velocity += my_force(p.position);".to_string()))
+            .with_rule(Rule::custom("p.velocity += my_force(p.position);"))
             .with_rule(Rule::BounceWalls);
 
         let shader = sim.generate_compute_shader();
@@ -3344,13 +4540,26 @@ fn my_force(pos: vec3<f32>) -> vec3<f32> {
             .with_bounds(1.0)
             .with_uniform::<f32>("force_strength", 1.0)
             .with_uniform::<f32>("decay_rate", 0.1)
-            .with_rule(Rule::Custom("p.velocity *= (1.0 - uniforms.decay_rate);".to_string()))
+            .with_rule(Rule::custom("p.velocity *= (1.0 - uniforms.decay_rate);"))
             .with_rule(Rule::BounceWalls);
 
         let shader = sim.generate_compute_shader();
         validate_wgsl(&shader).expect("Custom uniform shader should be valid");
     }
 
+    #[test]
+    fn test_particle_count_and_bounds_uniforms_validate() {
+        let sim = Simulation::<TestParticle>::new()
+            .with_uniform::<f32>("strength", 1.0)
+            .with_rule(Rule::custom(
+                "let other = particles[(index + 1u) % uniforms.particle_count];\n\
+                 p.position = clamp(p.position, vec3(-uniforms.bounds), vec3(uniforms.bounds));",
+            ));
+        let shader = sim.generate_compute_shader();
+        assert!(shader.contains("particle_count: u32"));
+        validate_wgsl(&shader).expect("Shader using particle_count and bounds should be valid");
+    }
+
     #[test]
     fn test_typed_rules_shader_validates() {
         // Typed particle interactions
@@ -3381,6 +4590,66 @@ fn my_force(pos: vec3<f32>) -> vec3<f32> {
         validate_wgsl(&shader).expect("Typed rules shader should be valid");
     }
 
+    #[test]
+    fn test_2d_mode_shader_validates() {
+        let sim = Simulation::<TestParticle>::new()
+            .with_2d_mode()
+            .with_spatial_config(0.1, 32)
+            .with_rule(Rule::Separate { radius: 0.05, strength: 1.0 });
+
+        let shader = sim.generate_compute_shader();
+        assert!(shader.contains("p.position.z = 0.0;"));
+        assert!(shader.contains("offset_idx = 9u; offset_idx < 18u"));
+        validate_wgsl(&shader).expect("2D mode shader should be valid");
+
+        let flat = Simulation::<TestParticle>::new().with_2d_mode().with_rule(Rule::Drag(1.0));
+        validate_wgsl(&flat.generate_compute_shader()).expect("2D mode shader without neighbors should be valid");
+    }
+
+    #[test]
+    fn test_typed_multi_shader_validates() {
+        let mut matrix = InteractionMatrix::new(3);
+        matrix.set(0u32, 1u32, 1.0, 0.3);
+        matrix.set(1u32, 0u32, -0.5, 0.2);
+        matrix.set(2u32, 2u32, 0.25, 0.1);
+        let sim = Simulation::<TestParticle>::new()
+            .with_spatial_config(0.3, 32)
+            .with_rule(Rule::TypedMulti(matrix))
+            .with_rule(Rule::BounceWalls);
+
+        assert!(sim.has_neighbor_rules());
+        let shader = sim.generate_compute_shader();
+        assert!(shader.contains("const INTERACTION_MATRIX: array<array<f32, 3>, 3>"));
+        assert_eq!(shader.matches("apply_typed_interaction(&p, other, uniforms);").count(), 1);
+        validate_wgsl(&shader).expect("Typed multi shader should be valid");
+    }
+
+    #[test]
+    #[should_panic(expected = "only one interaction matrix")]
+    fn test_two_interaction_matrices_panic() {
+        let sim = Simulation::<TestParticle>::new()
+            .with_spatial_config(0.3, 32)
+            .with_interactions_sized(2, |m| m.set(0u32, 1u32, 1.0, 0.3))
+            .with_rule(Rule::TypedMulti(InteractionMatrix::new(2)));
+        sim.generate_compute_shader();
+    }
+
+    #[test]
+    fn test_interaction_matrix_shader_validates() {
+        let sim = Simulation::<TestParticle>::new()
+            .with_spatial_config(0.3, 32)
+            .with_interactions_sized(3, |m| {
+                m.set(0u32, 1u32, 1.0, 0.3);
+                m.set(1u32, 0u32, -0.5, 0.2);
+            })
+            .with_rule(Rule::BounceWalls);
+
+        let shader = sim.generate_compute_shader();
+        assert!(shader.contains("const INTERACTION_MATRIX: array<array<f32, 3>, 3>"));
+        assert!(shader.contains("apply_typed_interaction(&p, other, uniforms);"));
+        validate_wgsl(&shader).expect("Interaction matrix shader should be valid");
+    }
+
     #[test]
     fn test_chase_evade_shader_validates() {
         // Predator-prey dynamics
@@ -3435,8 +4704,10 @@ fn custom_decay(v: vec3<f32>) -> vec3<f32> {
                 center: Vec3::ZERO,
                 axis: Vec3::Y,
                 strength: 0.5,
+                radius: 0.0,
+                falloff: Falloff::Constant,
             })
-            .with_rule(Rule::Custom("p.velocity = custom_decay(p.velocity);".to_string()))
+            .with_rule(Rule::custom("p.velocity = custom_decay(p.velocity);"))
             .with_rule(Rule::SpeedLimit { min: 0.05, max: 1.5 })
             .with_rule(Rule::BounceWalls);
 
@@ -3475,9 +4746,10 @@ fn custom_decay(v: vec3<f32>) -> vec3<f32> {
                 strength: 0.5,
             })
             .with_rule(Rule::Pressure {
-                radius: 0.08,
-                strength: 1.0,
-                target_density: 8.0,
+                rest_density: 8.0,
+                pressure_constant: 1.0,
+                viscosity: 0.0,
+                kernel_radius: 0.08,
             })
             .with_rule(Rule::Gravity(2.0))
             .with_rule(Rule::BounceWalls);
@@ -3522,4 +4794,287 @@ fn custom_decay(v: vec3<f32>) -> vec3<f32> {
         let shader = sim.generate_compute_shader();
         validate_wgsl(&shader).expect("All falloff types shader should be valid");
     }
+
+    #[test]
+    fn test_sprite_sheet_render_shader_validates() {
+        use crate::visuals::{LoopMode, SpriteSheetConfig};
+
+        for loop_mode in [LoopMode::Loop, LoopMode::Clamp] {
+            let sim = Simulation::<TestParticle>::new()
+                .with_texture("flame", crate::textures::TextureConfig::solid(255, 128, 0, 255))
+                .with_visuals(|v| {
+                    v.sprite_sheet(SpriteSheetConfig {
+                        columns: 4,
+                        rows: 2,
+                        fps: 12.5,
+                        loop_mode,
+                    });
+                });
+
+            let shader = sim.generate_render_shader();
+            assert!(shader.contains("textureSample(tex_flame"));
+            validate_wgsl(&shader).expect("Sprite sheet render shader should be valid");
+        }
+    }
+
+    #[test]
+    fn test_light_render_shader_validates() {
+        use crate::visuals::LightConfig;
+
+        let sim = Simulation::<TestParticle>::new()
+            .with_visuals(|v| {
+                v.light(LightConfig::default());
+            });
+        let shader = sim.generate_render_shader();
+        assert!(shader.contains("@group(1) @binding(0) var shadow_map"));
+        assert!(shader.contains("let base_size = light.particle_size;"));
+        validate_wgsl(&shader).expect("Lit render shader should be valid");
+
+        // With textures the shadow group moves after the texture group
+        let sim = Simulation::<TestParticle>::new()
+            .with_texture("flame", crate::textures::TextureConfig::solid(255, 128, 0, 255))
+            .with_visuals(|v| {
+                v.light(LightConfig::default());
+            });
+        let shader = sim.generate_render_shader();
+        assert!(shader.contains("@group(2) @binding(0) var shadow_map"));
+        validate_wgsl(&shader).expect("Lit textured render shader should be valid");
+    }
+
+    #[test]
+    fn test_spawn_gpu_particles_start_dead() {
+        let spawner = |ctx: &mut SpawnContext| TestParticle {
+            position: Vec3::splat(ctx.index as f32),
+            velocity: Vec3::ZERO,
+        };
+
+        let alive = spawn_gpu_particles::<TestParticle>(&spawner, 3, 1.0, false, None, None);
+        assert!(alive.iter().all(|p| p.alive == 1));
+        assert_eq!(alive[2].position, [2.0; 3]);
+
+        let dead = spawn_gpu_particles::<TestParticle>(&spawner, 3, 1.0, true, None, None);
+        assert!(dead.iter().all(|p| p.alive == 0));
+        assert_eq!(dead[2].position, [2.0; 3]);
+    }
+
+    #[test]
+    fn test_spawn_gpu_particles_keeps_index_order_and_seed() {
+        let spawner = |ctx: &mut SpawnContext| TestParticle {
+            position: Vec3::new(ctx.index as f32, ctx.random(), 0.0),
+            velocity: Vec3::ZERO,
+        };
+
+        // Enough particles to be split across threads with the `rayon` feature
+        let first = spawn_gpu_particles::<TestParticle>(&spawner, 10_000, 1.0, false, Some(7), None);
+        let second = spawn_gpu_particles::<TestParticle>(&spawner, 10_000, 1.0, false, Some(7), None);
+        assert!(first.iter().enumerate().all(|(i, p)| p.position[0] == i as f32));
+        assert!(first.iter().zip(&second).all(|(a, b)| a.position == b.position));
+    }
+
+    #[test]
+    fn test_initial_particles() {
+        let sim = Simulation::<TestParticle>::new().with_particle_count(4);
+        assert!(matches!(sim.initial_particles(), Err(crate::error::SimulationError::NoSpawner)));
+
+        let sim = sim
+            .with_spawner(|ctx| TestParticle { position: ctx.random_in_sphere(1.0), velocity: Vec3::ZERO })
+            .deterministic(3, 1.0 / 60.0);
+        let first = sim.initial_particles().unwrap();
+        let second = sim.initial_particles().unwrap();
+        assert_eq!(first.len(), 4);
+        assert!(first.iter().zip(&second).all(|(a, b)| a.position == b.position));
+    }
+
+    #[test]
+    fn test_path_emitter_feeds_cursor_to_shader_and_spawner() {
+        let mut sim = Simulation::<TestParticle>::new()
+            .with_emitter(Emitter::along_path(vec![Vec3::ZERO, Vec3::X], 1.0, 100.0));
+        assert!(sim.custom_uniforms.get("path_emitter_0_count").is_some());
+        let shader = sim.generate_compute_shader();
+        assert!(shader.contains("atomicAdd(&counters.emitted[0u], 1u)"));
+        validate_wgsl(&shader).unwrap();
+
+        advance_emitters(&mut sim.emitters, &mut sim.custom_uniforms, 0.25);
+        assert!(matches!(sim.custom_uniforms.get("path_emitter_0_count"), Some(UniformValue::F32(c)) if *c == 25.0));
+        assert_eq!(sim.emitter_head(), Some((Vec3::new(0.25, 0.0, 0.0), Vec3::X)));
+
+        let spawner = |ctx: &mut SpawnContext| TestParticle {
+            position: ctx.emitter_position,
+            velocity: ctx.emitter_direction,
+        };
+        let spawned = spawn_gpu_particles::<TestParticle>(&spawner, 1, 1.0, false, None, sim.emitter_head());
+        assert_eq!(spawned[0].position, [0.25, 0.0, 0.0]);
+        assert_eq!(spawned[0].velocity, [1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_warmup_steps_advance_emitters() {
+        let mut sim = Simulation::<TestParticle>::new()
+            .with_emitter(Emitter::along_path(vec![Vec3::ZERO, Vec3::X], 1.0, 100.0));
+
+        let mut counts = Vec::new();
+        run_steps(
+            &mut sim.emitters,
+            &mut sim.custom_uniforms,
+            std::iter::repeat_n(0.25, 3),
+            |_, custom_bytes| counts.push(custom_bytes.is_some()),
+        );
+        assert_eq!(counts, vec![true; 3]);
+        assert_eq!(sim.emitter_head(), Some((Vec3::new(0.75, 0.0, 0.0), Vec3::X)));
+    }
+
+    #[test]
+    fn test_play_recording_checks_layout() {
+        let path = std::env::temp_dir().join(format!("rdpe_playback_{}.rec", std::process::id()));
+        {
+            // One byte per particle can't match TestParticle's layout
+            let mut recorder = ParticleRecorder::create(&path, 3, 1, 30).unwrap();
+            recorder.write_frame(0.0, &[0; 3]).unwrap();
+        }
+        let recording = ParticleRecording::open(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let sim = Simulation::<TestParticle>::new()
+            .with_spawner(|_| TestParticle { position: Vec3::ZERO, velocity: Vec3::ZERO })
+            .play_recording(&recording);
+        assert_eq!(sim.particle_count, 3);
+
+        let result = sim.run();
+        assert!(matches!(
+            result,
+            Err(crate::error::SimulationError::Recording(crate::error::RecordingError::LayoutMismatch {
+                found: (3, 1),
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_run_headless_requires_spawner() {
+        let result = Simulation::<TestParticle>::new().run_headless(1, 0.016);
+        assert!(matches!(result, Err(crate::error::SimulationError::NoSpawner)));
+    }
+
+    #[test]
+    fn test_age_trigger_requires_max_age() {
+        let result = Simulation::<TestParticle>::new()
+            .with_spawner(|_| TestParticle { position: Vec3::ZERO, velocity: Vec3::ZERO })
+            .with_sub_emitter(crate::sub_emitter::SubEmitter::new(0, 0).on_age(0.5))
+            .run_headless(1, 0.016);
+        assert!(matches!(result, Err(crate::error::SimulationError::NoMaxAge)));
+    }
+
+    #[test]
+    fn test_sprite_sheet_requires_one_texture() {
+        use crate::error::SimulationError;
+        use crate::textures::TextureConfig;
+        use crate::visuals::SpriteSheetConfig;
+
+        let sim = Simulation::<TestParticle>::new()
+            .with_visuals(|v| {
+                v.sprite_sheet(SpriteSheetConfig::default());
+            });
+        assert!(matches!(sim.check_sprite_texture(), Err(SimulationError::NoSpriteTexture)));
+
+        let sim = sim.with_texture("flame", TextureConfig::solid(255, 128, 0, 255));
+        assert!(sim.check_sprite_texture().is_ok());
+
+        let sim = sim.with_texture("smoke", TextureConfig::solid(64, 64, 64, 255));
+        assert!(matches!(sim.check_sprite_texture(), Err(SimulationError::MultipleSpriteTextures(2))));
+    }
+
+    #[test]
+    fn test_texture_emitter_requires_texture() {
+        let result = Simulation::<TestParticle>::new()
+            .with_spawner(|_| TestParticle { position: Vec3::ZERO, velocity: Vec3::ZERO })
+            .with_emitter(Emitter::from_texture("missing".into(), glam::Vec2::ONE, 1.0, 0.5))
+            .run_headless(1, 0.016);
+        assert!(matches!(result, Err(crate::error::SimulationError::UnknownTexture(name)) if name == "missing"));
+    }
+
+    #[test]
+    fn test_run_headless_applies_gravity() {
+        let result = Simulation::<TestParticle>::new()
+            .with_particle_count(64)
+            .with_spawner(|_| TestParticle { position: Vec3::ZERO, velocity: Vec3::ZERO })
+            .with_rule(Rule::Gravity(10.0))
+            .run_headless(10, 0.01);
+
+        // Skip on machines without a GPU adapter
+        let bytes = match result {
+            Err(crate::error::SimulationError::Gpu(crate::error::GpuError::NoAdapter)) => return,
+            other => other.expect("Headless run failed"),
+        };
+
+        let particles: &[TestParticleGpu] = bytemuck::cast_slice(&bytes);
+        assert_eq!(particles.len(), 64);
+        assert!(particles.iter().all(|p| p.velocity[1] < -0.5 && p.position[1] < 0.0));
+    }
+
+    #[test]
+    fn test_custom_rule_helpers_included_once() {
+        use crate::shader_utils::ShaderHelper;
+
+        let sim = Simulation::<TestParticle>::new()
+            .with_rule(Rule::Custom {
+                wgsl: "p.velocity.y += value_noise(p.position) * ease_bounce(fract(uniforms.time));".into(),
+                helpers: vec![ShaderHelper::Noise, ShaderHelper::Easing],
+            })
+            .with_rule(Rule::Custom {
+                wgsl: r#"
+    let q = quat_from_to(vec3<f32>(0.0, 1.0, 0.0), p.velocity + vec3<f32>(0.0, 0.001, 0.0));
+    let r = quat_slerp(vec4<f32>(0.0, 0.0, 0.0, 1.0), quat_mul(q, q), 0.5);
+    p.velocity = quat_rotate(r, p.velocity) * pcg_hash_f32(index);
+    if sdf_box(p.position, vec3<f32>(0.5)) > 0.0 && sdf_capsule(p.position, vec3<f32>(0.0), vec3<f32>(0.0, 1.0, 0.0), 0.1) > sdf_sphere(p.position, 0.2) {
+        p.velocity *= 0.5;
+    }
+"#
+                .into(),
+                helpers: ShaderHelper::ALL.to_vec(),
+            });
+        let shader = sim.generate_compute_shader();
+        assert_eq!(shader.matches("fn pcg_hash(").count(), 1);
+        assert_eq!(shader.matches("fn value_noise(").count(), 1);
+        validate_wgsl(&shader).expect("Compute shader with helper libraries should be valid");
+
+        // No helpers asked for, none included
+        let plain = Simulation::<TestParticle>::new().with_rule(Rule::custom("p.velocity *= 0.99;"));
+        assert!(!plain.generate_compute_shader().contains("fn pcg_hash("));
+    }
+
+    #[test]
+    fn test_deterministic_seed_reaches_compute_shader() {
+        let sim = Simulation::<TestParticle>::new()
+            .with_rule(Rule::custom("p.velocity += rand_sphere(index);"))
+            .deterministic(42, 0.01);
+        let shader = sim.generate_compute_shader();
+        assert!(shader.contains("seed_lo: u32"));
+        assert!(shader.contains("rng_seed = uniforms.seed_lo ^ hash(uniforms.seed_hi);"));
+        validate_wgsl(&shader).expect("Seeded compute shader should be valid");
+    }
+
+    #[test]
+    fn test_deterministic_runs_are_identical() {
+        let run = |seed| {
+            Simulation::<TestParticle>::new()
+                .with_particle_count(256)
+                .with_spawner(|ctx| TestParticle { position: ctx.random_in_sphere(0.5), velocity: Vec3::ZERO })
+                .with_rule(Rule::custom("p.velocity += rand_sphere(index + u32(uniforms.time * 1000.0));"))
+                .with_rule(Rule::Wander { strength: 0.5, frequency: 2.0 })
+                .with_rule(Rule::BounceWalls)
+                .deterministic(seed, 0.01)
+                .run_headless(30, 0.01)
+        };
+
+        // Skip on machines without a GPU adapter
+        let first = match run(42) {
+            Err(crate::error::SimulationError::Gpu(crate::error::GpuError::NoAdapter)) => return,
+            other => other.expect("Headless run failed"),
+        };
+        let second = run(42).expect("Headless run failed");
+        let other_seed = run(7).expect("Headless run failed");
+
+        assert_eq!(first, second);
+        assert_ne!(first, other_seed);
+    }
 }
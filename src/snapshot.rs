@@ -0,0 +1,179 @@
+//! Particle state snapshots for saving and branching simulations.
+//!
+//! A [`ParticleSnapshot`] holds the raw GPU particle buffer along with the
+//! layout needed to check it against a running simulation. Take one from an
+//! update callback, keep it in memory or save it to disk, and restore it
+//! later to rewind or branch the simulation.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut saved: Option<ParticleSnapshot> = None;
+//!
+//! Simulation::<Ball>::new()
+//!     .with_update(move |ctx| {
+//!         if ctx.key_pressed(KeyCode::KeyS) {
+//!             ctx.request_readback();
+//!         }
+//!         if let Some(snapshot) = ctx.snapshot() {
+//!             snapshot.save_to_file("state.snap").ok();
+//!             saved = Some(snapshot);
+//!         }
+//!         if ctx.key_pressed(KeyCode::KeyL) {
+//!             if let Some(snapshot) = &saved {
+//!                 ctx.restore(snapshot.clone());
+//!             }
+//!         }
+//!     })
+//!     .run();
+//! ```
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+
+use crate::error::SnapshotError;
+
+/// GPU particle structs are padded to a multiple of 16 bytes.
+const STRIDE_ALIGNMENT: usize = 16;
+
+/// Raw copy of the particle buffer at one point in time.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ParticleSnapshot {
+    /// Particle buffer bytes (`num_particles * stride`).
+    pub data: Vec<u8>,
+    /// Number of particles in the buffer.
+    pub num_particles: u32,
+    /// Bytes per particle.
+    pub stride: usize,
+}
+
+impl ParticleSnapshot {
+    /// Wrap raw particle buffer bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` is not a whole number of `stride`-sized particles.
+    pub fn new(data: Vec<u8>, stride: usize) -> Self {
+        assert!(
+            stride > 0 && data.len().is_multiple_of(stride),
+            "Snapshot data ({} bytes) is not a multiple of the particle stride ({})",
+            data.len(),
+            stride
+        );
+        let num_particles = (data.len() / stride) as u32;
+        Self { data, num_particles, stride }
+    }
+
+    /// Check that this snapshot fits a particle buffer of the given shape.
+    pub fn check_layout(&self, num_particles: u32, stride: usize) -> Result<(), SnapshotError> {
+        if self.num_particles != num_particles || self.stride != stride {
+            return Err(SnapshotError::LayoutMismatch {
+                expected: (num_particles, stride),
+                found: (self.num_particles, self.stride),
+            });
+        }
+        Ok(())
+    }
+
+    /// Save the snapshot to a binary file.
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        let writer = BufWriter::new(File::create(path)?);
+        bincode::serialize_into(writer, self)?;
+        Ok(())
+    }
+
+    /// Load a snapshot saved with [`save_to_file`](Self::save_to_file).
+    ///
+    /// Decoding is limited to the file's size, so a corrupt length prefix
+    /// can't allocate more memory than the file holds. The stride must be a
+    /// non-zero multiple of 16 bytes and the data must hold exactly
+    /// `num_particles` particles.
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        let file = File::open(path)?;
+        let limit = file.metadata()?.len();
+        // Same encoding as `bincode::serialize_into`, with a size limit
+        let snapshot: Self = bincode::options()
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+            .with_limit(limit)
+            .deserialize_from(BufReader::new(file))?;
+        snapshot.validate()?;
+        Ok(snapshot)
+    }
+
+    /// Check that the stride is usable and matches the data length.
+    fn validate(&self) -> Result<(), SnapshotError> {
+        if self.stride == 0 || !self.stride.is_multiple_of(STRIDE_ALIGNMENT) {
+            return Err(SnapshotError::InvalidFormat(format!(
+                "particle stride {} is not a multiple of {} bytes",
+                self.stride, STRIDE_ALIGNMENT
+            )));
+        }
+        let expected_len = (self.num_particles as usize).checked_mul(self.stride);
+        if expected_len != Some(self.data.len()) {
+            return Err(SnapshotError::InvalidFormat(format!(
+                "{} bytes of data for {} particles of {} bytes",
+                self.data.len(),
+                self.num_particles,
+                self.stride
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_round_trips_through_file() {
+        let snapshot = ParticleSnapshot::new((0..64u8).collect(), 16);
+        assert_eq!(snapshot.num_particles, 4);
+
+        let path = std::env::temp_dir().join(format!("rdpe_snapshot_{}.snap", std::process::id()));
+        snapshot.save_to_file(&path).unwrap();
+        let loaded = ParticleSnapshot::load_from_file(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(loaded, snapshot);
+    }
+
+    #[test]
+    fn test_load_rejects_inconsistent_snapshots() {
+        let path = std::env::temp_dir().join(format!("rdpe_snapshot_bad_{}.snap", std::process::id()));
+        let bad = [
+            // Count doesn't match the data
+            ParticleSnapshot { data: vec![0; 32], num_particles: 3, stride: 16 },
+            // Stride isn't a GPU struct size
+            ParticleSnapshot { data: vec![0; 30], num_particles: 3, stride: 10 },
+            ParticleSnapshot { data: Vec::new(), num_particles: 0, stride: 0 },
+        ];
+        for snapshot in bad {
+            snapshot.save_to_file(&path).unwrap();
+            let result = ParticleSnapshot::load_from_file(&path);
+            assert!(matches!(result, Err(SnapshotError::InvalidFormat(_))), "{snapshot:?}");
+        }
+
+        // A length prefix far beyond the file size fails without allocating it
+        let mut bytes = bincode::serialize(&ParticleSnapshot::new(vec![0; 16], 16)).unwrap();
+        bytes[..8].copy_from_slice(&u64::MAX.to_le_bytes());
+        std::fs::write(&path, bytes).unwrap();
+        let result = ParticleSnapshot::load_from_file(&path);
+        std::fs::remove_file(&path).ok();
+        assert!(matches!(result, Err(SnapshotError::Encoding(_))));
+    }
+
+    #[test]
+    fn test_snapshot_layout_check() {
+        let snapshot = ParticleSnapshot::new(vec![0; 32], 16);
+        assert!(snapshot.check_layout(2, 16).is_ok());
+        assert!(matches!(
+            snapshot.check_layout(3, 16),
+            Err(SnapshotError::LayoutMismatch { expected: (3, 16), found: (2, 16) })
+        ));
+    }
+}
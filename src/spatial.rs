@@ -12,6 +12,8 @@ pub struct SpatialConfig {
     pub grid_resolution: u32,
     /// Maximum neighbors to process per particle (0 = unlimited)
     pub max_neighbors: u32,
+    /// Skip neighbors whose `alive` field is 0 (default: true)
+    pub skip_dead_neighbors: bool,
 }
 
 impl Default for SpatialConfig {
@@ -20,6 +22,7 @@ impl Default for SpatialConfig {
             cell_size: 0.1,
             grid_resolution: 64, // 64^3 = 262144 cells, fits in 18-bit Morton code
             max_neighbors: 0,    // 0 = unlimited
+            skip_dead_neighbors: true,
         }
     }
 }
@@ -32,7 +35,7 @@ impl SpatialConfig {
     pub fn new(cell_size: f32, grid_resolution: u32) -> Self {
         assert!(grid_resolution.is_power_of_two(), "Grid resolution must be power of 2");
         assert!(grid_resolution <= 1024, "Grid resolution must be <= 1024 for 30-bit Morton codes");
-        Self { cell_size, grid_resolution, max_neighbors: 0 }
+        Self { cell_size, grid_resolution, max_neighbors: 0, skip_dead_neighbors: true }
     }
 
     /// Total number of cells in the grid
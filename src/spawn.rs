@@ -2,6 +2,7 @@
 //!
 //! Provides helper methods to reduce boilerplate when spawning particles.
 
+use crate::visuals::MeshData;
 use crate::Vec3;
 use rand::rngs::SmallRng;
 use rand::{Rng, SeedableRng};
@@ -35,6 +36,7 @@ use std::f32::consts::{PI, TAU};
 ///     color: Vec3::ONE,
 /// })
 /// ```
+#[derive(Clone)]
 pub struct SpawnContext {
     /// Index of the particle being spawned (0 to count-1).
     pub index: u32,
@@ -42,25 +44,121 @@ pub struct SpawnContext {
     pub count: u32,
     /// Simulation bounds (half-size of bounding cube).
     pub bounds: f32,
+    /// Head position of the simulation's first path emitter
+    /// ([`Emitter::along_path`](crate::Emitter::along_path)), or zero without one.
+    pub emitter_position: Vec3,
+    /// Travel direction of that emitter's head, or zero without one.
+    pub emitter_direction: Vec3,
     /// Internal RNG - use helper methods instead of accessing directly.
     rng: SmallRng,
+    /// Seed shared by every particle in the spawn, for noise jitter.
+    seed: u64,
+    /// Displacement added by the structured layout helpers.
+    jitter: Jitter,
+}
+
+/// Displacement added to structured layout positions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Jitter {
+    None,
+    /// Independent random offset per particle, up to `amplitude` per axis.
+    Uniform { amplitude: f32, vertical: bool },
+    /// Offset from value noise, so nearby particles move alike.
+    Noise { amplitude: f32, frequency: f32 },
 }
 
 impl SpawnContext {
     /// Create a new spawn context for a particle.
+    #[cfg(test)]
     pub(crate) fn new(index: u32, count: u32, bounds: f32) -> Self {
         // Seed RNG based on index for reproducibility within a run,
         // but different each program execution
-        let seed = index as u64 ^ (std::time::SystemTime::now()
+        Self::with_seed(index, count, bounds, Self::time_seed())
+    }
+
+    /// Seed that differs on each program execution.
+    pub(crate) fn time_seed() -> u64 {
+        std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .map(|d| d.as_nanos() as u64)
-            .unwrap_or(42));
+            .unwrap_or(42)
+    }
 
+    /// Create a spawn context whose RNG is derived from `seed` and the index,
+    /// so the same seed always spawns the same particles.
+    pub(crate) fn with_seed(index: u32, count: u32, bounds: f32, seed: u64) -> Self {
         Self {
             index,
             count,
             bounds,
-            rng: SmallRng::seed_from_u64(seed),
+            emitter_position: Vec3::ZERO,
+            emitter_direction: Vec3::ZERO,
+            rng: SmallRng::seed_from_u64(index as u64 ^ seed),
+            seed,
+            jitter: Jitter::None,
+        }
+    }
+
+    // ========== Jitter ==========
+
+    /// A copy of this context whose layout positions are displaced by up to
+    /// `amplitude` along X and Z, breaking up perfectly regular grids, lines
+    /// and circles.
+    ///
+    /// Applies to [`grid_position`](Self::grid_position),
+    /// [`grid_position_2d`](Self::grid_position_2d),
+    /// [`line_position`](Self::line_position),
+    /// [`circle_position`](Self::circle_position),
+    /// [`helix_position`](Self::helix_position) and
+    /// [`jittered`](Self::jittered). The offsets are hashed from the spawn
+    /// seed and the particle index, so a simulation seed makes them repeat.
+    ///
+    /// ```ignore
+    /// .with_spawner(|ctx| Cell {
+    ///     position: ctx.with_jitter(0.02).grid_position_2d(50, 50),
+    ///     ..Default::default()
+    /// })
+    /// ```
+    pub fn with_jitter(&self, amplitude: f32) -> SpawnContext {
+        self.with(Jitter::Uniform { amplitude, vertical: false })
+    }
+
+    /// Like [`with_jitter`](Self::with_jitter), but also displaces along Y.
+    pub fn with_jitter_3d(&self, amplitude: f32) -> SpawnContext {
+        self.with(Jitter::Uniform { amplitude, vertical: true })
+    }
+
+    /// A copy of this context whose layout positions are displaced along X
+    /// and Z by up to `amplitude` using value noise sampled at the
+    /// undisplaced position.
+    ///
+    /// Nearby particles get similar offsets, so a grid warps smoothly instead
+    /// of scattering. Higher `frequency` gives smaller warps. Every particle in
+    /// a spawn shares the same noise.
+    pub fn with_noise_jitter(&self, amplitude: f32, frequency: f32) -> SpawnContext {
+        self.with(Jitter::Noise { amplitude, frequency })
+    }
+
+    fn with(&self, jitter: Jitter) -> SpawnContext {
+        SpawnContext { jitter, ..self.clone() }
+    }
+
+    /// `position` displaced by the jitter set on this context, if any.
+    pub fn jittered(&self, position: Vec3) -> Vec3 {
+        match self.jitter {
+            Jitter::None => position,
+            Jitter::Uniform { amplitude, vertical } => {
+                let offset = |axis: u32| hash_signed(self.seed, self.index, axis) * amplitude;
+                let y = if vertical { offset(1) } else { 0.0 };
+                position + Vec3::new(offset(0), y, offset(2))
+            }
+            Jitter::Noise { amplitude, frequency } => {
+                let p = position * frequency;
+                // Offset lattices keep the X and Z offsets independent
+                let x = value_noise(p, self.seed);
+                let z = value_noise(p + Vec3::splat(17.31), self.seed);
+                position + Vec3::new(x, 0.0, z) * amplitude
+            }
         }
     }
 
@@ -178,6 +276,22 @@ impl SpawnContext {
         Vec3::new(radius * theta.cos(), 0.0, radius * theta.sin())
     }
 
+    /// Random point on a mesh surface, with the surface normal there.
+    ///
+    /// Points are spread evenly by area. Build the sampler once, outside the
+    /// spawner:
+    ///
+    /// ```ignore
+    /// let sampler = MeshSurfaceSampler::new(&MeshData::cube());
+    /// sim.with_spawner(move |ctx| {
+    ///     let (position, normal) = ctx.random_on_mesh(&sampler);
+    ///     Spark { position: position + normal * 0.01, velocity: normal * 0.1 }
+    /// })
+    /// ```
+    pub fn random_on_mesh(&mut self, sampler: &MeshSurfaceSampler) -> (Vec3, Vec3) {
+        sampler.sample(self.rng.gen(), self.rng.gen(), self.rng.gen())
+    }
+
     // ========== Direction/velocity helpers ==========
 
     /// Random unit vector (uniformly distributed on unit sphere).
@@ -259,7 +373,7 @@ impl SpawnContext {
         let fy = (y as f32 / (rows - 1).max(1) as f32) * 2.0 - 1.0;
         let fz = (z as f32 / (layers - 1).max(1) as f32) * 2.0 - 1.0;
 
-        Vec3::new(fx * self.bounds, fy * self.bounds, fz * self.bounds)
+        self.jittered(Vec3::new(fx * self.bounds, fy * self.bounds, fz * self.bounds))
     }
 
     /// Position on a 2D grid in the XZ plane (y=0).
@@ -271,14 +385,14 @@ impl SpawnContext {
         let fx = (x as f32 / (cols - 1).max(1) as f32) * 2.0 - 1.0;
         let fz = (z as f32 / (rows - 1).max(1) as f32) * 2.0 - 1.0;
 
-        Vec3::new(fx * self.bounds, 0.0, fz * self.bounds)
+        self.jittered(Vec3::new(fx * self.bounds, 0.0, fz * self.bounds))
     }
 
     /// Position along a line from `start` to `end`.
     ///
     /// Particles are distributed evenly along the line.
     pub fn line_position(&self, start: Vec3, end: Vec3) -> Vec3 {
-        start + (end - start) * self.progress()
+        self.jittered(start + (end - start) * self.progress())
     }
 
     /// Position on a circle in the XZ plane.
@@ -286,7 +400,7 @@ impl SpawnContext {
     /// Particles are distributed evenly around the circle.
     pub fn circle_position(&self, radius: f32) -> Vec3 {
         let angle = self.progress() * TAU;
-        Vec3::new(radius * angle.cos(), 0.0, radius * angle.sin())
+        self.jittered(Vec3::new(radius * angle.cos(), 0.0, radius * angle.sin()))
     }
 
     /// Position on a helix/spiral.
@@ -297,14 +411,182 @@ impl SpawnContext {
     pub fn helix_position(&self, radius: f32, height: f32, turns: f32) -> Vec3 {
         let t = self.progress();
         let angle = t * TAU * turns;
-        Vec3::new(
+        self.jittered(Vec3::new(
             radius * angle.cos(),
             (t - 0.5) * height,
             radius * angle.sin(),
-        )
+        ))
+    }
+}
+
+/// Area-weighted triangle picker for spreading points evenly over a mesh.
+///
+/// Stores each triangle's corners and face normal with a running total of
+/// triangle areas, so [`sample`](Self::sample) finds a triangle with a
+/// binary search instead of scanning the mesh.
+#[derive(Debug, Clone)]
+pub struct MeshSurfaceSampler {
+    triangles: Vec<[Vec3; 3]>,
+    normals: Vec<Vec3>,
+    cumulative_area: Vec<f32>,
+}
+
+impl MeshSurfaceSampler {
+    /// Build a sampler for `mesh`. Degenerate triangles are skipped.
+    pub fn new(mesh: &MeshData) -> Self {
+        let mut sampler = Self {
+            triangles: Vec::new(),
+            normals: Vec::new(),
+            cumulative_area: Vec::new(),
+        };
+        let mut total = 0.0;
+        for tri in mesh.indices.chunks_exact(3) {
+            let corners = [tri[0], tri[1], tri[2]].map(|i| Vec3::from(mesh.vertices[i as usize]));
+            let cross = (corners[1] - corners[0]).cross(corners[2] - corners[0]);
+            let area = cross.length() * 0.5;
+            if area <= f32::EPSILON {
+                continue;
+            }
+            total += area;
+            sampler.triangles.push(corners);
+            sampler.normals.push(cross.normalize());
+            sampler.cumulative_area.push(total);
+        }
+        sampler
+    }
+
+    /// Total surface area of the mesh.
+    pub fn total_area(&self) -> f32 {
+        self.cumulative_area.last().copied().unwrap_or(0.0)
+    }
+
+    /// Whether the mesh has no triangles with area.
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// Point and face normal for three uniform random numbers in `[0, 1)`.
+    ///
+    /// `r0` picks the triangle, weighted by area; `r1` and `r2` pick a
+    /// uniformly distributed barycentric point inside it. An empty sampler
+    /// returns the origin with a +Y normal.
+    pub fn sample(&self, r0: f32, r1: f32, r2: f32) -> (Vec3, Vec3) {
+        if self.is_empty() {
+            return (Vec3::ZERO, Vec3::Y);
+        }
+        let target = r0 * self.total_area();
+        let index = self
+            .cumulative_area
+            .partition_point(|&area| area <= target)
+            .min(self.triangles.len() - 1);
+
+        // Square root keeps the points from bunching at the first corner
+        let s = r1.sqrt();
+        let (u, v) = (1.0 - s, r2 * s);
+        let [a, b, c] = self.triangles[index];
+        (a * u + b * (s - v) + c * v, self.normals[index])
     }
 }
 
+/// Well-spaced points on the XZ plane (y = 0) by Bridson's Poisson disc sampling.
+///
+/// Points lie within `half_size` of the origin on both axes and no two are
+/// closer than `min_distance`. Sampling grows outward from the origin, trying
+/// up to `max_attempts` candidates around each point before retiring it, and
+/// stops once `max_points` points exist. Fewer points come back when the area
+/// is full. The same `seed` always gives the same points.
+///
+/// ```ignore
+/// let points = poisson_disc_xz(1.0, 0.05, 30, 1000, 42);
+/// sim.with_particle_count(points.len() as u32)
+///     .with_spawner(move |ctx| Cell { position: points[ctx.index as usize], ..Default::default() })
+/// ```
+pub fn poisson_disc_xz(half_size: f32, min_distance: f32, max_attempts: u32, max_points: usize, seed: u64) -> Vec<Vec3> {
+    if max_points == 0 || half_size < 0.0 || min_distance <= 0.0 {
+        return Vec::new();
+    }
+
+    // A cell this size holds at most one point
+    let cell_size = min_distance / std::f32::consts::SQRT_2;
+    let cell_of = |p: Vec3| ((p.x / cell_size).floor() as i32, (p.z / cell_size).floor() as i32);
+    let mut grid: std::collections::HashMap<(i32, i32), usize> = std::collections::HashMap::new();
+    let mut rng = SmallRng::seed_from_u64(seed);
+
+    let mut points = vec![Vec3::ZERO];
+    let mut active = vec![0];
+    grid.insert(cell_of(Vec3::ZERO), 0);
+
+    while !active.is_empty() && points.len() < max_points {
+        let slot = rng.gen_range(0..active.len());
+        let center = points[active[slot]];
+
+        let mut placed = false;
+        for _ in 0..max_attempts {
+            let angle = rng.gen_range(0.0..TAU);
+            let radius = rng.gen_range(min_distance..2.0 * min_distance);
+            let candidate = center + Vec3::new(angle.cos(), 0.0, angle.sin()) * radius;
+            if candidate.x.abs() > half_size || candidate.z.abs() > half_size {
+                continue;
+            }
+
+            let (cx, cz) = cell_of(candidate);
+            let too_close = (cx - 2..=cx + 2)
+                .flat_map(|x| (cz - 2..=cz + 2).map(move |z| (x, z)))
+                .filter_map(|cell| grid.get(&cell))
+                .any(|&i| points[i].distance_squared(candidate) < min_distance * min_distance);
+            if !too_close {
+                grid.insert((cx, cz), points.len());
+                active.push(points.len());
+                points.push(candidate);
+                placed = true;
+                break;
+            }
+        }
+
+        if !placed {
+            active.swap_remove(slot);
+        }
+    }
+
+    points
+}
+
+/// Random value in `[-1, 1]` for one axis of a particle's jitter.
+fn hash_signed(seed: u64, index: u32, axis: u32) -> f32 {
+    hash3_signed(seed, index, axis, 0x6a09_e667)
+}
+
+/// Random value in `[-1, 1]` from a SplitMix64 finalizer over three packed values.
+fn hash3_signed(seed: u64, a: u32, b: u32, c: u32) -> f32 {
+    let mut h = seed
+        ^ (a as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (b as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ (c as u64).wrapping_mul(0x1656_67B1_9E37_79F9);
+    h = (h ^ (h >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    h ^= h >> 31;
+    (h >> 40) as f32 / (1u64 << 24) as f32 * 2.0 - 1.0
+}
+
+/// Smooth value noise in `[-1, 1]`, blending random values at integer lattice points.
+fn value_noise(p: Vec3, seed: u64) -> f32 {
+    let lattice = |x: i32, y: i32, z: i32| hash3_signed(seed, x as u32, y as u32, z as u32);
+    let cell = p.floor();
+    let f = p - cell;
+    let s = f * f * (Vec3::splat(3.0) - 2.0 * f);
+    let (x, y, z) = (cell.x as i32, cell.y as i32, cell.z as i32);
+
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+    let plane = |z: i32| {
+        lerp(
+            lerp(lattice(x, y, z), lattice(x + 1, y, z), s.x),
+            lerp(lattice(x, y + 1, z), lattice(x + 1, y + 1, z), s.x),
+            s.y,
+        )
+    };
+    lerp(plane(z), plane(z + 1), s.z)
+}
+
 /// Convert HSV to RGB.
 fn hsv_to_rgb(h: f32, s: f32, v: f32) -> Vec3 {
     let c = v * s;
@@ -342,6 +624,13 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_seeded_contexts_repeat() {
+        let mut a = SpawnContext::with_seed(3, 10, 1.0, 42);
+        let mut b = SpawnContext::with_seed(3, 10, 1.0, 42);
+        assert_eq!(a.random_in_sphere(1.0), b.random_in_sphere(1.0));
+    }
+
     #[test]
     fn test_grid_position() {
         let ctx = SpawnContext::new(0, 27, 1.0);
@@ -351,6 +640,92 @@ mod tests {
         assert!((pos.z - (-1.0)).abs() < 0.001);
     }
 
+    #[test]
+    fn test_mesh_sampler_spreads_by_area() {
+        let cube = MeshData::cube();
+        let sampler = MeshSurfaceSampler::new(&cube);
+        assert!((sampler.total_area() - 6.0).abs() < 1e-4);
+
+        let mut ctx = SpawnContext::with_seed(0, 1, 1.0, 7);
+        let mut top = 0;
+        for _ in 0..6000 {
+            let (pos, normal) = ctx.random_on_mesh(&sampler);
+            // Every point lies on the face its normal points out of
+            assert!((pos.dot(normal) - 0.5).abs() < 1e-4);
+            assert!(pos.abs().max_element() <= 0.5 + 1e-4);
+            if normal == Vec3::Y {
+                top += 1;
+            }
+        }
+        // Each face holds a sixth of the area
+        assert!((800..1200).contains(&top), "top face got {} of 6000", top);
+    }
+
+    #[test]
+    fn test_empty_mesh_sampler() {
+        let sampler = MeshSurfaceSampler::new(&MeshData::default());
+        assert!(sampler.is_empty());
+        assert_eq!(sampler.sample(0.5, 0.5, 0.5), (Vec3::ZERO, Vec3::Y));
+    }
+
+    #[test]
+    fn test_poisson_disc_spacing() {
+        let points = poisson_disc_xz(1.0, 0.1, 30, usize::MAX, 3);
+        // Roughly 0.7 / d² points fit in a filled square of area 4
+        assert!(points.len() > 150, "only {} points", points.len());
+        for (i, a) in points.iter().enumerate() {
+            assert_eq!(a.y, 0.0);
+            assert!(a.x.abs() <= 1.0 && a.z.abs() <= 1.0);
+            for b in &points[i + 1..] {
+                assert!(a.distance(*b) >= 0.1 - 1e-5);
+            }
+        }
+        assert_eq!(points, poisson_disc_xz(1.0, 0.1, 30, usize::MAX, 3));
+    }
+
+    #[test]
+    fn test_poisson_disc_stops_at_max_points() {
+        assert_eq!(poisson_disc_xz(1.0, 0.05, 30, 100, 1).len(), 100);
+        assert!(poisson_disc_xz(1.0, 0.0, 30, 100, 1).is_empty());
+    }
+
+    #[test]
+    fn test_uniform_jitter() {
+        let ctx = SpawnContext::with_seed(0, 27, 1.0, 5);
+        assert_eq!(ctx.grid_position(3, 3, 3), Vec3::splat(-1.0));
+        let pos = ctx.with_jitter(0.1).grid_position(3, 3, 3);
+        assert_ne!(pos, Vec3::splat(-1.0));
+        assert_eq!(pos.y, -1.0);
+        assert!((pos - Vec3::splat(-1.0)).abs().max_element() <= 0.1);
+
+        let again = SpawnContext::with_seed(0, 27, 1.0, 5);
+        assert_eq!(again.with_jitter(0.1).grid_position(3, 3, 3), pos);
+
+        // Other particles get their own offsets
+        let next = SpawnContext::with_seed(1, 27, 1.0, 5);
+        let offset = next.with_jitter(0.1).grid_position(3, 3, 3) - next.grid_position(3, 3, 3);
+        assert_ne!(offset, pos - Vec3::splat(-1.0));
+
+        let pos = ctx.with_jitter_3d(0.1).circle_position(0.5);
+        assert_ne!(pos.y, 0.0);
+    }
+
+    #[test]
+    fn test_noise_jitter_is_spatially_correlated() {
+        let offset = |index: u32, position: Vec3| {
+            let ctx = SpawnContext::with_seed(index, 3, 1.0, 9);
+            ctx.with_noise_jitter(0.1, 2.0).jittered(position) - position
+        };
+        let a = offset(0, Vec3::new(0.3, 0.0, 0.3));
+        let near = offset(1, Vec3::new(0.301, 0.0, 0.3));
+        let far = offset(2, Vec3::new(3.7, 0.0, -2.2));
+
+        assert_eq!(a.y, 0.0);
+        assert!(a.abs().max_element() <= 0.1);
+        assert!((a - near).length() < 0.001);
+        assert!((a - far).length() > 0.001);
+    }
+
     #[test]
     fn test_hsv_to_rgb() {
         // Red
@@ -65,6 +65,32 @@ pub enum SpawnTrigger {
     /// SpawnTrigger::OnCondition("fract(p.age) < uniforms.delta_time".into())
     /// ```
     OnCondition(String),
+
+    /// Spawn once when the parent reaches a fraction of its lifetime.
+    ///
+    /// `fraction` is in `[0.0, 1.0]` relative to the simulation's maximum
+    /// age, taken from [`Rule::Lifetime`](crate::Rule::Lifetime) or
+    /// [`Rule::MaxAge`](crate::Rule::MaxAge). The trigger fires the first
+    /// time the particle's age reaches the threshold and sets the
+    /// particle's `triggered` field, so each particle triggers at most once
+    /// per life. `fraction` must be greater than zero; use
+    /// [`SpawnTrigger::OnDeath`] to spawn at death.
+    ///
+    /// Running a simulation with this trigger but no maximum age fails with
+    /// [`SimulationError::NoMaxAge`](crate::SimulationError::NoMaxAge).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Larvae turn into adults half way through their life
+    /// SubEmitter::new(Bug::Larva.into(), Bug::Adult.into())
+    ///     .trigger(SpawnTrigger::AgeThreshold { fraction: 0.5 })
+    ///     .count(1)
+    /// ```
+    AgeThreshold {
+        /// Fraction of the maximum age at which to spawn.
+        fraction: f32,
+    },
 }
 
 impl Default for SpawnTrigger {
@@ -182,6 +208,23 @@ impl SubEmitter {
         self
     }
 
+    /// Trigger once when the parent reaches `fraction` of its maximum age.
+    ///
+    /// See [`SpawnTrigger::AgeThreshold`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// SubEmitter::new(Larva, Adult).on_age(0.5).count(1)
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fraction` is not greater than zero.
+    pub fn on_age(self, fraction: f32) -> Self {
+        self.trigger(SpawnTrigger::AgeThreshold { fraction: fraction.min(1.0) })
+    }
+
     /// Set the trigger type explicitly.
     ///
     /// # Example
@@ -190,7 +233,18 @@ impl SubEmitter {
     /// SubEmitter::new(Parent, Child)
     ///     .trigger(SpawnTrigger::OnDeath)
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if an [`SpawnTrigger::AgeThreshold`] fraction is not greater
+    /// than zero.
     pub fn trigger(mut self, trigger: SpawnTrigger) -> Self {
+        if let SpawnTrigger::AgeThreshold { fraction } = trigger {
+            assert!(
+                fraction > 0.0,
+                "SpawnTrigger::AgeThreshold fraction must be greater than zero, got {fraction}; use SpawnTrigger::OnDeath to spawn at death"
+            );
+        }
         self.trigger = trigger;
         self
     }
@@ -321,7 +375,7 @@ impl SubEmitter {
         format!(
             r#"
     // Sub-emitter {emitter_index}: Spawn children for parent type {parent_type}
-    if death.parent_type == {parent_type}u {{
+    if death.emitter == {emitter_index}u {{
         let num_children = {count}u;
         let speed_min = {speed_min:.6};
         let speed_max = {speed_max:.6};
@@ -409,7 +463,7 @@ struct DeathEvent {
     position: vec3<f32>,
     parent_type: u32,
     velocity: vec3<f32>,
-    _pad0: u32,
+    emitter: u32,
     color: vec3<f32>,
     _pad1: u32,
 };
@@ -430,13 +484,14 @@ var<storage, read_write> next_child_slot: atomic<u32>;
 /// WGSL helper function to record a death event.
 pub const RECORD_DEATH_WGSL: &str = r#"
 // Record a particle death for sub-emitter processing
-fn record_death(pos: vec3<f32>, vel: vec3<f32>, col: vec3<f32>, ptype: u32) {
+fn record_death(pos: vec3<f32>, vel: vec3<f32>, col: vec3<f32>, ptype: u32, emitter: u32) {
     let idx = atomicAdd(&death_count, 1u);
     if idx < arrayLength(&death_buffer) {
         death_buffer[idx].position = pos;
         death_buffer[idx].velocity = vel;
         death_buffer[idx].color = col;
         death_buffer[idx].parent_type = ptype;
+        death_buffer[idx].emitter = emitter;
     }
 }
 "#;
@@ -473,4 +528,10 @@ mod tests {
         let se = SubEmitter::new(0, 1).inherit_velocity(-0.5);
         assert_eq!(se.inherit_velocity, 0.0);
     }
+
+    #[test]
+    #[should_panic(expected = "must be greater than zero")]
+    fn test_age_threshold_rejects_zero_fraction() {
+        let _ = SubEmitter::new(0, 1).on_age(0.0);
+    }
 }
@@ -357,6 +357,11 @@ impl TextureRegistry {
         self.textures.push((name.into(), config.into()));
     }
 
+    /// Get a texture's configuration by name.
+    pub fn get(&self, name: &str) -> Option<&TextureConfig> {
+        self.textures.iter().find(|(n, _)| n == name).map(|(_, config)| config)
+    }
+
     /// Get the number of textures.
     pub fn len(&self) -> usize {
         self.textures.len()
@@ -51,8 +51,13 @@ pub struct Time {
     pause_elapsed: Duration,
     /// Fixed delta time for deterministic updates (optional).
     fixed_delta: Option<f32>,
+    /// Whether elapsed time accumulates fixed steps instead of following
+    /// the wall clock.
+    fixed_step: bool,
     /// Time scale multiplier (1.0 = normal speed).
     time_scale: f32,
+    /// Simulated seconds added on top of the clock, e.g. by warmup frames.
+    offset_secs: f32,
 }
 
 impl Time {
@@ -72,7 +77,9 @@ impl Time {
             paused: false,
             pause_elapsed: Duration::ZERO,
             fixed_delta: None,
+            fixed_step: false,
             time_scale: 1.0,
+            offset_secs: 0.0,
         }
     }
 
@@ -92,9 +99,13 @@ impl Time {
         self.delta_secs = self.fixed_delta.unwrap_or(raw_delta) * self.time_scale;
         self.last_frame = now;
 
-        // Calculate elapsed time
-        let raw_elapsed = now.duration_since(self.start) - self.pause_elapsed;
-        self.elapsed_secs = raw_elapsed.as_secs_f32() * self.time_scale;
+        // Calculate elapsed time (accumulated steps in fixed-step mode)
+        if self.fixed_step {
+            self.elapsed_secs += self.delta_secs;
+        } else {
+            let raw_elapsed = now.duration_since(self.start) - self.pause_elapsed;
+            self.elapsed_secs = raw_elapsed.as_secs_f32() * self.time_scale + self.offset_secs;
+        }
 
         // Update frame count
         self.frame_count += 1;
@@ -178,9 +189,23 @@ impl Time {
     /// Set a fixed delta time for deterministic updates.
     ///
     /// Useful for physics simulations that need consistent timesteps.
+    /// Elapsed time still follows the wall clock; use
+    /// [`set_fixed_step`](Self::set_fixed_step) to fix both.
     /// Pass `None` to use real frame timing.
     pub fn set_fixed_delta(&mut self, delta: Option<f32>) {
         self.fixed_delta = delta;
+        self.fixed_step = false;
+    }
+
+    /// Advance by exactly `step` seconds per frame, for delta and elapsed time.
+    ///
+    /// Unlike [`set_fixed_delta`](Self::set_fixed_delta), elapsed time is the
+    /// sum of the steps taken rather than wall-clock time, so a run replays
+    /// identically however fast frames are drawn. Pass `None` to use real
+    /// frame timing.
+    pub fn set_fixed_step(&mut self, step: Option<f32>) {
+        self.fixed_delta = step;
+        self.fixed_step = step.is_some();
     }
 
     /// Set time scale multiplier.
@@ -192,6 +217,15 @@ impl Time {
         self.time_scale = scale.max(0.0);
     }
 
+    /// Move elapsed time forward by `secs` without a frame passing.
+    ///
+    /// Used after simulating frames off-screen, such as warmup frames, so
+    /// the time given to shaders carries on from where they stopped.
+    pub fn skip_ahead(&mut self, secs: f32) {
+        self.offset_secs += secs;
+        self.elapsed_secs += secs;
+    }
+
     /// Reset the timer to its initial state.
     pub fn reset(&mut self) {
         let now = Instant::now();
@@ -205,6 +239,7 @@ impl Time {
         self.fps_update_time = now;
         self.paused = false;
         self.pause_elapsed = Duration::ZERO;
+        self.offset_secs = 0.0;
     }
 
     /// Get the raw start instant.
@@ -284,6 +319,23 @@ mod tests {
         assert_eq!(time.time_scale(), 0.0);
     }
 
+    #[test]
+    fn test_skip_ahead_continues_elapsed_time() {
+        let mut wall_clock = Time::new();
+        wall_clock.skip_ahead(5.0);
+        assert_eq!(wall_clock.elapsed(), 5.0);
+        let (elapsed, _) = wall_clock.update();
+        assert!(elapsed >= 5.0);
+
+        let mut fixed = Time::new();
+        fixed.set_fixed_step(Some(0.5));
+        fixed.skip_ahead(2.0);
+        assert_eq!(fixed.update().0, 2.5);
+
+        fixed.reset();
+        assert_eq!(fixed.update().0, 0.5);
+    }
+
     #[test]
     fn test_fixed_delta() {
         let mut time = Time::new();
@@ -295,5 +347,28 @@ mod tests {
         // Should use fixed delta regardless of actual time
         let expected = 1.0 / 60.0;
         assert!((time.delta() - expected).abs() < 0.0001);
+
+        // Elapsed time still follows the wall clock
+        assert!(time.elapsed() >= 0.1);
+    }
+
+    #[test]
+    fn test_fixed_step() {
+        let mut time = Time::new();
+        time.set_fixed_step(Some(1.0 / 60.0));
+
+        thread::sleep(Duration::from_millis(100));
+        time.update();
+        time.update();
+
+        // Elapsed time counts fixed steps, not wall-clock time
+        let expected = 1.0 / 60.0;
+        assert!((time.delta() - expected).abs() < 0.0001);
+        assert!((time.elapsed() - 2.0 * expected).abs() < 0.0001);
+
+        // Switching back to a fixed delta restores wall-clock elapsed time
+        time.set_fixed_delta(Some(expected));
+        time.update();
+        assert!(time.elapsed() >= 0.1);
     }
 }
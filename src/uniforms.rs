@@ -17,14 +17,15 @@
 //!         }
 //!         ctx.set("strength", (ctx.time() * 2.0).sin() * 0.5 + 1.0);
 //!     })
-//!     .with_rule(Rule::Custom(r#"
+//!     .with_rule(Rule::custom(r#"
 //!         let dir = uniforms.attractor - p.position;
 //!         p.velocity += normalize(dir) * uniforms.strength * uniforms.delta_time;
-//!     "#.into()))
+//!     "#))
 //!     .run();
 //! ```
 
 use crate::input::{Input, KeyCode, MouseButton};
+use crate::snapshot::ParticleSnapshot;
 use glam::{Vec2, Vec3, Vec4};
 use std::collections::HashMap;
 
@@ -269,10 +270,16 @@ pub struct UpdateContext<'a> {
     pub(crate) aspect_ratio: f32,
     /// Grid opacity to set (None = no change).
     pub(crate) grid_opacity: &'a mut Option<f32>,
+    /// Background color to set (None = no change).
+    pub(crate) background_color: &'a mut Option<Vec3>,
     /// Whether to perform readback after this frame.
     pub(crate) readback_requested: &'a mut bool,
     /// Previous frame's readback data (if any).
     pub(crate) readback_data: Option<&'a [u8]>,
+    /// Bytes per particle in the GPU buffer.
+    pub(crate) particle_stride: usize,
+    /// Snapshot to write into the particle buffer before the next frame.
+    pub(crate) pending_restore: &'a mut Option<ParticleSnapshot>,
 }
 
 impl<'a> UpdateContext<'a> {
@@ -285,8 +292,11 @@ impl<'a> UpdateContext<'a> {
         bounds: f32,
         aspect_ratio: f32,
         grid_opacity: &'a mut Option<f32>,
+        background_color: &'a mut Option<Vec3>,
         readback_requested: &'a mut bool,
         readback_data: Option<&'a [u8]>,
+        particle_stride: usize,
+        pending_restore: &'a mut Option<ParticleSnapshot>,
     ) -> Self {
         Self {
             uniforms,
@@ -296,8 +306,11 @@ impl<'a> UpdateContext<'a> {
             bounds,
             aspect_ratio,
             grid_opacity,
+            background_color,
             readback_requested,
             readback_data,
+            particle_stride,
+            pending_restore,
         }
     }
 
@@ -403,6 +416,14 @@ impl<'a> UpdateContext<'a> {
         *self.grid_opacity = Some(opacity.clamp(0.0, 1.0));
     }
 
+    /// Set the background clear color.
+    ///
+    /// This is applied without rebuilding any pipelines.
+    /// The change takes effect on the next frame.
+    pub fn set_background(&mut self, color: Vec3) {
+        *self.background_color = Some(color);
+    }
+
     // ========== CPU Readback methods ==========
 
     /// Request particle data to be read back from GPU after this frame.
@@ -450,6 +471,23 @@ impl<'a> UpdateContext<'a> {
         self.readback_data
     }
 
+    /// Copy the previous readback into a [`ParticleSnapshot`].
+    ///
+    /// Returns `None` if no readback was requested on the previous frame.
+    /// Call [`request_readback`](Self::request_readback) first.
+    pub fn snapshot(&self) -> Option<ParticleSnapshot> {
+        self.readback_data
+            .map(|bytes| ParticleSnapshot::new(bytes.to_vec(), self.particle_stride))
+    }
+
+    /// Replace the particle state with a snapshot before the next frame.
+    ///
+    /// Snapshots whose particle count or layout doesn't match the running
+    /// simulation are reported on stderr and ignored.
+    pub fn restore(&mut self, snapshot: ParticleSnapshot) {
+        *self.pending_restore = Some(snapshot);
+    }
+
     /// Process particle data and return a result, handling borrow scope automatically.
     ///
     /// This is the recommended way to read particle data when you also need to
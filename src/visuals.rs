@@ -16,7 +16,10 @@
 //!     .run();
 //! ```
 
-use glam::Vec3;
+use glam::{Mat4, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::error::ObjError;
 
 /// Pre-defined color palettes for particle rendering.
 ///
@@ -232,10 +235,317 @@ pub enum BlendMode {
     Multiply,
 }
 
+/// Tone-mapping curve used to map HDR colors to the display.
+///
+/// Only applies when HDR rendering is enabled with [`VisualConfig::hdr`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToneMapMode {
+    /// `c / (1 + c)`. Never clips, but desaturates bright colors.
+    Reinhard,
+    /// Fast ACES curve fit. Punchy contrast, cheap.
+    AcesFast,
+    /// Fitted ACES RRT + ODT (default). Filmic look with better hue handling.
+    #[default]
+    AcesFilmic,
+    /// No curve; values above 1.0 are clipped.
+    Linear,
+}
+
+impl ToneMapMode {
+    /// WGSL defining `tone_map(color: vec3<f32>) -> vec3<f32>` for this mode.
+    pub fn to_wgsl(&self) -> &'static str {
+        use crate::shader_utils::*;
+        match self {
+            ToneMapMode::Reinhard => TONE_MAP_REINHARD_WGSL,
+            ToneMapMode::AcesFast => TONE_MAP_ACES_FAST_WGSL,
+            ToneMapMode::AcesFilmic => TONE_MAP_ACES_FILMIC_WGSL,
+            ToneMapMode::Linear => TONE_MAP_LINEAR_WGSL,
+        }
+    }
+}
+
+/// Directional light that makes particles cast shadows on each other.
+///
+/// Particles are rendered from the light's point of view into a depth
+/// texture before the main pass. Each particle then checks that shadow map
+/// and is darkened where other particles block the light. The light covers
+/// the simulation bounds with an orthographic projection.
+///
+/// Shadows apply to billboard particles; trails, connections, wireframes,
+/// and meshes are not shadowed.
+///
+/// # Example
+///
+/// ```ignore
+/// .with_visuals(|v| {
+///     v.light(LightConfig {
+///         direction: Vec3::new(-0.3, -1.0, -0.2),
+///         ..Default::default()
+///     });
+/// })
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightConfig {
+    /// Direction the light travels (from the light toward the scene).
+    pub direction: Vec3,
+    /// Light color multiplied into lit particles (RGB, 0.0-1.0).
+    pub color: Vec3,
+    /// Width and height of the shadow map in texels.
+    pub shadow_map_size: u32,
+    /// Depth offset that prevents particles from shadowing themselves.
+    pub shadow_bias: f32,
+}
+
+impl Default for LightConfig {
+    fn default() -> Self {
+        Self {
+            direction: Vec3::new(-0.4, -1.0, -0.3),
+            color: Vec3::ONE,
+            shadow_map_size: 2048,
+            shadow_bias: 0.005,
+        }
+    }
+}
+
+impl LightConfig {
+    /// Orthographic light-space transform covering a cube of half-size `bounds`.
+    pub fn view_proj(&self, bounds: f32) -> Mat4 {
+        let dir = self.direction.try_normalize().unwrap_or(Vec3::NEG_Y);
+        // Radius of the sphere enclosing the bounding cube
+        let radius = bounds.max(0.001) * 3f32.sqrt();
+        let up = if dir.y.abs() > 0.99 { Vec3::Z } else { Vec3::Y };
+        let view = Mat4::look_at_rh(-dir * radius * 2.0, Vec3::ZERO, up);
+        let proj = Mat4::orthographic_rh(-radius, radius, -radius, radius, 0.0, radius * 4.0);
+        proj * view
+    }
+}
+
+/// Color, opacity, and width of trails along their length.
+///
+/// Gradient stops are `(rgba, t)` pairs, where `t` is the normalized
+/// position along the trail: 0.0 at the particle, 1.0 at the oldest point.
+/// Colors between stops are interpolated linearly. With an empty gradient
+/// trails keep the particle's color, fading toward
+/// [`VisualConfig::trail_tail_color`] when one is set.
+///
+/// Width shrinks linearly from `width_head_scale` times the particle size at
+/// the newest point, losing `width_taper` of it by the oldest point.
+///
+/// # Example
+///
+/// ```ignore
+/// .with_visuals(|v| {
+///     v.trails(30);
+///     v.trail_config(TrailConfig {
+///         color_gradient: vec![
+///             ([1.0, 0.9, 0.4, 1.0], 0.0), // hot yellow at the head
+///             ([1.0, 0.3, 0.0, 0.8], 0.4),
+///         ],
+///         fade_to_transparent: true, // smoke out to nothing
+///         width_taper: 1.0,          // narrow to a point
+///         ..Default::default()
+///     });
+/// })
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrailConfig {
+    /// Gradient stops as (RGBA color, normalized trail position 0.0-1.0).
+    pub color_gradient: Vec<([f32; 4], f32)>,
+    /// End the gradient fully transparent at 1.0 in the last stop's color.
+    ///
+    /// Appends a transparent stop, or makes the last stop transparent when
+    /// it already sits at 1.0. Has no effect without gradient stops, since
+    /// trails then fade out with age anyway.
+    pub fade_to_transparent: bool,
+    /// Width lost by the oldest point (0.0 = constant, 1.0 = tapers to zero).
+    pub width_taper: f32,
+    /// Width at the newest point, relative to the particle size.
+    pub width_head_scale: f32,
+}
+
+impl Default for TrailConfig {
+    fn default() -> Self {
+        Self {
+            color_gradient: Vec::new(),
+            fade_to_transparent: false,
+            width_taper: 0.7,
+            width_head_scale: 0.5,
+        }
+    }
+}
+
+impl TrailConfig {
+    /// Gradient stops sorted by position, including the transparent tail stop.
+    pub fn stops(&self) -> Vec<([f32; 4], f32)> {
+        let mut stops: Vec<([f32; 4], f32)> = self
+            .color_gradient
+            .iter()
+            .map(|&(color, t)| (color, t.clamp(0.0, 1.0)))
+            .collect();
+        stops.sort_by(|a, b| a.1.total_cmp(&b.1));
+        if self.fade_to_transparent {
+            match stops.last_mut() {
+                // A second stop at 1.0 would never be reached
+                Some((color, t)) if *t >= 1.0 => color[3] = 0.0,
+                Some(&mut ([r, g, b, _], _)) => stops.push(([r, g, b, 0.0], 1.0)),
+                None => {}
+            }
+        }
+        stops
+    }
+
+    /// WGSL `trail_gradient(t)` function, or `None` without gradient stops.
+    pub(crate) fn to_wgsl(&self) -> Option<String> {
+        let stops = self.stops();
+        let (first, rest) = stops.split_first()?;
+        let color = |[r, g, b, a]: [f32; 4]| format!("vec4<f32>({r:.6}, {g:.6}, {b:.6}, {a:.6})");
+
+        let mut body = format!("    if t <= {:.6} {{ return {}; }}\n", first.1, color(first.0));
+        let mut prev = first;
+        for stop in rest {
+            body.push_str(&format!(
+                "    if t <= {t1:.6} {{ return mix({c0}, {c1}, (t - {t0:.6}) / max({t1:.6} - {t0:.6}, 0.000001)); }}\n",
+                t0 = prev.1,
+                t1 = stop.1,
+                c0 = color(prev.0),
+                c1 = color(stop.0),
+            ));
+            prev = stop;
+        }
+        body.push_str(&format!("    return {};\n", color(prev.0)));
+
+        Some(format!("fn trail_gradient(t: f32) -> vec4<f32> {{\n{body}}}\n"))
+    }
+}
+
+/// What a sprite sheet does after its last frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoopMode {
+    /// Start again from the first frame (default).
+    #[default]
+    Loop,
+    /// Hold the last frame.
+    Clamp,
+}
+
+/// Flipbook animation played on each particle over its lifetime.
+///
+/// Samples the texture added with [`crate::Simulation::with_texture`], which
+/// must be the only one; running with several textures is an error. The texture must be pre-composed as a grid of equal-size frames, read
+/// left to right, top to bottom. Each particle picks its frame from its own
+/// `age`, so particles spawned at different times animate independently.
+///
+/// The sprite replaces the particle shape; custom fragment shaders can sample
+/// the frame themselves using `in.sprite_uv`.
+///
+/// # Example
+///
+/// ```ignore
+/// Simulation::<Spark>::new()
+///     .with_texture("flame", "assets/flame_4x4.png")
+///     .with_visuals(|v| {
+///         v.sprite_sheet(SpriteSheetConfig {
+///             columns: 4,
+///             rows: 4,
+///             fps: 24.0,
+///             loop_mode: LoopMode::Clamp,
+///         });
+///     })
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpriteSheetConfig {
+    /// Number of frames per row.
+    pub columns: u32,
+    /// Number of rows of frames.
+    pub rows: u32,
+    /// Frames advanced per second of particle age.
+    pub fps: f32,
+    /// Behavior after the last frame.
+    pub loop_mode: LoopMode,
+}
+
+impl Default for SpriteSheetConfig {
+    fn default() -> Self {
+        Self {
+            columns: 4,
+            rows: 4,
+            fps: 12.0,
+            loop_mode: LoopMode::Loop,
+        }
+    }
+}
+
+impl SpriteSheetConfig {
+    /// WGSL statements computing `out.sprite_uv` from `age` and `quad_pos`.
+    pub(crate) fn to_wgsl_vertex(self) -> String {
+        let columns = self.columns.max(1);
+        let rows = self.rows.max(1);
+        let wrap = match self.loop_mode {
+            LoopMode::Loop => "sprite_frame % sprite_frames",
+            LoopMode::Clamp => "min(sprite_frame, sprite_frames - 1u)",
+        };
+        format!(
+            r#"    // Sprite sheet frame from particle age
+    let sprite_grid = vec2<f32>({columns}.0, {rows}.0);
+    let sprite_frames = {columns}u * {rows}u;
+    let sprite_frame = u32(max(age, 0.0) * {fps});
+    let sprite_index = {wrap};
+    let sprite_cell = vec2<f32>(f32(sprite_index % {columns}u), f32(sprite_index / {columns}u));
+    // Quad y points up, texture v points down
+    let sprite_local = vec2<f32>(quad_pos.x * 0.5 + 0.5, 0.5 - quad_pos.y * 0.5);
+    out.sprite_uv = (sprite_cell + sprite_local) / sprite_grid;
+"#,
+            fps = self.fps,
+        )
+    }
+
+    /// Default fragment body sampling texture `tex_name` at the current frame.
+    pub(crate) fn to_wgsl_fragment(texture_name: &str) -> String {
+        format!(
+            r#"    let sprite = textureSample(tex_{texture_name}, tex_{texture_name}_sampler, in.sprite_uv);
+    if sprite.a < 0.01 {{
+        discard;
+    }}
+    return vec4<f32>(in.color * sprite.rgb, sprite.a);"#
+        )
+    }
+}
+
+/// Multisample anti-aliasing level for scene rendering.
+///
+/// Higher counts smooth the edges of particles, trails, connections, and
+/// wireframes at the cost of fill rate. Counts the GPU doesn't support for
+/// the target format fall back to the highest supported lower count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MsaaSamples {
+    /// No anti-aliasing (default).
+    #[default]
+    X1,
+    /// 2 samples per pixel.
+    X2,
+    /// 4 samples per pixel. Supported by every WebGPU device.
+    X4,
+    /// 8 samples per pixel.
+    X8,
+}
+
+impl MsaaSamples {
+    /// Number of samples per pixel.
+    pub fn count(&self) -> u32 {
+        match self {
+            MsaaSamples::X1 => 1,
+            MsaaSamples::X2 => 2,
+            MsaaSamples::X4 => 4,
+            MsaaSamples::X8 => 8,
+        }
+    }
+}
+
 /// Particle shape for rendering.
 ///
 /// Controls the visual shape of each particle. All shapes use the UV coordinate
-/// system where (-1, -1) is bottom-left and (1, 1) is top-right of the particle quad.
+/// system where (-1, -1) is bottom-left and (1, 1) is top-right of the particle quad,
+/// except [`ParticleShape::Mesh`], which draws real geometry instead of a quad.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ParticleShape {
     /// Soft circle with smooth falloff (default).
@@ -265,6 +575,12 @@ pub enum ParticleShape {
 
     /// Single pixel point (fastest, no shape calculation).
     Point,
+
+    /// Instanced 3D triangle mesh, lit by a fixed directional light.
+    ///
+    /// The geometry is set with [`VisualConfig::mesh`], which also selects
+    /// this shape.
+    Mesh,
 }
 
 impl ParticleShape {
@@ -360,6 +676,48 @@ impl ParticleShape {
 
             ParticleShape::Point => r#"    // Single pixel - no shape calculation needed
     return vec4<f32>(in.color, 1.0);"#,
+
+            // Meshes use their own pipeline; the billboard shader is unused
+            ParticleShape::Mesh => r#"    return vec4<f32>(in.color, 1.0);"#,
+        }
+    }
+}
+
+/// Settings for the bloom post-processing effect.
+///
+/// Pixels brighter than `threshold` are blurred and added back onto the
+/// image, making bright particles glow.
+///
+/// # Example
+///
+/// ```ignore
+/// .with_visuals(|v| {
+///     v.bloom(BloomConfig {
+///         threshold: 0.6,
+///         intensity: 1.5,
+///         ..Default::default()
+///     });
+/// })
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BloomConfig {
+    /// Brightness (max RGB component) above which pixels start to glow.
+    pub threshold: f32,
+    /// Multiplier for the blurred glow when it is added back.
+    pub intensity: f32,
+    /// Distance between blur samples in texels; larger spreads the glow further.
+    pub radius: f32,
+    /// Number of Kawase blur passes.
+    pub iterations: u32,
+}
+
+impl Default for BloomConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 0.8,
+            intensity: 1.0,
+            radius: 1.0,
+            iterations: 4,
         }
     }
 }
@@ -375,12 +733,25 @@ pub struct VisualConfig {
     pub shape: ParticleShape,
     /// Trail length (0 = no trails).
     pub trail_length: u32,
+    /// Color trails fade toward at their oldest end (RGB, 0.0-1.0), or
+    /// `None` to keep the particle's color along the whole trail.
+    pub trail_tail_color: Option<Vec3>,
+    /// Trail color gradient over age.
+    pub trail: TrailConfig,
     /// Whether to draw connections between nearby particles.
     pub connections_enabled: bool,
     /// Radius for particle connections.
     pub connections_radius: f32,
     /// Color for particle connections (RGB, 0.0-1.0).
+    ///
+    /// With a far color set, this is the color of the shortest connections.
     pub connections_color: Vec3,
+    /// Color of connections at the full radius (None = same as `connections_color`).
+    pub connections_far_color: Option<Vec3>,
+    /// Draw an arrowhead on each connection pointing from the lower-index particle.
+    pub connections_directed: bool,
+    /// Speed of a dot flowing along each connection in lengths per second (0 = off).
+    pub connections_flow_speed: f32,
     /// Whether to stretch particles in velocity direction.
     pub velocity_stretch: bool,
     /// Maximum stretch factor for velocity stretching.
@@ -393,12 +764,33 @@ pub struct VisualConfig {
     pub background_color: Vec3,
     /// Custom post-processing shader code (fragment shader body).
     pub post_process_shader: Option<String>,
+    /// Further post-processing passes, run in order after `post_process_shader`.
+    pub post_process_chain: Vec<String>,
+    /// Radial RGB channel separation (0.0 = off, 1.0 = strong), run before
+    /// the custom post-process shaders.
+    pub chromatic_aberration: f32,
+    /// Bloom effect applied before the custom post-process shader (None = off).
+    pub bloom: Option<BloomConfig>,
+    /// Render the scene into an `Rgba16Float` target so colors can exceed 1.0.
+    pub hdr: bool,
+    /// Curve mapping HDR colors to the display (only used when `hdr` is set).
+    pub tone_map: ToneMapMode,
+    /// Multisample anti-aliasing level for the scene pass.
+    pub msaa_samples: MsaaSamples,
     /// Spatial grid visualization opacity (0.0 = off, 1.0 = full).
     pub spatial_grid_opacity: f32,
     /// Wireframe mesh for 3D particle shapes (None = use billboard shapes).
     pub wireframe_mesh: Option<WireframeMesh>,
     /// Line thickness for wireframe rendering (in clip space, ~0.001-0.01).
     pub wireframe_thickness: f32,
+    /// Geometry drawn per particle when `shape` is [`ParticleShape::Mesh`].
+    pub mesh: Option<MeshData>,
+    /// Rotate mesh particles so their +Y axis points along their velocity.
+    pub mesh_align_to_velocity: bool,
+    /// Flipbook animation from the first registered texture (None = off).
+    pub sprite_sheet: Option<SpriteSheetConfig>,
+    /// Directional light with shadow mapping (None = unlit, no shadows).
+    pub light: Option<LightConfig>,
 }
 
 impl Default for VisualConfig {
@@ -407,18 +799,33 @@ impl Default for VisualConfig {
             blend_mode: BlendMode::Alpha,
             shape: ParticleShape::Circle,
             trail_length: 0,
+            trail_tail_color: None,
+            trail: TrailConfig::default(),
             connections_enabled: false,
             connections_radius: 0.1,
             connections_color: Vec3::new(0.5, 0.7, 1.0),
+            connections_far_color: None,
+            connections_directed: false,
+            connections_flow_speed: 0.0,
             velocity_stretch: false,
             velocity_stretch_factor: 2.0,
             palette: Palette::None,
             color_mapping: ColorMapping::None,
             background_color: Vec3::new(0.02, 0.02, 0.05), // Dark blue-black
             post_process_shader: None,
+            post_process_chain: Vec::new(),
+            chromatic_aberration: 0.0,
+            bloom: None,
+            hdr: false,
+            tone_map: ToneMapMode::AcesFilmic,
+            msaa_samples: MsaaSamples::X1,
             spatial_grid_opacity: 0.0, // Off by default
             wireframe_mesh: None,
             wireframe_thickness: 0.003, // Default line thickness
+            mesh: None,
+            mesh_align_to_velocity: false,
+            sprite_sheet: None,
+            light: None,
         }
     }
 }
@@ -477,6 +884,45 @@ impl VisualConfig {
         self
     }
 
+    /// Set the color trails fade toward.
+    ///
+    /// Each trail starts at the particle's color and blends toward this color
+    /// as it ages. Without a tail color, trails keep the particle's color.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// .with_visuals(|v| {
+    ///     v.trails(20);
+    ///     v.trail_tail_color(Vec3::new(0.1, 0.3, 1.0)); // Plasma fades to blue
+    /// })
+    /// ```
+    pub fn trail_tail_color(&mut self, color: Vec3) -> &mut Self {
+        self.trail_tail_color = Some(color);
+        self
+    }
+
+    /// Color trails with a gradient over their length.
+    ///
+    /// Replaces the particle color and tail color blend when the gradient
+    /// has stops. See [`TrailConfig`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// .with_visuals(|v| {
+    ///     v.trails(20);
+    ///     v.trail_config(TrailConfig {
+    ///         color_gradient: vec![([0.2, 0.6, 1.0, 1.0], 0.0), ([1.0, 1.0, 1.0, 1.0], 1.0)],
+    ///         ..Default::default()
+    ///     });
+    /// })
+    /// ```
+    pub fn trail_config(&mut self, config: TrailConfig) -> &mut Self {
+        self.trail = config;
+        self
+    }
+
     /// Enable connections between nearby particles.
     ///
     /// Draws lines between particles within the specified radius.
@@ -518,6 +964,56 @@ impl VisualConfig {
         self
     }
 
+    /// Color connections by length, from `near` for touching particles to
+    /// `far` at the connection radius.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// .with_visuals(|v| {
+    ///     v.connections(0.15)
+    ///      .connections_distance_colors(Vec3::new(1.0, 1.0, 0.8), Vec3::new(0.2, 0.1, 0.6));
+    /// })
+    /// ```
+    pub fn connections_distance_colors(&mut self, near: Vec3, far: Vec3) -> &mut Self {
+        self.connections_color = near;
+        self.connections_far_color = Some(far);
+        self
+    }
+
+    /// Show connection direction with an arrowhead 60% of the way from the
+    /// lower-index particle to the higher-index one.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// .with_visuals(|v| {
+    ///     v.connections(0.15).connections_directed(true);
+    /// })
+    /// ```
+    pub fn connections_directed(&mut self, directed: bool) -> &mut Self {
+        self.connections_directed = directed;
+        self
+    }
+
+    /// Animate a dot traveling along each connection in its direction.
+    ///
+    /// `speed` is in connection lengths per second; 0.0 disables the dot.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// .with_visuals(|v| {
+    ///     v.connections(0.15)
+    ///      .connections_directed(true)
+    ///      .connections_flow_speed(0.5); // One trip every two seconds
+    /// })
+    /// ```
+    pub fn connections_flow_speed(&mut self, speed: f32) -> &mut Self {
+        self.connections_flow_speed = speed;
+        self
+    }
+
     /// Enable velocity-based stretching.
     ///
     /// Particles stretch in their direction of motion, creating
@@ -642,6 +1138,117 @@ impl VisualConfig {
         self
     }
 
+    /// Append a post-processing pass to the chain.
+    ///
+    /// Passes run in the order they are added, after any
+    /// [`post_process`](Self::post_process) shader. Each one is written like a
+    /// `post_process` body, but `scene` holds the previous pass's output.
+    /// Intermediate results are kept in `Rgba16Float`, so values above 1.0
+    /// carry through to the last pass.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// .with_visuals(|v| {
+    ///     // Blur horizontally, then vertically
+    ///     v.add_post_process(r#"
+    ///         let texel = 1.0 / vec2<f32>(textureDimensions(scene));
+    ///         let dx = vec2(texel.x, 0.0);
+    ///         let c = textureSample(scene, scene_sampler, in.uv - dx)
+    ///             + textureSample(scene, scene_sampler, in.uv)
+    ///             + textureSample(scene, scene_sampler, in.uv + dx);
+    ///         return c / 3.0;
+    ///     "#);
+    ///     v.add_post_process(r#"
+    ///         let texel = 1.0 / vec2<f32>(textureDimensions(scene));
+    ///         let dy = vec2(0.0, texel.y);
+    ///         let c = textureSample(scene, scene_sampler, in.uv - dy)
+    ///             + textureSample(scene, scene_sampler, in.uv)
+    ///             + textureSample(scene, scene_sampler, in.uv + dy);
+    ///         return c / 3.0;
+    ///     "#);
+    /// })
+    /// ```
+    pub fn add_post_process(&mut self, wgsl_code: &str) -> &mut Self {
+        self.post_process_chain.push(wgsl_code.to_string());
+        self
+    }
+
+    /// Enable bloom so bright particles glow.
+    ///
+    /// Runs before any custom [`post_process`](Self::post_process) shader,
+    /// which sees the scene with bloom already added.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// .with_visuals(|v| {
+    ///     v.blend_mode(BlendMode::Additive);
+    ///     v.bloom(BloomConfig { threshold: 0.5, ..Default::default() });
+    /// })
+    /// ```
+    pub fn bloom(&mut self, config: BloomConfig) -> &mut Self {
+        self.bloom = Some(config);
+        self
+    }
+
+    /// Enable HDR rendering with the given tone-mapping curve.
+    ///
+    /// The scene is rendered into a floating-point target, so additive
+    /// particles can build up brightness past 1.0 instead of clipping to
+    /// white. Bloom and any custom post-process shader see the HDR values;
+    /// tone mapping is applied to the output of the final pass.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// .with_visuals(|v| {
+    ///     v.blend_mode(BlendMode::Additive);
+    ///     v.hdr(ToneMapMode::AcesFilmic);
+    ///     v.bloom(BloomConfig { threshold: 1.0, ..Default::default() });
+    /// })
+    /// ```
+    pub fn hdr(&mut self, tone_map: ToneMapMode) -> &mut Self {
+        self.hdr = true;
+        self.tone_map = tone_map;
+        self
+    }
+
+    /// Split the color channels toward the screen edges, like a cheap lens.
+    ///
+    /// `strength` runs from 0.0 (off) to 1.0 (strong). The effect is a
+    /// built-in post-process pass that runs after bloom and before any
+    /// custom [`post_process`](Self::post_process) shaders.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// .with_visuals(|v| {
+    ///     v.chromatic_aberration(0.4);
+    /// })
+    /// ```
+    pub fn chromatic_aberration(&mut self, strength: f32) -> &mut Self {
+        self.chromatic_aberration = strength.max(0.0);
+        self
+    }
+
+    /// Set the multisample anti-aliasing level.
+    ///
+    /// The scene is drawn into a multisampled target and resolved before
+    /// volume rendering and post-processing.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// .with_visuals(|v| {
+    ///     v.msaa(MsaaSamples::X4);
+    /// })
+    /// ```
+    pub fn msaa(&mut self, samples: MsaaSamples) -> &mut Self {
+        self.msaa_samples = samples;
+        self
+    }
+
     /// Set a wireframe mesh for 3D particle shapes.
     ///
     /// Instead of rendering particles as billboards (flat shapes facing the camera),
@@ -661,6 +1268,80 @@ impl VisualConfig {
         self
     }
 
+    /// Draw each particle as an instance of a 3D triangle mesh.
+    ///
+    /// Sets the shape to [`ParticleShape::Mesh`]. Setting a billboard shape
+    /// afterwards keeps the mesh but stops drawing it.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// .with_visuals(|v| {
+    ///     v.mesh(MeshData::cube());
+    /// })
+    /// ```
+    pub fn mesh(&mut self, mesh: MeshData) -> &mut Self {
+        self.shape = ParticleShape::Mesh;
+        self.mesh = Some(mesh);
+        self
+    }
+
+    /// The mesh to draw per particle, if the shape is [`ParticleShape::Mesh`].
+    pub fn particle_mesh(&self) -> Option<&MeshData> {
+        match self.shape {
+            ParticleShape::Mesh => self.mesh.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Rotate [`ParticleShape::Mesh`] particles to face their direction of travel.
+    ///
+    /// The mesh's +Y axis is aligned with the particle's velocity. Particles
+    /// that are nearly stationary keep the mesh's original orientation.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// .with_visuals(|v| {
+    ///     v.mesh(MeshData::from_obj_bytes(FISH_OBJ).unwrap());
+    ///     v.align_mesh_to_velocity(true);
+    /// })
+    /// ```
+    pub fn align_mesh_to_velocity(&mut self, enabled: bool) -> &mut Self {
+        self.mesh_align_to_velocity = enabled;
+        self
+    }
+
+    /// Animate particles with a sprite sheet.
+    ///
+    /// See [`SpriteSheetConfig`] for how the texture must be laid out.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// .with_visuals(|v| {
+    ///     v.sprite_sheet(SpriteSheetConfig { columns: 8, rows: 1, ..Default::default() });
+    /// })
+    /// ```
+    pub fn sprite_sheet(&mut self, config: SpriteSheetConfig) -> &mut Self {
+        self.sprite_sheet = Some(config);
+        self
+    }
+
+    /// Light the scene with a directional light and cast particle shadows.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// .with_visuals(|v| {
+    ///     v.light(LightConfig { shadow_map_size: 1024, ..Default::default() });
+    /// })
+    /// ```
+    pub fn light(&mut self, config: LightConfig) -> &mut Self {
+        self.light = Some(config);
+        self
+    }
+
     /// Compare this config with another to determine what kind of rebuild is needed.
     ///
     /// Returns a `ConfigDiff` describing which changes can be hot-swapped and
@@ -682,13 +1363,28 @@ impl VisualConfig {
             || self.palette != other.palette
             || self.color_mapping != other.color_mapping
             || self.trail_length != other.trail_length
+            || self.trail_tail_color != other.trail_tail_color
+            || self.trail != other.trail
             || self.connections_enabled != other.connections_enabled
             || self.connections_radius != other.connections_radius
+            || self.connections_far_color != other.connections_far_color
+            || self.connections_directed != other.connections_directed
+            || self.connections_flow_speed != other.connections_flow_speed
             || self.velocity_stretch != other.velocity_stretch
             || self.velocity_stretch_factor != other.velocity_stretch_factor
             || self.wireframe_mesh != other.wireframe_mesh
             || self.wireframe_thickness != other.wireframe_thickness
-            || self.post_process_shader != other.post_process_shader;
+            || self.mesh != other.mesh
+            || self.mesh_align_to_velocity != other.mesh_align_to_velocity
+            || self.sprite_sheet != other.sprite_sheet
+            || self.light != other.light
+            || self.post_process_shader != other.post_process_shader
+            || self.post_process_chain != other.post_process_chain
+            || self.chromatic_aberration != other.chromatic_aberration
+            || self.bloom != other.bloom
+            || self.hdr != other.hdr
+            || self.tone_map != other.tone_map
+            || self.msaa_samples != other.msaa_samples;
 
         ConfigDiff {
             needs_render_rebuild,
@@ -748,8 +1444,11 @@ pub enum HotSwapChange {
 /// WireframeMesh::octahedron()  // 8 triangular faces
 /// WireframeMesh::diamond()     // Two pyramids joined at base
 /// WireframeMesh::axes()        // XYZ axis indicator
+/// WireframeMesh::from_primitive(Primitive::Torus) // Sphere, Cube, Torus, Cylinder
 /// ```
 ///
+/// Or load the edges of a model with [`WireframeMesh::from_obj_bytes`].
+///
 /// # Custom Shapes
 ///
 /// Create custom wireframes from line segment pairs:
@@ -999,6 +1698,87 @@ impl WireframeMesh {
         Self { lines }
     }
 
+    /// Built-in curved shape, sized to fit the unit cube like the other shapes.
+    pub fn from_primitive(shape: Primitive) -> Self {
+        let mut lines = Vec::new();
+        match shape {
+            Primitive::Cube => return Self::cube(),
+            Primitive::Sphere => {
+                let radius = 0.5;
+                // Latitude rings, skipping the poles
+                for i in 1..6 {
+                    let polar = i as f32 / 6.0 * std::f32::consts::PI;
+                    let center = Vec3::new(0.0, polar.cos() * radius, 0.0);
+                    push_ring(&mut lines, center, Vec3::X, Vec3::Z, polar.sin() * radius, 24);
+                }
+                // Meridians through both poles
+                for i in 0..4 {
+                    let azimuth = i as f32 / 4.0 * std::f32::consts::PI;
+                    let across = Vec3::new(azimuth.cos(), 0.0, azimuth.sin());
+                    push_ring(&mut lines, Vec3::ZERO, across, Vec3::Y, radius, 24);
+                }
+            }
+            Primitive::Torus => {
+                let (major, minor) = (0.35, 0.15);
+                // Rings around the tube
+                for i in 0..12 {
+                    let angle = i as f32 / 12.0 * std::f32::consts::TAU;
+                    let outward = Vec3::new(angle.cos(), 0.0, angle.sin());
+                    push_ring(&mut lines, outward * major, outward, Vec3::Y, minor, 8);
+                }
+                // Rings around the hole, at the tube's inside, outside, top and bottom
+                for (radius, height) in [(major - minor, 0.0), (major + minor, 0.0), (major, minor), (major, -minor)] {
+                    push_ring(&mut lines, Vec3::new(0.0, height, 0.0), Vec3::X, Vec3::Z, radius, 24);
+                }
+            }
+            Primitive::Cylinder => {
+                let radius = 0.5;
+                for y in [-0.5, 0.5] {
+                    push_ring(&mut lines, Vec3::new(0.0, y, 0.0), Vec3::X, Vec3::Z, radius, 24);
+                }
+                for i in 0..8 {
+                    let angle = i as f32 / 8.0 * std::f32::consts::TAU;
+                    let (x, z) = (angle.cos() * radius, angle.sin() * radius);
+                    lines.push((Vec3::new(x, -0.5, z), Vec3::new(x, 0.5, z)));
+                }
+            }
+        }
+        Self { lines }
+    }
+
+    /// Edges of a triangle mesh, each shared edge drawn once.
+    pub fn from_mesh(mesh: &MeshData) -> Self {
+        // OBJ loading splits vertices by normal, so match edges by position
+        let key = |i: u16| mesh.vertices[i as usize].map(f32::to_bits);
+        let mut seen = std::collections::HashSet::new();
+        let mut lines = Vec::new();
+        for tri in mesh.indices.chunks_exact(3) {
+            for (a, b) in [(tri[0], tri[1]), (tri[1], tri[2]), (tri[2], tri[0])] {
+                let (ka, kb) = (key(a), key(b));
+                if ka != kb && seen.insert(if ka < kb { (ka, kb) } else { (kb, ka) }) {
+                    lines.push((Vec3::from(mesh.vertices[a as usize]), Vec3::from(mesh.vertices[b as usize])));
+                }
+            }
+        }
+        Self { lines }
+    }
+
+    /// Parse the edges of a Wavefront OBJ file.
+    ///
+    /// Faces are read as by [`MeshData::from_obj_bytes`]: polygons with more
+    /// than three corners, like quads, are split into triangles, and every
+    /// triangle edge becomes a line.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mesh = WireframeMesh::from_obj_bytes(include_bytes!("ship.obj"))?;
+    /// .with_visuals(|v| { v.wireframe(mesh.clone(), 0.002); })
+    /// ```
+    pub fn from_obj_bytes(bytes: &[u8]) -> Result<Self, ObjError> {
+        MeshData::from_obj_bytes(bytes).map(|mesh| Self::from_mesh(&mesh))
+    }
+
     /// Get the total number of line segments.
     pub fn line_count(&self) -> u32 {
         self.lines.len() as u32
@@ -1014,6 +1794,214 @@ impl WireframeMesh {
     }
 }
 
+/// Push a circle of `segments` lines spanned by the unit vectors `u` and `v`.
+fn push_ring(lines: &mut Vec<(Vec3, Vec3)>, center: Vec3, u: Vec3, v: Vec3, radius: f32, segments: u32) {
+    let point = |i: u32| {
+        let angle = i as f32 / segments as f32 * std::f32::consts::TAU;
+        center + (u * angle.cos() + v * angle.sin()) * radius
+    };
+    for i in 0..segments {
+        lines.push((point(i), point(i + 1)));
+    }
+}
+
+/// Built-in shapes for [`WireframeMesh::from_primitive`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Primitive {
+    /// Sphere of radius 0.5 drawn as latitude rings and meridians.
+    Sphere,
+    /// Cube with side length 1.
+    Cube,
+    /// Ring with a 0.35 center radius and a 0.15 tube radius, around the Y axis.
+    Torus,
+    /// Cylinder of radius 0.5 and height 1 along the Y axis.
+    Cylinder,
+}
+
+/// Triangle mesh for rendering particles as 3D geometry.
+///
+/// Set with [`VisualConfig::mesh`]. Each particle draws one instance of the
+/// mesh, translated to its position and scaled by its `scale` field times the
+/// base particle size. Vertices should fit roughly in a unit cube centered on
+/// the origin, with +Y as the mesh's "forward" axis for velocity alignment.
+///
+/// # Example
+///
+/// ```ignore
+/// let mesh = MeshData::from_obj_bytes(include_bytes!("fish.obj"))?;
+/// .with_visuals(|v| {
+///     v.mesh(mesh.clone());
+///     v.align_mesh_to_velocity(true);
+/// })
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MeshData {
+    /// Vertex positions.
+    pub vertices: Vec<[f32; 3]>,
+    /// Vertex normals, one per vertex.
+    pub normals: Vec<[f32; 3]>,
+    /// Triangle list indices into `vertices`.
+    pub indices: Vec<u16>,
+}
+
+impl MeshData {
+    /// Axis-aligned cube with side length 1 and flat-shaded faces.
+    pub fn cube() -> Self {
+        let faces: [([f32; 3], [f32; 3], [f32; 3]); 6] = [
+            // (normal, u axis, v axis)
+            ([1.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, 1.0, 0.0]),
+            ([-1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]),
+            ([0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]),
+            ([0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+            ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+            ([0.0, 0.0, -1.0], [-1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+        ];
+
+        let mut mesh = Self::default();
+        for (n, u, v) in faces {
+            let base = mesh.vertices.len() as u16;
+            for (su, sv) in [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)] {
+                mesh.vertices.push([
+                    n[0] * 0.5 + u[0] * su + v[0] * sv,
+                    n[1] * 0.5 + u[1] * su + v[1] * sv,
+                    n[2] * 0.5 + u[2] * su + v[2] * sv,
+                ]);
+                mesh.normals.push(n);
+            }
+            mesh.indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+        }
+        mesh
+    }
+
+    /// Parse a Wavefront OBJ file.
+    ///
+    /// Reads `v`, `vn`, and `f` lines; everything else (texture coordinates,
+    /// groups, materials) is ignored. Polygons are triangulated as fans.
+    /// Vertices without an `vn` reference get smooth normals computed from the
+    /// faces that use them.
+    pub fn from_obj_bytes(bytes: &[u8]) -> Result<Self, ObjError> {
+        let text = std::str::from_utf8(bytes).map_err(|_| ObjError::InvalidUtf8)?;
+
+        let mut positions: Vec<[f32; 3]> = Vec::new();
+        let mut obj_normals: Vec<[f32; 3]> = Vec::new();
+        let mut mesh = Self::default();
+        // Maps each (position, normal) pair to its output vertex
+        let mut vertex_map: std::collections::HashMap<(usize, Option<usize>), u16> =
+            std::collections::HashMap::new();
+        let mut needs_normal: Vec<bool> = Vec::new();
+
+        for (line_idx, line) in text.lines().enumerate() {
+            let line_num = line_idx + 1;
+            let mut parts = line.split_whitespace();
+            match parts.next() {
+                Some("v") => positions.push(parse_obj_vec3(parts, line_num)?),
+                Some("vn") => obj_normals.push(parse_obj_vec3(parts, line_num)?),
+                Some("f") => {
+                    let mut corners = Vec::new();
+                    for corner in parts {
+                        let mut refs = corner.split('/');
+                        let v = resolve_obj_index(refs.next(), positions.len(), line_num)?
+                            .ok_or(ObjError::Parse { line: line_num })?;
+                        let _vt = refs.next();
+                        let vn = resolve_obj_index(refs.next(), obj_normals.len(), line_num)?;
+
+                        let index = match vertex_map.get(&(v, vn)) {
+                            Some(&index) => index,
+                            None => {
+                                let index = u16::try_from(mesh.vertices.len())
+                                    .map_err(|_| ObjError::TooManyVertices)?;
+                                mesh.vertices.push(positions[v]);
+                                mesh.normals.push(vn.map_or([0.0; 3], |n| obj_normals[n]));
+                                needs_normal.push(vn.is_none());
+                                vertex_map.insert((v, vn), index);
+                                index
+                            }
+                        };
+                        corners.push(index);
+                    }
+                    if corners.len() < 3 {
+                        return Err(ObjError::Parse { line: line_num });
+                    }
+                    for i in 1..corners.len() - 1 {
+                        mesh.indices.extend_from_slice(&[corners[0], corners[i], corners[i + 1]]);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if mesh.indices.is_empty() {
+            return Err(ObjError::NoFaces);
+        }
+
+        // Accumulate face normals for vertices the file gave no normal
+        if needs_normal.iter().any(|&n| n) {
+            let mut accum = vec![Vec3::ZERO; mesh.vertices.len()];
+            for tri in mesh.indices.chunks_exact(3) {
+                let [a, b, c] = [tri[0], tri[1], tri[2]].map(|i| Vec3::from(mesh.vertices[i as usize]));
+                let face_normal = (b - a).cross(c - a);
+                for &i in tri {
+                    accum[i as usize] += face_normal;
+                }
+            }
+            for (i, normal) in accum.into_iter().enumerate() {
+                if needs_normal[i] {
+                    mesh.normals[i] = normal.normalize_or_zero().to_array();
+                }
+            }
+        }
+
+        Ok(mesh)
+    }
+
+    /// Get vertices as interleaved f32 array for GPU buffer.
+    /// Each vertex is 6 floats: [px, py, pz, nx, ny, nz]
+    pub fn to_vertices(&self) -> Vec<f32> {
+        self.vertices
+            .iter()
+            .zip(&self.normals)
+            .flat_map(|(p, n)| [p[0], p[1], p[2], n[0], n[1], n[2]])
+            .collect()
+    }
+
+    /// Number of indices (3 per triangle).
+    pub fn index_count(&self) -> u32 {
+        self.indices.len() as u32
+    }
+}
+
+fn parse_obj_vec3<'a>(
+    mut parts: impl Iterator<Item = &'a str>,
+    line: usize,
+) -> Result<[f32; 3], ObjError> {
+    let mut out = [0.0; 3];
+    for value in &mut out {
+        *value = parts
+            .next()
+            .and_then(|s| s.parse().ok())
+            .ok_or(ObjError::Parse { line })?;
+    }
+    Ok(out)
+}
+
+/// Resolve a 1-based (or negative, relative) OBJ index into a 0-based one.
+fn resolve_obj_index(field: Option<&str>, len: usize, line: usize) -> Result<Option<usize>, ObjError> {
+    let field = match field {
+        Some(f) if !f.is_empty() => f,
+        _ => return Ok(None),
+    };
+    let raw: i64 = field.parse().map_err(|_| ObjError::Parse { line })?;
+    let index = if raw > 0 {
+        raw - 1
+    } else {
+        len as i64 + raw
+    };
+    if raw == 0 || index < 0 || index >= len as i64 {
+        return Err(ObjError::IndexOutOfRange { line });
+    }
+    Ok(Some(index as usize))
+}
+
 /// Vertex shader effects for particle rendering.
 ///
 /// Pre-built, composable effects that modify particle vertex transformations.
@@ -1434,3 +2422,127 @@ pub fn combine_vertex_effects(effects: &[VertexEffect], color_expr: &str) -> Str
 {final_transform}"#
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trail_stops_sorted_with_transparent_tail() {
+        let config = TrailConfig {
+            color_gradient: vec![([0.0, 0.0, 1.0, 1.0], 0.8), ([1.0, 0.0, 0.0, 1.0], 0.0)],
+            fade_to_transparent: true,
+            ..Default::default()
+        };
+        let stops = config.stops();
+        assert_eq!(stops.len(), 3);
+        assert_eq!(stops[0], ([1.0, 0.0, 0.0, 1.0], 0.0));
+        assert_eq!(stops[2], ([0.0, 0.0, 1.0, 0.0], 1.0));
+
+        // A stop already at the end becomes transparent instead of gaining a twin
+        let ending = TrailConfig {
+            color_gradient: vec![([1.0, 0.0, 0.0, 1.0], 0.0), ([0.0, 0.0, 1.0, 1.0], 1.0)],
+            fade_to_transparent: true,
+            ..Default::default()
+        };
+        assert_eq!(ending.stops(), vec![([1.0, 0.0, 0.0, 1.0], 0.0), ([0.0, 0.0, 1.0, 0.0], 1.0)]);
+        let wgsl = ending.to_wgsl().unwrap();
+        assert!(wgsl.contains("vec4<f32>(0.000000, 0.000000, 1.000000, 0.000000)"));
+
+        // Nothing to fade without a gradient
+        let empty = TrailConfig { fade_to_transparent: true, ..Default::default() };
+        assert!(empty.stops().is_empty());
+        assert!(empty.to_wgsl().is_none());
+    }
+
+    #[test]
+    fn test_obj_parses_quads_and_normals() {
+        let obj = b"# quad
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vn 0 0 1
+f 1//1 2//1 3//1 4//1
+";
+        let mesh = MeshData::from_obj_bytes(obj).unwrap();
+        assert_eq!(mesh.vertices.len(), 4);
+        assert_eq!(mesh.indices, vec![0, 1, 2, 0, 2, 3]);
+        assert!(mesh.normals.iter().all(|n| *n == [0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn test_obj_computes_missing_normals() {
+        let obj = b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf -3/1 -2/2 -1/3\n";
+        let mesh = MeshData::from_obj_bytes(obj).unwrap();
+        assert_eq!(mesh.indices, vec![0, 1, 2]);
+        assert!(mesh.normals.iter().all(|n| *n == [0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn test_obj_errors() {
+        assert_eq!(MeshData::from_obj_bytes(b"v 0 0 0\n"), Err(ObjError::NoFaces));
+        assert_eq!(
+            MeshData::from_obj_bytes(b"v 0 0\n"),
+            Err(ObjError::Parse { line: 1 })
+        );
+        assert_eq!(
+            MeshData::from_obj_bytes(b"v 0 0 0\nf 1 2 3\n"),
+            Err(ObjError::IndexOutOfRange { line: 2 })
+        );
+    }
+
+    #[test]
+    fn test_wireframe_from_obj_shares_edges() {
+        // Two quads sharing an edge, with per-face normals splitting the vertices
+        let obj = b"v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 0 0 1
+v 0 1 1
+vn 0 0 1
+vn 1 0 0
+f 1//1 2//1 3//1 4//1
+f 5//2 1//2 4//2 6//2
+";
+        let wireframe = WireframeMesh::from_obj_bytes(obj).unwrap();
+        // 4 + 3 outline edges, plus one diagonal per quad
+        assert_eq!(wireframe.line_count(), 9);
+        assert_eq!(WireframeMesh::from_obj_bytes(b"v 0 0 0
+"), Err(ObjError::NoFaces));
+    }
+
+    #[test]
+    fn test_wireframe_primitives_fit_unit_cube() {
+        for shape in [Primitive::Sphere, Primitive::Cube, Primitive::Torus, Primitive::Cylinder] {
+            let mesh = WireframeMesh::from_primitive(shape);
+            assert!(mesh.line_count() > 0, "{:?} has no lines", shape);
+            for (a, b) in &mesh.lines {
+                assert!(a.abs().max_element() <= 0.5 + 1e-5 && b.abs().max_element() <= 0.5 + 1e-5);
+            }
+        }
+        assert_eq!(WireframeMesh::from_primitive(Primitive::Cube), WireframeMesh::cube());
+    }
+
+    #[test]
+    fn test_cube_mesh_is_consistent() {
+        let cube = MeshData::cube();
+        assert_eq!(cube.vertices.len(), cube.normals.len());
+        assert_eq!(cube.index_count(), 36);
+        assert!(cube.indices.iter().all(|&i| (i as usize) < cube.vertices.len()));
+    }
+
+    #[test]
+    fn test_mesh_shape() {
+        let mut config = VisualConfig::default();
+        config.mesh(MeshData::cube());
+        assert_eq!(config.shape, ParticleShape::Mesh);
+        assert_eq!(config.particle_mesh(), Some(&MeshData::cube()));
+
+        // A billboard shape keeps the mesh but stops drawing it
+        config.shape(ParticleShape::Star);
+        assert!(config.mesh.is_some());
+        assert_eq!(config.particle_mesh(), None);
+    }
+}
@@ -45,6 +45,14 @@ fn test_particle_type_invalid_u32_defaults_to_first() {
     assert_eq!(invalid, Species::Prey); // Should default to first variant
 }
 
+#[test]
+fn test_particle_type_try_from_u32() {
+    assert_eq!(Species::try_from_u32(0), Some(Species::Prey));
+    assert_eq!(Species::try_from_u32(2), Some(Species::Plant));
+    assert_eq!(Species::try_from_u32(Species::count()), None);
+    assert_eq!(Species::try_from_u32(u32::MAX), None);
+}
+
 #[test]
 fn test_particle_type_count() {
     assert_eq!(Species::count(), 3);
@@ -101,6 +109,7 @@ fn test_minimal_particle_wgsl_struct() {
     assert!(wgsl.contains("age: f32"));
     assert!(wgsl.contains("alive: u32"));
     assert!(wgsl.contains("scale: f32"));
+    assert!(wgsl.contains("triggered: u32"));
 }
 
 #[test]
@@ -117,6 +126,7 @@ fn test_minimal_particle_to_gpu() {
     assert_eq!(gpu.particle_type, 0); // Auto-injected default
     assert_eq!(gpu.alive, 1); // Particles start alive
     assert!((gpu.scale - 1.0).abs() < 0.001); // Default scale
+    assert_eq!(gpu.triggered, 0); // No age trigger has fired
 }
 
 #[test]
@@ -256,6 +266,7 @@ fn test_complex_particle_to_gpu() {
 // ============================================================================
 
 #[derive(Particle, Clone)]
+#[particle(default)]
 struct VectorParticle {
     position: Vec3,
     velocity: Vec3,
@@ -294,6 +305,7 @@ fn test_vector_particle_to_gpu() {
 // ============================================================================
 
 #[derive(Particle, Clone)]
+#[particle(default)]
 struct IntParticle {
     position: Vec3,
     velocity: Vec3,
@@ -324,6 +336,267 @@ fn test_int_particle_to_gpu() {
     assert_eq!(gpu.offset, -10);
 }
 
+#[derive(Particle, Clone)]
+#[particle(default)]
+struct FlagParticle {
+    position: Vec3,
+    velocity: Vec3,
+    is_leader: bool,
+    collided: bool,
+}
+
+#[test]
+fn test_bool_particle_wgsl_struct() {
+    let wgsl = FlagParticle::WGSL_STRUCT;
+
+    assert!(wgsl.contains("is_leader: u32"));
+    assert!(wgsl.contains("collided: u32"));
+}
+
+#[test]
+fn test_bool_particle_roundtrip() {
+    let p = FlagParticle {
+        position: Vec3::ZERO,
+        velocity: Vec3::ZERO,
+        is_leader: true,
+        collided: false,
+    };
+
+    let gpu = p.to_gpu();
+    assert_eq!(gpu.is_leader, 1);
+    assert_eq!(gpu.collided, 0);
+
+    let back = FlagParticle::from_gpu(&gpu);
+    assert!(back.is_leader);
+    assert!(!back.collided);
+}
+
+#[test]
+fn test_bool_particle_inspect() {
+    let p = FlagParticle {
+        position: Vec3::ZERO,
+        velocity: Vec3::ZERO,
+        is_leader: true,
+        collided: false,
+    };
+
+    let fields = p.inspect_fields();
+    assert!(fields.contains(&("is_leader", "true".to_string())));
+    assert!(fields.contains(&("collided", "false".to_string())));
+}
+
+#[derive(Particle, Clone)]
+struct RangedParticle {
+    position: Vec3,
+    velocity: Vec3,
+    #[range(0.0, 1.0)]
+    energy: f32,
+    #[range(2, 10)]
+    count: u32,
+    #[range(-5, 5)]
+    mood: i32,
+    unclamped: f32,
+}
+
+#[test]
+fn test_range_clamps_from_gpu() {
+    let p = RangedParticle {
+        position: Vec3::ZERO,
+        velocity: Vec3::ZERO,
+        energy: -1000.0,
+        count: 50,
+        mood: -9,
+        unclamped: 5.0,
+    };
+
+    let back = RangedParticle::from_gpu(&p.to_gpu());
+
+    assert_eq!(back.energy, 0.0);
+    assert_eq!(back.count, 10);
+    assert_eq!(back.mood, -5);
+    assert_eq!(back.unclamped, 5.0);
+}
+
+#[test]
+fn test_range_keeps_values_inside() {
+    let p = RangedParticle {
+        position: Vec3::ZERO,
+        velocity: Vec3::ZERO,
+        energy: 0.5,
+        count: 4,
+        mood: 3,
+        unclamped: -2.0,
+    };
+
+    let back = RangedParticle::from_gpu(&p.to_gpu());
+
+    assert_eq!(back.energy, 0.5);
+    assert_eq!(back.count, 4);
+    assert_eq!(back.mood, 3);
+    assert_eq!(back.unclamped, -2.0);
+}
+
+#[derive(Particle, Clone)]
+struct LabeledParticle {
+    position: Vec3,
+    velocity: Vec3,
+    #[label("Flock ID")]
+    flock_id: u32,
+    energy_level: f32,
+}
+
+#[test]
+fn test_label_overrides_inspect_name() {
+    let p = LabeledParticle {
+        position: Vec3::ZERO,
+        velocity: Vec3::ZERO,
+        flock_id: 7,
+        energy_level: 0.5,
+    };
+
+    let fields = p.inspect_fields();
+    assert!(fields.contains(&("Flock ID", "7".to_string())));
+    assert!(fields.contains(&("energy_level", "0.500".to_string())));
+    assert!(!fields.iter().any(|(name, _)| *name == "flock_id"));
+}
+
+#[test]
+fn test_label_keeps_gpu_field_name() {
+    assert!(LabeledParticle::WGSL_STRUCT.contains("flock_id: u32"));
+    assert!(!LabeledParticle::WGSL_STRUCT.contains("Flock ID"));
+}
+
+#[derive(Particle, Clone)]
+struct HiddenFieldParticle {
+    position: Vec3,
+    velocity: Vec3,
+    #[hidden]
+    packed_flags: u32,
+    energy: f32,
+}
+
+#[test]
+fn test_hidden_field_not_inspected() {
+    let p = HiddenFieldParticle {
+        position: Vec3::ZERO,
+        velocity: Vec3::ZERO,
+        packed_flags: 0b1010,
+        energy: 1.0,
+    };
+
+    let fields = p.inspect_fields();
+    assert!(!fields.iter().any(|(name, _)| *name == "packed_flags"));
+    assert!(fields.iter().any(|(name, _)| *name == "energy"));
+}
+
+#[test]
+fn test_hidden_field_still_on_gpu() {
+    let p = HiddenFieldParticle {
+        position: Vec3::ZERO,
+        velocity: Vec3::ZERO,
+        packed_flags: 0b1010,
+        energy: 1.0,
+    };
+
+    assert!(HiddenFieldParticle::WGSL_STRUCT.contains("packed_flags: u32"));
+    let gpu = p.to_gpu();
+    assert_eq!(gpu.packed_flags, 0b1010);
+    assert_eq!(HiddenFieldParticle::from_gpu(&gpu).packed_flags, 0b1010);
+}
+
+#[test]
+fn test_generated_default_is_zeroed() {
+    let p = FlagParticle::default();
+    assert_eq!(p.position, Vec3::ZERO);
+    assert_eq!(p.velocity, Vec3::ZERO);
+    assert!(!p.is_leader);
+
+    let v = VectorParticle::default();
+    assert_eq!(v.uv, Vec2::ZERO);
+    assert_eq!(v.extra, Vec4::ZERO);
+
+    let i = IntParticle::default();
+    assert_eq!(i.count, 0);
+    assert_eq!(i.offset, 0);
+}
+
+// Without #[particle(default)], Default can still be derived alongside
+// Particle or written by hand without conflicting impls.
+#[derive(Particle, Clone, Default)]
+struct DerivedDefaultParticle {
+    position: Vec3,
+    velocity: Vec3,
+    heat: f32,
+}
+
+#[derive(Particle, Clone)]
+struct CustomDefaultParticle {
+    position: Vec3,
+    velocity: Vec3,
+    heat: f32,
+}
+
+impl Default for CustomDefaultParticle {
+    fn default() -> Self {
+        Self {
+            position: Vec3::ZERO,
+            velocity: Vec3::Y,
+            heat: 1.0,
+        }
+    }
+}
+
+#[test]
+fn test_user_default_impls() {
+    let derived = DerivedDefaultParticle::default();
+    assert_eq!(derived.velocity, Vec3::ZERO);
+    assert_eq!(derived.heat, 0.0);
+
+    let custom = CustomDefaultParticle::default();
+    assert_eq!(custom.velocity, Vec3::Y);
+    assert_eq!(custom.heat, 1.0);
+}
+
+// ============================================================================
+// Particle Derive Tests - Fully-Qualified Type Paths
+// ============================================================================
+
+mod qualified {
+    // A local `glam` module shadows the crate name, so generated code must
+    // refer to `::glam` explicitly.
+    mod glam {}
+
+    #[derive(rdpe::Particle, Clone)]
+    pub struct QualifiedParticle {
+        pub position: ::glam::Vec3,
+        pub velocity: ::glam::Vec3,
+        #[color]
+        pub color: ::glam::Vec3,
+        pub uv: ::glam::Vec2,
+    }
+}
+
+#[test]
+fn test_qualified_particle_roundtrip() {
+    use qualified::QualifiedParticle;
+
+    let p = QualifiedParticle {
+        position: Vec3::new(1.0, 2.0, 3.0),
+        velocity: Vec3::new(-1.0, 0.5, 0.0),
+        color: Vec3::new(0.2, 0.4, 0.6),
+        uv: Vec2::new(0.25, 0.75),
+    };
+
+    let back = QualifiedParticle::from_gpu(&p.to_gpu());
+
+    assert_eq!(back.position, p.position);
+    assert_eq!(back.velocity, p.velocity);
+    assert_eq!(back.color, p.color);
+    assert_eq!(back.uv, p.uv);
+    assert!(QualifiedParticle::WGSL_STRUCT.contains("uv: vec2<f32>"));
+    assert_eq!(QualifiedParticle::COLOR_FIELD, Some("color"));
+}
+
 // ============================================================================
 // Particle Derive Tests - GPU Struct Alignment
 // ============================================================================
@@ -338,6 +611,14 @@ fn test_gpu_struct_size_multiple_of_16() {
     assert_eq!(std::mem::size_of::<IntParticleGpu>() % 16, 0);
 }
 
+#[test]
+fn test_gpu_stride_matches_gpu_size() {
+    const STRIDE: usize = ComplexParticle::GPU_STRIDE;
+    assert_eq!(STRIDE, std::mem::size_of::<ComplexParticleGpu>());
+    assert_eq!(MinimalParticle::GPU_STRIDE, std::mem::size_of::<MinimalParticleGpu>());
+    assert_eq!(VectorParticle::GPU_STRIDE, std::mem::size_of::<VectorParticleGpu>());
+}
+
 #[test]
 fn test_gpu_struct_is_pod() {
     // All GPU structs should be Pod (plain old data)
@@ -390,6 +671,91 @@ fn test_scale_offset_after_alive_offset() {
     assert!(ColoredParticle::SCALE_OFFSET > ColoredParticle::ALIVE_OFFSET);
 }
 
+#[derive(Particle, Clone)]
+struct ReorderedParticle {
+    heat: f32,
+    position: Vec3,
+    velocity: Vec3,
+}
+
+#[derive(Particle, Clone)]
+struct StillParticle {
+    position: Vec3,
+    heat: f32,
+}
+
+#[test]
+fn test_velocity_offset() {
+    // Velocity follows the padded position in the usual layout
+    assert_eq!(MinimalParticle::VELOCITY_OFFSET, Some(16));
+    // Fields before position shift velocity along
+    assert_eq!(ReorderedParticle::VELOCITY_OFFSET, Some(32));
+    // No velocity field, nothing to align with
+    assert_eq!(StillParticle::VELOCITY_OFFSET, None);
+}
+
+// ============================================================================
+// MultiParticle Derive Tests - Variant Helpers
+// ============================================================================
+
+#[derive(rdpe::MultiParticle, Clone)]
+enum Creature {
+    Boid { position: Vec3, velocity: Vec3, flock_id: u32 },
+    Predator { position: Vec3, velocity: Vec3, hunger: f32 },
+}
+
+#[test]
+fn test_multi_particle_variant_predicates() {
+    let boid = Creature::Boid { position: Vec3::ZERO, velocity: Vec3::ZERO, flock_id: 2 };
+    let predator = Creature::Predator { position: Vec3::ZERO, velocity: Vec3::ZERO, hunger: 1.0 };
+
+    assert!(boid.is_boid());
+    assert!(!boid.is_predator());
+    assert!(predator.is_predator());
+    assert!(!predator.is_boid());
+}
+
+#[test]
+fn test_multi_particle_variant_name() {
+    let boid = Creature::Boid { position: Vec3::ZERO, velocity: Vec3::ZERO, flock_id: 0 };
+    let predator = Creature::Predator { position: Vec3::ZERO, velocity: Vec3::ZERO, hunger: 0.5 };
+
+    assert_eq!(boid.variant_name(), "Boid");
+    assert_eq!(predator.variant_name(), "Predator");
+}
+
+#[test]
+fn test_multi_particle_velocity_offset() {
+    assert_eq!(<Creature as ParticleTrait>::VELOCITY_OFFSET, Some(16));
+}
+
+#[derive(rdpe::MultiParticle, Clone)]
+enum Shoal {
+    Swimmer {
+        position: Vec3,
+        velocity: Vec3,
+        #[hidden]
+        school_index: u32,
+        energy: f32,
+    },
+}
+
+#[test]
+fn test_multi_particle_hidden_field() {
+    let fish = Shoal::Swimmer { position: Vec3::ZERO, velocity: Vec3::ZERO, school_index: 7, energy: 1.0 };
+    let fields = fish.inspect_fields();
+    assert!(!fields.iter().any(|(name, _)| *name == "school_index"));
+    assert!(fields.iter().any(|(name, _)| *name == "energy"));
+
+    // The standalone variant struct hides it too
+    let swimmer = Swimmer { position: Vec3::ZERO, velocity: Vec3::ZERO, school_index: 7, energy: 1.0 };
+    assert!(!swimmer.inspect_fields().iter().any(|(name, _)| *name == "school_index"));
+
+    // Still on the GPU
+    assert!(<Shoal as ParticleTrait>::WGSL_STRUCT.contains("school_index: u32"));
+    assert_eq!(Shoal::from_gpu(&fish.to_gpu()).inspect_fields(), fields);
+}
+
 // ============================================================================
 // WGSL Validation Tests
 // ============================================================================
@@ -457,3 +823,76 @@ fn test_int_particle_wgsl_validates() {
     validate_wgsl_struct(IntParticle::WGSL_STRUCT)
         .expect("IntParticle WGSL should be valid");
 }
+
+// ============================================================================
+// Rules running on the GPU
+// ============================================================================
+
+#[derive(Particle, Clone)]
+struct FadingParticle {
+    position: Vec3,
+    velocity: Vec3,
+    base_scale: f32,
+}
+
+#[test]
+fn test_max_age_shrinks_from_stored_scale() {
+    use rdpe::prelude::{Rule, Simulation};
+
+    let run = |frames| {
+        Simulation::<FadingParticle>::new()
+            .with_particle_count(16)
+            .with_spawner(|_| FadingParticle { position: Vec3::ZERO, velocity: Vec3::ZERO, base_scale: 0.0 })
+            .with_rule(Rule::Age)
+            // Particles grow to scale 2.0 before the fade starts
+            .with_rule(Rule::Grow { rate: 10.0, min: 0.0, max: 2.0 })
+            .with_rule(Rule::MaxAge { max_age: 1.0, fade_out_start: 0.5, scale_field: "base_scale".into() })
+            .run_headless(frames, 0.01)
+    };
+
+    // Skip on machines without a GPU adapter
+    let bytes = match run(75) {
+        Err(rdpe::SimulationError::Gpu(rdpe::GpuError::NoAdapter)) => return,
+        other => other.expect("Headless run failed"),
+    };
+    let particles: &[FadingParticleGpu] = bytemuck::cast_slice(&bytes);
+    for p in particles {
+        // Halfway through the fade window, half of the stored scale
+        assert!((p.base_scale - 2.0).abs() < 1e-4, "stored scale {}", p.base_scale);
+        assert!((p.scale - 1.0).abs() < 0.05, "scale {}", p.scale);
+        assert_eq!(p.alive, 1);
+    }
+
+    let bytes = run(110).expect("Headless run failed");
+    let particles: &[FadingParticleGpu] = bytemuck::cast_slice(&bytes);
+    assert!(particles.iter().all(|p| p.alive == 0 && p.scale == 0.0));
+}
+
+#[test]
+fn test_fade_in_grows_to_spawn_scale() {
+    use rdpe::prelude::Simulation;
+
+    let run = |frames| {
+        Simulation::<FadingParticle>::new()
+            .with_particle_count(16)
+            .with_spawner(|_| FadingParticle { position: Vec3::ZERO, velocity: Vec3::ZERO, base_scale: 0.0 })
+            .with_lifecycle(|l| l.fade_in(0.5, "base_scale"))
+            .run_headless(frames, 0.01)
+    };
+
+    // Skip on machines without a GPU adapter
+    let bytes = match run(25) {
+        Err(rdpe::SimulationError::Gpu(rdpe::GpuError::NoAdapter)) => return,
+        other => other.expect("Headless run failed"),
+    };
+    let particles: &[FadingParticleGpu] = bytemuck::cast_slice(&bytes);
+    for p in particles {
+        // Half way through the fade, half of the stored spawn scale
+        assert!((p.base_scale - 1.0).abs() < 1e-4, "stored scale {}", p.base_scale);
+        assert!((p.scale - 0.5).abs() < 0.05, "scale {}", p.scale);
+    }
+
+    let bytes = run(80).expect("Headless run failed");
+    let particles: &[FadingParticleGpu] = bytemuck::cast_slice(&bytes);
+    assert!(particles.iter().all(|p| (p.scale - 1.0).abs() < 1e-4));
+}